@@ -0,0 +1,72 @@
+//! Snapshot Compression Benchmarks
+//!
+//! Compares the snapshot codecs on a representative aggregate state,
+//! reporting compression and decompression speed for each. The compressed
+//! sizes are printed once up front, since criterion only measures time.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::json;
+use tokio::runtime::Runtime;
+use workflow_engine_api::db::events::{
+    GzipCompressor, Lz4Compressor, SnapshotCompressor, ZstdCompressor,
+};
+
+/// Aggregate state with a few thousand order lines
+fn sample_snapshot() -> Vec<u8> {
+    let lines: Vec<_> = (0..5000)
+        .map(|i| {
+            json!({
+                "line": i,
+                "sku": format!("SKU-{:05}", i % 37),
+                "quantity": i % 7 + 1,
+                "status": if i % 3 == 0 { "shipped" } else { "pending" },
+                "warehouse": "eu-west-1",
+            })
+        })
+        .collect();
+    json!({ "order_id": "ORD-1", "customer": "acme", "lines": lines })
+        .to_string()
+        .into_bytes()
+}
+
+fn codecs() -> Vec<Box<dyn SnapshotCompressor>> {
+    vec![
+        Box::new(GzipCompressor),
+        Box::new(Lz4Compressor),
+        Box::new(ZstdCompressor::default()),
+    ]
+}
+
+fn bench_snapshot_compression(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let data = sample_snapshot();
+
+    for codec in codecs() {
+        let compressed = rt.block_on(codec.compress(&data)).unwrap();
+        println!(
+            "{}: {} -> {} bytes ({:.1}%)",
+            codec.compression_type(),
+            data.len(),
+            compressed.len(),
+            compressed.len() as f64 * 100.0 / data.len() as f64
+        );
+    }
+
+    let mut group = c.benchmark_group("snapshot_compression");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    for codec in codecs() {
+        let name = codec.compression_type().to_string();
+        let compressed = rt.block_on(codec.compress(&data)).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("compress", &name), &data, |b, data| {
+            b.iter(|| rt.block_on(codec.compress(black_box(data))).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("decompress", &name), &compressed, |b, compressed| {
+            b.iter(|| rt.block_on(codec.decompress(black_box(compressed))).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_snapshot_compression);
+criterion_main!(benches);
@@ -0,0 +1,313 @@
+/*!
+# Circuit Breaker Status
+
+`GET /api/v1/admin/circuit-breakers` lists the circuit breakers guarding
+calls to MCP servers, AI providers, Dgraph and other services, with their
+state, failure counts and, for open circuits, the time until a trial call is
+let through. `POST /api/v1/admin/circuit-breakers/{kind}/{name}/reset` forces
+one closed, e.g. once an outage is known to be over.
+
+Services own their [`CircuitBreakerRegistry`]; each is added to the
+[`CircuitBreakers`] app data under a kind such as `"mcp"`. Both endpoints
+require a token with the `admin` role.
+*/
+
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use serde::Serialize;
+use std::sync::Arc;
+
+use workflow_engine_core::error::ErrorCategory;
+use workflow_engine_core::error::circuit_breaker::{
+    CircuitBreakerRegistry, CircuitBreakerStatus, CircuitState,
+};
+use crate::api::errors::{ErrorBody, ErrorEnvelope};
+use crate::api::middleware::auth::ClaimsExtractor;
+
+/// Circuit breaker registries by kind of service, registered as app data
+#[derive(Default)]
+pub struct CircuitBreakers {
+    registries: Vec<(String, Arc<CircuitBreakerRegistry>)>,
+}
+
+impl CircuitBreakers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report the breakers of `registry` under `kind`, e.g. the registry of
+    /// an MCP connection pool under `"mcp"`
+    pub fn with_registry(mut self, kind: impl Into<String>, registry: Arc<CircuitBreakerRegistry>) -> Self {
+        self.registries.push((kind.into(), registry));
+        self
+    }
+
+    fn registry(&self, kind: &str) -> Option<&CircuitBreakerRegistry> {
+        self.registries
+            .iter()
+            .find(|(registered, _)| registered == kind)
+            .map(|(_, registry)| registry.as_ref())
+    }
+}
+
+/// One circuit breaker's status
+#[derive(Debug, Serialize)]
+pub struct CircuitBreakerInfo {
+    pub kind: String,
+    pub name: String,
+    /// `closed`, `open` or `half-open`
+    pub state: &'static str,
+    /// Failures counted towards opening the circuit
+    pub failure_count: u32,
+    pub total_calls: u64,
+    pub total_failures: u64,
+    /// Milliseconds until an open circuit lets a trial call through
+    pub retry_in_ms: Option<u64>,
+}
+
+impl CircuitBreakerInfo {
+    fn new(kind: &str, name: String, status: CircuitBreakerStatus) -> Self {
+        Self {
+            kind: kind.to_string(),
+            name,
+            state: match status.state {
+                CircuitState::Closed => "closed",
+                CircuitState::Open => "open",
+                CircuitState::HalfOpen => "half-open",
+            },
+            failure_count: status.metrics.failure_count,
+            total_calls: status.metrics.total_calls,
+            total_failures: status.metrics.total_failures,
+            retry_in_ms: status.retry_in.map(|retry_in| retry_in.as_millis() as u64),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CircuitBreakersResponse {
+    pub circuit_breakers: Vec<CircuitBreakerInfo>,
+}
+
+fn rejection(status: StatusCode, code: &str, message: String) -> HttpResponse {
+    HttpResponse::build(status).json(ErrorEnvelope {
+        error: ErrorBody {
+            code: code.to_string(),
+            message,
+            category: ErrorCategory::User,
+        },
+    })
+}
+
+fn require_admin(req: &HttpRequest) -> Result<(), HttpResponse> {
+    match req.get_claims() {
+        Some(claims) if claims.role == "admin" => Ok(()),
+        _ => Err(rejection(
+            StatusCode::FORBIDDEN,
+            "WF_FORBIDDEN",
+            "Circuit breaker administration requires the admin role".to_string(),
+        )),
+    }
+}
+
+/// List every circuit breaker
+/// GET /api/v1/admin/circuit-breakers
+pub async fn list_circuit_breakers(req: HttpRequest) -> HttpResponse {
+    if let Err(response) = require_admin(&req) {
+        return response;
+    }
+    let mut circuit_breakers = Vec::new();
+    if let Some(breakers) = req.app_data::<web::Data<CircuitBreakers>>() {
+        for (kind, registry) in &breakers.registries {
+            for (name, status) in registry.status().await {
+                circuit_breakers.push(CircuitBreakerInfo::new(kind, name, status));
+            }
+        }
+    }
+    HttpResponse::Ok().json(CircuitBreakersResponse { circuit_breakers })
+}
+
+/// Force a circuit breaker closed
+/// POST /api/v1/admin/circuit-breakers/{kind}/{name}/reset
+pub async fn reset_circuit_breaker(req: HttpRequest, path: web::Path<(String, String)>) -> HttpResponse {
+    if let Err(response) = require_admin(&req) {
+        return response;
+    }
+    let (kind, name) = path.into_inner();
+    let registry = req
+        .app_data::<web::Data<CircuitBreakers>>()
+        .and_then(|breakers| breakers.registry(&kind));
+    let breaker = match registry {
+        Some(registry) => registry.find(&name).await,
+        None => None,
+    };
+    let Some(breaker) = breaker else {
+        return rejection(
+            StatusCode::NOT_FOUND,
+            "WF_CIRCUIT_BREAKER_NOT_FOUND",
+            format!("No circuit breaker '{}' of kind '{}'", name, kind),
+        );
+    };
+
+    breaker.reset().await;
+    log::info!("Circuit breaker {}/{} reset", kind, name);
+    HttpResponse::Ok().json(CircuitBreakerInfo::new(&kind, name, breaker.status().await))
+}
+
+/// Configure circuit breaker routes
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/admin/circuit-breakers")
+            .route("", web::get().to(list_circuit_breakers))
+            .route("/{kind}/{name}/reset", web::post().to(reset_circuit_breaker)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, HttpMessage, test};
+    use serde_json::Value;
+    use std::time::Duration;
+    use workflow_engine_core::auth::Claims;
+    use workflow_engine_core::error::WorkflowError;
+    use workflow_engine_core::error::circuit_breaker::CircuitBreakerConfig;
+
+    fn as_role<R: HttpMessage>(req: R, role: &str) -> R {
+        req.extensions_mut()
+            .insert(Claims::new("ops".to_string(), role.to_string()));
+        req
+    }
+
+    #[tokio::test]
+    async fn test_status_reflects_open_breaker_and_reset_closes_it() {
+        let mcp = Arc::new(CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            timeout: Duration::from_secs(60),
+            ..Default::default()
+        }));
+        let breaker = mcp.get("notion").await;
+        for _ in 0..2 {
+            let _ = breaker
+                .call(|| async { Err::<(), _>(WorkflowError::api_error_simple("connection refused")) })
+                .await;
+        }
+        mcp.get("slack").await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(CircuitBreakers::new().with_registry("mcp", mcp.clone())))
+                .configure(configure_routes),
+        )
+        .await;
+        let list = || {
+            as_role(test::TestRequest::get().uri("/api/v1/admin/circuit-breakers").to_request(), "admin")
+        };
+
+        let body: Value = test::call_and_read_body_json(&app, list()).await;
+        let notion = &body["circuit_breakers"][0];
+        assert_eq!(notion["kind"], "mcp");
+        assert_eq!(notion["name"], "notion");
+        assert_eq!(notion["state"], "open");
+        assert_eq!(notion["total_failures"], 2);
+        assert!(notion["retry_in_ms"].as_u64().unwrap() > 59_000);
+        assert_eq!(body["circuit_breakers"][1]["state"], "closed");
+        assert_eq!(body["circuit_breakers"][1]["retry_in_ms"], Value::Null);
+
+        let reset = as_role(
+            test::TestRequest::post().uri("/api/v1/admin/circuit-breakers/mcp/notion/reset").to_request(),
+            "admin",
+        );
+        let body: Value = test::call_and_read_body_json(&app, reset).await;
+        assert_eq!(body["state"], "closed");
+        assert_eq!(breaker.state().await, CircuitState::Closed);
+
+        let body: Value = test::call_and_read_body_json(&app, list()).await;
+        assert_eq!(body["circuit_breakers"][0]["state"], "closed");
+
+        let unknown = as_role(
+            test::TestRequest::post().uri("/api/v1/admin/circuit-breakers/ai/openai/reset").to_request(),
+            "admin",
+        );
+        let resp = test::call_service(&app, unknown).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_non_admins_are_forbidden() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(CircuitBreakers::new()))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let req = as_role(
+            test::TestRequest::get().uri("/api/v1/admin/circuit-breakers").to_request(),
+            "developer",
+        );
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/admin/circuit-breakers/mcp/notion/reset")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tool_steps_report_their_breaker() {
+        use crate::workflows::executor::{WorkflowExecutor, WorkflowFactory};
+        use crate::workflows::schema::{WorkflowDefinition, WorkflowStatus};
+        use workflow_engine_mcp::connection_pool::{ConnectionConfig, McpConnectionPool};
+        use workflow_engine_mcp::transport::TransportType;
+
+        let pool = Arc::new(McpConnectionPool::new(ConnectionConfig {
+            retry_attempts: 1,
+            ..Default::default()
+        }));
+        let transport = TransportType::Stdio {
+            command: "/nonexistent/notion-mcp-server".to_string(),
+            args: vec![],
+            auto_restart: false,
+            max_restarts: 0,
+        };
+        pool.register_server("notion".to_string(), transport, "workflow-engine".to_string(), "1.0.0".to_string())
+            .await;
+        let executor = WorkflowExecutor::new("http://localhost:8080".to_string(), None)
+            .with_mcp_pool(Arc::clone(&pool));
+
+        let workflow: WorkflowDefinition = serde_yaml::from_str(
+            r#"
+name: search_notion
+description: Searches Notion
+version: "1.0"
+steps:
+  - id: search
+    type:
+      kind: node
+      node: McpTool
+    input:
+      server: notion
+      tool: search_pages
+      arguments:
+        query: roadmap
+"#,
+        )
+        .unwrap();
+        let instance = WorkflowFactory::create_instance(workflow, serde_json::json!({})).unwrap();
+        let finished = executor.execute(instance).await.unwrap();
+        assert_eq!(finished.status, WorkflowStatus::Failed);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(CircuitBreakers::new().with_registry("mcp", pool.circuit_breakers())))
+                .configure(configure_routes),
+        )
+        .await;
+        let list = as_role(test::TestRequest::get().uri("/api/v1/admin/circuit-breakers").to_request(), "admin");
+        let body: Value = test::call_and_read_body_json(&app, list).await;
+        assert_eq!(body["circuit_breakers"][0]["kind"], "mcp");
+        assert_eq!(body["circuit_breakers"][0]["name"], "notion");
+        assert_eq!(body["circuit_breakers"][0]["total_failures"], 1);
+    }
+}
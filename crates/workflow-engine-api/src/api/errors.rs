@@ -0,0 +1,428 @@
+/*!
+# API Error Responses
+
+Maps [`WorkflowError`] onto HTTP status codes and a consistent JSON error
+envelope so handlers don't have to pick statuses ad hoc.
+
+```json
+{
+  "error": {
+    "code": "WF_VALIDATION_ERROR",
+    "message": "Validation failed: ...",
+    "category": "User"
+  }
+}
+```
+
+Clients that send `Accept: application/problem+json` (or deployments that set
+`API_ERROR_FORMAT=problem+json`) receive RFC 7807 problem details instead:
+
+```json
+{
+  "type": "urn:workflow-engine:error:WF_VALIDATION_ERROR",
+  "title": "Validation Error",
+  "status": 400,
+  "detail": "Validation failed: ...",
+  "instance": "/api/v1/workflows/trigger",
+  "code": "WF_VALIDATION_ERROR",
+  "category": "User"
+}
+```
+*/
+
+use actix_web::{http::header, http::StatusCode, HttpRequest, HttpResponse, ResponseError};
+use serde::Serialize;
+use std::fmt;
+
+use workflow_engine_core::error::{DatabaseErrorKind, ErrorCategory, ErrorExt, WorkflowError};
+
+/// JSON error envelope returned by API handlers
+#[derive(Debug, Serialize)]
+pub struct ErrorEnvelope {
+    pub error: ErrorBody,
+}
+
+/// Error details carried inside [`ErrorEnvelope`]
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    /// Stable machine-readable code, e.g. `WF_NODE_NOT_FOUND`
+    pub code: String,
+    /// Human-readable description
+    pub message: String,
+    /// Error category used for retry and alerting decisions
+    pub category: ErrorCategory,
+}
+
+impl From<&WorkflowError> for ErrorEnvelope {
+    fn from(error: &WorkflowError) -> Self {
+        Self {
+            error: ErrorBody {
+                code: error.error_code().to_string(),
+                message: error.to_string(),
+                category: error.category(),
+            },
+        }
+    }
+}
+
+/// RFC 7807 media type for problem details
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// RFC 7807 problem details document
+#[derive(Debug, Serialize)]
+pub struct ProblemDetails {
+    /// URI identifying the problem type
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Short summary of the problem type
+    pub title: String,
+    /// HTTP status code
+    pub status: u16,
+    /// Explanation specific to this occurrence
+    pub detail: String,
+    /// URI of the request that produced the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Workflow error code extension member
+    pub code: String,
+    /// Workflow error category extension member
+    pub category: ErrorCategory,
+}
+
+impl ProblemDetails {
+    /// Build problem details for a workflow error
+    pub fn from_error(error: &WorkflowError, instance: Option<String>) -> Self {
+        let code = error.error_code();
+
+        Self {
+            problem_type: format!("urn:workflow-engine:error:{}", code),
+            title: problem_title(code),
+            status: status_code_for(error).as_u16(),
+            detail: error.to_string(),
+            instance,
+            code: code.to_string(),
+            category: error.category(),
+        }
+    }
+}
+
+/// Turn an error code such as `WF_NODE_NOT_FOUND` into "Node Not Found"
+fn problem_title(code: &str) -> String {
+    code.trim_start_matches("WF_")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_string() + &chars.as_str().to_lowercase(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Wire format used for error responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// The [`ErrorEnvelope`] JSON format
+    #[default]
+    Json,
+    /// RFC 7807 `application/problem+json`
+    ProblemJson,
+}
+
+impl ErrorFormat {
+    /// Read the default format from `API_ERROR_FORMAT` (`json` or `problem+json`)
+    pub fn from_env() -> Self {
+        match std::env::var("API_ERROR_FORMAT").as_deref() {
+            Ok("problem+json") | Ok("problem_json") => Self::ProblemJson,
+            _ => Self::Json,
+        }
+    }
+
+    /// Negotiate the format for a request.
+    ///
+    /// An `Accept` header naming `application/problem+json` wins; otherwise the
+    /// `ErrorFormat` registered as app data is used, falling back to JSON.
+    pub fn for_request(req: &HttpRequest) -> Self {
+        let accepts_problem_json = req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map(|accept| accept.contains(PROBLEM_JSON_CONTENT_TYPE))
+            .unwrap_or(false);
+
+        if accepts_problem_json {
+            return Self::ProblemJson;
+        }
+
+        req.app_data::<actix_web::web::Data<ErrorFormat>>()
+            .map(|format| *format.get_ref())
+            .unwrap_or_default()
+    }
+}
+
+/// Map a workflow error to the HTTP status code returned to clients
+pub fn status_code_for(error: &WorkflowError) -> StatusCode {
+    match error {
+        // Bad input from the caller
+        WorkflowError::ValidationError { .. }
+        | WorkflowError::InvalidInput { .. }
+        | WorkflowError::DeserializationError { .. }
+        | WorkflowError::InvalidStepType { .. } => StatusCode::BAD_REQUEST,
+
+        // Well-formed requests describing an invalid workflow
+        WorkflowError::CycleDetected
+        | WorkflowError::UnreachableNodes { .. }
+        | WorkflowError::InvalidRouter { .. }
+        | WorkflowError::WorkflowTypeMismatch { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+
+        WorkflowError::NodeNotFound { .. } => StatusCode::NOT_FOUND,
+
+        // Nodes that classified their own failure
+        WorkflowError::NodeError { category, .. } => match category {
+            ErrorCategory::User => StatusCode::BAD_REQUEST,
+            ErrorCategory::Business => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCategory::Transient => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCategory::Permanent | ErrorCategory::System => StatusCode::INTERNAL_SERVER_ERROR,
+        },
+
+        // Upstream exhausted or throttling us: ask the client to back off
+        WorkflowError::ApiError {
+            status_code: Some(429) | Some(503),
+            ..
+        } => StatusCode::SERVICE_UNAVAILABLE,
+        WorkflowError::DatabaseError { .. } => match error.database_error_kind() {
+            Some(DatabaseErrorKind::ConnectionTimeout | DatabaseErrorKind::Deadlock) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            Some(DatabaseErrorKind::ConstraintViolation) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        },
+
+        // Failures talking to external systems
+        WorkflowError::ApiError { .. }
+        | WorkflowError::MCPError { .. }
+        | WorkflowError::MCPConnectionError { .. }
+        | WorkflowError::MCPProtocolError { .. }
+        | WorkflowError::MCPTransportError { .. }
+        | WorkflowError::CrossSystemError { .. } => StatusCode::BAD_GATEWAY,
+
+        WorkflowError::NodeTimeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+
+        // Runs cancelled while the request waited on them
+        WorkflowError::Cancelled { .. } => StatusCode::CONFLICT,
+
+        WorkflowError::ProcessingError { .. }
+        | WorkflowError::SerializationError { .. }
+        | WorkflowError::RuntimeError { .. }
+        | WorkflowError::RegistryError { .. }
+        | WorkflowError::ConfigurationError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Build the JSON error response for a workflow error
+pub fn error_response_for(error: &WorkflowError) -> HttpResponse {
+    HttpResponse::build(status_code_for(error)).json(ErrorEnvelope::from(error))
+}
+
+/// Build the error response for a workflow error in the format negotiated for `req`
+pub fn error_response_for_request(req: &HttpRequest, error: &WorkflowError) -> HttpResponse {
+    match ErrorFormat::for_request(req) {
+        ErrorFormat::Json => error_response_for(error),
+        ErrorFormat::ProblemJson => {
+            let problem = ProblemDetails::from_error(error, Some(req.path().to_string()));
+            HttpResponse::build(status_code_for(error))
+                .content_type(PROBLEM_JSON_CONTENT_TYPE)
+                .json(problem)
+        }
+    }
+}
+
+/// [`WorkflowError`] wrapper implementing [`ResponseError`], so handlers can
+/// propagate workflow errors with `?`.
+#[derive(Debug)]
+pub struct ApiError(pub WorkflowError);
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<WorkflowError> for ApiError {
+    fn from(error: WorkflowError) -> Self {
+        Self(error)
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        status_code_for(&self.0)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        error_response_for(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    async fn body_json(error: WorkflowError) -> (StatusCode, serde_json::Value) {
+        let response = ApiError::from(error).error_response();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[test]
+    fn test_status_mapping() {
+        let cases = vec![
+            (WorkflowError::validation_error_simple("bad"), StatusCode::BAD_REQUEST),
+            (WorkflowError::invalid_input_simple("bad"), StatusCode::BAD_REQUEST),
+            (WorkflowError::deserialization_error_simple("bad"), StatusCode::BAD_REQUEST),
+            (WorkflowError::invalid_step_type_simple("x", "wf"), StatusCode::BAD_REQUEST),
+            (WorkflowError::CycleDetected, StatusCode::UNPROCESSABLE_ENTITY),
+            (
+                WorkflowError::NodeNotFound { node_type: std::any::TypeId::of::<u8>() },
+                StatusCode::NOT_FOUND,
+            ),
+            (WorkflowError::mcp_connection_error_simple("down"), StatusCode::BAD_GATEWAY),
+            (WorkflowError::mcp_transport_error_simple("down"), StatusCode::BAD_GATEWAY),
+            (WorkflowError::mcp_protocol_error_simple("bad"), StatusCode::BAD_GATEWAY),
+            (WorkflowError::cross_system_error_simple("down"), StatusCode::BAD_GATEWAY),
+            (WorkflowError::api_error("err", "svc", "/x", Some(500)), StatusCode::BAD_GATEWAY),
+            (
+                WorkflowError::deadline_exceeded("SlowNode", std::time::Duration::from_secs(1)),
+                StatusCode::GATEWAY_TIMEOUT,
+            ),
+            (
+                WorkflowError::api_error("throttled", "svc", "/x", Some(429)),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                WorkflowError::database_error("pool exhausted", "connection", None),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                WorkflowError::database_error("deadlock detected", "UPDATE", None),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                WorkflowError::database_error(
+                    "duplicate key value violates unique constraint \"users_email_key\"",
+                    "INSERT",
+                    Some("users".to_string()),
+                ),
+                StatusCode::CONFLICT,
+            ),
+            (
+                WorkflowError::database_error("bad query", "SELECT", None),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (WorkflowError::processing_error_simple("boom"), StatusCode::INTERNAL_SERVER_ERROR),
+            (
+                WorkflowError::configuration_error_simple("missing"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (WorkflowError::user_error("no order id", "ChargeNode"), StatusCode::BAD_REQUEST),
+            (
+                WorkflowError::transient_error("provider busy", "ChargeNode"),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                WorkflowError::system_error("provider down", "ChargeNode"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+
+        for (error, expected) in cases {
+            assert_eq!(status_code_for(&error), expected, "unexpected status for {:?}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_envelope_shape() {
+        let (status, body) = body_json(WorkflowError::validation_error_simple("name is required")).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "WF_VALIDATION_ERROR");
+        assert_eq!(body["error"]["category"], "User");
+        assert!(body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("name is required"));
+    }
+
+    #[tokio::test]
+    async fn test_error_envelope_for_upstream_failure() {
+        let (status, body) = body_json(WorkflowError::mcp_connection_error_simple("refused")).await;
+
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["error"]["code"], "WF_MCP_CONNECTION_ERROR");
+        assert_eq!(body["error"]["category"], "Transient");
+    }
+
+    async fn negotiated(req: HttpRequest) -> (StatusCode, String, serde_json::Value) {
+        let response = error_response_for_request(
+            &req,
+            &WorkflowError::validation_error_simple("name is required"),
+        );
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        (status, content_type, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_problem_json_via_accept_header() {
+        let req = actix_web::test::TestRequest::post()
+            .uri("/api/v1/workflows/trigger")
+            .insert_header((header::ACCEPT, PROBLEM_JSON_CONTENT_TYPE))
+            .to_http_request();
+
+        let (status, content_type, body) = negotiated(req).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(content_type, PROBLEM_JSON_CONTENT_TYPE);
+        assert_eq!(body["type"], "urn:workflow-engine:error:WF_VALIDATION_ERROR");
+        assert_eq!(body["title"], "Validation Error");
+        assert_eq!(body["status"], 400);
+        assert!(body["detail"].as_str().unwrap().contains("name is required"));
+        assert_eq!(body["instance"], "/api/v1/workflows/trigger");
+    }
+
+    #[tokio::test]
+    async fn test_problem_json_via_app_config() {
+        let req = actix_web::test::TestRequest::post()
+            .uri("/api/v1/workflows/trigger")
+            .app_data(actix_web::web::Data::new(ErrorFormat::ProblemJson))
+            .to_http_request();
+
+        let (_, content_type, body) = negotiated(req).await;
+
+        assert_eq!(content_type, PROBLEM_JSON_CONTENT_TYPE);
+        assert_eq!(body["status"], 400);
+    }
+
+    #[tokio::test]
+    async fn test_default_format_is_envelope() {
+        let req = actix_web::test::TestRequest::post()
+            .uri("/api/v1/workflows/trigger")
+            .to_http_request();
+
+        let (_, content_type, body) = negotiated(req).await;
+
+        assert_eq!(content_type, "application/json");
+        assert_eq!(body["error"]["code"], "WF_VALIDATION_ERROR");
+    }
+}
@@ -1,11 +1,14 @@
 use actix_web::{web, HttpResponse, Result};
-use diesel::{PgConnection, RunQueryDsl};
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 use sysinfo::{System, Pid};
 use utoipa::ToSchema;
+use workflow_engine_nodes::external_config::{
+    HelpscoutServerConfig, NotionServerConfig, SlackServerConfig,
+};
+use workflow_engine_nodes::external_mcp::{BaseExternalMcpClient, ExternalMcpClientNode};
 
-use crate::db::session::DbPool;
+use crate::db::pool_stats::PoolMonitor;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct HealthStatus {
@@ -36,6 +39,7 @@ pub struct MCPServerHealth {
     pub url: String,
     pub status: String,
     pub response_time_ms: Option<u64>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -90,7 +94,7 @@ pub struct ProcessInfo {
     )
 )]
 pub async fn health_check(
-    pool: web::Data<std::sync::Arc<DbPool>>,
+    pool: web::Data<PoolMonitor>,
 ) -> Result<HttpResponse> {
     let mut overall_status = "healthy";
     
@@ -156,7 +160,7 @@ pub async fn health_check(
     )
 )]
 pub async fn detailed_health_check(
-    pool: web::Data<std::sync::Arc<DbPool>>,
+    pool: web::Data<PoolMonitor>,
 ) -> Result<HttpResponse> {
     let uptime_seconds = super::startup::get_uptime_seconds();
     
@@ -252,32 +256,26 @@ pub async fn detailed_health_check(
     Ok(HttpResponse::Ok().json(detailed_status))
 }
 
-async fn check_database(
-    pool: &DbPool,
+/// Check that the pool can hand out a connection within its acquire timeout,
+/// reporting the pool statistics either way
+pub(crate) async fn check_database(
+    pool: &PoolMonitor,
 ) -> ComponentHealth {
-    match pool.get() {
-        Ok(mut conn) => {
-            // Try a simple query
-            match diesel::sql_query("SELECT 1").execute(&mut conn) {
-                Ok(_) => ComponentHealth {
-                    status: "healthy".to_string(),
-                    message: Some("Database connection successful".to_string()),
-                    details: Some(serde_json::json!({
-                        "pool_size": pool.state().connections,
-                        "idle_connections": pool.state().idle_connections,
-                    })),
-                },
-                Err(e) => ComponentHealth {
-                    status: "unhealthy".to_string(),
-                    message: Some(format!("Database query failed: {}", e)),
-                    details: None,
-                },
-            }
-        }
+    let monitor = pool.clone();
+    let result = web::block(move || monitor.check())
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+    
+    match result {
+        Ok(stats) => ComponentHealth {
+            status: "healthy".to_string(),
+            message: Some("Database connection successful".to_string()),
+            details: Some(serde_json::json!(stats)),
+        },
         Err(e) => ComponentHealth {
             status: "unhealthy".to_string(),
             message: Some(format!("Failed to get database connection: {}", e)),
-            details: None,
+            details: Some(serde_json::json!(pool.stats())),
         },
     }
 }
@@ -322,43 +320,44 @@ fn check_disk() -> ComponentHealth {
 }
 
 async fn check_mcp_servers() -> Vec<MCPServerHealth> {
+    let notion = NotionServerConfig::default();
+    let slack = SlackServerConfig::default();
+    let helpscout = HelpscoutServerConfig::default();
     let servers = vec![
-        ("notion-mcp", "http://localhost:3001/health"),
-        ("slack-mcp", "http://localhost:3002/health"),
-        ("helpscout-mcp", "http://localhost:3003/health"),
+        ("notion-mcp", notion.url.clone(), notion.to_external_config()),
+        ("slack-mcp", slack.url.clone(), slack.to_external_config()),
+        ("helpscout-mcp", helpscout.url.clone(), helpscout.to_external_config()),
     ];
     
     let mut results = Vec::new();
     
-    for (name, url) in servers {
-        let start = std::time::Instant::now();
+    for (name, url, config) in servers {
+        let mut client = BaseExternalMcpClient::new(config);
         
-        let status = match reqwest::get(url).await {
-            Ok(response) => {
-                let response_time = start.elapsed().as_millis() as u64;
-                if response.status().is_success() {
-                    MCPServerHealth {
-                        name: name.to_string(),
-                        url: url.to_string(),
-                        status: "healthy".to_string(),
-                        response_time_ms: Some(response_time),
-                    }
+        let status = match client.health_check().await {
+            Ok(health) => MCPServerHealth {
+                name: name.to_string(),
+                url,
+                status: if health.healthy {
+                    "healthy"
+                } else if health.reachable {
+                    "unhealthy"
                 } else {
-                    MCPServerHealth {
-                        name: name.to_string(),
-                        url: url.to_string(),
-                        status: "unhealthy".to_string(),
-                        response_time_ms: Some(response_time),
-                    }
+                    "unreachable"
                 }
-            }
-            Err(_) => MCPServerHealth {
+                .to_string(),
+                response_time_ms: health.reachable.then_some(health.latency_ms),
+                error: health.error,
+            },
+            Err(e) => MCPServerHealth {
                 name: name.to_string(),
-                url: url.to_string(),
+                url,
                 status: "unreachable".to_string(),
                 response_time_ms: None,
+                error: Some(e.to_string()),
             },
         };
+        let _ = client.disconnect().await;
         
         results.push(status);
     }
@@ -375,4 +374,56 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         web::resource("/health/detailed")
             .route(web::get().to(detailed_health_check))
     );
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, App};
+    use crate::db::pool_stats::tests::single_connection_pool;
+
+    #[actix_web::test]
+    async fn test_health_reports_pool_stats() {
+        let (_pool, monitor) = single_connection_pool();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(monitor))
+                .route("/health", web::get().to(health_check))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/health").to_request();
+        let resp = test::call_service(&app, req).await;
+        let body: serde_json::Value = test::read_body_json(resp).await;
+
+        let database = &body["checks"]["database"];
+        assert_eq!(database["status"], "healthy");
+        assert_eq!(database["details"]["max_size"], 1);
+        assert_eq!(database["details"]["size"], 1);
+        assert_eq!(database["details"]["idle"], 1);
+        assert_eq!(database["details"]["in_use"], 0);
+        assert_eq!(database["details"]["checkouts"], 1);
+        assert_eq!(database["details"]["wait_count"], 0);
+        assert_eq!(database["details"]["timeouts"], 0);
+    }
+
+    #[actix_web::test]
+    async fn test_health_degraded_when_pool_is_exhausted() {
+        let (pool, monitor) = single_connection_pool();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(monitor))
+                .route("/health", web::get().to(health_check))
+        ).await;
+
+        let _busy = pool.get().unwrap();
+        let req = test::TestRequest::get().uri("/health").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let database = &body["checks"]["database"];
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(database["status"], "unhealthy");
+        assert_eq!(database["details"]["in_use"], 1);
+        assert_eq!(database["details"]["timeouts"], 1);
+    }
+}
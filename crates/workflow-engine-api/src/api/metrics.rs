@@ -11,6 +11,8 @@ use actix_web::{web, HttpResponse, Result as ActixResult};
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::db::migration::MigrationService;
+use crate::db::pool_stats::PoolMonitor;
 use crate::monitoring::metrics::{export_metrics, SystemMetrics};
 
 /// Health check endpoint with basic system information
@@ -73,7 +75,12 @@ pub async fn health_detailed() -> ActixResult<HttpResponse> {
 }
 
 /// Prometheus metrics endpoint
-pub async fn metrics() -> ActixResult<HttpResponse> {
+pub async fn metrics(pool: Option<web::Data<PoolMonitor>>) -> ActixResult<HttpResponse> {
+    // Pool gauges are sampled at scrape time
+    if let Some(pool) = &pool {
+        pool.update_metrics();
+    }
+
     match export_metrics() {
         Ok(metrics_output) => {
             Ok(HttpResponse::Ok()
@@ -128,7 +135,10 @@ pub async fn metrics_summary() -> ActixResult<HttpResponse> {
 }
 
 /// Readiness probe for Kubernetes
-pub async fn ready() -> ActixResult<HttpResponse> {
+pub async fn ready(
+    pool: Option<web::Data<PoolMonitor>>,
+    migrations: Option<web::Data<MigrationService>>,
+) -> ActixResult<HttpResponse> {
     // Check if all required services are ready
     // For now, we'll do basic checks
     
@@ -149,6 +159,32 @@ pub async fn ready() -> ActixResult<HttpResponse> {
         }
     }
     
+    // Check that the database pool can hand out a connection in time
+    if let Some(pool) = &pool {
+        let database = super::health::check_database(pool).await;
+        if database.status != "healthy" {
+            ready = false;
+        }
+        checks.insert("database", serde_json::json!(database));
+    }
+    
+    // Refuse traffic until the schema matches the migration files
+    if let Some(migrations) = &migrations {
+        match migrations.check().await {
+            Ok(report) => {
+                ready &= report.up_to_date;
+                checks.insert("migrations", serde_json::json!(report));
+            }
+            Err(e) => {
+                ready = false;
+                checks.insert("migrations", serde_json::json!({
+                    "up_to_date": false,
+                    "error": e.to_string()
+                }));
+            }
+        }
+    }
+    
     // Check if workflow service is available
     // This would typically check if the workflow engine is responsive
     checks.insert("workflow_engine", serde_json::json!({"status": "ok"}));
@@ -262,4 +298,56 @@ mod tests {
         let body: serde_json::Value = test::read_body_json(resp).await;
         assert_eq!(body["alive"], true);
     }
-}
\ No newline at end of file
+
+    #[actix_web::test]
+    async fn test_ready_fails_when_pool_is_exhausted() {
+        let (pool, monitor) = crate::db::pool_stats::tests::single_connection_pool();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(monitor))
+                .route("/ready", web::get().to(ready))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/ready").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let _busy = pool.get().unwrap();
+        let req = test::TestRequest::get().uri("/ready").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["checks"]["database"]["status"], "unhealthy");
+        assert_eq!(body["checks"]["database"]["details"]["in_use"], 1);
+    }
+
+    #[actix_web::test]
+    async fn test_ready_waits_for_migrations() {
+        use crate::db::migration::{MigrationConfig, tests::InMemoryMigrationManager};
+
+        let config = MigrationConfig {
+            auto_apply: true,
+            ..MigrationConfig::default()
+        };
+        let manager = InMemoryMigrationManager::new(&["20241213_000001", "20241213_000002"], 1);
+        let migrations = web::Data::new(manager.into_service(config));
+        let app = test::init_service(
+            App::new()
+                .app_data(migrations.clone())
+                .route("/ready", web::get().to(ready))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/ready").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["checks"]["migrations"]["pending"], serde_json::json!(["20241213_000002"]));
+
+        migrations.initialize().await.unwrap();
+        let req = test::TestRequest::get().uri("/ready").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+    }
+}
@@ -10,6 +10,7 @@ use std::{
 };
 
 use workflow_engine_core::auth::{Claims, JwtAuth};
+use workflow_engine_core::task::TenantId;
 
 /// JWT Authentication middleware
 pub struct JwtMiddleware {
@@ -68,9 +69,14 @@ where
         let jwt_auth = self.jwt_auth.clone();
 
         Box::pin(async move {
-            // Skip authentication for health check and auth endpoints
+            // Skip authentication for health check and auth endpoints, and
+            // for webhooks, which authenticate with a payload signature
             let path = req.path();
-            if path == "/health" || path.starts_with("/auth/") {
+            if path == "/health"
+                || path == "/health/migrations"
+                || path.starts_with("/auth/")
+                || path.starts_with("/webhooks/")
+            {
                 return service.call(req).await;
             }
 
@@ -107,6 +113,11 @@ where
 /// Extension trait to extract claims from request
 pub trait ClaimsExtractor {
     fn get_claims(&self) -> Option<Claims>;
+
+    /// Tenant of the authenticated caller, if its token names one
+    fn tenant_id(&self) -> Option<TenantId> {
+        self.get_claims()?.tenant_id.map(TenantId::new)
+    }
 }
 
 impl ClaimsExtractor for ServiceRequest {
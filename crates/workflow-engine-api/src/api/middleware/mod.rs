@@ -1,3 +1,7 @@
 pub mod auth;
+pub mod timeout;
+pub mod validation;
 
-pub use auth::{JwtMiddleware, ClaimsExtractor};
\ No newline at end of file
+pub use auth::{JwtMiddleware, ClaimsExtractor};
+pub use timeout::{RequestTimeout, RequestTimeoutConfig};
+pub use validation::RequestValidation;
\ No newline at end of file
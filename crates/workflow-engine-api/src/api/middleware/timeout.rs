@@ -0,0 +1,368 @@
+//! Per-route request timeouts
+//!
+//! [`RequestTimeout`] gives each request a time budget picked by its method
+//! and path. A handler still running when the budget is spent is dropped and
+//! the client receives a 504 with the usual JSON error envelope, so a slow
+//! workflow run cannot hold a worker indefinitely. Work the handler moved to
+//! a blocking thread keeps running there, but no longer ties up the worker.
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{Method, StatusCode},
+    Error, HttpResponse, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    env, fmt,
+    future::{ready, Ready},
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use workflow_engine_core::error::ErrorCategory;
+
+use crate::api::errors::{ErrorBody, ErrorEnvelope};
+
+/// Budget for routes without one of their own, unless configured otherwise
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Error code of the 504 returned for timed out requests
+pub const REQUEST_TIMEOUT_CODE: &str = "WF_REQUEST_TIMEOUT";
+
+/// Timeout for the requests matching a method and path pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteTimeout {
+    /// Method to match; any method when `None`
+    pub method: Option<Method>,
+    /// Path pattern. `{param}` segments match any value and a trailing `*`
+    /// segment matches any remainder, e.g. `/api/v1/workflows/*`.
+    pub path: String,
+    pub timeout: Duration,
+}
+
+impl RouteTimeout {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        if self.method.as_ref().is_some_and(|expected| expected != method) {
+            return false;
+        }
+        let mut actual = path.trim_end_matches('/').split('/');
+        for expected in self.path.trim_end_matches('/').split('/') {
+            if expected == "*" {
+                return true;
+            }
+            match actual.next() {
+                Some(segment) if expected == segment => {}
+                Some(_) if expected.starts_with('{') && expected.ends_with('}') => {}
+                _ => return false,
+            }
+        }
+        actual.next().is_none()
+    }
+
+    /// Parse `[METHOD ]PATH=SECONDS`, e.g. `POST /api/v1/workflows/trigger=120`
+    fn parse(spec: &str) -> Option<Self> {
+        let (route, seconds) = spec.trim().rsplit_once('=')?;
+        let timeout = parse_seconds(seconds)?;
+        let (method, path) = match route.trim().split_once(' ') {
+            Some((method, path)) => (Some(method.parse().ok()?), path.trim()),
+            None => (None, route.trim()),
+        };
+        Some(Self {
+            method,
+            path: path.to_string(),
+            timeout,
+        })
+    }
+}
+
+/// A finite, positive number of seconds as a duration
+fn parse_seconds(seconds: &str) -> Option<Duration> {
+    let seconds: f64 = seconds.trim().parse().ok()?;
+    if seconds > 0.0 {
+        Duration::try_from_secs_f64(seconds).ok()
+    } else {
+        None
+    }
+}
+
+/// Request timeouts by route
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestTimeoutConfig {
+    /// Budget for requests no route matches; unbounded when `None`
+    pub default: Option<Duration>,
+    /// Route timeouts, the first matching one applying
+    pub routes: Vec<RouteTimeout>,
+}
+
+impl Default for RequestTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default: Some(DEFAULT_REQUEST_TIMEOUT),
+            routes: Vec::new(),
+        }
+    }
+}
+
+impl RequestTimeoutConfig {
+    /// Read the configuration from the environment:
+    ///
+    /// - `REQUEST_TIMEOUT_SECS` - default budget, `0` for none
+    /// - `REQUEST_TIMEOUT_ROUTES` - comma-separated `[METHOD ]PATH=SECONDS`
+    ///   route timeouts, e.g. `POST /api/v1/workflows/trigger=120,/api/v1/health=2`
+    ///
+    /// Timeouts that aren't finite, positive numbers of seconds are logged
+    /// and ignored.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(seconds) = env::var("REQUEST_TIMEOUT_SECS") {
+            match parse_seconds(&seconds) {
+                Some(timeout) => config.default = Some(timeout),
+                None if seconds.trim().parse::<f64>() == Ok(0.0) => config.default = None,
+                None => log::warn!("Invalid REQUEST_TIMEOUT_SECS value '{}', using default", seconds),
+            }
+        }
+        if let Ok(routes) = env::var("REQUEST_TIMEOUT_ROUTES") {
+            for spec in routes.split(',').filter(|spec| !spec.trim().is_empty()) {
+                match RouteTimeout::parse(spec) {
+                    Some(route) => config.routes.push(route),
+                    None => log::warn!("Ignoring invalid REQUEST_TIMEOUT_ROUTES entry '{}'", spec),
+                }
+            }
+        }
+        config
+    }
+
+    pub fn with_default(mut self, timeout: Option<Duration>) -> Self {
+        self.default = timeout;
+        self
+    }
+
+    /// Give requests to `path`, with `method` if set, their own budget
+    pub fn with_route(mut self, method: Option<Method>, path: impl Into<String>, timeout: Duration) -> Self {
+        self.routes.push(RouteTimeout {
+            method,
+            path: path.into(),
+            timeout,
+        });
+        self
+    }
+
+    /// The budget for a request to `method` `path`
+    pub fn timeout_for(&self, method: &Method, path: &str) -> Option<Duration> {
+        self.routes
+            .iter()
+            .find(|route| route.matches(method, path))
+            .map(|route| route.timeout)
+            .or(self.default)
+    }
+}
+
+/// A request that ran past its budget
+#[derive(Debug)]
+pub struct RequestTimedOut {
+    pub method: Method,
+    pub path: String,
+    pub timeout: Duration,
+}
+
+impl fmt::Display for RequestTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} did not complete within {}ms",
+            self.method,
+            self.path,
+            self.timeout.as_millis()
+        )
+    }
+}
+
+impl ResponseError for RequestTimedOut {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::GATEWAY_TIMEOUT
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::GatewayTimeout().json(ErrorEnvelope {
+            error: ErrorBody {
+                code: REQUEST_TIMEOUT_CODE.to_string(),
+                message: self.to_string(),
+                category: ErrorCategory::Transient,
+            },
+        })
+    }
+}
+
+/// Middleware aborting requests that exceed their route's timeout
+#[derive(Clone, Default)]
+pub struct RequestTimeout {
+    config: Arc<RequestTimeoutConfig>,
+}
+
+impl RequestTimeout {
+    pub fn new(config: RequestTimeoutConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTimeout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestTimeoutService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTimeoutService {
+            service: Rc::new(service),
+            config: self.config.clone(),
+        }))
+    }
+}
+
+pub struct RequestTimeoutService<S> {
+    service: Rc<S>,
+    config: Arc<RequestTimeoutConfig>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTimeoutService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let Some(timeout) = self.config.timeout_for(req.method(), req.path()) else {
+            return Box::pin(self.service.call(req));
+        };
+        let method = req.method().clone();
+        let path = req.path().to_string();
+        let response = self.service.call(req);
+
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, response).await {
+                Ok(response) => response,
+                Err(_) => {
+                    let timed_out = RequestTimedOut { method, path, timeout };
+                    log::warn!("Request timed out: {}", timed_out);
+                    Err(timed_out.into())
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{body::to_bytes, test as actix_test, web, App};
+    use serde_json::Value;
+
+    const RUN_PATH: &str = "/api/v1/workflows/{id}/run";
+
+    async fn slow() -> HttpResponse {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        HttpResponse::Ok().body("done")
+    }
+
+    async fn call(uri: &str) -> (StatusCode, web::Bytes) {
+        let config = RequestTimeoutConfig::default()
+            .with_route(Some(Method::POST), RUN_PATH, Duration::from_millis(50))
+            .with_route(None, "/api/v1/reports/*", Duration::from_secs(5));
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(RequestTimeout::new(config))
+                .route(RUN_PATH, web::post().to(slow))
+                .route("/api/v1/reports/{name}", web::post().to(slow)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post().uri(uri).to_request();
+        let resp = match actix_test::try_call_service(&app, req).await {
+            Ok(resp) => resp.map_into_boxed_body().into_parts().1,
+            Err(error) => error.error_response(),
+        };
+        let status = resp.status();
+        (status, to_bytes(resp.into_body()).await.unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_slow_handler_over_route_timeout_returns_504() {
+        let (status, body) = call("/api/v1/workflows/42/run").await;
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], REQUEST_TIMEOUT_CODE);
+        assert_eq!(
+            body["error"]["message"],
+            "POST /api/v1/workflows/42/run did not complete within 50ms"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_handler_under_route_timeout_completes() {
+        let (status, body) = call("/api/v1/reports/weekly").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "done");
+    }
+
+    #[test]
+    fn test_timeout_for_routes() {
+        let config = RequestTimeoutConfig::default()
+            .with_route(Some(Method::POST), "/api/v1/workflows/trigger", Duration::from_secs(120))
+            .with_route(None, "/api/v1/workflows/*", Duration::from_secs(10));
+
+        let timeout = |method: Method, path: &str| config.timeout_for(&method, path);
+        assert_eq!(timeout(Method::POST, "/api/v1/workflows/trigger"), Some(Duration::from_secs(120)));
+        assert_eq!(timeout(Method::GET, "/api/v1/workflows/trigger"), Some(Duration::from_secs(10)));
+        assert_eq!(timeout(Method::GET, "/api/v1/workflows/a/b/"), Some(Duration::from_secs(10)));
+        assert_eq!(timeout(Method::GET, "/api/v1/health"), Some(DEFAULT_REQUEST_TIMEOUT));
+        assert_eq!(config.with_default(None).timeout_for(&Method::GET, "/api/v1/health"), None);
+
+        let route = RouteTimeout::parse(" POST /api/v1/workflows/trigger=2.5").unwrap();
+        assert_eq!(route.method, Some(Method::POST));
+        assert_eq!(route.timeout, Duration::from_millis(2500));
+        assert_eq!(RouteTimeout::parse("/api/v1/health=2").unwrap().method, None);
+        assert!(RouteTimeout::parse("/api/v1/health").is_none());
+        for seconds in ["-1", "0", "NaN", "inf", "1e300"] {
+            assert!(RouteTimeout::parse(&format!("/api/v1/health={}", seconds)).is_none(), "{}", seconds);
+        }
+    }
+
+    /// Read the configuration with the timeout variables set. This is the
+    /// only test setting them, so it cannot race with another.
+    fn config_from_env(default: &str, routes: &str) -> RequestTimeoutConfig {
+        env::set_var("REQUEST_TIMEOUT_SECS", default);
+        env::set_var("REQUEST_TIMEOUT_ROUTES", routes);
+        let config = RequestTimeoutConfig::from_env();
+        env::remove_var("REQUEST_TIMEOUT_SECS");
+        env::remove_var("REQUEST_TIMEOUT_ROUTES");
+        config
+    }
+
+    #[test]
+    fn test_from_env_ignores_invalid_timeouts() {
+        let config = config_from_env("12.5", "POST /api/v1/workflows/trigger=120,/x=-1,/y=inf,/z=NaN");
+        assert_eq!(config.default, Some(Duration::from_millis(12500)));
+        assert_eq!(config.routes.len(), 1);
+        assert_eq!(config.routes[0].path, "/api/v1/workflows/trigger");
+
+        assert_eq!(config_from_env("0", "").default, None);
+        for seconds in ["-1", "inf", "-inf", "NaN", "1e300", "soon"] {
+            assert_eq!(config_from_env(seconds, "").default, Some(DEFAULT_REQUEST_TIMEOUT), "{}", seconds);
+        }
+    }
+}
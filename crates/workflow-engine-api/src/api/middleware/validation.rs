@@ -0,0 +1,286 @@
+//! Request body validation against the OpenAPI spec
+//!
+//! [`RequestValidation`] checks JSON request bodies against the schema the
+//! spec declares for the matched route before the handler runs, and rejects
+//! bodies that don't conform with a 400 naming the offending field, e.g.
+//! `config.timeout` or `steps[2].name`. Routes without a declared JSON
+//! request body pass through untouched.
+
+use actix_web::{
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web::Bytes,
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use serde_json::Value;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+    sync::Arc,
+};
+use utoipa::OpenApi;
+
+pub use workflow_engine_core::json_schema::SchemaViolation;
+use workflow_engine_core::json_schema::SchemaValidator;
+
+use crate::api::{errors::ApiError, openapi::ApiDoc};
+
+/// Name used for the body itself in validation errors
+const BODY_FIELD: &str = "body";
+
+/// JSON request body schema declared for one operation
+#[derive(Debug)]
+struct BodySchema {
+    method: Method,
+    /// Path segments, `{param}` segments matching any value
+    segments: Vec<String>,
+    schema: Value,
+    required: bool,
+}
+
+impl BodySchema {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+        self.method == *method
+            && self.segments.len() == segments.len()
+            && self.segments.iter().zip(segments).all(|(expected, actual)| {
+                (expected.starts_with('{') && expected.ends_with('}')) || expected == actual
+            })
+    }
+}
+
+/// The request body schemas of an OpenAPI spec
+#[derive(Debug)]
+pub struct SpecValidator {
+    bodies: Vec<BodySchema>,
+    /// Validator over the whole spec, for resolving `$ref`s
+    validator: SchemaValidator,
+}
+
+impl SpecValidator {
+    pub fn new(spec: &utoipa::openapi::OpenApi) -> Self {
+        let spec = serde_json::to_value(spec).unwrap_or_default();
+        let mut bodies = Vec::new();
+
+        if let Some(paths) = spec["paths"].as_object() {
+            for (path, item) in paths {
+                for method in [Method::POST, Method::PUT, Method::PATCH, Method::DELETE] {
+                    let body = &item[method.as_str().to_lowercase()]["requestBody"];
+                    let schema = &body["content"]["application/json"]["schema"];
+                    if schema.is_null() {
+                        continue;
+                    }
+                    bodies.push(BodySchema {
+                        method,
+                        segments: path.trim_end_matches('/').split('/').map(String::from).collect(),
+                        schema: schema.clone(),
+                        required: body["required"].as_bool().unwrap_or(false),
+                    });
+                }
+            }
+        }
+
+        Self {
+            bodies,
+            validator: SchemaValidator::new(spec).with_root_name(BODY_FIELD),
+        }
+    }
+
+    /// Whether requests to `method` `path` have a body schema to check
+    pub fn has_body_schema(&self, method: &Method, path: &str) -> bool {
+        self.body_schema(method, path).is_some()
+    }
+
+    fn body_schema(&self, method: &Method, path: &str) -> Option<&BodySchema> {
+        self.bodies.iter().find(|body| body.matches(method, path))
+    }
+
+    /// Check `body` against the schema for `method` `path`. Routes without a
+    /// schema accept any body.
+    pub fn validate_body(&self, method: &Method, path: &str, body: &[u8]) -> Result<(), SchemaViolation> {
+        let Some(declared) = self.body_schema(method, path) else {
+            return Ok(());
+        };
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return if declared.required {
+                Err(SchemaViolation::new(BODY_FIELD, "required", "request body is required"))
+            } else {
+                Ok(())
+            };
+        }
+
+        let value: Value = serde_json::from_slice(body)
+            .map_err(|e| SchemaViolation::new(BODY_FIELD, "json", format!("invalid JSON: {}", e)))?;
+        self.validate(&declared.schema, &value, "")
+    }
+
+    /// Check `value`, found at `field`, against `schema`
+    pub fn validate(&self, schema: &Value, value: &Value, field: &str) -> Result<(), SchemaViolation> {
+        self.validator.validate_against(schema, value, field)
+    }
+}
+
+/// Middleware validating JSON request bodies against an OpenAPI spec
+#[derive(Clone)]
+pub struct RequestValidation {
+    validator: Arc<SpecValidator>,
+}
+
+impl RequestValidation {
+    /// Validate requests against `spec`, e.g. `ApiDoc::openapi()`
+    pub fn new(spec: &utoipa::openapi::OpenApi) -> Self {
+        Self {
+            validator: Arc::new(SpecValidator::new(spec)),
+        }
+    }
+}
+
+impl Default for RequestValidation {
+    /// Validate requests against this crate's [`ApiDoc`]
+    fn default() -> Self {
+        Self::new(&ApiDoc::openapi())
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestValidation
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestValidationService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestValidationService {
+            service: Rc::new(service),
+            validator: self.validator.clone(),
+        }))
+    }
+}
+
+pub struct RequestValidationService<S> {
+    service: Rc<S>,
+    validator: Arc<SpecValidator>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestValidationService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let validator = self.validator.clone();
+
+        Box::pin(async move {
+            if !validator.has_body_schema(req.method(), req.path()) {
+                return service.call(req).await;
+            }
+
+            // Read the body, then hand it back to the request for the handler
+            let body = req.extract::<Bytes>().await?;
+            if let Err(violation) = validator.validate_body(req.method(), req.path(), &body) {
+                log::debug!("Rejected request to {}: {:?}", req.path(), violation);
+                return Err(ApiError(violation.into_error("in request body")).into());
+            }
+            req.set_payload(Payload::from(body));
+
+            service.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, web, App, HttpResponse};
+    use serde_json::json;
+
+    const TRIGGER_PATH: &str = "/api/v1/workflows/trigger";
+
+    async fn echo(body: web::Json<Value>) -> HttpResponse {
+        HttpResponse::Ok().json(body.into_inner())
+    }
+
+    async fn post(body: Value) -> (StatusCode, Value) {
+        let app = test::init_service(
+            App::new()
+                .wrap(RequestValidation::default())
+                .route(TRIGGER_PATH, web::post().to(echo)),
+        )
+        .await;
+
+        let req = test::TestRequest::post().uri(TRIGGER_PATH).set_json(&body).to_request();
+        // Rejections are returned as errors, which the server renders
+        let resp = match test::try_call_service(&app, req).await {
+            Ok(resp) => resp.map_into_boxed_body().into_parts().1,
+            Err(error) => error.error_response(),
+        };
+        let status = resp.status();
+        let bytes = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_body_is_rejected_with_field() {
+        let (status, body) = post(json!({
+            "workflow_name": "research_to_documentation",
+            "inputs": {},
+            "config": { "timeout": "soon" }
+        }))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "WF_VALIDATION_ERROR");
+        let message = body["error"]["message"].as_str().unwrap();
+        assert!(message.contains("'config.timeout'"), "{}", message);
+
+        let (status, body) = post(json!({ "inputs": {} })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]["message"].as_str().unwrap().contains("'workflow_name'"));
+    }
+
+    #[actix_web::test]
+    async fn test_valid_body_reaches_handler() {
+        let request = json!({
+            "workflow_name": "research_to_documentation",
+            "inputs": { "topic": "rust" },
+            "config": { "timeout": 30, "environment": { "MODE": "fast" } }
+        });
+        let (status, body) = post(request.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, request);
+    }
+
+    #[actix_web::test]
+    async fn test_validator_paths_and_routes() {
+        let validator = SpecValidator::new(&ApiDoc::openapi());
+        assert!(validator.has_body_schema(&Method::POST, TRIGGER_PATH));
+        assert!(!validator.has_body_schema(&Method::GET, TRIGGER_PATH));
+        assert!(!validator.has_body_schema(&Method::POST, "/api/v1/unknown"));
+
+        let violation = validator
+            .validate_body(
+                &Method::POST,
+                TRIGGER_PATH,
+                br#"{"workflow_name": "x", "inputs": {}, "config": {"environment": {"A": 1}}}"#,
+            )
+            .unwrap_err();
+        assert_eq!(violation.field, "config.environment.A");
+        assert_eq!(violation.constraint, "type");
+
+        let violation = validator.validate_body(&Method::POST, TRIGGER_PATH, b"{").unwrap_err();
+        assert_eq!(violation.field, BODY_FIELD);
+    }
+}
@@ -25,6 +25,9 @@
 use actix_web::{HttpResponse, Responder, get, web};
 
 pub mod auth;
+// Status and reset of the service circuit breakers
+pub mod circuit_breakers;
+pub mod errors;
 pub mod events;
 pub mod health;
 pub mod login;
@@ -32,10 +35,14 @@ pub mod metrics;
 pub mod middleware;
 pub mod openapi;
 pub mod openapi_types;
+// Shared limit/cursor/sort/filter parameters for list endpoints
+pub mod pagination;
 pub mod rate_limit;
 pub mod routes;
 pub mod startup;
 pub mod uptime;
+// Signed inbound webhooks that trigger workflows
+pub mod webhooks;
 // Workflow API endpoints for triggering and monitoring workflows
 pub mod workflows;
 
@@ -58,6 +65,8 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
     // Workflow API routes for triggering and monitoring workflows
     workflows::configure_routes(cfg);
     metrics::configure_routes(cfg);
+    circuit_breakers::configure_routes(cfg);
+    webhooks::configure_routes(cfg);
     
     // Configure streaming routes
     // Streaming functionality not implemented - requires core::streaming module
@@ -5,12 +5,18 @@ use crate::{
     api::{
         login::{LoginRequest, LoginResponse},
         health::{HealthStatus, DetailedHealthStatus, HealthChecks, ComponentHealth, MCPServerHealth, SystemInfo, MemoryInfo, DiskInfo, ProcessInfo},
+        workflows::{
+            TriggerWorkflowRequest, WorkflowConfigOverrides, TriggerWorkflowResponse,
+            WorkflowStatusResponse, StepStatusInfo, WorkflowErrorInfo, WorkflowProgress,
+            WorkflowInstanceSummary, WorkflowInstancesResponse, AvailableWorkflowsResponse,
+        },
+        pagination::PageInfo,
         openapi_types::{
-            TriggerWorkflowRequest, TriggerWorkflowResponse, WorkflowStatusResponse,
             WorkflowTemplate, TemplateSearchParams,
             RegisterAgentRequest, RegisterAgentResponse, AgentInfo, HealthCheckResponse,
         },
     },
+    workflows::schema::{StepStatus, WorkflowStatus},
 };
 
 #[derive(OpenApi)]
@@ -19,6 +25,11 @@ use crate::{
         crate::api::login::login,
         crate::api::health::health_check,
         crate::api::health::detailed_health_check,
+        crate::api::workflows::trigger_workflow,
+        crate::api::workflows::get_workflow_status,
+        crate::api::workflows::stream_workflow_progress,
+        crate::api::workflows::list_workflow_instances,
+        crate::api::workflows::list_available_workflows,
     ),
     components(
         schemas(
@@ -34,8 +45,18 @@ use crate::{
             DiskInfo,
             ProcessInfo,
             TriggerWorkflowRequest,
+            WorkflowConfigOverrides,
             TriggerWorkflowResponse,
             WorkflowStatusResponse,
+            StepStatusInfo,
+            WorkflowErrorInfo,
+            WorkflowProgress,
+            WorkflowInstanceSummary,
+            WorkflowInstancesResponse,
+            AvailableWorkflowsResponse,
+            PageInfo,
+            WorkflowStatus,
+            StepStatus,
             WorkflowTemplate,
             TemplateSearchParams,
             RegisterAgentRequest,
@@ -115,6 +136,42 @@ pub fn configure(cfg: &mut actix_web::web::ServiceConfig) {
     
     cfg.service(
         SwaggerUi::new("/swagger-ui/{_:.*}")
-            .url("/api-docs/openapi.json", ApiDoc::openapi())
+            .url("/openapi.json", ApiDoc::openapi())
     );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_spec_documents_workflow_trigger_endpoint() {
+        let spec: Value = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let trigger = &spec["paths"]["/api/v1/workflows/trigger"]["post"];
+        assert_eq!(
+            trigger["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/TriggerWorkflowRequest"
+        );
+        assert_eq!(
+            trigger["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/TriggerWorkflowResponse"
+        );
+
+        let schemas = &spec["components"]["schemas"];
+        let request = &schemas["TriggerWorkflowRequest"];
+        assert!(request["properties"]["workflow_name"].is_object());
+        assert!(request["properties"]["config"].is_object());
+        assert!(schemas["TriggerWorkflowResponse"]["properties"]["status_url"].is_object());
+        assert!(schemas["WorkflowStatus"].is_object());
+
+        for path in [
+            "/api/v1/workflows/status/{instance_id}",
+            "/api/v1/workflows/instances",
+            "/api/v1/workflows/available",
+        ] {
+            assert!(spec["paths"][path]["get"].is_object(), "{} is not documented", path);
+        }
+    }
 }
\ No newline at end of file
@@ -1,31 +1,6 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-// Workflow types
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
-pub struct TriggerWorkflowRequest {
-    pub workflow_name: String,
-    pub inputs: serde_json::Value,
-}
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct TriggerWorkflowResponse {
-    pub instance_id: String,
-    pub status: String,
-    pub created_at: String,
-}
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct WorkflowStatusResponse {
-    pub instance_id: String,
-    pub workflow_name: String,
-    pub status: String,
-    pub created_at: String,
-    pub updated_at: String,
-    pub outputs: Option<serde_json::Value>,
-    pub error: Option<String>,
-}
-
 // Template types
 #[derive(Debug, Serialize, ToSchema)]
 pub struct WorkflowTemplate {
@@ -0,0 +1,351 @@
+/*!
+# List Parameters
+
+Shared query parameters for list endpoints:
+
+```text
+GET /api/v1/workflows/instances?limit=20&sort=-workflow_name&filter=research&cursor=...
+```
+
+- `limit` - page size, 1 to [`MAX_LIST_LIMIT`] (larger values are clamped),
+  [`DEFAULT_LIST_LIMIT`] when omitted
+- `cursor` - opaque `next_cursor` from the previous page
+- `sort` - a field the resource allows sorting by, prefixed with `-` for
+  descending order
+- `filter` - free text the resource matches items against
+
+Handlers take a [`ListParams<T>`] extractor, which rejects invalid parameters
+with a 400 validation error, and page their items with
+[`ListParams::paginate`].
+*/
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::future::{ready, Ready};
+use std::marker::PhantomData;
+use utoipa::{IntoParams, ToSchema};
+
+use workflow_engine_core::error::WorkflowError;
+
+use crate::api::errors::ApiError;
+
+/// Page size used when the request doesn't set `limit`
+pub const DEFAULT_LIST_LIMIT: usize = 20;
+
+/// Largest page size; larger `limit`s are clamped to it
+pub const MAX_LIST_LIMIT: usize = 100;
+
+const CURSOR_PREFIX: &str = "offset:";
+
+/// An item of a paginated listing
+pub trait Listable: Serialize {
+    /// Fields clients may sort by
+    const SORT_FIELDS: &'static [&'static str];
+
+    /// Field items are ordered by when no `sort` is given, and to break ties
+    /// otherwise. It should be unique so that pages are stable.
+    const DEFAULT_SORT: &'static str;
+
+    /// Whether the item matches the `filter` parameter
+    fn matches_filter(&self, filter: &str) -> bool;
+
+    /// The value of `field` to sort by. Defaults to the serialized field.
+    fn sort_value(&self, field: &str) -> Value {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|mut value| value.get_mut(field).map(Value::take))
+            .unwrap_or(Value::Null)
+    }
+}
+
+/// Plain names, sortable as `name` and filtered by substring
+impl Listable for String {
+    const SORT_FIELDS: &'static [&'static str] = &["name"];
+    const DEFAULT_SORT: &'static str = "name";
+
+    fn matches_filter(&self, filter: &str) -> bool {
+        self.to_lowercase().contains(&filter.to_lowercase())
+    }
+
+    fn sort_value(&self, _field: &str) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+/// Raw list query parameters, as sent by the client
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListQuery {
+    /// Page size, at most 100
+    pub limit: Option<i64>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Field to sort by, prefixed with `-` for descending order
+    pub sort: Option<String>,
+    /// Text to filter items by
+    pub filter: Option<String>,
+}
+
+/// Requested sort order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOrder {
+    pub field: String,
+    pub descending: bool,
+}
+
+/// Where a listing stands after a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PageInfo {
+    /// Cursor to pass as `cursor` for the next page
+    pub next_cursor: Option<String>,
+    pub has_next_page: bool,
+    /// Number of items matching the filter, across all pages
+    pub total_count: usize,
+}
+
+/// Validated list parameters for a listing of `T`
+#[derive(Debug, Clone)]
+pub struct ListParams<T> {
+    pub limit: usize,
+    /// Items to skip, decoded from the cursor
+    pub offset: usize,
+    pub sort: Option<SortOrder>,
+    pub filter: Option<String>,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T: Listable> ListParams<T> {
+    /// Validate `query` against the limits and sort fields of `T`
+    pub fn from_query(query: ListQuery) -> Result<Self, WorkflowError> {
+        let limit = match query.limit {
+            None => DEFAULT_LIST_LIMIT,
+            Some(limit) if limit < 1 => {
+                return Err(WorkflowError::validation_error_with_value(
+                    "limit must be at least 1",
+                    "limit",
+                    Some(limit.to_string()),
+                    "minimum",
+                    "in list parameters",
+                ));
+            }
+            Some(limit) => (limit as usize).min(MAX_LIST_LIMIT),
+        };
+
+        let offset = match query.cursor.as_deref() {
+            None | Some("") => 0,
+            Some(cursor) => decode_cursor(cursor).ok_or_else(|| {
+                WorkflowError::validation_error_with_value(
+                    "cursor is not a cursor returned by this listing",
+                    "cursor",
+                    Some(cursor.to_string()),
+                    "format",
+                    "in list parameters",
+                )
+            })?,
+        };
+
+        let sort = match query.sort.as_deref() {
+            None | Some("") => None,
+            Some(sort) => {
+                let (field, descending) = match sort.strip_prefix('-') {
+                    Some(field) => (field, true),
+                    None => (sort, false),
+                };
+                if !T::SORT_FIELDS.contains(&field) {
+                    return Err(WorkflowError::validation_error_with_value(
+                        format!("cannot sort by '{}', expected one of {}", field, T::SORT_FIELDS.join(", ")),
+                        "sort",
+                        Some(sort.to_string()),
+                        "allowed_fields",
+                        "in list parameters",
+                    ));
+                }
+                Some(SortOrder {
+                    field: field.to_string(),
+                    descending,
+                })
+            }
+        };
+
+        Ok(Self {
+            limit,
+            offset,
+            sort,
+            filter: query.filter.filter(|filter| !filter.is_empty()),
+            item: PhantomData,
+        })
+    }
+
+    /// Filter, sort and page `items`
+    pub fn paginate(&self, items: Vec<T>) -> (Vec<T>, PageInfo) {
+        let mut items: Vec<T> = match &self.filter {
+            Some(filter) => items.into_iter().filter(|item| item.matches_filter(filter)).collect(),
+            None => items,
+        };
+
+        items.sort_by(|a, b| {
+            let primary = match &self.sort {
+                Some(sort) => {
+                    let order = compare(&a.sort_value(&sort.field), &b.sort_value(&sort.field));
+                    if sort.descending { order.reverse() } else { order }
+                }
+                None => Ordering::Equal,
+            };
+            primary.then_with(|| compare(&a.sort_value(T::DEFAULT_SORT), &b.sort_value(T::DEFAULT_SORT)))
+        });
+
+        let total_count = items.len();
+        let page: Vec<T> = items.into_iter().skip(self.offset).take(self.limit).collect();
+        let end = self.offset + page.len();
+        let has_next_page = end < total_count;
+
+        let page_info = PageInfo {
+            next_cursor: has_next_page.then(|| encode_cursor(end)),
+            has_next_page,
+            total_count,
+        };
+        (page, page_info)
+    }
+}
+
+impl<T: Listable> FromRequest for ListParams<T> {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let params = web::Query::<ListQuery>::from_query(req.query_string())
+            .map_err(|e| {
+                WorkflowError::validation_error(e.to_string(), "query", "format", "in list parameters")
+            })
+            .and_then(|query| Self::from_query(query.into_inner()));
+        ready(params.map_err(ApiError))
+    }
+}
+
+fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}{}", CURSOR_PREFIX, offset))
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    let decoded = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    String::from_utf8(decoded).ok()?.strip_prefix(CURSOR_PREFIX)?.parse().ok()
+}
+
+/// Order JSON values of the same kind; nulls sort last
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap_or_default(), b.as_f64().unwrap_or_default());
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test as actix_test, App, HttpResponse, ResponseError};
+
+    #[derive(Debug, Serialize)]
+    struct Item {
+        id: u32,
+        name: String,
+    }
+
+    impl Listable for Item {
+        const SORT_FIELDS: &'static [&'static str] = &["name", "id"];
+        const DEFAULT_SORT: &'static str = "id";
+
+        fn matches_filter(&self, filter: &str) -> bool {
+            self.name.contains(filter)
+        }
+    }
+
+    fn items() -> Vec<Item> {
+        ["delta", "alpha", "charlie", "bravo", "alpha-2"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| Item {
+                id: id as u32,
+                name: name.to_string(),
+            })
+            .collect()
+    }
+
+    fn params(query: &str) -> Result<ListParams<Item>, WorkflowError> {
+        ListParams::from_query(web::Query::<ListQuery>::from_query(query).unwrap().into_inner())
+    }
+
+    fn names(items: &[Item]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_limit_bounds() {
+        assert_eq!(params("").unwrap().limit, DEFAULT_LIST_LIMIT);
+        assert_eq!(params("limit=5").unwrap().limit, 5);
+        assert_eq!(params("limit=1000").unwrap().limit, MAX_LIST_LIMIT);
+        assert!(params("limit=0").is_err());
+        assert!(params("limit=-3").is_err());
+    }
+
+    #[test]
+    fn test_sort_filter_and_cursor() {
+        let list = params("sort=-name&limit=2").unwrap();
+        let (page, info) = list.paginate(items());
+        assert_eq!(names(&page), ["delta", "charlie"]);
+        assert!(info.has_next_page);
+        assert_eq!(info.total_count, 5);
+
+        let next = params(&format!("sort=-name&limit=2&cursor={}", info.next_cursor.unwrap())).unwrap();
+        let (page, info) = next.paginate(items());
+        assert_eq!(names(&page), ["bravo", "alpha-2"]);
+
+        let (page, info) = params(&format!("sort=-name&limit=2&cursor={}", info.next_cursor.unwrap()))
+            .unwrap()
+            .paginate(items());
+        assert_eq!(names(&page), ["alpha"]);
+        assert_eq!(info.next_cursor, None);
+
+        let (page, info) = params("filter=alpha").unwrap().paginate(items());
+        assert_eq!(names(&page), ["alpha", "alpha-2"]);
+        assert_eq!(info.total_count, 2);
+
+        assert!(params("cursor=not-a-cursor").is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_unknown_sort_field_is_bad_request() {
+        let error = params("sort=colour").unwrap_err();
+        assert_eq!(ApiError(error).status_code(), StatusCode::BAD_REQUEST);
+
+        let app = actix_test::init_service(App::new().route(
+            "/items",
+            web::get().to(|list: ListParams<Item>| async move {
+                let (items, page_info) = list.paginate(items());
+                HttpResponse::Ok().json(serde_json::json!({ "items": items, "page_info": page_info }))
+            }),
+        ))
+        .await;
+
+        let req = actix_test::TestRequest::get().uri("/items?sort=-colour").to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: Value = actix_test::read_body_json(resp).await;
+        assert!(body["error"]["message"].as_str().unwrap().contains("cannot sort by 'colour'"));
+
+        let req = actix_test::TestRequest::get().uri("/items?sort=name&limit=2").to_request();
+        let body: Value = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["items"][0]["name"], "alpha");
+        assert_eq!(body["page_info"]["total_count"], 5);
+    }
+}
@@ -3,7 +3,10 @@ use actix_web::{
     error::ErrorTooManyRequests,
     Error, HttpMessage,
 };
+use async_trait::async_trait;
 use futures_util::future::LocalBoxFuture;
+use redis::aio::ConnectionManager;
+use redis::{Client, RedisResult, Script};
 use std::{
     collections::HashMap,
     future::{ready, Ready},
@@ -11,11 +14,17 @@ use std::{
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
+use tracing::{info, warn};
 
 #[derive(Clone)]
 pub struct RateLimitConfig {
     pub requests_per_minute: u32,
     pub burst_size: u32,
+    /// Redis URL used to share buckets across API instances.
+    /// When `None` (or Redis is unreachable) limits are enforced per instance.
+    pub redis_url: Option<String>,
+    /// Prefix for the Redis keys holding the shared buckets
+    pub redis_key_prefix: String,
 }
 
 impl Default for RateLimitConfig {
@@ -23,10 +32,18 @@ impl Default for RateLimitConfig {
         Self {
             requests_per_minute: 60,
             burst_size: 10,
+            redis_url: None,
+            redis_key_prefix: "rate_limit".to_string(),
         }
     }
 }
 
+impl RateLimitConfig {
+    fn refill_rate(&self) -> f64 {
+        self.requests_per_minute as f64 / 60.0
+    }
+}
+
 struct TokenBucket {
     tokens: f64,
     last_update: Instant,
@@ -62,22 +79,166 @@ impl TokenBucket {
     }
 }
 
+/// Token bucket evaluated atomically inside Redis.
+///
+/// Uses the Redis server clock so that every API instance refills the
+/// bucket at the same pace regardless of local clock skew.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local rate = tonumber(ARGV[1])
+local capacity = tonumber(ARGV[2])
+local requested = tonumber(ARGV[3])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) + tonumber(time[2]) / 1000000
+
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(bucket[1])
+local ts = tonumber(bucket[2])
+if tokens == nil or ts == nil then
+    tokens = capacity
+    ts = now
+end
+
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * rate)
+local allowed = 0
+if tokens >= requested then
+    tokens = tokens - requested
+    allowed = 1
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', tostring(now))
+local ttl = 3600
+if rate > 0 then
+    ttl = math.ceil(capacity / rate) + 1
+end
+redis.call('EXPIRE', KEYS[1], ttl)
+return allowed
+"#;
+
+/// Token buckets shared by every API instance using the same store
+#[async_trait]
+pub trait SharedRateLimitStore: Send + Sync {
+    /// Consume a token for `key`, returning whether the request is allowed
+    async fn check_rate_limit(&self, key: &str) -> RedisResult<bool>;
+}
+
+/// Rate limiter whose buckets live in Redis, enforcing limits cluster-wide
+pub struct RedisRateLimiter {
+    /// Multiplexed connection, cloned for each check so checks don't wait on
+    /// each other
+    connection: ConnectionManager,
+    script: Script,
+    key_prefix: String,
+    rate: f64,
+    capacity: f64,
+}
+
+impl RedisRateLimiter {
+    /// Connect to Redis and prepare the token bucket script
+    pub async fn connect(redis_url: &str, config: &RateLimitConfig) -> RedisResult<Self> {
+        let client = Client::open(redis_url)?;
+        let connection = ConnectionManager::new(client).await?;
+
+        Ok(Self {
+            connection,
+            script: Script::new(TOKEN_BUCKET_SCRIPT),
+            key_prefix: config.redis_key_prefix.clone(),
+            rate: config.refill_rate(),
+            capacity: config.burst_size as f64,
+        })
+    }
+
+}
+
+#[async_trait]
+impl SharedRateLimitStore for RedisRateLimiter {
+    async fn check_rate_limit(&self, key: &str) -> RedisResult<bool> {
+        let mut conn = self.connection.clone();
+        let allowed: i64 = self
+            .script
+            .key(format!("{}:{}", self.key_prefix, key))
+            .arg(self.rate)
+            .arg(self.capacity)
+            .arg(1.0)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(allowed == 1)
+    }
+}
+
 pub struct RateLimiter {
     buckets: Arc<Mutex<HashMap<String, TokenBucket>>>,
     config: RateLimitConfig,
+    redis: Option<Arc<dyn SharedRateLimitStore>>,
 }
 
 impl RateLimiter {
+    /// Create an in-memory rate limiter scoped to this instance
     pub fn new(config: RateLimitConfig) -> Self {
         Self {
             buckets: Arc::new(Mutex::new(HashMap::new())),
             config,
+            redis: None,
         }
     }
 
+    /// Create a rate limiter from configuration.
+    ///
+    /// When `redis_url` is set the limiter shares its buckets through Redis;
+    /// if Redis cannot be reached it falls back to in-memory limiting.
+    pub async fn from_config(config: RateLimitConfig) -> Self {
+        let redis = match &config.redis_url {
+            Some(redis_url) => match RedisRateLimiter::connect(redis_url, &config).await {
+                Ok(redis) => {
+                    info!("Rate limiting backed by Redis at {}", redis_url);
+                    Some(Arc::new(redis) as Arc<dyn SharedRateLimitStore>)
+                }
+                Err(e) => {
+                    warn!("Failed to connect rate limiter to Redis, using in-memory limits: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Self {
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            config,
+            redis,
+        }
+    }
+
+    /// Create a rate limiter sharing its buckets through `store`
+    pub fn with_shared_store(config: RateLimitConfig, store: Arc<dyn SharedRateLimitStore>) -> Self {
+        Self {
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            config,
+            redis: Some(store),
+        }
+    }
+
+    /// Whether limits are enforced cluster-wide through Redis
+    pub fn is_distributed(&self) -> bool {
+        self.redis.is_some()
+    }
+
+    /// Consume a token for `key`, returning whether the request is allowed.
+    ///
+    /// Redis errors degrade to the local bucket rather than rejecting traffic.
+    pub async fn check(&self, key: &str) -> bool {
+        if let Some(redis) = &self.redis {
+            match redis.check_rate_limit(key).await {
+                Ok(allowed) => return allowed,
+                Err(e) => warn!("Redis rate limit check failed, using in-memory bucket: {}", e),
+            }
+        }
+
+        self.check_rate_limit(key)
+    }
+
     fn check_rate_limit(&self, key: &str) -> bool {
         let mut buckets = self.buckets.lock().unwrap();
-        let rate = self.config.requests_per_minute as f64 / 60.0;
+        let rate = self.config.refill_rate();
         let capacity = self.config.burst_size as f64;
 
         let bucket = buckets
@@ -94,6 +255,18 @@ impl RateLimiter {
             now.duration_since(bucket.last_update) < Duration::from_secs(300)
         });
     }
+
+    /// Periodically drop idle in-memory buckets
+    pub fn spawn_cleanup_task(self: &Arc<Self>) {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                limiter.cleanup_old_buckets();
+            }
+        });
+    }
 }
 
 // Middleware factory
@@ -106,17 +279,15 @@ impl RateLimitMiddleware {
         let limiter = Arc::new(RateLimiter::new(config));
 
         // Spawn a cleanup task
-        let limiter_clone = limiter.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(60));
-            loop {
-                interval.tick().await;
-                limiter_clone.cleanup_old_buckets();
-            }
-        });
+        limiter.spawn_cleanup_task();
 
         Self { limiter }
     }
+
+    /// Wrap an existing limiter, e.g. one shared across workers via Redis
+    pub fn with_limiter(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
 }
 
 impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
@@ -177,11 +348,124 @@ where
             };
 
             // Check rate limit
-            if limiter.check_rate_limit(&client_id) {
+            if limiter.check(&client_id).await {
                 service.call(req).await
             } else {
                 Err(ErrorTooManyRequests("Rate limit exceeded"))
             }
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(burst_size: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_minute: 1,
+            burst_size,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_limit_enforced() {
+        let limiter = RateLimiter::from_config(config(2)).await;
+        assert!(!limiter.is_distributed());
+
+        assert!(limiter.check("client").await);
+        assert!(limiter.check("client").await);
+        assert!(!limiter.check("client").await);
+        // Other clients have their own bucket
+        assert!(limiter.check("other").await);
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_in_memory_when_redis_unreachable() {
+        let limiter = RateLimiter::from_config(RateLimitConfig {
+            redis_url: Some("redis://127.0.0.1:1".to_string()),
+            ..config(1)
+        })
+        .await;
+
+        assert!(!limiter.is_distributed());
+        assert!(limiter.check("client").await);
+        assert!(!limiter.check("client").await);
+    }
+
+    /// Buckets shared in memory, standing in for Redis
+    struct SharedBuckets {
+        limiter: RateLimiter,
+        available: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl SharedRateLimitStore for SharedBuckets {
+        async fn check_rate_limit(&self, key: &str) -> RedisResult<bool> {
+            if !self.available.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err((redis::ErrorKind::IoError, "connection refused").into());
+            }
+            Ok(self.limiter.check_rate_limit(key))
+        }
+    }
+
+    fn shared_buckets(burst_size: u32) -> Arc<SharedBuckets> {
+        Arc::new(SharedBuckets {
+            limiter: RateLimiter::new(config(burst_size)),
+            available: std::sync::atomic::AtomicBool::new(true),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_shared_store_limits_across_instances() {
+        let store = shared_buckets(3);
+        let first = RateLimiter::with_shared_store(config(3), store.clone());
+        let second = RateLimiter::with_shared_store(config(3), store);
+        assert!(first.is_distributed());
+
+        assert!(first.check("client").await);
+        assert!(second.check("client").await);
+        assert!(first.check("client").await);
+        // Burst of 3 is exhausted across both instances combined
+        assert!(!second.check("client").await);
+        assert!(!first.check("client").await);
+    }
+
+    #[tokio::test]
+    async fn test_store_errors_fall_back_to_the_local_bucket() {
+        let store = shared_buckets(1);
+        let limiter = RateLimiter::with_shared_store(config(2), store.clone());
+        assert!(limiter.check("client").await);
+        assert!(!limiter.check("client").await);
+
+        // While the store is down, the instance's own bucket (burst 2) applies
+        store.available.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert!(limiter.check("client").await);
+        assert!(limiter.check("client").await);
+        assert!(!limiter.check("client").await);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires Redis
+    async fn test_redis_limit_shared_across_instances() {
+        let redis_url = std::env::var("REDIS_URL")
+            .unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let shared = RateLimitConfig {
+            redis_url: Some(redis_url),
+            redis_key_prefix: format!("rate_limit_test:{}", uuid::Uuid::new_v4()),
+            ..config(3)
+        };
+
+        let first = RateLimiter::from_config(shared.clone()).await;
+        let second = RateLimiter::from_config(shared).await;
+        assert!(first.is_distributed());
+        assert!(second.is_distributed());
+
+        assert!(first.check("client").await);
+        assert!(second.check("client").await);
+        assert!(first.check("client").await);
+        // Burst of 3 is exhausted across both instances combined
+        assert!(!second.check("client").await);
+        assert!(!first.check("client").await);
+    }
+}
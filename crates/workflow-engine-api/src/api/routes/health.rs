@@ -3,6 +3,7 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 use crate::api::uptime::{UptimeTracker, get_uptime_tracker};
+use crate::db::migration::MigrationService;
 
 /// Health check response structure
 #[derive(Serialize, Deserialize, Debug)]
@@ -196,6 +197,29 @@ fn check_database_health(
     }
 }
 
+/// Migration status endpoint (GET /health/migrations)
+/// Reports whether every migration has been applied to the database, and
+/// responds 503 until then so deploys can hold traffic back
+pub async fn migration_status(
+    migrations: Option<web::Data<MigrationService>>,
+) -> Result<HttpResponse> {
+    let Some(migrations) = migrations else {
+        return Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "up_to_date": false,
+            "message": "Migration service not available in application data"
+        })));
+    };
+
+    match migrations.check().await {
+        Ok(report) if report.up_to_date => Ok(HttpResponse::Ok().json(report)),
+        Ok(report) => Ok(HttpResponse::ServiceUnavailable().json(report)),
+        Err(e) => Ok(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "up_to_date": false,
+            "error": e.to_string()
+        }))),
+    }
+}
+
 /// Configure health check routes
 pub fn configure_health_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -205,6 +229,10 @@ pub fn configure_health_routes(cfg: &mut web::ServiceConfig) {
     .service(
         web::resource("/health/detailed")
             .route(web::get().to(detailed_health_check))
+    )
+    .service(
+        web::resource("/health/migrations")
+            .route(web::get().to(migration_status))
     );
 }
 
@@ -272,4 +300,50 @@ mod tests {
         let body: HealthResponse = test::read_body_json(resp).await;
         assert_eq!(body.status, "healthy");
     }
-}
\ No newline at end of file
+
+    fn migration_service(applied: usize) -> web::Data<MigrationService> {
+        use crate::db::migration::{MigrationConfig, tests::InMemoryMigrationManager};
+
+        let manager = InMemoryMigrationManager::new(
+            &["20241213_000001", "20241213_000002", "20250101_000001"],
+            applied,
+        );
+        web::Data::new(manager.into_service(MigrationConfig::default()))
+    }
+
+    #[actix_web::test]
+    async fn test_migrations_endpoint_reports_pending_migrations() {
+        let app = test::init_service(
+            App::new()
+                .app_data(migration_service(2))
+                .configure(configure_health_routes)
+        ).await;
+
+        let req = test::TestRequest::get().uri("/health/migrations").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["up_to_date"], false);
+        assert_eq!(body["applied_version"], "20241213_000002");
+        assert_eq!(body["latest_version"], "20250101_000001");
+        assert_eq!(body["pending"], serde_json::json!(["20250101_000001"]));
+    }
+
+    #[actix_web::test]
+    async fn test_migrations_endpoint_up_to_date() {
+        let app = test::init_service(
+            App::new()
+                .app_data(migration_service(3))
+                .configure(configure_health_routes)
+        ).await;
+
+        let req = test::TestRequest::get().uri("/health/migrations").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["up_to_date"], true);
+        assert_eq!(body["pending"], serde_json::json!([]));
+    }
+}
@@ -0,0 +1,371 @@
+/*!
+# Inbound Webhooks
+
+`POST /webhooks/{workflow_type}` lets external systems trigger a workflow by
+posting a signed JSON payload.
+
+Each workflow type must be enabled with a [`WebhookEndpoint`] holding the
+secret shared with the sender. Requests are signed with HMAC-SHA256 over the
+raw body, sent as `X-Signature: sha256=<hex>`, the same scheme the outbound
+`WebhookNode` uses. A missing or invalid signature is rejected with 401
+before the body is parsed.
+
+In sync mode the workflow runs before the response is sent and its node
+results are returned; in async mode the request is acknowledged with 202 and
+the workflow runs in the background.
+*/
+
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use workflow_engine_core::error::{ErrorCategory, WorkflowError};
+use workflow_engine_core::workflow::versions::WorkflowVersions;
+use crate::api::errors::{ErrorBody, ErrorEnvelope, error_response_for_request};
+
+/// Header carrying the `sha256=<hex>` payload signature
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Whether a webhook waits for its workflow to finish
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionMode {
+    /// Run the workflow and respond with its results
+    #[default]
+    Sync,
+    /// Respond with 202 Accepted and run the workflow in the background
+    Async,
+}
+
+/// Webhook configuration for one workflow type
+#[derive(Debug, Clone)]
+pub struct WebhookEndpoint {
+    secret: String,
+    input_pointer: Option<String>,
+    mode: ExecutionMode,
+}
+
+impl WebhookEndpoint {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self {
+            secret: secret.into(),
+            input_pointer: None,
+            mode: ExecutionMode::default(),
+        }
+    }
+
+    /// Use the part of the body at this JSON pointer (e.g. `/data/object`) as
+    /// the workflow input instead of the whole body
+    pub fn with_input_pointer(mut self, pointer: impl Into<String>) -> Self {
+        self.input_pointer = Some(pointer.into());
+        self
+    }
+
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Check a `sha256=<hex>` signature of `body` in constant time
+    pub fn verify_signature(&self, body: &[u8], signature: &str) -> bool {
+        let Some(digest) = signature
+            .strip_prefix("sha256=")
+            .and_then(|hex_digest| hex::decode(hex_digest).ok())
+        else {
+            return false;
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any size");
+        mac.update(body);
+        mac.verify_slice(&digest).is_ok()
+    }
+
+    /// Map a verified request body to the workflow input
+    pub fn workflow_input(&self, body: &[u8]) -> Result<Value, WorkflowError> {
+        let payload: Value = serde_json::from_slice(body).map_err(|e| {
+            WorkflowError::DeserializationError {
+                message: format!("Webhook body is not valid JSON: {}", e),
+                expected_type: "JSON".to_string(),
+                context: "in WebhookEndpoint::workflow_input".to_string(),
+                raw_data: Some(String::from_utf8_lossy(&body[..body.len().min(256)]).into_owned()),
+                source: Some(e),
+            }
+        })?;
+
+        match &self.input_pointer {
+            None => Ok(payload),
+            Some(pointer) => payload.pointer(pointer).cloned().ok_or_else(|| {
+                WorkflowError::validation_error(
+                    format!("Webhook body has no value at {}", pointer),
+                    "body",
+                    "input pointer must resolve",
+                    "in WebhookEndpoint::workflow_input",
+                )
+            }),
+        }
+    }
+}
+
+/// Workflows that can be triggered by webhook, registered as app data
+pub struct WebhookTriggers {
+    workflows: Arc<WorkflowVersions>,
+    endpoints: HashMap<String, WebhookEndpoint>,
+}
+
+impl WebhookTriggers {
+    pub fn new(workflows: Arc<WorkflowVersions>) -> Self {
+        Self {
+            workflows,
+            endpoints: HashMap::new(),
+        }
+    }
+
+    /// Enable `POST /webhooks/{workflow_type}`, running the latest version
+    /// of that workflow
+    pub fn with_endpoint(mut self, workflow_type: impl Into<String>, endpoint: WebhookEndpoint) -> Self {
+        self.endpoints.insert(workflow_type.into(), endpoint);
+        self
+    }
+
+    pub fn endpoint(&self, workflow_type: &str) -> Option<&WebhookEndpoint> {
+        self.endpoints.get(workflow_type)
+    }
+}
+
+fn rejection(status: StatusCode, code: &str, message: String) -> HttpResponse {
+    HttpResponse::build(status).json(ErrorEnvelope {
+        error: ErrorBody {
+            code: code.to_string(),
+            message,
+            category: ErrorCategory::User,
+        },
+    })
+}
+
+/// Trigger a workflow from a signed webhook
+/// POST /webhooks/{workflow_type}
+pub async fn receive_webhook(
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Bytes,
+) -> HttpResponse {
+    let workflow_type = path.into_inner();
+    let Some(triggers) = req.app_data::<web::Data<WebhookTriggers>>() else {
+        return rejection(
+            StatusCode::NOT_FOUND,
+            "WF_WEBHOOK_NOT_FOUND",
+            "Webhooks are not enabled".to_string(),
+        );
+    };
+    let Some(endpoint) = triggers.endpoint(&workflow_type) else {
+        return rejection(
+            StatusCode::NOT_FOUND,
+            "WF_WEBHOOK_NOT_FOUND",
+            format!("No webhook is configured for workflow '{}'", workflow_type),
+        );
+    };
+
+    let signature = req
+        .headers()
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok());
+    if !signature.is_some_and(|signature| endpoint.verify_signature(&body, signature)) {
+        log::warn!("Rejected webhook for '{}': invalid signature", workflow_type);
+        return rejection(
+            StatusCode::UNAUTHORIZED,
+            "WF_INVALID_SIGNATURE",
+            format!("Missing or invalid {} header", SIGNATURE_HEADER),
+        );
+    }
+
+    let input = match endpoint.workflow_input(&body) {
+        Ok(input) => input,
+        Err(e) => return error_response_for_request(&req, &e),
+    };
+
+    let workflows = triggers.workflows.clone();
+    let run = {
+        let workflow_type = workflow_type.clone();
+        move || workflows.run(&workflow_type, None, input)
+    };
+
+    match endpoint.mode {
+        ExecutionMode::Async => {
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = run() {
+                    log::error!("Webhook-triggered workflow failed: {}", e);
+                }
+            });
+            HttpResponse::Accepted().json(json!({
+                "workflow_type": workflow_type,
+                "status": "accepted",
+            }))
+        }
+        ExecutionMode::Sync => match tokio::task::spawn_blocking(run).await {
+            Ok(Ok(context)) => HttpResponse::Ok().json(json!({
+                "workflow_type": workflow_type,
+                "event_id": context.event_id,
+                "status": "completed",
+                "nodes": context.get_all_data(),
+            })),
+            Ok(Err(e)) => error_response_for_request(&req, &e),
+            Err(e) => error_response_for_request(
+                &req,
+                &WorkflowError::RuntimeError {
+                    message: format!("Webhook workflow task failed: {}", e),
+                },
+            ),
+        },
+    }
+}
+
+/// Configure webhook routes
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/webhooks/{workflow_type}", web::post().to(receive_webhook));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, test};
+    use std::time::Duration;
+    use workflow_engine_core::nodes::Node;
+    use workflow_engine_core::task::TaskContext;
+    use workflow_engine_core::workflow::builder::WorkflowBuilder;
+
+    const SECRET: &str = "webhook-secret";
+
+    #[derive(Debug)]
+    struct RecordOrder;
+
+    impl Node for RecordOrder {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let order_id = task_context.event_data["order_id"].clone();
+            task_context.update_node("record_order", json!({ "order_id": order_id }));
+            Ok(task_context)
+        }
+    }
+
+    fn sign(body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body.as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn triggers(endpoint: WebhookEndpoint) -> web::Data<WebhookTriggers> {
+        let workflow = WorkflowBuilder::new::<RecordOrder>("orders".to_string())
+            .build()
+            .unwrap();
+        workflow.register_node(RecordOrder);
+        let versions = WorkflowVersions::new();
+        versions.register(workflow).unwrap();
+        web::Data::new(WebhookTriggers::new(Arc::new(versions)).with_endpoint("orders", endpoint))
+    }
+
+    #[tokio::test]
+    async fn test_signed_payload_runs_workflow() {
+        let app = test::init_service(
+            App::new()
+                .app_data(triggers(WebhookEndpoint::new(SECRET).with_input_pointer("/data")))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let body = r#"{"type":"order.created","data":{"order_id":42}}"#;
+        let req = test::TestRequest::post()
+            .uri("/webhooks/orders")
+            .insert_header((SIGNATURE_HEADER, sign(body)))
+            .set_payload(body)
+            .to_request();
+        let resp: Value = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(resp["status"], "completed");
+        assert_eq!(resp["nodes"]["record_order"], json!({ "order_id": 42 }));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_or_unsigned_payload_is_rejected() {
+        let app = test::init_service(
+            App::new()
+                .app_data(triggers(WebhookEndpoint::new(SECRET)))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let signature = sign(r#"{"order_id":42}"#);
+        let req = test::TestRequest::post()
+            .uri("/webhooks/orders")
+            .insert_header((SIGNATURE_HEADER, signature))
+            .set_payload(r#"{"order_id":43}"#)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"]["code"], "WF_INVALID_SIGNATURE");
+
+        let req = test::TestRequest::post()
+            .uri("/webhooks/orders")
+            .set_payload(r#"{"order_id":42}"#)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = test::TestRequest::post()
+            .uri("/webhooks/refunds")
+            .insert_header((SIGNATURE_HEADER, sign("{}")))
+            .set_payload("{}")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_async_endpoint_accepts_and_runs_in_background() {
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        #[derive(Debug)]
+        struct Notify(std::sync::Mutex<std::sync::mpsc::Sender<Value>>);
+
+        impl Node for Notify {
+            fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+                self.0.lock().unwrap().send(task_context.event_data.clone()).unwrap();
+                Ok(task_context)
+            }
+        }
+
+        let workflow = WorkflowBuilder::new::<Notify>("orders".to_string())
+            .build()
+            .unwrap();
+        workflow.register_node(Notify(std::sync::Mutex::new(tx)));
+        let versions = WorkflowVersions::new();
+        versions.register(workflow).unwrap();
+        let triggers = WebhookTriggers::new(Arc::new(versions)).with_endpoint(
+            "orders",
+            WebhookEndpoint::new(SECRET).with_mode(ExecutionMode::Async),
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(triggers))
+                .configure(configure_routes),
+        )
+        .await;
+
+        let body = r#"{"order_id":7}"#;
+        let req = test::TestRequest::post()
+            .uri("/webhooks/orders")
+            .insert_header((SIGNATURE_HEADER, sign(body)))
+            .set_payload(body)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+        let input = tokio::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(input, json!({ "order_id": 7 }));
+    }
+}
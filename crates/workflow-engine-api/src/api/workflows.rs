@@ -5,17 +5,26 @@ This module implements HTTP API endpoints for triggering and monitoring workflow
 
 Task 2.5: Create workflow trigger API endpoint (POST /api/v1/workflows/trigger)
 Task 2.6: Implement workflow status endpoint (GET /api/v1/workflows/status/{id})
+
+Progress of a running instance streams as server-sent events from
+GET /api/v1/workflows/progress/{id}.
 */
 
-use actix_web::{HttpResponse, Result as ActixResult, web};
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
+use futures_util::Stream;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use workflow_engine_core::error::WorkflowError;
+use workflow_engine_core::{error::WorkflowError, task::TenantId, workflow::events::NodeEvent};
+use workflow_engine_mcp::connection_pool::McpConnectionPool;
+use crate::api::errors::error_response_for_request;
+use crate::api::middleware::auth::ClaimsExtractor;
+use crate::api::pagination::{ListParams, ListQuery, Listable, PageInfo};
 use crate::workflows::{
     executor::{WorkflowExecutor, WorkflowFactory},
     parser::{WorkflowRegistry, create_default_registry},
@@ -24,7 +33,7 @@ use crate::workflows::{
 };
 
 /// Request payload for triggering a workflow
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct TriggerWorkflowRequest {
     /// Name of the workflow to execute
     pub workflow_name: String,
@@ -37,7 +46,7 @@ pub struct TriggerWorkflowRequest {
 }
 
 /// Configuration overrides for workflow execution
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct WorkflowConfigOverrides {
     /// Override workflow timeout in seconds
     pub timeout: Option<u64>,
@@ -53,7 +62,7 @@ pub struct WorkflowConfigOverrides {
 }
 
 /// Response for workflow trigger request
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TriggerWorkflowResponse {
     /// Unique instance ID for the triggered workflow
     pub instance_id: Uuid,
@@ -72,7 +81,7 @@ pub struct TriggerWorkflowResponse {
 }
 
 /// Response for workflow status request
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkflowStatusResponse {
     /// Workflow instance ID
     pub instance_id: Uuid,
@@ -105,7 +114,7 @@ pub struct WorkflowStatusResponse {
 }
 
 /// Step status information for API response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct StepStatusInfo {
     /// Step execution status
     pub status: crate::workflows::schema::StepStatus,
@@ -125,7 +134,7 @@ pub struct StepStatusInfo {
 }
 
 /// Workflow error information for API response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkflowErrorInfo {
     /// Error message
     pub message: String,
@@ -141,7 +150,7 @@ pub struct WorkflowErrorInfo {
 }
 
 /// Workflow execution progress information
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WorkflowProgress {
     /// Total number of steps
     pub total_steps: u32,
@@ -159,6 +168,67 @@ pub struct WorkflowProgress {
     pub percentage: u8,
 }
 
+/// Summary of a workflow instance for API listings
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkflowInstanceSummary {
+    /// Workflow instance ID
+    pub instance_id: Uuid,
+
+    /// Current workflow status
+    pub status: WorkflowStatus,
+
+    /// Workflow definition name
+    pub workflow_name: String,
+
+    /// URL to check workflow status
+    pub status_url: String,
+}
+
+impl Listable for WorkflowInstanceSummary {
+    const SORT_FIELDS: &'static [&'static str] = &["workflow_name", "status", "instance_id"];
+    const DEFAULT_SORT: &'static str = "instance_id";
+
+    fn matches_filter(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.workflow_name.to_lowercase().contains(&filter)
+            || format!("{:?}", self.status).to_lowercase() == filter
+    }
+}
+
+/// Response for workflow instance listing
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkflowInstancesResponse {
+    pub instances: Vec<WorkflowInstanceSummary>,
+    pub page_info: PageInfo,
+}
+
+/// Response for available workflow listing
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AvailableWorkflowsResponse {
+    /// Names of the registered workflow definitions
+    pub workflows: Vec<String>,
+    pub page_info: PageInfo,
+}
+
+impl Listable for WorkflowTemplateMetadata {
+    const SORT_FIELDS: &'static [&'static str] = &[
+        "id",
+        "name",
+        "category",
+        "estimated_duration",
+        "created_at",
+        "updated_at",
+    ];
+    const DEFAULT_SORT: &'static str = "id";
+
+    fn matches_filter(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.name.to_lowercase().contains(&filter)
+            || self.description.to_lowercase().contains(&filter)
+            || self.tags.iter().any(|tag| tag.to_lowercase() == filter)
+    }
+}
+
 /// Workflow management service
 pub struct WorkflowService {
     registry: Arc<RwLock<WorkflowRegistry>>,
@@ -172,25 +242,39 @@ impl WorkflowService {
         let registry = create_default_registry()?;
         let template_registry = WorkflowTemplateRegistry::new();
 
-        // Create executor with default configuration
-        let registry_endpoint = std::env::var("REGISTRY_ENDPOINT")
-            .unwrap_or_else(|_| "http://localhost:8080".to_string());
-        let auth_token = std::env::var("AUTH_TOKEN").ok();
-
-        let executor = WorkflowExecutor::new(registry_endpoint, auth_token);
-
         Ok(Self {
             registry: Arc::new(RwLock::new(registry)),
             template_registry: Arc::new(RwLock::new(template_registry)),
-            executor: Arc::new(executor),
+            executor: Arc::new(Self::executor_from_env()),
             running_instances: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
-    /// Trigger a workflow execution
+    /// Runs `McpTool` steps through `pool`, e.g. the pool whose circuit
+    /// breakers the admin API reports
+    pub fn with_mcp_pool(mut self, pool: Arc<McpConnectionPool>) -> Self {
+        self.executor = Arc::new(Self::executor_from_env().with_mcp_pool(pool));
+        self
+    }
+
+    /// Create executor with default configuration
+    fn executor_from_env() -> WorkflowExecutor {
+        let registry_endpoint = std::env::var("REGISTRY_ENDPOINT")
+            .unwrap_or_else(|_| "http://localhost:8080".to_string());
+        let auth_token = std::env::var("AUTH_TOKEN").ok();
+
+        WorkflowExecutor::new(registry_endpoint, auth_token)
+    }
+
+    /// Trigger a workflow execution on behalf of `tenant_id`.
+    ///
+    /// The instance and the run's task context (see
+    /// [`WorkflowContext::task_context`](crate::workflows::executor::WorkflowContext::task_context))
+    /// both belong to the tenant.
     pub async fn trigger_workflow(
         &self,
         request: TriggerWorkflowRequest,
+        tenant_id: Option<TenantId>,
     ) -> Result<TriggerWorkflowResponse, WorkflowError> {
         log::info!("Triggering workflow: {}", request.workflow_name);
 
@@ -210,6 +294,7 @@ impl WorkflowService {
 
         // Create workflow instance
         let mut instance = WorkflowFactory::create_instance(workflow, request.inputs)?;
+        instance.tenant_id = tenant_id;
 
         // Apply configuration overrides if provided
         if let Some(config_overrides) = request.config {
@@ -283,15 +368,29 @@ impl WorkflowService {
         })
     }
 
-    /// Get workflow status
+    /// Subscribes to step start, completion and failure events from every
+    /// subsequent execution. Events carry the instance id as their
+    /// `execution_id`.
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.executor.subscribe()
+    }
+
+    /// Get workflow status.
+    ///
+    /// Instances of other tenants are reported as not found, so that callers
+    /// cannot probe for them.
     pub async fn get_workflow_status(
         &self,
         instance_id: Uuid,
+        tenant_id: Option<&TenantId>,
     ) -> Result<WorkflowStatusResponse, WorkflowError> {
         let instances = self.running_instances.read().await;
-        let instance = instances.get(&instance_id).ok_or_else(|| {
-            WorkflowError::invalid_input_simple(format!("Workflow instance '{}' not found", instance_id))
-        })?;
+        let instance = instances
+            .get(&instance_id)
+            .filter(|instance| instance.tenant_id.as_ref() == tenant_id)
+            .ok_or_else(|| {
+                WorkflowError::invalid_input_simple(format!("Workflow instance '{}' not found", instance_id))
+            })?;
 
         // Convert step executions to API format
         let steps: HashMap<String, StepStatusInfo> = instance
@@ -364,11 +463,12 @@ impl WorkflowService {
         })
     }
 
-    /// List all workflow instances
-    pub async fn list_instances(&self) -> Vec<(Uuid, WorkflowStatus, String)> {
+    /// List the workflow instances of `tenant_id`
+    pub async fn list_instances(&self, tenant_id: Option<&TenantId>) -> Vec<(Uuid, WorkflowStatus, String)> {
         let instances = self.running_instances.read().await;
         instances
             .iter()
+            .filter(|(_, instance)| instance.tenant_id.as_ref() == tenant_id)
             .map(|(id, instance)| (*id, instance.status.clone(), instance.workflow.name.clone()))
             .collect()
     }
@@ -425,12 +525,14 @@ impl WorkflowService {
         template_registry.get_tags()
     }
 
-    /// Trigger workflow from template
+    /// Trigger workflow from template on behalf of `tenant_id`, as with
+    /// [`Self::trigger_workflow`]
     pub async fn trigger_from_template(
         &self,
         template_id: &str,
         inputs: Value,
         config_overrides: Option<WorkflowConfigOverrides>,
+        tenant_id: Option<TenantId>,
     ) -> Result<TriggerWorkflowResponse, WorkflowError> {
         log::info!("Triggering workflow from template: {}", template_id);
 
@@ -446,6 +548,7 @@ impl WorkflowService {
 
         // Create workflow instance
         let mut instance = WorkflowFactory::create_instance(workflow, inputs)?;
+        instance.tenant_id = tenant_id;
 
         // Apply configuration overrides if provided
         if let Some(config_overrides) = config_overrides {
@@ -528,7 +631,20 @@ impl WorkflowService {
 }
 
 /// HTTP handler for triggering workflows
+#[utoipa::path(
+    post,
+    path = "/api/v1/workflows/trigger",
+    tag = "Workflows",
+    request_body = TriggerWorkflowRequest,
+    responses(
+        (status = 200, description = "Workflow triggered", body = TriggerWorkflowResponse),
+        (status = 400, description = "Unknown workflow or invalid inputs", body = crate::api::openapi::ErrorResponse),
+        (status = 500, description = "Internal server error", body = crate::api::openapi::ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn trigger_workflow(
+    http_request: HttpRequest,
     service: web::Data<WorkflowService>,
     request: web::Json<TriggerWorkflowRequest>,
 ) -> ActixResult<HttpResponse> {
@@ -537,23 +653,36 @@ pub async fn trigger_workflow(
         request.workflow_name
     );
 
-    match service.trigger_workflow(request.into_inner()).await {
+    let tenant_id = http_request.tenant_id();
+    match service.trigger_workflow(request.into_inner(), tenant_id).await {
         Ok(response) => {
             log::info!("Successfully triggered workflow: {}", response.instance_id);
             Ok(HttpResponse::Ok().json(response))
         }
         Err(e) => {
             log::error!("Failed to trigger workflow: {}", e);
-            Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "workflow_trigger_failed",
-                "message": e.to_string()
-            })))
+            Ok(error_response_for_request(&http_request, &e))
         }
     }
 }
 
 /// HTTP handler for getting workflow status
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflows/status/{instance_id}",
+    tag = "Workflows",
+    params(
+        ("instance_id" = Uuid, Path, description = "Workflow instance ID")
+    ),
+    responses(
+        (status = 200, description = "Workflow instance status", body = WorkflowStatusResponse),
+        (status = 404, description = "Workflow instance not found"),
+        (status = 500, description = "Internal server error", body = crate::api::openapi::ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_workflow_status(
+    http_request: HttpRequest,
     service: web::Data<WorkflowService>,
     path: web::Path<Uuid>,
 ) -> ActixResult<HttpResponse> {
@@ -563,7 +692,10 @@ pub async fn get_workflow_status(
         instance_id
     );
 
-    match service.get_workflow_status(instance_id).await {
+    match service
+        .get_workflow_status(instance_id, http_request.tenant_id().as_ref())
+        .await
+    {
         Ok(response) => {
             log::debug!(
                 "Successfully retrieved status for workflow: {}",
@@ -580,45 +712,136 @@ pub async fn get_workflow_status(
         }
         Err(e) => {
             log::error!("Failed to get workflow status: {}", e);
-            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "status_retrieval_failed",
-                "message": e.to_string()
+            Ok(error_response_for_request(&http_request, &e))
+        }
+    }
+}
+
+/// Streams the events of one execution as server-sent events.
+///
+/// Each event is a `data:` line holding the JSON [`NodeEvent`]. Events the
+/// subscriber fell too far behind to receive are skipped.
+pub fn progress_stream(
+    events: broadcast::Receiver<NodeEvent>,
+    execution_id: Uuid,
+) -> impl Stream<Item = ActixResult<web::Bytes>> {
+    futures_util::stream::unfold(events, move |mut events| async move {
+        loop {
+            match events.recv().await {
+                Ok(event) if event.execution_id == execution_id => {
+                    let frame = serde_json::to_string(&event)
+                        .map(|data| web::Bytes::from(format!("data: {}\n\n", data)))
+                        .map_err(actix_web::error::ErrorInternalServerError);
+                    return Some((frame, events));
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// HTTP handler streaming a workflow instance's step events.
+///
+/// Only steps that start after the request are streamed; earlier ones are
+/// in the instance's status.
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflows/progress/{instance_id}",
+    tag = "Workflows",
+    params(
+        ("instance_id" = Uuid, Path, description = "Workflow instance ID")
+    ),
+    responses(
+        (status = 200, description = "Server-sent step events of the instance", content_type = "text/event-stream"),
+        (status = 404, description = "Workflow instance not found"),
+        (status = 500, description = "Internal server error", body = crate::api::openapi::ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn stream_workflow_progress(
+    http_request: HttpRequest,
+    service: web::Data<WorkflowService>,
+    path: web::Path<Uuid>,
+) -> ActixResult<HttpResponse> {
+    let instance_id = path.into_inner();
+    // Subscribe before the lookup, so no step starting after it is missed
+    let events = service.subscribe();
+
+    match service
+        .get_workflow_status(instance_id, http_request.tenant_id().as_ref())
+        .await
+    {
+        Ok(_) => Ok(HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header(("Cache-Control", "no-cache"))
+            .streaming(progress_stream(events, instance_id))),
+        Err(WorkflowError::InvalidInput { message, .. }) => {
+            log::warn!("Workflow instance not found: {}", message);
+            Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "workflow_not_found",
+                "message": message
             })))
         }
+        Err(e) => {
+            log::error!("Failed to stream workflow progress: {}", e);
+            Ok(error_response_for_request(&http_request, &e))
+        }
     }
 }
 
 /// HTTP handler for listing workflow instances
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflows/instances",
+    tag = "Workflows",
+    params(ListQuery),
+    responses(
+        (status = 200, description = "Tracked workflow instances", body = WorkflowInstancesResponse),
+        (status = 400, description = "Invalid list parameters", body = crate::api::openapi::ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list_workflow_instances(
+    http_request: HttpRequest,
     service: web::Data<WorkflowService>,
+    list: ListParams<WorkflowInstanceSummary>,
 ) -> ActixResult<HttpResponse> {
-    let instances = service.list_instances().await;
-
-    let response = serde_json::json!({
-        "instances": instances.iter().map(|(id, status, name)| {
-            serde_json::json!({
-                "instance_id": id,
-                "status": status,
-                "workflow_name": name,
-                "status_url": format!("/api/v1/workflows/status/{}", id)
-            })
-        }).collect::<Vec<_>>()
-    });
+    let instances = service
+        .list_instances(http_request.tenant_id().as_ref())
+        .await
+        .into_iter()
+        .map(|(instance_id, status, workflow_name)| WorkflowInstanceSummary {
+            instance_id,
+            status,
+            workflow_name,
+            status_url: format!("/api/v1/workflows/status/{}", instance_id),
+        })
+        .collect();
 
-    Ok(HttpResponse::Ok().json(response))
+    let (instances, page_info) = list.paginate(instances);
+    Ok(HttpResponse::Ok().json(WorkflowInstancesResponse { instances, page_info }))
 }
 
 /// HTTP handler for listing available workflows
+#[utoipa::path(
+    get,
+    path = "/api/v1/workflows/available",
+    tag = "Workflows",
+    params(ListQuery),
+    responses(
+        (status = 200, description = "Registered workflow definitions", body = AvailableWorkflowsResponse),
+        (status = 400, description = "Invalid list parameters", body = crate::api::openapi::ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list_available_workflows(
     service: web::Data<WorkflowService>,
+    list: ListParams<String>,
 ) -> ActixResult<HttpResponse> {
-    let workflows = service.list_workflows().await;
-
-    let response = serde_json::json!({
-        "workflows": workflows
-    });
+    let (workflows, page_info) = list.paginate(service.list_workflows().await);
 
-    Ok(HttpResponse::Ok().json(response))
+    Ok(HttpResponse::Ok().json(AvailableWorkflowsResponse { workflows, page_info }))
 }
 
 /// Request payload for triggering a workflow from template
@@ -635,11 +858,15 @@ pub struct TriggerTemplateRequest {
 }
 
 /// HTTP handler for listing workflow templates
-pub async fn list_templates(service: web::Data<WorkflowService>) -> ActixResult<HttpResponse> {
-    let templates = service.list_templates().await;
+pub async fn list_templates(
+    service: web::Data<WorkflowService>,
+    list: ListParams<WorkflowTemplateMetadata>,
+) -> ActixResult<HttpResponse> {
+    let (templates, page_info) = list.paginate(service.list_templates().await);
 
     let response = serde_json::json!({
-        "templates": templates
+        "templates": templates,
+        "page_info": page_info
     });
 
     Ok(HttpResponse::Ok().json(response))
@@ -701,6 +928,7 @@ pub async fn get_template_tags(service: web::Data<WorkflowService>) -> ActixResu
 
 /// HTTP handler for triggering workflow from template
 pub async fn trigger_from_template(
+    http_request: HttpRequest,
     service: web::Data<WorkflowService>,
     request: web::Json<TriggerTemplateRequest>,
 ) -> ActixResult<HttpResponse> {
@@ -714,6 +942,7 @@ pub async fn trigger_from_template(
             &request.template_id,
             request.inputs.clone(),
             request.config.clone(),
+            http_request.tenant_id(),
         )
         .await
     {
@@ -726,10 +955,7 @@ pub async fn trigger_from_template(
         }
         Err(e) => {
             log::error!("Failed to trigger workflow from template: {}", e);
-            Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "template_trigger_failed",
-                "message": e.to_string()
-            })))
+            Ok(error_response_for_request(&http_request, &e))
         }
     }
 }
@@ -740,6 +966,7 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/api/v1/workflows")
             .route("/trigger", web::post().to(trigger_workflow))
             .route("/status/{instance_id}", web::get().to(get_workflow_status))
+            .route("/progress/{instance_id}", web::get().to(stream_workflow_progress))
             .route("/instances", web::get().to(list_workflow_instances))
             .route("/available", web::get().to(list_available_workflows)),
     );
@@ -804,4 +1031,167 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert!(resp.status().is_success());
     }
+
+    #[tokio::test]
+    async fn test_tenants_cannot_read_each_others_instances() {
+        use actix_web::HttpMessage;
+        use workflow_engine_core::auth::Claims;
+
+        let service = web::Data::new(WorkflowService::new().await.unwrap());
+        let app = test::init_service(
+            App::new()
+                .app_data(service.clone())
+                .route("/trigger", web::post().to(trigger_workflow))
+                .route("/status/{instance_id}", web::get().to(get_workflow_status))
+                .route("/instances", web::get().to(list_workflow_instances)),
+        )
+        .await;
+        let as_tenant = |req: test::TestRequest, tenant: &str| {
+            let req = req.to_request();
+            req.extensions_mut()
+                .insert(Claims::new("user".to_string(), "developer".to_string()).with_tenant(tenant));
+            req
+        };
+
+        let trigger = test::TestRequest::post().uri("/trigger").set_json(TriggerWorkflowRequest {
+            workflow_name: "research_to_documentation".to_string(),
+            inputs: serde_json::json!({ "topic": "machine learning", "difficulty": "intermediate" }),
+            config: None,
+        });
+        let triggered: Value = test::call_and_read_body_json(&app, as_tenant(trigger, "acme")).await;
+        let status_uri = triggered["status_url"].as_str().unwrap().replace("/api/v1/workflows", "");
+
+        let status = |tenant| as_tenant(test::TestRequest::get().uri(&status_uri), tenant);
+        assert!(test::call_service(&app, status("acme")).await.status().is_success());
+        let resp = test::call_service(&app, status("globex")).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+        let anonymous = test::TestRequest::get().uri(&status_uri).to_request();
+        let resp = test::call_service(&app, anonymous).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+
+        let instances = |tenant| as_tenant(test::TestRequest::get().uri("/instances"), tenant);
+        let listed: Value = test::call_and_read_body_json(&app, instances("acme")).await;
+        assert_eq!(listed["instances"][0]["instance_id"], triggered["instance_id"]);
+        let listed: Value = test::call_and_read_body_json(&app, instances("globex")).await;
+        assert_eq!(listed["instances"], serde_json::json!([]));
+    }
+
+    #[derive(Debug)]
+    struct Draft;
+
+    impl workflow_engine_core::nodes::Node for Draft {
+        fn process(
+            &self,
+            task_context: workflow_engine_core::task::TaskContext,
+        ) -> Result<workflow_engine_core::task::TaskContext, WorkflowError> {
+            Ok(task_context)
+        }
+    }
+
+    #[derive(Debug)]
+    struct Publish;
+
+    impl workflow_engine_core::nodes::Node for Publish {
+        fn process(
+            &self,
+            _task_context: workflow_engine_core::task::TaskContext,
+        ) -> Result<workflow_engine_core::task::TaskContext, WorkflowError> {
+            Err(WorkflowError::user_error("Mailing list is empty", "Publish"))
+        }
+    }
+
+    fn parse_frame(frame: &[u8]) -> Value {
+        let frame = std::str::from_utf8(frame).unwrap();
+        serde_json::from_str(frame.strip_prefix("data: ").unwrap().trim_end()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_progress_stream_follows_one_run() {
+        use futures_util::StreamExt;
+        use workflow_engine_core::nodes::config::NodeConfig;
+        use workflow_engine_core::task::TaskContext;
+        use workflow_engine_core::workflow::builder::WorkflowBuilder;
+
+        let workflow = WorkflowBuilder::new::<Draft>("newsletter".to_string())
+            .add_node(NodeConfig::new::<Draft>().with_connections(vec![std::any::TypeId::of::<Publish>()]))
+            .add_node(NodeConfig::new::<Publish>())
+            .build()
+            .unwrap();
+        workflow.register_node(Draft);
+        workflow.register_node(Publish);
+
+        let run = TaskContext::new("newsletter".to_string(), serde_json::json!({}));
+        let execution_id = run.event_id;
+        let progress = progress_stream(workflow.subscribe(), execution_id);
+        // Other runs' events are left out
+        assert!(workflow.run(serde_json::json!({})).is_err());
+        assert!(workflow.run_with_context(run).is_err());
+
+        let frames: Vec<Value> = progress.take(4).map(|frame| parse_frame(&frame.unwrap())).collect().await;
+        assert!(frames.iter().all(|frame| frame["execution_id"] == serde_json::json!(execution_id)));
+        assert_eq!(
+            frames.iter().map(|frame| frame["type"].as_str().unwrap()).collect::<Vec<_>>(),
+            ["started", "completed", "started", "failed"]
+        );
+        assert_eq!(frames[0]["node"], frames[1]["node"]);
+        assert_eq!(frames[2]["node"], frames[3]["node"]);
+        assert_ne!(frames[0]["node"], frames[2]["node"]);
+        assert!(frames[3]["error"].as_str().unwrap().contains("Mailing list is empty"));
+    }
+
+    #[tokio::test]
+    async fn test_progress_route_streams_the_instances_steps() {
+        use actix_web::body::MessageBody;
+
+        let service = web::Data::new(WorkflowService::new().await.unwrap());
+        let app = test::init_service(
+            App::new()
+                .app_data(service.clone())
+                .route("/progress/{instance_id}", web::get().to(stream_workflow_progress)),
+        )
+        .await;
+
+        let unknown = test::TestRequest::get().uri(&format!("/progress/{}", Uuid::new_v4())).to_request();
+        let resp = test::call_service(&app, unknown).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+
+        let workflow = service
+            .registry
+            .read()
+            .await
+            .parser()
+            .get_workflow("research_to_documentation")
+            .unwrap()
+            .clone();
+        let inputs = serde_json::json!({ "topic": "machine learning", "difficulty": "intermediate" });
+        let instance = WorkflowFactory::create_instance(workflow, inputs).unwrap();
+        service.running_instances.write().await.insert(instance.id, instance.clone());
+
+        let req = test::TestRequest::get().uri(&format!("/progress/{}", instance.id)).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(resp.headers().get("content-type").unwrap(), "text/event-stream");
+
+        let finished = service.executor.execute(instance.clone()).await.unwrap();
+        let first_step = &instance.workflow.steps[0].id;
+        let outcome = match finished.steps[first_step].status {
+            crate::workflows::schema::StepStatus::Completed => "completed",
+            _ => "failed",
+        };
+
+        let mut body = resp.into_body();
+        let mut frames = Vec::new();
+        for _ in 0..2 {
+            let frame = std::future::poll_fn(|cx| std::pin::Pin::new(&mut body).poll_next(cx))
+                .await
+                .unwrap()
+                .unwrap();
+            frames.push(parse_frame(&frame));
+        }
+        assert_eq!(frames[0]["node"], serde_json::json!(first_step));
+        assert_eq!(frames[0]["type"], "started");
+        assert_eq!(frames[1]["node"], serde_json::json!(first_step));
+        assert_eq!(frames[1]["type"], outcome);
+        assert_eq!(frames[1]["execution_id"], serde_json::json!(instance.id));
+    }
 }
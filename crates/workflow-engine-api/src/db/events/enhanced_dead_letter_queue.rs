@@ -7,10 +7,11 @@ use chrono::{DateTime, Duration, Utc};
 use redis::aio::ConnectionManager;
 use redis::{AsyncCommands, Client};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
+use workflow_engine_core::workflow::scheduler::{Clock, SystemClock};
 
 use super::{EventEnvelope, EventError, EventResult};
 use super::dead_letter_queue::{DeadLetterConfig, DeadLetterEntry, DeadLetterStatus, DeadLetterStatistics};
@@ -27,6 +28,8 @@ pub struct EnhancedDLQConfig {
     pub poison_message_threshold: u32,
     pub enable_metrics: bool,
     pub retention_policy: RetentionPolicy,
+    #[serde(default)]
+    pub alerting: DLQAlertConfig,
 }
 
 /// Retention policy for dead letter entries
@@ -39,6 +42,89 @@ pub struct RetentionPolicy {
     pub cleanup_interval_hours: u32,
 }
 
+/// Thresholds at which the dead letter queue raises alerts.
+///
+/// Each threshold is disabled when unset. Once an alert of a kind has been
+/// raised, further alerts of that kind are suppressed for
+/// `min_alert_interval_seconds`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DLQAlertConfig {
+    /// Alert when the queue holds more entries than this
+    pub max_queue_depth: Option<usize>,
+    /// Alert when more events than this are dead-lettered within the window
+    pub max_failures_per_window: Option<u32>,
+    pub failure_window_seconds: u64,
+    pub min_alert_interval_seconds: u64,
+}
+
+impl Default for DLQAlertConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_depth: None,
+            max_failures_per_window: None,
+            failure_window_seconds: 60,
+            min_alert_interval_seconds: 300,
+        }
+    }
+}
+
+/// Alert raised when a dead letter queue threshold is exceeded
+#[derive(Debug, Clone, PartialEq)]
+pub enum DLQAlert {
+    QueueDepth {
+        depth: usize,
+        threshold: usize,
+        raised_at: DateTime<Utc>,
+    },
+    FailureRate {
+        failures: usize,
+        window_seconds: u64,
+        threshold: u32,
+        raised_at: DateTime<Utc>,
+    },
+}
+
+/// Destination for dead letter queue alerts
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn send_alert(&self, alert: &DLQAlert);
+}
+
+/// Alert sink that logs alerts as tracing errors
+#[derive(Debug, Clone, Default)]
+pub struct TracingAlertSink;
+
+#[async_trait]
+impl AlertSink for TracingAlertSink {
+    async fn send_alert(&self, alert: &DLQAlert) {
+        match alert {
+            DLQAlert::QueueDepth { depth, threshold, .. } => tracing::error!(
+                depth,
+                threshold,
+                "Dead letter queue depth {} exceeds threshold {}",
+                depth,
+                threshold
+            ),
+            DLQAlert::FailureRate { failures, window_seconds, threshold, .. } => tracing::error!(
+                failures,
+                threshold,
+                "{} events dead-lettered in the last {}s, threshold is {}",
+                failures,
+                window_seconds,
+                threshold
+            ),
+        }
+    }
+}
+
+/// Recent failures and when each kind of alert was last raised
+#[derive(Debug, Default)]
+struct AlertState {
+    recent_failures: VecDeque<DateTime<Utc>>,
+    last_depth_alert: Option<DateTime<Utc>>,
+    last_rate_alert: Option<DateTime<Utc>>,
+}
+
 impl Default for EnhancedDLQConfig {
     fn default() -> Self {
         Self {
@@ -57,6 +143,7 @@ impl Default for EnhancedDLQConfig {
                 auto_cleanup_enabled: true,
                 cleanup_interval_hours: 6,
             },
+            alerting: DLQAlertConfig::default(),
         }
     }
 }
@@ -136,6 +223,9 @@ pub struct EnhancedDeadLetterQueue {
     circuit_breaker: CircuitBreaker,
     metrics: Arc<RwLock<EnhancedDLQMetrics>>,
     poison_message_tracker: Arc<RwLock<HashMap<String, u32>>>,
+    alert_sink: Arc<dyn AlertSink>,
+    alert_state: Arc<RwLock<AlertState>>,
+    clock: Arc<dyn Clock>,
 }
 
 /// Enhanced metrics for dead letter queue
@@ -150,6 +240,7 @@ pub struct EnhancedDLQMetrics {
     pub average_retry_delay_seconds: f64,
     pub current_queue_size: usize,
     pub redis_operations_failed: u64,
+    pub alerts_raised: u64,
 }
 
 impl EnhancedDeadLetterQueue {
@@ -189,9 +280,24 @@ impl EnhancedDeadLetterQueue {
             circuit_breaker,
             metrics: Arc::new(RwLock::new(EnhancedDLQMetrics::default())),
             poison_message_tracker: Arc::new(RwLock::new(HashMap::new())),
+            alert_sink: Arc::new(TracingAlertSink),
+            alert_state: Arc::new(RwLock::new(AlertState::default())),
+            clock: Arc::new(SystemClock),
         })
     }
     
+    /// Send threshold alerts to `sink` instead of the tracing log
+    pub fn with_alert_sink(mut self, sink: Arc<dyn AlertSink>) -> Self {
+        self.alert_sink = sink;
+        self
+    }
+    
+    /// Use `clock` to measure failure rates and alert intervals
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
     /// Add a failed event to the enhanced dead letter queue
     pub async fn add_failed_event(
         &self,
@@ -221,8 +327,7 @@ impl EnhancedDeadLetterQueue {
         };
         
         if poison_count > self.config.poison_message_threshold {
-            let mut metrics = self.metrics.write().await;
-            metrics.poison_messages_detected += 1;
+            self.metrics.write().await.poison_messages_detected += 1;
             
             tracing::error!(
                 "Poison message detected for event {} (failure count: {})",
@@ -231,7 +336,9 @@ impl EnhancedDeadLetterQueue {
             );
             
             // Mark as permanently failed without retries
-            return self.mark_as_poison_message(event, error_message, poison_count).await;
+            self.mark_as_poison_message(event, error_message, poison_count).await?;
+            self.check_alert_thresholds().await;
+            return Ok(());
         }
         
         let entry = DeadLetterEntry {
@@ -252,15 +359,18 @@ impl EnhancedDeadLetterQueue {
         match self.persist_entry(&entry).await {
             Ok(_) => {
                 self.circuit_breaker.record_success().await;
-                let mut metrics = self.metrics.write().await;
-                metrics.total_events_added += 1;
-                metrics.current_queue_size += 1;
+                {
+                    let mut metrics = self.metrics.write().await;
+                    metrics.total_events_added += 1;
+                    metrics.current_queue_size += 1;
+                }
                 
                 tracing::warn!(
                     "Event {} added to enhanced dead letter queue: {}",
                     event.event_id,
                     error_message
                 );
+                self.check_alert_thresholds().await;
             }
             Err(e) => {
                 self.circuit_breaker.record_failure().await;
@@ -390,6 +500,54 @@ impl EnhancedDeadLetterQueue {
     
     // Private helper methods
     
+    /// Record a dead-lettered event and raise any alerts whose threshold it
+    /// crosses, unless an alert of the same kind was raised recently
+    async fn check_alert_thresholds(&self) {
+        let alerting = &self.config.alerting;
+        let now = self.clock.now();
+        let min_interval = Duration::seconds(alerting.min_alert_interval_seconds as i64);
+        let can_raise = |last: Option<DateTime<Utc>>| last.is_none_or(|last| now - last >= min_interval);
+        let depth = self.metrics.read().await.current_queue_size;
+        
+        let mut alerts = Vec::new();
+        {
+            let mut state = self.alert_state.write().await;
+            
+            let window_start = now - Duration::seconds(alerting.failure_window_seconds as i64);
+            state.recent_failures.push_back(now);
+            while state.recent_failures.front().is_some_and(|at| *at <= window_start) {
+                state.recent_failures.pop_front();
+            }
+            
+            if let Some(threshold) = alerting.max_queue_depth {
+                if depth > threshold && can_raise(state.last_depth_alert) {
+                    state.last_depth_alert = Some(now);
+                    alerts.push(DLQAlert::QueueDepth { depth, threshold, raised_at: now });
+                }
+            }
+            if let Some(threshold) = alerting.max_failures_per_window {
+                let failures = state.recent_failures.len();
+                if failures > threshold as usize && can_raise(state.last_rate_alert) {
+                    state.last_rate_alert = Some(now);
+                    alerts.push(DLQAlert::FailureRate {
+                        failures,
+                        window_seconds: alerting.failure_window_seconds,
+                        threshold,
+                        raised_at: now,
+                    });
+                }
+            }
+        }
+        
+        if alerts.is_empty() {
+            return;
+        }
+        self.metrics.write().await.alerts_raised += alerts.len() as u64;
+        for alert in &alerts {
+            self.alert_sink.send_alert(alert).await;
+        }
+    }
+    
     async fn persist_entry(&self, entry: &DeadLetterEntry) -> EventResult<()> {
         if let Some(ref redis_conn) = self.redis_connection {
             self.persist_to_redis(entry, redis_conn).await
@@ -730,4 +888,107 @@ mod tests {
         let stats = dlq.get_enhanced_statistics().await;
         assert_eq!(stats.enhanced_metrics.poison_messages_detected, 1);
     }
-}
\ No newline at end of file
+    
+    #[derive(Default)]
+    struct RecordingSink(std::sync::Mutex<Vec<DLQAlert>>);
+    
+    #[async_trait]
+    impl AlertSink for RecordingSink {
+        async fn send_alert(&self, alert: &DLQAlert) {
+            self.0.lock().unwrap().push(alert.clone());
+        }
+    }
+    
+    #[derive(Debug)]
+    struct FakeClock(std::sync::Mutex<DateTime<Utc>>);
+    
+    impl FakeClock {
+        fn advance(&self, seconds: i64) {
+            *self.0.lock().unwrap() += Duration::seconds(seconds);
+        }
+    }
+    
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+    
+    async fn alerting_dlq(alerting: DLQAlertConfig) -> (EnhancedDeadLetterQueue, Arc<RecordingSink>, Arc<FakeClock>) {
+        let config = EnhancedDLQConfig {
+            poison_message_threshold: 1000,
+            alerting,
+            ..EnhancedDLQConfig::default()
+        };
+        let sink = Arc::new(RecordingSink::default());
+        let clock = Arc::new(FakeClock(std::sync::Mutex::new(Utc::now())));
+        let dlq = EnhancedDeadLetterQueue::new(config)
+            .await
+            .unwrap()
+            .with_alert_sink(sink.clone())
+            .with_clock(clock.clone());
+        (dlq, sink, clock)
+    }
+    
+    async fn fail(dlq: &EnhancedDeadLetterQueue, count: usize) {
+        for i in 0..count {
+            dlq.add_failed_event(&create_test_event(), format!("Error {}", i), serde_json::json!({}))
+                .await
+                .unwrap();
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_depth_alert_is_rate_limited() {
+        let (dlq, sink, clock) = alerting_dlq(DLQAlertConfig {
+            max_queue_depth: Some(5),
+            min_alert_interval_seconds: 60,
+            ..DLQAlertConfig::default()
+        })
+        .await;
+        
+        fail(&dlq, 5).await;
+        assert!(sink.0.lock().unwrap().is_empty());
+        
+        fail(&dlq, 20).await;
+        clock.advance(59);
+        fail(&dlq, 5).await;
+        {
+            let alerts = sink.0.lock().unwrap();
+            assert_eq!(alerts.len(), 1);
+            assert!(matches!(alerts[0], DLQAlert::QueueDepth { depth: 6, threshold: 5, .. }));
+        }
+        
+        clock.advance(1);
+        fail(&dlq, 1).await;
+        assert_eq!(dlq.get_enhanced_statistics().await.enhanced_metrics.alerts_raised, 2);
+        let alerts = sink.0.lock().unwrap();
+        assert!(matches!(alerts[1], DLQAlert::QueueDepth { depth: 31, .. }));
+    }
+    
+    #[tokio::test]
+    async fn test_failure_rate_alert_counts_recent_failures() {
+        let (dlq, sink, clock) = alerting_dlq(DLQAlertConfig {
+            max_failures_per_window: Some(3),
+            failure_window_seconds: 10,
+            min_alert_interval_seconds: 60,
+            ..DLQAlertConfig::default()
+        })
+        .await;
+        
+        // Failures spread wider than the window never cross the threshold
+        for _ in 0..6 {
+            fail(&dlq, 1).await;
+            clock.advance(4);
+        }
+        assert!(sink.0.lock().unwrap().is_empty());
+        
+        fail(&dlq, 10).await;
+        let alerts = sink.0.lock().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(
+            alerts[0],
+            DLQAlert::FailureRate { failures: 4, window_seconds: 10, threshold: 3, .. }
+        ));
+    }
+}
@@ -73,6 +73,9 @@ impl ResilientEventStore {
             ),
             EventError::ProjectionError { message } => WorkflowError::processing_error_simple(message),
             EventError::HandlerError { message } => WorkflowError::processing_error_simple(message),
+            EventError::CorruptionError { event_id, message } => WorkflowError::serialization_error_simple(
+                format!("Event {} is corrupted: {}", event_id, message)
+            ),
         }
     }
 
@@ -14,6 +14,7 @@
 // - Event versioning and migration system
 
 pub mod store;
+pub mod query;
 pub mod types;
 pub mod dispatcher;
 pub mod projections;
@@ -38,7 +39,11 @@ pub mod performance;
 #[cfg(test)]
 pub mod tests;
 
-pub use store::{EventStore, EventStreaming, PostgreSQLEventStore, EventStoreConfig, AggregateSnapshot, EventStoreStatistics};
+pub use store::{
+    EventStore, EventStreaming, PostgreSQLEventStore, EventStoreConfig, AggregateSnapshot, EventStoreStatistics,
+    BatchAppendResult, BatchConflict,
+};
+pub use query::EventQuery;
 pub use types::{
     Event, AggregateEvent, EventMetadata,
     WorkflowEvent, AIInteractionEvent, ServiceCallEvent, SystemEvent
@@ -51,12 +56,16 @@ pub use streaming::{EventStream, EventStreamConfig, StreamPosition};
 pub use dead_letter_queue::{DeadLetterQueue, PostgreSQLDeadLetterQueue, DeadLetterConfig as DLQConfig, DeadLetterProcessor};
 pub use enhanced_dead_letter_queue::{
     EnhancedDeadLetterQueue, EnhancedDLQConfig, CircuitBreaker, CircuitBreakerState,
-    EnhancedDLQMetrics, EnhancedDLQStatistics, ProcessingResult, CleanupResult
+    EnhancedDLQMetrics, EnhancedDLQStatistics, ProcessingResult, CleanupResult,
+    DLQAlertConfig, DLQAlert, AlertSink, TracingAlertSink
 };
 pub use error_integration::{ResilientEventStore, EventSourcingRecovery};
-pub use replay::{EventReplayEngine, ReplayHandler, ReplayConfig, ReplayPosition, BatchReplayProcessor};
+pub use replay::{
+    EventReplayEngine, ReplayHandler, ReplayConfig, ReplayPosition, BatchReplayProcessor,
+    CheckpointStore, InMemoryCheckpointStore, PostgreSQLCheckpointStore,
+};
 pub use projection_rebuild::{ProjectionRebuildManager, ProjectionRebuildConfig, RebuildMetadata, RebuildStatistics, BatchProjectionRebuilder};
-pub use snapshots::{EnhancedSnapshotManager, SnapshotConfig, EnhancedSnapshot, CompressionType, SnapshotStatistics, SnapshotCompressor, GzipCompressor, Lz4Compressor};
+pub use snapshots::{EnhancedSnapshotManager, SnapshotConfig, EnhancedSnapshot, CompressionType, SnapshotStatistics, SnapshotCompressor, GzipCompressor, Lz4Compressor, ZstdCompressor};
 pub use snapshot_triggers::{SnapshotTriggerManager, SnapshotTriggerConfig, SnapshotTrigger, Snapshottable, TriggerEvent, TriggerStatistics, SnapshotScheduler};
 pub use versioning::{EventVersionManager, VersioningConfig, SchemaVersion, EventMigrator, VersioningStatistics, MigratingReplayHandler};
 pub use migrations::{MigrationRegistry, WorkflowStartedV1ToV2Migration, WorkflowCompletedV1ToV2Migration, PromptSentV1ToV2Migration, ResponseReceivedV1ToV2Migration, FieldRenameMigration, FieldRemovalMigration};
@@ -67,8 +76,8 @@ pub use cross_service_routing::{
     RoutingPriority, RoutedEvent, CrossServiceEventHandler
 };
 pub use saga::{
-    SagaOrchestrator, SagaDefinition, SagaStep, SagaExecution, SagaState,
-    SagaStepStatus, SagaStepResult, SagaStepExecutor, SagaStore, InMemorySagaStore,
+    SagaOrchestrator, SagaDefinition, SagaStep, SagaExecution, SagaStepExecution, SagaState,
+    SagaStepStatus, SagaStepResult, SagaStepExecutor, SagaStore, InMemorySagaStore, PostgreSQLSagaStore,
     RetryPolicy, CompensationStrategy
 };
 pub use ordering::{
@@ -115,6 +124,9 @@ pub enum EventError {
     
     #[error("Configuration error: {message}")]
     ConfigurationError { message: String },
+
+    #[error("Event {event_id} is corrupted: {message}")]
+    CorruptionError { event_id: Uuid, message: String },
 }
 
 impl From<diesel::result::Error> for EventError {
@@ -13,7 +13,7 @@ use uuid::Uuid;
 
 use super::{
     EventStore, EventEnvelope, EventError, EventResult,
-    replay::{EventReplayEngine, ReplayHandler, ReplayConfig, ReplayPosition},
+    replay::{EventReplayEngine, ReplayHandler, ReplayConfig, ReplayPosition, CheckpointStore},
     projections::{Projection, ProjectionState},
 };
 
@@ -30,6 +30,8 @@ pub struct ProjectionRebuildConfig {
     pub max_incremental_age_hours: i64,
     /// Timeout for rebuild operations (in seconds)
     pub rebuild_timeout_seconds: u64,
+    /// Save the rebuild position every N events
+    pub checkpoint_frequency: usize,
 }
 
 impl Default for ProjectionRebuildConfig {
@@ -40,6 +42,7 @@ impl Default for ProjectionRebuildConfig {
             incremental_rebuild: true,
             max_incremental_age_hours: 24,
             rebuild_timeout_seconds: 3600, // 1 hour
+            checkpoint_frequency: 1000,
         }
     }
 }
@@ -54,6 +57,8 @@ pub struct RebuildMetadata {
     pub from_position: i64,
     pub to_position: i64,
     pub is_incremental: bool,
+    /// Whether the rebuild continued an interrupted one from its checkpoint
+    pub resumed: bool,
     pub error: Option<String>,
 }
 
@@ -68,7 +73,13 @@ pub struct RebuildStatistics {
     pub average_throughput_events_per_second: f64,
 }
 
-/// Manager for rebuilding projections efficiently
+/// Manager for rebuilding projections efficiently.
+///
+/// Rebuild progress is checkpointed every `checkpoint_frequency` events. If a
+/// rebuild fails or the process dies part way, the projection is left in the
+/// `Rebuilding` or `Failed` state, and the next rebuild continues from the
+/// last checkpoint instead of starting over. Use a durable
+/// [`CheckpointStore`] for checkpoints to survive restarts.
 pub struct ProjectionRebuildManager {
     event_store: Arc<dyn EventStore>,
     replay_engine: EventReplayEngine,
@@ -85,6 +96,7 @@ impl ProjectionRebuildManager {
         let replay_config = ReplayConfig {
             batch_size: config.batch_size,
             parallelism: config.parallelism,
+            checkpoint_frequency: config.checkpoint_frequency,
             ..Default::default()
         };
         
@@ -99,6 +111,12 @@ impl ProjectionRebuildManager {
         }
     }
     
+    /// Save rebuild checkpoints to `store`
+    pub fn with_checkpoint_store(mut self, store: Arc<dyn CheckpointStore>) -> Self {
+        self.replay_engine = self.replay_engine.with_checkpoint_store(store);
+        self
+    }
+    
     /// Rebuild a single projection
     pub async fn rebuild_projection<P>(
         &self,
@@ -135,6 +153,7 @@ impl ProjectionRebuildManager {
             from_position: 0,
             to_position: 0,
             is_incremental: false,
+            resumed: false,
             error: None,
         };
         
@@ -346,27 +365,38 @@ impl ProjectionRebuildManager {
     where
         P: Projection + ReplayHandler + Send + Sync,
     {
+        let (state, last_position) = {
+            let p = projection.lock().await;
+            (p.state(), p.last_position())
+        };
+        
+        // A rebuild that did not finish leaves the projection rebuilding or failed
+        let interrupted = matches!(state, ProjectionState::Rebuilding | ProjectionState::Failed);
+        let checkpoint = if interrupted && !force_full_rebuild {
+            self.replay_engine.checkpoint(&metadata.projection_name).await?
+        } else {
+            None
+        };
+        
         // Determine starting position
-        let from_position = if force_full_rebuild || !self.config.incremental_rebuild {
+        let from_position = if let Some(checkpoint) = checkpoint {
+            // Continue the interrupted rebuild from its last checkpoint
+            metadata.resumed = true;
+            checkpoint.position
+        } else if let Some(last_position) = last_position.filter(|_| {
+            !force_full_rebuild && self.config.incremental_rebuild
+        }) {
+            // Incremental rebuild
+            metadata.is_incremental = true;
+            last_position
+        } else {
             // Full rebuild from the beginning
             {
                 let mut p = projection.lock().await;
                 p.reset().await?;
             }
+            self.replay_engine.reset_position(&metadata.projection_name).await?;
             0
-        } else {
-            // Try incremental rebuild
-            let p = projection.lock().await;
-            if let Some(last_position) = p.last_position() {
-                metadata.is_incremental = true;
-                last_position
-            } else {
-                // No position available, do full rebuild
-                drop(p);
-                let mut p = projection.lock().await;
-                p.reset().await?;
-                0
-            }
         };
         
         metadata.from_position = from_position;
@@ -375,7 +405,13 @@ impl ProjectionRebuildManager {
             "Rebuilding projection '{}' from position {} ({})",
             metadata.projection_name,
             from_position,
-            if metadata.is_incremental { "incremental" } else { "full" }
+            if metadata.resumed {
+                "resumed"
+            } else if metadata.is_incremental {
+                "incremental"
+            } else {
+                "full"
+            }
         );
         
         // Set projection state to rebuilding
@@ -583,6 +619,7 @@ mod tests {
         assert!(config.incremental_rebuild);
         assert_eq!(config.max_incremental_age_hours, 24);
         assert_eq!(config.rebuild_timeout_seconds, 3600);
+        assert_eq!(config.checkpoint_frequency, 1000);
     }
     
     #[test]
@@ -595,6 +632,7 @@ mod tests {
             from_position: 0,
             to_position: 0,
             is_incremental: false,
+            resumed: false,
             error: None,
         };
         
@@ -602,4 +640,184 @@ mod tests {
         assert!(!metadata.is_incremental);
         assert!(metadata.error.is_none());
     }
-}
\ No newline at end of file
+    
+    /// Read model summing deposits, keyed by event so reapplying is harmless
+    struct LedgerProjection {
+        state: ProjectionState,
+        balances: HashMap<Uuid, i64>,
+        handled: Vec<Uuid>,
+        crash_at: Option<Uuid>,
+    }
+    
+    #[async_trait]
+    impl Projection for LedgerProjection {
+        fn name(&self) -> &str {
+            "ledger"
+        }
+        
+        fn event_types(&self) -> Vec<String> {
+            vec!["deposit".to_string()]
+        }
+        
+        async fn handle_event(&mut self, event: &EventEnvelope) -> EventResult<()> {
+            self.handled.push(event.event_id);
+            self.balances.insert(event.event_id, event.event_data["amount"].as_i64().unwrap());
+            Ok(())
+        }
+        
+        async fn initialize(&mut self) -> EventResult<()> {
+            Ok(())
+        }
+        
+        async fn reset(&mut self) -> EventResult<()> {
+            self.balances.clear();
+            Ok(())
+        }
+        
+        fn state(&self) -> ProjectionState {
+            self.state.clone()
+        }
+        
+        async fn set_state(&mut self, state: ProjectionState) -> EventResult<()> {
+            self.state = state;
+            Ok(())
+        }
+        
+        fn last_position(&self) -> Option<i64> {
+            None
+        }
+        
+        fn last_updated(&self) -> Option<DateTime<Utc>> {
+            None
+        }
+    }
+    
+    #[async_trait]
+    impl ReplayHandler for LedgerProjection {
+        async fn handle_events(&mut self, events: &[EventEnvelope]) -> EventResult<()> {
+            if events.iter().any(|event| Some(event.event_id) == self.crash_at) {
+                return Err(EventError::HandlerError {
+                    message: "simulated crash".to_string(),
+                });
+            }
+            for event in events {
+                self.handle_event(event).await?;
+            }
+            Ok(())
+        }
+        
+        fn consumer_name(&self) -> &str {
+            "ledger"
+        }
+    }
+    
+    fn deposits(count: i64) -> Vec<EventEnvelope> {
+        let start = Utc::now() - chrono::Duration::days(1);
+        (0..count)
+            .map(|i| EventEnvelope {
+                event_id: Uuid::new_v4(),
+                aggregate_id: Uuid::new_v4(),
+                aggregate_type: "account".to_string(),
+                event_type: "deposit".to_string(),
+                aggregate_version: 1,
+                event_data: serde_json::json!({ "amount": i }),
+                metadata: crate::db::events::EventMetadata::new(),
+                occurred_at: start + chrono::Duration::seconds(i),
+                recorded_at: start + chrono::Duration::seconds(i),
+                schema_version: 1,
+                causation_id: None,
+                correlation_id: None,
+                checksum: None,
+            })
+            .collect()
+    }
+    
+    #[tokio::test]
+    async fn test_rebuild_resumes_from_checkpoint_after_crash() {
+        use crate::db::events::replay::InMemoryCheckpointStore;
+        use crate::db::events::tests::InMemoryEventStore;
+        
+        let events = deposits(100);
+        let event_store = Arc::new(InMemoryEventStore::with_events(events.clone()));
+        let checkpoints = Arc::new(InMemoryCheckpointStore::new());
+        let config = ProjectionRebuildConfig {
+            batch_size: 10,
+            checkpoint_frequency: 30,
+            ..ProjectionRebuildConfig::default()
+        };
+        let manager = || {
+            ProjectionRebuildManager::new(event_store.clone(), config.clone())
+                .with_checkpoint_store(checkpoints.clone())
+        };
+        
+        // Crash part way, after at least one checkpoint
+        let projection = Arc::new(Mutex::new(LedgerProjection {
+            state: ProjectionState::Ready,
+            balances: HashMap::new(),
+            handled: Vec::new(),
+            crash_at: Some(events[50].event_id),
+        }));
+        assert!(manager().rebuild_projection(projection.clone(), None, false).await.is_err());
+        
+        let checkpoint = checkpoints.load_checkpoint("ledger").await.unwrap().unwrap();
+        let checkpointed = checkpoint.events_processed as usize;
+        assert!(checkpointed >= 30);
+        assert_eq!(checkpoint.last_event_id, Some(events[checkpointed - 1].event_id));
+        let handled_before_crash = projection.lock().await.handled.len();
+        assert!(checkpointed < handled_before_crash && handled_before_crash <= 50);
+        
+        // A new manager, as after a restart, continues from the checkpoint
+        projection.lock().await.crash_at = None;
+        let metadata = manager().rebuild_projection(projection.clone(), None, false).await.unwrap();
+        assert!(metadata.resumed);
+        assert_eq!(metadata.from_position, checkpoint.position);
+        assert_eq!(metadata.events_processed, 100);
+        
+        let projection = projection.lock().await;
+        assert!(matches!(projection.state, ProjectionState::Ready));
+        assert_eq!(projection.handled[handled_before_crash], events[checkpointed].event_id);
+        let times_handled = |event: &EventEnvelope| {
+            projection.handled.iter().filter(|id| **id == event.event_id).count()
+        };
+        // Events before the checkpoint are never reprocessed; those handled
+        // between the checkpoint and the crash are handled again
+        assert!(events[..checkpointed].iter().all(|event| times_handled(event) == 1));
+        assert!(events[checkpointed..handled_before_crash].iter().all(|event| times_handled(event) == 2));
+        assert!(events[handled_before_crash..].iter().all(|event| times_handled(event) == 1));
+        assert_eq!(projection.balances.len(), 100);
+        assert_eq!(projection.balances.values().sum::<i64>(), (0..100).sum::<i64>());
+    }
+    
+    #[tokio::test]
+    async fn test_forced_rebuild_ignores_checkpoint() {
+        use crate::db::events::replay::InMemoryCheckpointStore;
+        use crate::db::events::tests::InMemoryEventStore;
+        
+        let events = deposits(20);
+        let event_store = Arc::new(InMemoryEventStore::with_events(events.clone()));
+        let config = ProjectionRebuildConfig {
+            batch_size: 5,
+            checkpoint_frequency: 5,
+            ..ProjectionRebuildConfig::default()
+        };
+        let manager = ProjectionRebuildManager::new(event_store, config)
+            .with_checkpoint_store(Arc::new(InMemoryCheckpointStore::new()));
+        
+        let projection = Arc::new(Mutex::new(LedgerProjection {
+            state: ProjectionState::Ready,
+            balances: HashMap::new(),
+            handled: Vec::new(),
+            crash_at: Some(events[12].event_id),
+        }));
+        assert!(manager.rebuild_projection(projection.clone(), None, false).await.is_err());
+        let handled_before_crash = projection.lock().await.handled.len();
+        
+        projection.lock().await.crash_at = None;
+        let metadata = manager.rebuild_projection(projection.clone(), None, true).await.unwrap();
+        assert!(!metadata.resumed);
+        assert_eq!(metadata.from_position, 0);
+        assert_eq!(projection.lock().await.handled.len(), handled_before_crash + 20);
+        assert_eq!(projection.lock().await.balances.len(), 20);
+    }
+}
+
@@ -0,0 +1,258 @@
+// File: src/db/events/query.rs
+//
+// Typed query builder for reading events from the event store
+
+use chrono::{DateTime, Utc};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::db::schema::event_store;
+use super::EventEnvelope;
+
+/// Filters for reading events from the store.
+///
+/// Each filter narrows the result; unset filters match everything. Events
+/// come back in the order they occurred, with events of the same aggregate
+/// that occurred at the same instant ordered by version.
+///
+/// ```rust,ignore
+/// let query = EventQuery::new()
+///     .aggregate(order_id)
+///     .event_type("WorkflowStarted")
+///     .since(yesterday)
+///     .limit(100);
+/// let events = store.query(&query).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventQuery {
+    aggregate_id: Option<Uuid>,
+    aggregate_type: Option<String>,
+    event_types: Vec<String>,
+    correlation_id: Option<Uuid>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    limit: Option<usize>,
+}
+
+impl EventQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only events of this aggregate
+    pub fn aggregate(mut self, aggregate_id: Uuid) -> Self {
+        self.aggregate_id = Some(aggregate_id);
+        self
+    }
+
+    /// Only events of aggregates of this type
+    pub fn aggregate_type(mut self, aggregate_type: impl Into<String>) -> Self {
+        self.aggregate_type = Some(aggregate_type.into());
+        self
+    }
+
+    /// Only events of this type. Calling it again matches any of the types.
+    pub fn event_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_types.push(event_type.into());
+        self
+    }
+
+    pub fn correlation_id(mut self, correlation_id: Uuid) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Only events that occurred at or after `from`
+    pub fn since(mut self, from: DateTime<Utc>) -> Self {
+        self.since = Some(from);
+        self
+    }
+
+    /// Only events that occurred before `to`
+    pub fn until(mut self, to: DateTime<Utc>) -> Self {
+        self.until = Some(to);
+        self
+    }
+
+    /// Return at most `limit` events
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether `event` passes the filters, ignoring the limit
+    pub fn matches(&self, event: &EventEnvelope) -> bool {
+        self.aggregate_id.is_none_or(|id| event.aggregate_id == id)
+            && self
+                .aggregate_type
+                .as_ref()
+                .is_none_or(|aggregate_type| &event.aggregate_type == aggregate_type)
+            && (self.event_types.is_empty() || self.event_types.contains(&event.event_type))
+            && self
+                .correlation_id
+                .is_none_or(|id| event.correlation_id == Some(id))
+            && self.since.is_none_or(|from| event.occurred_at >= from)
+            && self.until.is_none_or(|to| event.occurred_at < to)
+    }
+
+    /// Apply the query to events already in memory
+    pub fn apply<'a>(&self, events: impl IntoIterator<Item = &'a EventEnvelope>) -> Vec<EventEnvelope> {
+        let mut matched: Vec<EventEnvelope> = events
+            .into_iter()
+            .filter(|event| self.matches(event))
+            .cloned()
+            .collect();
+        matched.sort_by_key(|event| (event.occurred_at, event.aggregate_version));
+        if let Some(limit) = self.limit {
+            matched.truncate(limit);
+        }
+        matched
+    }
+
+    /// Compile the query against the `event_store` table
+    pub(crate) fn to_diesel(&self) -> event_store::BoxedQuery<'static, Pg> {
+        let mut query = event_store::table.into_boxed();
+
+        if let Some(aggregate_id) = self.aggregate_id {
+            query = query.filter(event_store::aggregate_id.eq(aggregate_id));
+        }
+        if let Some(aggregate_type) = &self.aggregate_type {
+            query = query.filter(event_store::aggregate_type.eq(aggregate_type.clone()));
+        }
+        match self.event_types.as_slice() {
+            [] => {}
+            [event_type] => query = query.filter(event_store::event_type.eq(event_type.clone())),
+            event_types => query = query.filter(event_store::event_type.eq_any(event_types.to_vec())),
+        }
+        if let Some(correlation_id) = self.correlation_id {
+            query = query.filter(event_store::correlation_id.eq(correlation_id));
+        }
+        if let Some(from) = self.since {
+            query = query.filter(event_store::occurred_at.ge(from));
+        }
+        if let Some(to) = self.until {
+            query = query.filter(event_store::occurred_at.lt(to));
+        }
+
+        query = query.order((event_store::occurred_at.asc(), event_store::aggregate_version.asc()));
+        if let Some(limit) = self.limit {
+            query = query.limit(limit as i64);
+        }
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::events::EventMetadata;
+    use chrono::Duration;
+
+    fn event(aggregate_id: Uuid, event_type: &str, version: i64, occurred_at: DateTime<Utc>) -> EventEnvelope {
+        EventEnvelope {
+            event_id: Uuid::new_v4(),
+            aggregate_id,
+            aggregate_type: "workflow".to_string(),
+            event_type: event_type.to_string(),
+            aggregate_version: version,
+            event_data: serde_json::json!({}),
+            metadata: EventMetadata::new(),
+            occurred_at,
+            recorded_at: occurred_at,
+            schema_version: 1,
+            causation_id: None,
+            correlation_id: None,
+            checksum: None,
+        }
+    }
+
+    /// Two workflows, each started, stepped and completed an hour apart
+    fn seeded() -> (Uuid, Uuid, DateTime<Utc>, Vec<EventEnvelope>) {
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = Utc::now() - Duration::hours(10);
+        let at = |hours| start + Duration::hours(hours);
+        let events = vec![
+            event(first, "WorkflowStarted", 1, at(0)),
+            event(second, "WorkflowStarted", 1, at(1)),
+            event(first, "StepCompleted", 2, at(2)),
+            event(second, "StepCompleted", 2, at(3)),
+            event(first, "WorkflowCompleted", 3, at(4)),
+            event(second, "WorkflowCompleted", 3, at(5)),
+        ];
+        (first, second, start, events)
+    }
+
+    fn versions(events: &[EventEnvelope]) -> Vec<(String, i64)> {
+        events
+            .iter()
+            .map(|event| (event.event_type.clone(), event.aggregate_version))
+            .collect()
+    }
+
+    #[test]
+    fn test_filters_select_subsets() {
+        let (first, second, start, events) = seeded();
+
+        let by_aggregate = EventQuery::new().aggregate(second).apply(&events);
+        assert_eq!(by_aggregate.len(), 3);
+        assert!(by_aggregate.iter().all(|event| event.aggregate_id == second));
+        assert_eq!(by_aggregate.iter().map(|e| e.aggregate_version).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let by_type = EventQuery::new().event_type("WorkflowStarted").apply(&events);
+        assert_eq!(
+            by_type.iter().map(|e| e.aggregate_id).collect::<Vec<_>>(),
+            [first, second]
+        );
+
+        let by_types = EventQuery::new()
+            .event_type("WorkflowStarted")
+            .event_type("WorkflowCompleted")
+            .aggregate(first)
+            .apply(&events);
+        assert_eq!(
+            versions(&by_types),
+            [("WorkflowStarted".to_string(), 1), ("WorkflowCompleted".to_string(), 3)]
+        );
+
+        let window = EventQuery::new()
+            .since(start + Duration::hours(2))
+            .until(start + Duration::hours(4))
+            .apply(&events);
+        assert_eq!(
+            versions(&window),
+            [("StepCompleted".to_string(), 2), ("StepCompleted".to_string(), 2)]
+        );
+
+        let limited = EventQuery::new().limit(4).apply(&events);
+        assert_eq!(limited.len(), 4);
+        assert_eq!(limited[3].event_type, "StepCompleted");
+
+        assert!(EventQuery::new().aggregate_type("order").apply(&events).is_empty());
+    }
+
+    #[test]
+    fn test_compiles_to_filtered_query() {
+        let aggregate_id = Uuid::new_v4();
+        let query = EventQuery::new()
+            .aggregate(aggregate_id)
+            .event_type("WorkflowStarted")
+            .since(Utc::now())
+            .limit(100)
+            .to_diesel();
+        let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+
+        assert!(sql.contains(r#""event_store"."aggregate_id" = $1"#), "{}", sql);
+        assert!(sql.contains(r#""event_store"."event_type" = $2"#), "{}", sql);
+        assert!(sql.contains(r#""event_store"."occurred_at" >= $3"#), "{}", sql);
+        assert!(sql.contains("LIMIT $4"), "{}", sql);
+        assert!(sql.contains(r#"ORDER BY "event_store"."occurred_at" ASC, "event_store"."aggregate_version" ASC"#), "{}", sql);
+
+        let unfiltered = diesel::debug_query::<Pg, _>(&EventQuery::new().to_diesel()).to_string();
+        assert!(!unfiltered.contains("WHERE"), "{}", unfiltered);
+
+        let any_type = EventQuery::new().event_type("a").event_type("b").to_diesel();
+        let sql = diesel::debug_query::<Pg, _>(&any_type).to_string();
+        assert!(sql.contains(r#""event_store"."event_type" = ANY($1)"#), "{}", sql);
+    }
+}
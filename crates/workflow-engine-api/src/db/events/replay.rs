@@ -5,12 +5,15 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tracing::{info, warn, error};
 use uuid::Uuid;
 
+use crate::db::schema::event_projections;
 use super::{
     EventStore, EventEnvelope, EventError, EventResult, EventMetadata,
     PostgreSQLEventStore, AggregateSnapshot
@@ -90,12 +93,151 @@ pub trait ReplayHandler: Send + Sync {
     fn consumer_name(&self) -> &str;
 }
 
-/// Event replay engine for batch processing with position tracking
+/// Durable storage for replay positions, so that a replay interrupted by a
+/// crash can resume from its last checkpoint
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    /// Load the last checkpoint saved for a consumer
+    async fn load_checkpoint(&self, consumer_name: &str) -> EventResult<Option<ReplayPosition>>;
+    
+    /// Save a checkpoint, replacing the previous one for the consumer
+    async fn save_checkpoint(&self, position: &ReplayPosition) -> EventResult<()>;
+    
+    /// Forget the checkpoint for a consumer
+    async fn clear_checkpoint(&self, consumer_name: &str) -> EventResult<()>;
+}
+
+/// Checkpoint store kept in memory; checkpoints do not survive a restart
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpointStore {
+    checkpoints: RwLock<HashMap<String, ReplayPosition>>,
+}
+
+impl InMemoryCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for InMemoryCheckpointStore {
+    async fn load_checkpoint(&self, consumer_name: &str) -> EventResult<Option<ReplayPosition>> {
+        Ok(self.checkpoints.read().await.get(consumer_name).cloned())
+    }
+    
+    async fn save_checkpoint(&self, position: &ReplayPosition) -> EventResult<()> {
+        self.checkpoints.write().await.insert(position.consumer_name.clone(), position.clone());
+        Ok(())
+    }
+    
+    async fn clear_checkpoint(&self, consumer_name: &str) -> EventResult<()> {
+        self.checkpoints.write().await.remove(consumer_name);
+        Ok(())
+    }
+}
+
+/// Checkpoint store backed by the `event_projections` table, which records
+/// the last processed position and event of each projection
+pub struct PostgreSQLCheckpointStore {
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+}
+
+impl PostgreSQLCheckpointStore {
+    pub fn new(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Self {
+        Self { pool }
+    }
+    
+    fn get_connection(&self) -> EventResult<diesel::r2d2::PooledConnection<ConnectionManager<PgConnection>>> {
+        self.pool.get().map_err(|e| EventError::DatabaseError {
+            message: format!("Failed to get database connection: {}", e),
+        })
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for PostgreSQLCheckpointStore {
+    async fn load_checkpoint(&self, consumer_name: &str) -> EventResult<Option<ReplayPosition>> {
+        let mut conn = self.get_connection()?;
+        
+        let record: Option<(Option<i64>, Option<Uuid>, DateTime<Utc>)> = event_projections::table
+            .filter(event_projections::projection_name.eq(consumer_name))
+            .select((
+                event_projections::last_processed_position,
+                event_projections::last_processed_event_id,
+                event_projections::updated_at,
+            ))
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to load checkpoint: {}", e),
+            })?;
+        
+        Ok(record.and_then(|(position, last_event_id, updated_at)| {
+            position.map(|position| ReplayPosition {
+                consumer_name: consumer_name.to_string(),
+                position,
+                last_event_id,
+                events_processed: 0,
+                last_checkpoint: updated_at,
+            })
+        }))
+    }
+    
+    async fn save_checkpoint(&self, position: &ReplayPosition) -> EventResult<()> {
+        let mut conn = self.get_connection()?;
+        
+        diesel::insert_into(event_projections::table)
+            .values((
+                event_projections::projection_name.eq(&position.consumer_name),
+                event_projections::last_processed_position.eq(Some(position.position)),
+                event_projections::last_processed_event_id.eq(position.last_event_id),
+                event_projections::status.eq("rebuilding"),
+            ))
+            .on_conflict(event_projections::projection_name)
+            .do_update()
+            .set((
+                event_projections::last_processed_position.eq(Some(position.position)),
+                event_projections::last_processed_event_id.eq(position.last_event_id),
+                event_projections::updated_at.eq(position.last_checkpoint),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to save checkpoint: {}", e),
+            })?;
+        
+        Ok(())
+    }
+    
+    async fn clear_checkpoint(&self, consumer_name: &str) -> EventResult<()> {
+        let mut conn = self.get_connection()?;
+        
+        diesel::update(event_projections::table)
+            .filter(event_projections::projection_name.eq(consumer_name))
+            .set((
+                event_projections::last_processed_position.eq(None::<i64>),
+                event_projections::last_processed_event_id.eq(None::<Uuid>),
+                event_projections::updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to clear checkpoint: {}", e),
+            })?;
+        
+        Ok(())
+    }
+}
+
+/// Event replay engine for batch processing with position tracking.
+///
+/// Positions are checkpointed to the checkpoint store every
+/// `checkpoint_frequency` events and at the end of each replay. A replay
+/// resumes from the consumer's last checkpoint and skips events up to the
+/// last one processed, so events before a checkpoint are never handled twice.
 pub struct EventReplayEngine {
     event_store: Arc<dyn EventStore>,
     config: ReplayConfig,
     positions: Arc<RwLock<HashMap<String, ReplayPosition>>>,
-    checkpoints: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+    checkpoint_store: Arc<dyn CheckpointStore>,
 }
 
 impl EventReplayEngine {
@@ -104,10 +246,21 @@ impl EventReplayEngine {
             event_store,
             config,
             positions: Arc::new(RwLock::new(HashMap::new())),
-            checkpoints: Arc::new(Mutex::new(HashMap::new())),
+            checkpoint_store: Arc::new(InMemoryCheckpointStore::new()),
         }
     }
     
+    /// Persist checkpoints to `store`
+    pub fn with_checkpoint_store(mut self, store: Arc<dyn CheckpointStore>) -> Self {
+        self.checkpoint_store = store;
+        self
+    }
+    
+    /// The last checkpoint saved for a consumer, if any
+    pub async fn checkpoint(&self, consumer_name: &str) -> EventResult<Option<ReplayPosition>> {
+        self.checkpoint_store.load_checkpoint(consumer_name).await
+    }
+    
     /// Replay events for a specific handler
     pub async fn replay_for_handler<H: ReplayHandler>(
         &self,
@@ -135,11 +288,11 @@ impl EventReplayEngine {
         
         let start_time = Utc::now();
         let mut total_events = 0u64;
-        let mut last_checkpoint = Utc::now();
+        let mut events_since_checkpoint = 0usize;
         
         loop {
             // Fetch next batch
-            let events = self.event_store
+            let mut events = self.event_store
                 .replay_events(
                     position.position,
                     event_types.clone(),
@@ -147,6 +300,13 @@ impl EventReplayEngine {
                 )
                 .await?;
             
+            // Positions are inclusive, so skip the events up to the last one processed
+            if let Some(last_event_id) = position.last_event_id {
+                if let Some(index) = events.iter().position(|event| event.event_id == last_event_id) {
+                    events.drain(..=index);
+                }
+            }
+            
             if events.is_empty() {
                 break;
             }
@@ -169,11 +329,12 @@ impl EventReplayEngine {
                 Ok(Ok(())) => {
                     total_events += batch_size as u64;
                     position.events_processed += batch_size as u64;
+                    events_since_checkpoint += batch_size;
                     
                     // Checkpoint if needed
-                    if total_events % self.config.checkpoint_frequency as u64 == 0 {
+                    if events_since_checkpoint >= self.config.checkpoint_frequency {
                         self.save_checkpoint(&mut position).await?;
-                        last_checkpoint = Utc::now();
+                        events_since_checkpoint = 0;
                     }
                 }
                 Ok(Err(e)) => {
@@ -305,7 +466,7 @@ impl EventReplayEngine {
         Ok(last_version)
     }
     
-    /// Get or create a replay position
+    /// Get or create a replay position, resuming from the last checkpoint
     async fn get_or_create_position(&self, consumer_name: &str) -> EventResult<ReplayPosition> {
         let positions = self.positions.read().await;
         
@@ -314,7 +475,10 @@ impl EventReplayEngine {
         } else {
             drop(positions);
             
-            let position = ReplayPosition::new(consumer_name.to_string());
+            let position = match self.checkpoint_store.load_checkpoint(consumer_name).await? {
+                Some(checkpoint) => checkpoint,
+                None => ReplayPosition::new(consumer_name.to_string()),
+            };
             let mut positions = self.positions.write().await;
             positions.insert(consumer_name.to_string(), position.clone());
             
@@ -328,9 +492,9 @@ impl EventReplayEngine {
         
         let mut positions = self.positions.write().await;
         positions.insert(position.consumer_name.clone(), position.clone());
+        drop(positions);
         
-        let mut checkpoints = self.checkpoints.lock().await;
-        checkpoints.insert(position.consumer_name.clone(), position.last_checkpoint);
+        self.checkpoint_store.save_checkpoint(position).await?;
         
         info!(
             "Saved checkpoint for consumer '{}' at position {} ({} events)",
@@ -349,9 +513,9 @@ impl EventReplayEngine {
     pub async fn reset_position(&self, consumer_name: &str) -> EventResult<()> {
         let mut positions = self.positions.write().await;
         positions.remove(consumer_name);
+        drop(positions);
         
-        let mut checkpoints = self.checkpoints.lock().await;
-        checkpoints.remove(consumer_name);
+        self.checkpoint_store.clear_checkpoint(consumer_name).await?;
         
         info!("Reset replay position for consumer '{}'", consumer_name);
         
@@ -365,7 +529,7 @@ impl Clone for EventReplayEngine {
             event_store: self.event_store.clone(),
             config: self.config.clone(),
             positions: self.positions.clone(),
-            checkpoints: self.checkpoints.clone(),
+            checkpoint_store: self.checkpoint_store.clone(),
         }
     }
 }
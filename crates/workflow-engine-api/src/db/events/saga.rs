@@ -4,12 +4,17 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use futures_util::future::join_all;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use crate::db::schema::saga_executions;
 use super::{EventEnvelope, EventError, EventResult, EventStore};
 
 /// Saga transaction state
@@ -27,6 +32,36 @@ pub enum SagaState {
     Aborted,
 }
 
+impl From<SagaState> for String {
+    fn from(state: SagaState) -> Self {
+        match state {
+            SagaState::Running => "running",
+            SagaState::Completed => "completed",
+            SagaState::Compensating => "compensating",
+            SagaState::Failed => "failed",
+            SagaState::Aborted => "aborted",
+        }
+        .to_string()
+    }
+}
+
+impl TryFrom<String> for SagaState {
+    type Error = EventError;
+    
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.as_str() {
+            "running" => Ok(SagaState::Running),
+            "completed" => Ok(SagaState::Completed),
+            "compensating" => Ok(SagaState::Compensating),
+            "failed" => Ok(SagaState::Failed),
+            "aborted" => Ok(SagaState::Aborted),
+            _ => Err(EventError::SerializationError {
+                message: format!("Unknown saga state '{}'", s),
+            }),
+        }
+    }
+}
+
 /// Saga step execution status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SagaStepStatus {
@@ -55,6 +90,7 @@ pub struct SagaStep {
     pub input_data: serde_json::Value,
     pub compensation_operation: Option<String>,
     pub compensation_data: Option<serde_json::Value>,
+    /// Fail the step if its executor takes longer than this
     pub timeout_seconds: Option<u64>,
     pub retry_policy: Option<RetryPolicy>,
     pub depends_on: Vec<String>,
@@ -94,6 +130,8 @@ pub struct SagaExecution {
     pub completed_at: Option<DateTime<Utc>>,
     pub error_message: Option<String>,
     pub total_timeout_seconds: Option<u64>,
+    #[serde(default)]
+    pub compensation_strategy: CompensationStrategy,
 }
 
 /// Saga step execution state
@@ -138,9 +176,10 @@ pub struct SagaDefinition {
 }
 
 /// Compensation strategy for failed sagas
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub enum CompensationStrategy {
     /// Compensate in reverse order of execution
+    #[default]
     ReverseOrder,
     /// Compensate in parallel where possible
     Parallel,
@@ -199,6 +238,7 @@ impl SagaOrchestrator {
             completed_at: None,
             error_message: None,
             total_timeout_seconds: definition.global_timeout_seconds,
+            compensation_strategy: definition.compensation_strategy.clone(),
         };
         
         // Store the saga
@@ -221,6 +261,47 @@ impl SagaOrchestrator {
         Ok(saga_id)
     }
     
+    /// Resume the sagas that were running or compensating when the process
+    /// stopped, as recorded in the saga store.
+    ///
+    /// Steps that were still executing are run again, so executors should be
+    /// idempotent. Returns the IDs of the resumed sagas.
+    pub async fn recover_sagas(&self) -> EventResult<Vec<Uuid>> {
+        let mut interrupted = self.saga_store.list_sagas_by_state(SagaState::Running).await?;
+        interrupted.extend(self.saga_store.list_sagas_by_state(SagaState::Compensating).await?);
+        
+        let mut recovered = Vec::with_capacity(interrupted.len());
+        for mut execution in interrupted {
+            let saga_id = execution.saga_id;
+            for step_exec in &mut execution.steps {
+                match step_exec.status {
+                    SagaStepStatus::Running => step_exec.status = SagaStepStatus::Pending,
+                    SagaStepStatus::Compensating => step_exec.status = SagaStepStatus::Completed,
+                    _ => {}
+                }
+            }
+            
+            self.emit_saga_event(saga_id, "saga_resumed", serde_json::json!({
+                "state": String::from(execution.state.clone())
+            })).await?;
+            
+            if execution.state == SagaState::Compensating {
+                self.start_compensation(&mut execution).await?;
+                execution.updated_at = Utc::now();
+                self.saga_store.save_saga(&execution).await?;
+                self.running_sagas.write().await.insert(saga_id, execution);
+            } else {
+                self.running_sagas.write().await.insert(saga_id, execution);
+                self.execute_next_steps(saga_id).await?;
+            }
+            
+            tracing::info!("Recovered saga {}", saga_id);
+            recovered.push(saga_id);
+        }
+        
+        Ok(recovered)
+    }
+    
     /// Continue saga execution after a step completes
     pub async fn handle_step_completion(
         &self,
@@ -378,66 +459,122 @@ impl SagaOrchestrator {
             .clone();
         drop(executors);
         
-        // Execute the step and handle completion
-        let result = executor.execute_step(&step, &global_context).await;
+        // Execute the step, treating an overrun of its timeout as a failure
+        let execution = executor.execute_step(&step, &global_context);
+        let result = match step.timeout_seconds {
+            Some(seconds) => tokio::time::timeout(Duration::from_secs(seconds), execution)
+                .await
+                .unwrap_or_else(|_| SagaStepResult::Failure {
+                    error_message: format!("Step {} timed out after {}s", step.step_id, seconds),
+                }),
+            None => execution.await,
+        };
         Box::pin(self.handle_step_completion(saga_id, step_id, result)).await?;
         
         Ok(())
     }
     
-    /// Start compensation for a failed saga
+    /// Compensate the saga's completed steps according to its compensation
+    /// strategy, then mark it failed
     async fn start_compensation(&self, execution: &mut SagaExecution) -> EventResult<()> {
         self.emit_saga_event(execution.saga_id, "compensation_started", serde_json::json!({
             "error": execution.error_message
         })).await?;
         
-        // Find steps that need compensation (completed steps in reverse order)
-        let mut compensatable_steps: Vec<_> = execution.steps.iter_mut()
-            .filter(|s| s.status == SagaStepStatus::Completed && s.step.compensation_operation.is_some())
-            .collect();
+        let order = Self::compensation_order(execution);
+        for &index in &order {
+            let step_exec = &mut execution.steps[index];
+            step_exec.status = SagaStepStatus::Compensating;
+            step_exec.compensation_started_at = Some(Utc::now());
+        }
         
-        compensatable_steps.reverse();
+        let results = {
+            let steps = &execution.steps;
+            let context = &execution.global_context;
+            if execution.compensation_strategy == CompensationStrategy::Parallel {
+                join_all(order.iter().map(|&index| async move {
+                    (index, self.execute_compensation(&steps[index].step, context).await)
+                }))
+                .await
+            } else {
+                let mut results = Vec::with_capacity(order.len());
+                for &index in &order {
+                    results.push((index, self.execute_compensation(&steps[index].step, context).await));
+                }
+                results
+            }
+        };
         
-        for step_exec in compensatable_steps {
-            if let Some(_) = &step_exec.step.compensation_operation {
-                step_exec.status = SagaStepStatus::Compensating;
-                step_exec.compensation_started_at = Some(Utc::now());
-                
-                // Execute compensation
-                self.execute_compensation(execution.saga_id, &step_exec.step.step_id).await?;
+        for (index, result) in results {
+            let step_exec = &mut execution.steps[index];
+            match result {
+                SagaStepResult::Success { .. } => {
+                    step_exec.status = SagaStepStatus::Compensated;
+                    step_exec.compensation_completed_at = Some(Utc::now());
+                    self.emit_saga_event(execution.saga_id, "step_compensated", serde_json::json!({
+                        "step_id": step_exec.step.step_id,
+                        "service": step_exec.step.service_name
+                    })).await?;
+                }
+                SagaStepResult::Failure { error_message } => {
+                    tracing::error!(
+                        "Compensation of step {} in saga {} failed: {}",
+                        step_exec.step.step_id,
+                        execution.saga_id,
+                        error_message
+                    );
+                    self.emit_saga_event(execution.saga_id, "compensation_failed", serde_json::json!({
+                        "step_id": step_exec.step.step_id,
+                        "service": step_exec.step.service_name,
+                        "error": error_message
+                    })).await?;
+                    step_exec.error_message = Some(error_message);
+                }
             }
         }
         
-        Ok(())
+        execution.state = SagaState::Failed;
+        execution.completed_at = Some(Utc::now());
+        self.emit_saga_event(execution.saga_id, "saga_failed", serde_json::json!({
+            "error": execution.error_message
+        })).await
     }
     
-    /// Execute compensation for a step
-    async fn execute_compensation(&self, saga_id: Uuid, step_id: &str) -> EventResult<()> {
-        // This would execute the compensation operation
-        // For now, we'll mark it as compensated
-        let mut running = self.running_sagas.write().await;
-        if let Some(execution) = running.get_mut(&saga_id) {
-            if let Some(step_exec) = execution.steps.iter_mut().find(|s| s.step.step_id == step_id) {
-                step_exec.status = SagaStepStatus::Compensated;
-                step_exec.compensation_completed_at = Some(Utc::now());
-            }
-            
-            // Check if all compensations are done
-            let all_compensated = execution.steps.iter()
-                .filter(|s| s.step.compensation_operation.is_some())
-                .all(|s| s.status == SagaStepStatus::Compensated);
-            
-            if all_compensated {
-                execution.state = SagaState::Failed;
-                execution.completed_at = Some(Utc::now());
-                
-                self.emit_saga_event(saga_id, "saga_failed", serde_json::json!({
-                    "error": execution.error_message
-                })).await?;
-            }
+    /// Indices of the completed steps that have a compensation operation, in
+    /// the order the compensation strategy runs them
+    fn compensation_order(execution: &SagaExecution) -> Vec<usize> {
+        let mut completed: Vec<usize> = (0..execution.steps.len())
+            .filter(|&index| {
+                let step_exec = &execution.steps[index];
+                step_exec.status == SagaStepStatus::Completed
+                    && step_exec.step.compensation_operation.is_some()
+            })
+            .collect();
+        
+        // Most recently completed first
+        completed.sort_by_key(|&index| execution.steps[index].completed_at);
+        completed.reverse();
+        
+        if let CompensationStrategy::Custom(order) = &execution.compensation_strategy {
+            // Listed steps in the given order, then any others in reverse
+            completed.sort_by_key(|&index| {
+                let step_id = &execution.steps[index].step.step_id;
+                order.iter().position(|id| id == step_id).unwrap_or(order.len())
+            });
         }
         
-        Ok(())
+        completed
+    }
+    
+    /// Run the compensation operation of a step
+    async fn execute_compensation(&self, step: &SagaStep, global_context: &serde_json::Value) -> SagaStepResult {
+        let executor = self.step_executors.read().await.get(&step.service_name).cloned();
+        match executor {
+            Some(executor) => executor.compensate_step(step, global_context).await,
+            None => SagaStepResult::Failure {
+                error_message: format!("No executor found for service {}", step.service_name),
+            },
+        }
     }
     
     /// Complete a successful saga
@@ -539,6 +676,15 @@ pub trait SagaStepExecutor: Send + Sync {
         global_context: &serde_json::Value,
     ) -> SagaStepResult;
     
+    /// Undo a completed step by running its compensation operation
+    async fn compensate_step(
+        &self,
+        _step: &SagaStep,
+        _global_context: &serde_json::Value,
+    ) -> SagaStepResult {
+        SagaStepResult::Success { output_data: serde_json::Value::Null }
+    }
+    
     /// Get the service name this executor handles
     fn service_name(&self) -> &str;
 }
@@ -605,6 +751,152 @@ impl SagaStore for InMemorySagaStore {
     }
 }
 
+/// Database model for saga executions
+#[derive(Debug, Clone, Queryable, Insertable, Selectable, AsChangeset)]
+#[diesel(table_name = saga_executions)]
+struct SagaRecord {
+    saga_id: Uuid,
+    saga_type: String,
+    state: String,
+    steps: serde_json::Value,
+    global_context: serde_json::Value,
+    compensation_strategy: serde_json::Value,
+    error_message: Option<String>,
+    total_timeout_seconds: Option<i64>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<&SagaExecution> for SagaRecord {
+    type Error = EventError;
+    
+    fn try_from(execution: &SagaExecution) -> Result<Self, Self::Error> {
+        let to_json = |value: serde_json::Result<serde_json::Value>| {
+            value.map_err(|e| EventError::SerializationError {
+                message: format!("Failed to serialize saga {}: {}", execution.saga_id, e),
+            })
+        };
+        
+        Ok(Self {
+            saga_id: execution.saga_id,
+            saga_type: execution.saga_type.clone(),
+            state: String::from(execution.state.clone()),
+            steps: to_json(serde_json::to_value(&execution.steps))?,
+            global_context: execution.global_context.clone(),
+            compensation_strategy: to_json(serde_json::to_value(&execution.compensation_strategy))?,
+            error_message: execution.error_message.clone(),
+            total_timeout_seconds: execution.total_timeout_seconds.map(|seconds| seconds as i64),
+            created_at: execution.created_at,
+            updated_at: execution.updated_at,
+            completed_at: execution.completed_at,
+        })
+    }
+}
+
+impl TryFrom<SagaRecord> for SagaExecution {
+    type Error = EventError;
+    
+    fn try_from(record: SagaRecord) -> Result<Self, Self::Error> {
+        let saga_id = record.saga_id;
+        let invalid = |e: serde_json::Error| EventError::SerializationError {
+            message: format!("Failed to deserialize saga {}: {}", saga_id, e),
+        };
+        
+        Ok(Self {
+            saga_id,
+            saga_type: record.saga_type,
+            state: SagaState::try_from(record.state)?,
+            steps: serde_json::from_value(record.steps).map_err(invalid)?,
+            global_context: record.global_context,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            completed_at: record.completed_at,
+            error_message: record.error_message,
+            total_timeout_seconds: record.total_timeout_seconds.map(|seconds| seconds as u64),
+            compensation_strategy: serde_json::from_value(record.compensation_strategy).map_err(invalid)?,
+        })
+    }
+}
+
+/// PostgreSQL saga store, so sagas survive restarts and can be resumed with
+/// [`SagaOrchestrator::recover_sagas`]
+pub struct PostgreSQLSagaStore {
+    pool: Arc<Pool<ConnectionManager<PgConnection>>>,
+}
+
+impl PostgreSQLSagaStore {
+    pub fn new(pool: Arc<Pool<ConnectionManager<PgConnection>>>) -> Self {
+        Self { pool }
+    }
+    
+    fn get_connection(&self) -> EventResult<diesel::r2d2::PooledConnection<ConnectionManager<PgConnection>>> {
+        self.pool.get().map_err(|e| EventError::DatabaseError {
+            message: format!("Failed to get database connection: {}", e),
+        })
+    }
+}
+
+#[async_trait]
+impl SagaStore for PostgreSQLSagaStore {
+    async fn save_saga(&self, execution: &SagaExecution) -> EventResult<()> {
+        let record = SagaRecord::try_from(execution)?;
+        let mut conn = self.get_connection()?;
+        
+        diesel::insert_into(saga_executions::table)
+            .values(&record)
+            .on_conflict(saga_executions::saga_id)
+            .do_update()
+            .set(&record)
+            .execute(&mut conn)
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to save saga {}: {}", execution.saga_id, e),
+            })?;
+        
+        Ok(())
+    }
+    
+    async fn get_saga(&self, saga_id: Uuid) -> EventResult<Option<SagaExecution>> {
+        let mut conn = self.get_connection()?;
+        
+        let record = saga_executions::table
+            .find(saga_id)
+            .select(SagaRecord::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to load saga {}: {}", saga_id, e),
+            })?;
+        
+        record.map(SagaExecution::try_from).transpose()
+    }
+    
+    async fn list_sagas_by_state(&self, state: SagaState) -> EventResult<Vec<SagaExecution>> {
+        let mut conn = self.get_connection()?;
+        
+        let records = saga_executions::table
+            .filter(saga_executions::state.eq(String::from(state)))
+            .order(saga_executions::created_at.asc())
+            .select(SagaRecord::as_select())
+            .load(&mut conn)
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to list sagas: {}", e),
+            })?;
+        
+        records.into_iter().map(SagaExecution::try_from).collect()
+    }
+    
+    async fn cleanup_old_sagas(&self, before: DateTime<Utc>) -> EventResult<usize> {
+        let mut conn = self.get_connection()?;
+        
+        diesel::delete(saga_executions::table.filter(saga_executions::completed_at.le(before)))
+            .execute(&mut conn)
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to clean up sagas: {}", e),
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -645,6 +937,7 @@ mod tests {
             completed_at: None,
             error_message: None,
             total_timeout_seconds: None,
+            compensation_strategy: CompensationStrategy::ReverseOrder,
         };
         
         // Save saga
@@ -659,4 +952,210 @@ mod tests {
         let running_sagas = store.list_sagas_by_state(SagaState::Running).await.unwrap();
         assert_eq!(running_sagas.len(), 1);
     }
-}
\ No newline at end of file
+    
+    use crate::db::events::tests::InMemoryEventStore;
+    
+    /// Executor whose steps succeed, except those named in `hang`, which
+    /// never finish. Records the steps it executes and compensates.
+    #[derive(Default)]
+    struct RecordingExecutor {
+        hang: Vec<&'static str>,
+        executed: std::sync::Mutex<Vec<String>>,
+        compensated: std::sync::Mutex<Vec<String>>,
+    }
+    
+    #[async_trait]
+    impl SagaStepExecutor for RecordingExecutor {
+        async fn execute_step(&self, step: &SagaStep, _global_context: &serde_json::Value) -> SagaStepResult {
+            self.executed.lock().unwrap().push(step.step_id.clone());
+            if self.hang.contains(&step.step_id.as_str()) {
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+            SagaStepResult::Success { output_data: serde_json::json!({}) }
+        }
+        
+        async fn compensate_step(&self, step: &SagaStep, _global_context: &serde_json::Value) -> SagaStepResult {
+            self.compensated.lock().unwrap().push(step.step_id.clone());
+            SagaStepResult::Success { output_data: serde_json::json!({}) }
+        }
+        
+        fn service_name(&self) -> &str {
+            "orders"
+        }
+    }
+    
+    fn step(step_id: &str, depends_on: Option<&str>, timeout_seconds: Option<u64>) -> SagaStep {
+        SagaStep {
+            step_id: step_id.to_string(),
+            service_name: "orders".to_string(),
+            operation: step_id.to_string(),
+            input_data: serde_json::json!({}),
+            compensation_operation: Some(format!("undo_{}", step_id)),
+            compensation_data: None,
+            timeout_seconds,
+            retry_policy: None,
+            depends_on: depends_on.into_iter().map(String::from).collect(),
+            parallel_group: None,
+        }
+    }
+    
+    /// Runs reserve, charge and ship in sequence, where shipping hangs past
+    /// its timeout
+    async fn run_timed_out_saga(strategy: CompensationStrategy) -> (SagaExecution, Vec<String>) {
+        let orchestrator = SagaOrchestrator::new(
+            Arc::new(InMemoryEventStore::default()),
+            Arc::new(InMemorySagaStore::new()),
+        );
+        let executor = Arc::new(RecordingExecutor {
+            hang: vec!["ship"],
+            ..RecordingExecutor::default()
+        });
+        orchestrator.register_step_executor("orders", executor.clone()).await;
+        
+        let definition = SagaDefinition {
+            saga_type: "order".to_string(),
+            name: "Order".to_string(),
+            description: "Reserve, charge and ship an order".to_string(),
+            steps: vec![
+                step("reserve", None, None),
+                step("charge", Some("reserve"), Some(5)),
+                step("ship", Some("charge"), Some(5)),
+            ],
+            global_timeout_seconds: None,
+            compensation_strategy: strategy,
+        };
+        let saga_id = orchestrator.start_saga(definition, serde_json::json!({})).await.unwrap();
+        
+        let execution = orchestrator.get_saga_status(saga_id).await.unwrap().unwrap();
+        let compensated = executor.compensated.lock().unwrap().clone();
+        (execution, compensated)
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn test_step_timeout_compensates_prior_steps() {
+        let started = tokio::time::Instant::now();
+        let (execution, compensated) = run_timed_out_saga(CompensationStrategy::ReverseOrder).await;
+        
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+        assert_eq!(execution.state, SagaState::Failed);
+        assert_eq!(execution.error_message.as_deref(), Some("Step ship timed out after 5s"));
+        assert_eq!(compensated, ["charge", "reserve"]);
+        
+        let statuses: Vec<_> = execution.steps.iter().map(|s| s.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            [SagaStepStatus::Compensated, SagaStepStatus::Compensated, SagaStepStatus::Failed]
+        );
+        assert!(execution.steps[0].compensation_completed_at.is_some());
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn test_timeout_compensation_follows_custom_order() {
+        let strategy = CompensationStrategy::Custom(vec!["reserve".to_string(), "charge".to_string()]);
+        let (execution, compensated) = run_timed_out_saga(strategy).await;
+        
+        assert_eq!(execution.state, SagaState::Failed);
+        assert_eq!(compensated, ["reserve", "charge"]);
+    }
+    
+    /// A saga whose first step completed and whose second step was executing
+    /// when the process stopped
+    fn interrupted_saga() -> SagaExecution {
+        let mut steps: Vec<SagaStepExecution> = vec![
+            step("reserve", None, None),
+            step("charge", Some("reserve"), None),
+            step("ship", Some("charge"), None),
+        ]
+        .into_iter()
+        .map(SagaStepExecution::new)
+        .collect();
+        steps[0].status = SagaStepStatus::Completed;
+        steps[0].attempt_count = 1;
+        steps[0].completed_at = Some(Utc::now());
+        steps[1].status = SagaStepStatus::Running;
+        steps[1].attempt_count = 1;
+        
+        SagaExecution {
+            saga_id: Uuid::new_v4(),
+            saga_type: "order".to_string(),
+            state: SagaState::Running,
+            steps,
+            global_context: serde_json::json!({"order_id": 7}),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            completed_at: None,
+            error_message: None,
+            total_timeout_seconds: Some(600),
+            compensation_strategy: CompensationStrategy::Custom(vec!["charge".to_string()]),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_recovered_saga_resumes_from_interrupted_step() {
+        let saga_store = Arc::new(InMemorySagaStore::new());
+        let execution = interrupted_saga();
+        saga_store.save_saga(&execution).await.unwrap();
+        
+        // A fresh orchestrator, as after a restart
+        let orchestrator = SagaOrchestrator::new(Arc::new(InMemoryEventStore::default()), saga_store.clone());
+        let executor = Arc::new(RecordingExecutor::default());
+        orchestrator.register_step_executor("orders", executor.clone()).await;
+        
+        let recovered = orchestrator.recover_sagas().await.unwrap();
+        
+        assert_eq!(recovered, [execution.saga_id]);
+        assert_eq!(*executor.executed.lock().unwrap(), ["charge", "ship"]);
+        
+        let saved = saga_store.get_saga(execution.saga_id).await.unwrap().unwrap();
+        assert_eq!(saved.state, SagaState::Completed);
+        assert_eq!(saved.steps[0].attempt_count, 1);
+        assert_eq!(saved.steps[1].attempt_count, 2);
+        assert!(saved.steps.iter().all(|s| s.status == SagaStepStatus::Completed));
+        
+        // Nothing is left to recover
+        let orchestrator = SagaOrchestrator::new(Arc::new(InMemoryEventStore::default()), saga_store);
+        assert!(orchestrator.recover_sagas().await.unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_recovery_finishes_interrupted_compensation() {
+        let saga_store = Arc::new(InMemorySagaStore::new());
+        let mut execution = interrupted_saga();
+        execution.state = SagaState::Compensating;
+        execution.steps[1].status = SagaStepStatus::Failed;
+        execution.steps[0].status = SagaStepStatus::Compensating;
+        saga_store.save_saga(&execution).await.unwrap();
+        
+        let orchestrator = SagaOrchestrator::new(Arc::new(InMemoryEventStore::default()), saga_store.clone());
+        let executor = Arc::new(RecordingExecutor::default());
+        orchestrator.register_step_executor("orders", executor.clone()).await;
+        orchestrator.recover_sagas().await.unwrap();
+        
+        assert!(executor.executed.lock().unwrap().is_empty());
+        assert_eq!(*executor.compensated.lock().unwrap(), ["reserve"]);
+        let saved = saga_store.get_saga(execution.saga_id).await.unwrap().unwrap();
+        assert_eq!(saved.state, SagaState::Failed);
+        assert_eq!(saved.steps[0].status, SagaStepStatus::Compensated);
+    }
+    
+    #[test]
+    fn test_saga_record_round_trip() {
+        let execution = interrupted_saga();
+        let record = SagaRecord::try_from(&execution).unwrap();
+        assert_eq!(record.state, "running");
+        assert_eq!(record.total_timeout_seconds, Some(600));
+        
+        let restored = SagaExecution::try_from(record).unwrap();
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&execution).unwrap()
+        );
+        
+        let mut corrupt = SagaRecord::try_from(&execution).unwrap();
+        corrupt.state = "paused".to_string();
+        assert!(matches!(
+            SagaExecution::try_from(corrupt),
+            Err(EventError::SerializationError { .. })
+        ));
+    }
+}
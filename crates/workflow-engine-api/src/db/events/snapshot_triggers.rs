@@ -10,6 +10,7 @@ use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tracing::{info, warn, error, debug};
 use uuid::Uuid;
+use workflow_engine_core::workflow::scheduler::{Clock, SystemClock};
 
 use super::{
     EventStore, EventEnvelope, EventError, EventResult,
@@ -37,8 +38,8 @@ pub enum SnapshotTrigger {
 pub struct SnapshotTriggerConfig {
     /// Event count trigger (snapshot every N events)
     pub event_count_threshold: i64,
-    /// Time trigger (snapshot every N hours)
-    pub time_threshold_hours: i64,
+    /// Time trigger (snapshot every N seconds, if new events arrived)
+    pub time_threshold_seconds: i64,
     /// Memory usage trigger (snapshot when usage > N%)
     pub memory_threshold_percent: f64,
     /// Aggregate size trigger (snapshot when JSON size > N bytes)
@@ -53,7 +54,7 @@ impl Default for SnapshotTriggerConfig {
     fn default() -> Self {
         Self {
             event_count_threshold: 100,
-            time_threshold_hours: 24,
+            time_threshold_seconds: 24 * 60 * 60,
             memory_threshold_percent: 80.0,
             aggregate_size_threshold: 1024 * 1024, // 1MB
             auto_triggers_enabled: true,
@@ -108,13 +109,33 @@ pub trait Snapshottable: Send + Sync {
     fn last_updated(&self) -> DateTime<Utc>;
 }
 
-/// Automatic snapshot trigger manager
+/// Where the snapshot history of an aggregate stands
+#[derive(Debug, Clone, Copy)]
+struct SnapshotMark {
+    /// Version of the latest snapshot, 0 if there is none
+    version: i64,
+    /// When the latest snapshot was taken
+    taken_at: Option<DateTime<Utc>>,
+    /// Start of the current time window: the latest snapshot, or when the
+    /// aggregate was first seen
+    since: DateTime<Utc>,
+}
+
+/// Automatic snapshot trigger manager.
+///
+/// The event count and time triggers are measured from the latest snapshot
+/// of each aggregate: a snapshot is due once `event_count_threshold` events
+/// were appended since, or once `time_threshold_seconds` passed with at least
+/// one new event, whichever comes first. Call
+/// [`after_append`](Self::after_append) after appending events to an
+/// aggregate to create due snapshots automatically.
 pub struct SnapshotTriggerManager {
     snapshot_manager: EnhancedSnapshotManager,
     config: SnapshotTriggerConfig,
     trigger_history: Arc<RwLock<Vec<TriggerEvent>>>,
-    aggregate_last_snapshot: Arc<RwLock<HashMap<Uuid, DateTime<Utc>>>>,
+    aggregate_last_snapshot: Arc<RwLock<HashMap<Uuid, SnapshotMark>>>,
     statistics: Arc<RwLock<TriggerStatistics>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl SnapshotTriggerManager {
@@ -137,9 +158,51 @@ impl SnapshotTriggerManager {
             trigger_history: Arc::new(RwLock::new(Vec::new())),
             aggregate_last_snapshot: Arc::new(RwLock::new(HashMap::new())),
             statistics: Arc::new(RwLock::new(statistics)),
+            clock: Arc::new(SystemClock),
         }
     }
     
+    /// Use `clock` to measure time triggers
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Evaluate the triggers after events were appended to `aggregate`, and
+    /// create a snapshot if one is due. At most one snapshot is created, even
+    /// if several triggers fire.
+    pub async fn after_append<T: Snapshottable + ?Sized>(
+        &self,
+        aggregate: &T,
+    ) -> EventResult<Option<TriggerEvent>> {
+        let Some(trigger) = self.check_triggers(aggregate).await?.into_iter().next() else {
+            return Ok(None);
+        };
+        self.execute_single_trigger(aggregate, trigger).await.map(Some)
+    }
+    
+    /// The snapshot mark for an aggregate, loading the latest stored
+    /// snapshot the first time the aggregate is seen
+    async fn snapshot_mark(&self, aggregate_id: Uuid) -> EventResult<SnapshotMark> {
+        if let Some(mark) = self.aggregate_last_snapshot.read().await.get(&aggregate_id) {
+            return Ok(*mark);
+        }
+        
+        let mark = match self.snapshot_manager.restore_snapshot(aggregate_id).await? {
+            Some(snapshot) => SnapshotMark {
+                version: snapshot.aggregate_version,
+                taken_at: Some(snapshot.created_at),
+                since: snapshot.created_at,
+            },
+            None => SnapshotMark {
+                version: 0,
+                taken_at: None,
+                since: self.clock.now(),
+            },
+        };
+        Ok(*self.aggregate_last_snapshot.write().await.entry(aggregate_id).or_insert(mark))
+    }
+    
     /// Check if any triggers should fire for the given aggregate
     pub async fn check_triggers<T: Snapshottable + ?Sized>(
         &self,
@@ -151,15 +214,12 @@ impl SnapshotTriggerManager {
         
         let mut triggers = Vec::new();
         let aggregate_id = aggregate.aggregate_id();
+        let mark = self.snapshot_mark(aggregate_id).await?;
+        let now = self.clock.now();
         
         // Check if minimum interval has passed
-        let last_snapshot_time = {
-            let last_snapshots = self.aggregate_last_snapshot.read().await;
-            last_snapshots.get(&aggregate_id).copied()
-        };
-        
-        if let Some(last_time) = last_snapshot_time {
-            let minutes_since_last = (Utc::now() - last_time).num_minutes();
+        if let Some(last_time) = mark.taken_at {
+            let minutes_since_last = (now - last_time).num_minutes();
             if minutes_since_last < self.config.min_snapshot_interval_minutes {
                 debug!(
                     "Skipping triggers for aggregate {}: only {} minutes since last snapshot",
@@ -170,22 +230,15 @@ impl SnapshotTriggerManager {
         }
         
         // Check event count trigger
-        if let Some(last_snapshot) = self.snapshot_manager.restore_snapshot(aggregate_id).await? {
-            let events_since_snapshot = aggregate.current_version() - last_snapshot.aggregate_version;
-            if events_since_snapshot >= self.config.event_count_threshold {
-                triggers.push(SnapshotTrigger::EventCount(events_since_snapshot));
-            }
-        } else if aggregate.current_version() >= self.config.event_count_threshold {
-            // No snapshot exists and we have enough events
-            triggers.push(SnapshotTrigger::EventCount(aggregate.current_version()));
+        let events_since_snapshot = aggregate.current_version() - mark.version;
+        if events_since_snapshot >= self.config.event_count_threshold {
+            triggers.push(SnapshotTrigger::EventCount(events_since_snapshot));
         }
         
-        // Check time trigger
-        let hours_since_update = (Utc::now() - aggregate.last_updated()).num_hours();
-        if hours_since_update >= self.config.time_threshold_hours {
-            triggers.push(SnapshotTrigger::TimeElapsed(
-                chrono::Duration::hours(hours_since_update)
-            ));
+        // Check time trigger, which only fires if there is something new to snapshot
+        let elapsed = now - mark.since;
+        if events_since_snapshot > 0 && elapsed.num_seconds() >= self.config.time_threshold_seconds {
+            triggers.push(SnapshotTrigger::TimeElapsed(elapsed));
         }
         
         // Check aggregate size trigger
@@ -238,7 +291,7 @@ impl SnapshotTriggerManager {
             trigger_id,
             aggregate_id,
             trigger_type: trigger.clone(),
-            triggered_at: Utc::now(),
+            triggered_at: self.clock.now(),
             snapshot_created: false,
             snapshot_id: None,
             error: None,
@@ -251,7 +304,7 @@ impl SnapshotTriggerManager {
                 trigger_event.metrics.insert("event_count".to_string(), *count as f64);
             }
             SnapshotTrigger::TimeElapsed(duration) => {
-                trigger_event.metrics.insert("hours_elapsed".to_string(), duration.num_hours() as f64);
+                trigger_event.metrics.insert("seconds_elapsed".to_string(), duration.num_seconds() as f64);
             }
             SnapshotTrigger::MemoryThreshold(usage) => {
                 trigger_event.metrics.insert("memory_usage_percent".to_string(), *usage);
@@ -277,9 +330,14 @@ impl SnapshotTriggerManager {
                         trigger_event.snapshot_created = true;
                         trigger_event.snapshot_id = Some(snapshot.id);
                         
-                        // Update last snapshot time
+                        // Restart the triggers from this snapshot
+                        let now = self.clock.now();
                         let mut last_snapshots = self.aggregate_last_snapshot.write().await;
-                        last_snapshots.insert(aggregate_id, Utc::now());
+                        last_snapshots.insert(aggregate_id, SnapshotMark {
+                            version: snapshot.aggregate_version,
+                            taken_at: Some(now),
+                            since: now,
+                        });
                         
                         info!(
                             "Successfully created snapshot {} for aggregate {} (trigger: {:?})",
@@ -523,6 +581,7 @@ impl Clone for SnapshotTriggerManager {
             trigger_history: self.trigger_history.clone(),
             aggregate_last_snapshot: self.aggregate_last_snapshot.clone(),
             statistics: self.statistics.clone(),
+            clock: self.clock.clone(),
         }
     }
 }
@@ -530,6 +589,7 @@ impl Clone for SnapshotTriggerManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::events::tests::InMemoryEventStore;
     use serde_json::json;
     
     struct TestAggregate {
@@ -575,7 +635,7 @@ mod tests {
     fn test_trigger_config_defaults() {
         let config = SnapshotTriggerConfig::default();
         assert_eq!(config.event_count_threshold, 100);
-        assert_eq!(config.time_threshold_hours, 24);
+        assert_eq!(config.time_threshold_seconds, 24 * 60 * 60);
         assert_eq!(config.memory_threshold_percent, 80.0);
         assert_eq!(config.aggregate_size_threshold, 1024 * 1024);
         assert!(config.auto_triggers_enabled);
@@ -617,4 +677,102 @@ mod tests {
         assert_eq!(snapshot_data["version"], 42);
         assert_eq!(snapshot_data["state"], "test_state");
     }
-}
\ No newline at end of file
+    
+    #[derive(Debug)]
+    struct FakeClock(std::sync::Mutex<DateTime<Utc>>);
+    
+    impl FakeClock {
+        fn advance(&self, seconds: i64) {
+            *self.0.lock().unwrap() += chrono::Duration::seconds(seconds);
+        }
+    }
+    
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+    
+    fn trigger_manager(
+        store: Arc<InMemoryEventStore>,
+        event_count_threshold: i64,
+        time_threshold_seconds: i64,
+    ) -> SnapshotTriggerManager {
+        let config = SnapshotTriggerConfig {
+            event_count_threshold,
+            time_threshold_seconds,
+            min_snapshot_interval_minutes: 0,
+            ..SnapshotTriggerConfig::default()
+        };
+        SnapshotTriggerManager::new(EnhancedSnapshotManager::new(store, SnapshotConfig::default()), config)
+    }
+    
+    fn aggregate_at(id: Uuid, version: i64) -> TestAggregate {
+        TestAggregate {
+            id,
+            version,
+            size_bytes: 256,
+            last_updated: Utc::now(),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_snapshots_every_n_events() {
+        let store = Arc::new(InMemoryEventStore::default());
+        let manager = trigger_manager(store.clone(), 5, 3600);
+        let id = Uuid::new_v4();
+        
+        let mut snapshot_versions = Vec::new();
+        for version in 1..=12 {
+            if let Some(event) = manager.after_append(&aggregate_at(id, version)).await.unwrap() {
+                assert!(event.snapshot_created);
+                assert_eq!(event.trigger_type, SnapshotTrigger::EventCount(5));
+                snapshot_versions.push(version);
+            }
+        }
+        
+        assert_eq!(snapshot_versions, [5, 10]);
+        assert_eq!(store.get_snapshot(id).await.unwrap().unwrap().aggregate_version, 10);
+        assert_eq!(manager.get_statistics().await.successful_snapshots, 2);
+    }
+    
+    #[tokio::test]
+    async fn test_picks_up_from_stored_snapshot() {
+        let store = Arc::new(InMemoryEventStore::default());
+        let id = Uuid::new_v4();
+        trigger_manager(store.clone(), 5, 3600)
+            .after_append(&aggregate_at(id, 5))
+            .await
+            .unwrap()
+            .unwrap();
+        
+        // A new manager counts from the stored snapshot at version 5
+        let manager = trigger_manager(store, 5, 3600);
+        assert!(manager.after_append(&aggregate_at(id, 9)).await.unwrap().is_none());
+        assert!(manager.after_append(&aggregate_at(id, 10)).await.unwrap().is_some());
+    }
+    
+    #[tokio::test]
+    async fn test_snapshots_after_time_threshold() {
+        let store = Arc::new(InMemoryEventStore::default());
+        let clock = Arc::new(FakeClock(std::sync::Mutex::new(Utc::now())));
+        let manager = trigger_manager(store.clone(), 100, 60).with_clock(clock.clone());
+        let id = Uuid::new_v4();
+        
+        assert!(manager.after_append(&aggregate_at(id, 1)).await.unwrap().is_none());
+        clock.advance(30);
+        assert!(manager.after_append(&aggregate_at(id, 2)).await.unwrap().is_none());
+        
+        clock.advance(31);
+        let event = manager.after_append(&aggregate_at(id, 3)).await.unwrap().unwrap();
+        assert_eq!(event.trigger_type, SnapshotTrigger::TimeElapsed(chrono::Duration::seconds(61)));
+        assert_eq!(event.metrics["seconds_elapsed"], 61.0);
+        assert_eq!(store.get_snapshot(id).await.unwrap().unwrap().aggregate_version, 3);
+        
+        // Time alone does not trigger a snapshot without new events
+        clock.advance(120);
+        assert!(manager.after_append(&aggregate_at(id, 3)).await.unwrap().is_none());
+        let event = manager.after_append(&aggregate_at(id, 4)).await.unwrap().unwrap();
+        assert_eq!(event.trigger_type, SnapshotTrigger::TimeElapsed(chrono::Duration::seconds(120)));
+    }
+}
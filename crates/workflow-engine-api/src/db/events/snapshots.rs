@@ -221,7 +221,53 @@ impl SnapshotCompressor for Lz4Compressor {
     }
 }
 
-/// Manager for enhanced snapshot operations
+/// Zstandard compressor implementation
+#[derive(Debug, Clone, Copy)]
+pub struct ZstdCompressor {
+    /// Compression level, from 1 (fastest) to 22 (smallest)
+    pub level: i32,
+}
+
+impl Default for ZstdCompressor {
+    fn default() -> Self {
+        Self { level: zstd::DEFAULT_COMPRESSION_LEVEL }
+    }
+}
+
+#[async_trait]
+impl SnapshotCompressor for ZstdCompressor {
+    async fn compress(&self, data: &[u8]) -> EventResult<Vec<u8>> {
+        zstd::encode_all(data, self.level).map_err(|e| EventError::SerializationError {
+            message: format!("Zstd compression failed: {}", e),
+        })
+    }
+    
+    async fn decompress(&self, compressed_data: &[u8]) -> EventResult<Vec<u8>> {
+        zstd::decode_all(compressed_data).map_err(|e| EventError::SerializationError {
+            message: format!("Zstd decompression failed: {}", e),
+        })
+    }
+    
+    fn compression_type(&self) -> CompressionType {
+        CompressionType::Zstd
+    }
+}
+
+/// Compressors for every supported compression type
+fn default_compressors() -> HashMap<CompressionType, Box<dyn SnapshotCompressor>> {
+    let mut compressors: HashMap<CompressionType, Box<dyn SnapshotCompressor>> = HashMap::new();
+    compressors.insert(CompressionType::Gzip, Box::new(GzipCompressor));
+    compressors.insert(CompressionType::Lz4, Box::new(Lz4Compressor));
+    compressors.insert(CompressionType::Zstd, Box::new(ZstdCompressor::default()));
+    compressors
+}
+
+/// Manager for enhanced snapshot operations.
+///
+/// New snapshots are compressed with the codec selected by
+/// [`SnapshotConfig::compression_type`]. Each stored snapshot records the
+/// codec that compressed it, so snapshots written under an earlier
+/// configuration are still restored with the right codec.
 pub struct EnhancedSnapshotManager {
     event_store: Arc<dyn EventStore>,
     config: SnapshotConfig,
@@ -231,14 +277,10 @@ pub struct EnhancedSnapshotManager {
 
 impl Clone for EnhancedSnapshotManager {
     fn clone(&self) -> Self {
-        let mut compressors: HashMap<CompressionType, Box<dyn SnapshotCompressor>> = HashMap::new();
-        compressors.insert(CompressionType::Gzip, Box::new(GzipCompressor));
-        compressors.insert(CompressionType::Lz4, Box::new(Lz4Compressor));
-        
         Self {
             event_store: Arc::clone(&self.event_store),
             config: self.config.clone(),
-            compressors,
+            compressors: default_compressors(),
             statistics: Arc::clone(&self.statistics),
         }
     }
@@ -246,10 +288,6 @@ impl Clone for EnhancedSnapshotManager {
 
 impl EnhancedSnapshotManager {
     pub fn new(event_store: Arc<dyn EventStore>, config: SnapshotConfig) -> Self {
-        let mut compressors: HashMap<CompressionType, Box<dyn SnapshotCompressor>> = HashMap::new();
-        compressors.insert(CompressionType::Gzip, Box::new(GzipCompressor));
-        compressors.insert(CompressionType::Lz4, Box::new(Lz4Compressor));
-        
         let statistics = SnapshotStatistics {
             total_snapshots: 0,
             compressed_snapshots: 0,
@@ -264,7 +302,7 @@ impl EnhancedSnapshotManager {
         Self {
             event_store,
             config,
-            compressors,
+            compressors: default_compressors(),
             statistics: Arc::new(RwLock::new(statistics)),
         }
     }
@@ -587,6 +625,7 @@ impl EnhancedSnapshotManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::events::tests::InMemoryEventStore;
     use serde_json::json;
     
     #[test]
@@ -650,4 +689,73 @@ mod tests {
         let decompressed = compressor.decompress(&compressed).await.unwrap();
         assert_eq!(decompressed, test_data);
     }
-}
\ No newline at end of file
+    
+    /// A few hundred order lines, repetitive the way aggregate state usually is
+    fn large_snapshot_data() -> serde_json::Value {
+        let lines: Vec<_> = (0..500)
+            .map(|i| json!({
+                "line": i,
+                "sku": format!("SKU-{:05}", i % 37),
+                "quantity": i % 7 + 1,
+                "status": if i % 3 == 0 { "shipped" } else { "pending" },
+                "warehouse": "eu-west-1",
+            }))
+            .collect();
+        json!({ "order_id": "ORD-1", "customer": "acme", "lines": lines })
+    }
+    
+    fn manager(store: Arc<InMemoryEventStore>, compression_type: CompressionType) -> EnhancedSnapshotManager {
+        EnhancedSnapshotManager::new(store, SnapshotConfig {
+            compression_type,
+            ..SnapshotConfig::default()
+        })
+    }
+    
+    #[tokio::test]
+    async fn test_each_codec_round_trips_snapshots() {
+        let data = large_snapshot_data();
+        let mut sizes = HashMap::new();
+        
+        for codec in [CompressionType::None, CompressionType::Gzip, CompressionType::Lz4, CompressionType::Zstd] {
+            let store = Arc::new(InMemoryEventStore::default());
+            let aggregate_id = Uuid::new_v4();
+            let snapshot = manager(store.clone(), codec)
+                .create_snapshot(aggregate_id, "order".to_string(), 7, data.clone())
+                .await
+                .unwrap();
+            assert_eq!(snapshot.compression_type, codec);
+            sizes.insert(codec, snapshot.compressed_size);
+            
+            let stored = store.get_snapshot(aggregate_id).await.unwrap().unwrap();
+            assert_eq!(stored.metadata["compression_type"], json!(codec.to_string()));
+            
+            // The recorded codec is used, whatever the reader is configured with
+            let restored = manager(store, CompressionType::Gzip)
+                .restore_snapshot(aggregate_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(restored.snapshot_data, data, "{} round trip", codec);
+            assert_eq!(restored.aggregate_version, 7);
+        }
+        
+        assert_eq!(sizes[&CompressionType::None], data.to_string().len());
+        assert!(
+            sizes[&CompressionType::Zstd] < sizes[&CompressionType::Gzip],
+            "zstd {} bytes, gzip {} bytes",
+            sizes[&CompressionType::Zstd],
+            sizes[&CompressionType::Gzip]
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_zstd_compressor() {
+        let compressor = ZstdCompressor::default();
+        let test_data = large_snapshot_data().to_string().into_bytes();
+        
+        let compressed = compressor.compress(&test_data).await.unwrap();
+        assert!(compressed.len() < test_data.len());
+        assert_eq!(compressor.decompress(&compressed).await.unwrap(), test_data);
+        assert!(compressor.decompress(b"not zstd").await.is_err());
+    }
+}
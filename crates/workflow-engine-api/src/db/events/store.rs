@@ -12,6 +12,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::db::schema::{event_store, event_snapshots, event_dead_letter_queue, event_projections};
+use super::query::EventQuery;
 use super::{
     EventError, EventResult, EventEnvelope, EventMetadata, EventSourcingConfig,
     Event as EventTrait, EventSerializable
@@ -35,6 +36,62 @@ pub struct EventStoreStatistics {
     pub total_snapshots: u64,
 }
 
+/// Outcome of [`PostgreSQLEventStore::append_batch`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchAppendResult {
+    /// Ids of the events written, in batch order
+    pub appended: Vec<Uuid>,
+    /// Events rejected because of version conflicts
+    pub conflicts: Vec<BatchConflict>,
+}
+
+/// An event rejected from a batch because its aggregate had a version conflict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchConflict {
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    /// Version the event would have written
+    pub attempted_version: i64,
+    /// Latest stored version of the aggregate
+    pub current_version: i64,
+}
+
+/// Split a batch into the events that can be written and the conflicts.
+///
+/// Each event must have a higher version than the stored aggregate and than
+/// any earlier event for the same aggregate in the batch. If any event of an
+/// aggregate conflicts, all of that aggregate's events are rejected, so its
+/// history is never written partially.
+fn partition_batch<'a>(
+    events: &'a [EventEnvelope],
+    stored_versions: &HashMap<Uuid, i64>,
+) -> (Vec<&'a EventEnvelope>, Vec<BatchConflict>) {
+    let mut latest = stored_versions.clone();
+    let mut conflicted = std::collections::HashSet::new();
+    for event in events {
+        let version = latest.entry(event.aggregate_id).or_insert(0);
+        if event.aggregate_version <= *version {
+            conflicted.insert(event.aggregate_id);
+        } else {
+            *version = event.aggregate_version;
+        }
+    }
+
+    let (rejected, accepted): (Vec<_>, Vec<_>) = events
+        .iter()
+        .partition(|event| conflicted.contains(&event.aggregate_id));
+    let conflicts = rejected
+        .into_iter()
+        .map(|event| BatchConflict {
+            event_id: event.event_id,
+            aggregate_id: event.aggregate_id,
+            attempted_version: event.aggregate_version,
+            current_version: stored_versions.get(&event.aggregate_id).copied().unwrap_or(0),
+        })
+        .collect();
+    (accepted, conflicts)
+}
+
 impl Default for EventStoreConfig {
     fn default() -> Self {
         Self {
@@ -232,6 +289,91 @@ impl PostgreSQLEventStore {
         })
     }
 
+    /// Append many events in one transaction.
+    ///
+    /// Stored versions for every aggregate in the batch are read with one
+    /// query, and the accepted events are written with a multi-row INSERT
+    /// (split into statements of `batch_size` rows for very large batches).
+    /// Aggregates whose events conflict with stored versions are skipped and
+    /// reported in the result rather than failing the whole batch. If another
+    /// writer inserts a conflicting version concurrently, the transaction is
+    /// rolled back and a [`EventError::ConcurrencyError`] returned.
+    pub async fn append_batch(&self, events: Vec<EventEnvelope>) -> EventResult<BatchAppendResult> {
+        if events.is_empty() {
+            return Ok(BatchAppendResult::default());
+        }
+
+        let mut aggregate_ids: Vec<Uuid> = events.iter().map(|event| event.aggregate_id).collect();
+        aggregate_ids.sort_unstable();
+        aggregate_ids.dedup();
+
+        let mut conn = self.get_connection()?;
+        conn.transaction::<_, EventError, _>(|conn| {
+            let stored_versions: HashMap<Uuid, i64> = event_store::table
+                .filter(event_store::aggregate_id.eq_any(&aggregate_ids))
+                .group_by(event_store::aggregate_id)
+                .select((event_store::aggregate_id, diesel::dsl::max(event_store::aggregate_version)))
+                .load::<(Uuid, Option<i64>)>(conn)
+                .map_err(|e| EventError::DatabaseError {
+                    message: format!("Failed to get current versions: {}", e),
+                })?
+                .into_iter()
+                .map(|(aggregate_id, version)| (aggregate_id, version.unwrap_or(0)))
+                .collect();
+
+            let (accepted, conflicts) = partition_batch(&events, &stored_versions);
+            let records: Vec<EventStoreRecord> = accepted
+                .iter()
+                .map(|event| {
+                    let mut record = self.event_to_db_model(event);
+                    if self.config.enable_checksums {
+                        record.checksum = Some(calculate_checksum(&record.event_data, &record.metadata));
+                    }
+                    record
+                })
+                .collect();
+
+            for chunk in records.chunks(self.config.batch_size.max(1)) {
+                diesel::insert_into(event_store::table)
+                    .values(chunk)
+                    .execute(conn)
+                    .map_err(|e| match e {
+                        diesel::result::Error::DatabaseError(
+                            diesel::result::DatabaseErrorKind::UniqueViolation,
+                            info,
+                        ) => EventError::ConcurrencyError {
+                            message: format!("Concurrent write during batch append: {}", info.message()),
+                        },
+                        e => EventError::DatabaseError {
+                            message: format!("Failed to insert event batch: {}", e),
+                        },
+                    })?;
+            }
+
+            Ok(BatchAppendResult {
+                appended: accepted.iter().map(|event| event.event_id).collect(),
+                conflicts,
+            })
+        })
+    }
+
+    /// Load the events matching `query`
+    pub async fn query(&self, query: &EventQuery) -> EventResult<Vec<EventEnvelope>> {
+        let mut conn = self.get_connection()?;
+
+        let records: Vec<EventStoreRecord> = query
+            .to_diesel()
+            .load(&mut conn)
+            .map_err(|e| EventError::DatabaseError {
+                message: format!("Failed to query events: {}", e),
+            })?;
+
+        records
+            .into_iter()
+            .map(|record| self.db_model_to_event(record))
+            .collect()
+    }
+
     /// Get a database connection from the pool
     fn get_connection(&self) -> EventResult<diesel::r2d2::PooledConnection<ConnectionManager<PgConnection>>> {
         self.pool.get().map_err(|e| EventError::DatabaseError {
@@ -241,25 +383,13 @@ impl PostgreSQLEventStore {
 
     /// Convert EventEnvelope to database model
     fn event_to_db_model(&self, event: &EventEnvelope) -> EventStoreRecord {
-        EventStoreRecord {
-            id: event.event_id,
-            aggregate_id: event.aggregate_id,
-            aggregate_type: event.aggregate_type.clone(),
-            event_type: event.event_type.clone(),
-            aggregate_version: event.aggregate_version,
-            event_data: event.event_data.clone(),
-            metadata: serde_json::to_value(&event.metadata).unwrap_or_default(),
-            occurred_at: event.occurred_at,
-            recorded_at: event.recorded_at,
-            schema_version: event.schema_version,
-            causation_id: event.causation_id,
-            correlation_id: event.correlation_id,
-            checksum: event.checksum.clone(),
-        }
+        record_from_envelope(event)
     }
 
     /// Convert database model to EventEnvelope
     fn db_model_to_event(&self, record: EventStoreRecord) -> EventResult<EventEnvelope> {
+        verify_checksum(&record)?;
+
         let metadata: EventMetadata = serde_json::from_value(record.metadata)
             .map_err(|e| EventError::SerializationError {
                 message: format!("Failed to deserialize event metadata: {}", e),
@@ -281,17 +411,123 @@ impl PostgreSQLEventStore {
             checksum: record.checksum,
         })
     }
+}
+
+/// Append `events` on `conn`, inside whatever transaction the caller has
+/// open, so that they commit or roll back together with the caller's other
+/// writes. Events keep the versions they were given, which must continue
+/// each aggregate's stored version; otherwise, or if another writer stores
+/// the same version first, nothing is written and a
+/// [`EventError::ConcurrencyError`] is returned.
+pub(crate) fn append_on_connection(conn: &mut PgConnection, events: &[EventEnvelope]) -> EventResult<()> {
+    let mut aggregate_ids: Vec<Uuid> = events.iter().map(|event| event.aggregate_id).collect();
+    aggregate_ids.sort_unstable();
+    aggregate_ids.dedup();
+
+    let stored_versions: HashMap<Uuid, i64> = event_store::table
+        .filter(event_store::aggregate_id.eq_any(&aggregate_ids))
+        .group_by(event_store::aggregate_id)
+        .select((event_store::aggregate_id, diesel::dsl::max(event_store::aggregate_version)))
+        .load::<(Uuid, Option<i64>)>(conn)
+        .map_err(|e| EventError::DatabaseError {
+            message: format!("Failed to get current versions: {}", e),
+        })?
+        .into_iter()
+        .map(|(aggregate_id, version)| (aggregate_id, version.unwrap_or(0)))
+        .collect();
+    check_expected_versions(events, &stored_versions)?;
+
+    let records: Vec<EventStoreRecord> = events
+        .iter()
+        .map(|event| {
+            let mut record = record_from_envelope(event);
+            record.checksum = Some(calculate_checksum(&record.event_data, &record.metadata));
+            record
+        })
+        .collect();
+    diesel::insert_into(event_store::table)
+        .values(&records)
+        .execute(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, info) => {
+                EventError::ConcurrencyError {
+                    message: format!("Concurrent write to aggregate: {}", info.message()),
+                }
+            }
+            e => EventError::DatabaseError {
+                message: format!("Failed to insert events: {}", e),
+            },
+        })?;
+    Ok(())
+}
+
+/// Fail with [`EventError::ConcurrencyError`] unless each aggregate's events
+/// follow its stored version one by one, in the order given
+pub(crate) fn check_expected_versions(
+    events: &[EventEnvelope],
+    stored_versions: &HashMap<Uuid, i64>,
+) -> EventResult<()> {
+    let mut latest = stored_versions.clone();
+    for event in events {
+        let version = latest.entry(event.aggregate_id).or_insert(0);
+        if event.aggregate_version != *version + 1 {
+            return Err(EventError::ConcurrencyError {
+                message: format!(
+                    "Aggregate {} expected version {}, got {}",
+                    event.aggregate_id,
+                    *version + 1,
+                    event.aggregate_version
+                ),
+            });
+        }
+        *version = event.aggregate_version;
+    }
+    Ok(())
+}
 
-    /// Calculate checksum for event data integrity
-    fn calculate_checksum(&self, event_data: &Value, metadata: &Value) -> String {
-        use sha2::{Sha256, Digest};
-        let combined = format!("{}{}", event_data, metadata);
-        let mut hasher = Sha256::new();
-        hasher.update(combined.as_bytes());
-        format!("{:x}", hasher.finalize())
+fn record_from_envelope(event: &EventEnvelope) -> EventStoreRecord {
+    EventStoreRecord {
+        id: event.event_id,
+        aggregate_id: event.aggregate_id,
+        aggregate_type: event.aggregate_type.clone(),
+        event_type: event.event_type.clone(),
+        aggregate_version: event.aggregate_version,
+        event_data: event.event_data.clone(),
+        metadata: serde_json::to_value(&event.metadata).unwrap_or_default(),
+        occurred_at: event.occurred_at,
+        recorded_at: event.recorded_at,
+        schema_version: event.schema_version,
+        causation_id: event.causation_id,
+        correlation_id: event.correlation_id,
+        checksum: event.checksum.clone(),
     }
 }
 
+/// Calculate checksum for event data integrity
+fn calculate_checksum(event_data: &Value, metadata: &Value) -> String {
+    use sha2::{Sha256, Digest};
+    let combined = format!("{}{}", event_data, metadata);
+    let mut hasher = Sha256::new();
+    hasher.update(combined.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Check a loaded record against its stored checksum. Records stored
+/// without a checksum are accepted as they are.
+fn verify_checksum(record: &EventStoreRecord) -> EventResult<()> {
+    let Some(expected) = &record.checksum else {
+        return Ok(());
+    };
+    let actual = calculate_checksum(&record.event_data, &record.metadata);
+    if &actual != expected {
+        return Err(EventError::CorruptionError {
+            event_id: record.id,
+            message: format!("checksum mismatch (stored {}, computed {})", expected, actual),
+        });
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl EventStore for PostgreSQLEventStore {
     async fn append_event(&self, event: &EventEnvelope) -> EventResult<()> {
@@ -312,7 +548,7 @@ impl EventStore for PostgreSQLEventStore {
         
         // Calculate checksum if enabled
         if self.config.enable_checksums {
-            db_event.checksum = Some(self.calculate_checksum(&db_event.event_data, &db_event.metadata));
+            db_event.checksum = Some(calculate_checksum(&db_event.event_data, &db_event.metadata));
         }
 
         // Insert the event
@@ -359,7 +595,7 @@ impl EventStore for PostgreSQLEventStore {
                 
                 // Calculate checksum if enabled
                 if self.config.enable_checksums {
-                    db_event.checksum = Some(self.calculate_checksum(&db_event.event_data, &db_event.metadata));
+                    db_event.checksum = Some(calculate_checksum(&db_event.event_data, &db_event.metadata));
                 }
 
                 // Insert the event
@@ -839,4 +1075,125 @@ impl AggregateSnapshot {
             metadata: HashMap::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(aggregate_id: Uuid, version: i64) -> EventEnvelope {
+        EventEnvelope {
+            event_id: Uuid::new_v4(),
+            aggregate_id,
+            aggregate_type: "order".to_string(),
+            event_type: "order_updated".to_string(),
+            aggregate_version: version,
+            event_data: serde_json::json!({ "version": version }),
+            metadata: EventMetadata::new(),
+            occurred_at: Utc::now(),
+            recorded_at: Utc::now(),
+            schema_version: 1,
+            causation_id: None,
+            correlation_id: None,
+            checksum: None,
+        }
+    }
+
+    #[test]
+    fn test_partition_batch_accepts_new_versions() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let events = vec![event(a, 3), event(b, 1), event(a, 4), event(b, 2)];
+        let stored = HashMap::from([(a, 2)]);
+
+        let (accepted, conflicts) = partition_batch(&events, &stored);
+        assert_eq!(accepted.len(), 4);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_partition_batch_rejects_conflicting_aggregates() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let events = vec![
+            // a is already at version 5
+            event(a, 5),
+            event(a, 6),
+            event(b, 1),
+            // c repeats a version within the batch
+            event(c, 1),
+            event(c, 1),
+        ];
+        let stored = HashMap::from([(a, 5)]);
+
+        let (accepted, conflicts) = partition_batch(&events, &stored);
+        assert_eq!(accepted.iter().map(|e| e.event_id).collect::<Vec<_>>(), [events[2].event_id]);
+        assert_eq!(
+            conflicts.iter().map(|c| c.event_id).collect::<Vec<_>>(),
+            [events[0].event_id, events[1].event_id, events[3].event_id, events[4].event_id]
+        );
+        assert_eq!(conflicts[1].attempted_version, 6);
+        assert_eq!(conflicts[1].current_version, 5);
+        assert_eq!(conflicts[2].current_version, 0);
+    }
+
+    #[test]
+    fn test_check_expected_versions() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let stored = HashMap::from([(a, 2)]);
+        assert!(check_expected_versions(&[event(a, 3), event(b, 1), event(a, 4)], &stored).is_ok());
+
+        // Stale, skipped and repeated versions are conflicts, not renumbered
+        for events in [vec![event(a, 2)], vec![event(a, 4)], vec![event(b, 1), event(b, 1)]] {
+            match check_expected_versions(&events, &stored) {
+                Err(EventError::ConcurrencyError { .. }) => {}
+                other => panic!("Expected ConcurrencyError, got {:?}", other),
+            }
+        }
+    }
+
+    fn checksummed_record() -> EventStoreRecord {
+        let event = event(Uuid::new_v4(), 1);
+        let event_data = event.event_data.clone();
+        let metadata = serde_json::to_value(&event.metadata).unwrap();
+        EventStoreRecord {
+            id: event.event_id,
+            aggregate_id: event.aggregate_id,
+            aggregate_type: event.aggregate_type,
+            event_type: event.event_type,
+            aggregate_version: event.aggregate_version,
+            checksum: Some(calculate_checksum(&event_data, &metadata)),
+            event_data,
+            metadata,
+            occurred_at: event.occurred_at,
+            recorded_at: event.recorded_at,
+            schema_version: event.schema_version,
+            causation_id: None,
+            correlation_id: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_checksum_accepts_intact_records() {
+        let mut record = checksummed_record();
+        assert!(verify_checksum(&record).is_ok());
+
+        record.checksum = None;
+        assert!(verify_checksum(&record).is_ok());
+    }
+
+    #[test]
+    fn test_verify_checksum_detects_tampering() {
+        let mut record = checksummed_record();
+        record.event_data = serde_json::json!({ "version": 99 });
+        match verify_checksum(&record) {
+            Err(EventError::CorruptionError { event_id, message }) => {
+                assert_eq!(event_id, record.id);
+                assert!(message.contains("checksum mismatch"));
+            }
+            other => panic!("Expected CorruptionError, got {:?}", other),
+        }
+
+        let mut record = checksummed_record();
+        record.metadata["source"] = serde_json::json!("tampered");
+        assert!(matches!(verify_checksum(&record), Err(EventError::CorruptionError { .. })));
+    }
+}
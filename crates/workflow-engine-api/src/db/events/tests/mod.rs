@@ -5,4 +5,182 @@
 pub mod cross_service_routing_tests;
 pub mod integration_tests;
 
-use super::*;
\ No newline at end of file
+use super::*;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Event store kept in memory, for tests that need real reads
+#[derive(Default)]
+pub(crate) struct InMemoryEventStore {
+    events: Mutex<Vec<EventEnvelope>>,
+    snapshots: Mutex<HashMap<Uuid, AggregateSnapshot>>,
+    failing_appends: AtomicUsize,
+}
+
+impl InMemoryEventStore {
+    pub(crate) fn with_events(events: Vec<EventEnvelope>) -> Self {
+        Self {
+            events: Mutex::new(events),
+            snapshots: Mutex::default(),
+            failing_appends: AtomicUsize::new(0),
+        }
+    }
+
+    /// Make the next `count` appends fail with a database error
+    pub(crate) fn fail_next_appends(&self, count: usize) {
+        self.failing_appends.store(count, Ordering::SeqCst);
+    }
+
+    fn check_append(&self) -> EventResult<()> {
+        let failed = self
+            .failing_appends
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| left.checked_sub(1));
+        match failed {
+            Ok(_) => Err(EventError::DatabaseError {
+                message: "connection reset".to_string(),
+            }),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn select(&self, filter: impl Fn(&EventEnvelope) -> bool) -> Vec<EventEnvelope> {
+        let mut events: Vec<EventEnvelope> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| filter(event))
+            .cloned()
+            .collect();
+        events.sort_by_key(|event| (event.recorded_at, event.event_id));
+        events
+    }
+}
+
+#[async_trait]
+impl EventStore for InMemoryEventStore {
+    async fn append_event(&self, event: &EventEnvelope) -> EventResult<()> {
+        self.check_append()?;
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+
+    async fn append_events(&self, events: &[EventEnvelope]) -> EventResult<()> {
+        self.check_append()?;
+        self.events.lock().unwrap().extend_from_slice(events);
+        Ok(())
+    }
+
+    async fn get_events(&self, aggregate_id: Uuid) -> EventResult<Vec<EventEnvelope>> {
+        Ok(self.select(|event| event.aggregate_id == aggregate_id))
+    }
+
+    async fn get_events_from_version(
+        &self,
+        aggregate_id: Uuid,
+        from_version: i64,
+    ) -> EventResult<Vec<EventEnvelope>> {
+        Ok(self.select(|event| event.aggregate_id == aggregate_id && event.aggregate_version > from_version))
+    }
+
+    async fn get_events_by_type(
+        &self,
+        event_type: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<usize>,
+    ) -> EventResult<Vec<EventEnvelope>> {
+        let mut events = self.select(|event| {
+            event.event_type == event_type
+                && from.is_none_or(|from| event.occurred_at >= from)
+                && to.is_none_or(|to| event.occurred_at <= to)
+        });
+        events.truncate(limit.unwrap_or(usize::MAX));
+        Ok(events)
+    }
+
+    async fn get_events_by_correlation_id(&self, correlation_id: Uuid) -> EventResult<Vec<EventEnvelope>> {
+        Ok(self.select(|event| event.correlation_id == Some(correlation_id)))
+    }
+
+    async fn get_aggregate_version(&self, aggregate_id: Uuid) -> EventResult<i64> {
+        Ok(self
+            .get_events(aggregate_id)
+            .await?
+            .iter()
+            .map(|event| event.aggregate_version)
+            .max()
+            .unwrap_or(0))
+    }
+
+    async fn aggregate_exists(&self, aggregate_id: Uuid) -> EventResult<bool> {
+        Ok(!self.get_events(aggregate_id).await?.is_empty())
+    }
+
+    async fn save_snapshot(&self, snapshot: &AggregateSnapshot) -> EventResult<()> {
+        self.snapshots.lock().unwrap().insert(snapshot.aggregate_id, snapshot.clone());
+        Ok(())
+    }
+
+    async fn get_snapshot(&self, aggregate_id: Uuid) -> EventResult<Option<AggregateSnapshot>> {
+        Ok(self.snapshots.lock().unwrap().get(&aggregate_id).cloned())
+    }
+
+    async fn get_events_from_position(&self, position: i64, limit: usize) -> EventResult<Vec<EventEnvelope>> {
+        self.replay_events(position, None, limit).await
+    }
+
+    async fn get_current_position(&self) -> EventResult<i64> {
+        Ok(self
+            .select(|_| true)
+            .last()
+            .map_or(0, |event| event.recorded_at.timestamp_millis()))
+    }
+
+    async fn replay_events(
+        &self,
+        from_position: i64,
+        event_types: Option<Vec<String>>,
+        batch_size: usize,
+    ) -> EventResult<Vec<EventEnvelope>> {
+        let mut events = self.select(|event| {
+            event.recorded_at.timestamp_millis() >= from_position
+                && event_types.as_ref().is_none_or(|types| types.contains(&event.event_type))
+        });
+        events.truncate(batch_size);
+        Ok(events)
+    }
+
+    async fn get_events_for_aggregates(&self, aggregate_ids: &[Uuid]) -> EventResult<Vec<EventEnvelope>> {
+        Ok(self.select(|event| aggregate_ids.contains(&event.aggregate_id)))
+    }
+
+    async fn cleanup_old_snapshots(&self, _keep_latest: usize) -> EventResult<usize> {
+        Ok(0)
+    }
+
+    async fn get_aggregate_ids_by_type(
+        &self,
+        aggregate_type: &str,
+        offset: i64,
+        limit: usize,
+    ) -> EventResult<Vec<Uuid>> {
+        let mut ids: Vec<Uuid> = self
+            .select(|event| event.aggregate_type == aggregate_type)
+            .iter()
+            .map(|event| event.aggregate_id)
+            .collect();
+        ids.dedup();
+        Ok(ids.into_iter().skip(offset as usize).take(limit).collect())
+    }
+
+    async fn optimize_storage(&self) -> EventResult<()> {
+        Ok(())
+    }
+}
+
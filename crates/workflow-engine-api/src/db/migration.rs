@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
+use include_dir::{Dir, include_dir};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -16,6 +17,24 @@ use uuid::Uuid;
 
 use crate::db::events::{EventError, EventResult};
 
+/// The repository's migration files, compiled into the binary so that the
+/// schema can be checked wherever the service runs
+static EMBEDDED_MIGRATIONS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/../../migrations");
+
+/// Migrations embedded at build time, in version order
+pub fn embedded_migrations() -> Vec<Migration> {
+    let mut migrations: Vec<Migration> = EMBEDDED_MIGRATIONS
+        .files()
+        .filter_map(|file| {
+            let filename = file.path().file_name()?.to_str()?;
+            let (version, name) = PostgreSQLMigrationManager::parse_migration_filename(filename)?;
+            Some(Migration::new(version, name, file.contents_utf8()?.to_string()))
+        })
+        .collect();
+    migrations.sort_by(|a, b| a.version.cmp(&b.version));
+    migrations
+}
+
 /// Migration metadata and tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Migration {
@@ -365,40 +384,7 @@ impl MigrationManager for PostgreSQLMigrationManager {
         let file_migrations = self.load_migrations(directory).await?;
         let applied_migrations = self.get_applied_migrations().await?;
 
-        let applied_map: HashMap<String, Migration> = 
-            applied_migrations.into_iter().map(|m| (m.version.clone(), m)).collect();
-
-        let mut results = Vec::new();
-
-        for file_migration in file_migrations {
-            if let Some(applied_migration) = applied_map.get(&file_migration.version) {
-                let status = if file_migration.checksum == applied_migration.checksum {
-                    MigrationStatus::Applied
-                } else {
-                    MigrationStatus::ChecksumMismatch
-                };
-
-                results.push(MigrationResult {
-                    migration: file_migration,
-                    status,
-                    execution_time_ms: applied_migration.execution_time_ms.unwrap_or(0),
-                    error: if status == MigrationStatus::ChecksumMismatch {
-                        Some("Checksum mismatch - migration file may have been modified".to_string())
-                    } else {
-                        None
-                    },
-                });
-            } else {
-                results.push(MigrationResult {
-                    migration: file_migration,
-                    status: MigrationStatus::Pending,
-                    execution_time_ms: 0,
-                    error: None,
-                });
-            }
-        }
-
-        Ok(results)
+        Ok(compare_migrations(file_migrations, applied_migrations))
     }
 
     async fn get_migration_status(&self, directory: &Path) -> EventResult<HashMap<String, MigrationStatus>> {
@@ -413,6 +399,90 @@ impl MigrationManager for PostgreSQLMigrationManager {
     }
 }
 
+/// Compare migration files against the migrations recorded as applied,
+/// marking each file as applied, pending or modified since it was applied
+pub fn compare_migrations(
+    file_migrations: Vec<Migration>,
+    applied_migrations: Vec<Migration>,
+) -> Vec<MigrationResult> {
+    let applied_map: HashMap<String, Migration> = 
+        applied_migrations.into_iter().map(|m| (m.version.clone(), m)).collect();
+
+    let mut results = Vec::new();
+
+    for file_migration in file_migrations {
+        if let Some(applied_migration) = applied_map.get(&file_migration.version) {
+            let status = if file_migration.checksum == applied_migration.checksum {
+                MigrationStatus::Applied
+            } else {
+                MigrationStatus::ChecksumMismatch
+            };
+
+            results.push(MigrationResult {
+                migration: file_migration,
+                status,
+                execution_time_ms: applied_migration.execution_time_ms.unwrap_or(0),
+                error: if status == MigrationStatus::ChecksumMismatch {
+                    Some("Checksum mismatch - migration file may have been modified".to_string())
+                } else {
+                    None
+                },
+            });
+        } else {
+            results.push(MigrationResult {
+                migration: file_migration,
+                status: MigrationStatus::Pending,
+                execution_time_ms: 0,
+                error: None,
+            });
+        }
+    }
+
+    results
+}
+
+/// Whether the database schema matches the migration files
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MigrationReport {
+    /// True when every migration is applied and unmodified
+    pub up_to_date: bool,
+    /// Version of the newest migration file
+    pub latest_version: Option<String>,
+    /// Version of the newest applied migration file
+    pub applied_version: Option<String>,
+    /// Versions not yet applied, oldest first
+    pub pending: Vec<String>,
+    /// Versions whose file changed after being applied
+    pub checksum_mismatches: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Summarize the results of [`compare_migrations`], given in version order
+    pub fn from_results(results: &[MigrationResult]) -> Self {
+        let versions = |status: MigrationStatus| -> Vec<String> {
+            results
+                .iter()
+                .filter(|result| result.status == status)
+                .map(|result| result.migration.version.clone())
+                .collect()
+        };
+        let pending = versions(MigrationStatus::Pending);
+        let checksum_mismatches = versions(MigrationStatus::ChecksumMismatch);
+
+        Self {
+            up_to_date: pending.is_empty() && checksum_mismatches.is_empty(),
+            latest_version: results.last().map(|result| result.migration.version.clone()),
+            applied_version: results
+                .iter()
+                .rev()
+                .find(|result| result.status != MigrationStatus::Pending)
+                .map(|result| result.migration.version.clone()),
+            pending,
+            checksum_mismatches,
+        }
+    }
+}
+
 /// Configuration for migration manager
 #[derive(Debug, Clone)]
 pub struct MigrationConfig {
@@ -435,11 +505,22 @@ impl Default for MigrationConfig {
 pub struct MigrationService {
     manager: Box<dyn MigrationManager>,
     config: MigrationConfig,
+    expected: Vec<Migration>,
 }
 
 impl MigrationService {
     pub fn new(manager: Box<dyn MigrationManager>, config: MigrationConfig) -> Self {
-        Self { manager, config }
+        Self {
+            manager,
+            config,
+            expected: embedded_migrations(),
+        }
+    }
+
+    /// Check the database against `migrations` instead of the embedded ones
+    pub fn with_expected_migrations(mut self, migrations: Vec<Migration>) -> Self {
+        self.expected = migrations;
+        self
     }
 
     /// Initialize migrations on application startup
@@ -500,13 +581,105 @@ impl MigrationService {
             .get_migration_status(&self.config.migration_directory)
             .await
     }
+
+    /// Check whether the database is at the latest embedded migration.
+    ///
+    /// Only reads the applied versions from the database, so it does not
+    /// depend on the migration directory being deployed.
+    pub async fn check(&self) -> EventResult<MigrationReport> {
+        let applied = self.manager.get_applied_migrations().await?;
+        let results = compare_migrations(self.expected.clone(), applied);
+        Ok(MigrationReport::from_results(&results))
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use std::sync::Mutex;
     use tempfile::TempDir;
 
+    /// Migration manager tracking applied migrations in memory
+    pub(crate) struct InMemoryMigrationManager {
+        files: Vec<Migration>,
+        applied: Mutex<Vec<Migration>>,
+    }
+
+    impl InMemoryMigrationManager {
+        /// Migration files `versions`, of which the first `applied` have run
+        pub(crate) fn new(versions: &[&str], applied: usize) -> Self {
+            let files: Vec<Migration> = versions
+                .iter()
+                .map(|version| {
+                    Migration::new(version.to_string(), "step".to_string(), format!("-- {}", version))
+                })
+                .collect();
+            let applied = files[..applied].to_vec();
+            Self { files, applied: Mutex::new(applied) }
+        }
+
+        /// A service expecting this manager's migration files
+        pub(crate) fn into_service(self, config: MigrationConfig) -> MigrationService {
+            let files = self.files.clone();
+            MigrationService::new(Box::new(self), config).with_expected_migrations(files)
+        }
+    }
+
+    #[async_trait]
+    impl MigrationManager for InMemoryMigrationManager {
+        async fn load_migrations(&self, _directory: &Path) -> EventResult<Vec<Migration>> {
+            Ok(self.files.clone())
+        }
+
+        async fn get_applied_migrations(&self) -> EventResult<Vec<Migration>> {
+            Ok(self.applied.lock().unwrap().clone())
+        }
+
+        async fn is_migration_applied(&self, version: &str) -> EventResult<bool> {
+            Ok(self.applied.lock().unwrap().iter().any(|m| m.version == version))
+        }
+
+        async fn apply_migration(&self, migration: &Migration) -> EventResult<MigrationResult> {
+            self.applied.lock().unwrap().push(migration.clone());
+            Ok(MigrationResult {
+                migration: migration.clone(),
+                status: MigrationStatus::Applied,
+                execution_time_ms: 0,
+                error: None,
+            })
+        }
+
+        async fn apply_pending_migrations(&self, directory: &Path) -> EventResult<Vec<MigrationResult>> {
+            let mut results = Vec::new();
+            for migration in self.load_migrations(directory).await? {
+                if !self.is_migration_applied(&migration.version).await? {
+                    results.push(self.apply_migration(&migration).await?);
+                }
+            }
+            Ok(results)
+        }
+
+        async fn rollback_migration(&self, _version: &str) -> EventResult<MigrationResult> {
+            Err(EventError::ConfigurationError {
+                message: "Rollback not supported".to_string(),
+            })
+        }
+
+        async fn validate_migration_checksums(&self, directory: &Path) -> EventResult<Vec<MigrationResult>> {
+            let files = self.load_migrations(directory).await?;
+            Ok(compare_migrations(files, self.get_applied_migrations().await?))
+        }
+
+        async fn get_migration_status(&self, directory: &Path) -> EventResult<HashMap<String, MigrationStatus>> {
+            Ok(self
+                .validate_migration_checksums(directory)
+                .await?
+                .into_iter()
+                .map(|result| (result.migration.version, result.status))
+                .collect())
+        }
+    }
+
     #[test]
     fn test_parse_migration_filename() {
         assert_eq!(
@@ -541,4 +714,59 @@ mod tests {
         assert!(!migration.checksum.is_empty());
         assert_eq!(migration.checksum.len(), 64); // SHA256 hex string length
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_check_reports_pending_migrations() {
+        let config = MigrationConfig {
+            auto_apply: true,
+            ..MigrationConfig::default()
+        };
+        let service = InMemoryMigrationManager::new(&["20240101_000001", "20240201_000001", "20240301_000001"], 1)
+            .into_service(config);
+
+        let report = service.check().await.unwrap();
+        assert!(!report.up_to_date);
+        assert_eq!(report.latest_version.as_deref(), Some("20240301_000001"));
+        assert_eq!(report.applied_version.as_deref(), Some("20240101_000001"));
+        assert_eq!(report.pending, ["20240201_000001", "20240301_000001"]);
+
+        service.initialize().await.unwrap();
+        let report = service.check().await.unwrap();
+        assert!(report.up_to_date);
+        assert_eq!(report.applied_version, report.latest_version);
+        assert!(report.pending.is_empty());
+    }
+
+    #[test]
+    fn test_migrations_are_embedded() {
+        let versions: Vec<String> = embedded_migrations().into_iter().map(|m| m.version).collect();
+        assert!(versions.contains(&"20241213_000001".to_string()));
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn test_check_does_not_read_the_migration_directory() {
+        let config = MigrationConfig {
+            migration_directory: PathBuf::from("/nonexistent/migrations"),
+            ..MigrationConfig::default()
+        };
+        let manager = InMemoryMigrationManager::new(&[], 0);
+        *manager.applied.lock().unwrap() = embedded_migrations();
+        let service = MigrationService::new(Box::new(manager), config);
+
+        let report = service.check().await.unwrap();
+        assert!(report.up_to_date);
+        assert_eq!(report.latest_version, embedded_migrations().last().map(|m| m.version.clone()));
+    }
+
+    #[test]
+    fn test_modified_migration_is_not_up_to_date() {
+        let files = vec![Migration::new("1".to_string(), "a".to_string(), "CREATE TABLE a ();".to_string())];
+        let applied = vec![Migration::new("1".to_string(), "a".to_string(), "CREATE TABLE b ();".to_string())];
+
+        let report = MigrationReport::from_results(&compare_migrations(files, applied));
+        assert!(!report.up_to_date);
+        assert!(report.pending.is_empty());
+        assert_eq!(report.checksum_mismatches, ["1"]);
+    }
+}
@@ -12,3 +12,4 @@ pub mod session;
 pub mod user;
 pub mod tenant;
 pub mod connection_pool;
+pub mod pool_stats;
@@ -0,0 +1,252 @@
+//! Connection pool statistics
+//!
+//! r2d2 only reports how many connections a pool holds and how many are idle.
+//! [`PoolStatsRecorder`] is an r2d2 event handler that also counts checkouts,
+//! waits and timeouts, and [`PoolMonitor`] combines both into [`PoolStats`]
+//! for the health endpoints and Prometheus metrics.
+
+use diesel::r2d2::event::{CheckoutEvent, TimeoutEvent};
+use diesel::r2d2::{HandleEvent, ManageConnection, Pool};
+use serde::Serialize;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::monitoring::metrics::{
+    DB_POOL_CHECKOUTS_TOTAL, DB_POOL_CONNECTIONS, DB_POOL_MAX_SIZE, DB_POOL_TIMEOUTS_TOTAL,
+    DB_POOL_WAITS_TOTAL,
+};
+
+/// Checkouts slower than this are counted as having waited for a connection
+pub const DEFAULT_WAIT_THRESHOLD: Duration = Duration::from_millis(10);
+
+/// How long a health check waits for a connection by default
+pub const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Default)]
+struct Counters {
+    checkouts: AtomicU64,
+    waits: AtomicU64,
+    wait_time_us: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+/// r2d2 event handler counting checkouts, waits and timeouts.
+///
+/// Install it with `Pool::builder().event_handler(Box::new(recorder.clone()))`
+/// and keep a clone to read the counts. A checkout counts as a wait when it
+/// takes longer than the wait threshold; checkout time includes r2d2's
+/// connection test, so the threshold should sit above a normal round trip.
+#[derive(Debug, Clone)]
+pub struct PoolStatsRecorder {
+    counters: Arc<Counters>,
+    wait_threshold: Duration,
+}
+
+impl Default for PoolStatsRecorder {
+    fn default() -> Self {
+        Self {
+            counters: Arc::default(),
+            wait_threshold: DEFAULT_WAIT_THRESHOLD,
+        }
+    }
+}
+
+impl PoolStatsRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_wait_threshold(mut self, threshold: Duration) -> Self {
+        self.wait_threshold = threshold;
+        self
+    }
+}
+
+impl HandleEvent for PoolStatsRecorder {
+    fn handle_checkout(&self, event: CheckoutEvent) {
+        self.counters.checkouts.fetch_add(1, Ordering::Relaxed);
+        DB_POOL_CHECKOUTS_TOTAL.inc();
+        if event.duration() > self.wait_threshold {
+            self.counters.waits.fetch_add(1, Ordering::Relaxed);
+            self.counters
+                .wait_time_us
+                .fetch_add(event.duration().as_micros() as u64, Ordering::Relaxed);
+            DB_POOL_WAITS_TOTAL.inc();
+        }
+    }
+
+    fn handle_timeout(&self, _event: TimeoutEvent) {
+        self.counters.timeouts.fetch_add(1, Ordering::Relaxed);
+        DB_POOL_TIMEOUTS_TOTAL.inc();
+    }
+}
+
+/// Snapshot of a connection pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PoolStats {
+    /// Maximum number of connections
+    pub max_size: u32,
+    /// Connections currently open
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    pub checkouts: u64,
+    /// Checkouts that had to wait for a connection
+    pub wait_count: u64,
+    /// Total time spent in those waits
+    pub wait_time_ms: u64,
+    /// Checkouts that gave up waiting
+    pub timeouts: u64,
+}
+
+/// Object-safe view of an r2d2 pool, whatever its connection manager
+trait PoolProbe: Send + Sync {
+    fn state(&self) -> (u32, u32, u32);
+    fn acquire(&self, timeout: Duration) -> Result<(), String>;
+}
+
+impl<M: ManageConnection> PoolProbe for Pool<M> {
+    fn state(&self) -> (u32, u32, u32) {
+        let state = Pool::state(self);
+        (self.max_size(), state.connections, state.idle_connections)
+    }
+
+    fn acquire(&self, timeout: Duration) -> Result<(), String> {
+        self.get_timeout(timeout).map(drop).map_err(|e| e.to_string())
+    }
+}
+
+/// Reports statistics for a pool and checks it can hand out connections
+#[derive(Clone)]
+pub struct PoolMonitor {
+    pool: Arc<dyn PoolProbe>,
+    recorder: PoolStatsRecorder,
+    acquire_timeout: Duration,
+}
+
+impl PoolMonitor {
+    /// Monitor `pool`, whose event handler should be `recorder`
+    pub fn new<M: ManageConnection>(pool: Pool<M>, recorder: PoolStatsRecorder) -> Self {
+        Self {
+            pool: Arc::new(pool),
+            recorder,
+            acquire_timeout: DEFAULT_ACQUIRE_TIMEOUT,
+        }
+    }
+
+    /// How long [`check`](Self::check) waits for a connection
+    pub fn with_acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.acquire_timeout = timeout;
+        self
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        let (max_size, size, idle) = self.pool.state();
+        let counters = &self.recorder.counters;
+        PoolStats {
+            max_size,
+            size,
+            idle,
+            in_use: size.saturating_sub(idle),
+            checkouts: counters.checkouts.load(Ordering::Relaxed),
+            wait_count: counters.waits.load(Ordering::Relaxed),
+            wait_time_ms: counters.wait_time_us.load(Ordering::Relaxed) / 1000,
+            timeouts: counters.timeouts.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Check out a connection, failing if none is available within the
+    /// acquire timeout. Connections are tested on checkout unless the pool
+    /// disables it. Blocks for up to the timeout.
+    pub fn check(&self) -> Result<PoolStats, String> {
+        self.pool.acquire(self.acquire_timeout)?;
+        Ok(self.stats())
+    }
+
+    /// Publish the current connection counts to the Prometheus gauges
+    pub fn update_metrics(&self) {
+        let stats = self.stats();
+        DB_POOL_MAX_SIZE.set(stats.max_size.into());
+        DB_POOL_CONNECTIONS.with_label_values(&["idle"]).set(stats.idle.into());
+        DB_POOL_CONNECTIONS.with_label_values(&["in_use"]).set(stats.in_use.into());
+    }
+}
+
+impl fmt::Debug for PoolMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolMonitor")
+            .field("stats", &self.stats())
+            .field("acquire_timeout", &self.acquire_timeout)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Connection manager whose connections need no database
+    #[derive(Debug)]
+    pub(crate) struct FakeManager;
+
+    impl ManageConnection for FakeManager {
+        type Connection = ();
+        type Error = std::io::Error;
+
+        fn connect(&self) -> Result<(), std::io::Error> {
+            Ok(())
+        }
+
+        fn is_valid(&self, _conn: &mut ()) -> Result<(), std::io::Error> {
+            Ok(())
+        }
+
+        fn has_broken(&self, _conn: &mut ()) -> bool {
+            false
+        }
+    }
+
+    /// Monitor over a pool of one fake connection
+    pub(crate) fn single_connection_pool() -> (Pool<FakeManager>, PoolMonitor) {
+        let recorder = PoolStatsRecorder::new();
+        let pool = Pool::builder()
+            .max_size(1)
+            .connection_timeout(Duration::from_millis(50))
+            .event_handler(Box::new(recorder.clone()))
+            .build(FakeManager)
+            .unwrap();
+        let monitor = PoolMonitor::new(pool.clone(), recorder)
+            .with_acquire_timeout(Duration::from_millis(50));
+        (pool, monitor)
+    }
+
+    #[test]
+    fn test_stats_track_checkouts_and_timeouts() {
+        let (pool, monitor) = single_connection_pool();
+        assert_eq!(monitor.check().unwrap().checkouts, 1);
+
+        let busy = pool.get().unwrap();
+        let stats = monitor.stats();
+        assert_eq!((stats.max_size, stats.size, stats.idle, stats.in_use), (1, 1, 0, 1));
+
+        assert!(monitor.check().is_err());
+        assert_eq!(monitor.stats().timeouts, 1);
+
+        // A checkout that waits for the busy connection to come back
+        let waiter = {
+            let pool = pool.clone();
+            std::thread::spawn(move || pool.get_timeout(Duration::from_secs(5)).map(drop))
+        };
+        std::thread::sleep(Duration::from_millis(30));
+        drop(busy);
+        waiter.join().unwrap().unwrap();
+
+        let stats = monitor.stats();
+        assert_eq!(stats.checkouts, 3);
+        assert_eq!(stats.wait_count, 1);
+        assert!(stats.wait_time_ms >= 20);
+        assert_eq!(stats.in_use, 0);
+    }
+}
@@ -109,6 +109,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    saga_executions (saga_id) {
+        saga_id -> Uuid,
+        saga_type -> Varchar,
+        state -> Varchar,
+        steps -> Json,
+        global_context -> Json,
+        compensation_strategy -> Json,
+        error_message -> Nullable<Text>,
+        total_timeout_seconds -> Nullable<Int8>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        completed_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
 
@@ -168,6 +186,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     event_subscriptions,
     event_dead_letter_queue,
     event_projections,
+    saga_executions,
     users,
     tenants,
     service_permissions,
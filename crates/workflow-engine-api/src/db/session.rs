@@ -1,12 +1,18 @@
 use diesel::{
     PgConnection,
-    r2d2::{ConnectionManager, Pool},
+    r2d2::{ConnectionManager, Pool, PoolError, PooledConnection},
 };
 use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use thiserror::Error;
 
+use super::pool_stats::PoolStatsRecorder;
+
 pub type DbPool = Pool<ConnectionManager<PgConnection>>;
 
+/// Comma-separated connection URLs of read replicas
+pub const REPLICA_URLS_VAR: &str = "DATABASE_REPLICA_URLS";
+
 #[derive(Error, Debug)]
 pub enum DatabaseError {
     #[error("Database URL not found: {0}")]
@@ -16,9 +22,15 @@ pub enum DatabaseError {
 }
 
 pub fn init_pool() -> Result<DbPool, DatabaseError> {
+    init_pool_with_stats(PoolStatsRecorder::new())
+}
+
+/// Like [`init_pool`], recording checkout statistics with `recorder`
+pub fn init_pool_with_stats(recorder: PoolStatsRecorder) -> Result<DbPool, DatabaseError> {
     let database_url = get_database_url()?;
     let manager = ConnectionManager::<PgConnection>::new(database_url);
     let pool = Pool::builder()
+        .event_handler(Box::new(recorder))
         .build(manager)
         .map_err(|e| DatabaseError::PoolCreationError(format!("Pool creation failed: {}", e)))?;
     Ok(pool)
@@ -27,3 +39,148 @@ pub fn init_pool() -> Result<DbPool, DatabaseError> {
 fn get_database_url() -> Result<String, DatabaseError> {
     env::var("DATABASE_URL").map_err(DatabaseError::MissingDatabaseUrl)
 }
+
+/// Whether a query only reads or also writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryIntent {
+    Read,
+    Write,
+}
+
+/// Routes queries between a primary pool and optional read replicas.
+///
+/// Writes always go to the primary. Reads go to the replicas in turn, or to
+/// the primary when there are none. Reads that must see the caller's own
+/// writes should use the primary, as replicas may lag behind it.
+#[derive(Debug)]
+pub struct DbRouter<P = DbPool> {
+    primary: P,
+    replicas: Vec<P>,
+    next_replica: AtomicUsize,
+}
+
+impl<P> DbRouter<P> {
+    pub fn new(primary: P) -> Self {
+        Self {
+            primary,
+            replicas: Vec::new(),
+            next_replica: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_replicas(mut self, replicas: Vec<P>) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    pub fn replicas(&self) -> &[P] {
+        &self.replicas
+    }
+
+    /// The pool for a read-only query: the next replica, round-robin
+    pub fn read(&self) -> &P {
+        if self.replicas.is_empty() {
+            return &self.primary;
+        }
+        let index = self.next_replica.fetch_add(1, Ordering::Relaxed) % self.replicas.len();
+        &self.replicas[index]
+    }
+
+    /// The pool for a query that writes: the primary
+    pub fn write(&self) -> &P {
+        &self.primary
+    }
+
+    pub fn route(&self, intent: QueryIntent) -> &P {
+        match intent {
+            QueryIntent::Read => self.read(),
+            QueryIntent::Write => self.write(),
+        }
+    }
+}
+
+impl DbRouter<DbPool> {
+    /// A connection for a read-only query
+    pub fn read_connection(
+        &self,
+    ) -> Result<PooledConnection<ConnectionManager<PgConnection>>, PoolError> {
+        self.read().get()
+    }
+
+    /// A connection for a query that writes
+    pub fn write_connection(
+        &self,
+    ) -> Result<PooledConnection<ConnectionManager<PgConnection>>, PoolError> {
+        self.write().get()
+    }
+}
+
+/// Like [`init_pool_with_stats`], adding a pool for each replica listed in
+/// `DATABASE_REPLICA_URLS`. Statistics are recorded for the primary only.
+pub fn init_router_with_stats(recorder: PoolStatsRecorder) -> Result<DbRouter, DatabaseError> {
+    let primary = init_pool_with_stats(recorder)?;
+    let replicas = replica_urls(&env::var(REPLICA_URLS_VAR).unwrap_or_default())
+        .into_iter()
+        .map(|url| {
+            Pool::builder()
+                .build(ConnectionManager::<PgConnection>::new(url))
+                .map_err(|e| {
+                    DatabaseError::PoolCreationError(format!("Replica pool creation failed: {}", e))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !replicas.is_empty() {
+        log::info!("Routing read-only queries to {} read replica(s)", replicas.len());
+    }
+    Ok(DbRouter::new(primary).with_replicas(replicas))
+}
+
+/// Parse a comma-separated list of replica URLs
+fn replica_urls(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for a connection pool, identified by name
+    #[derive(Debug, Clone, PartialEq)]
+    struct MockPool(&'static str);
+
+    #[test]
+    fn test_reads_go_to_replicas_round_robin_and_writes_to_primary() {
+        let router = DbRouter::new(MockPool("primary"))
+            .with_replicas(vec![MockPool("replica-1"), MockPool("replica-2")]);
+
+        let reads: Vec<_> = (0..4).map(|_| router.route(QueryIntent::Read).0).collect();
+        assert_eq!(reads, ["replica-1", "replica-2", "replica-1", "replica-2"]);
+        assert_eq!(router.route(QueryIntent::Write), &MockPool("primary"));
+        assert_eq!(router.write(), router.primary());
+    }
+
+    #[test]
+    fn test_reads_use_primary_without_replicas() {
+        let router = DbRouter::new(MockPool("primary"));
+        assert_eq!(router.read(), &MockPool("primary"));
+    }
+
+    #[test]
+    fn test_replica_urls_parsing() {
+        assert_eq!(
+            replica_urls(" postgres://r1/db, ,postgres://r2/db "),
+            ["postgres://r1/db", "postgres://r2/db"]
+        );
+        assert!(replica_urls("").is_empty());
+    }
+}
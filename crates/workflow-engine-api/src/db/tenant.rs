@@ -14,6 +14,7 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use workflow_engine_core::error::WorkflowError;
+use workflow_engine_core::task::TenantId;
 use super::schema::tenants;
 
 /// Tenant information
@@ -68,6 +69,19 @@ pub struct TenantContext {
     pub isolation_mode: TenantIsolationMode,
 }
 
+impl From<&TenantContext> for TenantId {
+    fn from(context: &TenantContext) -> Self {
+        TenantId::new(context.tenant_id.to_string())
+    }
+}
+
+/// The `tenants` row a [`TenantId`] from a token or task context names
+pub fn tenant_uuid(tenant_id: &TenantId) -> Result<Uuid, WorkflowError> {
+    Uuid::parse_str(tenant_id.as_str()).map_err(|_| {
+        WorkflowError::validation_error_simple(format!("Tenant id '{}' is not a UUID", tenant_id))
+    })
+}
+
 /// Tenant-aware database connection
 pub struct TenantConnection {
     conn: PooledConnection<ConnectionManager<PgConnection>>,
@@ -210,6 +224,11 @@ impl TenantManager {
         Ok(tenant)
     }
 
+    /// Get a tenant-aware connection for the tenant a token or run names
+    pub async fn get_connection_for(&self, tenant_id: &TenantId) -> Result<TenantConnection, WorkflowError> {
+        self.get_tenant_connection(tenant_uuid(tenant_id)?).await
+    }
+
     /// Get a tenant-aware connection
     pub async fn get_tenant_connection(
         &self,
@@ -415,4 +434,19 @@ mod tests {
         assert_eq!(new_tenant.isolation_mode, TenantIsolationMode::RowLevel);
         assert!(new_tenant.settings.is_some());
     }
+
+    #[test]
+    fn test_tenant_ids_map_to_tenant_rows() {
+        let context = TenantContext {
+            tenant_id: Uuid::new_v4(),
+            tenant_name: "acme".to_string(),
+            database_schema: "tenant_acme".to_string(),
+            isolation_mode: TenantIsolationMode::RowLevel,
+        };
+
+        let tenant_id = TenantId::from(&context);
+        assert_eq!(tenant_id.as_str(), context.tenant_id.to_string());
+        assert_eq!(tenant_uuid(&tenant_id).unwrap(), context.tenant_id);
+        assert!(tenant_uuid(&TenantId::new("acme")).is_err());
+    }
 }
\ No newline at end of file
@@ -157,6 +157,36 @@ lazy_static! {
             .subsystem("system"),
         &["connection_type"]
     ).unwrap();
+
+    // Database Pool Metrics
+
+    /// Connections held by the database pool, by state (`idle` or `in_use`)
+    pub static ref DB_POOL_CONNECTIONS: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("connections", "Database pool connections by state")
+            .namespace("ai_workflow")
+            .subsystem("db_pool"),
+        &["state"]
+    ).unwrap();
+
+    /// Maximum size of the database pool
+    pub static ref DB_POOL_MAX_SIZE: IntGauge = IntGauge::new(
+        "ai_workflow_db_pool_max_size", "Maximum number of database pool connections"
+    ).unwrap();
+
+    /// Connections checked out of the database pool
+    pub static ref DB_POOL_CHECKOUTS_TOTAL: IntCounter = IntCounter::new(
+        "ai_workflow_db_pool_checkouts_total", "Total number of database pool checkouts"
+    ).unwrap();
+
+    /// Checkouts that had to wait for a connection
+    pub static ref DB_POOL_WAITS_TOTAL: IntCounter = IntCounter::new(
+        "ai_workflow_db_pool_waits_total", "Total number of database pool checkouts that waited for a connection"
+    ).unwrap();
+
+    /// Checkouts that timed out waiting for a connection
+    pub static ref DB_POOL_TIMEOUTS_TOTAL: IntCounter = IntCounter::new(
+        "ai_workflow_db_pool_timeouts_total", "Total number of database pool checkout timeouts"
+    ).unwrap();
     
     // AI Token Usage Metrics
     
@@ -261,6 +291,13 @@ pub fn init_metrics() -> Result<(), prometheus::Error> {
     REGISTRY.register(Box::new(SYSTEM_UPTIME_SECONDS.clone()))?;
     REGISTRY.register(Box::new(MEMORY_USAGE_BYTES.clone()))?;
     REGISTRY.register(Box::new(ACTIVE_CONNECTIONS.clone()))?;
+
+    // Database pool metrics
+    REGISTRY.register(Box::new(DB_POOL_CONNECTIONS.clone()))?;
+    REGISTRY.register(Box::new(DB_POOL_MAX_SIZE.clone()))?;
+    REGISTRY.register(Box::new(DB_POOL_CHECKOUTS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(DB_POOL_WAITS_TOTAL.clone()))?;
+    REGISTRY.register(Box::new(DB_POOL_TIMEOUTS_TOTAL.clone()))?;
     
     // AI token usage metrics
     REGISTRY.register(Box::new(AI_REQUESTS_TOTAL.clone()))?;
@@ -487,9 +487,9 @@ async fn display_workflow_results(context: &TaskContext, elapsed: std::time::Dur
 }
 
 async fn display_node_results(context: &TaskContext) {
-    if !context.nodes.is_empty() {
+    if !context.get_all_data().is_empty() {
         println!("\n   📊 Node Execution Results:");
-        for (idx, (node_name, node_data)) in context.nodes.iter().enumerate() {
+        for (idx, (node_name, node_data)) in context.get_all_data().iter().enumerate() {
             let node_logger = NodeLogger::new(node_name);
             node_logger.starting().await;
             
@@ -516,9 +516,9 @@ async fn display_node_results(context: &TaskContext) {
 }
 
 async fn display_workflow_metadata(context: &TaskContext) {
-    if !context.metadata.is_empty() {
+    if !context.get_all_metadata().is_empty() {
         println!("\n   📋 Workflow Metadata:");
-        for (key, value) in &context.metadata {
+        for (key, value) in context.get_all_metadata().iter() {
             println!("      🔹 {} -> {}", key, value);
         }
         reading_pause().await;
@@ -809,12 +809,12 @@ pub async fn knowledge_base_mcp_demo() {
                     );
                     
                     println!("   🆔 Search Query ID: {}", context.event_id);
-                    println!("   📚 Search Results: {} nodes processed", context.nodes.len());
+                    println!("   📚 Search Results: {} nodes processed", context.get_all_data().len());
                     
                     // Show summary of search results
                     let mut sources_found = 0;
                     let mut total_results = 0;
-                    for (node_name, node_data) in &context.nodes {
+                    for (node_name, node_data) in context.get_all_data().iter() {
                         if node_name.contains("search") {
                             sources_found += 1;
                             if let Some(obj) = node_data.as_object() {
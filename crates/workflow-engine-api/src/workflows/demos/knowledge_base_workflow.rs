@@ -500,7 +500,7 @@ pub async fn knowledge_base_workflow_demo() {
                         );
 
                         println!("\n   🔍 Knowledge Search Node Results:");
-                        for (idx, (node_name, node_data)) in context.nodes.iter().enumerate() {
+                        for (idx, (node_name, node_data)) in context.get_all_data().iter().enumerate() {
                             println!("\n      🔸 Node {} - '{}'", idx + 1, node_name);
                             
                             // Show different processing for different node types
@@ -581,9 +581,9 @@ pub async fn knowledge_base_workflow_demo() {
                             }
                         }
 
-                        if !context.metadata.is_empty() {
+                        if !context.get_all_metadata().is_empty() {
                             println!("\n   📋 Search Metadata:");
-                            for (key, value) in &context.metadata {
+                            for (key, value) in context.get_all_metadata().iter() {
                                 println!("      🔹 {} -> {}", key, value);
                             }
                             sleep(QUICK_PAUSE).await;
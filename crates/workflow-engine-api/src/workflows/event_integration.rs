@@ -9,7 +9,11 @@ use std::sync::Arc;
 use uuid::Uuid;
 use serde_json::Value;
 
-use workflow_engine_core::{error::WorkflowError, task::TaskContext, workflow::Workflow};
+use workflow_engine_core::{
+    error::WorkflowError,
+    task::{TaskContext, CORRELATION_ID_KEY},
+    workflow::Workflow,
+};
 use crate::db::event::Event;
 use crate::db::events::{
     EventDispatcher, EventEnvelope, EventMetadata, EventStore, EventSerializable,
@@ -40,13 +44,12 @@ impl WorkflowEventExt for Workflow {
         if !event.task_context.is_null() {
             if let Value::Object(task_map) = &event.task_context {
                 for (key, value) in task_map {
-                    context.metadata.insert(key.clone(), value.clone());
+                    context.metadata_mut().insert(key.clone(), value.clone());
                 }
             }
         }
         
-        // Run the workflow - this would need to be implemented properly
-        Ok(context)
+        self.run_with_context(context)
     }
 }
 
@@ -63,10 +66,10 @@ impl TaskContextEventExt for TaskContext {
             "event_id": self.event_id.to_string(),
             "workflow_type": self.workflow_type,
             "data": self.event_data,
-            "node_outputs": self.nodes,
+            "node_outputs": self.get_all_data(),
             "created_at": self.created_at.to_rfc3339(),
             "updated_at": self.updated_at.to_rfc3339(),
-            "task_context": self.metadata,
+            "task_context": self.get_all_metadata(),
         });
         
         Ok(event_data)
@@ -135,7 +138,7 @@ impl WorkflowEventPublisher {
             user_id,
         });
         
-        self.publish_workflow_event(workflow_id, event, correlation_id).await
+        self.publish_workflow_event(workflow_id, event, correlation_id, None).await.map(drop)
     }
     
     /// Publish workflow completed event
@@ -154,7 +157,7 @@ impl WorkflowEventPublisher {
             nodes_executed,
         });
         
-        self.publish_workflow_event(workflow_id, event, correlation_id).await
+        self.publish_workflow_event(workflow_id, event, correlation_id, None).await.map(drop)
     }
     
     /// Publish workflow failed event
@@ -175,7 +178,28 @@ impl WorkflowEventPublisher {
             duration_ms,
         });
         
-        self.publish_workflow_event(workflow_id, event, correlation_id).await
+        self.publish_workflow_event(workflow_id, event, correlation_id, None).await.map(drop)
+    }
+    
+    /// Publish an event emitted during a workflow run, tagged with the run's
+    /// correlation id and caused by the event the run emitted before it.
+    /// Returns the new event's id.
+    pub async fn publish_for_run(
+        &self,
+        task_context: &mut TaskContext,
+        workflow_id: Uuid,
+        event: WorkflowEvent,
+    ) -> Result<Uuid, WorkflowError> {
+        let event_id = self
+            .publish_workflow_event(
+                workflow_id,
+                event,
+                Some(task_context.correlation_id()),
+                task_context.causation_id(),
+            )
+            .await?;
+        task_context.record_emitted_event(event_id);
+        Ok(event_id)
     }
     
     /// Publish AI interaction event for token tracking
@@ -263,7 +287,15 @@ impl WorkflowEventPublisher {
         workflow_id: Uuid,
         event: WorkflowEvent,
         correlation_id: Option<Uuid>,
-    ) -> Result<(), WorkflowError> {
+        causation_id: Option<Uuid>,
+    ) -> Result<Uuid, WorkflowError> {
+        let mut metadata = EventMetadata::new()
+            .with_source(self.source_name.clone())
+            .with_correlation_id(correlation_id.unwrap_or_else(Uuid::new_v4));
+        if let Some(causation_id) = causation_id {
+            metadata = metadata.with_causation_id(causation_id);
+        }
+        
         let event_envelope = EventEnvelope {
             event_id: Uuid::new_v4(),
             aggregate_id: workflow_id,
@@ -273,13 +305,11 @@ impl WorkflowEventPublisher {
             event_data: event.serialize().map_err(|e| WorkflowError::serialization_error_simple(
                 format!("Failed to serialize workflow event: {}", e)
             ))?,
-            metadata: EventMetadata::new()
-                .with_source(self.source_name.clone())
-                .with_correlation_id(correlation_id.unwrap_or_else(Uuid::new_v4)),
+            metadata,
             occurred_at: Utc::now(),
             recorded_at: Utc::now(),
             schema_version: 1,
-            causation_id: None,
+            causation_id,
             correlation_id,
             checksum: None,
         };
@@ -288,7 +318,8 @@ impl WorkflowEventPublisher {
             WorkflowError::serialization_error_simple(
                 format!("Failed to dispatch workflow event: {}", e)
             )
-        })
+        })?;
+        Ok(event_envelope.event_id)
     }
     
     /// Publish AI interaction event
@@ -459,7 +490,10 @@ impl EventDrivenWorkflowExecutor {
         Self { publisher }
     }
     
-    /// Execute a workflow with event publishing
+    /// Execute a workflow with event publishing.
+    ///
+    /// The run's events share `correlation_id` (the run's own id when none is
+    /// given) and each names the one before it as its cause.
     pub async fn execute_workflow(
         &self,
         workflow_id: Uuid,
@@ -468,17 +502,21 @@ impl EventDrivenWorkflowExecutor {
         correlation_id: Option<Uuid>,
     ) -> Result<serde_json::Value, WorkflowError> {
         let start_time = std::time::Instant::now();
+        let mut task_context = TaskContext::new(workflow_type.clone(), input_data.clone());
+        if let Some(correlation_id) = correlation_id {
+            task_context.set_metadata(CORRELATION_ID_KEY, correlation_id)?;
+        }
         
         // Publish workflow started event
         if let Some(publisher) = &self.publisher {
-            publisher.publish_workflow_started(
+            let event = WorkflowEvent::WorkflowStarted(WorkflowStartedEvent {
                 workflow_id,
-                workflow_type.clone(),
-                json!({}), // Empty configuration for now
-                input_data.clone(),
-                None, // No user ID for now
-                correlation_id,
-            ).await?;
+                workflow_type,
+                configuration: json!({}), // Empty configuration for now
+                input_data: input_data.clone(),
+                user_id: None, // No user ID for now
+            });
+            publisher.publish_for_run(&mut task_context, workflow_id, event).await?;
         }
         
         // Execute workflow logic (placeholder)
@@ -490,27 +528,27 @@ impl EventDrivenWorkflowExecutor {
             Ok(output) => {
                 // Publish success event
                 if let Some(publisher) = &self.publisher {
-                    publisher.publish_workflow_completed(
+                    let event = WorkflowEvent::WorkflowCompleted(WorkflowCompletedEvent {
                         workflow_id,
-                        output.clone(),
+                        output_data: output.clone(),
                         duration_ms,
-                        1, // Number of nodes executed (placeholder)
-                        correlation_id,
-                    ).await?;
+                        nodes_executed: 1, // Number of nodes executed (placeholder)
+                    });
+                    publisher.publish_for_run(&mut task_context, workflow_id, event).await?;
                 }
                 Ok(output)
             }
             Err(error) => {
                 // Publish failure event
                 if let Some(publisher) = &self.publisher {
-                    publisher.publish_workflow_failed(
+                    let event = WorkflowEvent::WorkflowFailed(WorkflowFailedEvent {
                         workflow_id,
-                        error.to_string(),
-                        json!({"error_type": "execution_error"}),
-                        None, // Failed node (would be determined in real implementation)
+                        error_message: error.to_string(),
+                        error_details: json!({"error_type": "execution_error"}),
+                        failed_node: None, // Failed node (would be determined in real implementation)
                         duration_ms,
-                        correlation_id,
-                    ).await?;
+                    });
+                    publisher.publish_for_run(&mut task_context, workflow_id, event).await?;
                 }
                 Err(error)
             }
@@ -573,4 +611,47 @@ impl EventAwareWorkflowFactory {
             source_name,
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::events::tests::InMemoryEventStore;
+
+    #[tokio::test]
+    async fn test_executor_events_form_a_causation_chain() {
+        let store = Arc::new(InMemoryEventStore::default());
+        let factory = EventAwareWorkflowFactory::new(Arc::new(EventDispatcher::new(store.clone())));
+        let executor = factory.create_workflow_executor("test".to_string());
+
+        let workflow_id = Uuid::new_v4();
+        let correlation_id = Uuid::new_v4();
+        executor
+            .execute_workflow(workflow_id, "order_fulfillment".to_string(), json!({"order_id": 42}), Some(correlation_id))
+            .await
+            .unwrap();
+
+        let events = store.get_events(workflow_id).await.unwrap();
+        assert_eq!(events.len(), 2);
+        let (started, completed) = (&events[0], &events[1]);
+        assert_eq!(started.causation_id, None);
+        assert_eq!(completed.causation_id, Some(started.event_id));
+        assert_eq!(completed.metadata.causation_id, Some(started.event_id));
+        for event in &events {
+            assert_eq!(event.correlation_id, Some(correlation_id));
+            assert_eq!(event.metadata.correlation_id, Some(correlation_id));
+        }
+
+        // Without a correlation id the run's events still share one
+        let workflow_id = Uuid::new_v4();
+        executor
+            .execute_workflow(workflow_id, "order_fulfillment".to_string(), json!({}), None)
+            .await
+            .unwrap();
+        let events = store.get_events(workflow_id).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].correlation_id.is_some());
+        assert_eq!(events[0].correlation_id, events[1].correlation_id);
+        assert_eq!(events[1].causation_id, Some(events[0].event_id));
+    }
+}
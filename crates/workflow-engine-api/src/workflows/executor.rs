@@ -21,6 +21,10 @@ use crate::{info_with_correlation, warn_with_correlation, error_with_correlation
 use tracing::{info, warn, error, debug, instrument};
 
 use workflow_engine_core::error::WorkflowError;
+use workflow_engine_core::task::TaskContext;
+use workflow_engine_core::workflow::events::{NodeEvent, NodeEventBus, NodeEventKind};
+use workflow_engine_mcp::connection_pool::McpConnectionPool;
+use workflow_engine_mcp::protocol::{McpResponse, ResponseResult};
 use crate::integrations::{CrossSystemClient, CrossSystemError};
 use crate::integrations::cross_system::HttpCrossSystemClient;
 use crate::workflows::schema::{
@@ -179,6 +183,67 @@ impl StepExecutor for CrossSystemExecutor {
     }
 }
 
+/// Node name of steps that call a tool on an MCP server
+pub const MCP_TOOL_NODE: &str = "McpTool";
+
+/// Executes `McpTool` node steps through a shared MCP connection pool.
+///
+/// The step input names the registered `server`, the `tool` and its
+/// `arguments`, all of which may use templates. Calls go through the pool's
+/// per-server circuit breakers.
+pub struct McpToolExecutor {
+    pool: Arc<McpConnectionPool>,
+}
+
+impl McpToolExecutor {
+    pub fn new(pool: Arc<McpConnectionPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl StepExecutor for McpToolExecutor {
+    async fn execute(
+        &self,
+        step: &StepDefinition,
+        context: &WorkflowContext,
+    ) -> Result<Value, WorkflowError> {
+        let input = context.create_template_engine().render_json(&step.input)?;
+        let field = |name: &str| {
+            input[name].as_str().map(str::to_string).ok_or_else(|| {
+                WorkflowError::invalid_input_simple(format!(
+                    "Step {} needs a string '{}' input",
+                    step.id, name
+                ))
+            })
+        };
+        let server = field("server")?;
+        let tool = field("tool")?;
+
+        let connection = self.pool.get_connection(&server).await?;
+        match connection.call_tool(&tool, input["arguments"].clone()).await? {
+            McpResponse::Result {
+                result: ResponseResult::CallTool(result),
+                ..
+            } => serde_json::to_value(result).map_err(|e| {
+                WorkflowError::serialization_error_simple(format!("Failed to serialize tool result: {}", e))
+            }),
+            McpResponse::Error { error, .. } => Err(WorkflowError::mcp_error(
+                format!("Tool {} failed: {}", tool, error.message),
+                &server,
+                "call_tool",
+            )),
+            other => Err(WorkflowError::mcp_protocol_error(
+                "Unexpected response to tool call",
+                &server,
+                "CallToolResult",
+                format!("{:?}", other),
+                "response",
+            )),
+        }
+    }
+}
+
 /// Node executor for local workflow nodes
 pub struct NodeExecutor {
     node_registry: HashMap<String, Box<dyn StepExecutor>>,
@@ -245,14 +310,22 @@ impl StepExecutor for NodeExecutor {
 /// Workflow execution context
 pub struct WorkflowContext {
     instance: WorkflowInstance,
+    task_context: TaskContext,
     step_outputs: HashMap<String, Value>,
     environment: HashMap<String, String>,
 }
 
 impl WorkflowContext {
     pub fn new(instance: WorkflowInstance) -> Self {
+        let mut task_context = TaskContext::new(instance.workflow.name.clone(), instance.inputs.clone());
+        task_context.event_id = instance.id;
+        if let Some(tenant_id) = &instance.tenant_id {
+            task_context.set_tenant_id(tenant_id);
+        }
+
         Self {
             instance,
+            task_context,
             step_outputs: HashMap::new(),
             environment: std::env::vars().collect(),
         }
@@ -265,6 +338,11 @@ impl WorkflowContext {
     pub fn get_step_output(&self, step_id: &str) -> Option<&Value> {
         self.step_outputs.get(step_id)
     }
+
+    /// The run's task context, carrying the instance's id and tenant
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
     
     pub fn create_template_engine(&self) -> TemplateEngine {
         let mut engine = TemplateEngine::new();
@@ -298,6 +376,7 @@ impl WorkflowContext {
 pub struct WorkflowExecutor {
     cross_system_executor: CrossSystemExecutor,
     node_executor: NodeExecutor,
+    events: NodeEventBus,
 }
 
 impl WorkflowExecutor {
@@ -305,8 +384,22 @@ impl WorkflowExecutor {
         Self {
             cross_system_executor: CrossSystemExecutor::new(registry_endpoint, auth_token),
             node_executor: NodeExecutor::new(),
+            events: NodeEventBus::default(),
         }
     }
+
+    /// Runs `McpTool` node steps through `pool`
+    pub fn with_mcp_pool(mut self, pool: Arc<McpConnectionPool>) -> Self {
+        self.node_executor
+            .register_node(MCP_TOOL_NODE.to_string(), Box::new(McpToolExecutor::new(pool)));
+        self
+    }
+
+    /// Subscribes to step start, completion and failure events from every
+    /// subsequent execution, keyed by instance id.
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<NodeEvent> {
+        self.events.subscribe()
+    }
     
     /// Execute a workflow instance
     #[instrument(skip(self, instance))]
@@ -379,7 +472,14 @@ impl WorkflowExecutor {
             };
             
             // Execute the step
-            match self.execute_step(step, &context).await {
+            self.events.publish(instance.id, &instance.workflow.name, &step.id, NodeEventKind::Started);
+            let result = self.execute_step(step, &context).await;
+            let kind = match &result {
+                Ok(_) => NodeEventKind::Completed,
+                Err(e) => NodeEventKind::Failed { error: e.to_string() },
+            };
+            self.events.publish(instance.id, &instance.workflow.name, &step.id, kind);
+            match result {
                 Ok(output) => {
                     let duration = step_start_time.elapsed();
                     step_execution.status = StepStatus::Completed;
@@ -530,6 +630,7 @@ impl WorkflowFactory {
             started_at: None,
             completed_at: None,
             error: None,
+            tenant_id: None,
         };
         
         Ok(instance)
@@ -612,4 +713,19 @@ mod tests {
         // This would fail in test without actual services, but tests the structure
         let _result = executor.execute(&step, &context).await;
     }
+
+    #[test]
+    fn test_run_context_belongs_to_the_instances_tenant() {
+        use workflow_engine_core::task::TenantId;
+
+        let workflow = templates::research_to_documentation();
+        let mut instance = WorkflowFactory::create_instance(workflow, serde_json::json!({"topic": "test"})).unwrap();
+        let untenanted = WorkflowContext::new(instance.clone());
+        assert_eq!(untenanted.task_context().tenant_id(), None);
+        assert_eq!(untenanted.task_context().event_id, instance.id);
+
+        instance.tenant_id = Some(TenantId::new("acme"));
+        let context = WorkflowContext::new(instance);
+        assert_eq!(context.task_context().tenant_id(), Some(TenantId::new("acme")));
+    }
 }
\ No newline at end of file
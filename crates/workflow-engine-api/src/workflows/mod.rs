@@ -337,14 +337,25 @@
 //! For more detailed information, see the individual module documentation and the
 //! comprehensive examples in the [`demos`] module.
 
-use diesel::PgConnection;
+use chrono::Utc;
 use serde_json::Value;
+use std::time::Instant;
+use uuid::Uuid;
 
-use workflow_engine_core::{error::WorkflowError, workflow::Workflow};
+use workflow_engine_core::{
+    error::WorkflowError,
+    task::{TaskContext, TENANT_ID_KEY},
+    workflow::Workflow,
+};
 use crate::db::event::{Event, NewEvent};
+use crate::db::events::{
+    types::{WorkflowCompletedEvent, WorkflowEvent, WorkflowStartedEvent},
+    EventEnvelope, EventMetadata, EventSerializable,
+};
 
 // Import extension traits
 use self::event_integration::{WorkflowEventExt, TaskContextEventExt};
+pub use self::persistence::RunStore;
 
 pub mod customer_support_workflow;
 pub mod demos;
@@ -352,6 +363,7 @@ pub mod executor;
 pub mod knowledge_base_workflow;
 pub mod nodes;
 pub mod parser;
+pub mod persistence;
 pub mod registry;
 pub mod schema;
 pub mod event_integration;
@@ -365,56 +377,124 @@ impl WorkflowRunner {
         Self { workflow }
     }
 
-    /// Process an event from the database
-    pub fn process_event(
+    /// Process an event from the database.
+    ///
+    /// Runs the workflow, then stores the updated event together with the
+    /// `WorkflowStarted` and `WorkflowCompleted` events of the run in a
+    /// single transaction. If any write fails the transaction is rolled back
+    /// and nothing is stored. A failed run stores nothing either.
+    ///
+    /// A run whose event names a tenant in its task context stays in that
+    /// tenant, and the events it stores are tagged with it.
+    ///
+    /// The domain events are versions 1 and 2 of the event's aggregate, so
+    /// processing an event whose run is already stored fails with a
+    /// concurrency conflict and stores nothing.
+    pub fn process_event<S: RunStore>(
         &self,
         event: &Event,
-        _conn: &mut PgConnection,
+        store: &mut S,
     ) -> Result<Event, WorkflowError> {
-        // Run the workflow
+        let started_at = Instant::now();
         let task_context = self.workflow.run_from_event(event)?;
+        let duration_ms = started_at.elapsed().as_millis() as i64;
 
-        // Convert task context to JSON for storage
-        let task_context_json = task_context.to_event()?;
-        
         // Create updated event with the task context
         let updated_event = Event {
             id: event.id,
             workflow_type: event.workflow_type.clone(),
             data: event.data.clone(),
-            task_context: task_context_json,
+            task_context: task_context.to_event()?,
             created_at: event.created_at,
-            updated_at: chrono::Utc::now(),
+            updated_at: Utc::now(),
         };
+        let domain_events = self.domain_events(event, &task_context, duration_ms)?;
 
-        // Event persistence not implemented - requires Event::store method implementation
-        // Currently returns in-memory event without database persistence
-        // updated_event.store(conn)?;
+        store.transaction(|store| {
+            store.save_event(&updated_event)?;
+            store.append_domain_events(&domain_events)
+        })?;
 
         Ok(updated_event)
     }
 
-    /// Create and process a new event
-    pub fn create_and_process(
+    /// Create and process a new event. The event is only stored if
+    /// processing succeeds.
+    pub fn create_and_process<S: RunStore>(
         &self,
         event_data: Value,
-        conn: &mut PgConnection,
+        store: &mut S,
     ) -> Result<Event, WorkflowError> {
-        // Create initial event
         let event = NewEvent::new(
             event_data,
             self.workflow.workflow_type().to_string(),
             Value::Null,
         );
-        
-        // Store the event
-        event.store(conn).map_err(|e| WorkflowError::database_error(
-            format!("Failed to store event: {}", e),
-            "event_store",
-            None
-        ))?;
+        self.process_event(&event, store)
+    }
+
+    /// The events recording a run of `event`'s workflow, each caused by the
+    /// one before
+    fn domain_events(
+        &self,
+        event: &Event,
+        task_context: &TaskContext,
+        duration_ms: i64,
+    ) -> Result<Vec<EventEnvelope>, WorkflowError> {
+        let run = [
+            WorkflowEvent::WorkflowStarted(WorkflowStartedEvent {
+                workflow_id: event.id,
+                workflow_type: event.workflow_type.clone(),
+                configuration: serde_json::json!({ "version": self.workflow.version() }),
+                input_data: event.data.clone(),
+                user_id: None,
+            }),
+            WorkflowEvent::WorkflowCompleted(WorkflowCompletedEvent {
+                workflow_id: event.id,
+                output_data: serde_json::to_value(task_context.get_all_data()).unwrap_or_default(),
+                duration_ms,
+                nodes_executed: task_context.get_all_data().len() as i32,
+            }),
+        ];
 
-        // Process it
-        self.process_event(&event, conn)
+        let correlation_id = task_context.correlation_id();
+        let mut causation_id = task_context.causation_id();
+        let tenant_id = task_context.tenant_id();
+        let mut envelopes = Vec::with_capacity(run.len());
+        for (version, workflow_event) in (1..).zip(run) {
+            let mut metadata = EventMetadata::new()
+                .with_source("workflow_runner".to_string())
+                .with_correlation_id(correlation_id);
+            if let Some(causation_id) = causation_id {
+                metadata = metadata.with_causation_id(causation_id);
+            }
+            if let Some(tenant_id) = &tenant_id {
+                metadata = metadata.add_tag(TENANT_ID_KEY.to_string(), tenant_id.to_string());
+            }
+            let now = Utc::now();
+            let envelope = EventEnvelope {
+                event_id: Uuid::new_v4(),
+                aggregate_id: event.id,
+                aggregate_type: "workflow".to_string(),
+                event_type: WorkflowEvent::event_type().to_string(),
+                aggregate_version: version,
+                event_data: workflow_event.serialize().map_err(|e| {
+                    WorkflowError::serialization_error_simple(format!(
+                        "Failed to serialize workflow event: {}",
+                        e
+                    ))
+                })?,
+                metadata,
+                occurred_at: now,
+                recorded_at: now,
+                schema_version: WorkflowEvent::schema_version(),
+                causation_id,
+                correlation_id: Some(correlation_id),
+                checksum: None,
+            };
+            causation_id = Some(envelope.event_id);
+            envelopes.push(envelope);
+        }
+        Ok(envelopes)
     }
 }
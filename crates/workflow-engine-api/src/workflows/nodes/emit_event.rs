@@ -0,0 +1,352 @@
+/*!
+# Emit Event Workflow Node
+
+This module implements a node that publishes a domain event to the event
+store, so workflows can record what happened as part of their execution.
+*/
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use workflow_engine_core::error::{retry_with_policy, RetryPolicy, WorkflowError};
+use workflow_engine_core::nodes::{AsyncNode, Node};
+use workflow_engine_core::task::TaskContext;
+
+use crate::db::events::error_integration::ResilientEventStore;
+use crate::db::events::types::{WorkflowCompletedEvent, WorkflowEvent};
+use crate::db::events::{EventEnvelope, EventError, EventMetadata, EventSerializable, EventStore};
+
+type EventBuilder = dyn Fn(&TaskContext) -> Result<WorkflowEvent, WorkflowError> + Send + Sync;
+
+/// Appends a [`WorkflowEvent`] built from the task context to an event store.
+///
+/// The event becomes the next version of its workflow's aggregate, and the
+/// new event id and version are stored as the `emit_event` node result.
+/// Events carry the run's correlation id, and each is caused by the event
+/// the run emitted before it, so a run's events can be queried and ordered
+/// by correlation id.
+/// Failed appends, including version conflicts with concurrent writers, are
+/// retried according to the retry policy; if they keep failing the node
+/// fails with a transient [`WorkflowError::DatabaseError`].
+///
+/// ```rust,ignore
+/// let node = EmitEventNode::new(event_store, |context| {
+///     Ok(WorkflowEvent::WorkflowCancelled(WorkflowCancelledEvent {
+///         workflow_id: context.event_id,
+///         reason: "Customer withdrew the request".to_string(),
+///         cancelled_by: None,
+///         duration_ms: 0,
+///     }))
+/// });
+/// ```
+pub struct EmitEventNode {
+    event_store: Arc<dyn EventStore>,
+    build_event: Arc<EventBuilder>,
+    retry_policy: RetryPolicy,
+    output_key: String,
+}
+
+impl EmitEventNode {
+    pub fn new(
+        event_store: Arc<dyn EventStore>,
+        build_event: impl Fn(&TaskContext) -> Result<WorkflowEvent, WorkflowError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            event_store,
+            build_event: Arc::new(build_event),
+            retry_policy: RetryPolicy::default(),
+            output_key: "emit_event".to_string(),
+        }
+    }
+
+    /// Emit a [`WorkflowEvent::WorkflowCompleted`] carrying all node results
+    pub fn workflow_completed(event_store: Arc<dyn EventStore>) -> Self {
+        Self::new(event_store, |context| {
+            Ok(WorkflowEvent::WorkflowCompleted(WorkflowCompletedEvent {
+                workflow_id: context.event_id,
+                output_data: json!(context.get_all_data()),
+                duration_ms: (Utc::now() - context.created_at).num_milliseconds(),
+                nodes_executed: context.get_all_data().len() as i32,
+            }))
+        })
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Append `event` as the next version of its workflow aggregate
+    async fn append(&self, event: &WorkflowEvent, context: &TaskContext) -> Result<EventEnvelope, WorkflowError> {
+        let workflow_id = workflow_id(event);
+        let version = self
+            .event_store
+            .get_aggregate_version(workflow_id)
+            .await
+            .map_err(append_error)?;
+
+        let correlation_id = context.correlation_id();
+        let causation_id = context.causation_id();
+        let mut metadata = EventMetadata::new()
+            .with_source("EmitEventNode".to_string())
+            .with_correlation_id(correlation_id);
+        if let Some(causation_id) = causation_id {
+            metadata = metadata.with_causation_id(causation_id);
+        }
+
+        let envelope = EventEnvelope {
+            event_id: Uuid::new_v4(),
+            aggregate_id: workflow_id,
+            aggregate_type: "workflow".to_string(),
+            event_type: "workflow_event".to_string(),
+            aggregate_version: version + 1,
+            event_data: event.serialize().map_err(ResilientEventStore::convert_event_error)?,
+            metadata,
+            occurred_at: Utc::now(),
+            recorded_at: Utc::now(),
+            schema_version: WorkflowEvent::schema_version(),
+            causation_id,
+            correlation_id: Some(correlation_id),
+            checksum: None,
+        };
+
+        self.event_store
+            .append_event(&envelope)
+            .await
+            .map_err(append_error)?;
+        Ok(envelope)
+    }
+}
+
+/// The workflow an event belongs to
+fn workflow_id(event: &WorkflowEvent) -> Uuid {
+    match event {
+        WorkflowEvent::WorkflowStarted(e) => e.workflow_id,
+        WorkflowEvent::WorkflowCompleted(e) => e.workflow_id,
+        WorkflowEvent::WorkflowFailed(e) => e.workflow_id,
+        WorkflowEvent::WorkflowCancelled(e) => e.workflow_id,
+        WorkflowEvent::NodeExecutionStarted(e) => e.workflow_id,
+        WorkflowEvent::NodeExecutionCompleted(e) => e.workflow_id,
+        WorkflowEvent::NodeExecutionFailed(e) => e.workflow_id,
+    }
+}
+
+/// Store failures and version conflicts are worth retrying, so they become
+/// transient database errors
+fn append_error(error: EventError) -> WorkflowError {
+    match error {
+        EventError::DatabaseError { message } | EventError::ConcurrencyError { message } => {
+            WorkflowError::database_error(
+                format!("Failed to append event: {}", message),
+                "append_event",
+                Some("event_store".to_string()),
+            )
+        }
+        other => ResilientEventStore::convert_event_error(other),
+    }
+}
+
+impl fmt::Debug for EmitEventNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmitEventNode")
+            .field("retry_policy", &self.retry_policy)
+            .field("output_key", &self.output_key)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl AsyncNode for EmitEventNode {
+    fn node_name(&self) -> String {
+        "EmitEventNode".to_string()
+    }
+
+    async fn process_async(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        let event = (self.build_event)(&task_context)?;
+        let envelope = retry_with_policy(&self.retry_policy, || self.append(&event, &task_context)).await?;
+        task_context.record_emitted_event(envelope.event_id);
+
+        task_context.update_node(
+            &self.output_key,
+            json!({
+                "event_id": envelope.event_id,
+                "aggregate_id": envelope.aggregate_id,
+                "aggregate_version": envelope.aggregate_version,
+            }),
+        );
+        Ok(task_context)
+    }
+}
+
+impl Node for EmitEventNode {
+    fn node_name(&self) -> String {
+        "EmitEventNode".to_string()
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| WorkflowError::RuntimeError {
+            message: format!("Failed to create runtime: {}", e),
+        })?;
+        runtime.block_on(self.process_with_cancellation(task_context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::events::tests::InMemoryEventStore;
+    use serde_json::Value;
+    use std::time::Duration;
+
+    fn context() -> TaskContext {
+        let mut context = TaskContext::new("order_fulfillment".to_string(), json!({ "order_id": 42 }));
+        context.update_node("ship", json!({ "carrier": "ups" }));
+        context
+    }
+
+    fn fast_retries(retries: u32) -> RetryPolicy {
+        RetryPolicy::fixed(retries, Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_emitted_event_is_stored() {
+        let store = Arc::new(InMemoryEventStore::default());
+        let node = EmitEventNode::workflow_completed(store.clone());
+        let context = context();
+
+        let context = node.process_async(context).await.unwrap();
+        let output: Value = context.get_node_data("emit_event").unwrap().unwrap();
+
+        let events = store.get_events(context.event_id).await.unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(output["event_id"], json!(event.event_id));
+        assert_eq!(output["aggregate_version"], 1);
+        assert_eq!(event.correlation_id, Some(context.event_id));
+
+        match WorkflowEvent::deserialize(&event.event_data, event.schema_version).unwrap() {
+            WorkflowEvent::WorkflowCompleted(completed) => {
+                assert_eq!(completed.workflow_id, context.event_id);
+                assert_eq!(completed.output_data, json!({ "ship": { "carrier": "ups" } }));
+            }
+            other => panic!("Expected WorkflowCompleted, got {:?}", other),
+        }
+
+        // A second emit for the same workflow becomes the next version
+        let context = node.process_async(context).await.unwrap();
+        let output: Value = context.get_node_data("emit_event").unwrap().unwrap();
+        assert_eq!(output["aggregate_version"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_appends_are_retried() {
+        let store = Arc::new(InMemoryEventStore::default());
+        store.fail_next_appends(2);
+        let node = EmitEventNode::workflow_completed(store.clone()).with_retry_policy(fast_retries(3));
+
+        let context = node.process_async(context()).await.unwrap();
+        assert_eq!(store.get_events(context.event_id).await.unwrap().len(), 1);
+
+        store.fail_next_appends(5);
+        match node.process_async(context).await {
+            Err(error @ WorkflowError::DatabaseError { .. }) => {
+                assert!(workflow_engine_core::error::RetryableError::is_retryable(&error));
+            }
+            other => panic!("Expected DatabaseError, got {:?}", other),
+        }
+    }
+
+    #[derive(Debug)]
+    struct EmitStarted(EmitEventNode);
+
+    impl Node for EmitStarted {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Node::process(&self.0, task_context)
+        }
+    }
+
+    #[derive(Debug)]
+    struct EmitCompleted(EmitEventNode);
+
+    impl Node for EmitCompleted {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Node::process(&self.0, task_context)
+        }
+    }
+
+    #[test]
+    fn test_run_events_share_correlation_and_chain_causation() {
+        use crate::db::events::types::WorkflowStartedEvent;
+        use crate::db::events::EventDispatcher;
+        use crate::workflows::event_integration::WorkflowEventPublisher;
+        use std::any::TypeId;
+        use workflow_engine_core::nodes::config::NodeConfig;
+        use workflow_engine_core::workflow::builder::WorkflowBuilder;
+
+        let store = Arc::new(InMemoryEventStore::default());
+        let started = EmitEventNode::new(store.clone(), |context| {
+            Ok(WorkflowEvent::WorkflowStarted(WorkflowStartedEvent {
+                workflow_id: context.event_id,
+                workflow_type: context.workflow_type.clone(),
+                configuration: json!({}),
+                input_data: context.event_data.clone(),
+                user_id: None,
+            }))
+        })
+        .with_output_key("started");
+        let workflow = WorkflowBuilder::new::<EmitStarted>("order_fulfillment".to_string())
+            .add_node(NodeConfig::new::<EmitStarted>().with_connections(vec![TypeId::of::<EmitCompleted>()]))
+            .add_node(NodeConfig::new::<EmitCompleted>())
+            .build()
+            .unwrap();
+        workflow.register_node(EmitStarted(started));
+        workflow.register_node(EmitCompleted(EmitEventNode::workflow_completed(store.clone())));
+
+        let mut context = workflow.run(json!({ "order_id": 42 })).unwrap();
+        let correlation_id = context.correlation_id();
+        assert_eq!(correlation_id, context.event_id);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let events = runtime.block_on(store.get_events_by_correlation_id(correlation_id)).unwrap();
+        assert_eq!(events.len(), 2);
+        let (first, second) = (&events[0], &events[1]);
+        assert_eq!(first.aggregate_version, 1);
+        assert_eq!(first.causation_id, None);
+        assert_eq!(second.causation_id, Some(first.event_id));
+        assert_eq!(second.metadata.causation_id, Some(first.event_id));
+        assert!(events.iter().all(|e| e.metadata.correlation_id == Some(correlation_id)));
+        assert_eq!(context.causation_id(), Some(second.event_id));
+
+        // Events published through the engine's instrumentation join the chain
+        let publisher = WorkflowEventPublisher::new(
+            Arc::new(EventDispatcher::new(store.clone())),
+            "test".to_string(),
+        );
+        let workflow_id = context.event_id;
+        let third = runtime
+            .block_on(publisher.publish_for_run(
+                &mut context,
+                workflow_id,
+                WorkflowEvent::WorkflowCompleted(WorkflowCompletedEvent {
+                    workflow_id,
+                    output_data: json!({}),
+                    duration_ms: 0,
+                    nodes_executed: 2,
+                }),
+            ))
+            .unwrap();
+        let events = runtime.block_on(store.get_events_by_correlation_id(correlation_id)).unwrap();
+        let published = events.iter().find(|e| e.event_id == third).unwrap();
+        assert_eq!(published.causation_id, Some(second.event_id));
+        assert_eq!(context.causation_id(), Some(third));
+    }
+}
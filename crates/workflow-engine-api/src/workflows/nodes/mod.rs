@@ -7,4 +7,5 @@ used in declarative workflow definitions and executed by the workflow engine.
 Task 2.3: Implement NotionClientNode for creating documentation pages
 */
 
+pub mod emit_event;
 pub mod notion_client;
\ No newline at end of file
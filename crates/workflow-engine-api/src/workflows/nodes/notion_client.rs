@@ -139,6 +139,7 @@ mod tests {
             started_at: Some(chrono::Utc::now()),
             completed_at: None,
             error: None,
+            tenant_id: None,
         };
         
         WorkflowContext::new(instance)
@@ -0,0 +1,243 @@
+//! Transactional persistence of workflow runs
+//!
+//! [`WorkflowRunner`](super::WorkflowRunner) stores a processed event together
+//! with the domain events derived from its run. [`RunStore`] is the storage
+//! it writes through: every write happens inside
+//! [`RunStore::transaction`], so either all of them are committed or, if any
+//! fails, none are.
+
+use diesel::{prelude::*, PgConnection};
+
+use workflow_engine_core::error::WorkflowError;
+
+use crate::db::event::Event;
+use crate::db::events::{store::append_on_connection, EventEnvelope};
+use crate::db::schema::events;
+
+/// Storage for processed events and their domain events
+pub trait RunStore {
+    /// Run `f` in a transaction, committing its writes if it succeeds and
+    /// rolling all of them back if it fails
+    fn transaction<T, F>(&mut self, f: F) -> Result<T, WorkflowError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, WorkflowError>;
+
+    /// Insert `event`, or update its task context if it is already stored
+    fn save_event(&mut self, event: &Event) -> Result<(), WorkflowError>;
+
+    /// Append domain events to the event store. Each event's
+    /// `aggregate_version` must follow its aggregate's stored version;
+    /// appends that don't fail rather than being renumbered.
+    fn append_domain_events(&mut self, events: &[EventEnvelope]) -> Result<(), WorkflowError>;
+}
+
+impl RunStore for PgConnection {
+    fn transaction<T, F>(&mut self, f: F) -> Result<T, WorkflowError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, WorkflowError>,
+    {
+        Connection::transaction(self, f)
+    }
+
+    fn save_event(&mut self, event: &Event) -> Result<(), WorkflowError> {
+        diesel::insert_into(events::table)
+            .values(event)
+            .on_conflict(events::id)
+            .do_update()
+            .set((
+                events::task_context.eq(&event.task_context),
+                events::updated_at.eq(event.updated_at),
+            ))
+            .execute(self)?;
+        Ok(())
+    }
+
+    fn append_domain_events(&mut self, events: &[EventEnvelope]) -> Result<(), WorkflowError> {
+        append_on_connection(self, events).map_err(|e| {
+            WorkflowError::database_error(e.to_string(), "INSERT", Some("event_store".to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::event::NewEvent;
+    use crate::db::events::store::check_expected_versions;
+    use crate::db::events::{types::WorkflowEvent, EventSerializable};
+    use crate::workflows::WorkflowRunner;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use uuid::Uuid;
+    use workflow_engine_core::nodes::Node;
+    use workflow_engine_core::task::TaskContext;
+    use workflow_engine_core::workflow::{builder::WorkflowBuilder, Workflow};
+
+    /// In-memory store whose transactions roll back by restoring a copy of
+    /// the committed state
+    #[derive(Debug, Clone, Default)]
+    struct MemoryRunStore {
+        events: HashMap<Uuid, Event>,
+        domain_events: Vec<EventEnvelope>,
+        fail_domain_events: bool,
+        commits: usize,
+    }
+
+    impl RunStore for MemoryRunStore {
+        fn transaction<T, F>(&mut self, f: F) -> Result<T, WorkflowError>
+        where
+            F: FnOnce(&mut Self) -> Result<T, WorkflowError>,
+        {
+            let committed = self.clone();
+            match f(self) {
+                Ok(value) => {
+                    self.commits += 1;
+                    Ok(value)
+                }
+                Err(error) => {
+                    *self = committed;
+                    Err(error)
+                }
+            }
+        }
+
+        fn save_event(&mut self, event: &Event) -> Result<(), WorkflowError> {
+            self.events.insert(event.id, event.clone());
+            Ok(())
+        }
+
+        fn append_domain_events(&mut self, events: &[EventEnvelope]) -> Result<(), WorkflowError> {
+            let mut stored_versions = HashMap::new();
+            for envelope in &self.domain_events {
+                let version = stored_versions.entry(envelope.aggregate_id).or_insert(0);
+                *version = envelope.aggregate_version.max(*version);
+            }
+            check_expected_versions(events, &stored_versions)
+                .map_err(|e| WorkflowError::database_error(e.to_string(), "INSERT", None))?;
+            self.domain_events.extend_from_slice(events);
+            if self.fail_domain_events {
+                return Err(WorkflowError::database_error(
+                    "could not extend file: No space left on device",
+                    "INSERT",
+                    Some("event_store".to_string()),
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct Greet;
+
+    impl Node for Greet {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let name = task_context.event_data["name"].as_str().unwrap_or("world").to_string();
+            task_context.update_node("greeting", format!("Hello, {}!", name));
+            Ok(task_context)
+        }
+    }
+
+    fn runner() -> WorkflowRunner {
+        let workflow: Workflow = WorkflowBuilder::new::<Greet>("greeting".to_string())
+            .build()
+            .unwrap();
+        workflow.register_node(Greet);
+        WorkflowRunner::new(workflow)
+    }
+
+    fn workflow_events(store: &MemoryRunStore) -> Vec<WorkflowEvent> {
+        store
+            .domain_events
+            .iter()
+            .map(|envelope| WorkflowEvent::deserialize(&envelope.event_data, envelope.schema_version).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_successful_run_commits_event_and_domain_events_together() {
+        let mut store = MemoryRunStore::default();
+        let event = NewEvent::new(json!({ "name": "Ada" }), "greeting".to_string(), Value::Null);
+
+        let updated = runner().process_event(&event, &mut store).unwrap();
+        assert_eq!(store.commits, 1);
+
+        let stored = &store.events[&event.id];
+        assert_eq!(stored.task_context, updated.task_context);
+        assert_eq!(stored.task_context["node_outputs"]["greeting"], "Hello, Ada!");
+
+        let emitted = workflow_events(&store);
+        assert!(matches!(&emitted[..], [WorkflowEvent::WorkflowStarted(_), WorkflowEvent::WorkflowCompleted(_)]));
+        let [started, completed] = &store.domain_events[..] else { unreachable!() };
+        assert!(store.domain_events.iter().all(|envelope| envelope.aggregate_id == event.id));
+        assert_eq!(completed.causation_id, Some(started.event_id));
+        assert_eq!(started.correlation_id, completed.correlation_id);
+        if let WorkflowEvent::WorkflowCompleted(completed) = &emitted[1] {
+            assert_eq!(completed.output_data["greeting"], "Hello, Ada!");
+            assert_eq!(completed.nodes_executed, 1);
+        }
+    }
+
+    #[test]
+    fn test_events_are_tagged_with_the_runs_tenant() {
+        use workflow_engine_core::task::TENANT_ID_KEY;
+
+        let mut store = MemoryRunStore::default();
+        let event = NewEvent::new(
+            json!({ "name": "Ada" }),
+            "greeting".to_string(),
+            json!({ TENANT_ID_KEY: "acme" }),
+        );
+
+        runner().process_event(&event, &mut store).unwrap();
+        assert_eq!(store.events[&event.id].task_context["task_context"][TENANT_ID_KEY], "acme");
+        assert_eq!(store.domain_events.len(), 2);
+        for envelope in &store.domain_events {
+            assert_eq!(envelope.metadata.tags.get(TENANT_ID_KEY).map(String::as_str), Some("acme"));
+        }
+
+        let untenanted = NewEvent::new(json!({}), "greeting".to_string(), Value::Null);
+        let mut store = MemoryRunStore::default();
+        runner().process_event(&untenanted, &mut store).unwrap();
+        assert!(store.domain_events.iter().all(|envelope| envelope.metadata.tags.is_empty()));
+    }
+
+    #[test]
+    fn test_persistence_failure_rolls_back_every_write() {
+        let mut store = MemoryRunStore {
+            fail_domain_events: true,
+            ..Default::default()
+        };
+        let event = NewEvent::new(json!({ "name": "Grace" }), "greeting".to_string(), Value::Null);
+
+        let error = runner().process_event(&event, &mut store).unwrap_err();
+        assert!(error.to_string().contains("No space left on device"));
+        assert_eq!(store.commits, 0);
+        assert!(store.events.is_empty());
+        assert!(store.domain_events.is_empty());
+
+        // A retry once storage recovers commits the whole run
+        store.fail_domain_events = false;
+        runner().process_event(&event, &mut store).unwrap();
+        assert_eq!(store.commits, 1);
+        assert_eq!(store.events.len(), 1);
+        assert_eq!(store.domain_events.len(), 2);
+    }
+
+    #[test]
+    fn test_reprocessed_event_conflicts_instead_of_renumbering() {
+        let mut store = MemoryRunStore::default();
+        let event = NewEvent::new(json!({ "name": "Ada" }), "greeting".to_string(), Value::Null);
+        let first = runner().process_event(&event, &mut store).unwrap();
+
+        // The run's events are versions 1 and 2 of its aggregate, which are
+        // already stored, so the second run is rejected as a whole
+        let error = runner().process_event(&event, &mut store).unwrap_err();
+        assert!(error.to_string().contains("expected version 3, got 1"), "{}", error);
+        assert_eq!(store.commits, 1);
+        assert_eq!(store.events[&event.id].task_context, first.task_context);
+        assert_eq!(
+            store.domain_events.iter().map(|envelope| envelope.aggregate_version).collect::<Vec<_>>(),
+            [1, 2]
+        );
+    }
+}
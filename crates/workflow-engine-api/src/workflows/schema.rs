@@ -9,7 +9,9 @@ Task 2.1: Define research_to_documentation workflow YAML schema
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 use uuid::Uuid;
+use workflow_engine_core::task::TenantId;
 
 /// Main workflow definition structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -242,10 +244,14 @@ pub struct WorkflowInstance {
     
     /// Error information if failed
     pub error: Option<WorkflowError>,
+
+    /// Tenant that triggered the instance; only that tenant can see it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<TenantId>,
 }
 
 /// Workflow execution status
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum WorkflowStatus {
     /// Workflow created but not started
     Created,
@@ -285,7 +291,7 @@ pub struct StepExecution {
 }
 
 /// Step execution status
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum StepStatus {
     /// Waiting for dependencies
     Pending,
@@ -973,6 +979,7 @@ mod tests {
             started_at: None,
             completed_at: None,
             error: None,
+            tenant_id: None,
         };
         
         assert_eq!(instance.status, WorkflowStatus::Created);
@@ -4,11 +4,20 @@ use dotenvy::dotenv;
 use log::info;
 use std::{env, sync::Arc};
 
+use workflow_engine_api::db::migration::{MigrationConfig, MigrationService, PostgreSQLMigrationManager};
+use workflow_engine_api::db::pool_stats::{PoolMonitor, PoolStatsRecorder};
 use workflow_engine_api::db::session::DbPool;
 use workflow_engine_api::api;
+use workflow_engine_api::api::circuit_breakers::CircuitBreakers;
+use workflow_engine_api::api::errors::ErrorFormat;
 use workflow_engine_core::auth::JwtAuth;
 use workflow_engine_api::api::middleware::auth::JwtMiddleware;
-use workflow_engine_api::api::rate_limit::{RateLimitConfig, RateLimitMiddleware};
+use workflow_engine_api::api::middleware::timeout::{RequestTimeout, RequestTimeoutConfig};
+use workflow_engine_api::api::middleware::validation::RequestValidation;
+use workflow_engine_api::api::rate_limit::{RateLimitConfig, RateLimitMiddleware, RateLimiter};
+use workflow_engine_api::api::workflows::WorkflowService;
+use workflow_engine_mcp::config::McpConfig;
+use workflow_engine_mcp::connection_pool::McpConnectionPool;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -37,15 +46,30 @@ async fn main() -> std::io::Result<()> {
 
     info!("Starting server at http://{}", server_url);
 
-    // Initialize database pool
-    let pool: DbPool = workflow_engine_api::db::session::init_pool()
+    // Initialize database pool, recording checkout statistics for health and metrics
+    let pool_stats = PoolStatsRecorder::new();
+    // Read-only queries can be routed to replicas listed in DATABASE_REPLICA_URLS
+    let db_router = workflow_engine_api::db::session::init_router_with_stats(pool_stats.clone())
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to initialize database pool: {}", e)))?;
+    let pool: DbPool = db_router.primary().clone();
+    let db_router = web::Data::new(db_router);
     let arc_pool = Arc::new(pool.clone());
+    let pool_monitor = web::Data::new(PoolMonitor::new(pool.clone(), pool_stats));
+
+    // Migration status for /health/migrations and the readiness probe,
+    // checked against the migrations embedded in the binary
+    let migrations = web::Data::new(MigrationService::new(
+        Box::new(PostgreSQLMigrationManager::new(pool)),
+        MigrationConfig::default(),
+    ));
 
     // Initialize JWT auth
     let jwt_secret = env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key".to_string());
     let jwt_auth = web::Data::new(JwtAuth::new(jwt_secret.clone()));
 
+    // Error response format (API_ERROR_FORMAT=problem+json for RFC 7807)
+    let error_format = web::Data::new(ErrorFormat::from_env());
+
     // Configure rate limiting
     let requests_per_minute = env::var("RATE_LIMIT_PER_MINUTE")
         .unwrap_or_else(|_| "60".to_string())
@@ -63,16 +87,55 @@ async fn main() -> std::io::Result<()> {
             10
         });
     
+    // Share rate limits across instances when a Redis URL is configured
     let rate_limit_config = RateLimitConfig {
         requests_per_minute,
         burst_size,
+        redis_url: env::var("RATE_LIMIT_REDIS_URL").ok(),
+        ..Default::default()
     };
+    let rate_limiter = Arc::new(RateLimiter::from_config(rate_limit_config).await);
+    rate_limiter.spawn_cleanup_task();
+
+    // MCP servers configured through MCP_* variables share one connection
+    // pool, used by workflows' McpTool steps. Its per-server circuit breakers
+    // are reported and reset through /api/v1/admin/circuit-breakers
+    let mcp_config = McpConfig::from_env()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Invalid MCP configuration: {}", e)))?;
+    let mcp_pool = Arc::new(McpConnectionPool::new(mcp_config.connection_pool.clone()));
+    for server in mcp_config.servers.into_values().filter(|server| server.enabled) {
+        mcp_pool
+            .register_server(
+                server.name,
+                server.transport,
+                mcp_config.client_name.clone(),
+                mcp_config.client_version.clone(),
+            )
+            .await;
+    }
+    let circuit_breakers = web::Data::new(
+        CircuitBreakers::new().with_registry("mcp", mcp_pool.circuit_breakers()),
+    );
+
+    // Workflow trigger and status endpoints
+    let workflows = web::Data::new(
+        WorkflowService::new()
+            .await
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to initialize workflows: {}", e)))?
+            .with_mcp_pool(mcp_pool),
+    );
 
     // Optional: Run demo workflows on startup (disabled by default for production)
     // Uncomment the following lines to run demos on server startup:
     // info!("Starting Demo Workflows");
     // workflow_engine_api::workflows::demos::run_all_demos().await;
 
+    // Request body schemas, built once and shared by all workers
+    let request_validation = RequestValidation::default();
+
+    // Per-route handler timeouts (REQUEST_TIMEOUT_SECS, REQUEST_TIMEOUT_ROUTES)
+    let request_timeout = RequestTimeout::new(RequestTimeoutConfig::from_env());
+
     // Start HTTP server
     HttpServer::new(move || {
         // Configure CORS
@@ -85,14 +148,26 @@ async fn main() -> std::io::Result<()> {
         App::new()
             // Add database pool to app data
             .app_data(web::Data::new(arc_pool.clone()))
+            .app_data(db_router.clone())
+            .app_data(pool_monitor.clone())
+            .app_data(migrations.clone())
+            .app_data(workflows.clone())
+            .app_data(circuit_breakers.clone())
             // Add JWT auth to app data
             .app_data(jwt_auth.clone())
+            // Add default error response format to app data
+            .app_data(error_format.clone())
+            // Abort handlers that exceed their route's timeout with a 504
+            .wrap(request_timeout.clone())
+            // Validate request bodies against the OpenAPI spec (runs after
+            // authentication)
+            .wrap(request_validation.clone())
             // Enable logger middleware
             .wrap(middleware::Logger::default())
             // Enable CORS
             .wrap(cors)
             // Enable rate limiting
-            .wrap(RateLimitMiddleware::new(rate_limit_config.clone()))
+            .wrap(RateLimitMiddleware::with_limiter(rate_limiter.clone()))
             // Enable JWT authentication
             .wrap(JwtMiddleware::new(jwt_secret.clone()))
             // Configure routes
@@ -352,6 +352,11 @@ impl TemplateManager {
         &self.metrics
     }
     
+    /// Get a registered template by ID
+    pub fn get_template(&self, template_id: &str) -> Result<std::sync::Arc<Template>, TemplateError> {
+        self.registry.get(template_id)
+    }
+    
     /// List all registered templates
     pub fn list_templates(&self) -> Result<Vec<TemplateMetadata>, TemplateError> {
         Ok(self.storage.list()?)
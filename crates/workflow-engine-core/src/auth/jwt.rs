@@ -34,6 +34,9 @@ pub struct Claims {
     pub role: String,
     /// Issued at time (as UTC timestamp)
     pub iat: usize,
+    /// Tenant the subject belongs to, for multi-tenant deployments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
 }
 
 impl Claims {
@@ -47,6 +50,7 @@ impl Claims {
             role,
             exp: exp.timestamp() as usize,
             iat: now.timestamp() as usize,
+            tenant_id: None,
         }
     }
     
@@ -59,8 +63,15 @@ impl Claims {
             role,
             exp: expires_at.timestamp() as usize,
             iat: now.timestamp() as usize,
+            tenant_id: None,
         }
     }
+
+    /// Scope the claims to `tenant_id`
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
 }
 
 /// Simple JWT middleware for Actix-web
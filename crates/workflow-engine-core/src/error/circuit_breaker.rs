@@ -3,7 +3,7 @@
 //! This module provides a circuit breaker implementation to prevent cascade failures
 //! in distributed systems by temporarily blocking calls to failing services.
 
-use super::WorkflowError;
+use super::{ErrorCategory, ErrorExt, WorkflowError};
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -134,6 +134,9 @@ impl CircuitBreaker {
                         self.on_success().await;
                         Ok(result)
                     }
+                    // The service handled bad input correctly, so user
+                    // errors say nothing about its health
+                    Err(error) if error.category() == ErrorCategory::User => Err(error),
                     Err(error) => {
                         self.on_failure().await;
                         Err(error)
@@ -253,6 +256,21 @@ impl CircuitBreaker {
         }
     }
     
+    /// Current state, counters and, when open, the time until calls are let
+    /// through again
+    pub async fn status(&self) -> CircuitBreakerStatus {
+        let state = self.state().await;
+        let retry_in = (state == CircuitState::Open).then(|| {
+            let state_changed_at = *self.state_changed_at.lock().unwrap();
+            self.config.timeout.saturating_sub(state_changed_at.elapsed())
+        });
+        CircuitBreakerStatus {
+            state,
+            retry_in,
+            metrics: self.metrics(),
+        }
+    }
+    
     /// Reset the circuit breaker
     pub async fn reset(&self) {
         self.transition_to(CircuitState::Closed).await;
@@ -272,6 +290,16 @@ pub struct CircuitBreakerMetrics {
     pub success_count: u32,
 }
 
+/// Snapshot of a circuit breaker, as returned by [`CircuitBreaker::status`]
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerStatus {
+    pub state: CircuitState,
+    /// Time until an open circuit lets a trial call through; `None` unless
+    /// open
+    pub retry_in: Option<Duration>,
+    pub metrics: CircuitBreakerMetrics,
+}
+
 /// Circuit breaker builder
 pub struct CircuitBreakerBuilder {
     config: CircuitBreakerConfig,
@@ -353,6 +381,17 @@ impl CircuitBreakerRegistry {
             .clone()
     }
     
+    /// Get the circuit breaker of a service without creating one
+    pub async fn find(&self, service: &str) -> Option<Arc<CircuitBreaker>> {
+        self.breakers.read().await.get(service).cloned()
+    }
+    
+    /// Add a circuit breaker built elsewhere, replacing any for the same
+    /// service
+    pub async fn register(&self, service: impl Into<String>, breaker: Arc<CircuitBreaker>) {
+        self.breakers.write().await.insert(service.into(), breaker);
+    }
+    
     /// Get all circuit breakers
     pub async fn all(&self) -> Vec<(String, Arc<CircuitBreaker>)> {
         self.breakers.read().await
@@ -360,6 +399,28 @@ impl CircuitBreakerRegistry {
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect()
     }
+    
+    /// Status of every circuit breaker, by service name
+    pub async fn status(&self) -> Vec<(String, CircuitBreakerStatus)> {
+        let mut statuses = Vec::new();
+        for (service, breaker) in self.all().await {
+            statuses.push((service, breaker.status().await));
+        }
+        statuses.sort_by(|(a, _), (b, _)| a.cmp(b));
+        statuses
+    }
+    
+    /// Force a service's circuit closed. Returns `false` if the service has
+    /// no circuit breaker.
+    pub async fn reset(&self, service: &str) -> bool {
+        match self.find(service).await {
+            Some(breaker) => {
+                breaker.reset().await;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -419,6 +480,34 @@ mod tests {
         ));
     }
     
+    #[tokio::test]
+    async fn test_registry_reports_and_resets_open_breakers() {
+        let registry = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            timeout: Duration::from_secs(30),
+            ..Default::default()
+        });
+        registry.register("dgraph", Arc::new(CircuitBreaker::default())).await;
+        let _ = registry.get("openai").await.call(|| async {
+            Err::<(), _>(WorkflowError::api_error_simple("Test error"))
+        }).await;
+        
+        let statuses = registry.status().await;
+        let names: Vec<_> = statuses.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["dgraph", "openai"]);
+        assert_eq!(statuses[0].1.state, CircuitState::Closed);
+        assert_eq!(statuses[0].1.retry_in, None);
+        let open = &statuses[1].1;
+        assert_eq!(open.state, CircuitState::Open);
+        assert!(open.retry_in.unwrap() > Duration::from_secs(29));
+        assert_eq!(open.metrics.total_failures, 1);
+        
+        assert!(registry.reset("openai").await);
+        assert_eq!(registry.find("openai").await.unwrap().state().await, CircuitState::Closed);
+        assert!(!registry.reset("anthropic").await);
+        assert!(registry.find("anthropic").await.is_none());
+    }
+    
     #[tokio::test]
     async fn test_circuit_breaker_metrics() {
         let cb = CircuitBreaker::default();
@@ -440,4 +529,26 @@ mod tests {
         assert_eq!(metrics.total_successes, 3);
         assert_eq!(metrics.total_failures, 2);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_user_errors_do_not_open_circuit() {
+        let cb = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..Default::default()
+        });
+
+        for _ in 0..3 {
+            let result = cb.call(|| async {
+                Err::<(), _>(WorkflowError::user_error("Missing order id", "ChargeNode"))
+            }).await;
+            assert!(matches!(result, Err(WorkflowError::NodeError { .. })));
+        }
+        assert_eq!(cb.state().await, CircuitState::Closed);
+        assert_eq!(cb.metrics().total_failures, 0);
+
+        let _ = cb.call(|| async {
+            Err::<(), _>(WorkflowError::system_error("Payment provider unreachable", "ChargeNode"))
+        }).await;
+        assert_eq!(cb.state().await, CircuitState::Open);
+    }
+}
@@ -66,6 +66,12 @@ impl ErrorContext {
     }
 }
 
+impl From<WorkflowError> for ErrorContext {
+    fn from(error: WorkflowError) -> Self {
+        Self::new(error)
+    }
+}
+
 /// Extension trait for adding context to errors
 pub trait ErrorContextExt: Sized {
     /// Add context to the error
@@ -0,0 +1,203 @@
+//! Classification of database failures
+//!
+//! Database errors reach [`WorkflowError::DatabaseError`] from many places,
+//! so rather than a variant per failure the kind is derived from the error:
+//! its Diesel or pool source when there is one, otherwise the message and
+//! operation. The kind decides whether retrying can help: a deadlock or a
+//! connection timeout may clear up, a constraint violation will not.
+
+use serde::{Deserialize, Serialize};
+
+use super::{ErrorCategory, WorkflowError};
+
+/// What kind of database failure a [`WorkflowError::DatabaseError`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseErrorKind {
+    /// No connection could be obtained in time, or it was lost
+    ConnectionTimeout,
+    /// The transaction was aborted by a deadlock or serialization conflict
+    Deadlock,
+    /// The statement violated a unique, foreign key, not-null or check
+    /// constraint
+    ConstraintViolation,
+    /// Anything else
+    Other,
+}
+
+impl DatabaseErrorKind {
+    /// How errors of this kind are handled: deadlocks and connection
+    /// timeouts are retried, constraint violations are not
+    pub fn category(self) -> ErrorCategory {
+        match self {
+            Self::ConnectionTimeout | Self::Deadlock => ErrorCategory::Transient,
+            Self::ConstraintViolation => ErrorCategory::Permanent,
+            Self::Other => ErrorCategory::System,
+        }
+    }
+
+    /// Classify a database error from its parts
+    pub fn classify(
+        message: &str,
+        operation: &str,
+        source: Option<&(dyn std::error::Error + Send + Sync + 'static)>,
+    ) -> Self {
+        #[cfg(feature = "database")]
+        if let Some(source) = source {
+            if let Some(error) = source.downcast_ref::<diesel::result::Error>() {
+                return Self::from_diesel(error);
+            }
+            if source.is::<diesel::r2d2::PoolError>() {
+                return Self::ConnectionTimeout;
+            }
+        }
+        #[cfg(not(feature = "database"))]
+        let _ = source;
+
+        Self::from_message(message).unwrap_or(if operation.contains("connection") {
+            Self::ConnectionTimeout
+        } else {
+            Self::Other
+        })
+    }
+
+    /// Classify a Diesel error
+    #[cfg(feature = "database")]
+    pub fn from_diesel(error: &diesel::result::Error) -> Self {
+        use diesel::result::{DatabaseErrorKind as Kind, Error};
+
+        match error {
+            Error::DatabaseError(kind, info) => match kind {
+                Kind::UniqueViolation
+                | Kind::ForeignKeyViolation
+                | Kind::NotNullViolation
+                | Kind::CheckViolation => Self::ConstraintViolation,
+                Kind::SerializationFailure => Self::Deadlock,
+                Kind::ClosedConnection => Self::ConnectionTimeout,
+                // Postgres deadlocks (40P01) and statement timeouts have no
+                // kind of their own
+                _ => Self::from_message(info.message()).unwrap_or(Self::Other),
+            },
+            Error::BrokenTransactionManager => Self::ConnectionTimeout,
+            _ => Self::Other,
+        }
+    }
+
+    /// Recognize a failure from the server's message
+    fn from_message(message: &str) -> Option<Self> {
+        let message = message.to_lowercase();
+        if message.contains("deadlock detected") || message.contains("could not serialize access") {
+            Some(Self::Deadlock)
+        } else if message.contains("timed out waiting for connection")
+            || message.contains("statement timeout")
+            || message.contains("lock timeout")
+        {
+            Some(Self::ConnectionTimeout)
+        } else if message.contains("violates") && message.contains("constraint") {
+            Some(Self::ConstraintViolation)
+        } else {
+            None
+        }
+    }
+}
+
+impl WorkflowError {
+    /// The kind of database failure, for [`WorkflowError::DatabaseError`]s
+    pub fn database_error_kind(&self) -> Option<DatabaseErrorKind> {
+        match self {
+            Self::DatabaseError {
+                message,
+                operation,
+                source,
+                ..
+            } => Some(DatabaseErrorKind::classify(message, operation, source.as_deref())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorExt;
+
+    #[test]
+    fn test_kinds_from_message_and_operation() {
+        let deadlock = WorkflowError::database_error(
+            "deadlock detected: process 4242 waits for ShareLock",
+            "UPDATE",
+            Some("workflow_instances".to_string()),
+        );
+        assert_eq!(deadlock.database_error_kind(), Some(DatabaseErrorKind::Deadlock));
+        assert_eq!(ErrorExt::category(&deadlock), ErrorCategory::Transient);
+
+        let pool = WorkflowError::database_error("pool exhausted", "connection", None);
+        assert_eq!(pool.database_error_kind(), Some(DatabaseErrorKind::ConnectionTimeout));
+
+        let other = WorkflowError::database_error("syntax error at or near", "SELECT", None);
+        assert_eq!(other.database_error_kind(), Some(DatabaseErrorKind::Other));
+        assert_eq!(ErrorExt::category(&other), ErrorCategory::System);
+
+        assert_eq!(WorkflowError::CycleDetected.database_error_kind(), None);
+    }
+
+    #[cfg(feature = "database")]
+    #[test]
+    fn test_diesel_deadlock_is_transient_and_unique_violation_permanent() {
+        use crate::error::RetryableError;
+        use diesel::result::{DatabaseErrorKind as Kind, Error};
+
+        let deadlock: WorkflowError = Error::DatabaseError(
+            Kind::Unknown,
+            Box::new("deadlock detected".to_string()),
+        )
+        .into();
+        assert_eq!(deadlock.database_error_kind(), Some(DatabaseErrorKind::Deadlock));
+        assert_eq!(ErrorExt::category(&deadlock), ErrorCategory::Transient);
+        assert!(RetryableError::is_retryable(&deadlock));
+
+        let duplicate: WorkflowError = Error::DatabaseError(
+            Kind::UniqueViolation,
+            Box::new("duplicate key value violates unique constraint \"users_email_key\"".to_string()),
+        )
+        .into();
+        assert_eq!(duplicate.database_error_kind(), Some(DatabaseErrorKind::ConstraintViolation));
+        assert_eq!(ErrorExt::category(&duplicate), ErrorCategory::Permanent);
+        assert_eq!(RetryableError::category(&duplicate), ErrorCategory::Permanent);
+        assert!(!RetryableError::is_retryable(&duplicate));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_retries_deadlocks_only() {
+        use crate::error::RetryPolicy;
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::time::Duration;
+
+        let policy = RetryPolicy::fixed(3, Duration::from_millis(1));
+
+        let attempts = AtomicU32::new(0);
+        let result = crate::error::retry_with_policy(&policy, || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(WorkflowError::database_error("deadlock detected", "UPDATE", None))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), _> = crate::error::retry_with_policy(&policy, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(WorkflowError::database_error(
+                "duplicate key value violates unique constraint \"users_email_key\"",
+                "INSERT",
+                Some("users".to_string()),
+            ))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -152,6 +152,8 @@ pub fn record_error(error: &WorkflowError, category: ErrorCategory, severity: Er
         WorkflowError::WorkflowTypeMismatch { .. } => "WorkflowTypeMismatch",
         WorkflowError::ApiError { .. } => "ApiError",
         WorkflowError::RuntimeError { .. } => "RuntimeError",
+        WorkflowError::NodeTimeout { .. } => "NodeTimeout",
+        WorkflowError::Cancelled { .. } => "Cancelled",
         WorkflowError::MCPError { .. } => "MCPError",
         WorkflowError::MCPConnectionError { .. } => "MCPConnectionError",
         WorkflowError::MCPProtocolError { .. } => "MCPProtocolError",
@@ -162,6 +164,7 @@ pub fn record_error(error: &WorkflowError, category: ErrorCategory, severity: Er
         WorkflowError::InvalidInput { .. } => "InvalidInput",
         WorkflowError::CrossSystemError { .. } => "CrossSystemError",
         WorkflowError::ConfigurationError { .. } => "ConfigurationError",
+        WorkflowError::NodeError { .. } => "NodeError",
     };
     
     let error_code = get_error_code(error);
@@ -227,6 +230,7 @@ fn get_error_code(error: &WorkflowError) -> String {
         WorkflowError::InvalidInput { .. } => "INPUT_001",
         WorkflowError::NodeNotFound { .. } => "NODE_404",
         WorkflowError::ProcessingError { .. } => "PROC_001",
+        WorkflowError::NodeTimeout { .. } => "NODE_TIMEOUT_001",
         WorkflowError::SerializationError { .. } => "SER_001",
         WorkflowError::DeserializationError { .. } => "DESER_001",
         _ => "UNKNOWN_001",
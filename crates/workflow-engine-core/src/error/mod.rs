@@ -18,6 +18,7 @@
 //! 5. **Recovery Strategies**: Fallback mechanisms for graceful degradation
 
 pub mod types;
+pub mod database;
 pub mod retry;
 pub mod circuit_breaker;
 pub mod context;
@@ -28,6 +29,7 @@ pub mod metrics;
 
 // Re-export core types
 pub use types::WorkflowError;
+pub use database::DatabaseErrorKind;
 pub use retry::{RetryPolicy, RetryableError, retry_with_policy, RetryBuilder};
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
 pub use context::{ErrorContext, ErrorContextExt};
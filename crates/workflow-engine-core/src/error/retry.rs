@@ -3,7 +3,7 @@
 //! This module provides configurable retry logic for handling transient failures
 //! with exponential backoff and jitter to prevent thundering herd problems.
 
-use super::{WorkflowError, ErrorCategory};
+use super::{WorkflowError, ErrorCategory, DatabaseErrorKind};
 use std::future::Future;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -139,8 +139,13 @@ impl RetryableError for WorkflowError {
             // Transient errors - can be retried
             WorkflowError::MCPConnectionError { .. } |
             WorkflowError::MCPTransportError { .. } |
-            WorkflowError::ApiError { .. } |
-            WorkflowError::DatabaseError { .. } => ErrorCategory::Transient,
+            WorkflowError::ApiError { .. } => ErrorCategory::Transient,
+
+            // Constraint violations fail again however often they're retried
+            WorkflowError::DatabaseError { .. } => match self.database_error_kind() {
+                Some(DatabaseErrorKind::ConstraintViolation) => ErrorCategory::Permanent,
+                _ => ErrorCategory::Transient,
+            },
             
             // Permanent errors - should not be retried
             WorkflowError::CycleDetected |
@@ -150,6 +155,7 @@ impl RetryableError for WorkflowError {
             WorkflowError::ValidationError { .. } |
             WorkflowError::InvalidStepType { .. } |
             WorkflowError::InvalidInput { .. } |
+            WorkflowError::NodeTimeout { deadline_exceeded: true, .. } |
             WorkflowError::ConfigurationError { .. } => ErrorCategory::Permanent,
             
             // System errors - may be retryable
@@ -157,6 +163,9 @@ impl RetryableError for WorkflowError {
             WorkflowError::SerializationError { .. } |
             WorkflowError::DeserializationError { .. } |
             WorkflowError::RuntimeError { .. } => ErrorCategory::System,
+
+            // Nodes classify their own errors
+            WorkflowError::NodeError { category, .. } => *category,
             
             // Other errors - check message for clues
             _ => ErrorCategory::System,
@@ -166,13 +175,18 @@ impl RetryableError for WorkflowError {
 
 /// Check if an error is retryable based on its type
 fn is_retryable_error(error: &WorkflowError) -> bool {
-    matches!(
-        error,
-        WorkflowError::MCPConnectionError { .. } |
-        WorkflowError::MCPTransportError { .. } |
-        WorkflowError::ApiError { .. } |
-        WorkflowError::DatabaseError { .. }
-    )
+    match error {
+        WorkflowError::DatabaseError { .. } => {
+            error.database_error_kind() != Some(DatabaseErrorKind::ConstraintViolation)
+        }
+        _ => matches!(
+            error,
+            WorkflowError::MCPConnectionError { .. } |
+            WorkflowError::MCPTransportError { .. } |
+            WorkflowError::ApiError { .. } |
+            WorkflowError::NodeError { category: ErrorCategory::Transient, .. }
+        ),
+    }
 }
 
 /// Retry an async operation with the given policy
@@ -218,6 +218,7 @@
 //! The [`WorkflowError`] type implements [`From`] for common error types:
 //!
 //! - `diesel::result::Error` → `WorkflowError::DatabaseError`
+//! - `diesel::r2d2::PoolError` → `WorkflowError::DatabaseError`
 //! - `reqwest::Error` → `WorkflowError::ApiError`
 //! - `serde_json::Error` → `WorkflowError::SerializationError`
 //! - `TransportError` → `WorkflowError::MCPTransportError`
@@ -502,6 +503,35 @@ pub enum WorkflowError {
         message: String 
     },
 
+    /// Node did not finish within its time budget.
+    ///
+    /// This error occurs when a node exceeds its timeout or when the
+    /// workflow's overall deadline passes before or during the node.
+    ///
+    /// # Fields
+    /// - `node_name` - Name of the node that timed out
+    /// - `timeout_ms` - Time budget the node was given, in milliseconds
+    /// - `deadline_exceeded` - Whether the budget was the workflow's remaining deadline
+    #[error("Node '{node_name}' timed out after {timeout_ms}ms{}", if *deadline_exceeded { " (workflow deadline exceeded)" } else { "" })]
+    NodeTimeout {
+        /// Name of the node that timed out
+        node_name: String,
+        /// Time budget the node was given, in milliseconds
+        timeout_ms: u64,
+        /// Whether the workflow's overall deadline was exceeded
+        deadline_exceeded: bool,
+    },
+
+    /// The run was cancelled before or during a node.
+    ///
+    /// # Fields
+    /// - `node_name` - Name of the node the run was cancelled at
+    #[error("Workflow run cancelled at node '{node_name}'")]
+    Cancelled {
+        /// Name of the node the run was cancelled at
+        node_name: String,
+    },
+
     /// General Model Context Protocol error.
     ///
     /// This error represents general MCP-related failures that don't
@@ -764,6 +794,31 @@ pub enum WorkflowError {
         #[source]
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
+
+    /// Node failure the node classified itself.
+    ///
+    /// Nodes return this error to tell the engine how their failure should
+    /// be handled: only [`ErrorCategory::Transient`](super::ErrorCategory::Transient)
+    /// failures are retried, user errors do not count against circuit
+    /// breakers, and the API maps the category to an HTTP status.
+    ///
+    /// # Fields
+    /// - `message` - Error details
+    /// - `node_type` - Node that failed
+    /// - `category` - How the failure should be handled
+    /// - `source` - Underlying error
+    #[error("{category:?} error in {node_type}: {message}")]
+    NodeError {
+        /// Details about the failure
+        message: String,
+        /// Node that failed
+        node_type: String,
+        /// How the failure should be handled
+        category: super::ErrorCategory,
+        /// Underlying error
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
 }
 
 #[cfg(feature = "database")]
@@ -784,6 +839,18 @@ impl From<diesel::result::Error> for WorkflowError {
     }
 }
 
+#[cfg(feature = "database")]
+impl From<diesel::r2d2::PoolError> for WorkflowError {
+    fn from(error: diesel::r2d2::PoolError) -> Self {
+        WorkflowError::DatabaseError {
+            message: format!("Connection pool error: {}", error),
+            operation: "connection".to_string(),
+            table: None,
+            source: Some(Box::new(error)),
+        }
+    }
+}
+
 // Add error variants to WorkflowError
 impl From<reqwest::Error> for WorkflowError {
     fn from(error: reqwest::Error) -> Self {
@@ -982,6 +1049,38 @@ impl WorkflowError {
         }
     }
 
+    /// Create a node error with an explicit category
+    pub fn classified_error(
+        message: impl Into<String>,
+        node_type: impl Into<String>,
+        category: super::ErrorCategory,
+    ) -> Self {
+        Self::NodeError {
+            message: message.into(),
+            node_type: node_type.into(),
+            category,
+            source: None,
+        }
+    }
+
+    /// Create a node error caused by bad input from the user. It is not
+    /// retried and maps to a client error.
+    pub fn user_error(message: impl Into<String>, node_type: impl Into<String>) -> Self {
+        Self::classified_error(message, node_type, super::ErrorCategory::User)
+    }
+
+    /// Create a node error caused by the node's infrastructure or
+    /// dependencies. It is not retried; use [`transient_error`](Self::transient_error)
+    /// for failures that may succeed on retry.
+    pub fn system_error(message: impl Into<String>, node_type: impl Into<String>) -> Self {
+        Self::classified_error(message, node_type, super::ErrorCategory::System)
+    }
+
+    /// Create a node error that may succeed on retry
+    pub fn transient_error(message: impl Into<String>, node_type: impl Into<String>) -> Self {
+        Self::classified_error(message, node_type, super::ErrorCategory::Transient)
+    }
+
     /// Create an API error with full context
     pub fn api_error(
         message: impl Into<String>,
@@ -1098,6 +1197,31 @@ impl WorkflowError {
         }
     }
 
+    /// Create a node timeout error for a node that exceeded its own timeout
+    pub fn node_timeout(node_name: impl Into<String>, timeout: std::time::Duration) -> Self {
+        Self::NodeTimeout {
+            node_name: node_name.into(),
+            timeout_ms: timeout.as_millis() as u64,
+            deadline_exceeded: false,
+        }
+    }
+
+    /// Create a node timeout error for a node cut off by the workflow deadline
+    pub fn deadline_exceeded(node_name: impl Into<String>, budget: std::time::Duration) -> Self {
+        Self::NodeTimeout {
+            node_name: node_name.into(),
+            timeout_ms: budget.as_millis() as u64,
+            deadline_exceeded: true,
+        }
+    }
+
+    /// Create an error for a run cancelled at `node_name`
+    pub fn cancelled(node_name: impl Into<String>) -> Self {
+        Self::Cancelled {
+            node_name: node_name.into(),
+        }
+    }
+
     /// Create a cross-system error
     pub fn cross_system_error(
         message: impl Into<String>,
@@ -1297,11 +1421,19 @@ impl super::ErrorExt for WorkflowError {
             Self::ApiError { .. } => {
                 ErrorCategory::Transient
             }
-            Self::DatabaseError { operation, .. } if operation.contains("connection") => {
+            // Deadlocks and timeouts are transient, constraint violations
+            // permanent, anything else a system error
+            Self::DatabaseError { .. } => {
+                self.database_error_kind().map_or(ErrorCategory::System, |kind| kind.category())
+            }
+            
+            Self::NodeTimeout { deadline_exceeded: false, .. } => {
                 ErrorCategory::Transient
             }
             
             // Permanent errors that won't succeed on retry
+            Self::NodeTimeout { deadline_exceeded: true, .. } |
+            Self::Cancelled { .. } |
             Self::CycleDetected |
             Self::UnreachableNodes { .. } |
             Self::InvalidRouter { .. } |
@@ -1320,13 +1452,14 @@ impl super::ErrorExt for WorkflowError {
             }
             
             // System errors (infrastructure, dependencies)
-            Self::DatabaseError { .. } |
             Self::SerializationError { .. } |
             Self::RuntimeError { .. } |
             Self::CrossSystemError { .. } => {
                 ErrorCategory::System
             }
             
+            Self::NodeError { category, .. } => *category,
+
             // Business logic errors
             Self::ProcessingError { .. } |
             Self::RegistryError { .. } |
@@ -1354,6 +1487,7 @@ impl super::ErrorExt for WorkflowError {
             Self::RegistryError { .. } |
             Self::MCPConnectionError { .. } |
             Self::CrossSystemError { .. } |
+            Self::NodeTimeout { .. } |
             Self::RuntimeError { .. } => {
                 ErrorSeverity::Error
             }
@@ -1371,6 +1505,7 @@ impl super::ErrorExt for WorkflowError {
             }
             
             // Info - validation and user input errors
+            Self::Cancelled { .. } |
             Self::ValidationError { .. } |
             Self::DeserializationError { .. } |
             Self::WorkflowTypeMismatch { .. } |
@@ -1379,6 +1514,16 @@ impl super::ErrorExt for WorkflowError {
             Self::ConfigurationError { .. } => {
                 ErrorSeverity::Info
             }
+
+            Self::NodeError { category, .. } => match category {
+                super::ErrorCategory::User => ErrorSeverity::Info,
+                super::ErrorCategory::Transient | super::ErrorCategory::Business => {
+                    ErrorSeverity::Warning
+                }
+                super::ErrorCategory::Permanent | super::ErrorCategory::System => {
+                    ErrorSeverity::Error
+                }
+            },
         }
     }
     
@@ -1395,6 +1540,8 @@ impl super::ErrorExt for WorkflowError {
             Self::WorkflowTypeMismatch { .. } => "WF_TYPE_MISMATCH",
             Self::ApiError { .. } => "WF_API_ERROR",
             Self::RuntimeError { .. } => "WF_RUNTIME_ERROR",
+            Self::NodeTimeout { .. } => "WF_NODE_TIMEOUT",
+            Self::Cancelled { .. } => "WF_CANCELLED",
             Self::MCPError { .. } => "WF_MCP_ERROR",
             Self::MCPConnectionError { .. } => "WF_MCP_CONNECTION_ERROR",
             Self::MCPProtocolError { .. } => "WF_MCP_PROTOCOL_ERROR",
@@ -1405,6 +1552,7 @@ impl super::ErrorExt for WorkflowError {
             Self::InvalidInput { .. } => "WF_INVALID_INPUT",
             Self::CrossSystemError { .. } => "WF_CROSS_SYSTEM_ERROR",
             Self::ConfigurationError { .. } => "WF_CONFIGURATION_ERROR",
+            Self::NodeError { .. } => "WF_NODE_ERROR",
         }
     }
 }
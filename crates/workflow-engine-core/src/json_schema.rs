@@ -0,0 +1,351 @@
+//! Validation of JSON values against JSON Schemas
+//!
+//! [`SchemaValidator`] checks values against the subset of JSON Schema used
+//! for request bodies and workflow inputs: `type`, `enum`, `required`,
+//! `properties`, `additionalProperties`, `items`, `minimum`, `maximum`, the
+//! `uuid` and `date-time` formats, and `allOf`, `anyOf` and `oneOf`. Other
+//! keywords are ignored. The first violation found is reported with the path
+//! to the offending value, e.g. `config.timeout` or `steps[2].name`.
+//!
+//! ```rust
+//! use serde_json::json;
+//! use workflow_engine_core::json_schema::SchemaValidator;
+//!
+//! let validator = SchemaValidator::new(json!({
+//!     "type": "object",
+//!     "required": ["ticket_id"],
+//!     "properties": { "ticket_id": { "type": "integer" } }
+//! }));
+//! let violation = validator.validate(&json!({ "ticket_id": "42" })).unwrap_err();
+//! assert_eq!(violation.field, "ticket_id");
+//! assert_eq!(violation.constraint, "type");
+//! ```
+
+use serde_json::Value;
+
+use crate::error::WorkflowError;
+
+/// Name given to the validated value itself unless configured otherwise
+pub const DEFAULT_ROOT_FIELD: &str = "input";
+
+/// A violation of a schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// Path to the offending value, e.g. `config.timeout`
+    pub field: String,
+    /// The schema keyword that failed, e.g. `type` or `required`
+    pub constraint: String,
+    pub message: String,
+}
+
+impl SchemaViolation {
+    pub fn new(field: impl Into<String>, constraint: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            constraint: constraint.into(),
+            message: message.into(),
+        }
+    }
+
+    /// The violation as a [`WorkflowError::ValidationError`] with `context`,
+    /// e.g. `"in request body"`
+    pub fn into_error(self, context: impl Into<String>) -> WorkflowError {
+        WorkflowError::validation_error(self.message, self.field, self.constraint, context)
+    }
+}
+
+/// Validates values against a JSON Schema.
+///
+/// `$ref`s are resolved as JSON pointers into the document the validator was
+/// created with, so a schema can refer to its own `definitions`, and an
+/// OpenAPI spec to its `components.schemas`. A `$ref` that leads back to
+/// itself without moving deeper into the value, such as `{"$ref": "#"}`, is
+/// reported as a `$ref` violation.
+#[derive(Debug, Clone)]
+pub struct SchemaValidator {
+    document: Value,
+    root: String,
+}
+
+impl SchemaValidator {
+    pub fn new(document: Value) -> Self {
+        Self {
+            document,
+            root: DEFAULT_ROOT_FIELD.to_string(),
+        }
+    }
+
+    /// Name violations of the validated value itself with `name` rather
+    /// than [`DEFAULT_ROOT_FIELD`]
+    pub fn with_root_name(mut self, name: impl Into<String>) -> Self {
+        self.root = name.into();
+        self
+    }
+
+    pub fn document(&self) -> &Value {
+        &self.document
+    }
+
+    /// Check `value` against the whole document
+    pub fn validate(&self, value: &Value) -> Result<(), SchemaViolation> {
+        self.validate_against(&self.document, value, "")
+    }
+
+    /// Check `value`, found at `field`, against `schema`, a part of the
+    /// document
+    pub fn validate_against(&self, schema: &Value, value: &Value, field: &str) -> Result<(), SchemaViolation> {
+        self.check(schema, value, field, &[])
+    }
+
+    /// Check `value` against `schema`, where `refs` are the `$ref`s already
+    /// followed for this same value. A `$ref` met again is circular: it would
+    /// be followed forever without moving deeper into the value.
+    fn check<'a>(
+        &'a self,
+        schema: &'a Value,
+        value: &Value,
+        field: &str,
+        refs: &[&'a str],
+    ) -> Result<(), SchemaViolation> {
+        let (schema, refs) = self.resolve(schema, field, refs)?;
+
+        if let Some(all) = schema["allOf"].as_array() {
+            for sub in all {
+                self.check(sub, value, field, &refs)?;
+            }
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(alternatives) = schema[keyword].as_array() {
+                // When no alternative matches, report the violation found
+                // deepest in the value, which is the most specific one
+                let mut errors = Vec::new();
+                for sub in alternatives {
+                    match self.check(sub, value, field, &refs) {
+                        Ok(()) => {
+                            errors.clear();
+                            break;
+                        }
+                        Err(e) => errors.push(e),
+                    }
+                }
+                if let Some(error) = errors.into_iter().max_by_key(|e| e.field.len()) {
+                    return Err(error);
+                }
+            }
+        }
+
+        if let Some(allowed) = schema["enum"].as_array() {
+            if !allowed.contains(value) {
+                let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                return Err(self.violation(
+                    field,
+                    "enum",
+                    format!("must be one of {}", allowed.join(", ")),
+                ));
+            }
+        }
+
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|name| is_type(value, name)) {
+            return Err(self.violation(
+                field,
+                "type",
+                format!("expected {}, found {}", types.join(" or "), type_name(value)),
+            ));
+        }
+
+        match value {
+            Value::Object(object) => {
+                if let Some(required) = schema["required"].as_array() {
+                    for name in required.iter().filter_map(Value::as_str) {
+                        if !object.contains_key(name) {
+                            return Err(self.violation(&child(field, name), "required", "missing required field"));
+                        }
+                    }
+                }
+                for (name, item) in object {
+                    let item_field = child(field, name);
+                    match (&schema["properties"][name], &schema["additionalProperties"]) {
+                        (Value::Null, Value::Bool(false)) => {
+                            return Err(self.violation(&item_field, "additionalProperties", "unknown field"));
+                        }
+                        (Value::Null, Value::Object(_)) => {
+                            self.check(&schema["additionalProperties"], item, &item_field, &[])?
+                        }
+                        (Value::Null, _) => {}
+                        (property, _) => self.check(property, item, &item_field, &[])?,
+                    }
+                }
+            }
+            Value::Array(items) if !schema["items"].is_null() => {
+                for (i, item) in items.iter().enumerate() {
+                    self.check(&schema["items"], item, &format!("{}[{}]", field, i), &[])?;
+                }
+            }
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if schema["minimum"].as_f64().is_some_and(|minimum| number < minimum) {
+                    return Err(self.violation(
+                        field,
+                        "minimum",
+                        format!("must be at least {}", schema["minimum"]),
+                    ));
+                }
+                if schema["maximum"].as_f64().is_some_and(|maximum| number > maximum) {
+                    return Err(self.violation(
+                        field,
+                        "maximum",
+                        format!("must be at most {}", schema["maximum"]),
+                    ));
+                }
+            }
+            Value::String(text) => {
+                let valid = match schema["format"].as_str() {
+                    Some("uuid") => uuid::Uuid::parse_str(text).is_ok(),
+                    Some("date-time") => chrono::DateTime::parse_from_rfc3339(text).is_ok(),
+                    _ => true,
+                };
+                if !valid {
+                    return Err(self.violation(
+                        field,
+                        "format",
+                        format!("must be a valid {}", schema["format"].as_str().unwrap_or_default()),
+                    ));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn violation(&self, field: &str, constraint: &str, message: impl Into<String>) -> SchemaViolation {
+        let field = if field.is_empty() { self.root.as_str() } else { field };
+        SchemaViolation::new(field, constraint, message)
+    }
+
+    /// Follow `$ref`s into the document, adding them to `refs`, until a
+    /// schema without one is reached
+    fn resolve<'a>(
+        &'a self,
+        mut schema: &'a Value,
+        field: &str,
+        refs: &[&'a str],
+    ) -> Result<(&'a Value, Vec<&'a str>), SchemaViolation> {
+        let mut refs = refs.to_vec();
+        while let Some(reference) = schema["$ref"].as_str() {
+            if refs.contains(&reference) {
+                return Err(self.violation(field, "$ref", format!("circular $ref '{}'", reference)));
+            }
+            match reference.strip_prefix('#').and_then(|pointer| self.document.pointer(pointer)) {
+                Some(target) => {
+                    refs.push(reference);
+                    schema = target;
+                }
+                None => break,
+            }
+        }
+        Ok((schema, refs))
+    }
+}
+
+fn child(field: &str, name: &str) -> String {
+    if field.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", field, name)
+    }
+}
+
+fn is_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "string" => value.is_string(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_violations_name_the_offending_field() {
+        let validator = SchemaValidator::new(json!({
+            "type": "object",
+            "required": ["steps"],
+            "properties": {
+                "steps": { "type": "array", "items": { "$ref": "#/definitions/step" } }
+            },
+            "definitions": {
+                "step": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "retries": { "type": "integer", "minimum": 0 } }
+                }
+            }
+        }));
+
+        assert!(validator.validate(&json!({ "steps": [{ "name": "a" }] })).is_ok());
+        let violation = validator
+            .validate(&json!({ "steps": [{ "name": "a" }, { "name": "b", "retries": -1 }] }))
+            .unwrap_err();
+        assert_eq!(violation.field, "steps[1].retries");
+        assert_eq!(violation.constraint, "minimum");
+        assert_eq!(validator.validate(&json!({})).unwrap_err().field, "steps");
+
+        let violation = validator.validate(&json!([])).unwrap_err();
+        assert_eq!(violation, SchemaViolation::new("input", "type", "expected object, found array"));
+        let renamed = validator.with_root_name("body").validate(&json!(1)).unwrap_err();
+        assert_eq!(renamed.field, "body");
+    }
+
+    #[test]
+    fn test_circular_refs_are_violations() {
+        let violation = SchemaValidator::new(json!({ "$ref": "#" })).validate(&json!({})).unwrap_err();
+        assert_eq!(violation, SchemaViolation::new("input", "$ref", "circular $ref '#'"));
+
+        let validator = SchemaValidator::new(json!({
+            "properties": { "config": { "$ref": "#/definitions/a" } },
+            "definitions": {
+                "a": { "$ref": "#/definitions/b" },
+                "b": { "allOf": [{ "$ref": "#/definitions/a" }] }
+            }
+        }));
+        let violation = validator.validate(&json!({ "config": {} })).unwrap_err();
+        assert_eq!(violation.field, "config");
+        assert_eq!(violation.constraint, "$ref");
+
+        // Recursion that moves deeper into the value is not circular
+        let tree = SchemaValidator::new(json!({
+            "type": "object",
+            "properties": {
+                "label": { "type": "string" },
+                "children": { "type": "array", "items": { "allOf": [{ "$ref": "#" }] } }
+            }
+        }));
+        let nested = json!({ "label": "a", "children": [{ "label": "b", "children": [{ "label": 3 }] }] });
+        assert_eq!(tree.validate(&nested).unwrap_err().field, "children[0].children[0].label");
+    }
+}
@@ -10,8 +10,10 @@
 //! ## Features
 //! 
 //! - `database` - Enables database integration with Diesel ORM
-//! - `monitoring` - Enables Prometheus metrics collection  
+//! - `monitoring` - Enables Prometheus metrics collection, including the
+//!   [`PrometheusRecorder`](metrics::PrometheusRecorder) metrics backend
 //! - `aws` - Enables AWS Bedrock AI integration
+//! - `testing` - Enables the in-memory workflow test harness
 //! - `full` - Enables all optional features
 //! 
 //! ## Core Concepts
@@ -56,6 +58,8 @@ pub mod ai;
 pub mod auth;
 pub mod models;
 pub mod config;
+pub mod metrics;
+pub mod json_schema;
 #[cfg(feature = "streaming")]
 #[cfg_attr(docsrs, doc(cfg(feature = "streaming")))]
 pub mod streaming;
@@ -64,6 +68,9 @@ pub mod streaming;
 #[cfg(feature = "database")]
 #[cfg_attr(docsrs, doc(cfg(feature = "database")))]
 pub mod registry;
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
 
 
 // Monitoring is now in the API crate
@@ -76,6 +83,7 @@ pub use nodes::{
     type_safe::{NodeId, TypedNodeConfig, TypedWorkflowBuilder, TypedWorkflow}
 };
 pub use workflow::builder::WorkflowBuilder;
+pub use workflow::shared_state::SharedState;
 
 // Feature-specific re-exports are now in respective crates
 
@@ -87,7 +95,7 @@ pub mod prelude {
     pub use crate::{
         Node, Router, ParallelNode, AsyncNode, AsyncNodeAdapter,
         NodeId, TypedNodeConfig, TypedWorkflowBuilder, TypedWorkflow,
-        TaskContext, WorkflowError, Result, WorkflowBuilder,
+        TaskContext, WorkflowError, Result, WorkflowBuilder, SharedState,
     };
     pub use async_trait::async_trait;
     pub use serde_json::{json, Value};
@@ -0,0 +1,210 @@
+//! # Metrics Facade
+//!
+//! The engine records metrics through the [`MetricsRecorder`] trait rather
+//! than a particular backend, so deployments can plug in whichever metrics
+//! system they run. Workflows use [`NoopRecorder`] unless given another one
+//! with [`Workflow::with_metrics`](crate::workflow::Workflow::with_metrics).
+//! With the `monitoring` feature, [`PrometheusRecorder`] records into a
+//! Prometheus registry.
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use workflow_engine_core::metrics::{MetricsRecorder, NoopRecorder, NODE_RETRIES_TOTAL};
+//!
+//! let recorder: Arc<dyn MetricsRecorder> = Arc::new(NoopRecorder);
+//! recorder.increment_counter(NODE_RETRIES_TOTAL, &[("workflow", "orders"), ("node", "Charge")], 1);
+//! ```
+
+use std::fmt;
+
+/// Histogram of node processing time, labelled by `workflow`, `node` and `status`
+pub const NODE_DURATION_SECONDS: &str = "workflow_node_duration_seconds";
+
+/// Counter of node retries, labelled by `workflow` and `node`
+pub const NODE_RETRIES_TOTAL: &str = "workflow_node_retries_total";
+
+/// Counter of node failures, labelled by `workflow`, `node` and `error_code`
+pub const NODE_ERRORS_TOTAL: &str = "workflow_node_errors_total";
+
+/// Histogram of the serialized size of a run's context after each node,
+/// labelled by `workflow` and `node`
+pub const CONTEXT_SIZE_BYTES: &str = "workflow_context_size_bytes";
+
+/// Counter of runs whose context outgrew the warning threshold, labelled by
+/// `workflow` and the `node` that had grown it most
+pub const CONTEXT_SIZE_WARNINGS_TOTAL: &str = "workflow_context_size_warnings_total";
+
+/// Labels attached to a metric, as name/value pairs
+pub type Labels<'a> = &'a [(&'a str, &'a str)];
+
+/// Records metrics to some backend.
+///
+/// A metric name should always be recorded with the same label names.
+pub trait MetricsRecorder: Send + Sync + fmt::Debug {
+    /// Add `value` to a counter
+    fn increment_counter(&self, name: &str, labels: Labels<'_>, value: u64);
+
+    /// Set a gauge to `value`
+    fn set_gauge(&self, name: &str, labels: Labels<'_>, value: f64);
+
+    /// Record an observation in a histogram
+    fn record_histogram(&self, name: &str, labels: Labels<'_>, value: f64);
+}
+
+/// Discards all metrics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopRecorder;
+
+impl MetricsRecorder for NoopRecorder {
+    fn increment_counter(&self, _name: &str, _labels: Labels<'_>, _value: u64) {}
+
+    fn set_gauge(&self, _name: &str, _labels: Labels<'_>, _value: f64) {}
+
+    fn record_histogram(&self, _name: &str, _labels: Labels<'_>, _value: f64) {}
+}
+
+#[cfg(feature = "monitoring")]
+pub use prometheus_recorder::PrometheusRecorder;
+
+#[cfg(feature = "monitoring")]
+mod prometheus_recorder {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use prometheus::core::Collector;
+    use prometheus::{CounterVec, GaugeVec, HistogramOpts, HistogramVec, Opts, Registry};
+
+    use super::{Labels, MetricsRecorder};
+
+    /// Records metrics into a Prometheus [`Registry`].
+    ///
+    /// Each metric is registered the first time it is recorded, with the
+    /// label names it was recorded with. Recording it later with different
+    /// label names, or under a name the registry already uses for something
+    /// else, is logged and dropped.
+    #[derive(Debug, Default)]
+    pub struct PrometheusRecorder {
+        registry: Registry,
+        counters: Mutex<HashMap<String, CounterVec>>,
+        gauges: Mutex<HashMap<String, GaugeVec>>,
+        histograms: Mutex<HashMap<String, HistogramVec>>,
+    }
+
+    impl PrometheusRecorder {
+        pub fn new(registry: Registry) -> Self {
+            Self {
+                registry,
+                ..Default::default()
+            }
+        }
+
+        pub fn registry(&self) -> &Registry {
+            &self.registry
+        }
+
+        /// Find or register the metric `name`, then apply `record` to it
+        fn with_metric<M>(
+            &self,
+            metrics: &Mutex<HashMap<String, M>>,
+            name: &str,
+            labels: Labels<'_>,
+            create: impl FnOnce(&[&str]) -> prometheus::Result<M>,
+            record: impl FnOnce(&M, &[&str]) -> prometheus::Result<()>,
+        ) where
+            M: Collector + Clone + 'static,
+        {
+            let names: Vec<&str> = labels.iter().map(|(name, _)| *name).collect();
+            let values: Vec<&str> = labels.iter().map(|(_, value)| *value).collect();
+
+            let mut metrics = metrics.lock().unwrap();
+            let metric = match metrics.get(name) {
+                Some(metric) => metric,
+                None => {
+                    let metric = match create(&names) {
+                        Ok(metric) => metric,
+                        Err(e) => {
+                            tracing::warn!(metric = name, error = %e, "Invalid metric");
+                            return;
+                        }
+                    };
+                    if let Err(e) = self.registry.register(Box::new(metric.clone())) {
+                        tracing::warn!(metric = name, error = %e, "Failed to register metric");
+                        return;
+                    }
+                    metrics.entry(name.to_string()).or_insert(metric)
+                }
+            };
+            if let Err(e) = record(metric, &values) {
+                tracing::warn!(metric = name, labels = ?names, error = %e, "Failed to record metric");
+            }
+        }
+    }
+
+    impl MetricsRecorder for PrometheusRecorder {
+        fn increment_counter(&self, name: &str, labels: Labels<'_>, value: u64) {
+            self.with_metric(
+                &self.counters,
+                name,
+                labels,
+                |names| CounterVec::new(Opts::new(name, name), names),
+                |counter, values| {
+                    counter.get_metric_with_label_values(values)?.inc_by(value as f64);
+                    Ok(())
+                },
+            );
+        }
+
+        fn set_gauge(&self, name: &str, labels: Labels<'_>, value: f64) {
+            self.with_metric(
+                &self.gauges,
+                name,
+                labels,
+                |names| GaugeVec::new(Opts::new(name, name), names),
+                |gauge, values| {
+                    gauge.get_metric_with_label_values(values)?.set(value);
+                    Ok(())
+                },
+            );
+        }
+
+        fn record_histogram(&self, name: &str, labels: Labels<'_>, value: f64) {
+            self.with_metric(
+                &self.histograms,
+                name,
+                labels,
+                |names| HistogramVec::new(HistogramOpts::new(name, name), names),
+                |histogram, values| {
+                    histogram.get_metric_with_label_values(values)?.observe(value);
+                    Ok(())
+                },
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "monitoring"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_recorder_registers_metrics_on_first_use() {
+        let recorder = PrometheusRecorder::default();
+        recorder.increment_counter(NODE_RETRIES_TOTAL, &[("workflow", "orders"), ("node", "Charge")], 2);
+        recorder.increment_counter(NODE_RETRIES_TOTAL, &[("workflow", "orders"), ("node", "Charge")], 1);
+        recorder.record_histogram(NODE_DURATION_SECONDS, &[("node", "Charge")], 0.25);
+        recorder.set_gauge("workflow_active_runs", &[], 3.0);
+        // Different label names for an existing metric are dropped
+        recorder.increment_counter(NODE_RETRIES_TOTAL, &[("node", "Charge")], 1);
+
+        let families = recorder.registry().gather();
+        let family = |name: &str| families.iter().find(|f| f.name() == name).unwrap();
+
+        let retries = &family(NODE_RETRIES_TOTAL).get_metric()[0];
+        assert_eq!(retries.get_counter().get_value(), 3.0);
+        assert_eq!(
+            family(NODE_DURATION_SECONDS).get_metric()[0].get_histogram().get_sample_count(),
+            1
+        );
+        assert_eq!(family("workflow_active_runs").get_metric()[0].get_gauge().get_value(), 3.0);
+    }
+}
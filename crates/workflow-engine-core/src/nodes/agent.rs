@@ -5,7 +5,9 @@ use std::sync::Arc;
 use futures_util::stream::{Stream, StreamExt};
 use std::pin::Pin;
 
+use crate::ai::templates::TemplateManager;
 use crate::error::WorkflowError;
+use crate::json_schema::{SchemaValidator, SchemaViolation};
 // // use workflow_engine_mcp::clients::MCPClient;  // Removed to avoid circular dependency
 use crate::nodes::Node;
 use crate::task::TaskContext;
@@ -26,8 +28,15 @@ pub struct AgentConfig {
     pub model_provider: ModelProvider,
     pub model_name: String,
     pub mcp_server_uri: Option<String>,
+    /// ID of a template rendered in place of the model's response when the
+    /// provider can't be reached. Requires a template manager on the node.
+    pub ai_fallback_template: Option<String>,
 }
 
+/// How many times an agent node with an output schema asks the model again
+/// for a response that doesn't conform to it
+pub const DEFAULT_OUTPUT_RETRIES: u32 = 2;
+
 /// Base trait for agent nodes that process tasks using AI models
 #[async_trait]
 pub trait AgentNode: Node {
@@ -46,6 +55,10 @@ pub trait AgentNode: Node {
 pub struct BaseAgentNode {
     config: AgentConfig,
     client: Arc<reqwest::Client>,
+    template_manager: Option<Arc<TemplateManager>>,
+    model: Option<Arc<dyn ModelInstance>>,
+    output_schema: Option<SchemaValidator>,
+    output_retries: u32,
     // mcp_client: Option<Arc<tokio::sync::Mutex<Box<dyn MCPClient>>>>,
 }
 
@@ -54,10 +67,46 @@ impl BaseAgentNode {
         Self {
             config,
             client: Arc::new(reqwest::Client::new()),
+            template_manager: None,
+            model: None,
+            output_schema: None,
+            output_retries: DEFAULT_OUTPUT_RETRIES,
             // mcp_client: None,
         }
     }
 
+    /// Set the template manager the `ai_fallback_template` is rendered with
+    pub fn with_template_manager(mut self, template_manager: Arc<TemplateManager>) -> Self {
+        self.template_manager = Some(template_manager);
+        self
+    }
+
+    /// Send requests to `model` rather than the configured provider's API
+    pub fn with_model_instance(mut self, model: Arc<dyn ModelInstance>) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Require responses to be JSON conforming to `schema`, a JSON Schema.
+    ///
+    /// The JSON is extracted from the response, also when the model wraps it
+    /// in prose or a code fence, and stored as the `output` of the node's
+    /// `ai_response`. A response that isn't JSON or doesn't conform is sent
+    /// back to the model with the violation and the schema, up to
+    /// [`with_output_retries`](Self::with_output_retries) times, after which
+    /// the node fails with a [`WorkflowError::ValidationError`].
+    pub fn with_output_schema(mut self, schema: serde_json::Value) -> Self {
+        self.output_schema = Some(SchemaValidator::new(schema).with_root_name("output"));
+        self
+    }
+
+    /// How many times to ask again for a response conforming to the output
+    /// schema, [`DEFAULT_OUTPUT_RETRIES`] by default
+    pub fn with_output_retries(mut self, retries: u32) -> Self {
+        self.output_retries = retries;
+        self
+    }
+
     // MCP integration stub implementations - circular dependency prevents full implementation
     // These methods provide API compatibility until dependency architecture is refactored
     pub fn with_mcp_client(self, _mcp_client: Box<dyn std::any::Any + Send + Sync>) -> Self {
@@ -103,7 +152,10 @@ impl BaseAgentNode {
     }
     */
 
-    async fn get_model_instance(&self) -> Result<Box<dyn ModelInstance>, WorkflowError> {
+    async fn get_model_instance(&self) -> Result<Arc<dyn ModelInstance>, WorkflowError> {
+        if let Some(model) = &self.model {
+            return Ok(model.clone());
+        }
         match self.config.model_provider {
             ModelProvider::OpenAI => {
                 let instance = OpenAIModelInstance {
@@ -111,7 +163,7 @@ impl BaseAgentNode {
                     model_name: self.config.model_name.clone(),
                     system_prompt: self.config.system_prompt.clone(),
                 };
-                Ok(Box::new(instance))
+                Ok(Arc::new(instance))
             }
             ModelProvider::AzureOpenAI => {
                 // For now, Azure OpenAI uses the same implementation as OpenAI
@@ -121,7 +173,7 @@ impl BaseAgentNode {
                     model_name: self.config.model_name.clone(),
                     system_prompt: self.config.system_prompt.clone(),
                 };
-                Ok(Box::new(instance))
+                Ok(Arc::new(instance))
             }
             ModelProvider::Anthropic => {
                 let instance = AnthropicModelInstance {
@@ -129,7 +181,7 @@ impl BaseAgentNode {
                     model_name: self.config.model_name.clone(),
                     system_prompt: self.config.system_prompt.clone(),
                 };
-                Ok(Box::new(instance))
+                Ok(Arc::new(instance))
             }
             #[cfg(feature = "aws")]
             ModelProvider::Bedrock => {
@@ -137,7 +189,7 @@ impl BaseAgentNode {
                     model_name: self.config.model_name.clone(),
                     system_prompt: self.config.system_prompt.clone(),
                 };
-                Ok(Box::new(instance))
+                Ok(Arc::new(instance))
             }
             #[cfg(not(feature = "aws"))]
             ModelProvider::Bedrock => {
@@ -152,6 +204,79 @@ impl BaseAgentNode {
         }
     }
     
+    /// Render the `ai_fallback_template` in place of a response the
+    /// provider failed to give
+    fn render_fallback(
+        &self,
+        task_context: &TaskContext,
+        prompt: &str,
+        error: &WorkflowError,
+    ) -> Result<String, WorkflowError> {
+        let template_id = self.config.ai_fallback_template.as_deref().unwrap_or_default();
+        let template_manager = self.template_manager.as_ref().ok_or_else(|| {
+            WorkflowError::configuration_error_simple(
+                "ai_fallback_template requires a template manager on the agent node",
+            )
+        })?;
+        
+        let vars = std::collections::HashMap::from([
+            ("prompt".to_string(), serde_json::json!(prompt)),
+            ("data".to_string(), task_context.event_data.clone()),
+            ("workflow_context".to_string(), serde_json::json!(task_context.get_all_data())),
+            ("model".to_string(), serde_json::json!(self.config.model_name)),
+            ("error".to_string(), serde_json::json!(error.to_string())),
+        ]);
+        
+        template_manager
+            .render(template_id, &vars)
+            .map_err(|e| WorkflowError::ProcessingError {
+                message: format!("Failed to render AI fallback template: {}", e),
+                node_id: None,
+                node_type: "agent".to_string(),
+                source: Some(Box::new(e)),
+            })
+    }
+    
+    /// Ask `model` again until its response conforms to `validator`,
+    /// returning the conforming response and the JSON extracted from it
+    async fn conform_to_schema(
+        &self,
+        model: &dyn ModelInstance,
+        validator: &SchemaValidator,
+        prompt: &str,
+        mut response: String,
+    ) -> Result<(String, serde_json::Value), WorkflowError> {
+        let mut retries = 0;
+        loop {
+            let violation = match extract_json(&response) {
+                Some(output) => match validator.validate(&output) {
+                    Ok(()) => return Ok((response, output)),
+                    Err(violation) => violation,
+                },
+                None => SchemaViolation::new("output", "json", "response contains no JSON"),
+            };
+            if retries == self.output_retries {
+                return Err(violation.into_error(format!("in AI response after {} attempts", retries + 1)));
+            }
+            retries += 1;
+            tracing::warn!(
+                "Response from {} does not conform to the output schema ({}: {}), asking again",
+                self.config.model_name, violation.field, violation.message
+            );
+
+            let correction = format!(
+                "{}\n\nYour previous response was:\n{}\n\nIt was rejected because {}: {}. \
+                 Respond with only JSON conforming to this JSON Schema:\n{}",
+                prompt,
+                response,
+                violation.field,
+                violation.message,
+                serde_json::to_string_pretty(validator.document()).unwrap_or_default()
+            );
+            response = model.process_request(&correction).await?;
+        }
+    }
+
     /// Extract prompt from the task context
     fn extract_prompt_from_context(&self, task_context: &TaskContext) -> Result<String, WorkflowError> {
         // Try various common fields for the prompt
@@ -293,8 +418,6 @@ impl AgentNode for BaseAgentNode {
         &self,
         mut task_context: TaskContext,
     ) -> Result<TaskContext, WorkflowError> {
-        let model = self.get_model_instance().await?;
-        
         // Extract prompt from context
         let prompt = self.extract_prompt_from_context(&task_context)?;
         
@@ -302,15 +425,50 @@ impl AgentNode for BaseAgentNode {
         let enhanced_prompt = prompt;
         
         // Process the request with the model
-        let response = model.process_request(&enhanced_prompt).await?;
+        let (model, result) = match self.get_model_instance().await {
+            Ok(model) => {
+                let result = model.process_request(&enhanced_prompt).await;
+                (Some(model), result)
+            }
+            Err(e) => (None, Err(e)),
+        };
+        
+        let ai_response = match result {
+            Ok(response) => {
+                let mut ai_response = serde_json::json!({
+                    "response": response,
+                    "model": self.config.model_name.clone(),
+                    "provider": format!("{:?}", self.config.model_provider),
+                    "timestamp": chrono::Utc::now()
+                });
+                if let (Some(validator), Some(model)) = (&self.output_schema, &model) {
+                    let (response, output) = self
+                        .conform_to_schema(model.as_ref(), validator, &enhanced_prompt, response)
+                        .await?;
+                    ai_response["response"] = serde_json::json!(response);
+                    ai_response["output"] = output;
+                }
+                ai_response
+            }
+            Err(e) if self.config.ai_fallback_template.is_some() => {
+                tracing::warn!(
+                    "{:?} provider unavailable, using fallback template: {}",
+                    self.config.model_provider, e
+                );
+                serde_json::json!({
+                    "response": self.render_fallback(&task_context, &enhanced_prompt, &e)?,
+                    "model": self.config.model_name.clone(),
+                    "provider": format!("{:?}", self.config.model_provider),
+                    "timestamp": chrono::Utc::now(),
+                    "synthetic": true,
+                    "fallback_reason": e.to_string()
+                })
+            }
+            Err(e) => return Err(e),
+        };
         
         // Store the response in the task context
-        task_context.update_node("ai_response", serde_json::json!({
-            "response": response,
-            "model": self.config.model_name.clone(),
-            "provider": format!("{:?}", self.config.model_provider),
-            "timestamp": chrono::Utc::now()
-        }));
+        task_context.update_node("ai_response", ai_response);
         
         Ok(task_context)
     }
@@ -334,6 +492,27 @@ pub trait ModelInstance: Send + Sync + Debug {
     }
 }
 
+/// The JSON in a model's response: the whole response, the contents of a
+/// code fence, or the outermost object or array within surrounding prose
+fn extract_json(response: &str) -> Option<serde_json::Value> {
+    let response = response.trim();
+    if let Ok(value) = serde_json::from_str(response) {
+        return Some(value);
+    }
+    if let Some((_, fenced)) = response.split_once("```") {
+        let fenced = fenced.strip_prefix("json").unwrap_or(fenced);
+        if let Some(Ok(value)) = fenced.split_once("```").map(|(json, _)| serde_json::from_str(json.trim())) {
+            return Some(value);
+        }
+    }
+    ['{', '['].into_iter().find_map(|open| {
+        let close = if open == '{' { '}' } else { ']' };
+        let start = response.find(open)?;
+        let end = response.rfind(close)?;
+        serde_json::from_str(response.get(start..=end)?).ok()
+    })
+}
+
 /// Response chunk for streaming
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamChunk {
@@ -724,6 +903,7 @@ mod tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-4".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         assert_eq!(config.model_provider, ModelProvider::OpenAI);
@@ -737,6 +917,7 @@ mod tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-4".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -745,4 +926,153 @@ mod tests {
             ModelProvider::OpenAI
         );
     }
+
+    #[cfg(not(feature = "aws"))]
+    fn fallback_templates() -> Arc<TemplateManager> {
+        use crate::ai::templates::Template;
+
+        let mut manager = TemplateManager::new().unwrap();
+        manager
+            .register(Template::new("offline_reply", "[offline] We received: {{prompt}}").unwrap())
+            .unwrap();
+        Arc::new(manager)
+    }
+
+    /// Bedrock isn't compiled in without the `aws` feature, so its provider
+    /// is always unavailable
+    #[cfg(not(feature = "aws"))]
+    fn unavailable_provider_config(fallback: Option<&str>) -> AgentConfig {
+        AgentConfig {
+            system_prompt: "Test prompt".to_string(),
+            model_provider: ModelProvider::Bedrock,
+            model_name: "claude-v2".to_string(),
+            mcp_server_uri: None,
+            ai_fallback_template: fallback.map(str::to_string),
+        }
+    }
+
+    #[cfg(not(feature = "aws"))]
+    #[tokio::test]
+    async fn test_unavailable_provider_falls_back_to_template() {
+        let agent = BaseAgentNode::new(unavailable_provider_config(Some("offline_reply")))
+            .with_template_manager(fallback_templates());
+        let mut context = TaskContext::new("test".to_string(), serde_json::json!({}));
+        context.update_node("prompt", serde_json::json!("Where is my order?"));
+
+        let context = agent.process_with_ai(context).await.unwrap();
+        let ai_response: serde_json::Value = context.get_data("ai_response").unwrap().unwrap();
+        assert_eq!(ai_response["response"], "[offline] We received: Where is my order?");
+        assert_eq!(ai_response["synthetic"], true);
+        assert!(ai_response["fallback_reason"].as_str().unwrap().contains("aws"));
+    }
+
+    #[cfg(not(feature = "aws"))]
+    #[tokio::test]
+    async fn test_unavailable_provider_errors_without_fallback() {
+        let context = || TaskContext::new("test".to_string(), serde_json::json!({ "prompt": "Hi" }));
+
+        let agent = BaseAgentNode::new(unavailable_provider_config(None))
+            .with_template_manager(fallback_templates());
+        assert!(agent.process_with_ai(context()).await.is_err());
+
+        // A fallback template needs a manager to render it
+        let agent = BaseAgentNode::new(unavailable_provider_config(Some("offline_reply")));
+        assert!(matches!(
+            agent.process_with_ai(context()).await,
+            Err(WorkflowError::ConfigurationError { .. })
+        ));
+    }
+
+    /// Answers with scripted responses, recording the prompts it is sent
+    #[derive(Debug)]
+    struct ScriptedModel {
+        responses: std::sync::Mutex<Vec<&'static str>>,
+        prompts: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl ScriptedModel {
+        fn new(mut responses: Vec<&'static str>) -> Arc<Self> {
+            responses.reverse();
+            Arc::new(Self {
+                responses: std::sync::Mutex::new(responses),
+                prompts: std::sync::Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ModelInstance for ScriptedModel {
+        async fn process_request(&self, prompt: &str) -> Result<String, WorkflowError> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            Ok(self.responses.lock().unwrap().pop().expect("no scripted response left").to_string())
+        }
+    }
+
+    fn sentiment_agent(model: Arc<ScriptedModel>) -> BaseAgentNode {
+        let config = AgentConfig {
+            system_prompt: "Classify the sentiment of the message".to_string(),
+            model_provider: ModelProvider::OpenAI,
+            model_name: "gpt-4".to_string(),
+            mcp_server_uri: None,
+            ai_fallback_template: None,
+        };
+        BaseAgentNode::new(config)
+            .with_model_instance(model)
+            .with_output_schema(serde_json::json!({
+                "type": "object",
+                "required": ["sentiment", "confidence"],
+                "properties": {
+                    "sentiment": { "enum": ["positive", "neutral", "negative"] },
+                    "confidence": { "type": "number", "minimum": 0, "maximum": 1 }
+                }
+            }))
+    }
+
+    fn sentiment_context() -> TaskContext {
+        let mut context = TaskContext::new("test".to_string(), serde_json::json!({}));
+        context.update_node("prompt", serde_json::json!("I love it"));
+        context
+    }
+
+    #[tokio::test]
+    async fn test_nonconforming_output_is_retried_with_correction() {
+        let model = ScriptedModel::new(vec![
+            "The sentiment is clearly positive!",
+            "```json\n{\"sentiment\": \"happy\", \"confidence\": 0.9}\n```",
+            "Sure: {\"sentiment\": \"positive\", \"confidence\": 0.9}",
+        ]);
+
+        let context = sentiment_agent(model.clone())
+            .process_with_ai(sentiment_context())
+            .await
+            .unwrap();
+        let ai_response: serde_json::Value = context.get_data("ai_response").unwrap().unwrap();
+        assert_eq!(ai_response["output"], serde_json::json!({ "sentiment": "positive", "confidence": 0.9 }));
+
+        let prompts = model.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 3);
+        assert_eq!(prompts[0], "I love it");
+        assert!(prompts[1].contains("The sentiment is clearly positive!"));
+        assert!(prompts[1].contains("response contains no JSON"));
+        assert!(prompts[2].contains("sentiment: must be one of"));
+        assert!(prompts[2].contains("\"required\""));
+    }
+
+    #[tokio::test]
+    async fn test_output_still_nonconforming_after_retries_errors() {
+        let model = ScriptedModel::new(vec!["positive", "{\"sentiment\": \"positive\"}"]);
+
+        let result = sentiment_agent(model.clone())
+            .with_output_retries(1)
+            .process_with_ai(sentiment_context())
+            .await;
+        match result {
+            Err(WorkflowError::ValidationError { field, constraint, .. }) => {
+                assert_eq!(field, "confidence");
+                assert_eq!(constraint, "required");
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+        assert_eq!(model.prompts.lock().unwrap().len(), 2);
+    }
 }
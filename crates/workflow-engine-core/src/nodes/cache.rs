@@ -0,0 +1,386 @@
+// =============================================================================
+// Cached Nodes - Reuse the output of pure nodes for identical input
+// =============================================================================
+
+#![allow(clippy::result_large_err, reason = "cache lookups surface node failures as the crate-wide WorkflowError")]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::Node;
+use crate::error::WorkflowError;
+use crate::task::{TaskContext, TENANT_ID_KEY};
+
+/// How long cached outputs are kept unless configured otherwise
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// How many outputs an [`InMemoryCacheStore`] holds unless configured
+/// otherwise
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// Part of the context a [`CachedNode`]'s cache key is computed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheKeyInput {
+    /// The whole event data
+    EventData,
+    /// A top-level field of the event data
+    EventField(String),
+    /// The result stored by a previous node
+    NodeResult(String),
+    /// Every node result
+    AllNodeResults,
+    /// A metadata entry
+    Metadata(String),
+}
+
+/// What a node wrote to the context: the node results and metadata it added
+/// or changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CachedOutput {
+    pub nodes: HashMap<String, Value>,
+    pub metadata: HashMap<String, Value>,
+}
+
+impl CachedOutput {
+    /// The changes from `before` to `after`
+    fn between(before: &TaskContext, after: &TaskContext) -> Self {
+        let changed = |before: &HashMap<String, Value>, after: &HashMap<String, Value>| {
+            after
+                .iter()
+                .filter(|(key, value)| before.get(*key) != Some(*value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        };
+        Self {
+            nodes: changed(before.get_all_data(), after.get_all_data()),
+            metadata: changed(before.get_all_metadata(), after.get_all_metadata()),
+        }
+    }
+
+    fn apply(&self, task_context: &mut TaskContext) -> Result<(), WorkflowError> {
+        for (key, value) in &self.nodes {
+            task_context.update_node(key, value);
+        }
+        for (key, value) in &self.metadata {
+            task_context.set_metadata(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where a [`CachedNode`] keeps outputs
+pub trait NodeCacheStore: Send + Sync + fmt::Debug {
+    /// The output stored under `key`, unless it has expired
+    fn get(&self, key: &str) -> Option<CachedOutput>;
+
+    /// Store `output` under `key` for `ttl`
+    fn insert(&self, key: String, output: CachedOutput, ttl: Duration);
+}
+
+/// Process-local [`NodeCacheStore`] holding a bounded number of outputs.
+///
+/// Expired entries are dropped when they are next looked up, and all of them
+/// whenever an insert finds the store full. If it is still full, the entry
+/// closest to expiring makes room.
+#[derive(Debug)]
+pub struct InMemoryCacheStore {
+    /// Outputs with when they expire; `None` for TTLs too long to represent
+    entries: Mutex<HashMap<String, (Option<Instant>, CachedOutput)>>,
+    capacity: usize,
+}
+
+impl Default for InMemoryCacheStore {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+impl InMemoryCacheStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store holding at most `capacity` outputs
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl NodeCacheStore for InMemoryCacheStore {
+    fn get(&self, key: &str) -> Option<CachedOutput> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((expires_at, output)) if expires_at.is_none_or(|at| Instant::now() < at) => {
+                Some(output.clone())
+            }
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: String, output: CachedOutput, ttl: Duration) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.retain(|_, (expires_at, _)| expires_at.is_none_or(|at| now < at));
+            if entries.len() >= self.capacity {
+                let soonest = entries
+                    .iter()
+                    .min_by_key(|(_, (expires_at, _))| (expires_at.is_none(), *expires_at))
+                    .map(|(key, _)| key.clone());
+                if let Some(soonest) = soonest {
+                    entries.remove(&soonest);
+                }
+            }
+        }
+        entries.insert(key, (now.checked_add(ttl), output));
+    }
+}
+
+/// Runs a pure node once per distinct input, replaying its output for
+/// repeated input.
+///
+/// The cache key is a hash of the wrapped node's name and the configured
+/// [`CacheKeyInput`]s, by default the event data and every node result. On
+/// a hit the node results and metadata the node wrote last time are applied
+/// to the context and the node is not run. Failures are not cached.
+///
+/// ```rust,ignore
+/// let node = CachedNode::new(EmbeddingNode::default())
+///     .with_key_inputs(vec![CacheKeyInput::EventField("text".to_string())])
+///     .with_ttl(Duration::from_secs(3600));
+/// workflow.register_node(node);
+/// ```
+pub struct CachedNode {
+    inner: Box<dyn Node>,
+    store: Arc<dyn NodeCacheStore>,
+    key_inputs: Vec<CacheKeyInput>,
+    ttl: Duration,
+}
+
+impl CachedNode {
+    pub fn new(inner: impl Node + 'static) -> Self {
+        Self {
+            inner: Box::new(inner),
+            store: Arc::new(InMemoryCacheStore::new()),
+            key_inputs: vec![CacheKeyInput::EventData, CacheKeyInput::AllNodeResults],
+            ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    /// Keep outputs in `store`, e.g. to share them between nodes or
+    /// processes. Defaults to an [`InMemoryCacheStore`].
+    pub fn with_store(mut self, store: Arc<dyn NodeCacheStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Compute the cache key from `inputs` only
+    pub fn with_key_inputs(mut self, inputs: Vec<CacheKeyInput>) -> Self {
+        self.key_inputs = inputs;
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The cache key for `task_context`. Runs of different tenants never
+    /// share outputs.
+    pub fn cache_key(&self, task_context: &TaskContext) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.inner.node_name().as_bytes());
+        hasher.update([0]);
+        hasher.update(serde_json::to_vec(&task_context.get_all_metadata().get(TENANT_ID_KEY)).unwrap_or_default());
+        for input in &self.key_inputs {
+            // Separate inputs so that adjacent values can't run together
+            hasher.update([0]);
+            let value = match input {
+                CacheKeyInput::EventData => Some(&task_context.event_data),
+                CacheKeyInput::EventField(key) => task_context.event_data.get(key),
+                CacheKeyInput::NodeResult(node) => task_context.get_all_data().get(node),
+                CacheKeyInput::Metadata(key) => task_context.get_all_metadata().get(key),
+                CacheKeyInput::AllNodeResults => {
+                    let mut results: Vec<_> = task_context.get_all_data().iter().collect();
+                    results.sort_by_key(|(key, _)| *key);
+                    hasher.update(serde_json::to_vec(&results).unwrap_or_default());
+                    continue;
+                }
+            };
+            hasher.update(serde_json::to_vec(&value).unwrap_or_default());
+        }
+        hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+impl fmt::Debug for CachedNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedNode")
+            .field("inner", &self.inner)
+            .field("store", &self.store)
+            .field("key_inputs", &self.key_inputs)
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl Node for CachedNode {
+    fn node_name(&self) -> String {
+        format!("CachedNode({})", self.inner.node_name())
+    }
+
+    fn config_schema(&self) -> Option<Value> {
+        self.inner.config_schema()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        let key = self.cache_key(&task_context);
+        if let Some(output) = self.store.get(&key) {
+            tracing::debug!(node = %self.inner.node_name(), "Cache hit, skipping node");
+            output.apply(&mut task_context)?;
+            return Ok(task_context);
+        }
+
+        let before = task_context.clone();
+        let result = self.inner.process(task_context)?;
+        self.store.insert(key, CachedOutput::between(&before, &result), self.ttl);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Upper-cases the event's text, counting its runs
+    #[derive(Debug)]
+    struct Shout(Arc<AtomicUsize>);
+
+    impl Node for Shout {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let text = task_context.event_data["text"].as_str().unwrap_or_default().to_uppercase();
+            task_context.update_node("shout", text);
+            task_context.set_metadata("shouted", true)?;
+            Ok(task_context)
+        }
+    }
+
+    fn context(text: &str) -> TaskContext {
+        TaskContext::new("cache_test".to_string(), json!({ "text": text, "request_id": text.len() }))
+    }
+
+    #[test]
+    fn test_identical_input_runs_inner_node_once() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let node = CachedNode::new(Shout(runs.clone()));
+
+        let first = node.process(context("hello")).unwrap();
+        let second = node.process(context("hello")).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(second.get_node_data::<String>("shout").unwrap().as_deref(), Some("HELLO"));
+        assert_eq!(second.get_metadata::<bool>("shouted").unwrap(), Some(true));
+        assert_eq!(
+            first.get_node_data::<String>("shout").unwrap(),
+            second.get_node_data::<String>("shout").unwrap()
+        );
+
+        node.process(context("world")).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_key_inputs_and_ttl() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let store = Arc::new(InMemoryCacheStore::new());
+        let node = CachedNode::new(Shout(runs.clone()))
+            .with_store(store.clone())
+            .with_key_inputs(vec![CacheKeyInput::EventField("text".to_string())]);
+
+        // Fields outside the key don't miss the cache
+        let mut other = context("hello");
+        other.event_data["request_id"] = json!(99);
+        node.process(context("hello")).unwrap();
+        node.process(other).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(store.len(), 1);
+
+        // Expired outputs are recomputed
+        let node = CachedNode::new(Shout(runs.clone())).with_ttl(Duration::ZERO);
+        node.process(context("hello")).unwrap();
+        node.process(context("hello")).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        // TTLs past what an Instant can hold never expire
+        let node = CachedNode::new(Shout(runs.clone())).with_ttl(Duration::MAX);
+        node.process(context("hello")).unwrap();
+        node.process(context("hello")).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_tenants_do_not_share_outputs() {
+        use crate::task::TenantId;
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let node = CachedNode::new(Shout(runs.clone()));
+        let tenant_context = |tenant: &str| {
+            let mut context = context("hello");
+            context.set_tenant_id(&TenantId::new(tenant));
+            context
+        };
+
+        node.process(tenant_context("acme")).unwrap();
+        node.process(tenant_context("acme")).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        node.process(tenant_context("globex")).unwrap();
+        node.process(context("hello")).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_store_is_bounded() {
+        let store = InMemoryCacheStore::with_capacity(2);
+        let output = CachedOutput::default();
+
+        // Expired entries are purged to make room
+        store.insert("expired".to_string(), output.clone(), Duration::ZERO);
+        store.insert("a".to_string(), output.clone(), Duration::from_secs(60));
+        store.insert("b".to_string(), output.clone(), Duration::from_secs(120));
+        assert_eq!(store.len(), 2);
+        assert!(store.get("a").is_some() && store.get("b").is_some());
+
+        // Otherwise the entry closest to expiring is evicted
+        store.insert("c".to_string(), output.clone(), Duration::from_secs(180));
+        assert_eq!(store.len(), 2);
+        assert!(store.get("a").is_none());
+        assert!(store.get("b").is_some() && store.get("c").is_some());
+
+        // Replacing an entry evicts nothing
+        store.insert("c".to_string(), output, Duration::from_secs(240));
+        assert!(store.get("b").is_some() && store.get("c").is_some());
+    }
+}
@@ -12,9 +12,18 @@ pub struct NodeConfig {
     pub is_router: bool,
     pub description: Option<String>,
     pub parallel_nodes: Vec<TypeId>,
+    /// Names of the parallel nodes whose output is kept under their own
+    /// name rather than merged into the context
+    pub parallel_scopes: HashMap<TypeId, String>,
+    /// Ids of named node instances run in order before this node, after
+    /// its parallel nodes
+    pub named_nodes: Vec<String>,
     pub timeout: Option<Duration>,
     pub retry_attempts: Option<u32>,
     pub retry_delay: Option<Duration>,
+    /// Whether the node failing fails the run; when it doesn't, the run
+    /// records the failure and carries on
+    pub critical: bool,
     pub required_inputs: Vec<String>,
     pub metadata: HashMap<String, serde_json::Value>,
     pub max_concurrent_executions: Option<usize>,
@@ -24,15 +33,22 @@ pub struct NodeConfig {
 
 impl NodeConfig {
     pub fn new<T: Node + 'static>() -> Self {
+        Self::for_type(TypeId::of::<T>())
+    }
+
+    pub(crate) fn for_type(node_type: TypeId) -> Self {
         Self {
-            node_type: TypeId::of::<T>(),
+            node_type,
             connections: Vec::new(),
             is_router: false,
             description: None,
             parallel_nodes: Vec::new(),
+            parallel_scopes: HashMap::new(),
+            named_nodes: Vec::new(),
             timeout: None,
             retry_attempts: None,
             retry_delay: None,
+            critical: true,
             required_inputs: Vec::new(),
             metadata: HashMap::new(),
             max_concurrent_executions: None,
@@ -61,6 +77,28 @@ impl NodeConfig {
         self
     }
 
+    /// Run `node_type` in parallel before this node, keeping what it writes
+    /// to the context under `name`: its node data is stored as one object
+    /// under `name` instead of being merged, so branches writing the same
+    /// keys don't overwrite each other.
+    pub fn with_scoped_parallel_node(mut self, name: impl Into<String>, node_type: TypeId) -> Self {
+        if !self.parallel_nodes.contains(&node_type) {
+            self.parallel_nodes.push(node_type);
+        }
+        self.parallel_scopes.insert(node_type, name.into());
+        self
+    }
+
+    /// Run the node registered under `id` with
+    /// [`Workflow::register_named_node`](crate::workflow::Workflow::register_named_node)
+    /// before this node. Unlike node types, ids can refer to several
+    /// instances of one node type configured differently. Named nodes run in
+    /// the order they are added, after the parallel nodes.
+    pub fn with_named_node(mut self, id: impl Into<String>) -> Self {
+        self.named_nodes.push(id.into());
+        self
+    }
+
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
         self
@@ -72,6 +110,16 @@ impl NodeConfig {
         self
     }
 
+    /// Mark the node critical, the default, or not. When a non-critical
+    /// node fails, its failure is recorded under
+    /// [`NODE_FAILURES_KEY`](crate::workflow::NODE_FAILURES_KEY) and the run
+    /// continues with the context the node was given, ending partially
+    /// completed rather than failed.
+    pub fn with_critical(mut self, critical: bool) -> Self {
+        self.critical = critical;
+        self
+    }
+
     pub fn with_required_inputs(mut self, inputs: Vec<String>) -> Self {
         self.required_inputs = inputs;
         self
@@ -165,9 +213,12 @@ impl Serialize for NodeConfig {
         state.serialize_field("is_router", &self.is_router)?;
         state.serialize_field("description", &self.description)?;
         state.serialize_field("parallel_nodes", &self.parallel_nodes.iter().map(|id| format!("{:?}", id)).collect::<Vec<_>>())?;
+        state.serialize_field("parallel_scopes", &self.parallel_scopes.iter().map(|(id, name)| (format!("{:?}", id), name)).collect::<HashMap<_, _>>())?;
+        state.serialize_field("named_nodes", &self.named_nodes)?;
         state.serialize_field("timeout", &self.timeout)?;
         state.serialize_field("retry_attempts", &self.retry_attempts)?;
         state.serialize_field("retry_delay", &self.retry_delay)?;
+        state.serialize_field("critical", &self.critical)?;
         state.serialize_field("required_inputs", &self.required_inputs)?;
         state.serialize_field("metadata", &self.metadata)?;
         state.serialize_field("max_concurrent_executions", &self.max_concurrent_executions)?;
@@ -207,9 +258,12 @@ impl<'de> Deserialize<'de> for NodeConfig {
                     is_router: false,
                     description: None,
                     parallel_nodes: Vec::new(),
+                    parallel_scopes: HashMap::new(),
+                    named_nodes: Vec::new(),
                     timeout: None,
                     retry_attempts: None,
                     retry_delay: None,
+                    critical: true,
                     required_inputs: Vec::new(),
                     metadata: HashMap::new(),
                     max_concurrent_executions: None,
@@ -221,9 +275,11 @@ impl<'de> Deserialize<'de> for NodeConfig {
                     match key.as_str() {
                         "is_router" => config.is_router = map.next_value()?,
                         "description" => config.description = map.next_value()?,
+                        "named_nodes" => config.named_nodes = map.next_value()?,
                         "timeout" => config.timeout = map.next_value()?,
                         "retry_attempts" => config.retry_attempts = map.next_value()?,
                         "retry_delay" => config.retry_delay = map.next_value()?,
+                        "critical" => config.critical = map.next_value()?,
                         "required_inputs" => config.required_inputs = map.next_value()?,
                         "metadata" => config.metadata = map.next_value()?,
                         "max_concurrent_executions" => config.max_concurrent_executions = map.next_value()?,
@@ -200,9 +200,12 @@ impl<T: Node + 'static> NodeConfigBuilder<T> {
             is_router: self.is_router,
             description: self.description,
             parallel_nodes: self.parallel_nodes,
+            parallel_scopes: HashMap::new(),
+            named_nodes: Vec::new(),
             timeout: self.timeout,
             retry_attempts: self.retry_attempts,
             retry_delay: self.retry_delay,
+            critical: true,
             required_inputs: self.required_inputs,
             metadata: self.metadata,
             max_concurrent_executions: self.max_concurrent_executions,
@@ -0,0 +1,181 @@
+// =============================================================================
+// Fallback Chains - Try alternative nodes when a node fails
+// =============================================================================
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::Node;
+use crate::error::recovery::{DefaultRecoveryHandler, RecoveryHandler};
+use crate::error::WorkflowError;
+use crate::task::TaskContext;
+
+/// Runs a primary node and, if it fails, each fallback node in turn.
+///
+/// Every node gets the context as it was before the chain started, and the
+/// first successful result is returned. A failure the recovery handler
+/// declines to recover from is returned immediately; if every node fails the
+/// chain returns a [`WorkflowError::ProcessingError`] listing each failure.
+/// This is the node-level counterpart of
+/// [`with_fallback`](crate::error::recovery::with_fallback).
+///
+/// ```rust,ignore
+/// let node = FallbackNode::new(OpenAiSummarizer::default())
+///     .with_fallback(AnthropicSummarizer::default())
+///     .with_fallback(ExtractiveSummarizer);
+/// workflow.register_node(node);
+/// ```
+pub struct FallbackNode {
+    primary: Box<dyn Node>,
+    fallbacks: Vec<Box<dyn Node>>,
+    recovery: Arc<dyn RecoveryHandler>,
+}
+
+impl FallbackNode {
+    pub fn new(primary: impl Node + 'static) -> Self {
+        Self {
+            primary: Box::new(primary),
+            fallbacks: Vec::new(),
+            recovery: Arc::new(DefaultRecoveryHandler),
+        }
+    }
+
+    /// Add a node to try after the primary and any earlier fallbacks fail
+    pub fn with_fallback(mut self, node: impl Node + 'static) -> Self {
+        self.fallbacks.push(Box::new(node));
+        self
+    }
+
+    /// Decide which failures fall through to the next node. Defaults to
+    /// [`DefaultRecoveryHandler`].
+    pub fn with_recovery_handler(mut self, handler: Arc<dyn RecoveryHandler>) -> Self {
+        self.recovery = handler;
+        self
+    }
+}
+
+impl fmt::Debug for FallbackNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FallbackNode")
+            .field("primary", &self.primary)
+            .field("fallbacks", &self.fallbacks)
+            .finish()
+    }
+}
+
+impl Node for FallbackNode {
+    fn node_name(&self) -> String {
+        format!("FallbackNode({})", self.primary.node_name())
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        let mut failures = Vec::new();
+        let chain = std::iter::once(&self.primary).chain(&self.fallbacks);
+        let remaining = self.fallbacks.len();
+
+        for (index, node) in chain.enumerate() {
+            let error = match node.process(task_context.clone()) {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+            if !self.recovery.should_recover(&error) {
+                return Err(error);
+            }
+            if index < remaining {
+                tracing::warn!(
+                    node = %node.node_name(),
+                    error = %error,
+                    "Node failed, trying next fallback"
+                );
+            }
+            failures.push(format!("{}: {}", node.node_name(), error));
+        }
+
+        Err(WorkflowError::processing_error(
+            format!("All {} nodes in the fallback chain failed: {}", failures.len(), failures.join("; ")),
+            self.node_name(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug)]
+    struct Failing(&'static str);
+
+    impl Node for Failing {
+        fn node_name(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn process(&self, _task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Err(WorkflowError::api_error(format!("{} unavailable", self.0), self.0, "/", Some(503)))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Succeeding(&'static str);
+
+    impl Node for Succeeding {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.update_node("summary", json!({ "by": self.0 }));
+            Ok(task_context)
+        }
+    }
+
+    fn context() -> TaskContext {
+        TaskContext::new("summarize".to_string(), json!({ "text": "..." }))
+    }
+
+    #[test]
+    fn test_second_fallback_output_is_used() {
+        let node = FallbackNode::new(Failing("primary"))
+            .with_fallback(Failing("first"))
+            .with_fallback(Succeeding("second"))
+            .with_fallback(Succeeding("third"));
+
+        let context = node.process(context()).unwrap();
+        assert_eq!(
+            context.get_node_data::<serde_json::Value>("summary").unwrap(),
+            Some(json!({ "by": "second" }))
+        );
+        assert_eq!(node.node_name(), "FallbackNode(primary)");
+    }
+
+    #[test]
+    fn test_failures_are_aggregated_unless_unrecoverable() {
+        let node = FallbackNode::new(Failing("primary")).with_fallback(Failing("backup"));
+        match node.process(context()) {
+            Err(WorkflowError::ProcessingError { message, .. }) => {
+                assert!(message.starts_with("All 2 nodes in the fallback chain failed"), "{}", message);
+                assert!(message.contains("primary: API error from primary"), "{}", message);
+                assert!(message.contains("backup: API error from backup"), "{}", message);
+            }
+            other => panic!("Expected ProcessingError, got {:?}", other),
+        }
+
+        /// Never recovers, so the primary's error is returned as is
+        struct NoRecovery;
+
+        impl RecoveryHandler for NoRecovery {
+            fn handle_recovery(
+                &self,
+                _context: &crate::error::recovery::RecoveryContext,
+            ) -> crate::error::RecoveryStrategy {
+                crate::error::RecoveryStrategy::FailFast
+            }
+
+            fn should_recover(&self, _error: &WorkflowError) -> bool {
+                false
+            }
+        }
+
+        let node = FallbackNode::new(Failing("primary"))
+            .with_fallback(Succeeding("backup"))
+            .with_recovery_handler(Arc::new(NoRecovery));
+        assert!(matches!(node.process(context()), Err(WorkflowError::ApiError { .. })));
+    }
+}
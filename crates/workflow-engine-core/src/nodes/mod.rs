@@ -222,6 +222,7 @@
 //!     model_provider: ModelProvider::Anthropic,
 //!     model_name: "claude-3-sonnet-20240229".to_string(),
 //!     mcp_server_uri: Some("ws://localhost:8080/mcp".to_string()),
+//!     ai_fallback_template: None,
 //! };
 //!
 //! let agent_node = AnthropicAgentNode::new(agent_config);
@@ -420,8 +421,11 @@ use super::error::WorkflowError;
 use super::task::TaskContext;
 
 pub mod agent;
+pub mod cache;
 pub mod config;
 pub mod config_builder;
+pub mod fallback;
+pub mod progress;
 pub mod registry;
 pub mod template_agent;
 pub mod type_safe;
@@ -525,6 +529,47 @@ pub trait Node: Send + Sync + Debug {
     /// }
     /// ```
     fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError>;
+
+    /// Processes the task context while reporting progress to the caller.
+    ///
+    /// Long-running nodes override this to publish incremental updates via
+    /// the [`ProgressReporter`](progress::ProgressReporter). The default
+    /// implementation ignores the reporter and delegates to [`Node::process`].
+    fn process_with_progress(
+        &self,
+        task_context: TaskContext,
+        progress: &progress::ProgressReporter,
+    ) -> Result<TaskContext, WorkflowError> {
+        let _ = progress;
+        self.process(task_context)
+    }
+
+    /// JSON Schema describing this node's configuration, for UIs that render
+    /// configuration forms.
+    ///
+    /// Nodes without configuration keep the default, `None`. Schemas of the
+    /// nodes in a registry are collected by
+    /// [`NodeRegistry::config_schemas`](registry::NodeRegistry::config_schemas).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// impl Node for SummarizeNode {
+    ///     fn config_schema(&self) -> Option<serde_json::Value> {
+    ///         Some(json!({
+    ///             "type": "object",
+    ///             "properties": {
+    ///                 "max_words": { "type": "integer", "minimum": 1, "default": 200 }
+    ///             }
+    ///         }))
+    ///     }
+    ///
+    ///     // ... rest of implementation
+    /// }
+    /// ```
+    fn config_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 /// Trait for nodes that determine routing in workflows.
@@ -755,6 +800,54 @@ pub trait AsyncNode: Send + Sync + Debug {
     /// }
     /// ```
     async fn process_async(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError>;
+
+    /// Cleans up after processing was stopped because the run was cancelled.
+    ///
+    /// Called by [`process_with_cancellation`](Self::process_with_cancellation)
+    /// with the context the node was given, so the node can release what it
+    /// holds, such as locks, temporary files or jobs started elsewhere. Does
+    /// nothing by default.
+    async fn on_cancel(&self, task_context: &TaskContext) {
+        let _ = task_context;
+    }
+
+    /// Processes the task context within the context's remaining deadline.
+    ///
+    /// Without a deadline this is equivalent to [`process_async`](Self::process_async).
+    /// Otherwise processing is cut off at the deadline with a
+    /// deadline-exceeded [`WorkflowError::NodeTimeout`].
+    async fn process_with_deadline(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        let node_name = self.node_name();
+        task_context.check_deadline(&node_name)?;
+        match task_context.remaining_time() {
+            Some(remaining) => tokio::time::timeout(remaining, self.process_async(task_context))
+                .await
+                .map_err(|_| WorkflowError::deadline_exceeded(node_name, remaining))?,
+            None => self.process_async(task_context).await,
+        }
+    }
+
+    /// Processes the task context like [`process_with_deadline`](Self::process_with_deadline),
+    /// stopping if the run is cancelled.
+    ///
+    /// When the context's [cancellation token](TaskContext::cancellation)
+    /// fires first, processing is dropped, [`on_cancel`](Self::on_cancel) is
+    /// called and the node fails with [`WorkflowError::Cancelled`].
+    async fn process_with_cancellation(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        let Some(token) = task_context.cancellation.clone() else {
+            return self.process_with_deadline(task_context).await;
+        };
+        let node_name = self.node_name();
+        task_context.check_cancelled(&node_name)?;
+        let given = task_context.clone();
+        tokio::select! {
+            result = self.process_with_deadline(task_context) => result,
+            _ = token.cancelled() => {
+                self.on_cancel(&given).await;
+                Err(WorkflowError::cancelled(node_name))
+            }
+        }
+    }
 }
 
 /// Adapter to make synchronous nodes work in async contexts
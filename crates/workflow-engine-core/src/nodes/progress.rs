@@ -0,0 +1,51 @@
+// =============================================================================
+// Progress Reporting - Lets long-running nodes publish incremental progress
+// =============================================================================
+
+use tokio::sync::mpsc;
+
+/// A single progress update emitted by a node
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressUpdate {
+    pub progress: f64,
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+/// Callback channel passed to [`Node::process_with_progress`](super::Node::process_with_progress).
+///
+/// Reporting never blocks and never fails: updates are dropped when nobody is
+/// listening, so nodes can report unconditionally.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+    sender: Option<mpsc::UnboundedSender<ProgressUpdate>>,
+}
+
+impl ProgressReporter {
+    pub fn new(sender: mpsc::UnboundedSender<ProgressUpdate>) -> Self {
+        Self {
+            sender: Some(sender),
+        }
+    }
+
+    /// Create a reporter together with the receiver its updates are sent to
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<ProgressUpdate>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self::new(sender), receiver)
+    }
+
+    /// A reporter that discards every update
+    pub fn noop() -> Self {
+        Self::default()
+    }
+
+    pub fn report(&self, progress: f64, total: Option<f64>, message: Option<String>) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(ProgressUpdate {
+                progress,
+                total,
+                message,
+            });
+        }
+    }
+}
@@ -1,29 +1,64 @@
 // =============================================================================
-// Node Registry - Maps TypeIds to actual node instances
+// Node Registry - Maps TypeIds and ids to actual node instances
 // =============================================================================
 
-use std::{any::TypeId, collections::HashMap};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use serde_json::Value;
 
 use super::Node;
 
+/// The nodes of a workflow, keyed by type, and named node instances keyed
+/// by id, so one node type can be registered several times with different
+/// configurations
 #[derive(Debug)]
 pub struct NodeRegistry {
-    nodes: HashMap<TypeId, Box<dyn Node>>,
+    nodes: HashMap<TypeId, Arc<dyn Node>>,
+    named: HashMap<String, Arc<dyn Node>>,
 }
 
 impl NodeRegistry {
     pub fn new() -> Self {
         Self {
             nodes: HashMap::new(),
+            named: HashMap::new(),
         }
     }
 
     pub fn register<T: Node + 'static>(&mut self, node: T) {
-        self.nodes.insert(TypeId::of::<T>(), Box::new(node));
+        self.nodes.insert(TypeId::of::<T>(), Arc::new(node));
+    }
+
+    /// Registers `node` in place of the node of type `type_id`
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn register_as(&mut self, type_id: TypeId, node: Box<dyn Node>) {
+        self.nodes.insert(type_id, node.into());
     }
 
     pub fn get(&self, type_id: &TypeId) -> Option<&dyn Node> {
-        self.nodes.get(type_id).map(|boxed| boxed.as_ref())
+        self.nodes.get(type_id).map(|node| node.as_ref())
+    }
+
+    /// The node of type `type_id`, as a handle that outlives the registry lock
+    pub fn get_shared(&self, type_id: &TypeId) -> Option<Arc<dyn Node>> {
+        self.nodes.get(type_id).cloned()
+    }
+
+    /// Registers `node` under `id`, replacing any node registered under it
+    pub fn register_named<T: Node + 'static>(&mut self, id: impl Into<String>, node: T) {
+        self.named.insert(id.into(), Arc::new(node));
+    }
+
+    pub fn get_named(&self, id: &str) -> Option<&dyn Node> {
+        self.named.get(id).map(|node| node.as_ref())
+    }
+
+    pub fn get_all_node_ids(&self) -> Vec<String> {
+        self.named.keys().cloned().collect()
     }
 
     pub fn get_all_node_types(&self) -> Vec<TypeId> {
@@ -33,4 +68,69 @@ impl NodeRegistry {
     pub fn get_node_count(&self) -> usize {
         self.nodes.len()
     }
+
+    /// Configuration schemas of the registered nodes that declare one, keyed
+    /// by node name
+    pub fn config_schemas(&self) -> BTreeMap<String, Value> {
+        self.nodes
+            .values()
+            .filter_map(|node| Some((node.node_name(), node.config_schema()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WorkflowError;
+    use crate::nodes::cache::CachedNode;
+    use crate::task::TaskContext;
+    use serde_json::json;
+
+    #[derive(Debug)]
+    struct Summarize;
+
+    impl Node for Summarize {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Ok(task_context)
+        }
+
+        fn config_schema(&self) -> Option<Value> {
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "max_words": { "type": "integer", "minimum": 1, "default": 200 },
+                    "style": { "type": "string", "enum": ["bullets", "prose"] }
+                },
+                "required": ["style"]
+            }))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Passthrough;
+
+    impl Node for Passthrough {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Ok(task_context)
+        }
+    }
+
+    #[test]
+    fn test_collects_declared_config_schemas() {
+        let mut registry = NodeRegistry::new();
+        registry.register(Summarize);
+        registry.register(Passthrough);
+        registry.register(CachedNode::new(Summarize));
+
+        let schemas = registry.config_schemas();
+        assert_eq!(schemas.keys().collect::<Vec<_>>(), ["CachedNode(Summarize)", "Summarize"]);
+
+        let schema = &schemas["Summarize"];
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["max_words"]["type"], "integer");
+        assert_eq!(schema["properties"]["style"]["enum"], json!(["bullets", "prose"]));
+        assert_eq!(schema["required"], json!(["style"]));
+        assert_eq!(schemas["CachedNode(Summarize)"], *schema);
+    }
 }
@@ -46,7 +46,8 @@ impl TemplateAgentNode {
         template_manager: Arc<TemplateManager>,
     ) -> Result<Self, WorkflowError> {
         let base_config = config.agent_config.clone();
-        let base_node = BaseAgentNode::new(base_config);
+        let base_node = BaseAgentNode::new(base_config)
+            .with_template_manager(template_manager.clone());
         
         Ok(Self {
             base_node,
@@ -217,7 +218,8 @@ impl AgentNode for TemplateAgentNode {
         modified_config.system_prompt = system_prompt;
         
         // Create a temporary base node with the modified config
-        let temp_node = BaseAgentNode::new(modified_config);
+        let temp_node = BaseAgentNode::new(modified_config)
+            .with_template_manager(self.template_manager.clone());
         
         // Process with the base node
         let mut result_context = temp_node.process_with_ai(context).await?;
@@ -414,6 +416,7 @@ mod tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-4".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
         
         let config = TemplateAgentBuilder::new(agent_config)
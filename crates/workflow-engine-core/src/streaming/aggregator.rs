@@ -0,0 +1,156 @@
+//! Merging the token streams of parallel AI calls into one stream
+//!
+//! When a workflow fans out to several agents, [`StreamAggregator`] combines
+//! their [`StreamResponse`]s into a single stream of [`SourcedChunk`]s, each
+//! tagged with the source it came from. Chunks keep their own `is_final`
+//! flag, which marks the end of that source rather than of the merged
+//! stream.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::error::WorkflowError;
+use super::types::{StreamChunk, StreamResponse};
+
+/// How the chunks of several streams are ordered in the merged stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// One chunk from each source in turn, skipping sources that have ended
+    #[default]
+    RoundRobin,
+    /// Every chunk of the first source, then every chunk of the next
+    Sequential,
+}
+
+/// A chunk of a merged stream and the source it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourcedChunk {
+    pub source: String,
+    pub chunk: StreamChunk,
+}
+
+/// A merged stream of chunks from several sources
+pub type AggregatedStream = Pin<Box<dyn Stream<Item = Result<SourcedChunk, WorkflowError>> + Send>>;
+
+/// Combines the streams of parallel AI calls
+///
+/// # Examples
+///
+/// ```ignore
+/// let merged = StreamAggregator::new(MergeStrategy::RoundRobin)
+///     .with_source("researcher", researcher.stream_response(prompt, &config))
+///     .with_source("critic", critic.stream_response(prompt, &config))
+///     .merge();
+/// ```
+pub struct StreamAggregator {
+    strategy: MergeStrategy,
+    sources: Vec<(String, StreamResponse)>,
+}
+
+impl StreamAggregator {
+    pub fn new(strategy: MergeStrategy) -> Self {
+        Self {
+            strategy,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Add a stream, tagging its chunks with `source`. Sources are merged in
+    /// the order they are added.
+    pub fn with_source(mut self, source: impl Into<String>, stream: StreamResponse) -> Self {
+        self.sources.push((source.into(), stream));
+        self
+    }
+
+    /// The merged stream. Errors are passed through in place of the chunk
+    /// they replace, and the failing source keeps being polled.
+    pub fn merge(self) -> AggregatedStream {
+        match self.strategy {
+            MergeStrategy::Sequential => Box::pin(stream::iter(self.sources).flat_map(|(source, stream)| {
+                stream.map(move |chunk| tag(&source, chunk))
+            })),
+            MergeStrategy::RoundRobin => {
+                let sources: VecDeque<_> = self.sources.into_iter().collect();
+                Box::pin(stream::unfold(sources, |mut sources| async move {
+                    while let Some((source, mut stream)) = sources.pop_front() {
+                        if let Some(chunk) = stream.next().await {
+                            let chunk = tag(&source, chunk);
+                            sources.push_back((source, stream));
+                            return Some((chunk, sources));
+                        }
+                    }
+                    None
+                }))
+            }
+        }
+    }
+}
+
+fn tag(source: &str, chunk: Result<StreamChunk, WorkflowError>) -> Result<SourcedChunk, WorkflowError> {
+    chunk.map(|chunk| SourcedChunk {
+        source: source.to_string(),
+        chunk,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(tokens: &'static [&'static str]) -> StreamResponse {
+        let last = tokens.len() - 1;
+        Box::pin(stream::iter(tokens.iter().enumerate().map(move |(i, token)| {
+            Ok(StreamChunk::new(token.to_string(), i == last))
+        })))
+    }
+
+    async fn merged(strategy: MergeStrategy) -> Vec<(String, String, bool)> {
+        StreamAggregator::new(strategy)
+            .with_source("a", tokens(&["a1", "a2", "a3"]))
+            .with_source("b", tokens(&["b1"]))
+            .merge()
+            .map(|chunk| {
+                let SourcedChunk { source, chunk } = chunk.unwrap();
+                (source, chunk.content, chunk.is_final)
+            })
+            .collect()
+            .await
+    }
+
+    fn expected(chunks: &[(&str, &str, bool)]) -> Vec<(String, String, bool)> {
+        chunks
+            .iter()
+            .map(|(source, content, is_final)| (source.to_string(), content.to_string(), *is_final))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_interleaves_sources() {
+        assert_eq!(
+            merged(MergeStrategy::RoundRobin).await,
+            expected(&[
+                ("a", "a1", false),
+                ("b", "b1", true),
+                ("a", "a2", false),
+                ("a", "a3", true),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequential_drains_each_source_in_turn() {
+        assert_eq!(
+            merged(MergeStrategy::Sequential).await,
+            expected(&[
+                ("a", "a1", false),
+                ("a", "a2", false),
+                ("a", "a3", true),
+                ("b", "b1", true),
+            ])
+        );
+    }
+}
@@ -5,6 +5,7 @@ pub mod websocket;
 pub mod handlers;
 pub mod backpressure;
 pub mod recovery;
+pub mod aggregator;
 
 pub use types::*;
 pub use providers::*;
@@ -12,4 +13,5 @@ pub use sse::*;
 pub use websocket::*;
 pub use handlers::*;
 pub use backpressure::*;
-pub use recovery::*;
\ No newline at end of file
+pub use recovery::*;
+pub use aggregator::*;
\ No newline at end of file
@@ -683,8 +683,12 @@ pub fn create_streaming_provider(
         }
         #[cfg(not(feature = "aws"))]
         "bedrock" => {
-            Err(WorkflowError::ConfigurationError(
-                "Bedrock provider requires 'aws' feature to be enabled".to_string(),
+            Err(WorkflowError::configuration_error(
+                "Bedrock provider requires 'aws' feature to be enabled",
+                "provider_name",
+                "streaming configuration",
+                "openai or anthropic without the 'aws' feature",
+                Some(provider_name.to_string()),
             ))
         }
         _ => Err(WorkflowError::configuration_error(
@@ -733,8 +737,10 @@ mod tests {
             "You are a helpful assistant".to_string(),
             None,
         );
-        assert!(bedrock_provider.is_ok());
+        #[cfg(feature = "aws")]
         assert_eq!(bedrock_provider.unwrap().provider_name(), "bedrock");
+        #[cfg(not(feature = "aws"))]
+        assert!(bedrock_provider.is_err());
         
         // Test unsupported provider
         let unsupported = create_streaming_provider(
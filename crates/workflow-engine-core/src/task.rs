@@ -137,6 +137,26 @@
 //! }
 //! ```
 //!
+//! ### Mapping to Domain Models
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use workflow_engine_core::task::TaskContext;
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Ticket {
+//!     ticket_id: String,
+//!     // Filled from the `sentiment` node result
+//!     sentiment: Option<String>,
+//! }
+//!
+//! let ticket = Ticket { ticket_id: "T-1".to_string(), sentiment: None };
+//! let mut context = TaskContext::from_model("support".to_string(), &ticket).unwrap();
+//! context.update_node("sentiment", "positive");
+//! let ticket: Ticket = context.extract_into().unwrap();
+//! assert_eq!(ticket.sentiment.as_deref(), Some("positive"));
+//! ```
+//!
 //! ### Working with Metadata
 //!
 //! ```rust
@@ -256,17 +276,62 @@
 //! 4. **Clean Up Intermediate Data**: Remove large temporary data that's no longer needed
 //! 5. **Use Metadata**: Store processing information, timestamps, and debug data in metadata
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 
+/// Cancels runs; see [`TaskContext::cancellation`]
+pub use tokio_util::sync::CancellationToken;
+
 // Event integration is in the API crate
 // use crate::db::event::Event;
 
 use super::error::WorkflowError;
+use super::workflow::replay::ExternalCalls;
+use super::workflow::shared_state::SharedState;
+
+/// Metadata key holding the correlation id shared by every event a run emits
+pub const CORRELATION_ID_KEY: &str = "correlation_id";
+
+/// Metadata key holding the id of the event a run emitted last, which is the
+/// cause of the next event it emits
+pub const CAUSATION_ID_KEY: &str = "causation_id";
+
+/// Metadata key holding the id of the tenant a run belongs to
+pub const TENANT_ID_KEY: &str = "tenant_id";
+
+/// Identifies the tenant that owns a run and everything it stores.
+///
+/// For tenants kept in the API's `tenants` table this is the string form of
+/// the tenant's UUID, as carried in the `tenant_id` JWT claim.
+///
+/// Runs without a tenant belong to no tenant rather than to every tenant:
+/// their results are only visible to callers without a tenant either.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TenantId(String);
+
+impl TenantId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for TenantId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
 
 /// The primary data container that flows through workflow execution.
 ///
@@ -286,6 +351,10 @@ use super::error::WorkflowError;
 /// - `metadata`: Additional execution metadata and debugging information
 /// - `created_at`: When this context was originally created
 /// - `updated_at`: When this context was last modified
+/// - `deadline`: Optional instant by which the workflow must finish
+/// - `shared_state`: Optional store shared live by all nodes of the run
+/// - `external_calls`: Optional log recording or replaying external calls
+/// - `retention`: Optional limit on which node results are kept
 ///
 /// # Thread Safety
 ///
@@ -330,17 +399,115 @@ pub struct TaskContext {
     /// Original event data that triggered this workflow
     pub event_data: Value,
     
-    /// Results from each processing node, keyed by node name
-    pub nodes: HashMap<String, Value>,
+    /// Results from each processing node, keyed by node name.
+    ///
+    /// Shared between clones until one of them writes, so cloning a context
+    /// for parallel branches does not copy the results. Read them with
+    /// [`get_all_data`](Self::get_all_data) and modify the map with
+    /// [`nodes_mut`](Self::nodes_mut).
+    pub(crate) nodes: Arc<HashMap<String, Value>>,
     
-    /// Additional metadata about execution state and debugging information
-    pub metadata: HashMap<String, Value>,
+    /// Additional metadata about execution state and debugging information.
+    ///
+    /// Shared between clones until one of them writes, like the node
+    /// results. Read it with [`get_all_metadata`](Self::get_all_metadata)
+    /// and modify it with [`metadata_mut`](Self::metadata_mut).
+    pub(crate) metadata: Arc<HashMap<String, Value>>,
     
     /// Timestamp when this context was originally created
     pub created_at: DateTime<Utc>,
     
     /// Timestamp when this context was last updated
     pub updated_at: DateTime<Utc>,
+
+    /// Instant by which the workflow must finish, if it runs under a deadline.
+    ///
+    /// Deadlines are tied to the local clock, so they are not serialized.
+    #[serde(skip)]
+    pub deadline: Option<Instant>,
+
+    /// State shared by every node of the run, if the workflow has one.
+    ///
+    /// Unlike node results, writes are visible to parallel branches
+    /// immediately. Only the handle is carried, so it is not serialized.
+    #[serde(skip)]
+    pub shared_state: Option<SharedState>,
+
+    /// Log of the run's external calls, if the run is being recorded or
+    /// replayed. See [`external_call`](Self::external_call).
+    #[serde(skip)]
+    pub external_calls: Option<ExternalCalls>,
+
+    /// Token that cancels the run when it fires, if the run can be
+    /// cancelled. See [`AsyncNode::on_cancel`](crate::nodes::AsyncNode::on_cancel).
+    #[serde(skip)]
+    pub cancellation: Option<CancellationToken>,
+
+    /// Opt-in limit on which node results are kept, to bound memory in long
+    /// workflows. Set it with [`set_retention_policy`](Self::set_retention_policy).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<RetentionPolicy>,
+
+    /// Unpinned node result keys, least recently written first
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    recent_nodes: VecDeque<String>,
+
+    /// Keys of the node results written since tracking began, when tracked
+    #[serde(skip)]
+    written_nodes: Option<HashSet<String>>,
+}
+
+/// Limits which node results a [`TaskContext`] keeps.
+///
+/// Pinned results are always kept. Of the others, only the `keep_last` most
+/// recently written survive; older ones are dropped as new results are stored.
+///
+/// ```rust
+/// use workflow_engine_core::task::{RetentionPolicy, TaskContext};
+/// use serde_json::json;
+///
+/// let mut context = TaskContext::new("report".to_string(), json!({}));
+/// context.set_retention_policy(RetentionPolicy::keep_last(1).with_pinned(["summary"]));
+/// context.update_node("summary", "kept");
+/// context.update_node("page_1", "dropped");
+/// context.update_node("page_2", "kept");
+/// assert_eq!(context.get_all_data().len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Number of unpinned results to keep
+    pub keep_last: usize,
+    /// Keys of results that are never dropped
+    pub pinned: HashSet<String>,
+}
+
+impl RetentionPolicy {
+    /// Keep the `n` most recently written results
+    pub fn keep_last(n: usize) -> Self {
+        Self {
+            keep_last: n,
+            pinned: HashSet::new(),
+        }
+    }
+
+    /// Keep only the results stored under `keys`
+    pub fn pinned_only<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::keep_last(0).with_pinned(keys)
+    }
+
+    /// Also keep the results stored under `keys`, however old
+    pub fn with_pinned<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.pinned.extend(keys.into_iter().map(Into::into));
+        self
+    }
 }
 
 impl TaskContext {
@@ -365,16 +532,24 @@ impl TaskContext {
             event_id: Uuid::new_v4(),
             workflow_type,
             event_data,
-            nodes: HashMap::new(),
-            metadata: HashMap::new(),
+            nodes: Arc::default(),
+            metadata: Arc::default(),
             created_at: now,
             updated_at: now,
+            deadline: None,
+            shared_state: None,
+            external_calls: None,
+            cancellation: None,
+            retention: None,
+            recent_nodes: VecDeque::new(),
+            written_nodes: None,
         }
     }
 
     pub fn update_node<T: Serialize>(&mut self, node_name: &str, data: T) {
         if let Ok(value) = serde_json::to_value(data) {
-            self.nodes.insert(node_name.to_string(), value);
+            self.nodes_mut().insert(node_name.to_string(), value);
+            self.record_node_write(node_name);
             self.updated_at = Utc::now();
         }
     }
@@ -391,6 +566,21 @@ impl TaskContext {
         })
     }
 
+    /// Top-level `field` of the event data, or `None` when it is missing,
+    /// null or not a `T`
+    pub fn get_event_field<T: for<'de> Deserialize<'de>>(&self, field: &str) -> Option<T> {
+        let value = self.event_data.get(field).filter(|value| !value.is_null())?;
+        T::deserialize(value)
+            .inspect_err(|e| tracing::debug!(field, error = %e, "Ignoring event data field of the wrong type"))
+            .ok()
+    }
+
+    /// Like [`get_event_field`](Self::get_event_field), falling back to
+    /// `default`
+    pub fn get_event_field_or<T: for<'de> Deserialize<'de>>(&self, field: &str, default: T) -> T {
+        self.get_event_field(field).unwrap_or(default)
+    }
+
     pub fn get_node_data<T: for<'de> Deserialize<'de>>(
         &self,
         node_name: &str,
@@ -415,6 +605,76 @@ impl TaskContext {
         }
     }
 
+    /// Read the data `node_name` wrote in the scoped parallel branch named
+    /// `branch`; see [`WorkflowBuilder::parallel_scoped`](crate::workflow::builder::WorkflowBuilder::parallel_scoped)
+    pub fn get_branch_data<T: for<'de> Deserialize<'de>>(
+        &self,
+        branch: &str,
+        node_name: &str,
+    ) -> Result<Option<T>, WorkflowError> {
+        let Some(value) = self.nodes.get(branch).and_then(|output| output.get(node_name)) else {
+            return Ok(None);
+        };
+        serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| WorkflowError::DeserializationError {
+                message: format!(
+                    "Failed to deserialize node data for {} in branch {}: {}",
+                    node_name, branch, e
+                ),
+                expected_type: std::any::type_name::<T>().to_string(),
+                context: format!("from node '{}' data in branch '{}'", node_name, branch),
+                raw_data: Some(value.to_string()),
+                source: Some(e),
+            })
+    }
+
+    /// Create a context whose event data is `model`, which must serialize to
+    /// a JSON object. The reverse of [`extract_into`](Self::extract_into).
+    pub fn from_model<T: Serialize>(workflow_type: String, model: &T) -> Result<Self, WorkflowError> {
+        let value = serde_json::to_value(model).map_err(|e| WorkflowError::SerializationError {
+            message: format!("Failed to serialize model: {}", e),
+            type_name: std::any::type_name::<T>().to_string(),
+            context: "in TaskContext::from_model".to_string(),
+            source: Some(e),
+        })?;
+        if !value.is_object() {
+            return Err(WorkflowError::validation_error(
+                format!("{} does not serialize to a JSON object", std::any::type_name::<T>()),
+                "model",
+                "json_object",
+                "in TaskContext::from_model",
+            ));
+        }
+        Ok(Self::new(workflow_type, value))
+    }
+
+    /// Assemble a `T` from the event data and node results.
+    ///
+    /// The fields of the event data, if it is an object, are combined with
+    /// the node results, each under its node name. Where a node result has
+    /// the same name as an event data field, the node result takes
+    /// precedence, so nodes can refine the input they were given. Metadata
+    /// is not included.
+    pub fn extract_into<T: DeserializeOwned>(&self) -> Result<T, WorkflowError> {
+        let mut combined = match &self.event_data {
+            Value::Object(fields) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+        for (node_name, value) in self.nodes.iter() {
+            combined.insert(node_name.clone(), value.clone());
+        }
+
+        let combined = Value::Object(combined);
+        serde_json::from_value(combined.clone()).map_err(|e| WorkflowError::DeserializationError {
+            message: format!("Failed to assemble model from task context: {}", e),
+            expected_type: std::any::type_name::<T>().to_string(),
+            context: "from event data and node results".to_string(),
+            raw_data: Some(combined.to_string()),
+            source: Some(e),
+        })
+    }
+
     /*
     pub fn to_event(&self) -> Result<Event, WorkflowError> {
         let task_context_value =
@@ -441,7 +701,8 @@ impl TaskContext {
             context: format!("for key '{}'", key),
             source: Some(e),
         })?;
-        self.nodes.insert(key.to_string(), value);
+        self.nodes_mut().insert(key.to_string(), value);
+        self.record_node_write(key);
         self.updated_at = Utc::now();
         Ok(())
     }
@@ -454,6 +715,87 @@ impl TaskContext {
         &self.nodes
     }
 
+    /// Mutable access to the node results, copying them first if another
+    /// context still shares them.
+    ///
+    /// Writes made here are not seen by the retention policy until
+    /// [`apply_retention`](Self::apply_retention) is called. Nor are they
+    /// tracked by key, so a scoped parallel branch writing here only keeps
+    /// the results that differ from the ones it started with.
+    pub fn nodes_mut(&mut self) -> &mut HashMap<String, Value> {
+        Arc::make_mut(&mut self.nodes)
+    }
+
+    /// Limit which node results are kept from now on.
+    ///
+    /// Existing results are treated as older than any written afterwards,
+    /// and are dropped immediately if the policy does not keep them.
+    pub fn set_retention_policy(&mut self, policy: RetentionPolicy) {
+        self.retention = Some(policy);
+        self.recent_nodes.clear();
+        self.apply_retention();
+    }
+
+    pub fn retention_policy(&self) -> Option<&RetentionPolicy> {
+        self.retention.as_ref()
+    }
+
+    /// Record the keys of the node results written from now on
+    pub(crate) fn track_node_writes(&mut self) {
+        self.written_nodes = Some(HashSet::new());
+    }
+
+    /// The keys recorded since [`track_node_writes`](Self::track_node_writes),
+    /// ending the tracking
+    pub(crate) fn take_written_nodes(&mut self) -> Option<HashSet<String>> {
+        self.written_nodes.take()
+    }
+
+    fn record_node_write(&mut self, key: &str) {
+        if let Some(written) = &mut self.written_nodes {
+            written.insert(key.to_string());
+        }
+        match &self.retention {
+            Some(policy) if !policy.pinned.contains(key) => {
+                self.recent_nodes.retain(|recent| recent != key);
+                self.recent_nodes.push_back(key.to_string());
+                self.apply_retention();
+            }
+            _ => {}
+        }
+    }
+
+    /// Drop the node results the retention policy no longer keeps.
+    ///
+    /// Results this context has not seen written, such as those added through
+    /// [`nodes_mut`](Self::nodes_mut) or merged from parallel branches, count
+    /// as the most recent. Does nothing without a retention policy.
+    pub fn apply_retention(&mut self) {
+        let Some(policy) = &self.retention else {
+            return;
+        };
+
+        let nodes = &self.nodes;
+        self.recent_nodes
+            .retain(|key| nodes.contains_key(key) && !policy.pinned.contains(key));
+        let mut untracked: Vec<_> = nodes
+            .keys()
+            .filter(|key| !policy.pinned.contains(*key) && !self.recent_nodes.contains(key))
+            .cloned()
+            .collect();
+        untracked.sort();
+        self.recent_nodes.extend(untracked);
+
+        let excess = self.recent_nodes.len().saturating_sub(policy.keep_last);
+        if excess > 0 {
+            let evicted: Vec<_> = self.recent_nodes.drain(..excess).collect();
+            let nodes = Arc::make_mut(&mut self.nodes);
+            for key in evicted {
+                nodes.remove(&key);
+            }
+        }
+    }
+
     pub fn add_data<T: Serialize>(&mut self, key: &str, data: T) -> Result<(), WorkflowError> {
         self.set_data(key, data)
     }
@@ -465,7 +807,7 @@ impl TaskContext {
             context: format!("for metadata key '{}'", key),
             source: Some(e),
         })?;
-        self.metadata.insert(key.to_string(), serialized_value);
+        self.metadata_mut().insert(key.to_string(), serialized_value);
         self.updated_at = Utc::now();
         Ok(())
     }
@@ -491,4 +833,362 @@ impl TaskContext {
     pub fn get_all_metadata(&self) -> &HashMap<String, Value> {
         &self.metadata
     }
+
+    /// Mutable access to the metadata, copying it first if another context
+    /// still shares it
+    pub fn metadata_mut(&mut self) -> &mut HashMap<String, Value> {
+        Arc::make_mut(&mut self.metadata)
+    }
+
+    pub fn shared_state(&self) -> Option<&SharedState> {
+        self.shared_state.as_ref()
+    }
+
+    /// Make a call to an external `service` through `call`.
+    ///
+    /// When the run is recorded, the request and response are kept so that
+    /// [`Workflow::replay`](crate::workflow::Workflow::replay) can answer the
+    /// call without reaching the service; when it is replayed, `call` is not
+    /// invoked at all. `request` should identify the call, e.g. the tool name
+    /// and arguments. Outside recorded runs this just invokes `call`.
+    pub fn external_call<F>(&self, service: &str, request: Value, call: F) -> Result<Value, WorkflowError>
+    where
+        F: FnOnce() -> Result<Value, WorkflowError>,
+    {
+        match &self.external_calls {
+            Some(calls) => calls.call(service, request, call),
+            None => call(),
+        }
+    }
+
+    /// Async version of [`external_call`](Self::external_call)
+    pub async fn external_call_async<F, Fut>(
+        &self,
+        service: &str,
+        request: Value,
+        call: F,
+    ) -> Result<Value, WorkflowError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value, WorkflowError>>,
+    {
+        match &self.external_calls {
+            Some(calls) => calls.call_async(service, request, call).await,
+            None => call().await,
+        }
+    }
+
+    /// Correlation id for events emitted during this run.
+    ///
+    /// Workflows record the run's `event_id` as the correlation id when they
+    /// start, unless the context already carries one.
+    pub fn correlation_id(&self) -> Uuid {
+        self.metadata_uuid(CORRELATION_ID_KEY).unwrap_or(self.event_id)
+    }
+
+    /// Id of the event this run emitted last, to use as the causation id of
+    /// the next event
+    pub fn causation_id(&self) -> Option<Uuid> {
+        self.metadata_uuid(CAUSATION_ID_KEY)
+    }
+
+    /// The tenant this run belongs to, if any
+    pub fn tenant_id(&self) -> Option<TenantId> {
+        self.metadata
+            .get(TENANT_ID_KEY)
+            .and_then(Value::as_str)
+            .map(TenantId::new)
+    }
+
+    /// Make this run belong to `tenant_id`
+    pub fn set_tenant_id(&mut self, tenant_id: &TenantId) {
+        self.metadata_mut()
+            .insert(TENANT_ID_KEY.to_string(), Value::String(tenant_id.to_string()));
+        self.updated_at = Utc::now();
+    }
+
+    pub fn with_tenant_id(mut self, tenant_id: &TenantId) -> Self {
+        self.set_tenant_id(tenant_id);
+        self
+    }
+
+    /// Record that this run emitted `event_id`, making it the cause of the
+    /// next event
+    pub fn record_emitted_event(&mut self, event_id: Uuid) {
+        self.metadata_mut()
+            .insert(CAUSATION_ID_KEY.to_string(), Value::String(event_id.to_string()));
+        self.updated_at = Utc::now();
+    }
+
+    fn metadata_uuid(&self, key: &str) -> Option<Uuid> {
+        self.metadata
+            .get(key)
+            .and_then(Value::as_str)
+            .and_then(|id| Uuid::parse_str(id).ok())
+    }
+
+    /// Size in bytes of this context serialized as JSON, without building
+    /// the JSON
+    pub fn serialized_size(&self) -> usize {
+        struct ByteCounter(usize);
+
+        impl std::io::Write for ByteCounter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0 += buf.len();
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut counter = ByteCounter(0);
+        // Serializing a context can't fail: its maps have string keys
+        let _ = serde_json::to_writer(&mut counter, self);
+        counter.0
+    }
+
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
+    /// Time left before the deadline.
+    ///
+    /// Returns `None` when there is no deadline and zero once it has passed.
+    pub fn remaining_time(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Shrink `timeout` so that it ends no later than the deadline
+    pub fn bounded_timeout(&self, timeout: Duration) -> Duration {
+        match self.remaining_time() {
+            Some(remaining) => timeout.min(remaining),
+            None => timeout,
+        }
+    }
+
+    /// Fail with a deadline-exceeded [`WorkflowError::NodeTimeout`] if the
+    /// deadline has already passed
+    pub fn check_deadline(&self, node_name: &str) -> Result<(), WorkflowError> {
+        match self.remaining_time() {
+            Some(remaining) if remaining.is_zero() => {
+                Err(WorkflowError::deadline_exceeded(node_name, Duration::ZERO))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the run has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Fail with [`WorkflowError::Cancelled`] if the run has been cancelled
+    pub fn check_cancelled(&self, node_name: &str) -> Result<(), WorkflowError> {
+        if self.is_cancelled() {
+            return Err(WorkflowError::cancelled(node_name));
+        }
+        Ok(())
+    }
+
+    /// Await `future`, failing with a deadline-exceeded
+    /// [`WorkflowError::NodeTimeout`] if it is still running at the deadline
+    pub async fn within_deadline<T, F>(&self, node_name: &str, future: F) -> Result<T, WorkflowError>
+    where
+        F: Future<Output = Result<T, WorkflowError>>,
+    {
+        match self.remaining_time() {
+            Some(remaining) => tokio::time::timeout(remaining, future)
+                .await
+                .map_err(|_| WorkflowError::deadline_exceeded(node_name, remaining))?,
+            None => future.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_emitted_events_chain_causation() {
+        let mut context = TaskContext::new("lineage_test".to_string(), json!({}));
+        assert_eq!(context.correlation_id(), context.event_id);
+        assert_eq!(context.causation_id(), None);
+
+        let correlation_id = Uuid::new_v4();
+        context.set_metadata(CORRELATION_ID_KEY, correlation_id).unwrap();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        context.record_emitted_event(first);
+        assert_eq!(context.causation_id(), Some(first));
+        context.record_emitted_event(second);
+        assert_eq!(context.causation_id(), Some(second));
+        assert_eq!(context.correlation_id(), correlation_id);
+    }
+
+    #[test]
+    fn test_clone_shares_data_until_written() {
+        let mut context = TaskContext::new("cow_test".to_string(), json!({}));
+        context.update_node("large", json!({ "items": vec![0; 1000] }));
+        context.set_metadata("run", 1).unwrap();
+
+        let mut branch = context.clone();
+        assert!(Arc::ptr_eq(&context.nodes, &branch.nodes));
+        assert_eq!(Arc::strong_count(&context.nodes), 2);
+        assert_eq!(Arc::strong_count(&context.metadata), 2);
+
+        branch.update_node("branch", true);
+        assert!(!Arc::ptr_eq(&context.nodes, &branch.nodes));
+        assert_eq!(Arc::strong_count(&context.nodes), 1);
+        // Metadata has not been written, so it is still shared
+        assert_eq!(Arc::strong_count(&context.metadata), 2);
+    }
+
+    #[test]
+    fn test_writes_do_not_leak_across_clones() {
+        let mut context = TaskContext::new("cow_test".to_string(), json!({}));
+        context.update_node("shared", 1);
+
+        let mut branch = context.clone();
+        branch.update_node("shared", 2);
+        branch.set_metadata("branch", true).unwrap();
+        context.nodes_mut().remove("shared");
+
+        assert_eq!(context.get_node_data::<i32>("shared").unwrap(), None);
+        assert_eq!(branch.get_node_data::<i32>("shared").unwrap(), Some(2));
+        assert_eq!(context.get_metadata::<bool>("branch").unwrap(), None);
+        assert_eq!(branch.get_metadata::<bool>("branch").unwrap(), Some(true));
+    }
+
+    #[test]
+    fn test_retention_keeps_last_results_and_pinned_keys() {
+        let mut context = TaskContext::new("retention_test".to_string(), json!({}));
+        context.set_retention_policy(RetentionPolicy::keep_last(2).with_pinned(["config"]));
+
+        context.update_node("config", json!({ "mode": "fast" }));
+        for step in 1..=4 {
+            context.update_node(&format!("step_{}", step), step);
+        }
+
+        let mut keys: Vec<_> = context.get_all_data().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, ["config", "step_3", "step_4"]);
+
+        // Rewriting a result makes it the most recent again
+        context.update_node("step_3", 33);
+        context.set_data("step_5", 5).unwrap();
+        assert_eq!(context.get_node_data::<i32>("step_3").unwrap(), Some(33));
+        assert_eq!(context.get_node_data::<i32>("step_4").unwrap(), None);
+        assert!(context.get_all_data().contains_key("config"));
+    }
+
+    #[test]
+    fn test_retention_applies_to_existing_and_merged_results() {
+        let mut context = TaskContext::new("retention_test".to_string(), json!({}));
+        context.update_node("draft", 1);
+        context.update_node("summary", 2);
+        context.set_retention_policy(RetentionPolicy::pinned_only(["summary"]));
+        assert_eq!(context.get_all_data().keys().collect::<Vec<_>>(), ["summary"]);
+
+        context.set_retention_policy(RetentionPolicy::keep_last(1));
+        context.nodes_mut().insert("branch_a".to_string(), json!(true));
+        context.apply_retention();
+        assert_eq!(context.get_all_data().keys().collect::<Vec<_>>(), ["branch_a"]);
+    }
+
+    #[test]
+    fn test_results_are_kept_without_retention_policy() {
+        let mut context = TaskContext::new("retention_test".to_string(), json!({}));
+        for step in 0..100 {
+            context.update_node(&format!("step_{}", step), step);
+        }
+        context.apply_retention();
+        assert_eq!(context.get_all_data().len(), 100);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        order_id: u32,
+        customer: String,
+        items: Vec<String>,
+        total: Option<f64>,
+    }
+
+    fn order() -> Order {
+        Order {
+            order_id: 42,
+            customer: "Ada".to_string(),
+            items: vec!["keyboard".to_string(), "mouse".to_string()],
+            total: None,
+        }
+    }
+
+    #[test]
+    fn test_event_fields() {
+        let context = TaskContext::new(
+            "test".to_string(),
+            json!({ "ticket_id": 42, "subject": "Refund", "priority": "high", "assignee": null }),
+        );
+
+        assert_eq!(context.get_event_field::<u64>("ticket_id"), Some(42));
+        assert_eq!(context.get_event_field::<String>("subject").as_deref(), Some("Refund"));
+        assert_eq!(context.get_event_field::<u64>("missing"), None);
+        assert_eq!(context.get_event_field::<String>("assignee"), None);
+        assert_eq!(context.get_event_field::<u8>("priority"), None);
+
+        assert_eq!(context.get_event_field_or("ticket_id", 0u64), 42);
+        assert_eq!(context.get_event_field_or("missing", 3u8), 3);
+        assert_eq!(context.get_event_field_or("priority", 1u8), 1);
+        assert_eq!(context.get_event_field_or("assignee", "unassigned".to_string()), "unassigned");
+
+        let not_an_object = TaskContext::new("test".to_string(), json!([1, 2]));
+        assert_eq!(not_an_object.get_event_field_or("ticket_id", 7u64), 7);
+    }
+
+    #[test]
+    fn test_model_round_trips_through_context() {
+        let context = TaskContext::from_model("orders".to_string(), &order()).unwrap();
+        assert_eq!(context.event_data["customer"], "Ada");
+        assert_eq!(context.extract_into::<Order>().unwrap(), order());
+
+        // Node results fill in and override event data fields
+        let mut context = context;
+        context.update_node("total", 64.5);
+        context.update_node("items", vec!["keyboard"]);
+        let priced: Order = context.extract_into().unwrap();
+        assert_eq!(priced.total, Some(64.5));
+        assert_eq!(priced.items, ["keyboard"]);
+        assert_eq!(priced.order_id, 42);
+
+        let context = TaskContext::from_model("orders".to_string(), &priced).unwrap();
+        assert_eq!(context.extract_into::<Order>().unwrap(), priced);
+    }
+
+    #[test]
+    fn test_model_mapping_errors() {
+        assert!(TaskContext::from_model("orders".to_string(), &vec![1, 2]).is_err());
+
+        let context = TaskContext::new("orders".to_string(), json!({ "order_id": "not a number" }));
+        match context.extract_into::<Order>() {
+            Err(WorkflowError::DeserializationError { expected_type, .. }) => {
+                assert!(expected_type.ends_with("Order"));
+            }
+            other => panic!("Expected DeserializationError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_serialization_is_unchanged() {
+        let mut context = TaskContext::new("cow_test".to_string(), json!({ "id": 7 }));
+        context.update_node("result", json!({ "ok": true }));
+
+        let serialized = serde_json::to_value(&context).unwrap();
+        assert_eq!(serialized["nodes"], json!({ "result": { "ok": true } }));
+
+        let restored: TaskContext = serde_json::from_value(serialized).unwrap();
+        assert_eq!(restored.get_all_data(), context.get_all_data());
+    }
 }
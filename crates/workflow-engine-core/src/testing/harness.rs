@@ -0,0 +1,290 @@
+//! # Workflow Test Harness
+//!
+//! In-memory harness for testing workflows. [`WorkflowTestHarness`] builds a
+//! workflow, registers its nodes, records every node that runs, and returns a
+//! [`TestRun`] with assertions over the executed nodes and their outputs.
+//!
+//! Enable the `testing` feature to use it from other crates, typically as a
+//! dev-dependency feature.
+//!
+//! ```rust,ignore
+//! let run = WorkflowTestHarness::new(
+//!     WorkflowBuilder::new::<Validate>("orders".to_string())
+//!         .add_node(NodeConfig::new::<Validate>().with_connections(vec![TypeId::of::<Ship>()]))
+//!         .add_node(NodeConfig::new::<Ship>()),
+//! )
+//! .with_node(Validate)
+//! .with_node(Ship)
+//! .run(json!({ "order_id": 7 }))
+//! .unwrap();
+//!
+//! run.assert_order(["Validate", "Ship"])
+//!     .assert_output("shipment", json!({ "order_id": 7 }));
+//! ```
+
+use std::any::TypeId;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::WorkflowError;
+use crate::nodes::Node;
+use crate::task::TaskContext;
+use crate::workflow::{builder::WorkflowBuilder, Workflow};
+
+/// Builds and runs a workflow, recording which nodes execute.
+pub struct WorkflowTestHarness {
+    workflow: Workflow,
+    executed: Arc<Mutex<Vec<String>>>,
+}
+
+impl WorkflowTestHarness {
+    /// Builds the workflow described by `builder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the workflow fails validation.
+    #[track_caller]
+    pub fn new(builder: WorkflowBuilder) -> Self {
+        let workflow = builder
+            .build()
+            .unwrap_or_else(|e| panic!("test workflow failed to build: {}", e));
+        Self {
+            workflow,
+            executed: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Registers `node` so that its executions are recorded
+    pub fn with_node<T: Node + 'static>(self, node: T) -> Self {
+        let recorded = Recorded {
+            node: Box::new(node),
+            executed: Arc::clone(&self.executed),
+        };
+        self.workflow
+            .get_registry()
+            .write()
+            .unwrap()
+            .register_as(TypeId::of::<T>(), Box::new(recorded));
+        self
+    }
+
+    pub fn workflow(&self) -> &Workflow {
+        &self.workflow
+    }
+
+    /// Runs the workflow on `input`.
+    ///
+    /// Returns the workflow's error if it fails; the nodes executed before the
+    /// failure are discarded.
+    pub fn run(&self, input: Value) -> Result<TestRun, WorkflowError> {
+        self.executed.lock().unwrap().clear();
+        let context = self.workflow.run(input)?;
+        let executed = std::mem::take(&mut *self.executed.lock().unwrap());
+        Ok(TestRun { context, executed })
+    }
+}
+
+/// Forwards to a registered node, recording its name when it runs
+#[derive(Debug)]
+struct Recorded {
+    node: Box<dyn Node>,
+    executed: Arc<Mutex<Vec<String>>>,
+}
+
+impl Node for Recorded {
+    fn node_name(&self) -> String {
+        self.node.node_name()
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        self.executed.lock().unwrap().push(self.node.node_name());
+        self.node.process(task_context)
+    }
+}
+
+/// Outcome of a [`WorkflowTestHarness`] run.
+///
+/// Assertions panic with a description of what actually happened, and return
+/// `&Self` so they can be chained.
+#[derive(Debug)]
+pub struct TestRun {
+    context: TaskContext,
+    executed: Vec<String>,
+}
+
+impl TestRun {
+    /// The final task context
+    pub fn context(&self) -> &TaskContext {
+        &self.context
+    }
+
+    /// Names of the nodes that ran, in the order they started
+    pub fn executed(&self) -> &[String] {
+        &self.executed
+    }
+
+    #[track_caller]
+    pub fn assert_node_ran(&self, name: &str) -> &Self {
+        assert!(
+            self.executed.iter().any(|executed| executed == name),
+            "expected node '{}' to run, but only {:?} ran",
+            name,
+            self.executed
+        );
+        self
+    }
+
+    #[track_caller]
+    pub fn assert_node_not_ran(&self, name: &str) -> &Self {
+        assert!(
+            !self.executed.iter().any(|executed| executed == name),
+            "expected node '{}' not to run, but it did: {:?}",
+            name,
+            self.executed
+        );
+        self
+    }
+
+    /// Asserts that the node result stored under `key` equals `expected`
+    #[track_caller]
+    pub fn assert_output<T: Serialize>(&self, key: &str, expected: T) -> &Self {
+        let expected = serde_json::to_value(expected).expect("expected output must serialize");
+        match self.context.nodes.get(key) {
+            Some(actual) => assert_eq!(actual, &expected, "unexpected output for '{}'", key),
+            None => panic!(
+                "expected output '{}', but the context only has {:?}",
+                key,
+                self.context.nodes.keys().collect::<Vec<_>>()
+            ),
+        }
+        self
+    }
+
+    /// Asserts that each of `names` ran, and that they first ran in this order.
+    ///
+    /// Other nodes may run in between.
+    #[track_caller]
+    pub fn assert_order<I, S>(&self, names: I) -> &Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut previous: Option<(String, usize)> = None;
+        for name in names {
+            let name = name.as_ref();
+            self.assert_node_ran(name);
+            let position = self.executed.iter().position(|executed| executed == name).unwrap();
+            if let Some((previous_name, previous_position)) = &previous {
+                assert!(
+                    position > *previous_position,
+                    "expected '{}' to run after '{}', but the order was {:?}",
+                    name,
+                    previous_name,
+                    self.executed
+                );
+            }
+            previous = Some((name.to_string(), position));
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::config::NodeConfig;
+    use serde_json::json;
+
+    #[derive(Debug)]
+    struct Validate;
+
+    impl Node for Validate {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let order: Value = task_context.get_event_data()?;
+            task_context.update_node("validated", order["express"].is_boolean());
+            Ok(task_context)
+        }
+    }
+
+    /// Router between shipping methods; the engine follows its first connection
+    #[derive(Debug)]
+    struct Dispatch;
+
+    impl Node for Dispatch {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Ok(task_context)
+        }
+    }
+
+    #[derive(Debug)]
+    struct Express;
+
+    impl Node for Express {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.update_node("shipping", json!({ "days": 1 }));
+            Ok(task_context)
+        }
+    }
+
+    #[derive(Debug)]
+    struct Standard;
+
+    impl Node for Standard {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.update_node("shipping", json!({ "days": 5 }));
+            Ok(task_context)
+        }
+    }
+
+    fn harness() -> WorkflowTestHarness {
+        WorkflowTestHarness::new(
+            WorkflowBuilder::new::<Validate>("shipping".to_string())
+                .add_node(NodeConfig::new::<Validate>().with_connections(vec![TypeId::of::<Dispatch>()]))
+                .add_node(
+                    NodeConfig::new::<Dispatch>()
+                        .with_router(true)
+                        .with_connections(vec![TypeId::of::<Standard>(), TypeId::of::<Express>()]),
+                )
+                .add_node(NodeConfig::new::<Standard>())
+                .add_node(NodeConfig::new::<Express>()),
+        )
+        .with_node(Validate)
+        .with_node(Dispatch)
+        .with_node(Standard)
+        .with_node(Express)
+    }
+
+    #[test]
+    fn test_records_executed_nodes_and_outputs() {
+        let run = harness().run(json!({ "express": false })).unwrap();
+
+        run.assert_order(["Validate", "Dispatch", "Standard"])
+            .assert_node_not_ran("Express")
+            .assert_output("validated", true)
+            .assert_output("shipping", json!({ "days": 5 }));
+        assert_eq!(run.executed(), ["Validate", "Dispatch", "Standard"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected node 'Express' to run")]
+    fn test_catches_node_that_did_not_run() {
+        let run = harness().run(json!({ "express": true })).unwrap();
+        run.assert_node_ran("Validate").assert_node_ran("Express");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 'Validate' to run after 'Standard'")]
+    fn test_catches_out_of_order_execution() {
+        let run = harness().run(json!({})).unwrap();
+        run.assert_order(["Standard", "Validate"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected output for 'shipping'")]
+    fn test_catches_wrong_output() {
+        let run = harness().run(json!({})).unwrap();
+        run.assert_output("shipping", json!({ "days": 1 }));
+    }
+}
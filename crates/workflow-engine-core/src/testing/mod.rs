@@ -1,13 +1,18 @@
-/// Testing utilities and mock implementations for workflow-engine-core
-/// 
-/// This module provides test infrastructure that allows running tests
-/// without external service dependencies.
+//! Testing utilities and mock implementations for workflow-engine-core
+//!
+//! This module provides test infrastructure that allows running tests
+//! without external service dependencies.
 
-#[cfg(test)]
-pub mod mocks;
+// Commented out - written against the old registry and workflow definition APIs
+// #[cfg(test)]
+// pub mod mocks;
 
-#[cfg(test)]
-pub mod fixtures;
+// #[cfg(test)]
+// pub mod fixtures;
 
-#[cfg(test)]
-pub mod test_config;
\ No newline at end of file
+// #[cfg(test)]
+// pub mod test_config;
+
+pub mod harness;
+
+pub use harness::{TestRun, WorkflowTestHarness};
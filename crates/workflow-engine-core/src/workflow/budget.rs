@@ -0,0 +1,167 @@
+//! Cost and latency budgets for the AI nodes of a run
+//!
+//! An [`AiBudget`] declares what each AI node is expected to cost and how
+//! long it is expected to take, and caps the total for a run. Before an AI
+//! node runs, the engine checks whether it still fits: if not, the node is
+//! skipped or, under [`BudgetPolicy::Downgrade`], replaced by a cheaper
+//! fallback node when one is declared and fits. What the run has spent is
+//! kept in the context metadata under [`AI_BUDGET_KEY`].
+//!
+//! ```rust,ignore
+//! let budget = AiBudget::new(BudgetPolicy::Downgrade)
+//!     .with_max_cost(0.05)
+//!     .with_max_latency(Duration::from_secs(10))
+//!     .with_ai_node::<SummarizeNode>(AiNodeCost::new(0.04, Duration::from_secs(3)))
+//!     .with_fallback::<SummarizeNode, CheapSummarizeNode>(AiNodeCost::new(0.005, Duration::from_secs(1)));
+//! let workflow = workflow.with_ai_budget(budget);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::nodes::Node;
+
+/// Metadata key holding the run's [`BudgetState`], when the workflow has an
+/// AI budget
+pub const AI_BUDGET_KEY: &str = "ai_budget";
+
+/// What to do with an AI node that would take the run over budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPolicy {
+    /// Don't run the node
+    Skip,
+    /// Run the node's fallback instead if it fits, otherwise skip it
+    Downgrade,
+}
+
+/// Expected cost and latency of one run of an AI node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AiNodeCost {
+    pub cost_usd: f64,
+    pub latency: Duration,
+}
+
+impl AiNodeCost {
+    pub fn new(cost_usd: f64, latency: Duration) -> Self {
+        Self { cost_usd, latency }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AiNode {
+    cost: AiNodeCost,
+    fallback: Option<(TypeId, AiNodeCost)>,
+}
+
+/// What a run has spent on AI nodes, and which were cut to stay in budget
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetState {
+    pub spent_usd: f64,
+    /// Time since the run started, as of the last AI node
+    pub elapsed_ms: u64,
+    pub skipped: Vec<String>,
+    pub downgraded: Vec<String>,
+}
+
+/// Caps on the total cost and latency of a run's AI nodes
+#[derive(Debug, Clone)]
+pub struct AiBudget {
+    max_cost_usd: Option<f64>,
+    max_latency: Option<Duration>,
+    policy: BudgetPolicy,
+    nodes: HashMap<TypeId, AiNode>,
+}
+
+impl AiBudget {
+    pub fn new(policy: BudgetPolicy) -> Self {
+        Self {
+            max_cost_usd: None,
+            max_latency: None,
+            policy,
+            nodes: HashMap::new(),
+        }
+    }
+
+    pub fn with_max_cost(mut self, max_cost_usd: f64) -> Self {
+        self.max_cost_usd = Some(max_cost_usd);
+        self
+    }
+
+    /// Caps the time from the start of the run until the end of its last
+    /// AI node
+    pub fn with_max_latency(mut self, max_latency: Duration) -> Self {
+        self.max_latency = Some(max_latency);
+        self
+    }
+
+    /// Counts node `T` against the budget
+    pub fn with_ai_node<T: Node + 'static>(mut self, cost: AiNodeCost) -> Self {
+        self.nodes
+            .entry(TypeId::of::<T>())
+            .and_modify(|node| node.cost = cost)
+            .or_insert(AiNode { cost, fallback: None });
+        self
+    }
+
+    /// Declares `F`, costing `cost`, as the cheaper stand-in for AI node
+    /// `T` under [`BudgetPolicy::Downgrade`]. `F` must be registered with
+    /// the workflow.
+    pub fn with_fallback<T: Node + 'static, F: Node + 'static>(mut self, cost: AiNodeCost) -> Self {
+        if let Some(node) = self.nodes.get_mut(&TypeId::of::<T>()) {
+            node.fallback = Some((TypeId::of::<F>(), cost));
+        }
+        self
+    }
+
+    pub fn policy(&self) -> BudgetPolicy {
+        self.policy
+    }
+
+    /// Decides which node to run in place of `node_type`, charging it to
+    /// `state`, or `None` to skip it. Nodes the budget doesn't know about
+    /// always run.
+    pub(crate) fn admit(
+        &self,
+        node_type: TypeId,
+        node_name: &str,
+        state: &mut BudgetState,
+        elapsed: Duration,
+    ) -> Option<TypeId> {
+        let Some(node) = self.nodes.get(&node_type) else {
+            return Some(node_type);
+        };
+        state.elapsed_ms = elapsed.as_millis() as u64;
+
+        if self.fits(&node.cost, state, elapsed) {
+            state.spent_usd += node.cost.cost_usd;
+            return Some(node_type);
+        }
+        if self.policy == BudgetPolicy::Downgrade {
+            if let Some((fallback, cost)) = &node.fallback {
+                if self.fits(cost, state, elapsed) {
+                    state.spent_usd += cost.cost_usd;
+                    state.downgraded.push(node_name.to_string());
+                    tracing::warn!(node = node_name, "AI budget reached, running fallback node");
+                    return Some(*fallback);
+                }
+            }
+        }
+
+        state.skipped.push(node_name.to_string());
+        tracing::warn!(node = node_name, "AI budget reached, skipping node");
+        None
+    }
+
+    fn fits(&self, cost: &AiNodeCost, state: &BudgetState, elapsed: Duration) -> bool {
+        let within_cost = self
+            .max_cost_usd
+            .is_none_or(|max| state.spent_usd + cost.cost_usd <= max + f64::EPSILON);
+        let within_latency = self
+            .max_latency
+            .is_none_or(|max| elapsed + cost.latency <= max);
+        within_cost && within_latency
+    }
+}
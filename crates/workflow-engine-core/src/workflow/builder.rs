@@ -38,16 +38,61 @@ impl WorkflowBuilder {
         }
     }
 
+    pub fn version(mut self, version: u32) -> Self {
+        self.schema.version = version;
+        self
+    }
+
     pub fn description(mut self, description: String) -> Self {
         self.schema.description = Some(description);
         self
     }
 
+    /// Declare the input the workflow accepts as a JSON Schema. Runs whose
+    /// event data doesn't conform fail with a
+    /// [`WorkflowError::ValidationError`] before any node runs; see
+    /// [`crate::json_schema`] for the keywords checked.
+    pub fn with_input_schema(mut self, schema: serde_json::Value) -> Self {
+        self.schema.input_schema = Some(schema);
+        self
+    }
+
     pub fn add_node(mut self, config: NodeConfig) -> Self {
         self.schema.nodes.push(config);
         self
     }
 
+    /// Run `branches` in parallel before the node added last, or before the
+    /// start node if none has been added yet, each keeping its output under
+    /// its name.
+    ///
+    /// A later node reads a branch's output with
+    /// [`TaskContext::get_branch_data`](crate::task::TaskContext::get_branch_data).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let workflow = WorkflowBuilder::new::<Aggregate>("compare_models".to_string())
+    ///     .add_node(NodeConfig::new::<Aggregate>())
+    ///     .parallel_scoped(&[
+    ///         ("openai", TypeId::of::<OpenAiSummary>()),
+    ///         ("anthropic", TypeId::of::<AnthropicSummary>()),
+    ///     ])
+    ///     .build()?;
+    /// ```
+    pub fn parallel_scoped(mut self, branches: &[(&str, TypeId)]) -> Self {
+        let config = self
+            .schema
+            .nodes
+            .pop()
+            .unwrap_or_else(|| NodeConfig::for_type(self.schema.start));
+        let config = branches
+            .iter()
+            .fold(config, |config, &(name, node_type)| config.with_scoped_parallel_node(name, node_type));
+        self.schema.nodes.push(config);
+        self
+    }
+
     // MCP client methods removed - use workflow-engine-mcp crate directly for MCP integration
 
 
@@ -0,0 +1,158 @@
+//! Structured descriptions of workflows
+//!
+//! [`Workflow::describe`](super::Workflow::describe) summarizes a workflow's
+//! nodes and edges from its schema and node registry, for admin UIs and other
+//! tooling that needs the structure without running the workflow.
+
+use serde::{Deserialize, Serialize};
+
+/// Structure of a workflow: its nodes, in execution order, and the edges between them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowDescription {
+    pub workflow_type: String,
+    pub version: u32,
+    pub description: Option<String>,
+    /// Name of the node execution starts at
+    pub start: String,
+    /// Nodes in the order they are reached from the start node, followed by
+    /// any nodes that cannot be reached
+    pub nodes: Vec<NodeDescription>,
+    pub edges: Vec<EdgeDescription>,
+}
+
+/// A node of a [`WorkflowDescription`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeDescription {
+    /// The node's [`Node::node_name`](crate::nodes::Node::node_name), or its
+    /// type id if no node of this type is registered
+    pub name: String,
+    pub description: Option<String>,
+    /// Whether this node chooses which of its connections runs next
+    pub is_router: bool,
+    /// Whether this node fans out to parallel nodes before running
+    pub has_parallel_nodes: bool,
+    /// Whether this node runs as a parallel branch of another node
+    pub is_parallel_branch: bool,
+    /// Whether a node of this type is registered with the workflow
+    pub registered: bool,
+}
+
+/// How one node leads to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// `to` runs after `from`
+    Connection,
+    /// `to` runs in parallel, before `from` itself runs
+    Parallel,
+}
+
+/// A directed edge of a [`WorkflowDescription`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeDescription {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WorkflowError;
+    use crate::nodes::{config::NodeConfig, Node};
+    use crate::task::TaskContext;
+    use crate::workflow::builder::WorkflowBuilder;
+    use std::any::TypeId;
+
+    macro_rules! test_node {
+        ($name:ident) => {
+            #[derive(Debug)]
+            struct $name;
+
+            impl Node for $name {
+                fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+                    Ok(task_context)
+                }
+            }
+        };
+    }
+
+    test_node!(Intake);
+    test_node!(Triage);
+    test_node!(Enrich);
+    test_node!(Score);
+    test_node!(Escalate);
+    test_node!(Resolve);
+
+    fn node<'a>(description: &'a WorkflowDescription, name: &str) -> &'a NodeDescription {
+        description
+            .nodes
+            .iter()
+            .find(|node| node.name == name)
+            .unwrap_or_else(|| panic!("{} missing from description", name))
+    }
+
+    #[test]
+    fn test_describes_router_and_parallel_fan_out() {
+        let workflow = WorkflowBuilder::new::<Intake>("support".to_string())
+            .version(3)
+            .add_node(
+                NodeConfig::new::<Intake>()
+                    .with_connections(vec![TypeId::of::<Triage>()])
+                    .with_parallel_nodes(vec![TypeId::of::<Enrich>(), TypeId::of::<Score>()]),
+            )
+            .add_node(
+                NodeConfig::new::<Triage>()
+                    .with_router(true)
+                    .with_description("Routes by priority".to_string())
+                    .with_connections(vec![TypeId::of::<Escalate>(), TypeId::of::<Resolve>()]),
+            )
+            .add_node(NodeConfig::new::<Escalate>())
+            .add_node(NodeConfig::new::<Resolve>())
+            .build()
+            .unwrap();
+        workflow.register_node(Intake);
+        workflow.register_node(Triage);
+        workflow.register_node(Enrich);
+        workflow.register_node(Score);
+        workflow.register_node(Escalate);
+
+        let description = workflow.describe();
+        assert_eq!(description.workflow_type, "support");
+        assert_eq!(description.version, 3);
+        assert_eq!(description.start, "Intake");
+
+        let names: Vec<_> = description.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names[..5], ["Intake", "Enrich", "Score", "Triage", "Escalate"]);
+
+        let intake = node(&description, "Intake");
+        assert!(intake.has_parallel_nodes && !intake.is_router && !intake.is_parallel_branch);
+        let triage = node(&description, "Triage");
+        assert!(triage.is_router);
+        assert_eq!(triage.description.as_deref(), Some("Routes by priority"));
+        assert!(node(&description, "Score").is_parallel_branch);
+
+        // Resolve was never registered, so it is described by its type id
+        let resolve = description.nodes.last().unwrap();
+        assert!(!resolve.registered);
+        assert!(resolve.name.starts_with("TypeId"));
+
+        let edge = |from: &str, to: &str, kind| EdgeDescription {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        };
+        assert_eq!(
+            description.edges[..4],
+            [
+                edge("Intake", "Enrich", EdgeKind::Parallel),
+                edge("Intake", "Score", EdgeKind::Parallel),
+                edge("Intake", "Triage", EdgeKind::Connection),
+                edge("Triage", "Escalate", EdgeKind::Connection),
+            ]
+        );
+        assert_eq!(description.edges.len(), 5);
+        assert_eq!(description.edges[4].from, "Triage");
+        assert_eq!(description.edges[4].to, resolve.name);
+    }
+}
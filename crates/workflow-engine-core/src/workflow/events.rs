@@ -0,0 +1,90 @@
+//! Node lifecycle events published while a workflow runs
+//!
+//! Every [`Workflow`](super::Workflow) publishes a [`NodeEvent`] when a node
+//! starts, completes or fails. Subscribers get their own receiver from
+//! [`Workflow::subscribe`](super::Workflow::subscribe) and can filter by
+//! execution to follow a single run, e.g. to stream its progress to a client.
+//!
+//! ```rust,ignore
+//! let mut events = workflow.subscribe();
+//! let context = workflow.run(json!({ "text": "..." }))?;
+//! while let Ok(event) = events.try_recv() {
+//!     println!("{} {:?}", event.node, event.kind);
+//! }
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Events buffered per subscriber before the slowest one starts missing them
+pub const NODE_EVENT_CAPACITY: usize = 256;
+
+/// What happened to a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeEventKind {
+    Started,
+    Completed,
+    Failed { error: String },
+}
+
+/// A node starting, completing or failing during a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeEvent {
+    /// The run's [`TaskContext::event_id`](crate::task::TaskContext::event_id)
+    pub execution_id: Uuid,
+    pub workflow_type: String,
+    pub node: String,
+    #[serde(flatten)]
+    pub kind: NodeEventKind,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Publishes node events to any number of subscribers.
+///
+/// Publishing never blocks and never fails; events are dropped when nobody
+/// is subscribed.
+#[derive(Debug, Clone)]
+pub struct NodeEventBus {
+    sender: broadcast::Sender<NodeEvent>,
+}
+
+impl Default for NodeEventBus {
+    fn default() -> Self {
+        Self::new(NODE_EVENT_CAPACITY)
+    }
+}
+
+impl NodeEventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publishes a node event. Workflows publish their own; executors that
+    /// run steps outside [`Workflow`](super::Workflow) can publish theirs too.
+    pub fn publish(
+        &self,
+        execution_id: Uuid,
+        workflow_type: &str,
+        node: &str,
+        kind: NodeEventKind,
+    ) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let _ = self.sender.send(NodeEvent {
+            execution_id,
+            workflow_type: workflow_type.to_string(),
+            node: node.to_string(),
+            kind,
+            timestamp: Utc::now(),
+        });
+    }
+}
@@ -0,0 +1,370 @@
+//! Lints for workflow definitions
+//!
+//! [`WorkflowValidator`](super::validator::WorkflowValidator) rejects
+//! workflows that cannot run. [`WorkflowLinter`] looks at workflows that
+//! can, and points out definitions that are probably mistakes: nodes that
+//! never run, branches that stop early, routers with nothing to choose
+//! between and parallel nodes that run more than once.
+
+use std::any::TypeId;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::Workflow;
+
+/// Tag marking a node as a deliberate end of the workflow, see
+/// [`LintKind::DeadEnd`]
+pub const TERMINAL_TAG: &str = "terminal";
+
+/// How much a lint matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    /// Harmless, but the definition could be simpler
+    Info,
+    /// The workflow probably doesn't do what was intended
+    Warning,
+}
+
+/// What a lint is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// The node is registered or configured but cannot be reached from the
+    /// start node
+    UnreachableNode,
+    /// The node has no connections, so the run ends there, but it isn't
+    /// tagged [`TERMINAL_TAG`]. A workflow with a single such node is taken
+    /// to end there and isn't linted.
+    DeadEnd,
+    /// The router has a single connection, so it has nothing to choose
+    SingleConnectionRouter,
+    /// The node is listed as a parallel node more than once, so it runs
+    /// several times
+    DuplicateParallelNode,
+}
+
+/// A problem found by [`WorkflowLinter`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub severity: LintSeverity,
+    pub message: String,
+    /// Name of the node the lint is about
+    pub node: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} [{}]: {}", self.severity, self.node, self.message)
+    }
+}
+
+/// Checks a built workflow for common mistakes.
+///
+/// ```rust,ignore
+/// for lint in WorkflowLinter::new(&workflow).lint() {
+///     eprintln!("{}", lint);
+/// }
+/// ```
+pub struct WorkflowLinter<'a> {
+    workflow: &'a Workflow,
+}
+
+impl<'a> WorkflowLinter<'a> {
+    pub fn new(workflow: &'a Workflow) -> Self {
+        Self { workflow }
+    }
+
+    /// Every lint for the workflow, warnings first
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        self.lint_unreachable_nodes(&mut lints);
+        self.lint_dead_ends(&mut lints);
+        self.lint_routers(&mut lints);
+        self.lint_parallel_nodes(&mut lints);
+        lints.sort_by_key(|lint| std::cmp::Reverse(lint.severity));
+        lints
+    }
+
+    fn lint_unreachable_nodes(&self, lints: &mut Vec<Lint>) {
+        let reachable = self.reachable_nodes();
+        let mut registered = self.workflow.registry.read().unwrap().get_all_node_types();
+        registered.sort_by_cached_key(|node_type| self.name_of(*node_type));
+        let mut linted = HashSet::new();
+        let nodes = self
+            .workflow
+            .schema
+            .nodes
+            .iter()
+            .map(|config| config.node_type)
+            .chain(registered);
+        for node_type in nodes {
+            if reachable.contains(&node_type) || !linted.insert(node_type) {
+                continue;
+            }
+            lints.push(self.lint_for(
+                LintKind::UnreachableNode,
+                LintSeverity::Warning,
+                node_type,
+                "is never run: no path from the start node leads to it".to_string(),
+            ));
+        }
+    }
+
+    fn lint_dead_ends(&self, lints: &mut Vec<Lint>) {
+        let schema = &self.workflow.schema;
+        let branches: HashSet<TypeId> = schema
+            .nodes
+            .iter()
+            .flat_map(|config| config.parallel_nodes.iter().copied())
+            .collect();
+        let ends: Vec<TypeId> = self
+            .reachable_order()
+            .into_iter()
+            .filter(|node_type| !branches.contains(node_type))
+            .filter(|node_type| {
+                schema
+                    .nodes
+                    .iter()
+                    .find(|config| config.node_type == *node_type)
+                    .is_none_or(|config| config.connections.is_empty())
+            })
+            .collect();
+        if ends.len() < 2 {
+            return;
+        }
+
+        for node_type in ends {
+            let tagged = schema
+                .nodes
+                .iter()
+                .find(|config| config.node_type == node_type)
+                .is_some_and(|config| config.tags.iter().any(|tag| tag == TERMINAL_TAG));
+            if !tagged {
+                lints.push(self.lint_for(
+                    LintKind::DeadEnd,
+                    LintSeverity::Warning,
+                    node_type,
+                    format!(
+                        "has no outgoing connections, so runs reaching it stop there; \
+                         tag it '{}' if that is intended",
+                        TERMINAL_TAG
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn lint_routers(&self, lints: &mut Vec<Lint>) {
+        for config in &self.workflow.schema.nodes {
+            if config.is_router && config.connections.len() == 1 {
+                lints.push(self.lint_for(
+                    LintKind::SingleConnectionRouter,
+                    LintSeverity::Info,
+                    config.node_type,
+                    format!(
+                        "is a router with a single connection, to {}; a plain connection does the same",
+                        self.name_of(config.connections[0])
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn lint_parallel_nodes(&self, lints: &mut Vec<Lint>) {
+        let mut seen: Vec<(TypeId, TypeId)> = Vec::new();
+        for config in &self.workflow.schema.nodes {
+            for &branch in &config.parallel_nodes {
+                if let Some((first_parent, _)) = seen.iter().find(|(_, seen)| *seen == branch) {
+                    let message = if *first_parent == config.node_type {
+                        format!("is listed more than once as a parallel node of {}", self.name_of(config.node_type))
+                    } else {
+                        format!(
+                            "is a parallel node of both {} and {}, so it runs twice",
+                            self.name_of(*first_parent),
+                            self.name_of(config.node_type)
+                        )
+                    };
+                    lints.push(self.lint_for(LintKind::DuplicateParallelNode, LintSeverity::Warning, branch, message));
+                } else {
+                    seen.push((config.node_type, branch));
+                }
+            }
+        }
+    }
+
+    fn reachable_nodes(&self) -> HashSet<TypeId> {
+        self.reachable_order().into_iter().collect()
+    }
+
+    /// Nodes reachable from the start node through connections and parallel
+    /// nodes, breadth-first
+    fn reachable_order(&self) -> Vec<TypeId> {
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([self.workflow.schema.start]);
+        while let Some(node_type) = queue.pop_front() {
+            if order.contains(&node_type) {
+                continue;
+            }
+            order.push(node_type);
+            if let Some(config) = self.workflow.schema.nodes.iter().find(|config| config.node_type == node_type) {
+                queue.extend(&config.parallel_nodes);
+                queue.extend(&config.connections);
+            }
+        }
+        order
+    }
+
+    fn lint_for(&self, kind: LintKind, severity: LintSeverity, node_type: TypeId, message: String) -> Lint {
+        let node = self.name_of(node_type);
+        Lint {
+            kind,
+            severity,
+            message: format!("{} {}", node, message),
+            node,
+        }
+    }
+
+    fn name_of(&self, node_type: TypeId) -> String {
+        self.workflow
+            .registry
+            .read()
+            .unwrap()
+            .get(&node_type)
+            .map(|node| node.node_name())
+            .unwrap_or_else(|| format!("{:?}", node_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WorkflowError;
+    use crate::nodes::{config::NodeConfig, Node};
+    use crate::task::TaskContext;
+    use crate::workflow::builder::WorkflowBuilder;
+
+    macro_rules! test_node {
+        ($($name:ident),*) => {
+            $(
+                #[derive(Debug)]
+                struct $name;
+
+                impl Node for $name {
+                    fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+                        Ok(task_context)
+                    }
+                }
+            )*
+        };
+    }
+
+    test_node!(Intake, Triage, Enrich, Score, Escalate, Resolve, Archive);
+
+    fn register_all(workflow: &Workflow) {
+        workflow.register_node(Intake);
+        workflow.register_node(Triage);
+        workflow.register_node(Enrich);
+        workflow.register_node(Score);
+        workflow.register_node(Escalate);
+        workflow.register_node(Resolve);
+    }
+
+    fn kinds(lints: &[Lint]) -> Vec<(LintKind, &str)> {
+        lints.iter().map(|lint| (lint.kind, lint.node.as_str())).collect()
+    }
+
+    #[test]
+    fn test_clean_workflow_has_no_lints() {
+        let workflow = WorkflowBuilder::new::<Intake>("support".to_string())
+            .add_node(
+                NodeConfig::new::<Intake>()
+                    .with_connections(vec![TypeId::of::<Triage>()])
+                    .with_parallel_nodes(vec![TypeId::of::<Enrich>(), TypeId::of::<Score>()]),
+            )
+            .add_node(
+                NodeConfig::new::<Triage>()
+                    .with_router(true)
+                    .with_connections(vec![TypeId::of::<Escalate>(), TypeId::of::<Resolve>()]),
+            )
+            .add_node(NodeConfig::new::<Escalate>().with_tags(vec![TERMINAL_TAG.to_string()]))
+            .add_node(NodeConfig::new::<Resolve>().with_tags(vec![TERMINAL_TAG.to_string()]))
+            .build()
+            .unwrap();
+        register_all(&workflow);
+
+        assert_eq!(WorkflowLinter::new(&workflow).lint(), vec![]);
+    }
+
+    #[test]
+    fn test_unreachable_nodes_and_dead_ends() {
+        let workflow = WorkflowBuilder::new::<Intake>("support".to_string())
+            .add_node(
+                NodeConfig::new::<Intake>()
+                    .with_router(true)
+                    .with_connections(vec![TypeId::of::<Escalate>(), TypeId::of::<Resolve>()]),
+            )
+            .add_node(NodeConfig::new::<Escalate>())
+            .add_node(NodeConfig::new::<Resolve>().with_tags(vec![TERMINAL_TAG.to_string()]))
+            .build()
+            .unwrap();
+        register_all(&workflow);
+
+        let lints = WorkflowLinter::new(&workflow).lint();
+        let mut found = kinds(&lints);
+        found.sort_by_key(|(_, node)| node.to_string());
+        assert_eq!(
+            found,
+            [
+                (LintKind::UnreachableNode, "Enrich"),
+                (LintKind::DeadEnd, "Escalate"),
+                (LintKind::UnreachableNode, "Score"),
+                (LintKind::UnreachableNode, "Triage"),
+            ]
+        );
+        assert!(lints.iter().all(|lint| lint.severity == LintSeverity::Warning));
+        let dead_end = lints.iter().find(|lint| lint.kind == LintKind::DeadEnd).unwrap();
+        assert!(dead_end.message.starts_with("Escalate has no outgoing connections"));
+    }
+
+    #[test]
+    fn test_single_connection_router_and_duplicate_parallel_nodes() {
+        let workflow = WorkflowBuilder::new::<Intake>("support".to_string())
+            .add_node(
+                NodeConfig::new::<Intake>()
+                    .with_connections(vec![TypeId::of::<Triage>()])
+                    .with_parallel_nodes(vec![TypeId::of::<Enrich>(), TypeId::of::<Enrich>()]),
+            )
+            .add_node(
+                NodeConfig::new::<Triage>()
+                    .with_router(true)
+                    .with_connections(vec![TypeId::of::<Archive>()])
+                    .with_parallel_nodes(vec![TypeId::of::<Score>(), TypeId::of::<Enrich>()]),
+            )
+            .add_node(NodeConfig::new::<Archive>())
+            .build()
+            .unwrap();
+        workflow.register_node(Intake);
+        workflow.register_node(Triage);
+        workflow.register_node(Enrich);
+        workflow.register_node(Score);
+        workflow.register_node(Archive);
+
+        let lints = WorkflowLinter::new(&workflow).lint();
+        assert_eq!(
+            kinds(&lints),
+            [
+                (LintKind::DuplicateParallelNode, "Enrich"),
+                (LintKind::DuplicateParallelNode, "Enrich"),
+                (LintKind::SingleConnectionRouter, "Triage"),
+            ]
+        );
+        assert!(lints[0].message.contains("more than once as a parallel node of Intake"));
+        assert!(lints[1].message.contains("parallel node of both Intake and Triage"));
+        assert_eq!(lints[2].severity, LintSeverity::Info);
+        assert!(lints[2].message.contains("single connection, to Archive"));
+    }
+}
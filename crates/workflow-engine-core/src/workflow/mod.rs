@@ -20,10 +20,38 @@
 //! [`builder::WorkflowBuilder`] provides a fluent interface for constructing workflows
 //! with type safety and validation.
 //!
+//! ### Description
+//! [`description::WorkflowDescription`] summarizes a workflow's nodes and edges,
+//! as returned by [`Workflow::describe`].
+//!
 //! ### Validator
 //! [`validator::WorkflowValidator`] ensures workflow schemas are valid before execution,
 //! checking for cycles, unreachable nodes, and proper routing configuration.
 //!
+//! ### Replay
+//! [`replay::ReplayStore`] keeps the input and external call responses of each
+//! run, so that [`Workflow::replay`] can reproduce a failed run.
+//!
+//! ### Tenants
+//! A run started with a [`TenantId`](crate::task::TenantId) in its context
+//! stays in that tenant: nodes cannot move it to another one.
+//!
+//! ### Non-critical Nodes
+//! A node marked with [`NodeConfig::with_critical(false)`](crate::nodes::config::NodeConfig::with_critical)
+//! doesn't fail the run: its failure is recorded under [`NODE_FAILURES_KEY`]
+//! and the run continues from the context the node was given, ending
+//! [partially completed](report::ExecutionStatus::PartiallyCompleted).
+//!
+//! ### Named Nodes
+//! Nodes registered by id with [`Workflow::register_named_node`] can be
+//! referenced from node configs with
+//! [`NodeConfig::with_named_node`](crate::nodes::config::NodeConfig::with_named_node),
+//! so one node type can appear several times, configured differently.
+//!
+//! ### Pipeline
+//! [`pipeline::Pipeline`] chains workflows, running each on the final
+//! context of the previous one and stopping at the first failure.
+//!
 //! ## Usage Examples
 //!
 //! ### Basic Workflow Creation
@@ -189,6 +217,7 @@
 //!     model_provider: ModelProvider::Anthropic,
 //!     model_name: "claude-3-sonnet-20240229".to_string(),
 //!     mcp_server_uri: None,
+//!     ai_fallback_template: None,
 //! };
 //!
 //! // Build AI-enhanced workflow
@@ -443,33 +472,85 @@
 
 use std::{
     any::TypeId,
-    sync::{Arc, RwLock},
+    collections::HashMap,
+    sync::{mpsc, Arc, RwLock},
     thread,
+    time::Instant,
 };
 
+use futures_util::stream::{Stream, StreamExt};
 use serde_json::Value;
 
+use budget::{AiBudget, BudgetState, AI_BUDGET_KEY};
+use events::{NodeEvent, NodeEventBus, NodeEventKind};
+use replay::{ExecutionRecord, ExternalCalls, ReplayStore};
+use report::{ExecutionReport, NodeFailure};
+use description::{EdgeDescription, EdgeKind, NodeDescription, WorkflowDescription};
 use schema::WorkflowSchema;
+use shared_state::SharedState;
 use validator::WorkflowValidator;
 
 // use crate::db::event::Event;  // Commented out - db moved to API crate
 
 use super::{
-    error::WorkflowError,
+    error::{ErrorContext, ErrorExt, WorkflowError},
+    json_schema::SchemaValidator,
+    metrics::{
+        MetricsRecorder, NoopRecorder, CONTEXT_SIZE_BYTES, CONTEXT_SIZE_WARNINGS_TOTAL, NODE_DURATION_SECONDS,
+        NODE_ERRORS_TOTAL, NODE_RETRIES_TOTAL,
+    },
     // mcp::server::MCPToolServer,  // Commented out to avoid circular dependency
     nodes::{Node, registry::NodeRegistry},
-    task::TaskContext,
+    task::{CancellationToken, TaskContext, TenantId, CORRELATION_ID_KEY},
 };
 
+pub mod budget;
 pub mod builder;
+pub mod description;
+pub mod events;
+pub mod linter;
+pub mod pipeline;
+pub mod replay;
+pub mod report;
+pub mod scheduler;
 pub mod schema;
+pub mod shared_state;
 pub mod validator;
+pub mod versions;
 pub mod workflow_builder;
+mod worker_pool;
+
+/// Metadata key under which each run records the version of the workflow it ran
+pub const WORKFLOW_VERSION_KEY: &str = "workflow_version";
+
+/// Metadata key holding how many node retries a run has left, when the
+/// workflow has a retry budget
+pub const RETRY_BUDGET_KEY: &str = "retry_budget_remaining";
+
+/// Suggested threshold for [`Workflow::with_context_size_warning`], in bytes
+pub const DEFAULT_CONTEXT_SIZE_WARNING_BYTES: usize = 1024 * 1024;
+
+/// Metadata key listing the [`NodeFailure`]s of non-critical nodes a run
+/// continued past
+pub const NODE_FAILURES_KEY: &str = "node_failures";
+
+/// [`ErrorContext`] keys under which failed runs record where they failed
+pub const WORKFLOW_TYPE_CONTEXT_KEY: &str = "workflow_type";
+pub const NODE_NAME_CONTEXT_KEY: &str = "node_name";
+pub const EXECUTION_ID_CONTEXT_KEY: &str = "execution_id";
 
 /// Represents a workflow with its schema and node registry.
 pub struct Workflow {
     schema: WorkflowSchema,
     registry: Arc<RwLock<NodeRegistry>>,
+    shared_state: Option<SharedState>,
+    retry_budget: Option<u32>,
+    ai_budget: Option<AiBudget>,
+    metrics: Arc<dyn MetricsRecorder>,
+    events: NodeEventBus,
+    context_size_warning: Option<usize>,
+    replay_store: Option<Arc<dyn ReplayStore>>,
+    input_validator: Option<SchemaValidator>,
 }
 
 impl Workflow {
@@ -496,12 +577,131 @@ impl Workflow {
         let validator = WorkflowValidator::new(&schema);
         validator.validate()?;
 
+        let input_validator = schema.input_schema.clone().map(SchemaValidator::new);
         Ok(Self {
             schema,
             registry: Arc::new(RwLock::new(NodeRegistry::new())),
+            shared_state: None,
+            retry_budget: None,
+            ai_budget: None,
+            metrics: Arc::new(NoopRecorder),
+            events: NodeEventBus::default(),
+            context_size_warning: None,
+            replay_store: None,
+            input_validator,
         })
     }
 
+    /// Attaches state that every node of each run can read and write live.
+    ///
+    /// Parallel nodes otherwise only see their own copy of the context until
+    /// results are merged. The same state is handed to every run of this
+    /// workflow, so it can also carry data between runs.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use your_crate::{Workflow, workflow::shared_state::SharedState};
+    ///
+    /// let state = SharedState::new();
+    /// let workflow = Workflow::new(schema)
+    ///     .expect("Failed to create workflow")
+    ///     .with_shared_state(state.clone());
+    /// ```
+    pub fn with_shared_state(mut self, shared_state: SharedState) -> Self {
+        self.shared_state = Some(shared_state);
+        self
+    }
+
+    pub fn shared_state(&self) -> Option<&SharedState> {
+        self.shared_state.as_ref()
+    }
+
+    /// Caps the total number of node retries in each run.
+    ///
+    /// Nodes configured with [`NodeConfig::with_retry`](crate::nodes::config::NodeConfig::with_retry)
+    /// draw from this budget on every retry. Once it is spent, the next
+    /// failure is returned immediately, however many attempts the node has
+    /// left. The remaining budget is kept in the context metadata under
+    /// [`RETRY_BUDGET_KEY`].
+    pub fn with_retry_budget(mut self, retries: u32) -> Self {
+        self.retry_budget = Some(retries);
+        self
+    }
+
+    pub fn retry_budget(&self) -> Option<u32> {
+        self.retry_budget
+    }
+
+    /// Caps the cost and latency of the AI nodes in each run.
+    ///
+    /// An AI node that would take the run over budget is skipped or
+    /// downgraded to its fallback, per the budget's
+    /// [`BudgetPolicy`](budget::BudgetPolicy). The run's spending is kept in
+    /// the context metadata under [`AI_BUDGET_KEY`].
+    pub fn with_ai_budget(mut self, budget: AiBudget) -> Self {
+        self.ai_budget = Some(budget);
+        self
+    }
+
+    pub fn ai_budget(&self) -> Option<&AiBudget> {
+        self.ai_budget.as_ref()
+    }
+
+    /// Records node durations, retries and errors through `recorder`.
+    ///
+    /// Metrics are discarded by default. See [`crate::metrics`] for the
+    /// metric names and labels.
+    pub fn with_metrics(mut self, recorder: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = recorder;
+        self
+    }
+
+    /// Warns when a run's context grows past `bytes` serialized.
+    ///
+    /// The context is measured after every node and its size recorded as
+    /// [`CONTEXT_SIZE_BYTES`]. The first time in a run that it exceeds the
+    /// threshold, a warning is logged and [`CONTEXT_SIZE_WARNINGS_TOTAL`]
+    /// incremented, naming the node that has grown the context most.
+    ///
+    /// Measuring serializes the whole context after every node, so it is off
+    /// unless enabled here; [`DEFAULT_CONTEXT_SIZE_WARNING_BYTES`] is a
+    /// reasonable threshold.
+    pub fn with_context_size_warning(mut self, bytes: usize) -> Self {
+        self.context_size_warning = Some(bytes);
+        self
+    }
+
+    /// Records every run in `store` so that it can be [replayed](Self::replay).
+    ///
+    /// A run's record holds its input and initial metadata, and the
+    /// responses to the external calls its nodes make through
+    /// [`TaskContext::external_call`]. It is saved when the run ends, whether
+    /// it succeeded or failed.
+    pub fn with_replay_store(mut self, store: Arc<dyn ReplayStore>) -> Self {
+        self.replay_store = Some(store);
+        self
+    }
+
+    /// Publishes node events to `bus` instead of a bus of its own.
+    ///
+    /// Lets a server follow every workflow it runs through one subscription.
+    pub fn with_event_bus(mut self, bus: NodeEventBus) -> Self {
+        self.events = bus;
+        self
+    }
+
+    /// Subscribes to node start, completion and failure events from every
+    /// subsequent run of this workflow.
+    ///
+    /// Events from all runs share one channel; filter on
+    /// [`NodeEvent::execution_id`] to follow a single run. A receiver that
+    /// falls more than [`events::NODE_EVENT_CAPACITY`] events behind misses
+    /// the oldest ones.
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<NodeEvent> {
+        self.events.subscribe()
+    }
+
     /// Registers a node with the workflow.
     ///
     /// # Arguments
@@ -522,6 +722,24 @@ impl Workflow {
         }
     }
 
+    /// Registers `node` under `id`, for node configs to run with
+    /// [`NodeConfig::with_named_node`](crate::nodes::config::NodeConfig::with_named_node).
+    ///
+    /// Unlike [`register_node`](Self::register_node), this allows several
+    /// instances of one node type, each configured differently.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// workflow.register_named_node("fetch_orders", HttpFetchNode::new("https://orders.internal/api"));
+    /// workflow.register_named_node("fetch_stock", HttpFetchNode::new("https://stock.internal/api"));
+    /// ```
+    pub fn register_named_node<T: Node + 'static>(&self, id: impl Into<String>, node: T) {
+        if let Ok(mut registry) = self.registry.write() {
+            registry.register_named(id, node);
+        }
+    }
+
     // Event integration methods moved to workflow-engine-api crate to avoid circular dependency
     // Event type is defined in the API crate and should not be referenced here
 
@@ -546,31 +764,317 @@ impl Workflow {
     /// let result = workflow.run(json!({"key": "value"}));
     /// ```
     pub fn run(&self, event_data: Value) -> Result<TaskContext, WorkflowError> {
+        let task_context = TaskContext::new(self.schema.workflow_type.clone(), event_data);
+        self.execute_workflow(task_context).map_err(|context| context.error)
+    }
+
+    /// Runs the workflow on a prepared context, e.g. one restored from a
+    /// stored event, keeping its event id and metadata.
+    pub fn run_with_context(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        self.execute_workflow(task_context).map_err(|context| context.error)
+    }
+
+    /// Like [`run_with_context`](Self::run_with_context), but a failure comes
+    /// with where it happened: the workflow type, execution id and
+    /// correlation id, and the name of the failing node under `node_name`
+    /// when a node failed.
+    pub fn run_with_error_context(&self, task_context: TaskContext) -> Result<TaskContext, ErrorContext> {
+        self.execute_workflow(task_context)
+    }
+
+    /// Runs the workflow with new data like [`run`](Self::run), also
+    /// returning an [`ExecutionReport`] of the run: its duration, the time
+    /// each node took, retries, AI budget spending and the errors met,
+    /// whether the run succeeded or not.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (result, report) = workflow.run_with_report(json!({"ticket_id": 42}));
+    /// tracing::info!(report = %serde_json::to_string(&report)?, "Run finished");
+    /// ```
+    pub fn run_with_report(&self, event_data: Value) -> (Result<TaskContext, WorkflowError>, ExecutionReport) {
+        let task_context = TaskContext::new(self.schema.workflow_type.clone(), event_data);
+        let (result, report) = self.execute_workflow_with_report(task_context);
+        (result.map_err(|context| context.error), report)
+    }
+
+    /// Runs a recorded execution again, to reproduce it while debugging.
+    ///
+    /// The run starts from the recorded input and metadata with the original
+    /// event id, and external calls made through
+    /// [`TaskContext::external_call`] are answered from the record, failures
+    /// included, without reaching the services. A call the original run did
+    /// not make fails the node. Replays are not recorded themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let store = Arc::new(InMemoryReplayStore::new());
+    /// let workflow = Workflow::new(schema)?.with_replay_store(store);
+    /// let failed = workflow.run(json!({"ticket_id": 42})).unwrap_err();
+    /// // ... find the execution id in the logs, then:
+    /// let reproduced = workflow.replay(execution_id);
+    /// ```
+    pub fn replay(&self, execution_id: uuid::Uuid) -> Result<TaskContext, WorkflowError> {
+        let store = self.replay_store.as_ref().ok_or_else(|| {
+            WorkflowError::configuration_error(
+                "Replaying requires a replay store; see Workflow::with_replay_store",
+                "replay_store",
+                "Workflow",
+                "a ReplayStore",
+                None,
+            )
+        })?;
+        let record = store.load(execution_id)?.ok_or_else(|| {
+            WorkflowError::validation_error_with_value(
+                "No recorded execution with this id",
+                "execution_id",
+                Some(execution_id.to_string()),
+                "recorded_execution",
+                "in Workflow::replay",
+            )
+        })?;
+        if record.workflow_type != self.schema.workflow_type {
+            return Err(WorkflowError::WorkflowTypeMismatch {
+                expected: self.schema.workflow_type.clone(),
+                actual: record.workflow_type,
+            });
+        }
+        self.execute_workflow(record.replay_context())
+            .map_err(|context| context.error)
+    }
+
+    /// Runs the workflow with new data under an overall deadline.
+    ///
+    /// The deadline is stored in the [`TaskContext`] so that async nodes and
+    /// MCP calls can fit their own timeouts into the remaining budget. Each
+    /// node is given only the time left before the deadline; once it passes,
+    /// execution stops with a deadline-exceeded [`WorkflowError::NodeTimeout`].
+    ///
+    /// Synchronous nodes cannot be interrupted, so a node still running at the
+    /// deadline is abandoned on a shared worker thread and its context's
+    /// cancellation token fires; nodes that check
+    /// [`TaskContext::is_cancelled`] can stop early.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let workflow = Workflow::new(schema).expect("Failed to create workflow");
+    /// let result = workflow.run_with_deadline(
+    ///     json!({"key": "value"}),
+    ///     Instant::now() + Duration::from_secs(30),
+    /// );
+    /// ```
+    pub fn run_with_deadline(
+        &self,
+        event_data: Value,
+        deadline: Instant,
+    ) -> Result<TaskContext, WorkflowError> {
         let mut task_context = TaskContext::new(self.schema.workflow_type.clone(), event_data);
-        self.execute_workflow(&mut task_context)
+        task_context.set_deadline(deadline);
+        self.execute_workflow(task_context).map_err(|context| context.error)
     }
 
-    /// Core workflow execution logic.
+    /// Runs the workflow with new data until `cancellation` fires.
     ///
-    /// This method is private and used internally by `run` and `run_from_event`.
-    fn execute_workflow(
+    /// The token is stored in the [`TaskContext`]. Once it fires, no further
+    /// node is started and the run fails with [`WorkflowError::Cancelled`].
+    /// Async nodes processing through
+    /// [`AsyncNode::process_with_cancellation`](crate::nodes::AsyncNode::process_with_cancellation)
+    /// are stopped and given the chance to clean up in
+    /// [`AsyncNode::on_cancel`](crate::nodes::AsyncNode::on_cancel);
+    /// synchronous nodes cannot be interrupted and finish first.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let token = CancellationToken::new();
+    /// let run = {
+    ///     let (workflow, token) = (workflow.clone(), token.clone());
+    ///     std::thread::spawn(move || workflow.run_with_cancellation(json!({"key": "value"}), token))
+    /// };
+    /// token.cancel();
+    /// ```
+    pub fn run_with_cancellation(
         &self,
-        task_context: &mut TaskContext,
+        event_data: Value,
+        cancellation: CancellationToken,
     ) -> Result<TaskContext, WorkflowError> {
+        let mut task_context = TaskContext::new(self.schema.workflow_type.clone(), event_data);
+        task_context.cancellation = Some(cancellation);
+        self.execute_workflow(task_context).map_err(|context| context.error)
+    }
+
+    /// Runs each event of a stream through the workflow.
+    ///
+    /// Up to `max_concurrency` events are processed at once, each on Tokio's
+    /// blocking thread pool, and results are emitted as they complete, so
+    /// they may arrive out of order. A failing event yields an `Err` item and
+    /// does not end the stream. Must be polled within a Tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use futures_util::{stream, StreamExt};
+    ///
+    /// let workflow = Arc::new(Workflow::new(schema).expect("Failed to create workflow"));
+    /// let mut results = workflow.run_stream(stream::iter(events), 4);
+    /// while let Some(result) = results.next().await {
+    ///     println!("Processed: {:?}", result);
+    /// }
+    /// ```
+    pub fn run_stream<S>(
+        self: Arc<Self>,
+        events: S,
+        max_concurrency: usize,
+    ) -> impl Stream<Item = Result<TaskContext, WorkflowError>>
+    where
+        S: Stream<Item = Value>,
+    {
+        events
+            .map(move |event_data| {
+                let workflow = Arc::clone(&self);
+                async move {
+                    let workflow_type = workflow.workflow_type().to_string();
+                    tokio::task::spawn_blocking(move || workflow.run(event_data))
+                        .await
+                        .unwrap_or_else(|e| {
+                            Err(WorkflowError::processing_error(
+                                format!("Workflow run panicked: {}", e),
+                                workflow_type,
+                            ))
+                        })
+                }
+            })
+            .buffer_unordered(max_concurrency.max(1))
+    }
+
+    /// Executes the workflow, recording the run if there is a replay store
+    /// and the run isn't already recorded or replayed.
+    ///
+    /// Failures are logged with the context of the run and where it failed.
+    ///
+    /// This method is private and used internally by `run` and `run_with_context`.
+    fn execute_workflow(&self, task_context: TaskContext) -> Result<TaskContext, ErrorContext> {
+        self.execute_workflow_with_report(task_context).0
+    }
+
+    /// Executes the workflow as [`execute_workflow`](Self::execute_workflow)
+    /// does, reporting on the run.
+    fn execute_workflow_with_report(
+        &self,
+        mut task_context: TaskContext,
+    ) -> (Result<TaskContext, ErrorContext>, ExecutionReport) {
+        let run_started = Instant::now();
+        let mut report = ExecutionReport::start(&task_context);
+        let execution_id = task_context.event_id;
+        let correlation_id = task_context.correlation_id();
+        let recording = match &self.replay_store {
+            Some(store) if task_context.external_calls.is_none() => {
+                let calls = ExternalCalls::recording();
+                task_context.external_calls = Some(calls.clone());
+                Some((store, ExecutionRecord::start(&task_context), calls))
+            }
+            _ => None,
+        };
+
+        let result = self.execute_nodes(task_context, &mut report).map_err(|context| {
+            let context = context
+                .with_context(WORKFLOW_TYPE_CONTEXT_KEY, &self.schema.workflow_type)
+                .with_context(EXECUTION_ID_CONTEXT_KEY, execution_id)
+                .with_correlation_id(correlation_id.to_string());
+            tracing::error!(
+                workflow = self.schema.workflow_type.as_str(),
+                execution_id = %execution_id,
+                error_context = %context.to_json(),
+                "Workflow run failed: {}",
+                context.error
+            );
+            context
+        });
+
+        if let Some((store, mut record, calls)) = recording {
+            record.external_calls = calls.calls();
+            let execution_id = record.execution_id;
+            if let Err(error) = store.save(record) {
+                tracing::warn!(
+                    workflow = self.schema.workflow_type.as_str(),
+                    execution_id = %execution_id,
+                    error = %error,
+                    "Failed to save execution record"
+                );
+            }
+        }
+        report.finish(run_started.elapsed(), result.as_ref().err());
+        (result, report)
+    }
+
+    /// Core workflow execution logic.
+    ///
+    /// The context is moved from node to node rather than cloned; only
+    /// parallel branches get copies of it.
+    fn execute_nodes(
+        &self,
+        mut task_context: TaskContext,
+        report: &mut ExecutionReport,
+    ) -> Result<TaskContext, ErrorContext> {
+        if let Some(validator) = &self.input_validator {
+            validator
+                .validate(&task_context.event_data)
+                .map_err(|violation| violation.into_error("in workflow input"))?;
+        }
+        let external_calls = task_context.external_calls.clone();
+        let tenant_id = task_context.tenant_id();
+        task_context.shared_state = self.shared_state.clone();
+        task_context.set_metadata(WORKFLOW_VERSION_KEY, self.schema.version)?;
+        if task_context.get_all_metadata().get(CORRELATION_ID_KEY).is_none() {
+            task_context.set_metadata(CORRELATION_ID_KEY, task_context.event_id)?;
+        }
+        let mut retry_budget = self.retry_budget;
+        if let Some(budget) = retry_budget {
+            task_context.set_metadata(RETRY_BUDGET_KEY, budget)?;
+        }
+        let run_started = Instant::now();
+        let mut ai_budget_state = BudgetState::default();
+        if self.ai_budget.is_some() {
+            task_context.set_metadata(AI_BUDGET_KEY, &ai_budget_state)?;
+        }
+        let mut context_size = self
+            .context_size_warning
+            .map(|threshold| ContextSizeTracker::new(threshold, task_context.serialized_size()));
         let mut current_node_type = Some(self.schema.start);
 
         while let Some(node_type) = current_node_type {
-            let node_name = {
-                let registry = self.registry.read().unwrap();
-                let node = registry
-                    .get(&node_type)
-                    .ok_or(WorkflowError::NodeNotFound { node_type })?;
-                node.node_name()
-            };
+            let mut node_name = self.node_name(node_type)?;
+            task_context
+                .check_cancelled(&node_name)
+                .map_err(|error| ErrorContext::new(error).with_context(NODE_NAME_CONTEXT_KEY, &node_name))?;
+
+            let mut run_type = node_type;
+            if let Some(budget) = &self.ai_budget {
+                let admitted =
+                    budget.admit(node_type, &node_name, &mut ai_budget_state, run_started.elapsed());
+                task_context.set_metadata(AI_BUDGET_KEY, &ai_budget_state)?;
+                report.ai_usage = Some(ai_budget_state.clone());
+                match admitted {
+                    Some(admitted) if admitted != node_type => {
+                        run_type = admitted;
+                        node_name = self.node_name(admitted)?;
+                    }
+                    Some(_) => {}
+                    None => {
+                        current_node_type = self.get_next_node_type(node_type, &task_context)?;
+                        continue;
+                    }
+                }
+            }
 
             println!("Processing node: {}", node_name);
 
-            // Process parallel nodes if any
+            // Process parallel and named nodes if any
             if let Some(node_config) = self
                 .schema
                 .nodes
@@ -578,24 +1082,332 @@ impl Workflow {
                 .find(|nc| nc.node_type == node_type)
             {
                 if !node_config.parallel_nodes.is_empty() {
-                    self.execute_parallel_nodes(&node_config.parallel_nodes, task_context)?;
+                    self.execute_parallel_nodes(node_config, &mut task_context)
+                        .map_err(|error| ErrorContext::new(error).with_context(NODE_NAME_CONTEXT_KEY, &node_name))?;
+                }
+                for id in &node_config.named_nodes {
+                    task_context = self
+                        .process_named_node(id, task_context, report)
+                        .map_err(|error| ErrorContext::new(error).with_context(NODE_NAME_CONTEXT_KEY, id))?;
                 }
             }
 
             // Actually process the node
-            *task_context = {
-                let registry = self.registry.read().unwrap();
-                let node = registry
-                    .get(&node_type)
-                    .ok_or(WorkflowError::NodeNotFound { node_type })?;
-                node.process(task_context.clone())?
+            let deadline = task_context.deadline;
+            let cancellation = task_context.cancellation.clone();
+            let execution_id = task_context.event_id;
+            let workflow_type = self.schema.workflow_type.as_str();
+            self.events.publish(execution_id, workflow_type, &node_name, NodeEventKind::Started);
+            // A non-critical node's failure is skipped over, so keep what it was given
+            let critical = self
+                .schema
+                .nodes
+                .iter()
+                .find(|nc| nc.node_type == node_type)
+                .is_none_or(|nc| nc.critical);
+            let given = (!critical).then(|| task_context.clone());
+            let started = Instant::now();
+            let result =
+                self.process_node_with_retries(run_type, &node_name, task_context, &mut retry_budget, report);
+            let status = if result.is_ok() { "success" } else { "error" };
+            let duration = started.elapsed();
+            self.metrics.record_histogram(
+                NODE_DURATION_SECONDS,
+                &[("workflow", workflow_type), ("node", &node_name), ("status", status)],
+                duration.as_secs_f64(),
+            );
+            report.record_node(&node_name, duration, result.is_ok());
+            let kind = match &result {
+                Ok(_) => NodeEventKind::Completed,
+                Err(error) => {
+                    self.metrics.increment_counter(
+                        NODE_ERRORS_TOTAL,
+                        &[("workflow", workflow_type), ("node", &node_name), ("error_code", error.error_code())],
+                        1,
+                    );
+                    NodeEventKind::Failed {
+                        error: error.to_string(),
+                    }
+                }
+            };
+            self.events.publish(execution_id, workflow_type, &node_name, kind);
+            task_context = match (result, given) {
+                (Ok(task_context), _) => task_context,
+                (Err(error), Some(mut given)) => {
+                    tracing::warn!(
+                        workflow = workflow_type,
+                        execution_id = %execution_id,
+                        node = node_name.as_str(),
+                        error = %error,
+                        "Non-critical node failed, continuing"
+                    );
+                    report.record_skipped_failure(&node_name, &error);
+                    let mut failures: Vec<NodeFailure> = given.get_metadata(NODE_FAILURES_KEY)?.unwrap_or_default();
+                    failures.push(NodeFailure::new(&node_name, &error));
+                    given.set_metadata(NODE_FAILURES_KEY, failures)?;
+                    given
+                }
+                (Err(error), None) => {
+                    return Err(ErrorContext::new(error).with_context(NODE_NAME_CONTEXT_KEY, &node_name));
+                }
             };
+            // Nodes may build a fresh context; keep the run's deadline and state for later nodes
+            task_context.deadline = deadline;
+            task_context.cancellation = cancellation;
+            task_context.shared_state = self.shared_state.clone();
+            task_context.external_calls = external_calls.clone();
+            self.enforce_tenant(tenant_id.as_ref(), &node_name, &mut task_context)?;
+            if self.ai_budget.is_some() {
+                task_context.set_metadata(AI_BUDGET_KEY, &ai_budget_state)?;
+            }
+            if let Some(tracker) = context_size.as_mut() {
+                self.check_context_size(tracker, &node_name, &task_context);
+            }
 
             // Get next node
-            current_node_type = self.get_next_node_type(node_type, task_context)?;
+            current_node_type = self.get_next_node_type(node_type, &task_context)?;
+        }
+
+        Ok(task_context)
+    }
+
+    /// Keeps the run in the tenant it started in. A node building a fresh
+    /// context gets the tenant back; one switching tenants fails the run, as
+    /// its results would be stored under the other tenant.
+    fn enforce_tenant(
+        &self,
+        tenant_id: Option<&TenantId>,
+        node_name: &str,
+        task_context: &mut TaskContext,
+    ) -> Result<(), WorkflowError> {
+        match (tenant_id, task_context.tenant_id()) {
+            (Some(tenant_id), None) => task_context.set_tenant_id(tenant_id),
+            (expected, actual) if expected != actual.as_ref() => {
+                return Err(WorkflowError::processing_error(
+                    format!(
+                        "Node moved the run from tenant {} to tenant {}",
+                        expected.map_or("<none>".to_string(), ToString::to_string),
+                        actual.map_or("<none>".to_string(), |tenant| tenant.to_string()),
+                    ),
+                    node_name,
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Records the context's size after `node_name` ran, warning the first
+    /// time it exceeds the threshold
+    fn check_context_size(&self, tracker: &mut ContextSizeTracker, node_name: &str, task_context: &TaskContext) {
+        let workflow_type = self.schema.workflow_type.as_str();
+        let size = task_context.serialized_size();
+        self.metrics.record_histogram(
+            CONTEXT_SIZE_BYTES,
+            &[("workflow", workflow_type), ("node", node_name)],
+            size as f64,
+        );
+        tracker.record(node_name, size);
+
+        if size > tracker.threshold && !tracker.warned {
+            tracker.warned = true;
+            let (largest, growth) = tracker.largest_growth();
+            tracing::warn!(
+                workflow = workflow_type,
+                execution_id = %task_context.event_id,
+                size_bytes = size,
+                threshold_bytes = tracker.threshold,
+                node = largest,
+                node_growth_bytes = growth,
+                "Task context exceeds size threshold; {} grew it most",
+                largest
+            );
+            self.metrics.increment_counter(
+                CONTEXT_SIZE_WARNINGS_TOTAL,
+                &[("workflow", workflow_type), ("node", largest)],
+                1,
+            );
+        }
+    }
+
+    /// Looks up the name of a registered node
+    fn node_name(&self, node_type: TypeId) -> Result<String, WorkflowError> {
+        let registry = self.registry.read().unwrap();
+        let node = registry
+            .get(&node_type)
+            .ok_or(WorkflowError::NodeNotFound { node_type })?;
+        Ok(node.node_name())
+    }
+
+    /// Processes a single node, retrying retryable failures as configured for
+    /// the node while the run's retry budget lasts.
+    ///
+    /// This method is private and used internally by `execute_workflow`.
+    fn process_node_with_retries(
+        &self,
+        node_type: TypeId,
+        node_name: &str,
+        mut task_context: TaskContext,
+        retry_budget: &mut Option<u32>,
+        report: &mut ExecutionReport,
+    ) -> Result<TaskContext, WorkflowError> {
+        let (max_retries, delay) = self
+            .schema
+            .nodes
+            .iter()
+            .find(|nc| nc.node_type == node_type)
+            .map(|nc| (nc.retry_attempts.unwrap_or(0), nc.retry_delay.unwrap_or_default()))
+            .unwrap_or_default();
+        if max_retries == 0 {
+            return self.process_node(node_type, node_name, task_context);
+        }
+
+        let mut attempt = 0;
+        loop {
+            let error = match self.process_node(node_type, node_name, task_context.clone()) {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+            if attempt >= max_retries || !error.is_retryable() {
+                return Err(error);
+            }
+            match retry_budget {
+                Some(0) => {
+                    tracing::warn!(
+                        node = node_name,
+                        error = %error,
+                        "Retry budget exhausted, not retrying"
+                    );
+                    return Err(error);
+                }
+                Some(remaining) => {
+                    *remaining -= 1;
+                    task_context.set_metadata(RETRY_BUDGET_KEY, *remaining)?;
+                }
+                None => {}
+            }
+
+            attempt += 1;
+            report.record_retry(node_name, &error);
+            self.metrics.increment_counter(
+                NODE_RETRIES_TOTAL,
+                &[("workflow", self.schema.workflow_type.as_str()), ("node", node_name)],
+                1,
+            );
+            tracing::warn!(
+                node = node_name,
+                error = %error,
+                attempt = attempt,
+                max_retries = max_retries,
+                "Node failed, retrying"
+            );
+            thread::sleep(delay);
+        }
+    }
+
+    /// Processes a single node, enforcing the context's deadline if it has one.
+    ///
+    /// Under a deadline the node runs on the shared worker pool with its own
+    /// cancellation token, which fires if the deadline passes first so that
+    /// nodes checking [`TaskContext::is_cancelled`] stop the abandoned work.
+    ///
+    /// This method is private and used internally by `execute_workflow`.
+    fn process_node(
+        &self,
+        node_type: TypeId,
+        node_name: &str,
+        mut task_context: TaskContext,
+    ) -> Result<TaskContext, WorkflowError> {
+        let Some(remaining) = task_context.remaining_time() else {
+            let registry = self.registry.read().unwrap();
+            let node = registry
+                .get(&node_type)
+                .ok_or(WorkflowError::NodeNotFound { node_type })?;
+            return node.process(task_context);
+        };
+        task_context.check_deadline(node_name)?;
+
+        let node = self
+            .registry
+            .read()
+            .unwrap()
+            .get_shared(&node_type)
+            .ok_or(WorkflowError::NodeNotFound { node_type })?;
+        let token = match &task_context.cancellation {
+            Some(cancellation) => cancellation.child_token(),
+            None => CancellationToken::new(),
+        };
+        task_context.cancellation = Some(token.clone());
+
+        let (sender, receiver) = mpsc::channel();
+        worker_pool::execute(move || {
+            // Nobody is listening any more if the deadline has passed
+            let _ = sender.send(node.process(task_context));
+        });
+
+        match receiver.recv_timeout(remaining) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                token.cancel();
+                Err(WorkflowError::deadline_exceeded(node_name, remaining))
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(WorkflowError::processing_error(
+                "Node panicked during processing",
+                node_name,
+            )),
         }
+    }
+
+    /// Processes the node registered under `id`, reporting it under its id.
+    ///
+    /// This method is private and used internally by `execute_workflow`.
+    fn process_named_node(
+        &self,
+        id: &str,
+        task_context: TaskContext,
+        report: &mut ExecutionReport,
+    ) -> Result<TaskContext, WorkflowError> {
+        task_context.check_deadline(id)?;
+        task_context.check_cancelled(id)?;
+        let execution_id = task_context.event_id;
+        let workflow_type = self.schema.workflow_type.as_str();
+        let deadline = task_context.deadline;
+        let cancellation = task_context.cancellation.clone();
+        let external_calls = task_context.external_calls.clone();
+        self.events.publish(execution_id, workflow_type, id, NodeEventKind::Started);
+        let started = Instant::now();
+        let result = match self.registry.read().unwrap().get_named(id) {
+            Some(node) => node.process(task_context),
+            None => Err(WorkflowError::configuration_error(
+                format!("No node registered under '{}'", id),
+                "named_nodes",
+                "NodeConfig",
+                "the id of a node registered with Workflow::register_named_node",
+                Some(id.to_string()),
+            )),
+        };
+        let duration = started.elapsed();
+        let status = if result.is_ok() { "success" } else { "error" };
+        self.metrics.record_histogram(
+            NODE_DURATION_SECONDS,
+            &[("workflow", workflow_type), ("node", id), ("status", status)],
+            duration.as_secs_f64(),
+        );
+        report.record_node(id, duration, result.is_ok());
+        let kind = match &result {
+            Ok(_) => NodeEventKind::Completed,
+            Err(error) => NodeEventKind::Failed {
+                error: error.to_string(),
+            },
+        };
+        self.events.publish(execution_id, workflow_type, id, kind);
 
-        Ok(task_context.clone())
+        let mut task_context = result?;
+        task_context.deadline = deadline;
+        task_context.cancellation = cancellation;
+        task_context.shared_state = self.shared_state.clone();
+        task_context.external_calls = external_calls;
+        Ok(task_context)
     }
 
     /// Executes parallel nodes in the workflow.
@@ -603,46 +1415,100 @@ impl Workflow {
     /// This method is private and used internally by `execute_workflow`.
     fn execute_parallel_nodes(
         &self,
-        parallel_nodes: &[TypeId],
+        node_config: &crate::nodes::config::NodeConfig,
         task_context: &mut TaskContext,
     ) -> Result<(), WorkflowError> {
-        let mut handles = Vec::new();
+        let parallel_nodes = &node_config.parallel_nodes;
+        let (sender, receiver) = mpsc::channel();
 
-        for &node_type in parallel_nodes {
-            let context_clone = task_context.clone();
+        for (index, &node_type) in parallel_nodes.iter().enumerate() {
+            let mut context_clone = task_context.clone();
+            if node_config.parallel_scopes.contains_key(&node_type) {
+                context_clone.track_node_writes();
+            }
             let registry_clone = self.registry.clone();
+            let sender = sender.clone();
 
-            let handle = thread::spawn(move || -> Result<TaskContext, WorkflowError> {
+            thread::spawn(move || {
                 let registry = registry_clone.read().unwrap();
-                let node = registry
-                    .get(&node_type)
-                    .ok_or(WorkflowError::NodeNotFound { node_type })?;
-
-                println!("Processing parallel node: {}", node.node_name());
-                node.process(context_clone)
+                let result = match registry.get(&node_type) {
+                    Some(node) => {
+                        println!("Processing parallel node: {}", node.node_name());
+                        node.process(context_clone)
+                    }
+                    None => Err(WorkflowError::NodeNotFound { node_type }),
+                };
+                let _ = sender.send((index, result));
             });
-            handles.push(handle);
         }
+        drop(sender);
 
-        let results: Result<Vec<TaskContext>, WorkflowError> =
-            handles.into_iter().map(|h| h.join().unwrap()).collect();
+        let mut results: Vec<Option<Result<TaskContext, WorkflowError>>> =
+            parallel_nodes.iter().map(|_| None).collect();
+        for _ in parallel_nodes {
+            let received = match task_context.remaining_time() {
+                Some(remaining) => receiver.recv_timeout(remaining).map_err(|e| match e {
+                    mpsc::RecvTimeoutError::Timeout => {
+                        // Report the first node that has not finished
+                        let pending = results.iter().position(Option::is_none).unwrap_or(0);
+                        WorkflowError::deadline_exceeded(
+                            self.node_name_of(parallel_nodes[pending]),
+                            remaining,
+                        )
+                    }
+                    mpsc::RecvTimeoutError::Disconnected => WorkflowError::processing_error(
+                        "Parallel node panicked during processing",
+                        "parallel_nodes",
+                    ),
+                })?,
+                None => receiver.recv().map_err(|_| {
+                    WorkflowError::processing_error(
+                        "Parallel node panicked during processing",
+                        "parallel_nodes",
+                    )
+                })?,
+            };
+            let (index, result) = received;
+            results[index] = Some(result);
+        }
 
-        let parallel_results = results?;
+        let parallel_results = results
+            .into_iter()
+            .flatten()
+            .collect::<Result<Vec<TaskContext>, WorkflowError>>()?;
 
-        // Merge results back into main context
-        for result in parallel_results {
-            for (key, value) in result.nodes {
-                task_context.nodes.insert(key, value);
+        // Merge results back into main context. Scoped branches keep the node
+        // data they wrote under their own name, along with any results they
+        // changed through `nodes_mut`, which aren't tracked by key.
+        let before = task_context.nodes.clone();
+        for (node_type, mut result) in parallel_nodes.iter().zip(parallel_results) {
+            match node_config.parallel_scopes.get(node_type) {
+                Some(scope) => {
+                    let written = result.take_written_nodes().unwrap_or_default();
+                    let output: serde_json::Map<String, Value> = Arc::unwrap_or_clone(result.nodes)
+                        .into_iter()
+                        .filter(|(key, value)| written.contains(key) || before.get(key) != Some(value))
+                        .collect();
+                    task_context.update_node(scope, Value::Object(output));
+                }
+                None => task_context.nodes_mut().extend(Arc::unwrap_or_clone(result.nodes)),
             }
             // Merge metadata as well
-            for (key, value) in result.metadata {
-                task_context.metadata.insert(key, value);
-            }
+            task_context.metadata_mut().extend(Arc::unwrap_or_clone(result.metadata));
         }
+        task_context.apply_retention();
 
         Ok(())
     }
 
+    fn node_name_of(&self, node_type: TypeId) -> String {
+        let registry = self.registry.read().unwrap();
+        registry
+            .get(&node_type)
+            .map(|node| node.node_name())
+            .unwrap_or_else(|| format!("{:?}", node_type))
+    }
+
     /// Determines the next node type in the workflow.
     ///
     /// This method is private and used internally by `execute_workflow`.
@@ -696,6 +1562,90 @@ impl Workflow {
         &self.schema.workflow_type
     }
 
+    /// Returns the version of the schema this workflow runs.
+    ///
+    /// Every run records it in the context metadata under
+    /// [`WORKFLOW_VERSION_KEY`].
+    pub fn version(&self) -> u32 {
+        self.schema.version
+    }
+
+    /// Describes the workflow's nodes and how they connect.
+    ///
+    /// Nodes are listed breadth-first from the start node, with each node's
+    /// parallel branches before its connections; nodes that cannot be reached
+    /// follow in schema order. Node names come from the registered nodes.
+    pub fn describe(&self) -> WorkflowDescription {
+        let config_of =
+            |node_type: TypeId| self.schema.nodes.iter().find(|config| config.node_type == node_type);
+        let registry = self.registry.read().unwrap();
+        let name_of = |node_type: TypeId| {
+            registry
+                .get(&node_type)
+                .map(|node| node.node_name())
+                .unwrap_or_else(|| format!("{:?}", node_type))
+        };
+
+        let mut order = vec![self.schema.start];
+        let mut edges = Vec::new();
+        let mut index = 0;
+        while index < order.len() {
+            let node_type = order[index];
+            index += 1;
+            let Some(config) = config_of(node_type) else {
+                continue;
+            };
+            let targets = config
+                .parallel_nodes
+                .iter()
+                .map(|target| (*target, EdgeKind::Parallel))
+                .chain(config.connections.iter().map(|target| (*target, EdgeKind::Connection)));
+            for (target, kind) in targets {
+                edges.push(EdgeDescription {
+                    from: name_of(node_type),
+                    to: name_of(target),
+                    kind,
+                });
+                if !order.contains(&target) {
+                    order.push(target);
+                }
+            }
+        }
+        for config in &self.schema.nodes {
+            if !order.contains(&config.node_type) {
+                order.push(config.node_type);
+            }
+        }
+
+        let nodes = order
+            .into_iter()
+            .map(|node_type| {
+                let config = config_of(node_type);
+                NodeDescription {
+                    name: name_of(node_type),
+                    description: config.and_then(|config| config.description.clone()),
+                    is_router: config.is_some_and(|config| config.is_router),
+                    has_parallel_nodes: config.is_some_and(|config| !config.parallel_nodes.is_empty()),
+                    is_parallel_branch: self
+                        .schema
+                        .nodes
+                        .iter()
+                        .any(|config| config.parallel_nodes.contains(&node_type)),
+                    registered: registry.get(&node_type).is_some(),
+                }
+            })
+            .collect();
+
+        WorkflowDescription {
+            workflow_type: self.schema.workflow_type.clone(),
+            version: self.schema.version,
+            description: self.schema.description.clone(),
+            start: name_of(self.schema.start),
+            nodes,
+            edges,
+        }
+    }
+
     // MCP server methods removed - use workflow-engine-mcp crate directly for MCP server functionality
 
 
@@ -709,6 +1659,40 @@ impl Workflow {
     }
 }
 
+/// How much each node of a run has grown its context
+struct ContextSizeTracker {
+    threshold: usize,
+    last_size: usize,
+    growth: HashMap<String, usize>,
+    warned: bool,
+}
+
+impl ContextSizeTracker {
+    fn new(threshold: usize, initial_size: usize) -> Self {
+        Self {
+            threshold,
+            last_size: initial_size,
+            growth: HashMap::new(),
+            warned: false,
+        }
+    }
+
+    fn record(&mut self, node_name: &str, size: usize) {
+        let growth = size.saturating_sub(self.last_size);
+        *self.growth.entry(node_name.to_string()).or_default() += growth;
+        self.last_size = size;
+    }
+
+    /// The node that has grown the context most, and by how many bytes
+    fn largest_growth(&self) -> (&str, usize) {
+        self.growth
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(node, growth)| (node.as_str(), *growth))
+            .unwrap_or(("", 0))
+    }
+}
+
 /// Wrapper to make existing nodes compatible with MCP server registration
 #[derive(Debug)]
 struct NodeWrapper {
@@ -743,3 +1727,1193 @@ impl Node for NodeWrapper {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::{config::NodeConfig, AsyncNode};
+    use crate::workflow::builder::WorkflowBuilder;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    struct FastNode;
+
+    impl Node for FastNode {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.update_node("fast", json!({ "done": true }));
+            Ok(task_context)
+        }
+    }
+
+    #[derive(Debug)]
+    struct SlowNode;
+
+    impl Node for SlowNode {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            thread::sleep(Duration::from_millis(500));
+            task_context.update_node("slow", json!({ "done": true }));
+            Ok(task_context)
+        }
+    }
+
+    fn workflow() -> Workflow {
+        let workflow = WorkflowBuilder::new::<FastNode>("deadline_test".to_string())
+            .add_node(NodeConfig::new::<FastNode>().with_connections(vec![TypeId::of::<SlowNode>()]))
+            .add_node(NodeConfig::new::<SlowNode>())
+            .build()
+            .unwrap();
+        workflow.register_node(FastNode);
+        workflow.register_node(SlowNode);
+        workflow
+    }
+
+    #[test]
+    fn test_completes_within_generous_deadline() {
+        let context = workflow()
+            .run_with_deadline(json!({}), Instant::now() + Duration::from_secs(5))
+            .unwrap();
+
+        assert!(context.get_all_data().contains_key("slow"));
+        assert!(context.deadline.is_some());
+    }
+
+    #[test]
+    fn test_aborts_slow_node_at_deadline() {
+        let start = Instant::now();
+        let result = workflow().run_with_deadline(json!({}), start + Duration::from_millis(100));
+        let elapsed = start.elapsed();
+
+        match result {
+            Err(WorkflowError::NodeTimeout {
+                node_name,
+                deadline_exceeded,
+                ..
+            }) => {
+                assert_eq!(node_name, "SlowNode");
+                assert!(deadline_exceeded);
+            }
+            other => panic!("Expected deadline-exceeded NodeTimeout, got {:?}", other),
+        }
+        // The workflow gives up at the deadline instead of waiting for the slow node
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed < Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_past_deadline_fails_before_first_node() {
+        let result = workflow().run_with_deadline(json!({}), Instant::now());
+
+        assert!(matches!(
+            result,
+            Err(WorkflowError::NodeTimeout { ref node_name, deadline_exceeded: true, .. })
+                if node_name == "FastNode"
+        ));
+    }
+
+    static ABANDONED_NODE_STOPPED: std::sync::atomic::AtomicBool =
+        std::sync::atomic::AtomicBool::new(false);
+
+    /// Works until its run is cancelled
+    #[derive(Debug)]
+    struct CooperativeNode;
+
+    impl Node for CooperativeNode {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let started = Instant::now();
+            while started.elapsed() < Duration::from_secs(5) {
+                if task_context.is_cancelled() {
+                    ABANDONED_NODE_STOPPED.store(true, std::sync::atomic::Ordering::SeqCst);
+                    return Err(WorkflowError::cancelled("CooperativeNode"));
+                }
+                thread::sleep(Duration::from_millis(5));
+            }
+            Ok(task_context)
+        }
+    }
+
+    #[test]
+    fn test_abandoned_node_is_cancelled_and_releases_the_registry() {
+        let workflow = WorkflowBuilder::new::<CooperativeNode>("cooperative".to_string())
+            .add_node(NodeConfig::new::<CooperativeNode>())
+            .build()
+            .unwrap();
+        workflow.register_node(CooperativeNode);
+
+        let result = workflow.run_with_deadline(json!({}), Instant::now() + Duration::from_millis(50));
+        assert!(matches!(
+            result,
+            Err(WorkflowError::NodeTimeout { deadline_exceeded: true, .. })
+        ));
+
+        // The abandoned node holds no registry lock
+        let (sender, receiver) = mpsc::channel();
+        let registering = {
+            let registry = workflow.registry.clone();
+            thread::spawn(move || {
+                registry.write().unwrap().register(FastNode);
+                let _ = sender.send(());
+            })
+        };
+        assert!(receiver.recv_timeout(Duration::from_millis(500)).is_ok());
+        registering.join().unwrap();
+
+        let stopped_by = Instant::now() + Duration::from_secs(1);
+        while !ABANDONED_NODE_STOPPED.load(std::sync::atomic::Ordering::SeqCst) {
+            assert!(Instant::now() < stopped_by, "abandoned node was never cancelled");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[derive(Debug)]
+    struct CounterNode<const BRANCH: u8>;
+
+    impl<const BRANCH: u8> Node for CounterNode<BRANCH> {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let state = task_context.shared_state().unwrap().clone();
+            state.update("counter", |count| {
+                json!(count.and_then(Value::as_i64).unwrap_or(0) + 1)
+            });
+            task_context.update_node(&format!("branch_{}", BRANCH), json!({ "done": true }));
+            Ok(task_context)
+        }
+    }
+
+    #[test]
+    fn test_parallel_nodes_share_state() {
+        let state = SharedState::new();
+        let workflow = WorkflowBuilder::new::<FastNode>("shared_state_test".to_string())
+            .add_node(NodeConfig::new::<FastNode>().with_parallel_nodes(vec![
+                TypeId::of::<CounterNode<1>>(),
+                TypeId::of::<CounterNode<2>>(),
+            ]))
+            .build()
+            .unwrap()
+            .with_shared_state(state.clone());
+        workflow.register_node(FastNode);
+        workflow.register_node(CounterNode::<1>);
+        workflow.register_node(CounterNode::<2>);
+
+        let context = workflow.run(json!({})).unwrap();
+
+        assert_eq!(state.get::<i64>("counter").unwrap(), Some(2));
+        assert!(context.get_all_data().contains_key("branch_1"));
+        assert!(context.get_all_data().contains_key("branch_2"));
+    }
+
+    #[test]
+    fn test_input_schema_rejects_invalid_input_before_any_node_runs() {
+        let workflow = WorkflowBuilder::new::<FastNode>("input_schema_test".to_string())
+            .with_input_schema(json!({
+                "type": "object",
+                "required": ["ticket_id"],
+                "properties": {
+                    "ticket_id": { "type": "integer" },
+                    "priority": { "enum": ["low", "high"] }
+                }
+            }))
+            .add_node(NodeConfig::new::<FastNode>())
+            .build()
+            .unwrap();
+        workflow.register_node(FastNode);
+
+        let (result, report) = workflow.run_with_report(json!({ "ticket_id": 42, "priority": "high" }));
+        assert!(result.is_ok());
+        assert_eq!(report.node_timings.len(), 1);
+
+        for (input, field, constraint) in [
+            (json!({ "priority": "high" }), "ticket_id", "required"),
+            (json!({ "ticket_id": 42, "priority": "urgent" }), "priority", "enum"),
+            (json!("42"), "input", "type"),
+        ] {
+            let (result, report) = workflow.run_with_report(input);
+            match result.unwrap_err() {
+                WorkflowError::ValidationError { field: actual, constraint: actual_constraint, .. } => {
+                    assert_eq!(actual, field);
+                    assert_eq!(actual_constraint, constraint);
+                }
+                other => panic!("expected a validation error, got {:?}", other),
+            }
+            assert!(report.node_timings.is_empty());
+        }
+    }
+
+    /// Always fails, with a transient error so that retries are attempted
+    #[derive(Debug)]
+    struct BrokenEnrichment;
+
+    impl Node for BrokenEnrichment {
+        fn process(&self, _task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Err(WorkflowError::api_error("enrichment service unavailable", "crm", "/enrich", Some(503)))
+        }
+    }
+
+    fn partial_workflow(critical: bool) -> Workflow {
+        let workflow = WorkflowBuilder::new::<FastNode>("partial_results_test".to_string())
+            .add_node(NodeConfig::new::<FastNode>().with_connections(vec![TypeId::of::<BrokenEnrichment>()]))
+            .add_node(
+                NodeConfig::new::<BrokenEnrichment>()
+                    .with_critical(critical)
+                    .with_retry(1, Duration::ZERO)
+                    .with_connections(vec![TypeId::of::<ModelNode<'z'>>()]),
+            )
+            .add_node(NodeConfig::new::<ModelNode<'z'>>())
+            .build()
+            .unwrap();
+        workflow.register_node(FastNode);
+        workflow.register_node(BrokenEnrichment);
+        workflow.register_node(ModelNode::<'z'>);
+        workflow
+    }
+
+    #[test]
+    fn test_run_continues_past_non_critical_node_failure() {
+        let workflow = partial_workflow(false);
+        let broken = workflow.node_name_of(TypeId::of::<BrokenEnrichment>());
+
+        let (result, report) = workflow.run_with_report(json!({}));
+        let context = result.unwrap();
+
+        // The node after the failed one still ran
+        assert!(context.get_node_data::<bool>("model_z").unwrap().unwrap());
+        let failures = report::NodeFailure::of(&context);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].node, broken);
+        assert_eq!(failures[0].error_code, "WF_API_ERROR");
+        assert!(failures[0].message.contains("enrichment service unavailable"));
+
+        assert_eq!(report.status, report::ExecutionStatus::PartiallyCompleted);
+        assert_eq!(report.retries[&broken], 1);
+        let errors: Vec<_> = report.errors.iter().map(|e| e.retried).collect();
+        assert_eq!(errors, vec![true, false]);
+    }
+
+    #[test]
+    fn test_critical_node_failure_still_aborts_run() {
+        let workflow = partial_workflow(true);
+
+        let (result, report) = workflow.run_with_report(json!({}));
+        assert!(result.is_err());
+        assert_eq!(report.status, report::ExecutionStatus::Failed);
+        assert!(!report.node_timings.iter().any(|t| t.node.contains("ModelNode")));
+    }
+
+    /// Writes its own model's answer under the same key as its siblings
+    #[derive(Debug)]
+    struct AnswerNode<const MODEL: char>;
+
+    impl<const MODEL: char> Node for AnswerNode<MODEL> {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.update_node("answer", format!("answer from {}", MODEL));
+            Ok(task_context)
+        }
+    }
+
+    /// Collects the scoped answers into one list
+    #[derive(Debug)]
+    struct CompareAnswers;
+
+    impl Node for CompareAnswers {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let answers = ["a", "b", "c"]
+                .iter()
+                .map(|branch| task_context.get_branch_data::<String>(branch, "answer"))
+                .collect::<Result<Option<Vec<_>>, _>>()?;
+            task_context.update_node("answers", answers);
+            Ok(task_context)
+        }
+    }
+
+    fn scoped_workflow() -> Workflow {
+        let workflow = WorkflowBuilder::new::<FastNode>("scoped_parallel_test".to_string())
+            .add_node(NodeConfig::new::<FastNode>().with_connections(vec![TypeId::of::<CompareAnswers>()]))
+            .add_node(NodeConfig::new::<CompareAnswers>())
+            .parallel_scoped(&[
+                ("a", TypeId::of::<AnswerNode<'a'>>()),
+                ("b", TypeId::of::<AnswerNode<'b'>>()),
+                ("c", TypeId::of::<AnswerNode<'c'>>()),
+            ])
+            .build()
+            .unwrap();
+        workflow.register_node(FastNode);
+        workflow.register_node(CompareAnswers);
+        workflow.register_node(AnswerNode::<'a'>);
+        workflow.register_node(AnswerNode::<'b'>);
+        workflow.register_node(AnswerNode::<'c'>);
+        workflow
+    }
+
+    #[test]
+    fn test_scoped_parallel_branches_keep_their_outputs_apart() {
+        let context = scoped_workflow().run(json!({})).unwrap();
+
+        assert_eq!(
+            context.get_node_data::<Vec<String>>("answers").unwrap().unwrap(),
+            vec!["answer from a", "answer from b", "answer from c"]
+        );
+        // Only what each branch wrote is kept under its name, and nothing is
+        // merged into the context itself
+        assert_eq!(context.get_node_data::<Value>("b").unwrap().unwrap(), json!({ "answer": "answer from b" }));
+        assert!(!context.get_all_data().contains_key("answer"));
+    }
+
+    #[test]
+    fn test_scoped_branch_keeps_writes_equal_to_existing_results() {
+        let mut context = TaskContext::new("scoped_parallel_test".to_string(), json!({}));
+        context.update_node("answer", "answer from a");
+
+        let context = scoped_workflow().run_with_context(context).unwrap();
+
+        // Branch a rewrote the value it started with, which still counts as its output
+        assert_eq!(context.get_node_data::<Value>("a").unwrap().unwrap(), json!({ "answer": "answer from a" }));
+        assert_eq!(context.get_node_data::<Value>("c").unwrap().unwrap(), json!({ "answer": "answer from c" }));
+        assert_eq!(context.get_node_data::<String>("answer").unwrap().unwrap(), "answer from a");
+    }
+
+    #[test]
+    fn test_context_has_no_shared_state_by_default() {
+        let context = workflow()
+            .run_with_deadline(json!({}), Instant::now() + Duration::from_secs(5))
+            .unwrap();
+        assert!(context.shared_state().is_none());
+    }
+
+    #[test]
+    fn test_run_records_correlation_id() {
+        let context = workflow().run(json!({})).unwrap();
+        assert_eq!(
+            context.get_metadata::<uuid::Uuid>(CORRELATION_ID_KEY).unwrap(),
+            Some(context.event_id)
+        );
+    }
+
+    #[derive(Debug)]
+    struct RejectOrder;
+
+    impl Node for RejectOrder {
+        fn process(&self, _task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Err(WorkflowError::user_error("Order total is negative", "RejectOrder"))
+        }
+    }
+
+    #[test]
+    fn test_node_errors_carry_where_they_failed() {
+        let workflow = WorkflowBuilder::new::<FastNode>("orders".to_string())
+            .add_node(NodeConfig::new::<FastNode>().with_connections(vec![TypeId::of::<RejectOrder>()]))
+            .add_node(NodeConfig::new::<RejectOrder>())
+            .build()
+            .unwrap();
+        workflow.register_node(FastNode);
+        workflow.register_node(RejectOrder);
+
+        let task_context = TaskContext::new("orders".to_string(), json!({}));
+        let execution_id = task_context.event_id;
+        let error = workflow.run_with_error_context(task_context).unwrap_err();
+        let context = &error.metadata.context;
+        assert_eq!(context[NODE_NAME_CONTEXT_KEY], json!(workflow.node_name_of(TypeId::of::<RejectOrder>())));
+        assert_eq!(context[WORKFLOW_TYPE_CONTEXT_KEY], "orders");
+        assert_eq!(context[EXECUTION_ID_CONTEXT_KEY], json!(execution_id));
+        assert_eq!(error.metadata.correlation_id, Some(execution_id.to_string()));
+        assert!(error.error.to_string().contains("Order total is negative"));
+
+        // Plain runs still fail with the node's own error
+        assert!(matches!(workflow.run(json!({})), Err(WorkflowError::NodeError { .. })));
+    }
+
+    /// Returns a fresh context, or one moved to another tenant
+    #[derive(Debug)]
+    struct TenantSwitch<const MOVE: bool>;
+
+    impl<const MOVE: bool> Node for TenantSwitch<MOVE> {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let mut fresh = TaskContext::new(task_context.workflow_type, task_context.event_data);
+            fresh.event_id = task_context.event_id;
+            if MOVE {
+                fresh.set_tenant_id(&TenantId::new("globex"));
+            }
+            Ok(fresh)
+        }
+    }
+
+    #[test]
+    fn test_runs_stay_in_their_tenant() {
+        let acme = TenantId::new("acme");
+        let started = |tenant: Option<&TenantId>| {
+            let context = TaskContext::new("tenant_test".to_string(), json!({}));
+            match tenant {
+                Some(tenant) => context.with_tenant_id(tenant),
+                None => context,
+            }
+        };
+
+        let workflow: Workflow = WorkflowBuilder::new::<TenantSwitch<false>>("tenant_test".to_string())
+            .build()
+            .unwrap();
+        workflow.register_node(TenantSwitch::<false>);
+        let context = workflow.run_with_context(started(Some(&acme))).unwrap();
+        assert_eq!(context.tenant_id(), Some(acme.clone()));
+
+        let workflow: Workflow = WorkflowBuilder::new::<TenantSwitch<true>>("tenant_test".to_string())
+            .build()
+            .unwrap();
+        workflow.register_node(TenantSwitch::<true>);
+        let error = workflow.run_with_context(started(Some(&acme))).unwrap_err();
+        assert!(error.to_string().contains("from tenant acme to tenant globex"));
+        let error = workflow.run_with_context(started(None)).unwrap_err();
+        assert!(error.to_string().contains("from tenant <none> to tenant globex"));
+    }
+
+    #[derive(Debug)]
+    struct SlowAsyncNode;
+
+    #[async_trait::async_trait]
+    impl AsyncNode for SlowAsyncNode {
+        async fn process_async(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            Ok(task_context)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_node_honors_remaining_deadline() {
+        let mut context = TaskContext::new("deadline_test".to_string(), json!({}));
+        context.set_deadline(Instant::now() + Duration::from_millis(50));
+        assert_eq!(context.bounded_timeout(Duration::from_secs(30)).as_secs(), 0);
+
+        let start = Instant::now();
+        let result = SlowAsyncNode.process_with_deadline(context).await;
+
+        assert!(matches!(
+            result,
+            Err(WorkflowError::NodeTimeout { deadline_exceeded: true, .. })
+        ));
+        assert!(start.elapsed() < Duration::from_millis(400));
+    }
+
+    /// Holds a lock for a long time, releasing it when cancelled
+    #[derive(Debug, Default)]
+    struct LockingAsyncNode {
+        released: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncNode for LockingAsyncNode {
+        async fn process_async(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(task_context)
+        }
+
+        async fn on_cancel(&self, task_context: &TaskContext) {
+            assert_eq!(task_context.workflow_type, "cancel_test");
+            self.released.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    impl Node for LockingAsyncNode {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            tokio::runtime::Runtime::new()
+                .unwrap()
+                .block_on(self.process_with_cancellation(task_context))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_async_node_runs_on_cancel() {
+        let token = CancellationToken::new();
+        let mut context = TaskContext::new("cancel_test".to_string(), json!({}));
+        context.cancellation = Some(token.clone());
+        let node = LockingAsyncNode::default();
+
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            token.cancel();
+        });
+        let start = Instant::now();
+        let result = node.process_with_cancellation(context).await;
+        canceller.await.unwrap();
+
+        assert!(matches!(result, Err(WorkflowError::Cancelled { .. })));
+        assert!(node.released.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_cancelled_run_stops_at_async_node() {
+        let workflow = WorkflowBuilder::new::<LockingAsyncNode>("cancel_test".to_string())
+            .add_node(NodeConfig::new::<LockingAsyncNode>().with_connections(vec![TypeId::of::<FastNode>()]))
+            .add_node(NodeConfig::new::<FastNode>())
+            .build()
+            .unwrap();
+        let node = LockingAsyncNode::default();
+        let released = node.released.clone();
+        workflow.register_node(node);
+        workflow.register_node(FastNode);
+
+        let token = CancellationToken::new();
+        let canceller = {
+            let token = token.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                token.cancel();
+            })
+        };
+        let error = workflow
+            .run_with_error_context({
+                let mut context = TaskContext::new("cancel_test".to_string(), json!({}));
+                context.cancellation = Some(token.clone());
+                context
+            })
+            .unwrap_err();
+        canceller.join().unwrap();
+
+        assert!(matches!(error.error, WorkflowError::Cancelled { .. }));
+        let locking = workflow.node_name_of(TypeId::of::<LockingAsyncNode>());
+        assert_eq!(error.metadata.context[NODE_NAME_CONTEXT_KEY], json!(locking));
+        assert!(released.load(std::sync::atomic::Ordering::SeqCst));
+
+        // A cancelled token stops the next run before its first node
+        let error = workflow.run_with_cancellation(json!({}), token).unwrap_err();
+        assert!(matches!(error, WorkflowError::Cancelled { .. }));
+    }
+
+    /// Records the address of the event payload's buffer, which changes
+    /// whenever the context has been cloned
+    #[derive(Debug)]
+    struct PayloadProbe<const ID: char>;
+
+    impl<const ID: char> Node for PayloadProbe<ID> {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let address = task_context.event_data["payload"].as_str().unwrap().as_ptr() as usize;
+            task_context.update_node(&format!("probe_{}", ID), address);
+            Ok(task_context)
+        }
+    }
+
+    #[test]
+    fn test_context_is_moved_not_cloned_between_nodes() {
+        let workflow = WorkflowBuilder::new::<PayloadProbe<'a'>>("no_clone_test".to_string())
+            .add_node(
+                NodeConfig::new::<PayloadProbe<'a'>>()
+                    .with_parallel_nodes(vec![TypeId::of::<PayloadProbe<'p'>>()])
+                    .with_connections(vec![TypeId::of::<PayloadProbe<'b'>>()]),
+            )
+            .add_node(NodeConfig::new::<PayloadProbe<'b'>>().with_connections(vec![TypeId::of::<PayloadProbe<'c'>>()]))
+            .add_node(NodeConfig::new::<PayloadProbe<'c'>>())
+            .build()
+            .unwrap();
+        workflow.register_node(PayloadProbe::<'a'>);
+        workflow.register_node(PayloadProbe::<'b'>);
+        workflow.register_node(PayloadProbe::<'c'>);
+        workflow.register_node(PayloadProbe::<'p'>);
+
+        for deadline in [None, Some(Instant::now() + Duration::from_secs(5))] {
+            let payload = "x".repeat(4 * 1024 * 1024);
+            let original = payload.as_ptr() as usize;
+            let event_data =
+                Value::Object([("payload".to_string(), Value::String(payload))].into_iter().collect());
+
+            let context = match deadline {
+                Some(deadline) => workflow.run_with_deadline(event_data, deadline).unwrap(),
+                None => workflow.run(event_data).unwrap(),
+            };
+
+            let seen = |id: char| {
+                context.get_node_data::<usize>(&format!("probe_{}", id)).unwrap().unwrap()
+            };
+            for id in ['a', 'b', 'c'] {
+                assert_eq!(seen(id), original, "node {} saw a copy of the payload", id);
+            }
+            assert_eq!(context.event_data["payload"].as_str().unwrap().as_ptr() as usize, original);
+            // The parallel branch runs alongside the main chain, so it needs its own copy
+            assert_ne!(seen('p'), original);
+        }
+    }
+
+    /// Fails events flagged with `"fail": true`, and tracks how many events
+    /// are in flight at once
+    #[derive(Debug, Default)]
+    struct StreamNode {
+        in_flight: std::sync::atomic::AtomicUsize,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl Node for StreamNode {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            use std::sync::atomic::Ordering;
+
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(in_flight, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let event: Value = task_context.get_event_data()?;
+            if event["fail"] == json!(true) {
+                return Err(WorkflowError::processing_error(
+                    format!("event {} failed", event["id"]),
+                    "StreamNode",
+                ));
+            }
+            task_context.update_node("stream", json!({ "id": event["id"] }));
+            Ok(task_context)
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_stream_keeps_going_after_errors() {
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let workflow = WorkflowBuilder::new::<StreamNode>("stream_test".to_string())
+            .add_node(NodeConfig::new::<StreamNode>())
+            .build()
+            .unwrap();
+        workflow.register_node(StreamNode { peak: Arc::clone(&peak), ..Default::default() });
+
+        let events = (1..=5).map(|id| json!({ "id": id, "fail": id == 3 }));
+        let results: Vec<_> = Arc::new(workflow)
+            .run_stream(futures_util::stream::iter(events), 2)
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 5);
+        let errors: Vec<_> = results.iter().filter_map(|result| result.as_ref().err()).collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("event 3 failed"));
+
+        let mut ids: Vec<i64> = results
+            .iter()
+            .flatten()
+            .map(|context| {
+                let result: Value = context.get_node_data("stream").unwrap().unwrap();
+                result["id"].as_i64().unwrap()
+            })
+            .collect();
+        ids.sort();
+        assert_eq!(ids, [1, 2, 4, 5]);
+        assert!(peak.load(std::sync::atomic::Ordering::SeqCst) <= 2);
+    }
+
+    /// Fails with a transient error on its first `FAILURES` attempts
+    #[derive(Debug, Default)]
+    struct FlakyNode<const ID: char, const FAILURES: usize> {
+        attempts: std::sync::atomic::AtomicUsize,
+    }
+
+    impl<const ID: char, const FAILURES: usize> Node for FlakyNode<ID, FAILURES> {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let attempt = self.attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            if attempt <= FAILURES {
+                return Err(WorkflowError::api_error(
+                    format!("flaky node {} failed attempt {}", ID, attempt),
+                    "flaky",
+                    "/",
+                    Some(503),
+                ));
+            }
+            task_context.update_node(&format!("flaky_{}", ID), attempt);
+            Ok(task_context)
+        }
+    }
+
+    fn flaky_workflow<const A: usize, const B: usize>(budget: Option<u32>) -> Workflow {
+        let mut workflow = WorkflowBuilder::new::<FlakyNode<'a', A>>("retry_budget_test".to_string())
+            .add_node(
+                NodeConfig::new::<FlakyNode<'a', A>>()
+                    .with_retry(5, Duration::ZERO)
+                    .with_connections(vec![TypeId::of::<FlakyNode<'b', B>>()]),
+            )
+            .add_node(NodeConfig::new::<FlakyNode<'b', B>>().with_retry(5, Duration::ZERO))
+            .build()
+            .unwrap();
+        if let Some(budget) = budget {
+            workflow = workflow.with_retry_budget(budget);
+        }
+        workflow.register_node(FlakyNode::<'a', A>::default());
+        workflow.register_node(FlakyNode::<'b', B>::default());
+        workflow
+    }
+
+    #[test]
+    fn test_retries_draw_from_workflow_budget() {
+        let context = flaky_workflow::<1, 1>(Some(3)).run(json!({})).unwrap();
+
+        assert_eq!(context.get_node_data::<usize>("flaky_a").unwrap(), Some(2));
+        assert_eq!(context.get_node_data::<usize>("flaky_b").unwrap(), Some(2));
+        assert_eq!(context.get_metadata::<u32>(RETRY_BUDGET_KEY).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_exhausted_budget_stops_retries() {
+        // Node a uses two of the three retries, so node b gets only one of the
+        // three it needs and its second failure is returned
+        let result = flaky_workflow::<2, 3>(Some(3)).run(json!({}));
+        match result {
+            Err(WorkflowError::ApiError { message, .. }) => {
+                assert_eq!(message, "flaky node b failed attempt 2")
+            }
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+
+        // Without a budget, each node may use all of its own retries
+        let context = flaky_workflow::<2, 3>(None).run(json!({})).unwrap();
+        assert_eq!(context.get_node_data::<usize>("flaky_b").unwrap(), Some(4));
+        assert_eq!(context.get_metadata::<u32>(RETRY_BUDGET_KEY).unwrap(), None);
+    }
+
+    /// Stands in for an AI node, recording that it ran
+    #[derive(Debug, Default)]
+    struct ModelNode<const ID: char>;
+
+    impl<const ID: char> Node for ModelNode<ID> {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.update_node(&format!("model_{}", ID), true);
+            Ok(task_context)
+        }
+    }
+
+    /// Two expensive AI nodes, `a` then `b`, each with the cheap `c` as its
+    /// fallback, and a final node `d` outside the budget
+    fn budgeted_workflow(policy: budget::BudgetPolicy) -> Workflow {
+        use budget::AiNodeCost;
+
+        let expensive = AiNodeCost::new(0.04, Duration::from_secs(2));
+        let cheap = AiNodeCost::new(0.01, Duration::from_millis(500));
+        let workflow = WorkflowBuilder::new::<ModelNode<'a'>>("ai_budget_test".to_string())
+            .add_node(NodeConfig::new::<ModelNode<'a'>>().with_connections(vec![TypeId::of::<ModelNode<'b'>>()]))
+            .add_node(NodeConfig::new::<ModelNode<'b'>>().with_connections(vec![TypeId::of::<ModelNode<'d'>>()]))
+            .add_node(NodeConfig::new::<ModelNode<'d'>>())
+            .build()
+            .unwrap()
+            .with_ai_budget(
+                AiBudget::new(policy)
+                    .with_max_cost(0.05)
+                    .with_ai_node::<ModelNode<'a'>>(expensive)
+                    .with_ai_node::<ModelNode<'b'>>(expensive)
+                    .with_fallback::<ModelNode<'a'>, ModelNode<'c'>>(cheap)
+                    .with_fallback::<ModelNode<'b'>, ModelNode<'c'>>(cheap),
+            );
+        workflow.register_node(ModelNode::<'a'>);
+        workflow.register_node(ModelNode::<'b'>);
+        workflow.register_node(ModelNode::<'c'>);
+        workflow.register_node(ModelNode::<'d'>);
+        workflow
+    }
+
+    fn ran(context: &TaskContext, id: char) -> bool {
+        context.get_node_data::<bool>(&format!("model_{}", id)).unwrap().unwrap_or(false)
+    }
+
+    #[test]
+    fn test_ai_budget_downgrades_node_once_reached() {
+        let context = budgeted_workflow(budget::BudgetPolicy::Downgrade).run(json!({})).unwrap();
+
+        // a spends 0.04 of the 0.05, so b is replaced by its 0.01 fallback
+        assert!(ran(&context, 'a'));
+        assert!(!ran(&context, 'b'));
+        assert!(ran(&context, 'c'));
+        assert!(ran(&context, 'd'));
+        let state: BudgetState = context.get_metadata(AI_BUDGET_KEY).unwrap().unwrap();
+        assert!((state.spent_usd - 0.05).abs() < 1e-9);
+        assert_eq!(state.downgraded.len(), 1);
+        assert!(state.downgraded[0].contains("ModelNode<'b'>"));
+        assert!(state.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_ai_budget_skips_node_once_reached() {
+        let context = budgeted_workflow(budget::BudgetPolicy::Skip).run(json!({})).unwrap();
+
+        assert!(ran(&context, 'a'));
+        assert!(!ran(&context, 'b'));
+        assert!(!ran(&context, 'c'));
+        // The run carries on past the skipped node
+        assert!(ran(&context, 'd'));
+        let state: BudgetState = context.get_metadata(AI_BUDGET_KEY).unwrap().unwrap();
+        assert!((state.spent_usd - 0.04).abs() < 1e-9);
+        assert_eq!(state.skipped.len(), 1);
+        assert!(state.downgraded.is_empty());
+    }
+
+    /// A flaky node failing `FAILURES` times, with one retry, then an AI node
+    fn reported_workflow<const FAILURES: usize>() -> Workflow {
+        let workflow = WorkflowBuilder::new::<FlakyNode<'a', FAILURES>>("report_test".to_string())
+            .add_node(
+                NodeConfig::new::<FlakyNode<'a', FAILURES>>()
+                    .with_retry(1, Duration::ZERO)
+                    .with_connections(vec![TypeId::of::<ModelNode<'b'>>()]),
+            )
+            .add_node(NodeConfig::new::<ModelNode<'b'>>())
+            .build()
+            .unwrap()
+            .with_ai_budget(
+                AiBudget::new(budget::BudgetPolicy::Skip)
+                    .with_ai_node::<ModelNode<'b'>>(budget::AiNodeCost::new(0.02, Duration::from_secs(1))),
+            );
+        workflow.register_node(FlakyNode::<'a', FAILURES>::default());
+        workflow.register_node(ModelNode::<'b'>);
+        workflow
+    }
+
+    #[test]
+    fn test_report_aggregates_timings_retries_and_ai_usage() {
+        let workflow = reported_workflow::<1>();
+        let flaky = workflow.node_name_of(TypeId::of::<FlakyNode<'a', 1>>());
+        let model = workflow.node_name_of(TypeId::of::<ModelNode<'b'>>());
+
+        let (result, report) = workflow.run_with_report(json!({}));
+        let context = result.unwrap();
+
+        assert_eq!(report.execution_id, context.event_id);
+        assert_eq!(report.workflow_type, "report_test");
+        assert_eq!(report.status, report::ExecutionStatus::Completed);
+        let timings: Vec<_> = report.node_timings.iter().map(|t| (t.node.clone(), t.succeeded)).collect();
+        assert_eq!(timings, vec![(flaky.clone(), true), (model, true)]);
+        assert!(report.node_timings.iter().map(|t| t.duration).sum::<Duration>() <= report.total_duration);
+        assert_eq!(report.retries, HashMap::from([(flaky.clone(), 1)]));
+        assert_eq!(report.total_retries(), 1);
+        let ai_usage = report.ai_usage.as_ref().unwrap();
+        assert!((ai_usage.spent_usd - 0.02).abs() < 1e-9);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].node.as_deref(), Some(flaky.as_str()));
+        assert_eq!(report.errors[0].error_code, "WF_API_ERROR");
+        assert!(report.errors[0].retried);
+        assert!(serde_json::to_value(&report).is_ok());
+    }
+
+    #[test]
+    fn test_report_of_failed_run() {
+        let workflow = reported_workflow::<2>();
+        let flaky = workflow.node_name_of(TypeId::of::<FlakyNode<'a', 2>>());
+
+        let (result, report) = workflow.run_with_report(json!({}));
+        assert!(result.is_err());
+
+        assert_eq!(report.status, report::ExecutionStatus::Failed);
+        assert_eq!(report.node_timings.len(), 1);
+        assert!(!report.node_timings[0].succeeded);
+        assert_eq!(report.retries[&flaky], 1);
+        // The AI node never ran, so nothing was spent
+        assert_eq!(report.ai_usage.as_ref().unwrap().spent_usd, 0.0);
+        let errors: Vec<_> = report.errors.iter().map(|e| (e.node.as_deref(), e.retried)).collect();
+        assert_eq!(errors, vec![(Some(flaky.as_str()), true), (Some(flaky.as_str()), false)]);
+        assert!(report.errors[1].message.contains("failed attempt 2"));
+    }
+
+    /// Keeps every metric recorded, as (name, labels, value)
+    #[derive(Debug, Default)]
+    struct RecordingMetrics(std::sync::Mutex<Vec<(String, Vec<(String, String)>, f64)>>);
+
+    impl RecordingMetrics {
+        fn record(&self, name: &str, labels: crate::metrics::Labels<'_>, value: f64) {
+            let labels = labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            self.0.lock().unwrap().push((name.to_string(), labels, value));
+        }
+
+        fn named(&self, name: &str) -> Vec<(Vec<(String, String)>, f64)> {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(metric, _, _)| metric == name)
+                .map(|(_, labels, value)| (labels.clone(), *value))
+                .collect()
+        }
+    }
+
+    impl MetricsRecorder for RecordingMetrics {
+        fn increment_counter(&self, name: &str, labels: crate::metrics::Labels<'_>, value: u64) {
+            self.record(name, labels, value as f64);
+        }
+
+        fn set_gauge(&self, name: &str, labels: crate::metrics::Labels<'_>, value: f64) {
+            self.record(name, labels, value);
+        }
+
+        fn record_histogram(&self, name: &str, labels: crate::metrics::Labels<'_>, value: f64) {
+            self.record(name, labels, value);
+        }
+    }
+
+    #[test]
+    fn test_run_records_node_metrics() {
+        let metrics = Arc::new(RecordingMetrics::default());
+        let workflow = flaky_workflow::<2, 3>(Some(3)).with_metrics(metrics.clone());
+        let node_a = workflow.node_name_of(TypeId::of::<FlakyNode<'a', 2>>());
+        let node_b = workflow.node_name_of(TypeId::of::<FlakyNode<'b', 3>>());
+        assert!(workflow.run(json!({})).is_err());
+
+        let label = |key: &str, value: &str| (key.to_string(), value.to_string());
+        let durations = metrics.named(NODE_DURATION_SECONDS);
+        assert_eq!(
+            durations.iter().map(|(labels, _)| labels.clone()).collect::<Vec<_>>(),
+            [
+                vec![label("workflow", "retry_budget_test"), label("node", &node_a), label("status", "success")],
+                vec![label("workflow", "retry_budget_test"), label("node", &node_b), label("status", "error")],
+            ]
+        );
+
+        let retries = metrics.named(NODE_RETRIES_TOTAL);
+        assert_eq!(retries.len(), 3);
+        assert_eq!(retries.iter().filter(|(labels, _)| labels[1].1 == node_a).count(), 2);
+
+        assert_eq!(
+            metrics.named(NODE_ERRORS_TOTAL),
+            [(
+                vec![label("workflow", "retry_budget_test"), label("node", &node_b), label("error_code", "WF_API_ERROR")],
+                1.0
+            )]
+        );
+    }
+
+    /// Stores a blob of `SIZE` bytes
+    #[derive(Debug)]
+    struct BlobNode<const SIZE: usize>;
+
+    impl<const SIZE: usize> Node for BlobNode<SIZE> {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.update_node(&format!("blob_{}", SIZE), "x".repeat(SIZE));
+            Ok(task_context)
+        }
+    }
+
+    #[test]
+    fn test_context_size_warning_names_largest_growth() {
+        let metrics = Arc::new(RecordingMetrics::default());
+        let workflow = WorkflowBuilder::new::<BlobNode<1_000>>("context_size_test".to_string())
+            .add_node(NodeConfig::new::<BlobNode<1_000>>().with_connections(vec![TypeId::of::<BlobNode<40_000>>()]))
+            .add_node(NodeConfig::new::<BlobNode<40_000>>().with_connections(vec![TypeId::of::<BlobNode<5_000>>()]))
+            .add_node(NodeConfig::new::<BlobNode<5_000>>())
+            .build()
+            .unwrap()
+            .with_metrics(metrics.clone())
+            .with_context_size_warning(44_000);
+        workflow.register_node(BlobNode::<1_000>);
+        workflow.register_node(BlobNode::<40_000>);
+        workflow.register_node(BlobNode::<5_000>);
+        let large = workflow.node_name_of(TypeId::of::<BlobNode<40_000>>());
+
+        let context = workflow.run(json!({})).unwrap();
+
+        let sizes = metrics.named(CONTEXT_SIZE_BYTES);
+        assert_eq!(sizes.len(), 3);
+        assert!(sizes.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert_eq!(sizes[2].1 as usize, context.serialized_size());
+
+        // Only the last node takes the context over the threshold, but the
+        // warning blames the node that grew it most
+        assert!(sizes[1].1 < 44_000.0 && sizes[2].1 > 44_000.0);
+        let label = |key: &str, value: &str| (key.to_string(), value.to_string());
+        assert_eq!(
+            metrics.named(CONTEXT_SIZE_WARNINGS_TOTAL),
+            [(vec![label("workflow", "context_size_test"), label("node", &large)], 1.0)]
+        );
+
+        // Runs under the threshold don't warn
+        let metrics = Arc::new(RecordingMetrics::default());
+        let workflow = WorkflowBuilder::new::<BlobNode<1_000>>("context_size_test".to_string())
+            .build()
+            .unwrap()
+            .with_metrics(metrics.clone())
+            .with_context_size_warning(DEFAULT_CONTEXT_SIZE_WARNING_BYTES);
+        workflow.register_node(BlobNode::<1_000>);
+        workflow.run(json!({})).unwrap();
+        assert_eq!(metrics.named(CONTEXT_SIZE_BYTES).len(), 1);
+        assert!(metrics.named(CONTEXT_SIZE_WARNINGS_TOTAL).is_empty());
+    }
+
+    #[test]
+    fn test_context_size_is_not_measured_unless_enabled() {
+        let metrics = Arc::new(RecordingMetrics::default());
+        let workflow = WorkflowBuilder::new::<BlobNode<40_000>>("context_size_test".to_string())
+            .build()
+            .unwrap()
+            .with_metrics(metrics.clone());
+        workflow.register_node(BlobNode::<40_000>);
+        workflow.run(json!({})).unwrap();
+        assert!(metrics.named(CONTEXT_SIZE_BYTES).is_empty());
+        assert!(metrics.named(CONTEXT_SIZE_WARNINGS_TOTAL).is_empty());
+    }
+
+    /// Reserves stock through a stubbed inventory service whose answer
+    /// changes on every call, as a live service's might
+    #[derive(Debug, Default)]
+    struct ReserveStock {
+        service_calls: std::sync::atomic::AtomicU64,
+    }
+
+    impl Node for ReserveStock {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let sku = task_context.event_data["sku"].clone();
+            let stock = task_context.external_call("inventory", json!({ "sku": sku }), || {
+                let calls = self.service_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(json!({ "available": calls }))
+            })?;
+            if stock["available"] == 0 {
+                return Err(WorkflowError::processing_error("Out of stock", "ReserveStock"));
+            }
+            task_context.update_node("reservation", stock);
+            Ok(task_context)
+        }
+    }
+
+    #[test]
+    fn test_replay_reproduces_recorded_runs() {
+        use replay::{InMemoryReplayStore, ReplayStore};
+
+        let store = Arc::new(InMemoryReplayStore::new());
+        let workflow = WorkflowBuilder::new::<ReserveStock>("replay_test".to_string())
+            .build()
+            .unwrap()
+            .with_replay_store(store.clone());
+        workflow.register_node(ReserveStock::default());
+
+        // The first call finds no stock, the second finds some
+        let failed = TaskContext::new("replay_test".to_string(), json!({ "sku": "KB-42" }));
+        let failed_id = failed.event_id;
+        let error = workflow.run_with_context(failed).unwrap_err();
+        let succeeded = workflow.run(json!({ "sku": "KB-42" })).unwrap();
+
+        let record = store.load(failed_id).unwrap().unwrap();
+        assert_eq!(record.input, json!({ "sku": "KB-42" }));
+        assert_eq!(record.external_calls.len(), 1);
+        assert_eq!(record.external_calls[0].response, Some(json!({ "available": 0 })));
+
+        // A live rerun would now find stock; the replay fails as the run did
+        let replayed = workflow.replay(failed_id).unwrap_err();
+        assert_eq!(replayed.to_string(), error.to_string());
+
+        let replayed = workflow.replay(succeeded.event_id).unwrap();
+        assert_eq!(replayed.event_id, succeeded.event_id);
+        assert_eq!(replayed.event_data, succeeded.event_data);
+        assert_eq!(replayed.nodes, succeeded.nodes);
+        assert_eq!(replayed.metadata, succeeded.metadata);
+
+        // Replays are not recorded over the original
+        assert_eq!(store.load(failed_id).unwrap().unwrap(), record);
+        assert!(workflow.replay(uuid::Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_subscribers_receive_node_events_for_each_run() {
+        let workflow = flaky_workflow::<2, 3>(Some(3));
+        let node_a = workflow.node_name_of(TypeId::of::<FlakyNode<'a', 2>>());
+        let node_b = workflow.node_name_of(TypeId::of::<FlakyNode<'b', 3>>());
+        let mut events = workflow.subscribe();
+        assert!(workflow.run(json!({})).is_err());
+
+        let events: Vec<NodeEvent> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        let execution_id = events[0].execution_id;
+        assert!(events.iter().all(|event| event.execution_id == execution_id));
+        assert!(events.iter().all(|event| event.workflow_type == "retry_budget_test"));
+        assert_eq!(
+            events.iter().map(|event| (event.node.as_str(), event.kind.clone())).collect::<Vec<_>>(),
+            [
+                (node_a.as_str(), NodeEventKind::Started),
+                (node_a.as_str(), NodeEventKind::Completed),
+                (node_b.as_str(), NodeEventKind::Started),
+                (
+                    node_b.as_str(),
+                    NodeEventKind::Failed {
+                        error: "API error from flaky at / (status 503): flaky node b failed attempt 2".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+
+    /// Always fails with a node error of category `CATEGORY`, counting attempts
+    #[derive(Debug)]
+    struct ClassifiedFailure<const CATEGORY: u8>(Arc<std::sync::atomic::AtomicUsize>);
+
+    impl<const CATEGORY: u8> Node for ClassifiedFailure<CATEGORY> {
+        fn process(&self, _task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(match CATEGORY {
+                0 => WorkflowError::user_error("Order id is missing", "ClassifiedFailure"),
+                _ => WorkflowError::transient_error("Inventory service busy", "ClassifiedFailure"),
+            })
+        }
+    }
+
+    fn attempts_until_failure<const CATEGORY: u8>() -> (usize, WorkflowError) {
+        let workflow = WorkflowBuilder::new::<ClassifiedFailure<CATEGORY>>("classified".to_string())
+            .add_node(NodeConfig::new::<ClassifiedFailure<CATEGORY>>().with_retry(3, Duration::ZERO))
+            .build()
+            .unwrap();
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        workflow.register_node(ClassifiedFailure::<CATEGORY>(attempts.clone()));
+        let error = workflow.run(json!({})).unwrap_err();
+        (attempts.load(std::sync::atomic::Ordering::SeqCst), error)
+    }
+
+    #[test]
+    fn test_user_errors_are_not_retried() {
+        let (attempts, error) = attempts_until_failure::<0>();
+        assert_eq!(attempts, 1);
+        assert_eq!(error.category(), crate::error::ErrorCategory::User);
+        assert!(!error.is_retryable());
+
+        let (attempts, error) = attempts_until_failure::<1>();
+        assert_eq!(attempts, 4);
+        assert_eq!(error.category(), crate::error::ErrorCategory::Transient);
+        assert!(crate::error::RetryableError::is_retryable(&error));
+    }
+
+    /// Stands in for a node fetching a URL, recording what it fetched
+    #[derive(Debug)]
+    struct HttpFetchNode {
+        url: String,
+    }
+
+    impl HttpFetchNode {
+        fn new(url: &str) -> Self {
+            Self { url: url.to_string() }
+        }
+    }
+
+    impl Node for HttpFetchNode {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let mut fetched: Vec<String> = task_context.get_data("fetched")?.unwrap_or_default();
+            fetched.push(self.url.clone());
+            task_context.update_node("fetched", json!(fetched));
+            Ok(task_context)
+        }
+    }
+
+    #[test]
+    fn test_named_instances_of_one_node_type() {
+        let workflow = WorkflowBuilder::new::<FastNode>("named".to_string())
+            .add_node(NodeConfig::new::<FastNode>().with_named_node("orders").with_named_node("stock"))
+            .build()
+            .unwrap();
+        workflow.register_node(FastNode);
+        workflow.register_named_node("orders", HttpFetchNode::new("https://orders.example.com"));
+        workflow.register_named_node("stock", HttpFetchNode::new("https://stock.example.com"));
+
+        let (result, report) = workflow.run_with_report(json!({}));
+        let context = result.unwrap();
+        assert_eq!(
+            context.get_all_data()["fetched"],
+            json!(["https://orders.example.com", "https://stock.example.com"])
+        );
+        assert_eq!(context.get_all_data()["fast"], json!({ "done": true }));
+        let nodes: Vec<&str> = report.node_timings.iter().map(|timing| timing.node.as_str()).collect();
+        assert_eq!(nodes[..2], ["orders", "stock"]);
+
+        // Ids survive a round trip through JSON, unlike node types
+        let config = NodeConfig::new::<FastNode>().with_named_node("orders");
+        let restored: NodeConfig = serde_json::from_value(serde_json::to_value(&config).unwrap()).unwrap();
+        assert_eq!(restored.named_nodes, ["orders"]);
+    }
+
+    #[test]
+    fn test_unregistered_named_node_fails_run() {
+        let workflow = WorkflowBuilder::new::<FastNode>("named".to_string())
+            .add_node(NodeConfig::new::<FastNode>().with_named_node("orders"))
+            .build()
+            .unwrap();
+        workflow.register_node(FastNode);
+
+        let error = workflow.run_with_error_context(TaskContext::new("named".to_string(), json!({}))).unwrap_err();
+        assert!(matches!(error.error, WorkflowError::ConfigurationError { .. }));
+        assert_eq!(error.metadata.context[NODE_NAME_CONTEXT_KEY], json!("orders"));
+    }
+}
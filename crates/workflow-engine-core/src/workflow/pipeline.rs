@@ -0,0 +1,172 @@
+//! Sequential composition of workflows
+//!
+//! A [`Pipeline`] runs workflows one after another, each continuing from the
+//! final context of the one before, so later workflows see the node results
+//! and metadata of earlier ones. The first failure ends the pipeline.
+//!
+//! ```ignore
+//! let pipeline = Pipeline::new().then(ingest_workflow).then(enrich_workflow);
+//! let context = pipeline.run(json!({"ticket_id": 42}))?;
+//! ```
+
+#![allow(clippy::result_large_err, reason = "a pipeline returns the same WorkflowError as the workflows it runs")]
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use super::Workflow;
+use crate::error::WorkflowError;
+use crate::task::TaskContext;
+
+/// Workflow type of the context an empty pipeline returns
+pub const EMPTY_PIPELINE_TYPE: &str = "pipeline";
+
+/// Workflows run in sequence, each on the final context of the previous one
+#[derive(Clone, Default)]
+pub struct Pipeline {
+    stages: Vec<Arc<Workflow>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `workflow` after the workflows added so far
+    pub fn then(mut self, workflow: impl Into<Arc<Workflow>>) -> Self {
+        self.stages.push(workflow.into());
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Runs the pipeline with new data, as the input of its first workflow
+    pub fn run(&self, event_data: Value) -> Result<TaskContext, WorkflowError> {
+        let workflow_type = self
+            .stages
+            .first()
+            .map_or(EMPTY_PIPELINE_TYPE, |workflow| workflow.workflow_type());
+        self.run_with_context(TaskContext::new(workflow_type.to_string(), event_data))
+    }
+
+    /// Runs the pipeline on a prepared context.
+    ///
+    /// Each workflow runs on the context the previous one finished with,
+    /// relabelled with its own workflow type; the event id, event data and
+    /// metadata carry through. The first failing workflow's error is
+    /// returned without running the rest.
+    pub fn run_with_context(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        for (stage, workflow) in self.stages.iter().enumerate() {
+            task_context.workflow_type = workflow.workflow_type().to_string();
+            task_context = workflow.run_with_context(task_context).map_err(|error| {
+                tracing::warn!(
+                    workflow = workflow.workflow_type(),
+                    stage,
+                    error = %error,
+                    "Pipeline stopped at failing workflow"
+                );
+                error
+            })?;
+        }
+        Ok(task_context)
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.stages.iter().map(|workflow| workflow.workflow_type()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::{config::NodeConfig, Node};
+    use crate::workflow::builder::WorkflowBuilder;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug)]
+    struct ExtractTicket;
+
+    impl Node for ExtractTicket {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let id = task_context.event_data["ticket_id"].clone();
+            task_context.update_node("ticket", json!({ "id": id, "subject": "Refund request" }));
+            Ok(task_context)
+        }
+    }
+
+    #[derive(Debug)]
+    struct RejectTicket;
+
+    impl Node for RejectTicket {
+        fn process(&self, _task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Err(WorkflowError::validation_error_simple("Ticket is closed"))
+        }
+    }
+
+    /// Summarizes the ticket extracted earlier, counting its runs
+    #[derive(Debug)]
+    struct SummarizeTicket(Arc<AtomicUsize>);
+
+    impl Node for SummarizeTicket {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let ticket: Value = task_context
+                .get_data("ticket")?
+                .ok_or_else(|| WorkflowError::validation_error_simple("No ticket extracted"))?;
+            let summary = format!("#{}: {}", ticket["id"], ticket["subject"].as_str().unwrap_or_default());
+            task_context.update_node("summary", json!(summary));
+            Ok(task_context)
+        }
+    }
+
+    fn workflow<T: Node + 'static>(workflow_type: &str, node: T) -> Workflow {
+        let workflow = WorkflowBuilder::new::<T>(workflow_type.to_string())
+            .add_node(NodeConfig::new::<T>())
+            .build()
+            .unwrap();
+        workflow.register_node(node);
+        workflow
+    }
+
+    #[test]
+    fn test_later_workflows_see_earlier_output() {
+        let summaries = Arc::new(AtomicUsize::new(0));
+        let pipeline = Pipeline::new()
+            .then(workflow("extract", ExtractTicket))
+            .then(workflow("summarize", SummarizeTicket(summaries.clone())));
+        assert_eq!(format!("{:?}", pipeline), r#"["extract", "summarize"]"#);
+
+        let context = pipeline.run(json!({ "ticket_id": 42 })).unwrap();
+        assert_eq!(context.get_all_data()["summary"], "#42: Refund request");
+        assert_eq!(context.workflow_type, "summarize");
+        assert_eq!(summaries.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_failure_stops_pipeline() {
+        let summaries = Arc::new(AtomicUsize::new(0));
+        let pipeline = Pipeline::new()
+            .then(workflow("reject", RejectTicket))
+            .then(workflow("summarize", SummarizeTicket(summaries.clone())));
+
+        let error = pipeline.run(json!({ "ticket_id": 42 })).unwrap_err();
+        assert!(matches!(error, WorkflowError::ValidationError { .. }));
+        assert_eq!(summaries.load(Ordering::SeqCst), 0);
+
+        let context = Pipeline::new().run(json!({ "ticket_id": 42 })).unwrap();
+        assert_eq!(context.workflow_type, EMPTY_PIPELINE_TYPE);
+    }
+}
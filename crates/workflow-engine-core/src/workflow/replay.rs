@@ -0,0 +1,267 @@
+//! Recording and replaying workflow runs
+//!
+//! A workflow with a [`ReplayStore`] records every run it starts: the input,
+//! the initial metadata and the response to every external call made through
+//! [`TaskContext::external_call`](crate::task::TaskContext::external_call) or
+//! its async counterpart. [`Workflow::replay`](super::Workflow::replay) runs
+//! the workflow again on the recorded input with those calls answered from
+//! the record rather than the services, so a failed run can be reproduced
+//! deterministically while debugging.
+
+#![allow(clippy::result_large_err, reason = "replayed calls fail with the WorkflowError the recorded run saw")]
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::WorkflowError;
+use crate::task::TaskContext;
+
+/// An external call made during a recorded run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// Service the call went to, e.g. `"notion"`
+    pub service: String,
+    /// What was asked of the service; replayed calls are matched on it
+    pub request: Value,
+    /// The service's response, if the call succeeded
+    pub response: Option<Value>,
+    /// The error message, if the call failed
+    pub error: Option<String>,
+}
+
+/// Everything needed to replay a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionRecord {
+    /// The run's `event_id`
+    pub execution_id: Uuid,
+    pub workflow_type: String,
+    /// The run's event data
+    pub input: Value,
+    /// Metadata the context carried before the first node ran
+    pub metadata: HashMap<String, Value>,
+    pub started_at: DateTime<Utc>,
+    /// External calls in the order they completed
+    pub external_calls: Vec<RecordedCall>,
+}
+
+impl ExecutionRecord {
+    /// Start a record of a run on `task_context`, before any node has run
+    pub fn start(task_context: &TaskContext) -> Self {
+        Self {
+            execution_id: task_context.event_id,
+            workflow_type: task_context.workflow_type.clone(),
+            input: task_context.event_data.clone(),
+            metadata: task_context.get_all_metadata().clone(),
+            started_at: task_context.created_at,
+            external_calls: Vec::new(),
+        }
+    }
+
+    /// A fresh context for replaying the run, with the original event id
+    /// and the recorded calls to answer from
+    pub fn replay_context(&self) -> TaskContext {
+        let mut task_context = TaskContext::new(self.workflow_type.clone(), self.input.clone());
+        task_context.event_id = self.execution_id;
+        task_context.created_at = self.started_at;
+        *task_context.metadata_mut() = self.metadata.clone();
+        task_context.external_calls = Some(ExternalCalls::replaying(self.external_calls.clone()));
+        task_context
+    }
+}
+
+/// Storage for execution records
+pub trait ReplayStore: Send + Sync + std::fmt::Debug {
+    /// Store `record`, replacing any earlier record of the same execution
+    fn save(&self, record: ExecutionRecord) -> Result<(), WorkflowError>;
+
+    fn load(&self, execution_id: Uuid) -> Result<Option<ExecutionRecord>, WorkflowError>;
+}
+
+/// A [`ReplayStore`] keeping records in memory
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryReplayStore {
+    records: Arc<Mutex<HashMap<Uuid, ExecutionRecord>>>,
+}
+
+impl InMemoryReplayStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn records(&self) -> MutexGuard<'_, HashMap<Uuid, ExecutionRecord>> {
+        self.records.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ReplayStore for InMemoryReplayStore {
+    fn save(&self, record: ExecutionRecord) -> Result<(), WorkflowError> {
+        self.records().insert(record.execution_id, record);
+        Ok(())
+    }
+
+    fn load(&self, execution_id: Uuid) -> Result<Option<ExecutionRecord>, WorkflowError> {
+        Ok(self.records().get(&execution_id).cloned())
+    }
+}
+
+#[derive(Debug)]
+enum CallLog {
+    Recording(Vec<RecordedCall>),
+    /// Recorded calls not yet answered
+    Replaying(Vec<RecordedCall>),
+}
+
+/// The external calls of one run, recorded as they are made or answered from
+/// an earlier recording.
+///
+/// Cloning yields another handle to the same log, so parallel branches share
+/// it.
+#[derive(Debug, Clone)]
+pub struct ExternalCalls {
+    log: Arc<Mutex<CallLog>>,
+}
+
+impl ExternalCalls {
+    /// A log recording the calls it is given
+    pub fn recording() -> Self {
+        Self::with_log(CallLog::Recording(Vec::new()))
+    }
+
+    /// A log answering calls from `calls` instead of making them
+    pub fn replaying(calls: Vec<RecordedCall>) -> Self {
+        Self::with_log(CallLog::Replaying(calls))
+    }
+
+    fn with_log(log: CallLog) -> Self {
+        Self {
+            log: Arc::new(Mutex::new(log)),
+        }
+    }
+
+    fn log(&self) -> MutexGuard<'_, CallLog> {
+        self.log.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        matches!(*self.log(), CallLog::Replaying(_))
+    }
+
+    /// The calls recorded so far, or the ones left to answer when replaying
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        match &*self.log() {
+            CallLog::Recording(calls) | CallLog::Replaying(calls) => calls.clone(),
+        }
+    }
+
+    /// Make a call to `service` through `call`, or answer it from the
+    /// recording when replaying
+    pub fn call<F>(&self, service: &str, request: Value, call: F) -> Result<Value, WorkflowError>
+    where
+        F: FnOnce() -> Result<Value, WorkflowError>,
+    {
+        if self.is_replaying() {
+            return self.answer(service, &request);
+        }
+        let result = call();
+        self.record(service, request, &result);
+        result
+    }
+
+    /// Async version of [`call`](Self::call)
+    pub async fn call_async<F, Fut>(&self, service: &str, request: Value, call: F) -> Result<Value, WorkflowError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value, WorkflowError>>,
+    {
+        if self.is_replaying() {
+            return self.answer(service, &request);
+        }
+        let result = call().await;
+        self.record(service, request, &result);
+        result
+    }
+
+    fn record(&self, service: &str, request: Value, result: &Result<Value, WorkflowError>) {
+        if let CallLog::Recording(calls) = &mut *self.log() {
+            calls.push(RecordedCall {
+                service: service.to_string(),
+                request,
+                response: result.as_ref().ok().cloned(),
+                error: result.as_ref().err().map(ToString::to_string),
+            });
+        }
+    }
+
+    /// Take the first unanswered recording of the same call. Matching on the
+    /// request rather than the order keeps parallel branches deterministic.
+    fn answer(&self, service: &str, request: &Value) -> Result<Value, WorkflowError> {
+        let mut log = self.log();
+        let CallLog::Replaying(calls) = &mut *log else {
+            unreachable!("only replaying logs answer calls");
+        };
+        let Some(index) = calls
+            .iter()
+            .position(|call| call.service == service && call.request == *request)
+        else {
+            return Err(WorkflowError::RuntimeError {
+                message: format!(
+                    "Replay diverged from the recorded run: no recorded call to {} with request {}",
+                    service, request
+                ),
+            });
+        };
+
+        let call = calls.remove(index);
+        match (call.response, call.error) {
+            (Some(response), _) => Ok(response),
+            (None, error) => Err(WorkflowError::api_error(
+                error.unwrap_or_default(),
+                service,
+                "replay",
+                None,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_replayed_calls_are_matched_by_request() {
+        let recording = ExternalCalls::recording();
+        for id in ["a", "b"] {
+            recording
+                .call("crm", json!({ "lookup": id }), || Ok(json!({ "name": id.to_uppercase() })))
+                .unwrap();
+        }
+        recording
+            .call("crm", json!({ "lookup": "c" }), || {
+                Err(WorkflowError::api_error("rate limited", "crm", "/lookup", Some(429)))
+            })
+            .unwrap_err();
+        assert!(!recording.is_replaying());
+
+        let replaying = ExternalCalls::replaying(recording.calls());
+        let answer = |id: &str| {
+            replaying.call("crm", json!({ "lookup": id }), || -> Result<Value, WorkflowError> {
+                panic!("replayed calls must not reach the service")
+            })
+        };
+        assert_eq!(answer("b").unwrap(), json!({ "name": "B" }));
+        assert_eq!(answer("a").unwrap(), json!({ "name": "A" }));
+        assert!(answer("c").unwrap_err().to_string().contains("rate limited"));
+
+        let diverged = answer("a").unwrap_err();
+        assert!(diverged.to_string().contains("Replay diverged"));
+        assert!(replaying.calls().is_empty());
+    }
+}
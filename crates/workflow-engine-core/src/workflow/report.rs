@@ -0,0 +1,161 @@
+//! Summaries of workflow runs
+//!
+//! [`Workflow::run_with_report`](super::Workflow::run_with_report) returns an
+//! [`ExecutionReport`] alongside the run's result: how long the run and each
+//! of its nodes took, which nodes were retried, what the AI nodes spent and
+//! every error met along the way, including the ones a retry recovered from.
+//! Reports serialize to JSON for logging and audit trails.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::budget::BudgetState;
+use super::{NODE_FAILURES_KEY, NODE_NAME_CONTEXT_KEY};
+use crate::error::{ErrorContext, ErrorExt, WorkflowError};
+use crate::task::TaskContext;
+
+/// How a run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Completed,
+    /// Completed, but past the failure of at least one non-critical node
+    PartiallyCompleted,
+    Failed,
+}
+
+/// How long one node took, retries included
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeTiming {
+    pub node: String,
+    pub duration: Duration,
+    pub succeeded: bool,
+}
+
+/// An error met during a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportedError {
+    /// The node that failed, if the error came from a node
+    pub node: Option<String>,
+    pub error_code: String,
+    pub message: String,
+    /// Whether the node was retried after this error
+    pub retried: bool,
+}
+
+/// The failure of a non-critical node, recorded in the context under
+/// [`NODE_FAILURES_KEY`](super::NODE_FAILURES_KEY)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeFailure {
+    pub node: String,
+    pub error_code: String,
+    pub message: String,
+}
+
+impl NodeFailure {
+    pub(crate) fn new(node: &str, error: &WorkflowError) -> Self {
+        Self {
+            node: node.to_string(),
+            error_code: error.error_code().to_string(),
+            message: error.to_string(),
+        }
+    }
+
+    /// The non-critical failures a run continued past
+    pub fn of(task_context: &TaskContext) -> Vec<NodeFailure> {
+        task_context
+            .get_metadata(NODE_FAILURES_KEY)
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+}
+
+/// Summary of one run of a workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// The run's `event_id`
+    pub execution_id: Uuid,
+    pub workflow_type: String,
+    pub status: ExecutionStatus,
+    pub total_duration: Duration,
+    /// Nodes in the order they ran
+    pub node_timings: Vec<NodeTiming>,
+    /// Retries by node, for the nodes that were retried
+    pub retries: HashMap<String, u32>,
+    /// What the AI nodes spent, when the workflow has an AI budget
+    pub ai_usage: Option<BudgetState>,
+    pub errors: Vec<ReportedError>,
+}
+
+impl ExecutionReport {
+    pub(crate) fn start(task_context: &TaskContext) -> Self {
+        Self {
+            execution_id: task_context.event_id,
+            workflow_type: task_context.workflow_type.clone(),
+            status: ExecutionStatus::Completed,
+            total_duration: Duration::ZERO,
+            node_timings: Vec::new(),
+            retries: HashMap::new(),
+            ai_usage: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Retries across all nodes
+    pub fn total_retries(&self) -> u32 {
+        self.retries.values().sum()
+    }
+
+    pub(crate) fn record_node(&mut self, node: &str, duration: Duration, succeeded: bool) {
+        self.node_timings.push(NodeTiming {
+            node: node.to_string(),
+            duration,
+            succeeded,
+        });
+    }
+
+    /// Record a failed attempt of `node` that is about to be retried
+    pub(crate) fn record_retry(&mut self, node: &str, error: &WorkflowError) {
+        *self.retries.entry(node.to_string()).or_default() += 1;
+        self.errors.push(ReportedError {
+            node: Some(node.to_string()),
+            error_code: error.error_code().to_string(),
+            message: error.to_string(),
+            retried: true,
+        });
+    }
+
+    /// Record the failure of a non-critical node the run continued past
+    pub(crate) fn record_skipped_failure(&mut self, node: &str, error: &WorkflowError) {
+        self.status = ExecutionStatus::PartiallyCompleted;
+        self.errors.push(ReportedError {
+            node: Some(node.to_string()),
+            error_code: error.error_code().to_string(),
+            message: error.to_string(),
+            retried: false,
+        });
+    }
+
+    pub(crate) fn finish(&mut self, total_duration: Duration, failure: Option<&ErrorContext>) {
+        self.total_duration = total_duration;
+        let Some(failure) = failure else {
+            return;
+        };
+        self.status = ExecutionStatus::Failed;
+        self.errors.push(ReportedError {
+            node: failure
+                .metadata
+                .context
+                .get(NODE_NAME_CONTEXT_KEY)
+                .and_then(|node| node.as_str())
+                .map(ToString::to_string),
+            error_code: failure.error.error_code().to_string(),
+            message: failure.error.to_string(),
+            retried: false,
+        });
+    }
+}
@@ -0,0 +1,572 @@
+//! Cron-scheduled workflow execution
+//!
+//! [`WorkflowScheduler`] runs workflows from a [`WorkflowVersions`] registry
+//! at the times given by cron expressions. Each schedule gets its own Tokio
+//! task that sleeps until the next run time, so schedules must be added from
+//! within a Tokio runtime.
+//!
+//! Cron expressions have five fields (`minute hour day-of-month month
+//! day-of-week`) or six with a leading seconds field, and are evaluated in
+//! UTC. Fields accept `*`, numbers, ranges (`1-5`), steps (`*/15`, `0-30/10`)
+//! and comma-separated lists. Day of week runs from 0 (Sunday) to 6, with 7
+//! also meaning Sunday.
+//!
+//! ```rust,no_run
+//! # use std::sync::Arc;
+//! # use serde_json::json;
+//! # use workflow_engine_core::workflow::versions::WorkflowVersions;
+//! use workflow_engine_core::workflow::scheduler::{MissedRunPolicy, Schedule, WorkflowScheduler};
+//!
+//! # async fn example(versions: Arc<WorkflowVersions>) -> Result<(), workflow_engine_core::error::WorkflowError> {
+//! let scheduler = WorkflowScheduler::new(versions);
+//! let nightly = Schedule::new("0 30 2 * * *", "reindex", json!({ "full": true }))?
+//!     .with_missed_runs(MissedRunPolicy::CatchUp { max_runs: 3 });
+//! let id = scheduler.add(nightly);
+//!
+//! for (id, schedule) in scheduler.list() {
+//!     println!("{}: {} at {}", id, schedule.workflow_type(), schedule.cron());
+//! }
+//! scheduler.remove(id);
+//! # Ok(())
+//! # }
+//! ```
+
+#![allow(clippy::result_large_err, reason = "scheduled runs report the WorkflowError of the workflow they start")]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Datelike, Days, Duration, TimeZone, Timelike, Utc};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::versions::WorkflowVersions;
+use crate::error::WorkflowError;
+
+/// Source of the current time for a [`WorkflowScheduler`]
+pub trait Clock: Send + Sync + fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Values allowed by one cron field, as a bitset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CronField {
+    bits: u64,
+    /// Whether the field was `*`, which matters for the day fields
+    any: bool,
+}
+
+impl CronField {
+    fn parse(field: &str, name: &str, min: u32, max: u32) -> Result<Self, WorkflowError> {
+        let invalid = |reason: String| {
+            WorkflowError::validation_error(
+                format!("Invalid cron {} field '{}': {}", name, field, reason),
+                "cron",
+                format!("{} values must be between {} and {}", name, min, max),
+                "in CronExpression::parse",
+            )
+        };
+        let number = |value: &str| {
+            value
+                .parse::<u32>()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .ok_or_else(|| invalid(format!("'{}' is out of range", value)))
+        };
+
+        let mut bits = 0;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step = step
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|step| *step > 0)
+                        .ok_or_else(|| invalid(format!("'{}' is not a valid step", step)))?;
+                    (range, Some(step))
+                }
+                None => (part, None),
+            };
+            let (start, end) = match range {
+                "*" => (min, max),
+                _ => match range.split_once('-') {
+                    Some((start, end)) => (number(start)?, number(end)?),
+                    // `5/10` means every 10th value from 5
+                    None if step.is_some() => (number(range)?, max),
+                    None => {
+                        let value = number(range)?;
+                        (value, value)
+                    }
+                },
+            };
+            if start > end {
+                return Err(invalid(format!("range {}-{} is empty", start, end)));
+            }
+            for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+                bits |= 1 << value;
+            }
+        }
+
+        Ok(Self {
+            bits,
+            any: field == "*",
+        })
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+}
+
+/// A parsed cron expression
+#[derive(Clone, PartialEq, Eq)]
+pub struct CronExpression {
+    source: String,
+    seconds: CronField,
+    minutes: CronField,
+    hours: CronField,
+    days_of_month: CronField,
+    months: CronField,
+    days_of_week: CronField,
+}
+
+impl CronExpression {
+    /// Parse a five-field expression, or a six-field one starting with seconds
+    pub fn parse(expression: &str) -> Result<Self, WorkflowError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => {
+                return Err(WorkflowError::validation_error(
+                    format!("Cron expression '{}' has {} fields", expression, n),
+                    "cron",
+                    "must have 5 or 6 fields",
+                    "in CronExpression::parse",
+                ))
+            }
+        };
+
+        let mut days_of_week = CronField::parse(rest[4], "day-of-week", 0, 7)?;
+        if days_of_week.contains(7) {
+            days_of_week.bits |= 1;
+        }
+
+        Ok(Self {
+            source: expression.to_string(),
+            seconds: CronField::parse(seconds, "second", 0, 59)?,
+            minutes: CronField::parse(rest[0], "minute", 0, 59)?,
+            hours: CronField::parse(rest[1], "hour", 0, 23)?,
+            days_of_month: CronField::parse(rest[2], "day-of-month", 1, 31)?,
+            months: CronField::parse(rest[3], "month", 1, 12)?,
+            days_of_week,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the expression allows the day of `time`. As in standard cron,
+    /// when both day fields are restricted a day matching either one matches.
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = self.days_of_month.contains(time.day());
+        let day_of_week = self.days_of_week.contains(time.weekday().num_days_from_sunday());
+        match (self.days_of_month.any, self.days_of_week.any) {
+            (false, false) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+
+    /// The first time strictly after `after` that matches the expression, or
+    /// `None` if there is none in the next five years
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = after + Duration::days(5 * 366);
+        let mut time = after.with_nanosecond(0)? + Duration::seconds(1);
+
+        while time <= limit {
+            if !self.months.contains(time.month()) {
+                let (year, month) = match time.month() {
+                    12 => (time.year() + 1, 1),
+                    month => (time.year(), month + 1),
+                };
+                time = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(time) {
+                time = (time.date_naive() + Days::new(1)).and_hms_opt(0, 0, 0)?.and_utc();
+            } else if !self.hours.contains(time.hour()) {
+                time = time.with_minute(0)?.with_second(0)? + Duration::hours(1);
+            } else if !self.minutes.contains(time.minute()) {
+                time = time.with_second(0)? + Duration::minutes(1);
+            } else if !self.seconds.contains(time.second()) {
+                time += Duration::seconds(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Debug for CronExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CronExpression").field(&self.source).finish()
+    }
+}
+
+impl fmt::Display for CronExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// What to do about run times that passed while the scheduler could not run
+/// them, e.g. because a previous run overran or the host was suspended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedRunPolicy {
+    /// Run once for the most recent missed time and drop the others
+    #[default]
+    Skip,
+    /// Run once for every missed time, one after another, up to `max_runs`
+    /// runs; later missed times are dropped
+    CatchUp { max_runs: usize },
+}
+
+/// A workflow to run on a cron schedule
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    cron: CronExpression,
+    workflow_type: String,
+    input: Value,
+    missed_runs: MissedRunPolicy,
+}
+
+impl Schedule {
+    /// Run the latest version of `workflow_type` with `input` as its event
+    /// data whenever `cron` matches
+    pub fn new(
+        cron: &str,
+        workflow_type: impl Into<String>,
+        input: Value,
+    ) -> Result<Self, WorkflowError> {
+        Ok(Self {
+            cron: CronExpression::parse(cron)?,
+            workflow_type: workflow_type.into(),
+            input,
+            missed_runs: MissedRunPolicy::default(),
+        })
+    }
+
+    pub fn with_missed_runs(mut self, policy: MissedRunPolicy) -> Self {
+        self.missed_runs = policy;
+        self
+    }
+
+    pub fn cron(&self) -> &CronExpression {
+        &self.cron
+    }
+
+    pub fn workflow_type(&self) -> &str {
+        &self.workflow_type
+    }
+
+    pub fn input(&self) -> &Value {
+        &self.input
+    }
+
+    pub fn missed_runs(&self) -> MissedRunPolicy {
+        self.missed_runs
+    }
+}
+
+struct ScheduledTask {
+    schedule: Schedule,
+    handle: JoinHandle<()>,
+}
+
+/// Runs workflows on cron schedules
+pub struct WorkflowScheduler {
+    workflows: Arc<WorkflowVersions>,
+    clock: Arc<dyn Clock>,
+    schedules: Mutex<HashMap<Uuid, ScheduledTask>>,
+}
+
+impl WorkflowScheduler {
+    pub fn new(workflows: Arc<WorkflowVersions>) -> Self {
+        Self {
+            workflows,
+            clock: Arc::new(SystemClock),
+            schedules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Read the time from `clock` instead of the system clock. Waits between
+    /// runs still use Tokio timers, so tests can pair a fake clock with
+    /// paused Tokio time.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Start running `schedule`, returning its id
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub fn add(&self, schedule: Schedule) -> Uuid {
+        let id = Uuid::new_v4();
+        let handle = tokio::spawn(run_schedule(
+            id,
+            schedule.clone(),
+            self.workflows.clone(),
+            self.clock.clone(),
+        ));
+        self.schedules
+            .lock()
+            .unwrap()
+            .insert(id, ScheduledTask { schedule, handle });
+        id
+    }
+
+    /// Stop a schedule. A run already in progress is allowed to finish.
+    /// Returns whether the schedule existed.
+    pub fn remove(&self, id: Uuid) -> bool {
+        match self.schedules.lock().unwrap().remove(&id) {
+            Some(task) => {
+                task.handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// All active schedules
+    pub fn list(&self) -> Vec<(Uuid, Schedule)> {
+        self.schedules
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, task)| (*id, task.schedule.clone()))
+            .collect()
+    }
+}
+
+impl Drop for WorkflowScheduler {
+    fn drop(&mut self) {
+        for task in self.schedules.get_mut().unwrap().values() {
+            task.handle.abort();
+        }
+    }
+}
+
+async fn run_schedule(
+    id: Uuid,
+    schedule: Schedule,
+    workflows: Arc<WorkflowVersions>,
+    clock: Arc<dyn Clock>,
+) {
+    let mut last = clock.now();
+    loop {
+        let Some(next) = schedule.cron.next_after(last) else {
+            tracing::warn!(schedule = %id, cron = %schedule.cron, "Schedule has no future run times");
+            return;
+        };
+        let wait = (next - clock.now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        let now = clock.now();
+        if now < next {
+            // Woke early relative to the clock; wait again
+            continue;
+        }
+        if schedule.cron.next_after(next).is_some_and(|time| time <= now) {
+            tracing::warn!(
+                schedule = %id,
+                policy = ?schedule.missed_runs,
+                "Schedule missed run times"
+            );
+        }
+
+        // Only the times that will run are enumerated; after them the
+        // schedule resumes from now
+        let runs = match schedule.missed_runs {
+            MissedRunPolicy::Skip => {
+                last = now;
+                1
+            }
+            MissedRunPolicy::CatchUp { max_runs } => {
+                let mut runs = 1;
+                last = next;
+                while runs < max_runs {
+                    match schedule.cron.next_after(last).filter(|time| *time <= now) {
+                        Some(time) => {
+                            last = time;
+                            runs += 1;
+                        }
+                        None => break,
+                    }
+                }
+                if runs == max_runs {
+                    last = now;
+                }
+                runs
+            }
+        };
+        for _ in 0..runs {
+            let workflows = workflows.clone();
+            let workflow_type = schedule.workflow_type.clone();
+            let input = schedule.input.clone();
+            let result =
+                tokio::task::spawn_blocking(move || workflows.run(&workflow_type, None, input)).await;
+            match result {
+                Ok(Ok(context)) => tracing::debug!(
+                    schedule = %id,
+                    event_id = %context.event_id,
+                    "Scheduled workflow run completed"
+                ),
+                Ok(Err(e)) => tracing::warn!(schedule = %id, error = %e, "Scheduled workflow run failed"),
+                Err(e) => tracing::error!(schedule = %id, error = %e, "Scheduled workflow run panicked"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::Node;
+    use crate::task::TaskContext;
+    use crate::workflow::builder::WorkflowBuilder;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Wall clock that follows paused Tokio time, plus manual jumps
+    #[derive(Debug)]
+    struct FakeClock {
+        start: DateTime<Utc>,
+        started: tokio::time::Instant,
+        jumped: Mutex<Duration>,
+    }
+
+    impl FakeClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                start: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                started: tokio::time::Instant::now(),
+                jumped: Mutex::new(Duration::zero()),
+            })
+        }
+
+        /// Move the wall clock forward without Tokio time passing, as when
+        /// the host is suspended
+        fn jump(&self, by: Duration) {
+            *self.jumped.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            let elapsed = Duration::from_std(self.started.elapsed()).unwrap();
+            self.start + elapsed + *self.jumped.lock().unwrap()
+        }
+    }
+
+    #[derive(Debug)]
+    struct Tick(Arc<AtomicUsize>);
+
+    impl Node for Tick {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(task_context)
+        }
+    }
+
+    fn scheduler(clock: Arc<FakeClock>) -> (WorkflowScheduler, Arc<AtomicUsize>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let workflow = WorkflowBuilder::new::<Tick>("tick".to_string()).build().unwrap();
+        workflow.register_node(Tick(runs.clone()));
+        let versions = WorkflowVersions::new();
+        versions.register(workflow).unwrap();
+        (WorkflowScheduler::new(Arc::new(versions)).with_clock(clock), runs)
+    }
+
+    fn at(expression: &str, time: &str) -> Option<String> {
+        let after = DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&Utc);
+        CronExpression::parse(expression)
+            .unwrap()
+            .next_after(after)
+            .map(|time| time.to_rfc3339())
+    }
+
+    #[test]
+    fn test_next_run_times() {
+        assert_eq!(at("* * * * * *", "2024-01-01T00:00:00.500Z").unwrap(), "2024-01-01T00:00:01+00:00");
+        assert_eq!(at("*/15 * * * *", "2024-01-01T10:07:00Z").unwrap(), "2024-01-01T10:15:00+00:00");
+        assert_eq!(at("0 30 2 * * *", "2024-01-01T03:00:00Z").unwrap(), "2024-01-02T02:30:00+00:00");
+        // 2024-01-06 is a Saturday; 7 means Sunday
+        assert_eq!(at("0 9 * * 1-5", "2024-01-05T09:00:00Z").unwrap(), "2024-01-08T09:00:00+00:00");
+        assert_eq!(at("0 0 * * 7", "2024-01-01T00:00:00Z").unwrap(), "2024-01-07T00:00:00+00:00");
+        assert_eq!(at("0 0 29 2 *", "2024-03-01T00:00:00Z").unwrap(), "2028-02-29T00:00:00+00:00");
+        assert_eq!(at("0 0 31 2 *", "2024-01-01T00:00:00Z"), None);
+
+        for invalid in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "a * * * *"] {
+            assert!(CronExpression::parse(invalid).is_err(), "{} should not parse", invalid);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_per_second_schedule_fires_until_removed() {
+        let (scheduler, runs) = scheduler(FakeClock::new());
+        let id = scheduler.add(Schedule::new("* * * * * *", "tick", json!({})).unwrap());
+        assert_eq!(scheduler.list().len(), 1);
+        assert_eq!(scheduler.list()[0].1.workflow_type(), "tick");
+
+        tokio::time::sleep(std::time::Duration::from_millis(5500)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 5);
+
+        assert!(scheduler.remove(id));
+        assert!(!scheduler.remove(id));
+        assert!(scheduler.list().is_empty());
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_missed_run_policies() {
+        for (policy, expected) in [
+            (MissedRunPolicy::Skip, 1),
+            (MissedRunPolicy::CatchUp { max_runs: 20 }, 11),
+            (MissedRunPolicy::CatchUp { max_runs: 4 }, 4),
+        ] {
+            let clock = FakeClock::new();
+            let (scheduler, runs) = scheduler(clock.clone());
+            scheduler.add(
+                Schedule::new("* * * * * *", "tick", json!({}))
+                    .unwrap()
+                    .with_missed_runs(policy),
+            );
+
+            // The clock jumps ten seconds while the first run is pending, so
+            // it wakes at 00:00:11 with eleven run times due
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            clock.jump(Duration::seconds(10));
+            tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+            assert_eq!(runs.load(Ordering::SeqCst), expected, "{:?}", policy);
+
+            // Dropped times are not run later; the schedule carries on from now
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            assert_eq!(runs.load(Ordering::SeqCst), expected + 1, "{:?}", policy);
+        }
+    }
+}
@@ -5,26 +5,42 @@ use crate::nodes::config::NodeConfig;
 #[derive(Debug)]
 pub struct WorkflowSchema {
     pub workflow_type: String,
+    /// Version of this workflow definition; later versions compare greater
+    pub version: u32,
     pub description: Option<String>,
     pub start: TypeId,
     pub nodes: Vec<NodeConfig>,
+    /// JSON Schema the event data of every run must conform to
+    pub input_schema: Option<serde_json::Value>,
 }
 
 impl WorkflowSchema {
     pub fn new(workflow_type: String, start: TypeId) -> Self {
         Self {
             workflow_type,
+            version: 1,
             description: None,
             start,
             nodes: Vec::new(),
+            input_schema: None,
         }
     }
 
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
     pub fn with_description(mut self, description: String) -> Self {
         self.description = Some(description);
         self
     }
 
+    pub fn with_input_schema(mut self, input_schema: serde_json::Value) -> Self {
+        self.input_schema = Some(input_schema);
+        self
+    }
+
     pub fn with_nodes(mut self, nodes: Vec<NodeConfig>) -> Self {
         self.nodes = nodes;
         self
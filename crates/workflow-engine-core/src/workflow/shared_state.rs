@@ -0,0 +1,138 @@
+//! Shared state for coordinating nodes during a workflow run
+//!
+//! Each node, including every parallel branch, works on its own copy of the
+//! [`TaskContext`](crate::task::TaskContext), and branch results are only
+//! merged once all branches finish. [`SharedState`] is a single map that every
+//! node of a run sees live, for counters, caches and other coordination.
+
+#![allow(clippy::result_large_err, reason = "state access errors join the run's other WorkflowErrors")]
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::WorkflowError;
+
+/// Concurrency-safe key/value store shared by all nodes of a workflow.
+///
+/// Cloning a `SharedState` yields another handle to the same map.
+///
+/// ```rust
+/// use workflow_engine_core::workflow::shared_state::SharedState;
+/// use serde_json::json;
+///
+/// let state = SharedState::new();
+/// state.update("processed", |count| json!(count.and_then(|c| c.as_i64()).unwrap_or(0) + 1));
+/// assert_eq!(state.get::<i64>("processed").unwrap(), Some(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SharedState {
+    inner: Arc<RwLock<HashMap<String, Value>>>,
+}
+
+impl SharedState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A panicking node must not make the state unusable for the rest of the run
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Value>> {
+        self.inner.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Value>> {
+        self.inner.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn get<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<Option<T>, WorkflowError> {
+        match self.read().get(key) {
+            Some(value) => serde_json::from_value(value.clone())
+                .map(Some)
+                .map_err(|e| WorkflowError::DeserializationError {
+                    message: format!("Failed to deserialize shared state for {}: {}", key, e),
+                    expected_type: std::any::type_name::<T>().to_string(),
+                    context: format!("from shared state key '{}'", key),
+                    raw_data: Some(value.to_string()),
+                    source: Some(e),
+                }),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: T) -> Result<(), WorkflowError> {
+        let value = serde_json::to_value(value).map_err(|e| WorkflowError::SerializationError {
+            message: format!("Failed to serialize shared state for key {}: {}", key, e),
+            type_name: std::any::type_name::<T>().to_string(),
+            context: format!("for shared state key '{}'", key),
+            source: Some(e),
+        })?;
+        self.write().insert(key.to_string(), value);
+        Ok(())
+    }
+
+    pub fn remove(&self, key: &str) -> Option<Value> {
+        self.write().remove(key)
+    }
+
+    /// Atomically replace the value at `key` with `f(current value)`.
+    ///
+    /// No other node can modify the state while `f` runs, so read-modify-write
+    /// operations such as counters do not lose updates. Returns the new value.
+    pub fn update<F>(&self, key: &str, f: F) -> Value
+    where
+        F: FnOnce(Option<&Value>) -> Value,
+    {
+        let mut state = self.write();
+        let value = f(state.get(key));
+        state.insert(key.to_string(), value.clone());
+        value
+    }
+
+    /// Copy of the current contents
+    pub fn snapshot(&self) -> HashMap<String, Value> {
+        self.read().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_clones_share_the_same_map() {
+        let state = SharedState::new();
+        let handle = state.clone();
+
+        handle.set("cache", json!({ "user-1": "Ada" })).unwrap();
+        let cache: Value = state.get("cache").unwrap().unwrap();
+        assert_eq!(cache["user-1"], "Ada");
+
+        assert!(state.remove("cache").is_some());
+        assert!(handle.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let state = SharedState::new();
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        state.update("count", |count| {
+                            json!(count.and_then(Value::as_i64).unwrap_or(0) + 1)
+                        });
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(state.get::<i64>("count").unwrap(), Some(800));
+    }
+}
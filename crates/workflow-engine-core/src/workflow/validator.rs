@@ -2,6 +2,8 @@
 // Workflow Validator
 // =============================================================================
 
+#![allow(clippy::result_large_err, reason = "validation errors are WorkflowError variants callers match on")]
+
 use std::{
     any::TypeId,
     collections::{HashSet, VecDeque},
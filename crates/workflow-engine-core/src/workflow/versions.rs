@@ -0,0 +1,253 @@
+//! Registry of versioned workflows
+//!
+//! Workflows change over time, but executions that started on one version
+//! must finish on it. [`WorkflowVersions`] keeps every registered version of
+//! each named workflow. New runs use the latest version unless one is
+//! requested, while executions already in flight hold on to the version they
+//! started with.
+
+#![allow(clippy::result_large_err, reason = "version lookups fail the run with a WorkflowError like any other")]
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use serde_json::Value;
+
+use super::{Workflow, WORKFLOW_VERSION_KEY};
+use crate::error::WorkflowError;
+use crate::task::TaskContext;
+
+/// Holds multiple versions of named workflows.
+#[derive(Default)]
+pub struct WorkflowVersions {
+    workflows: RwLock<HashMap<String, BTreeMap<u32, Arc<Workflow>>>>,
+}
+
+impl WorkflowVersions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a workflow under its type and schema version.
+    ///
+    /// Versions are immutable once registered: registering the same version
+    /// twice is an error, so running executions never see their definition change.
+    pub fn register(&self, workflow: Workflow) -> Result<(), WorkflowError> {
+        let name = workflow.workflow_type().to_string();
+        let version = workflow.version();
+        let mut workflows = self.workflows.write().unwrap();
+        let versions = workflows.entry(name.clone()).or_default();
+        if versions.contains_key(&version) {
+            return Err(WorkflowError::registry_error(
+                format!("Workflow '{}' version {} is already registered", name, version),
+                "register",
+                "workflow",
+                Some(format!("{}@{}", name, version)),
+            ));
+        }
+        versions.insert(version, Arc::new(workflow));
+        Ok(())
+    }
+
+    /// Returns the requested version of a workflow, or the latest if `version` is `None`
+    pub fn get(&self, name: &str, version: Option<u32>) -> Result<Arc<Workflow>, WorkflowError> {
+        let workflows = self.workflows.read().unwrap();
+        let versions = workflows.get(name).ok_or_else(|| {
+            WorkflowError::registry_error(
+                format!("No workflow named '{}' is registered", name),
+                "lookup",
+                "workflow",
+                Some(name.to_string()),
+            )
+        })?;
+
+        let found = match version {
+            Some(version) => versions.get(&version),
+            None => versions.values().next_back(),
+        };
+        found.cloned().ok_or_else(|| {
+            WorkflowError::registry_error(
+                format!(
+                    "Workflow '{}' has no version {}; registered versions: {:?}",
+                    name,
+                    version.unwrap_or_default(),
+                    versions.keys().collect::<Vec<_>>()
+                ),
+                "lookup",
+                "workflow",
+                Some(name.to_string()),
+            )
+        })
+    }
+
+    /// Returns the version a previously started execution ran on.
+    ///
+    /// Use this to pick up a persisted execution on its original version,
+    /// even if newer versions have been registered since it started.
+    pub fn get_for_context(&self, context: &TaskContext) -> Result<Arc<Workflow>, WorkflowError> {
+        let version = context.get_metadata::<u32>(WORKFLOW_VERSION_KEY)?;
+        self.get(&context.workflow_type, version)
+    }
+
+    pub fn latest_version(&self, name: &str) -> Option<u32> {
+        let workflows = self.workflows.read().unwrap();
+        workflows.get(name)?.keys().next_back().copied()
+    }
+
+    /// Registered versions of a workflow, oldest first
+    pub fn versions(&self, name: &str) -> Vec<u32> {
+        let workflows = self.workflows.read().unwrap();
+        workflows
+            .get(name)
+            .map(|versions| versions.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Runs the requested version of a workflow, or the latest if `version` is `None`.
+    ///
+    /// The version is resolved once, up front; registering a newer version
+    /// while this run is in progress does not affect it.
+    pub fn run(
+        &self,
+        name: &str,
+        version: Option<u32>,
+        event_data: Value,
+    ) -> Result<TaskContext, WorkflowError> {
+        self.get(name, version)?.run(event_data)
+    }
+
+    /// Like [`run`](Self::run), under an overall deadline
+    pub fn run_with_deadline(
+        &self,
+        name: &str,
+        version: Option<u32>,
+        event_data: Value,
+        deadline: Instant,
+    ) -> Result<TaskContext, WorkflowError> {
+        self.get(name, version)?.run_with_deadline(event_data, deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::{config::NodeConfig, Node};
+    use crate::workflow::builder::WorkflowBuilder;
+    use serde_json::json;
+    use std::any::TypeId;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// Appends its name to the `sequence` node result
+    #[derive(Debug)]
+    struct Step<const ID: char>;
+
+    impl<const ID: char> Node for Step<ID> {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let mut sequence: Vec<String> = task_context.get_node_data("sequence")?.unwrap_or_default();
+            sequence.push(ID.to_string());
+            task_context.update_node("sequence", sequence);
+            Ok(task_context)
+        }
+    }
+
+    /// Blocks until signalled, so a run can be held in flight
+    #[derive(Debug)]
+    struct Gate(std::sync::Mutex<mpsc::Receiver<()>>);
+
+    impl Node for Gate {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            self.0.lock().unwrap().recv().unwrap();
+            Ok(task_context)
+        }
+    }
+
+    fn v1() -> Workflow {
+        let workflow = WorkflowBuilder::new::<Step<'a'>>("onboarding".to_string())
+            .version(1)
+            .add_node(NodeConfig::new::<Step<'a'>>().with_connections(vec![TypeId::of::<Step<'b'>>()]))
+            .add_node(NodeConfig::new::<Step<'b'>>())
+            .build()
+            .unwrap();
+        workflow.register_node(Step::<'a'>);
+        workflow.register_node(Step::<'b'>);
+        workflow
+    }
+
+    fn v2() -> Workflow {
+        let workflow = WorkflowBuilder::new::<Step<'a'>>("onboarding".to_string())
+            .version(2)
+            .add_node(NodeConfig::new::<Step<'a'>>().with_connections(vec![TypeId::of::<Step<'c'>>()]))
+            .add_node(NodeConfig::new::<Step<'c'>>())
+            .build()
+            .unwrap();
+        workflow.register_node(Step::<'a'>);
+        workflow.register_node(Step::<'c'>);
+        workflow
+    }
+
+    fn sequence(context: &TaskContext) -> Vec<String> {
+        context.get_node_data("sequence").unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_pinned_and_latest_versions() {
+        let registry = WorkflowVersions::new();
+        registry.register(v1()).unwrap();
+        registry.register(v2()).unwrap();
+        assert_eq!(registry.versions("onboarding"), vec![1, 2]);
+        assert_eq!(registry.latest_version("onboarding"), Some(2));
+
+        let pinned = registry.run("onboarding", Some(1), json!({})).unwrap();
+        assert_eq!(sequence(&pinned), ["a", "b"]);
+        assert_eq!(pinned.get_metadata::<u32>(WORKFLOW_VERSION_KEY).unwrap(), Some(1));
+
+        let latest = registry.run("onboarding", None, json!({})).unwrap();
+        assert_eq!(sequence(&latest), ["a", "c"]);
+        assert_eq!(latest.get_metadata::<u32>(WORKFLOW_VERSION_KEY).unwrap(), Some(2));
+
+        // A stored execution resumes on the version it started on
+        assert_eq!(registry.get_for_context(&pinned).unwrap().version(), 1);
+    }
+
+    #[test]
+    fn test_in_flight_run_keeps_its_version() {
+        let registry = Arc::new(WorkflowVersions::new());
+        let (release, gate) = mpsc::channel();
+        let workflow = WorkflowBuilder::new::<Gate>("onboarding".to_string())
+            .version(1)
+            .add_node(NodeConfig::new::<Gate>().with_connections(vec![TypeId::of::<Step<'b'>>()]))
+            .add_node(NodeConfig::new::<Step<'b'>>())
+            .build()
+            .unwrap();
+        workflow.register_node(Gate(std::sync::Mutex::new(gate)));
+        workflow.register_node(Step::<'b'>);
+        registry.register(workflow).unwrap();
+
+        let in_flight = {
+            let registry = Arc::clone(&registry);
+            std::thread::spawn(move || registry.run("onboarding", None, json!({})))
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        registry.register(v2()).unwrap();
+        release.send(()).unwrap();
+
+        let context = in_flight.join().unwrap().unwrap();
+        assert_eq!(sequence(&context), ["b"]);
+        assert_eq!(context.get_metadata::<u32>(WORKFLOW_VERSION_KEY).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_duplicate_and_unknown_versions_are_rejected() {
+        let registry = WorkflowVersions::new();
+        registry.register(v1()).unwrap();
+
+        assert!(matches!(registry.register(v1()), Err(WorkflowError::RegistryError { .. })));
+        assert!(matches!(
+            registry.run("onboarding", Some(3), json!({})),
+            Err(WorkflowError::RegistryError { .. })
+        ));
+        assert!(registry.get("missing", None).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Shared worker threads for running nodes under a deadline
+//!
+//! A node run with a deadline is handed to one of a fixed set of threads so
+//! the caller can stop waiting for it at the deadline. Runs never get a
+//! thread of their own: when every worker is busy, jobs queue until one
+//! frees up, so abandoned nodes can't pile up threads.
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use once_cell::sync::Lazy;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Workers used when the machine's parallelism can't be determined
+const DEFAULT_WORKERS: usize = 4;
+
+static POOL: Lazy<WorkerPool> = Lazy::new(|| {
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(DEFAULT_WORKERS)
+        .max(DEFAULT_WORKERS);
+    WorkerPool::new(workers)
+});
+
+/// Run `job` on the shared pool
+pub(crate) fn execute(job: impl FnOnce() + Send + 'static) {
+    POOL.execute(Box::new(job));
+}
+
+struct WorkerPool {
+    sender: Mutex<mpsc::Sender<Job>>,
+}
+
+impl WorkerPool {
+    fn new(workers: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..workers {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("workflow-node-{}", index))
+                .spawn(move || loop {
+                    let job = match receiver.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => return,
+                    };
+                    // A panicking node only fails its own run
+                    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                })
+                .expect("failed to spawn workflow worker thread");
+        }
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    fn execute(&self, job: Job) {
+        // Workers only exit once the sender is dropped, which never happens
+        // for the static pool
+        let _ = self.sender.lock().unwrap().send(job);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_runs_jobs_on_a_fixed_set_of_threads() {
+        let pool = WorkerPool::new(2);
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..8 {
+            let sender = sender.clone();
+            pool.execute(Box::new(move || {
+                thread::sleep(Duration::from_millis(10));
+                let _ = sender.send(thread::current().name().map(str::to_string));
+            }));
+        }
+        let mut names: Vec<_> = (0..8).map(|_| receiver.recv().unwrap().unwrap()).collect();
+        names.sort();
+        names.dedup();
+        assert!(names.len() <= 2);
+    }
+
+    #[test]
+    fn test_survives_a_panicking_job() {
+        let pool = WorkerPool::new(1);
+        pool.execute(Box::new(|| panic!("node panicked")));
+        let (sender, receiver) = mpsc::channel();
+        pool.execute(Box::new(move || sender.send(()).unwrap()));
+        assert!(receiver.recv_timeout(Duration::from_secs(1)).is_ok());
+    }
+}
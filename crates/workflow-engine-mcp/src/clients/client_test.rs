@@ -145,6 +145,7 @@ mod tests {
         let tool_def = ToolDefinition {
             name: "test_tool".to_string(),
             description: Some("A test tool".to_string()),
+            version: None,
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
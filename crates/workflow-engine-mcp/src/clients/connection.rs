@@ -46,21 +46,35 @@ impl McpConnection {
             .map(|id| id.to_string())
             .unwrap_or_else(|| Uuid::new_v4().to_string());
 
-        let (tx, rx) = tokio::sync::oneshot::channel();
+        let (tx, mut rx) = tokio::sync::oneshot::channel();
         {
             let mut pending = self.pending_requests.lock().await;
             pending.insert(id.clone(), tx);
         }
 
-        self.transport.send(request).await?;
+        if let Err(e) = self.transport.send(request).await {
+            self.pending_requests.lock().await.remove(&id);
+            return Err(e.into());
+        }
+
+        // Read responses off the transport until the one for this request arrives
+        loop {
+            match rx.try_recv() {
+                Ok(response) => return Ok(response),
+                Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
+                    return Err(WorkflowError::mcp_error(
+                        "Request timeout or connection closed",
+                        "connection_client",
+                        "send_request"
+                    ));
+                }
+                Err(tokio::sync::oneshot::error::TryRecvError::Empty) => {}
+            }
 
-        match rx.await {
-            Ok(response) => Ok(response),
-            Err(_) => Err(WorkflowError::mcp_error(
-                "Request timeout or connection closed",
-                "connection_client",
-                "send_request"
-            )),
+            if let Err(e) = self.receive_response().await {
+                self.pending_requests.lock().await.remove(&id);
+                return Err(e);
+            }
         }
     }
 
@@ -200,6 +200,7 @@ impl McpClient for HttpMcpClient {
             params: ToolCallParams {
                 name: name.to_string(),
                 arguments,
+                meta: None,
             },
         };
 
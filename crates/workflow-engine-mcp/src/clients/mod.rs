@@ -3,17 +3,22 @@ use std::collections::HashMap;
 
 pub mod connection;
 pub mod http;
+pub mod rate_limited;
 pub mod stdio;
 pub mod websocket;
 
 pub use connection::McpConnection;
 pub use http::HttpMcpClient;
+pub use rate_limited::{RateLimitConfig, RateLimitedClient, RateLimiter};
 pub use stdio::StdioMcpClient;
 pub use websocket::WebSocketMcpClient;
 
 use workflow_engine_core::error::WorkflowError;
 use crate::protocol::{CallToolResult, ToolDefinition};
 
+/// A tool name and its arguments, as passed to [`McpClient::call_tools_batch`]
+pub type ToolCall = (String, Option<HashMap<String, serde_json::Value>>);
+
 #[async_trait]
 pub trait McpClient: Send + Sync + std::fmt::Debug {
     async fn connect(&mut self) -> Result<(), WorkflowError>;
@@ -30,4 +35,19 @@ pub trait McpClient: Send + Sync + std::fmt::Debug {
     ) -> Result<CallToolResult, WorkflowError>;
     async fn disconnect(&mut self) -> Result<(), WorkflowError>;
     fn is_connected(&self) -> bool;
+
+    /// Call several tools, returning one result per call in order.
+    ///
+    /// Clients whose server accepts batched calls override this to use a
+    /// single round-trip; the default calls the tools one at a time.
+    async fn call_tools_batch(
+        &mut self,
+        calls: Vec<ToolCall>,
+    ) -> Vec<Result<CallToolResult, WorkflowError>> {
+        let mut results = Vec::with_capacity(calls.len());
+        for (name, arguments) in calls {
+            results.push(self.call_tool(&name, arguments).await);
+        }
+        results
+    }
 }
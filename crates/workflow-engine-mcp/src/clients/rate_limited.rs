@@ -0,0 +1,357 @@
+//! Client-side rate limiting for MCP tool calls
+//!
+//! [`RateLimitedClient`] wraps another [`McpClient`] and paces its tool calls
+//! through a token bucket, so a server with a strict request quota is never
+//! sent more than it allows. Calls the server still rejects as rate limited
+//! are retried with exponential backoff. Clients talking to the same server
+//! should share one [`RateLimiter`] so the quota covers all of them.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Instant};
+
+use workflow_engine_core::error::{RetryPolicy, WorkflowError};
+use crate::protocol::{CallToolResult, ToolDefinition};
+use super::McpClient;
+
+/// Request rate allowed for one server
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Sustained tool calls per second
+    pub requests_per_second: f64,
+    /// Calls that may be made at once after a quiet period
+    pub burst: u32,
+    /// Backoff for calls the server rejects as rate limited
+    pub retry: RetryPolicy,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 10.0,
+            burst: 1,
+            retry: RetryPolicy {
+                initial_delay: Duration::from_millis(500),
+                ..RetryPolicy::exponential(3)
+            },
+        }
+    }
+}
+
+/// Lowest rate a limiter paces calls at. Zero, negative and NaN rates are
+/// raised to it, so a misconfigured server is slowed to a crawl rather than
+/// stalling forever or panicking.
+pub const MIN_REQUESTS_PER_SECOND: f64 = 0.001;
+
+fn clamp_rate(requests_per_second: f64) -> f64 {
+    if requests_per_second >= MIN_REQUESTS_PER_SECOND {
+        requests_per_second
+    } else {
+        MIN_REQUESTS_PER_SECOND
+    }
+}
+
+impl RateLimitConfig {
+    /// A config allowing `requests_per_second`, raised to
+    /// [`MIN_REQUESTS_PER_SECOND`] if lower
+    pub fn new(requests_per_second: f64) -> Self {
+        Self {
+            requests_per_second: clamp_rate(requests_per_second),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// A token bucket pacing the calls to one server.
+///
+/// Cloning yields another handle to the same bucket.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// A limiter for `config`, its rate raised to
+    /// [`MIN_REQUESTS_PER_SECOND`] if lower
+    pub fn new(mut config: RateLimitConfig) -> Self {
+        config.requests_per_second = clamp_rate(config.requests_per_second);
+        let bucket = TokenBucket {
+            tokens: config.burst as f64,
+            refilled_at: Instant::now(),
+        };
+        Self {
+            config: Arc::new(config),
+            bucket: Arc::new(Mutex::new(bucket)),
+        }
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Wait until a call may be made and take its token. Waiters are served
+    /// in turn, as the bucket stays locked while one of them sleeps.
+    pub async fn acquire(&self) {
+        let mut bucket = self.bucket.lock().await;
+        let rate = self.config.requests_per_second;
+        let capacity = self.config.burst as f64;
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens < 1.0 {
+            let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / rate);
+            sleep(wait).await;
+            bucket.tokens = 1.0;
+            bucket.refilled_at = Instant::now();
+        }
+        bucket.tokens -= 1.0;
+    }
+}
+
+/// Whether `error` is a server refusing a call for exceeding its rate limit
+pub fn is_rate_limited(error: &WorkflowError) -> bool {
+    if let WorkflowError::ApiError { status_code: Some(429), .. } = error {
+        return true;
+    }
+    let message = error.to_string().to_lowercase();
+    message.contains("rate limit") || message.contains("too many requests")
+}
+
+/// An [`McpClient`] whose tool calls are paced by a [`RateLimiter`] and
+/// retried when rate limited
+#[derive(Debug)]
+pub struct RateLimitedClient<C> {
+    inner: C,
+    limiter: RateLimiter,
+}
+
+impl<C: McpClient> RateLimitedClient<C> {
+    pub fn new(inner: C, config: RateLimitConfig) -> Self {
+        Self::with_limiter(inner, RateLimiter::new(config))
+    }
+
+    /// Pace `inner` with a limiter shared with other clients of its server
+    pub fn with_limiter(inner: C, limiter: RateLimiter) -> Self {
+        Self { inner, limiter }
+    }
+
+    pub fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<C: McpClient> McpClient for RateLimitedClient<C> {
+    async fn connect(&mut self) -> Result<(), WorkflowError> {
+        self.inner.connect().await
+    }
+
+    async fn initialize(
+        &mut self,
+        client_name: &str,
+        client_version: &str,
+    ) -> Result<(), WorkflowError> {
+        self.inner.initialize(client_name, client_version).await
+    }
+
+    async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError> {
+        self.inner.list_tools().await
+    }
+
+    async fn call_tool(
+        &mut self,
+        name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let retry = self.limiter.config.retry.clone();
+        let mut attempt = 0;
+        loop {
+            self.limiter.acquire().await;
+            match self.inner.call_tool(name, arguments.clone()).await {
+                Err(error) if is_rate_limited(&error) && attempt < retry.max_attempts => {
+                    attempt += 1;
+                    let delay = retry.calculate_delay(attempt);
+                    log::warn!(
+                        "Tool call {} was rate limited, retrying in {}ms (attempt {}/{})",
+                        name,
+                        delay.as_millis(),
+                        attempt,
+                        retry.max_attempts
+                    );
+                    sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn disconnect(&mut self) -> Result<(), WorkflowError> {
+        self.inner.disconnect().await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ToolContent;
+
+    /// Records when each call arrives and rejects the first `rate_limited`
+    /// of them with a 429
+    #[derive(Debug, Default)]
+    struct RecordingClient {
+        calls: Vec<Instant>,
+        rate_limited: usize,
+    }
+
+    #[async_trait]
+    impl McpClient for RecordingClient {
+        async fn connect(&mut self) -> Result<(), WorkflowError> {
+            Ok(())
+        }
+
+        async fn initialize(&mut self, _: &str, _: &str) -> Result<(), WorkflowError> {
+            Ok(())
+        }
+
+        async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError> {
+            Ok(Vec::new())
+        }
+
+        async fn call_tool(
+            &mut self,
+            name: &str,
+            _arguments: Option<HashMap<String, serde_json::Value>>,
+        ) -> Result<CallToolResult, WorkflowError> {
+            self.calls.push(Instant::now());
+            if self.calls.len() <= self.rate_limited {
+                return Err(WorkflowError::api_error("Too many requests", "search", name, Some(429)));
+            }
+            Ok(CallToolResult {
+                content: vec![ToolContent::Text { text: "ok".to_string() }],
+                is_error: None,
+            })
+        }
+
+        async fn disconnect(&mut self) -> Result<(), WorkflowError> {
+            Ok(())
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+    }
+
+    fn gaps(calls: &[Instant]) -> Vec<Duration> {
+        calls.windows(2).map(|pair| pair[1] - pair[0]).collect()
+    }
+
+    #[tokio::test]
+    async fn test_calls_faster_than_the_rate_are_paced() {
+        let mut client = RateLimitedClient::new(
+            RecordingClient::default(),
+            RateLimitConfig::new(20.0).with_burst(2),
+        );
+        for _ in 0..5 {
+            client.call_tool("search", None).await.unwrap();
+        }
+
+        let calls = client.into_inner().calls;
+        let gaps = gaps(&calls);
+        // The burst goes out at once, the rest one every 50ms
+        assert!(gaps[0] < Duration::from_millis(25), "{:?}", gaps);
+        for gap in &gaps[1..] {
+            assert!(*gap >= Duration::from_millis(45), "{:?}", gaps);
+        }
+        assert!(calls[4] - calls[0] >= Duration::from_millis(145));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_calls_are_retried_with_backoff() {
+        let config = RateLimitConfig::new(1000.0).with_retry_policy(RetryPolicy {
+            initial_delay: Duration::from_millis(40),
+            jitter_factor: 0.0,
+            ..RetryPolicy::exponential(3)
+        });
+        let mut client = RateLimitedClient::new(
+            RecordingClient {
+                rate_limited: 2,
+                ..Default::default()
+            },
+            config.clone(),
+        );
+
+        let result = client.call_tool("search", None).await.unwrap();
+        assert!(matches!(&result.content[0], ToolContent::Text { text } if text == "ok"));
+        let gaps = gaps(&client.into_inner().calls);
+        assert_eq!(gaps.len(), 2);
+        assert!(gaps[0] >= Duration::from_millis(40), "{:?}", gaps);
+        assert!(gaps[1] >= Duration::from_millis(80), "{:?}", gaps);
+
+        // Rejections past the retry budget are returned
+        let mut client = RateLimitedClient::new(
+            RecordingClient {
+                rate_limited: 10,
+                ..Default::default()
+            },
+            config,
+        );
+        let error = client.call_tool("search", None).await.unwrap_err();
+        assert!(is_rate_limited(&error));
+        assert_eq!(client.into_inner().calls.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_rates_are_raised_to_the_minimum() {
+        for rate in [0.0, -5.0, f64::NAN] {
+            assert_eq!(RateLimitConfig::new(rate).requests_per_second, MIN_REQUESTS_PER_SECOND);
+
+            let limiter = RateLimiter::new(RateLimitConfig {
+                requests_per_second: rate,
+                ..Default::default()
+            });
+            assert_eq!(limiter.config().requests_per_second, MIN_REQUESTS_PER_SECOND);
+            limiter.acquire().await;
+            // The next token is far off, but waiting for it doesn't panic
+            let next = tokio::time::timeout(Duration::from_millis(10), limiter.acquire()).await;
+            assert!(next.is_err());
+        }
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        assert!(is_rate_limited(&WorkflowError::api_error("slow down", "search", "/call", Some(429))));
+        assert!(is_rate_limited(&WorkflowError::mcp_error("Rate limit exceeded", "search", "call_tool")));
+        assert!(!is_rate_limited(&WorkflowError::api_error("bad gateway", "search", "/call", Some(502))));
+    }
+}
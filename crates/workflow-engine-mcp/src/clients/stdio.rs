@@ -16,6 +16,7 @@ pub struct StdioMcpClient {
     connection: Option<McpConnection>,
     command: String,
     args: Vec<String>,
+    env: HashMap<String, String>,
 }
 
 impl StdioMcpClient {
@@ -24,14 +25,23 @@ impl StdioMcpClient {
             connection: None,
             command,
             args,
+            env: HashMap::new(),
         }
     }
+
+    /// Set environment variables for the server process
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
 }
 
 #[async_trait]
 impl McpClient for StdioMcpClient {
     async fn connect(&mut self) -> Result<(), WorkflowError> {
-        let transport = Box::new(StdioTransport::new(self.command.clone(), self.args.clone()));
+        let transport = Box::new(
+            StdioTransport::new(self.command.clone(), self.args.clone()).with_env(self.env.clone()),
+        );
         let mut connection = McpConnection::new(transport);
 
         connection.transport.connect().await?;
@@ -184,6 +194,7 @@ impl McpClient for StdioMcpClient {
             params: ToolCallParams {
                 name: name.to_string(),
                 arguments,
+                meta: None,
             },
         };
 
@@ -182,6 +182,7 @@ impl McpClient for WebSocketMcpClient {
             params: ToolCallParams {
                 name: name.to_string(),
                 arguments,
+                meta: None,
             },
         };
 
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tokio::time::{sleep, timeout, interval};
 use rand::seq::SliceRandom;
 use serde::{Serialize, Deserialize};
@@ -24,8 +24,10 @@ pub struct BorrowedConnection {
 
 impl BorrowedConnection {
     pub async fn list_tools(&self) -> Result<Vec<crate::protocol::ToolDefinition>, WorkflowError> {
-        let mut client = self.client.write().await;
-        client.list_tools().await
+        let breaker = self.pool.circuit_breakers.get(&self.server_id).await;
+        breaker
+            .call(|| async { self.client.write().await.list_tools().await })
+            .await
     }
 
     pub async fn call_tool(
@@ -33,8 +35,6 @@ impl BorrowedConnection {
         name: &str,
         args: serde_json::Value,
     ) -> Result<crate::protocol::McpResponse, WorkflowError> {
-        let mut client = self.client.write().await;
-        
         // Convert args to expected format
         let args_map = if args.is_null() {
             None
@@ -44,7 +44,11 @@ impl BorrowedConnection {
             Some(HashMap::from([("value".to_string(), args)]))
         };
         
-        let result = client.call_tool(name, args_map).await?;
+        // Failed calls count towards opening the server's circuit
+        let breaker = self.pool.circuit_breakers.get(&self.server_id).await;
+        let result = breaker
+            .call(|| async { self.client.write().await.call_tool(name, args_map).await })
+            .await?;
         
         // Convert CallToolResult to McpResponse
         Ok(crate::protocol::McpResponse::Result {
@@ -252,6 +256,16 @@ impl PooledConnection {
         is_healthy && !is_busy && is_connected
     }
 
+    /// Mark the connection as in use, unless another borrower got there first
+    async fn try_acquire(&self) -> bool {
+        let mut in_use = self.in_use.write().await;
+        if *in_use {
+            return false;
+        }
+        *in_use = true;
+        true
+    }
+
     async fn get_use_count(&self) -> u64 {
         *self.use_count.read().await
     }
@@ -267,6 +281,15 @@ pub struct McpConnectionPool {
     load_balancer: Arc<RwLock<McpLoadBalancer>>,
     metrics_collector: Option<Arc<MCPMetricsCollector>>,
     background_tasks: Arc<RwLock<Vec<tokio::task::JoinHandle<()>>>>,
+    creation_lock: Arc<Mutex<()>>,
+}
+
+impl std::fmt::Debug for McpConnectionPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McpConnectionPool")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
 }
 
 impl McpConnectionPool {
@@ -284,6 +307,7 @@ impl McpConnectionPool {
             load_balancer,
             metrics_collector: None,
             background_tasks: Arc::new(RwLock::new(Vec::new())),
+            creation_lock: Arc::new(Mutex::new(())),
         }
     }
 
@@ -353,13 +377,42 @@ impl McpConnectionPool {
         &self,
         server_id: &str,
     ) -> Result<BorrowedConnection, WorkflowError> {
-        // Try to get an existing connection first
-        if let Some(borrowed_conn) = self.try_get_existing_connection(server_id).await? {
-            return Ok(borrowed_conn);
+        let deadline = Instant::now() + self.config.connection_timeout;
+
+        loop {
+            // Try to get an existing connection first
+            if let Some(borrowed_conn) = self.try_get_existing_connection(server_id).await? {
+                return Ok(borrowed_conn);
+            }
+
+            // Create a new connection if the pool has room. Creation is serialized so
+            // concurrent borrowers cannot overshoot the per-server limit.
+            {
+                let _creating = self.creation_lock.lock().await;
+                if self.connection_count(server_id).await < self.config.max_connections_per_server {
+                    let breaker = self.circuit_breakers.get(server_id).await;
+                    return breaker
+                        .call(|| self.create_connection_with_retry(server_id))
+                        .await;
+                }
+            }
+
+            // Otherwise wait for a borrowed connection to be returned
+            if Instant::now() >= deadline {
+                return Err(WorkflowError::mcp_connection_error(
+                    format!("Timed out waiting for a pooled connection to {}", server_id),
+                    server_id,
+                    "pool",
+                    server_id,
+                ));
+            }
+            sleep(Duration::from_millis(10)).await;
         }
+    }
 
-        // Create a new connection with retry logic
-        self.create_connection_with_retry(server_id).await
+    async fn connection_count(&self, server_id: &str) -> usize {
+        let connections = self.connections.read().await;
+        connections.get(server_id).map_or(0, Vec::len)
     }
 
     async fn try_get_existing_connection(
@@ -396,8 +449,7 @@ impl McpConnectionPool {
             if let Some(selected_id) = load_balancer.select_connection(server_id, &connection_infos).await? {
                 // Find the selected connection and mark it as in use
                 for conn in pool.iter() {
-                    if conn.connection_id == selected_id && conn.is_available().await {
-                        conn.set_in_use(true).await;
+                    if conn.connection_id == selected_id && conn.is_available().await && conn.try_acquire().await {
                         conn.touch().await;
                         
                         return Ok(Some(BorrowedConnection {
@@ -442,19 +494,8 @@ impl McpConnectionPool {
                     let latency = start_time.elapsed();
                     self.record_connection_request(true, latency);
                     
-                    // Create a new connection for the pool
-                    let pool_client = self
-                        .create_single_connection(&transport, &client_name, &client_version)
-                        .await?;
-                    let connection_id = self.add_to_pool(server_id, pool_client, &transport).await?;
-                    
-                    // Start monitoring the new connection
-                    self.health_monitor
-                        .start_monitoring(connection_id.clone(), server_id.to_string())
-                        .await;
-                    
-                    // Return a borrowed connection for the new client
-                    return self.create_borrowed_connection(server_id, client).await;
+                    // Add the new client to the pool and borrow it
+                    return self.create_borrowed_connection(server_id, client, &transport).await;
                 }
                 Err(error) => {
                     let latency = start_time.elapsed();
@@ -585,10 +626,14 @@ impl McpConnectionPool {
         &self,
         server_id: &str,
         client: Box<dyn McpClient>,
+        transport: &TransportType,
     ) -> Result<BorrowedConnection, WorkflowError> {
-        // Create a new connection for the pool
-        let pool_client = self.create_single_connection_from_server(server_id).await?;
-        let connection_id = self.add_to_pool(server_id, pool_client, &self.get_transport_for_server(server_id).await?).await?;
+        let connection_id = self.add_to_pool(server_id, client, transport).await?;
+
+        // Start monitoring the new connection
+        self.health_monitor
+            .start_monitoring(connection_id.clone(), server_id.to_string())
+            .await;
         
         // Create the borrowed connection
         let connections = self.connections.read().await;
@@ -596,6 +641,7 @@ impl McpConnectionPool {
             for conn in pool.iter() {
                 if conn.connection_id == connection_id {
                     conn.set_in_use(true).await;
+                    conn.touch().await;
                     return Ok(BorrowedConnection {
                         client: Arc::clone(&conn.client),
                         connection_id: conn.connection_id.clone(),
@@ -613,36 +659,6 @@ impl McpConnectionPool {
         ))
     }
     
-    /// Get transport type for a server
-    async fn get_transport_for_server(&self, server_id: &str) -> Result<TransportType, WorkflowError> {
-        let configs = self.server_configs.read().await;
-        let (transport, _, _) = configs
-            .get(server_id)
-            .ok_or_else(|| WorkflowError::mcp_error(
-                format!("Server {} not registered", server_id),
-                server_id,
-                "get_transport"
-            ))?
-            .clone();
-        Ok(transport)
-    }
-    
-    /// Create a single connection for a registered server
-    async fn create_single_connection_from_server(&self, server_id: &str) -> Result<Box<dyn McpClient>, WorkflowError> {
-        let configs = self.server_configs.read().await;
-        let (transport, client_name, client_version) = configs
-            .get(server_id)
-            .ok_or_else(|| WorkflowError::mcp_error(
-                format!("Server {} not registered", server_id),
-                server_id,
-                "create_connection"
-            ))?
-            .clone();
-        drop(configs);
-        
-        self.create_single_connection(&transport, &client_name, &client_version).await
-    }
-
     pub async fn health_check(&self) -> Result<HashMap<String, bool>, WorkflowError> {
         let mut results = HashMap::new();
         let connections = self.connections.read().await;
@@ -933,6 +949,11 @@ impl McpConnectionPool {
         Ok(())
     }
 
+    /// The circuit breakers of the pool's servers, by server id
+    pub fn circuit_breakers(&self) -> Arc<CircuitBreakerRegistry> {
+        Arc::clone(&self.circuit_breakers)
+    }
+
     /// Get circuit breaker metrics for all servers
     pub async fn get_circuit_breaker_metrics(&self) -> HashMap<String, workflow_engine_core::error::circuit_breaker::CircuitBreakerMetrics> {
         let mut metrics = HashMap::new();
@@ -214,6 +214,7 @@
 //!         model_provider: ModelProvider::Anthropic,
 //!         model_name: "claude-3-sonnet-20240229".to_string(),
 //!         mcp_server_uri: Some("ws://localhost:8080/tools".to_string()),
+//!         ai_fallback_template: None,
 //!     };
 //!
 //!     let mut agent = AnthropicAgentNode::new(agent_config);
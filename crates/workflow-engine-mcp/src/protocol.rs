@@ -18,6 +18,24 @@ pub enum McpRequest {
         id: String,
         params: ToolCallParams,
     },
+    #[serde(rename = "resources/list")]
+    ListResources {
+        id: String,
+    },
+    #[serde(rename = "resources/read")]
+    ReadResource {
+        id: String,
+        params: ReadResourceParams,
+    },
+    #[serde(rename = "prompts/list")]
+    ListPrompts {
+        id: String,
+    },
+    #[serde(rename = "prompts/get")]
+    GetPrompt {
+        id: String,
+        params: GetPromptParams,
+    },
     #[serde(rename = "notifications/initialized")]
     Initialized,
 }
@@ -43,6 +61,10 @@ pub enum ResponseResult {
     Initialize(InitializeResult),
     ListTools(ListToolsResult),
     CallTool(CallToolResult),
+    ListResources(ListResourcesResult),
+    ReadResource(ReadResourceResult),
+    ListPrompts(ListPromptsResult),
+    GetPrompt(GetPromptResult),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,12 +144,32 @@ pub struct ToolDefinition {
     pub name: String,
     pub description: Option<String>,
     pub input_schema: serde_json::Value,
+    /// Semantic version of the tool, when the server versions its tools
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCallParams {
     pub name: String,
     pub arguments: Option<HashMap<String, serde_json::Value>>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<RequestMeta>,
+}
+
+/// Request metadata carried in `_meta` alongside tool call parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestMeta {
+    /// Key identifying retries of the same logical call; servers with
+    /// idempotency enabled return the first call's result instead of re-running the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// Token echoed back in `notifications/progress` messages for this call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress_token: Option<String>,
+    /// Semver range (e.g. `^1`) of tool versions the client accepts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -153,6 +195,91 @@ pub struct ResourceReference {
     pub mime_type: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceDefinition {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourcesResult {
+    pub resources: Vec<ResourceDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadResourceParams {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceContents {
+    pub uri: String,
+    pub mime_type: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadResourceResult {
+    pub contents: Vec<ResourceContents>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptDefinition {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListPromptsResult {
+    pub prompts: Vec<PromptDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptParams {
+    pub name: String,
+    pub arguments: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    pub role: String,
+    pub content: ToolContent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+/// Server-initiated messages that do not expect a response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method")]
+pub enum McpNotification {
+    #[serde(rename = "notifications/progress")]
+    Progress { params: ProgressNotificationParams },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressNotificationParams {
+    pub progress_token: String,
+    pub progress: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpError {
     pub code: i32,
@@ -179,6 +306,10 @@ impl McpRequest {
             McpRequest::Initialize { id, .. } => Some(id),
             McpRequest::ListTools { id } => Some(id),
             McpRequest::CallTool { id, .. } => Some(id),
+            McpRequest::ListResources { id } => Some(id),
+            McpRequest::ReadResource { id, .. } => Some(id),
+            McpRequest::ListPrompts { id } => Some(id),
+            McpRequest::GetPrompt { id, .. } => Some(id),
             McpRequest::Initialized => None,
         }
     }
@@ -265,6 +396,7 @@ mod tests {
             params: ToolCallParams {
                 name: "analyze_text".to_string(),
                 arguments: Some(arguments),
+                meta: None,
             },
         };
 
@@ -520,6 +652,7 @@ mod tests {
                 params: ToolCallParams {
                     name: "test_tool".to_string(),
                     arguments: None,
+                    meta: None,
                 },
             },
             McpRequest::Initialized,
@@ -546,6 +679,7 @@ mod tests {
         let tool_def = ToolDefinition {
             name: "test_tool".to_string(),
             description: Some("A test tool".to_string()),
+            version: None,
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -660,6 +794,7 @@ mod tests {
         let tool = ToolDefinition {
             name: "test_tool".to_string(),
             description: None,
+            version: None,
             input_schema: json!({}),
         };
 
@@ -673,6 +808,7 @@ mod tests {
         let full_tool = ToolDefinition {
             name: "complex_tool".to_string(),
             description: Some("A complex tool with schema".to_string()),
+            version: None,
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -772,6 +908,7 @@ mod tests {
         let params = ToolCallParams {
             name: "complex_tool".to_string(),
             arguments: Some(complex_args.clone()),
+            meta: None,
         };
 
         let serialized = serde_json::to_string(&params).unwrap();
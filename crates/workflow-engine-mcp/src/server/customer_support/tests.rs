@@ -2,6 +2,7 @@
 mod tests {
     use super::super::server::CustomerSupportMcpServer;
     use crate::protocol::McpRequest;
+    use crate::server::McpSession;
 
     #[tokio::test]
     async fn test_customer_support_server_creation() {
@@ -38,7 +39,12 @@ mod tests {
             id: "test-123".to_string(),
         };
 
-        let response = server.get_server().handle_request(request).await.unwrap();
+        let response = server
+            .get_server()
+            .handle_request(&McpSession::new(), request)
+            .await
+            .unwrap()
+            .unwrap();
         
         match response {
             crate::protocol::McpResponse::Result { 
@@ -37,6 +37,7 @@ impl AnalyzeTicketNode {
                     }
                 }),
                 node_type: TypeId::of::<AnalyzeTicketNode>(),
+                version: ToolMetadata::default_version(),
             },
         }
     }
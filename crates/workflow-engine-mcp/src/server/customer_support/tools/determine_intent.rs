@@ -53,6 +53,7 @@ impl DetermineTicketIntentNode {
                     }
                 }),
                 node_type: TypeId::of::<DetermineTicketIntentNode>(),
+                version: ToolMetadata::default_version(),
             },
         }
     }
@@ -55,6 +55,7 @@ impl GenerateCustomerResponseNode {
                     }
                 }),
                 node_type: TypeId::of::<GenerateCustomerResponseNode>(),
+                version: ToolMetadata::default_version(),
             },
         }
     }
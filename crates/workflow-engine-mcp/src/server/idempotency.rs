@@ -0,0 +1,96 @@
+//! Idempotent tool execution for [`McpToolServer`](super::McpToolServer).
+//!
+//! Tool calls carrying an idempotency key share a single execution: retries
+//! that arrive while the first call is in flight wait for it, and retries that
+//! arrive afterwards receive the cached result until the retention window
+//! expires.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::protocol::CallToolResult;
+use workflow_engine_core::error::WorkflowError;
+
+struct IdempotencyEntry {
+    result: Arc<OnceCell<CallToolResult>>,
+    created_at: Instant,
+}
+
+/// Outcome of an execution that is not cached
+enum Uncached {
+    Failed(WorkflowError),
+    /// A result flagged `is_error`
+    ErrorResult(CallToolResult),
+}
+
+/// Cache of in-flight and completed tool calls keyed by idempotency key
+pub struct IdempotencyCache {
+    retention: Duration,
+    entries: Mutex<HashMap<String, IdempotencyEntry>>,
+}
+
+impl IdempotencyCache {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `execute` at most once per `(tool_name, key)` within the retention window.
+    ///
+    /// Failed executions, including results flagged `is_error`, are not
+    /// cached, so a later retry runs the tool again.
+    pub async fn execute<F, Fut>(
+        &self,
+        tool_name: &str,
+        key: &str,
+        execute: F,
+    ) -> Result<CallToolResult, WorkflowError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<CallToolResult, WorkflowError>>,
+    {
+        let cell = {
+            let mut entries = self.entries.lock().await;
+            let retention = self.retention;
+            entries.retain(|_, entry| entry.created_at.elapsed() < retention);
+
+            entries
+                .entry(format!("{}:{}", tool_name, key))
+                .or_insert_with(|| IdempotencyEntry {
+                    result: Arc::new(OnceCell::new()),
+                    created_at: Instant::now(),
+                })
+                .result
+                .clone()
+        };
+
+        let outcome = cell
+            .get_or_try_init(|| async {
+                match execute().await {
+                    Ok(result) if result.is_error == Some(true) => Err(Uncached::ErrorResult(result)),
+                    Ok(result) => Ok(result),
+                    Err(error) => Err(Uncached::Failed(error)),
+                }
+            })
+            .await;
+        match outcome {
+            Ok(result) => Ok(result.clone()),
+            Err(Uncached::ErrorResult(result)) => Ok(result),
+            Err(Uncached::Failed(error)) => Err(error),
+        }
+    }
+
+    /// Number of tracked keys, including expired entries not yet purged
+    pub async fn len(&self) -> usize {
+        self.entries.lock().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
@@ -1,26 +1,37 @@
+use semver::{Version, VersionReq};
 use serde_json;
 use std::any::TypeId;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
 
 use workflow_engine_core::error::WorkflowError;
 use crate::protocol::{
-    CallToolResult, InitializeResult, ListToolsResult, McpError, McpRequest, McpResponse,
+    CallToolResult, GetPromptParams, GetPromptResult, InitializeResult, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, McpError, McpNotification, McpRequest, McpResponse,
+    ProgressNotificationParams, PromptArgument, PromptDefinition, PromptMessage, ReadResourceResult, ResourceContents, ResourceDefinition,
     ResponseResult, ServerCapabilities, ServerInfo, ToolContent, ToolDefinition,
 };
+use workflow_engine_core::ai::templates::{TemplateManager, VariableType};
+use workflow_engine_core::nodes::progress::{ProgressReporter, ProgressUpdate};
 use workflow_engine_core::nodes::Node;
 use workflow_engine_core::task::TaskContext;
 
 pub mod customer_support;
+pub mod idempotency;
 pub mod knowledge_base;
 
+use idempotency::IdempotencyCache;
+
 #[derive(Debug, Clone)]
 pub struct ToolMetadata {
     pub name: String,
     pub description: String,
     pub input_schema: serde_json::Value,
     pub node_type: TypeId,
+    pub version: Version,
 }
 
 impl ToolMetadata {
@@ -35,23 +46,62 @@ impl ToolMetadata {
             description,
             input_schema,
             node_type,
+            version: Self::default_version(),
         }
     }
 
+    /// Version assigned to tools registered without an explicit version
+    pub fn default_version() -> Version {
+        Version::new(1, 0, 0)
+    }
+
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
     pub fn to_tool_definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: self.name.clone(),
             description: Some(self.description.clone()),
             input_schema: self.input_schema.clone(),
+            version: Some(self.version.to_string()),
         }
     }
 }
 
+/// Registered versions of a tool, sorted by ascending version
+type ToolVersions = Vec<(ToolMetadata, Arc<dyn Node>)>;
+
+/// One client connection to an [`McpToolServer`].
+///
+/// The MCP handshake is per connection: each client must send
+/// `notifications/initialized` on its own session before calling tools.
+/// Clones share the same session.
+#[derive(Debug, Clone, Default)]
+pub struct McpSession {
+    initialized: Arc<AtomicBool>,
+}
+
+impl McpSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the client has sent the `notifications/initialized` notification
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+}
+
 pub struct McpToolServer {
     server_name: String,
     server_version: String,
-    tools: Arc<RwLock<HashMap<String, (ToolMetadata, Arc<dyn Node>)>>>,
+    tools: Arc<RwLock<HashMap<String, ToolVersions>>>,
+    resources: Arc<RwLock<HashMap<String, (ResourceDefinition, String)>>>,
+    prompt_templates: Option<Arc<TemplateManager>>,
     capabilities: ServerCapabilities,
+    idempotency: Option<IdempotencyCache>,
 }
 
 impl McpToolServer {
@@ -60,17 +110,57 @@ impl McpToolServer {
             server_name,
             server_version,
             tools: Arc::new(RwLock::new(HashMap::new())),
+            resources: Arc::new(RwLock::new(HashMap::new())),
+            prompt_templates: None,
             capabilities: ServerCapabilities {
                 logging: None,
                 prompts: None,
-                resources: None,
+                resources: Some(crate::protocol::ResourcesCapability {
+                    subscribe: Some(false),
+                    list_changed: Some(true),
+                }),
                 tools: Some(crate::protocol::ToolsCapability {
                     list_changed: Some(true),
                 }),
             },
+            idempotency: None,
         }
     }
 
+    /// Expose the templates of a [`TemplateManager`] as MCP prompts
+    pub fn with_prompts(mut self, templates: Arc<TemplateManager>) -> Self {
+        self.prompt_templates = Some(templates);
+        self.capabilities.prompts = Some(crate::protocol::PromptsCapability {
+            list_changed: Some(false),
+        });
+        self
+    }
+
+    /// Register read-only text content served through `resources/read`
+    pub async fn register_resource(
+        &self,
+        definition: ResourceDefinition,
+        text: impl Into<String>,
+    ) -> Result<(), WorkflowError> {
+        let mut resources = self.resources.write().await;
+        resources.insert(definition.uri.clone(), (definition, text.into()));
+        Ok(())
+    }
+
+    pub async fn remove_resource(&self, uri: &str) -> Result<bool, WorkflowError> {
+        let mut resources = self.resources.write().await;
+        Ok(resources.remove(uri).is_some())
+    }
+
+    /// Deduplicate `CallTool` requests carrying an idempotency key.
+    ///
+    /// Repeated calls with the same key within `retention` return the result
+    /// of the first successful call instead of executing the node again.
+    pub fn with_idempotency(mut self, retention: Duration) -> Self {
+        self.idempotency = Some(IdempotencyCache::new(retention));
+        self
+    }
+
     pub async fn register_node_as_tool<T>(
         &self,
         node: Arc<T>,
@@ -81,7 +171,12 @@ impl McpToolServer {
     {
         let mut tools = self.tools.write().await;
         let node_arc: Arc<dyn Node> = node;
-        tools.insert(metadata.name.clone(), (metadata, node_arc));
+        let versions = tools.entry(metadata.name.clone()).or_default();
+
+        // Re-registering a version replaces it; new versions are served side by side
+        versions.retain(|(existing, _)| existing.version != metadata.version);
+        versions.push((metadata, node_arc));
+        versions.sort_by(|(a, _), (b, _)| a.version.cmp(&b.version));
         Ok(())
     }
 
@@ -125,8 +220,36 @@ impl McpToolServer {
         ))
     }
 
-    pub async fn handle_request(&self, request: McpRequest) -> Result<McpResponse, WorkflowError> {
-        match request {
+    /// Handle a request or notification from `session`'s client;
+    /// notifications produce no response
+    pub async fn handle_request(
+        &self,
+        session: &McpSession,
+        request: McpRequest,
+    ) -> Result<Option<McpResponse>, WorkflowError> {
+        self.dispatch(session, request, None).await
+    }
+
+    /// Handle a request, forwarding `notifications/progress` messages for tool
+    /// calls that carry a progress token.
+    ///
+    /// All progress notifications are sent before the response is returned.
+    pub async fn handle_request_with_notifications(
+        &self,
+        session: &McpSession,
+        request: McpRequest,
+        notifications: &mpsc::UnboundedSender<McpNotification>,
+    ) -> Result<Option<McpResponse>, WorkflowError> {
+        self.dispatch(session, request, Some(notifications)).await
+    }
+
+    async fn dispatch(
+        &self,
+        session: &McpSession,
+        request: McpRequest,
+        notifications: Option<&mpsc::UnboundedSender<McpNotification>>,
+    ) -> Result<Option<McpResponse>, WorkflowError> {
+        let response = match request {
             McpRequest::Initialize { id, params } => {
                 let result = InitializeResult {
                     protocol_version: params.protocol_version,
@@ -144,8 +267,10 @@ impl McpToolServer {
             }
             McpRequest::ListTools { id } => {
                 let tools = self.tools.read().await;
+                // Advertise the latest version of each tool
                 let tool_definitions: Vec<ToolDefinition> = tools
                     .values()
+                    .filter_map(|versions| versions.last())
                     .map(|(metadata, _)| metadata.to_tool_definition())
                     .collect();
 
@@ -157,63 +282,307 @@ impl McpToolServer {
                 })
             }
             McpRequest::CallTool { id, params } => {
-                let tools = self.tools.read().await;
+                if !session.is_initialized() {
+                    return Ok(Some(McpResponse::Error {
+                        id,
+                        error: McpError {
+                            code: -32600,
+                            message: "Server not initialized".to_string(),
+                            data: None,
+                        },
+                    }));
+                }
 
-                if let Some((_metadata, node)) = tools.get(&params.name) {
-                    // Convert MCP arguments to TaskContext
-                    let task_context = self.arguments_to_task_context(params.arguments)?;
-
-                    // Execute the node
-                    match node.process(task_context) {
-                        Ok(result_context) => {
-                            let content = self.task_context_to_content(result_context)?;
-
-                            Ok(McpResponse::Result {
-                                id,
-                                result: ResponseResult::CallTool(CallToolResult {
-                                    content,
-                                    is_error: Some(false),
-                                }),
-                            })
-                        }
-                        Err(error) => {
-                            let content = vec![ToolContent::Text {
-                                text: format!("Error executing tool: {}", error),
-                            }];
-
-                            Ok(McpResponse::Result {
-                                id,
-                                result: ResponseResult::CallTool(CallToolResult {
-                                    content,
-                                    is_error: Some(true),
-                                }),
+                let requested_version = params
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.tool_version.as_deref());
+
+                let node = match self.resolve_tool(&params.name, requested_version).await {
+                    Ok(node) => node,
+                    Err(error) => return Ok(Some(McpResponse::Error { id, error })),
+                };
+
+                let idempotency_key = params
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.idempotency_key.clone());
+                let progress_token = params
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.progress_token.clone());
+
+                let (progress, forwarder) = match (notifications, progress_token) {
+                    (Some(notifications), Some(token)) => {
+                        let (reporter, updates) = ProgressReporter::channel();
+                        let forwarder = tokio::spawn(Self::forward_progress(
+                            token,
+                            updates,
+                            notifications.clone(),
+                        ));
+                        (reporter, Some(forwarder))
+                    }
+                    _ => (ProgressReporter::noop(), None),
+                };
+
+                let result = match (&self.idempotency, idempotency_key) {
+                    (Some(cache), Some(key)) => {
+                        let arguments = params.arguments;
+                        cache
+                            .execute(&params.name, &key, || async {
+                                self.execute_tool(node.as_ref(), arguments, &progress)
                             })
-                        }
+                            .await
                     }
-                } else {
-                    Ok(McpResponse::Error {
+                    _ => self.execute_tool(node.as_ref(), params.arguments, &progress),
+                };
+
+                // Closing the reporter ends the forwarder once queued updates are flushed
+                drop(progress);
+                if let Some(forwarder) = forwarder {
+                    let _ = forwarder.await;
+                }
+                let result = result?;
+
+                Ok(McpResponse::Result {
+                    id,
+                    result: ResponseResult::CallTool(result),
+                })
+            }
+            McpRequest::ListResources { id } => {
+                let resources = self.resources.read().await;
+                let mut definitions: Vec<ResourceDefinition> = resources
+                    .values()
+                    .map(|(definition, _)| definition.clone())
+                    .collect();
+                definitions.sort_by(|a, b| a.uri.cmp(&b.uri));
+
+                Ok(McpResponse::Result {
+                    id,
+                    result: ResponseResult::ListResources(ListResourcesResult {
+                        resources: definitions,
+                    }),
+                })
+            }
+            McpRequest::ReadResource { id, params } => {
+                let resources = self.resources.read().await;
+
+                match resources.get(&params.uri) {
+                    Some((definition, text)) => Ok(McpResponse::Result {
+                        id,
+                        result: ResponseResult::ReadResource(ReadResourceResult {
+                            contents: vec![ResourceContents {
+                                uri: definition.uri.clone(),
+                                mime_type: definition.mime_type.clone(),
+                                text: text.clone(),
+                            }],
+                        }),
+                    }),
+                    None => Ok(McpResponse::Error {
                         id,
                         error: McpError {
-                            code: -32601,
-                            message: format!("Tool '{}' not found", params.name),
+                            code: -32002,
+                            message: format!("Resource '{}' not found", params.uri),
                             data: None,
                         },
-                    })
+                    }),
                 }
             }
+            McpRequest::ListPrompts { id } => {
+                let prompts = match &self.prompt_templates {
+                    Some(templates) => self.prompt_definitions(templates)?,
+                    None => Vec::new(),
+                };
+
+                Ok(McpResponse::Result {
+                    id,
+                    result: ResponseResult::ListPrompts(ListPromptsResult { prompts }),
+                })
+            }
+            McpRequest::GetPrompt { id, params } => match self.render_prompt(params) {
+                Ok(result) => Ok(McpResponse::Result {
+                    id,
+                    result: ResponseResult::GetPrompt(result),
+                }),
+                Err(message) => Ok(McpResponse::Error {
+                    id,
+                    error: McpError {
+                        code: -32602,
+                        message,
+                        data: None,
+                    },
+                }),
+            },
             McpRequest::Initialized => {
-                // Notification - no response needed
-                Err(WorkflowError::mcp_protocol_error(
-                    "Initialized notification should not expect a response",
-                    "mcp_server",
-                    "notification",
-                    "response_request",
-                    "initialized"
-                ))
+                // Notification - record the handshake and send no response
+                session.initialized.store(true, Ordering::SeqCst);
+                return Ok(None);
+            }
+        };
+
+        response.map(Some)
+    }
+
+    fn execute_tool(
+        &self,
+        node: &dyn Node,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+        progress: &ProgressReporter,
+    ) -> Result<CallToolResult, WorkflowError> {
+        // Convert MCP arguments to TaskContext
+        let task_context = self.arguments_to_task_context(arguments)?;
+
+        // Execute the node
+        match node.process_with_progress(task_context, progress) {
+            Ok(result_context) => Ok(CallToolResult {
+                content: self.task_context_to_content(result_context)?,
+                is_error: Some(false),
+            }),
+            Err(error) => Ok(CallToolResult {
+                content: vec![ToolContent::Text {
+                    text: format!("Error executing tool: {}", error),
+                }],
+                is_error: Some(true),
+            }),
+        }
+    }
+
+    /// Select the highest registered version of a tool matching the requested range
+    async fn resolve_tool(
+        &self,
+        name: &str,
+        requested_version: Option<&str>,
+    ) -> Result<Arc<dyn Node>, McpError> {
+        let tools = self.tools.read().await;
+        let versions = tools.get(name).filter(|versions| !versions.is_empty()).ok_or_else(|| {
+            McpError {
+                code: -32601,
+                message: format!("Tool '{}' not found", name),
+                data: None,
+            }
+        })?;
+
+        let Some(requested) = requested_version else {
+            return Ok(versions[versions.len() - 1].1.clone());
+        };
+
+        let range = VersionReq::parse(requested).map_err(|e| McpError {
+            code: -32602,
+            message: format!("Invalid version range '{}' for tool '{}': {}", requested, name, e),
+            data: None,
+        })?;
+
+        versions
+            .iter()
+            .rev()
+            .find(|(metadata, _)| range.matches(&metadata.version))
+            .map(|(_, node)| node.clone())
+            .ok_or_else(|| {
+                let available: Vec<String> = versions
+                    .iter()
+                    .map(|(metadata, _)| metadata.version.to_string())
+                    .collect();
+                McpError {
+                    code: -32602,
+                    message: format!(
+                        "Tool '{}' has no version matching '{}' (available: {})",
+                        name,
+                        requested,
+                        available.join(", ")
+                    ),
+                    data: Some(serde_json::json!({ "available_versions": available })),
+                }
+            })
+    }
+
+    async fn forward_progress(
+        progress_token: String,
+        mut updates: mpsc::UnboundedReceiver<ProgressUpdate>,
+        notifications: mpsc::UnboundedSender<McpNotification>,
+    ) {
+        while let Some(update) = updates.recv().await {
+            let notification = McpNotification::Progress {
+                params: ProgressNotificationParams {
+                    progress_token: progress_token.clone(),
+                    progress: update.progress,
+                    total: update.total,
+                    message: update.message,
+                },
+            };
+            if notifications.send(notification).is_err() {
+                break;
             }
         }
     }
 
+    fn prompt_definitions(
+        &self,
+        templates: &TemplateManager,
+    ) -> Result<Vec<PromptDefinition>, WorkflowError> {
+        let mut prompts = Vec::new();
+
+        for metadata in templates.list_templates()? {
+            let template = templates.get_template(&metadata.id.0)?;
+            let mut arguments: Vec<PromptArgument> = template
+                .variables
+                .keys()
+                .map(|name| PromptArgument {
+                    name: name.clone(),
+                    description: None,
+                    required: Some(true),
+                })
+                .collect();
+            arguments.sort_by(|a, b| a.name.cmp(&b.name));
+
+            prompts.push(PromptDefinition {
+                name: metadata.id.0.clone(),
+                description: template.description.clone(),
+                arguments,
+            });
+        }
+
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(prompts)
+    }
+
+    /// Render a prompt template; errors are returned as MCP error messages
+    fn render_prompt(&self, params: GetPromptParams) -> Result<GetPromptResult, String> {
+        let templates = self
+            .prompt_templates
+            .as_ref()
+            .ok_or_else(|| "Prompts are not enabled on this server".to_string())?;
+        let template = templates
+            .get_template(&params.name)
+            .map_err(|_| format!("Prompt '{}' not found", params.name))?;
+
+        // Prompt arguments arrive as strings; decode non-string variables as JSON
+        let variables: HashMap<String, serde_json::Value> = params
+            .arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, raw)| {
+                let value = match template.variables.get(&name) {
+                    Some(VariableType::String) | None => serde_json::Value::String(raw),
+                    Some(_) => serde_json::from_str(&raw)
+                        .unwrap_or(serde_json::Value::String(raw)),
+                };
+                (name, value)
+            })
+            .collect();
+
+        let text = templates
+            .render(&params.name, &variables)
+            .map_err(|e| format!("Failed to render prompt '{}': {}", params.name, e))?;
+
+        Ok(GetPromptResult {
+            description: template.description.clone(),
+            messages: vec![PromptMessage {
+                role: "user".to_string(),
+                content: ToolContent::Text { text },
+            }],
+        })
+    }
+
     fn arguments_to_task_context(
         &self,
         arguments: Option<HashMap<String, serde_json::Value>>,
@@ -345,6 +714,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_handle_list_tools_request() {
+        let session = McpSession::new();
         let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string());
         let node = Arc::new(TestNode::new("TestNode".to_string()));
         server.register_node_with_auto_metadata(node).await.unwrap();
@@ -353,7 +723,7 @@ mod tests {
             id: "test-123".to_string(),
         };
 
-        let response = server.handle_request(request).await.unwrap();
+        let response = server.handle_request(&session, request).await.unwrap().unwrap();
         match response {
             McpResponse::Result {
                 result: ResponseResult::ListTools(tools_result),
@@ -365,4 +735,515 @@ mod tests {
             _ => panic!("Expected ListTools response"),
         }
     }
+
+    #[derive(Debug, Default)]
+    struct CountingNode {
+        runs: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Node for CountingNode {
+        fn node_name(&self) -> String {
+            "CountingNode".to_string()
+        }
+
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            let run = self.runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            // Keep the call in flight long enough for retries to overlap
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            task_context.set_data("run", serde_json::json!(run))?;
+            Ok(task_context)
+        }
+    }
+
+    /// Fails its first run, then succeeds
+    #[derive(Debug, Default)]
+    struct FailingOnceNode {
+        runs: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Node for FailingOnceNode {
+        fn node_name(&self) -> String {
+            "FailingOnceNode".to_string()
+        }
+
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            if self.runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                return Err(WorkflowError::processing_error_simple("upstream unavailable"));
+            }
+            Ok(task_context)
+        }
+    }
+
+    fn keyed_call(id: &str, key: Option<&str>) -> McpRequest {
+        McpRequest::CallTool {
+            id: id.to_string(),
+            params: crate::protocol::ToolCallParams {
+                name: "counting".to_string(),
+                arguments: None,
+                meta: key.map(|key| crate::protocol::RequestMeta {
+                    idempotency_key: Some(key.to_string()),
+                    ..Default::default()
+                }),
+            },
+        }
+    }
+
+    async fn counting_server(retention: Duration) -> (Arc<McpToolServer>, Arc<CountingNode>, McpSession) {
+        let session = McpSession::new();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string())
+            .with_idempotency(retention);
+        let node = Arc::new(CountingNode::default());
+        let metadata = ToolMetadata::new(
+            "counting".to_string(),
+            "Counts executions".to_string(),
+            serde_json::json!({"type": "object"}),
+            TypeId::of::<CountingNode>(),
+        );
+        server.register_node_as_tool(node.clone(), metadata).await.unwrap();
+        server.handle_request(&session, McpRequest::Initialized).await.unwrap();
+        (Arc::new(server), node, session)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_keyed_calls_execute_once() {
+        let (server, node, session) = counting_server(Duration::from_secs(60)).await;
+
+        let handles: Vec<_> = (0..5)
+            .map(|i| {
+                let server = server.clone();
+                let session = session.clone();
+                tokio::spawn(async move {
+                    server
+                        .handle_request(&session, keyed_call(&format!("call-{}", i), Some("retry-key")))
+                        .await
+                        .unwrap()
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            match handle.await.unwrap() {
+                McpResponse::Result {
+                    id,
+                    result: ResponseResult::CallTool(result),
+                } => {
+                    assert_eq!(id, format!("call-{}", i));
+                    assert_eq!(result.is_error, Some(false));
+                }
+                other => panic!("Expected CallTool response, got {:?}", other),
+            }
+        }
+
+        assert_eq!(node.runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unkeyed_and_expired_calls_execute_again() {
+        let (server, node, session) = counting_server(Duration::from_millis(10)).await;
+
+        server.handle_request(&session, keyed_call("a", None)).await.unwrap().unwrap();
+        server.handle_request(&session, keyed_call("b", None)).await.unwrap().unwrap();
+        assert_eq!(node.runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        server.handle_request(&session, keyed_call("c", Some("key"))).await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        server.handle_request(&session, keyed_call("d", Some("key"))).await.unwrap().unwrap();
+        assert_eq!(node.runs.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_keyed_error_results_are_not_cached() {
+        let session = McpSession::new();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string())
+            .with_idempotency(Duration::from_secs(60));
+        let node = Arc::new(FailingOnceNode::default());
+        let metadata = ToolMetadata::new(
+            "counting".to_string(),
+            "Fails once".to_string(),
+            serde_json::json!({"type": "object"}),
+            TypeId::of::<FailingOnceNode>(),
+        );
+        server.register_node_as_tool(node.clone(), metadata).await.unwrap();
+        server.handle_request(&session, McpRequest::Initialized).await.unwrap();
+
+        let is_error = |response: McpResponse| match response {
+            McpResponse::Result {
+                result: ResponseResult::CallTool(result),
+                ..
+            } => result.is_error,
+            other => panic!("Expected CallTool response, got {:?}", other),
+        };
+        for (id, expected) in [("a", Some(true)), ("b", Some(false)), ("c", Some(false))] {
+            let response = server.handle_request(&session, keyed_call(id, Some("key"))).await.unwrap().unwrap();
+            assert_eq!(is_error(response), expected, "{}", id);
+        }
+        // The failure was retried, the success served from the cache
+        assert_eq!(node.runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_register_and_read_resource() {
+        let session = McpSession::new();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string());
+        server
+            .register_resource(
+                crate::protocol::ResourceDefinition {
+                    uri: "workflow://docs/readme".to_string(),
+                    name: "README".to_string(),
+                    description: Some("Workflow documentation".to_string()),
+                    mime_type: Some("text/markdown".to_string()),
+                },
+                "# Workflows",
+            )
+            .await
+            .unwrap();
+
+        let response = server
+            .handle_request(&session, McpRequest::ListResources {
+                id: "1".to_string(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        match response {
+            McpResponse::Result {
+                result: ResponseResult::ListResources(result),
+                ..
+            } => {
+                assert_eq!(result.resources.len(), 1);
+                assert_eq!(result.resources[0].uri, "workflow://docs/readme");
+            }
+            other => panic!("Expected ListResources response, got {:?}", other),
+        }
+
+        let response = server
+            .handle_request(&session, McpRequest::ReadResource {
+                id: "2".to_string(),
+                params: crate::protocol::ReadResourceParams {
+                    uri: "workflow://docs/readme".to_string(),
+                },
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        match response {
+            McpResponse::Result {
+                result: ResponseResult::ReadResource(result),
+                ..
+            } => {
+                assert_eq!(result.contents.len(), 1);
+                assert_eq!(result.contents[0].text, "# Workflows");
+                assert_eq!(result.contents[0].mime_type.as_deref(), Some("text/markdown"));
+            }
+            other => panic!("Expected ReadResource response, got {:?}", other),
+        }
+
+        let response = server
+            .handle_request(&session, McpRequest::ReadResource {
+                id: "3".to_string(),
+                params: crate::protocol::ReadResourceParams {
+                    uri: "workflow://missing".to_string(),
+                },
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(response, McpResponse::Error { error, .. } if error.code == -32002));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_prompt() {
+        let session = McpSession::new();
+        use workflow_engine_core::ai::templates::{Template, TemplateManager, VariableType};
+
+        let mut templates = TemplateManager::new().unwrap();
+        templates
+            .register(
+                Template::new("greeting", "Hello {{name}}!")
+                    .unwrap()
+                    .with_variable("name", VariableType::String),
+            )
+            .unwrap();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string())
+            .with_prompts(Arc::new(templates));
+        assert!(server.capabilities.prompts.is_some());
+
+        let response = server
+            .handle_request(&session, McpRequest::ListPrompts {
+                id: "1".to_string(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        match response {
+            McpResponse::Result {
+                result: ResponseResult::ListPrompts(result),
+                ..
+            } => {
+                assert_eq!(result.prompts.len(), 1);
+                assert_eq!(result.prompts[0].name, "greeting");
+                assert_eq!(result.prompts[0].arguments[0].name, "name");
+            }
+            other => panic!("Expected ListPrompts response, got {:?}", other),
+        }
+
+        let response = server
+            .handle_request(&session, McpRequest::GetPrompt {
+                id: "2".to_string(),
+                params: crate::protocol::GetPromptParams {
+                    name: "greeting".to_string(),
+                    arguments: Some(HashMap::from([(
+                        "name".to_string(),
+                        "World".to_string(),
+                    )])),
+                },
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        match response {
+            McpResponse::Result {
+                result: ResponseResult::GetPrompt(result),
+                ..
+            } => match &result.messages[0].content {
+                ToolContent::Text { text } => assert_eq!(text, "Hello World!"),
+                other => panic!("Expected text content, got {:?}", other),
+            },
+            other => panic!("Expected GetPrompt response, got {:?}", other),
+        }
+    }
+
+    #[derive(Debug)]
+    struct ProgressNode;
+
+    impl Node for ProgressNode {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            Ok(task_context)
+        }
+
+        fn process_with_progress(
+            &self,
+            task_context: TaskContext,
+            progress: &ProgressReporter,
+        ) -> Result<TaskContext, WorkflowError> {
+            progress.report(1.0, Some(2.0), Some("halfway".to_string()));
+            progress.report(2.0, Some(2.0), None);
+            self.process(task_context)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progress_notifications_forwarded_before_result() {
+        let session = McpSession::new();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string());
+        let metadata = ToolMetadata::new(
+            "progress".to_string(),
+            "Reports progress".to_string(),
+            serde_json::json!({"type": "object"}),
+            TypeId::of::<ProgressNode>(),
+        );
+        server
+            .register_node_as_tool(Arc::new(ProgressNode), metadata)
+            .await
+            .unwrap();
+        server.handle_request(&session, McpRequest::Initialized).await.unwrap();
+
+        let request = McpRequest::CallTool {
+            id: "1".to_string(),
+            params: crate::protocol::ToolCallParams {
+                name: "progress".to_string(),
+                arguments: None,
+                meta: Some(crate::protocol::RequestMeta {
+                    progress_token: Some("token-1".to_string()),
+                    ..Default::default()
+                }),
+            },
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let response = server
+            .handle_request_with_notifications(&session, request, &tx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            response,
+            McpResponse::Result {
+                result: ResponseResult::CallTool(_),
+                ..
+            }
+        ));
+
+        // Both updates must already be queued when the result is returned
+        let mut updates = Vec::new();
+        while let Ok(McpNotification::Progress { params }) = rx.try_recv() {
+            updates.push(params);
+        }
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().all(|u| u.progress_token == "token-1"));
+        assert_eq!(updates[0].progress, 1.0);
+        assert_eq!(updates[0].message.as_deref(), Some("halfway"));
+        assert_eq!(updates[1].progress, 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_initialized_notification_has_no_response() {
+        let session = McpSession::new();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string());
+        assert!(!session.is_initialized());
+
+        let response = server.handle_request(&session, McpRequest::Initialized).await.unwrap();
+        assert!(response.is_none());
+        assert!(session.is_initialized());
+    }
+
+    #[tokio::test]
+    async fn test_each_session_must_initialize() {
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string());
+        let metadata = ToolMetadata::new(
+            "counting".to_string(),
+            "Counts executions".to_string(),
+            serde_json::json!({"type": "object"}),
+            TypeId::of::<CountingNode>(),
+        );
+        let node = Arc::new(CountingNode::default());
+        server.register_node_as_tool(node.clone(), metadata).await.unwrap();
+
+        let first = McpSession::new();
+        let second = McpSession::new();
+        server.handle_request(&first, McpRequest::Initialized).await.unwrap();
+        assert!(!second.is_initialized());
+
+        let response = server.handle_request(&first, keyed_call("first", None)).await.unwrap().unwrap();
+        assert!(matches!(response, McpResponse::Result { .. }));
+
+        // The first client's handshake doesn't let the second one skip its own
+        let response = server.handle_request(&second, keyed_call("second", None)).await.unwrap().unwrap();
+        match response {
+            McpResponse::Error { id, error } => {
+                assert_eq!(id, "second");
+                assert!(error.message.contains("not initialized"));
+            }
+            other => panic!("Expected not initialized error, got {:?}", other),
+        }
+        assert_eq!(node.runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_before_initialized_is_rejected() {
+        let session = McpSession::new();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string());
+        let metadata = ToolMetadata::new(
+            "counting".to_string(),
+            "Counts executions".to_string(),
+            serde_json::json!({"type": "object"}),
+            TypeId::of::<CountingNode>(),
+        );
+        let node = Arc::new(CountingNode::default());
+        server.register_node_as_tool(node.clone(), metadata).await.unwrap();
+
+        let response = server
+            .handle_request(&session, keyed_call("early", None))
+            .await
+            .unwrap()
+            .unwrap();
+        match response {
+            McpResponse::Error { id, error } => {
+                assert_eq!(id, "early");
+                assert_eq!(error.code, -32600);
+                assert!(error.message.contains("not initialized"));
+            }
+            other => panic!("Expected not initialized error, got {:?}", other),
+        }
+        assert_eq!(node.runs.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[derive(Debug)]
+    struct VersionedNode(&'static str);
+
+    impl Node for VersionedNode {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            task_context.set_data("served_by", serde_json::json!(self.0))?;
+            Ok(task_context)
+        }
+    }
+
+    async fn call_versioned(server: &McpToolServer, session: &McpSession, range: &str) -> McpResponse {
+        let request = McpRequest::CallTool {
+            id: range.to_string(),
+            params: crate::protocol::ToolCallParams {
+                name: "versioned".to_string(),
+                arguments: None,
+                meta: Some(crate::protocol::RequestMeta {
+                    tool_version: Some(range.to_string()),
+                    ..Default::default()
+                }),
+            },
+        };
+        server.handle_request(session, request).await.unwrap().unwrap()
+    }
+
+    fn served_by(response: &McpResponse) -> String {
+        match response {
+            McpResponse::Result {
+                result: ResponseResult::CallTool(result),
+                ..
+            } => match &result.content[0] {
+                ToolContent::Text { text } => {
+                    let context: serde_json::Value = serde_json::from_str(text).unwrap();
+                    context["nodes"]["served_by"].as_str().unwrap().to_string()
+                }
+                other => panic!("Expected text content, got {:?}", other),
+            },
+            other => panic!("Expected CallTool response, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_selects_matching_version() {
+        let session = McpSession::new();
+        let server = McpToolServer::new("test-server".to_string(), "1.0.0".to_string());
+        for (version, label) in [("1.2.0", "v1"), ("2.0.1", "v2")] {
+            let metadata = ToolMetadata::new(
+                "versioned".to_string(),
+                "Versioned tool".to_string(),
+                serde_json::json!({"type": "object"}),
+                TypeId::of::<VersionedNode>(),
+            )
+            .with_version(Version::parse(version).unwrap());
+            server
+                .register_node_as_tool(Arc::new(VersionedNode(label)), metadata)
+                .await
+                .unwrap();
+        }
+        server.handle_request(&session, McpRequest::Initialized).await.unwrap();
+        assert_eq!(server.get_tool_count().await, 1);
+
+        assert_eq!(served_by(&call_versioned(&server, &session, "^1").await), "v1");
+        assert_eq!(served_by(&call_versioned(&server, &session, "^2").await), "v2");
+
+        match call_versioned(&server, &session, "^3").await {
+            McpResponse::Error { error, .. } => {
+                assert_eq!(error.code, -32602);
+                assert!(error.message.contains("1.2.0, 2.0.1"));
+            }
+            other => panic!("Expected version mismatch error, got {:?}", other),
+        }
+
+        let response = server
+            .handle_request(&session, McpRequest::ListTools {
+                id: "list".to_string(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        match response {
+            McpResponse::Result {
+                result: ResponseResult::ListTools(result),
+                ..
+            } => assert_eq!(result.tools[0].version.as_deref(), Some("2.0.1")),
+            other => panic!("Expected ListTools response, got {:?}", other),
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde_json;
+use std::collections::HashMap;
 use std::io;
 use std::time::{Duration, Instant};
 use std::sync::Arc;
@@ -1031,6 +1032,7 @@ mod tests {
 pub struct StdioTransport {
     command: String,
     args: Vec<String>,
+    env: HashMap<String, String>,
     auto_restart: bool,
     max_restarts: u32,
     process: Option<Child>,
@@ -1047,6 +1049,7 @@ impl StdioTransport {
         Self {
             command,
             args,
+            env: HashMap::new(),
             auto_restart: true,
             max_restarts: 3,
             process: None,
@@ -1064,6 +1067,12 @@ impl StdioTransport {
         self.max_restarts = max_restarts;
         self
     }
+
+    /// Set environment variables for the spawned process
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
     
     async fn attempt_restart(&mut self) -> Result<(), TransportError> {
         if !self.auto_restart || self.restart_count >= self.max_restarts {
@@ -1100,6 +1109,7 @@ impl McpTransport for StdioTransport {
         let result = async {
             let mut child = Command::new(&self.command)
                 .args(&self.args)
+                .envs(&self.env)
                 .stdin(std::process::Stdio::piped())
                 .stdout(std::process::Stdio::piped())
                 .stderr(std::process::Stdio::piped())
@@ -74,6 +74,7 @@ mod tests {
             model_provider: ModelProvider::Anthropic,
             model_name: "claude-3-opus-20240229".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = AnthropicAgentNode::new(config);
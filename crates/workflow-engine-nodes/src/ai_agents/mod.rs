@@ -39,6 +39,7 @@
 //!     model_provider: ModelProvider::Anthropic,
 //!     model_name: "claude-3-opus-20240229".to_string(),
 //!     mcp_server_uri: None,
+//!     ai_fallback_template: None,
 //! };
 //!
 //! let anthropic_agent = AnthropicAgentNode::new(anthropic_config);
@@ -49,6 +50,7 @@
 //!     model_provider: ModelProvider::OpenAI,
 //!     model_name: "gpt-4".to_string(),
 //!     mcp_server_uri: None,
+//!     ai_fallback_template: None,
 //! };
 //!
 //! let openai_agent = OpenAIAgentNode::new(openai_config)?;
@@ -77,6 +79,7 @@
 //!     model_provider: ModelProvider::Anthropic,
 //!     model_name: "claude-3-sonnet-20240229".to_string(),
 //!     mcp_server_uri: None,
+//!     ai_fallback_template: None,
 //! };
 //!
 //! workflow.register_node(AnthropicAgentNode::new(agent_config));
@@ -105,6 +108,7 @@
 //!     model_provider: ModelProvider::OpenAI,
 //!     model_name: "gpt-4".to_string(),
 //!     mcp_server_uri: Some("ws://localhost:8080/mcp".to_string()),
+//!     ai_fallback_template: None,
 //! };
 //!
 //! let mut agent = OpenAIAgentNode::new(config)?;
@@ -212,6 +216,7 @@
 //!     
 //!     // Optional MCP server for tool access
 //!     mcp_server_uri: Some("ws://localhost:8080/tools".to_string()),
+//!     ai_fallback_template: None,
 //! };
 //! ```
 //!
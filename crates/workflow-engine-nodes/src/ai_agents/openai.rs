@@ -80,6 +80,7 @@ mod tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-4".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = OpenAIAgentNode::new(config).unwrap();
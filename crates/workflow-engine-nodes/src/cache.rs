@@ -0,0 +1,345 @@
+//! Cache lookup nodes
+//!
+//! This module provides a node that enriches the context with reference data
+//! kept in a key-value cache, such as Redis, filling the cache from a
+//! fallback node on a miss.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use workflow_engine_core::ai::templates::{EngineConfig, Template, TemplateEngine, TemplateVariables};
+use workflow_engine_core::nodes::cache::DEFAULT_CACHE_TTL;
+use workflow_engine_core::prelude::*;
+
+/// A key-value cache of JSON values
+pub trait KeyValueCache: Send + Sync + std::fmt::Debug {
+    /// The value stored under `key`, unless it has expired
+    fn get(&self, key: &str) -> Result<Option<Value>>;
+
+    /// Store `value` under `key` for `ttl`
+    fn set(&self, key: &str, value: &Value, ttl: Duration) -> Result<()>;
+}
+
+/// Process-local [`KeyValueCache`]. Expired entries are dropped when they are
+/// next looked up.
+#[derive(Debug, Default)]
+pub struct InMemoryKeyValueCache {
+    entries: Mutex<HashMap<String, (Instant, Value)>>,
+}
+
+impl InMemoryKeyValueCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KeyValueCache for InMemoryKeyValueCache {
+    fn get(&self, key: &str) -> Result<Option<Value>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((expires_at, value)) if Instant::now() < *expires_at => Ok(Some(value.clone())),
+            Some(_) => {
+                entries.remove(key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn set(&self, key: &str, value: &Value, ttl: Duration) -> Result<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), (Instant::now() + ttl, value.clone()));
+        Ok(())
+    }
+}
+
+/// [`KeyValueCache`] backed by Redis, storing values as JSON strings
+#[cfg(feature = "redis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "redis")))]
+#[derive(Debug)]
+pub struct RedisKeyValueCache {
+    client: redis::Client,
+    url: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisKeyValueCache {
+    /// Connect lazily to the Redis server at `url`, e.g. `redis://localhost:6379`
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        let url = url.into();
+        let client = redis::Client::open(url.as_str()).map_err(|e| Self::error(&url, "open", e))?;
+        Ok(Self { client, url })
+    }
+
+    fn error(url: &str, operation: &str, error: redis::RedisError) -> WorkflowError {
+        WorkflowError::api_error(format!("Redis {} failed: {}", operation, error), "redis", url, None)
+    }
+
+    fn connection(&self) -> Result<redis::Connection> {
+        self.client
+            .get_connection()
+            .map_err(|e| Self::error(&self.url, "connect", e))
+    }
+}
+
+#[cfg(feature = "redis")]
+impl KeyValueCache for RedisKeyValueCache {
+    fn get(&self, key: &str) -> Result<Option<Value>> {
+        let raw: Option<String> = redis::cmd("GET")
+            .arg(key)
+            .query(&mut self.connection()?)
+            .map_err(|e| Self::error(&self.url, "GET", e))?;
+        Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    fn set(&self, key: &str, value: &Value, ttl: Duration) -> Result<()> {
+        redis::cmd("SET")
+            .arg(key)
+            .arg(value.to_string())
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query::<()>(&mut self.connection()?)
+            .map_err(|e| Self::error(&self.url, "SET", e))
+    }
+}
+
+/// A value to look up, stored as the node result `name`
+#[derive(Debug)]
+struct Lookup {
+    name: String,
+    key: Template,
+}
+
+/// Looks up values in a [`KeyValueCache`] and stores each as a node result.
+///
+/// Keys are rendered from Handlebars templates, which see the context as
+/// `event`, `nodes` and `metadata`. Values found are stored under their
+/// lookup's name. On a miss, the fallback node, if any, runs once on the
+/// context and is expected to store the missing values under the same names;
+/// they are then cached for the TTL. Without a fallback, missing values are
+/// left out. Cache failures are logged and treated as misses.
+///
+/// Which lookups hit and missed is recorded under `cache_lookup`.
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use workflow_engine_nodes::cache::{CacheLookupNode, InMemoryKeyValueCache};
+///
+/// # #[derive(Debug)]
+/// # struct LoadCustomer;
+/// # impl workflow_engine_core::nodes::Node for LoadCustomer {
+/// #     fn process(&self, context: workflow_engine_core::task::TaskContext)
+/// #         -> Result<workflow_engine_core::task::TaskContext, workflow_engine_core::error::WorkflowError> {
+/// #         Ok(context)
+/// #     }
+/// # }
+/// let node = CacheLookupNode::new(Arc::new(InMemoryKeyValueCache::new()))
+///     .with_lookup("customer", "customer:{{event.customer_id}}")
+///     .with_fallback(Arc::new(LoadCustomer))
+///     .with_ttl(Duration::from_secs(600));
+/// ```
+#[derive(Debug)]
+pub struct CacheLookupNode {
+    cache: Arc<dyn KeyValueCache>,
+    lookups: Vec<Lookup>,
+    fallback: Option<Arc<dyn Node>>,
+    ttl: Duration,
+    output_key: String,
+    templates: TemplateEngine,
+}
+
+impl CacheLookupNode {
+    pub fn new(cache: Arc<dyn KeyValueCache>) -> Self {
+        Self {
+            cache,
+            lookups: Vec::new(),
+            fallback: None,
+            ttl: DEFAULT_CACHE_TTL,
+            output_key: "cache_lookup".to_string(),
+            // Keys are used verbatim, so they must not be HTML escaped
+            templates: TemplateEngine::with_config(EngineConfig {
+                escape_html: false,
+                ..EngineConfig::default()
+            }),
+        }
+    }
+
+    /// Look up the key rendered from `key_template`, storing the value as the
+    /// node result `name`
+    pub fn with_lookup(mut self, name: impl Into<String>, key_template: impl Into<String>) -> Self {
+        let name = name.into();
+        let key = Template::new(format!("cache_key_{}", name), key_template)
+            .expect("creating a template cannot fail");
+        self.lookups.push(Lookup { name, key });
+        self
+    }
+
+    /// Run `node` on a miss to produce the missing values
+    pub fn with_fallback(mut self, node: Arc<dyn Node>) -> Self {
+        self.fallback = Some(node);
+        self
+    }
+
+    /// How long values produced by the fallback are cached
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// The cache key of each lookup for `context`, by lookup name
+    pub fn render_keys(&self, context: &TaskContext) -> Result<Vec<(String, String)>> {
+        let variables = TemplateVariables::from_map(HashMap::from([
+            ("event".to_string(), context.event_data.clone()),
+            ("nodes".to_string(), json!(context.get_all_data())),
+            ("metadata".to_string(), json!(context.get_all_metadata())),
+        ]));
+        self.lookups
+            .iter()
+            .map(|lookup| Ok((lookup.name.clone(), self.templates.render(&lookup.key, &variables)?)))
+            .collect()
+    }
+
+    fn cached(&self, key: &str) -> Option<Value> {
+        self.cache.get(key).unwrap_or_else(|e| {
+            log::warn!("Cache lookup of '{}' failed, treating it as a miss: {}", key, e);
+            None
+        })
+    }
+}
+
+impl Node for CacheLookupNode {
+    fn node_name(&self) -> String {
+        "CacheLookupNode".to_string()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let keys = self.render_keys(&task_context)?;
+        let mut hits = Vec::new();
+        let mut misses = Vec::new();
+        for (name, key) in keys {
+            match self.cached(&key) {
+                Some(value) => {
+                    task_context.update_node(&name, value);
+                    hits.push(name);
+                }
+                None => misses.push((name, key)),
+            }
+        }
+
+        if let (Some(fallback), false) = (&self.fallback, misses.is_empty()) {
+            task_context = fallback.process(task_context)?;
+            for (name, key) in &misses {
+                match task_context.get_all_data().get(name) {
+                    Some(value) => {
+                        if let Err(e) = self.cache.set(key, value, self.ttl) {
+                            log::warn!("Caching '{}' failed: {}", key, e);
+                        }
+                    }
+                    None => log::warn!(
+                        "Fallback {} did not produce '{}' for cache key '{}'",
+                        fallback.node_name(),
+                        name,
+                        key
+                    ),
+                }
+            }
+        }
+
+        let misses: Vec<String> = misses.into_iter().map(|(name, _)| name).collect();
+        task_context.update_node(
+            &self.output_key,
+            json!({ "hits": hits, "misses": misses, "fallback_ran": self.fallback.is_some() && !misses.is_empty() }),
+        );
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Loads a customer record, counting how often it is asked to
+    #[derive(Debug, Default)]
+    struct LoadCustomer {
+        calls: AtomicUsize,
+    }
+
+    impl Node for LoadCustomer {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let id = task_context.event_data["customer_id"].clone();
+            task_context.update_node("customer", json!({ "id": id, "tier": "gold" }));
+            Ok(task_context)
+        }
+    }
+
+    fn context(customer_id: u32) -> TaskContext {
+        TaskContext::new("cache_test".to_string(), json!({ "customer_id": customer_id }))
+    }
+
+    fn node(cache: Arc<InMemoryKeyValueCache>, fallback: Arc<LoadCustomer>) -> CacheLookupNode {
+        CacheLookupNode::new(cache)
+            .with_lookup("customer", "customer:{{event.customer_id}}")
+            .with_fallback(fallback)
+    }
+
+    #[test]
+    fn test_cache_hit_skips_fallback() {
+        let cache = Arc::new(InMemoryKeyValueCache::new());
+        cache
+            .set("customer:7", &json!({ "id": 7, "tier": "silver" }), DEFAULT_CACHE_TTL)
+            .unwrap();
+        let fallback = Arc::new(LoadCustomer::default());
+
+        let context = node(cache, fallback.clone()).process(context(7)).unwrap();
+        assert_eq!(context.get_all_data()["customer"], json!({ "id": 7, "tier": "silver" }));
+        assert_eq!(
+            context.get_all_data()["cache_lookup"],
+            json!({ "hits": ["customer"], "misses": [], "fallback_ran": false })
+        );
+        assert_eq!(fallback.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_miss_runs_fallback_once_and_caches_its_value() {
+        let cache = Arc::new(InMemoryKeyValueCache::new());
+        let fallback = Arc::new(LoadCustomer::default());
+        let node = node(cache.clone(), fallback.clone());
+
+        let first = node.process(context(42)).unwrap();
+        assert_eq!(first.get_all_data()["customer"], json!({ "id": 42, "tier": "gold" }));
+        assert_eq!(first.get_all_data()["cache_lookup"]["fallback_ran"], true);
+        assert_eq!(cache.get("customer:42").unwrap(), Some(json!({ "id": 42, "tier": "gold" })));
+
+        // The cached value answers the next run; another customer misses
+        let second = node.process(context(42)).unwrap();
+        assert_eq!(second.get_all_data()["customer"], first.get_all_data()["customer"]);
+        assert_eq!(second.get_all_data()["cache_lookup"]["hits"], json!(["customer"]));
+        assert_eq!(fallback.calls.load(Ordering::SeqCst), 1);
+
+        node.process(context(43)).unwrap();
+        assert_eq!(fallback.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_miss_without_fallback_leaves_value_out() {
+        let cache = Arc::new(InMemoryKeyValueCache::new());
+        cache.set("customer:1", &json!({ "id": 1 }), Duration::ZERO).unwrap();
+        let node = CacheLookupNode::new(cache.clone()).with_lookup("customer", "customer:{{event.customer_id}}");
+
+        // The entry has already expired
+        let context = node.process(context(1)).unwrap();
+        assert!(!context.get_all_data().contains_key("customer"));
+        assert_eq!(context.get_all_data()["cache_lookup"]["misses"], json!(["customer"]));
+        assert_eq!(cache.get("customer:1").unwrap(), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! Guard-clause nodes
+//!
+//! This module provides nodes that run other nodes only when the context
+//! satisfies a condition.
+
+use serde_json_path::JsonPath;
+use workflow_engine_core::prelude::*;
+
+/// Runs an inner node only when a JSONPath predicate holds for the context.
+///
+/// The predicate is evaluated against the whole serialized context, so it can
+/// reference `$.event_data`, `$.nodes` and `$.metadata`. It holds when the path
+/// matches at least one value that is neither `null` nor `false`; filter
+/// expressions can express comparisons. When it does not hold the context is
+/// passed through unchanged.
+///
+/// ```rust
+/// use workflow_engine_nodes::conditional::ConditionalNode;
+/// use workflow_engine_nodes::transform::TransformNode;
+///
+/// let summary = TransformNode::new([("customer", "$.user.name")]).unwrap();
+/// let node = ConditionalNode::new(summary, "$.nodes[?@.score > 0.8]").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ConditionalNode {
+    inner: Box<dyn Node>,
+    predicate: JsonPath,
+    expression: String,
+}
+
+impl ConditionalNode {
+    pub fn new(inner: impl Node + 'static, predicate: &str) -> Result<Self> {
+        Self::boxed(Box::new(inner), predicate)
+    }
+
+    pub fn boxed(inner: Box<dyn Node>, predicate: &str) -> Result<Self> {
+        let path = JsonPath::parse(predicate).map_err(|e| {
+            WorkflowError::validation_error(
+                format!("Invalid JSONPath predicate '{}': {}", predicate, e),
+                "predicate",
+                "valid JSONPath expression",
+                "in ConditionalNode::new",
+            )
+        })?;
+
+        Ok(Self {
+            inner,
+            predicate: path,
+            expression: predicate.to_string(),
+        })
+    }
+
+    pub fn predicate(&self) -> &str {
+        &self.expression
+    }
+
+    /// Evaluate the predicate against `context`
+    pub fn should_run(&self, context: &TaskContext) -> Result<bool> {
+        let context_value = serde_json::to_value(context).map_err(|e| {
+            WorkflowError::SerializationError {
+                message: format!("Failed to serialize task context: {}", e),
+                type_name: "TaskContext".to_string(),
+                context: "while evaluating ConditionalNode predicate".to_string(),
+                source: Some(e),
+            }
+        })?;
+
+        Ok(self
+            .predicate
+            .query(&context_value)
+            .all()
+            .into_iter()
+            .any(|value| !matches!(value, Value::Null | Value::Bool(false))))
+    }
+}
+
+impl Node for ConditionalNode {
+    fn node_name(&self) -> String {
+        format!("ConditionalNode({})", self.inner.node_name())
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext> {
+        if self.should_run(&task_context)? {
+            self.inner.process(task_context)
+        } else {
+            log::debug!(
+                "Skipping {}: predicate '{}' not met",
+                self.inner.node_name(),
+                self.expression
+            );
+            Ok(task_context)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MarkerNode;
+
+    impl Node for MarkerNode {
+        fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+            task_context.update_node("marker", json!({ "ran": true }));
+            Ok(task_context)
+        }
+    }
+
+    fn context(approved: bool, score: f64) -> TaskContext {
+        let mut context = TaskContext::new(
+            "conditional_test".to_string(),
+            json!({ "approved": approved, "amount": 250 }),
+        );
+        context.update_node("classifier", json!({ "score": score }));
+        context
+    }
+
+    #[test]
+    fn test_runs_inner_node_when_predicate_holds() {
+        let node = ConditionalNode::new(MarkerNode, "$.event_data.approved").unwrap();
+
+        let context = node.process(context(true, 0.5)).unwrap();
+        let marker: Option<Value> = context.get_node_data("marker").unwrap();
+        assert_eq!(marker, Some(json!({ "ran": true })));
+    }
+
+    #[test]
+    fn test_skips_inner_node_when_predicate_fails() {
+        let node = ConditionalNode::new(MarkerNode, "$.event_data.approved").unwrap();
+        let before = context(false, 0.5);
+        let nodes_before = before.get_all_data().clone();
+
+        let context = node.process(before).unwrap();
+        let marker: Option<Value> = context.get_node_data("marker").unwrap();
+        assert_eq!(marker, None);
+        assert_eq!(context.get_all_data(), &nodes_before);
+    }
+
+    #[test]
+    fn test_filter_predicates() {
+        let node = ConditionalNode::new(MarkerNode, "$.nodes[?@.score > 0.8]").unwrap();
+        assert!(node.should_run(&context(false, 0.9)).unwrap());
+        assert!(!node.should_run(&context(false, 0.3)).unwrap());
+
+        // A path that matches nothing does not hold
+        let node = ConditionalNode::new(MarkerNode, "$.event_data.missing").unwrap();
+        assert!(!node.should_run(&context(true, 0.9)).unwrap());
+    }
+
+    #[test]
+    fn test_malformed_predicate_fails_at_construction() {
+        let result = ConditionalNode::new(MarkerNode, "$.event_data[");
+        assert!(matches!(
+            result,
+            Err(WorkflowError::ValidationError { ref field, .. }) if field == "predicate"
+        ));
+    }
+}
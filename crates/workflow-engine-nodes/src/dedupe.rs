@@ -0,0 +1,247 @@
+//! Deduplication nodes
+//!
+//! This module provides a node that drops repeated items from a list, e.g.
+//! the same article returned by several research sources.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use workflow_engine_core::prelude::*;
+
+use crate::utils::InputExtractor;
+
+/// How a [`DedupeNode`] decides two items are the same
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DedupeMode {
+    /// Identical content
+    Exact,
+    /// Identical once runs of whitespace are collapsed and the ends trimmed
+    NormalizedWhitespace,
+    /// Near-duplicates: the Jaccard similarity of the items' word shingles
+    /// is at least `threshold`. Case and whitespace are ignored.
+    Shingled { size: usize, threshold: f64 },
+}
+
+impl DedupeMode {
+    /// Shingles of three words, treating items at least 80% alike as duplicates
+    pub fn shingled() -> Self {
+        Self::Shingled {
+            size: 3,
+            threshold: 0.8,
+        }
+    }
+}
+
+/// Removes duplicate items from an array, keeping the first occurrence.
+///
+/// Items are compared by their text: a string item is its own text, other
+/// items are compared by their JSON, or by one of their fields if
+/// [`with_field`](DedupeNode::with_field) is set. The result, stored under
+/// `dedupe` by default, holds the remaining `items` and how many were
+/// `removed`.
+///
+/// ```rust
+/// use workflow_engine_nodes::dedupe::{DedupeMode, DedupeNode};
+/// use workflow_engine_nodes::utils::InputExtractor;
+///
+/// let node = DedupeNode::new(DedupeMode::shingled())
+///     .with_input(InputExtractor::new().node_result("search"))
+///     .with_field("content");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DedupeNode {
+    mode: DedupeMode,
+    input: InputExtractor,
+    field: Option<String>,
+    output_key: String,
+}
+
+impl DedupeNode {
+    /// Create a node deduplicating the event data, which must be an array
+    pub fn new(mode: DedupeMode) -> Self {
+        Self {
+            mode,
+            input: InputExtractor::new().event_data(),
+            field: None,
+            output_key: "dedupe".to_string(),
+        }
+    }
+
+    /// Read the array from `input` instead of the event data
+    pub fn with_input(mut self, input: InputExtractor) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Compare object items by this field only
+    pub fn with_field(mut self, field: impl Into<String>) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    pub fn mode(&self) -> DedupeMode {
+        self.mode
+    }
+
+    /// Drop the duplicates from `items`, returning the rest in order
+    pub fn dedupe(&self, items: Vec<Value>) -> Vec<Value> {
+        match self.mode {
+            DedupeMode::Exact => self.dedupe_by_hash(items, |text| text.to_string()),
+            DedupeMode::NormalizedWhitespace => self.dedupe_by_hash(items, normalize_whitespace),
+            DedupeMode::Shingled { size, threshold } => {
+                let mut kept: Vec<(Value, HashSet<u64>)> = Vec::new();
+                for item in items {
+                    let shingles = shingles(&self.text(&item), size);
+                    if !kept.iter().any(|(_, other)| jaccard(&shingles, other) >= threshold) {
+                        kept.push((item, shingles));
+                    }
+                }
+                kept.into_iter().map(|(item, _)| item).collect()
+            }
+        }
+    }
+
+    fn dedupe_by_hash(&self, items: Vec<Value>, normalize: impl Fn(&str) -> String) -> Vec<Value> {
+        let mut seen = HashSet::new();
+        items
+            .into_iter()
+            .filter(|item| seen.insert(hash(&normalize(&self.text(item)))))
+            .collect()
+    }
+
+    /// The text an item is compared by
+    fn text(&self, item: &Value) -> String {
+        let compared = match &self.field {
+            Some(field) => item.get(field).unwrap_or(&Value::Null),
+            None => item,
+        };
+        match compared {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl Node for DedupeNode {
+    fn node_name(&self) -> String {
+        "DedupeNode".to_string()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let items: Vec<Value> = self.input.extract_as(&task_context)?;
+        let total = items.len();
+        let items = self.dedupe(items);
+        let removed = total - items.len();
+        task_context.update_node(&self.output_key, json!({ "items": items, "removed": removed }));
+        Ok(task_context)
+    }
+}
+
+fn hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Hashes of every run of `size` consecutive words. A text shorter than
+/// `size` words is a single shingle.
+fn shingles(text: &str, size: usize) -> HashSet<u64> {
+    let lowercase = text.to_lowercase();
+    let words: Vec<&str> = lowercase.split_whitespace().collect();
+    if words.len() <= size {
+        return HashSet::from([hash(&words.join(" "))]);
+    }
+    words.windows(size.max(1)).map(|window| hash(&window.join(" "))).collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = "Rust 1.80 stabilises lazy cells, exclusive range patterns and more in the standard library";
+
+    fn items() -> Vec<Value> {
+        vec![
+            json!(ARTICLE),
+            json!("Tokio adds task dumps for debugging deadlocked async applications in production"),
+            json!(ARTICLE),
+            // Same text, different whitespace
+            json!(format!("  {}  ", ARTICLE.replace(' ', "\n "))),
+            // Same story, one word changed
+            json!(ARTICLE.replace("more", "others")),
+        ]
+    }
+
+    fn run(mode: DedupeMode) -> (Vec<Value>, u64) {
+        let context = TaskContext::new("dedupe_test".to_string(), json!(items()));
+        let context = DedupeNode::new(mode).process(context).unwrap();
+        let result: Value = context.get_node_data("dedupe").unwrap().unwrap();
+        (
+            result["items"].as_array().unwrap().clone(),
+            result["removed"].as_u64().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_exact_mode_removes_identical_items_only() {
+        let (kept, removed) = run(DedupeMode::Exact);
+        assert_eq!(removed, 1);
+        let items = items();
+        assert_eq!(kept, [&items[..2], &items[3..]].concat());
+    }
+
+    #[test]
+    fn test_normalized_mode_ignores_whitespace() {
+        let (kept, removed) = run(DedupeMode::NormalizedWhitespace);
+        assert_eq!(removed, 2);
+        let items = items();
+        assert_eq!(kept, vec![items[0].clone(), items[1].clone(), items[4].clone()]);
+    }
+
+    #[test]
+    fn test_shingled_mode_removes_near_duplicates() {
+        let (kept, removed) = run(DedupeMode::Shingled {
+            size: 2,
+            threshold: 0.7,
+        });
+        assert_eq!(removed, 3);
+        assert_eq!(kept, items()[..2].to_vec());
+
+        // A strict threshold keeps the edited story
+        let (_, removed) = run(DedupeMode::Shingled {
+            size: 2,
+            threshold: 0.95,
+        });
+        assert_eq!(removed, 2);
+    }
+
+    #[test]
+    fn test_dedupes_objects_by_field() {
+        let node = DedupeNode::new(DedupeMode::NormalizedWhitespace).with_field("title");
+        let kept = node.dedupe(vec![
+            json!({ "title": "Release notes", "source": "blog" }),
+            json!({ "title": "Release  notes", "source": "forum" }),
+            json!({ "title": "Roadmap", "source": "blog" }),
+        ]);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0]["source"], "blog");
+        assert_eq!(kept[1]["title"], "Roadmap");
+    }
+}
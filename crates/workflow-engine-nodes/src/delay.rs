@@ -0,0 +1,140 @@
+//! Pacing nodes
+//!
+//! This module provides nodes that delay workflow steps, e.g. to stay within
+//! a downstream API's rate limit.
+
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use workflow_engine_core::prelude::*;
+
+/// How a [`DelayNode`] decides how long to wait
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelayMode {
+    /// Wait the same duration on every execution
+    Fixed(Duration),
+    /// Wait until at least this long has passed since the node's previous execution
+    MinInterval(Duration),
+}
+
+/// Async node that waits before passing the context through unchanged.
+///
+/// The wait is a timer, so no thread is blocked. In
+/// [`DelayMode::MinInterval`] the last execution time is tracked per node
+/// instance, so concurrent executions sharing the node are spaced out.
+#[derive(Debug)]
+pub struct DelayNode {
+    mode: DelayMode,
+    last_execution: Mutex<Option<Instant>>,
+}
+
+impl DelayNode {
+    pub fn new(mode: DelayMode) -> Self {
+        Self {
+            mode,
+            last_execution: Mutex::new(None),
+        }
+    }
+
+    /// Wait `delay` on every execution
+    pub fn fixed(delay: Duration) -> Self {
+        Self::new(DelayMode::Fixed(delay))
+    }
+
+    /// Space executions at least `interval` apart
+    pub fn min_interval(interval: Duration) -> Self {
+        Self::new(DelayMode::MinInterval(interval))
+    }
+
+    pub fn mode(&self) -> DelayMode {
+        self.mode
+    }
+}
+
+#[async_trait]
+impl AsyncNode for DelayNode {
+    async fn process_async(&self, task_context: TaskContext) -> Result<TaskContext> {
+        match self.mode {
+            DelayMode::Fixed(delay) => tokio::time::sleep(delay).await,
+            DelayMode::MinInterval(interval) => {
+                // Holding the lock while waiting queues concurrent executions
+                let mut last_execution = self.last_execution.lock().await;
+                if let Some(last) = *last_execution {
+                    tokio::time::sleep_until(last + interval).await;
+                }
+                *last_execution = Some(Instant::now());
+            }
+        }
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn context() -> TaskContext {
+        TaskContext::new("delay_test".to_string(), json!({ "step": 1 }))
+    }
+
+    #[tokio::test]
+    async fn test_fixed_delay_waits_each_time() {
+        let node = DelayNode::fixed(Duration::from_millis(30));
+
+        let start = Instant::now();
+        let context = node.process_async(context()).await.unwrap();
+        node.process_async(context).await.unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[tokio::test]
+    async fn test_min_interval_spaces_invocations() {
+        let node = DelayNode::min_interval(Duration::from_millis(50));
+
+        // The first execution has nothing to wait for
+        let start = Instant::now();
+        node.process_async(context()).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        let mut completions = vec![Instant::now()];
+        for _ in 0..2 {
+            node.process_async(context()).await.unwrap();
+            completions.push(Instant::now());
+        }
+        for pair in completions.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(50));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_min_interval_counts_time_already_elapsed() {
+        let node = DelayNode::min_interval(Duration::from_millis(50));
+        node.process_async(context()).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let start = Instant::now();
+        node.process_async(context()).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn test_min_interval_spaces_concurrent_executions() {
+        let node = Arc::new(DelayNode::min_interval(Duration::from_millis(40)));
+
+        let start = Instant::now();
+        let executions: Vec<_> = (0..3)
+            .map(|_| {
+                let node = Arc::clone(&node);
+                tokio::spawn(async move { node.process_async(context()).await })
+            })
+            .collect();
+        for execution in executions {
+            let context = execution.await.unwrap().unwrap();
+            assert_eq!(context.event_data["step"], 1);
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+}
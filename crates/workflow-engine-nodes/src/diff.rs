@@ -0,0 +1,346 @@
+//! Text diff nodes
+//!
+//! This module provides a node that compares two versions of a text, e.g. a
+//! draft and its edited revision.
+
+use serde::Serialize;
+use workflow_engine_core::prelude::*;
+
+use crate::utils::InputExtractor;
+
+/// Lines of unchanged context around each hunk of a unified diff
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Unit a [`DiffNode`] compares texts in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffGranularity {
+    Line,
+    /// Words, with the whitespace between them kept as unchanged spans
+    /// wherever it is
+    Word,
+}
+
+/// Whether a span was added, removed or kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+/// A run of text with the same [`SpanKind`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffSpan {
+    pub kind: SpanKind,
+    pub text: String,
+}
+
+/// A comparison of two texts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextDiff {
+    /// Spans in order; the unchanged and removed spans spell the old text,
+    /// the unchanged and added spans the new one
+    pub spans: Vec<DiffSpan>,
+    /// Line-based diff in unified format, empty if the texts are equal
+    pub unified: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Compares two text values from the context.
+///
+/// The result is a [`TextDiff`] stored as a node result (under `diff` by
+/// default). The `added` and `removed` counts are in units of the
+/// configured [`DiffGranularity`].
+///
+/// ```rust
+/// use workflow_engine_nodes::diff::{DiffGranularity, DiffNode};
+///
+/// let node = DiffNode::new("draft", "edited").with_granularity(DiffGranularity::Word);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiffNode {
+    before: InputExtractor,
+    after: InputExtractor,
+    granularity: DiffGranularity,
+    context_lines: usize,
+    output_key: String,
+}
+
+impl DiffNode {
+    /// Compare the values under `before_key` and `after_key`, looking in
+    /// node results first and then in the event data
+    pub fn new(before_key: impl Into<String>, after_key: impl Into<String>) -> Self {
+        let (before_key, after_key) = (before_key.into(), after_key.into());
+        Self {
+            before: InputExtractor::new().node_result(&before_key).event_field(before_key),
+            after: InputExtractor::new().node_result(&after_key).event_field(after_key),
+            granularity: DiffGranularity::Line,
+            context_lines: DEFAULT_CONTEXT_LINES,
+            output_key: "diff".to_string(),
+        }
+    }
+
+    /// Read the old text from `before` instead
+    pub fn with_before(mut self, before: InputExtractor) -> Self {
+        self.before = before;
+        self
+    }
+
+    /// Read the new text from `after` instead
+    pub fn with_after(mut self, after: InputExtractor) -> Self {
+        self.after = after;
+        self
+    }
+
+    pub fn with_granularity(mut self, granularity: DiffGranularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Lines of context around each hunk of the unified diff
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Compare `before` with `after`
+    pub fn diff(&self, before: &str, after: &str) -> TextDiff {
+        let tokenize = match self.granularity {
+            DiffGranularity::Line => lines,
+            DiffGranularity::Word => words,
+        };
+        let ops = diff_tokens(&tokenize(before), &tokenize(after));
+
+        let count = |kind: SpanKind| {
+            ops.iter()
+                .filter(|(k, token)| *k == kind && !token.trim().is_empty())
+                .count()
+        };
+        let (added, removed) = (count(SpanKind::Added), count(SpanKind::Removed));
+
+        let mut spans: Vec<DiffSpan> = Vec::new();
+        for (kind, token) in ops {
+            match spans.last_mut() {
+                Some(span) if span.kind == kind => span.text.push_str(token),
+                _ => spans.push(DiffSpan {
+                    kind,
+                    text: token.to_string(),
+                }),
+            }
+        }
+
+        TextDiff {
+            spans,
+            unified: unified_diff(&diff_tokens(&lines(before), &lines(after)), self.context_lines),
+            added,
+            removed,
+        }
+    }
+}
+
+impl Node for DiffNode {
+    fn node_name(&self) -> String {
+        "DiffNode".to_string()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let before: String = self.before.extract_as(&task_context)?;
+        let after: String = self.after.extract_as(&task_context)?;
+        task_context.update_node(&self.output_key, self.diff(&before, &after));
+        Ok(task_context)
+    }
+}
+
+/// Lines, each keeping its line break
+fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// Alternating runs of whitespace and non-whitespace
+fn words(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (i, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if in_space.is_some_and(|in_space| in_space != space) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// Edit script turning `old` into `new`, from their longest common subsequence
+fn diff_tokens<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(SpanKind, &'a str)> {
+    // common[i][j] is the LCS length of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push((SpanKind::Unchanged, old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            ops.push((SpanKind::Removed, old[i]));
+            i += 1;
+        } else {
+            ops.push((SpanKind::Added, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|token| (SpanKind::Removed, *token)));
+    ops.extend(new[j..].iter().map(|token| (SpanKind::Added, *token)));
+    ops
+}
+
+/// Format line operations as a unified diff
+fn unified_diff(ops: &[(SpanKind, &str)], context: usize) -> String {
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (kind, _))| *kind != SpanKind::Unchanged)
+        .map(|(i, _)| i)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Ranges of operations to show, merged where their context overlaps
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        let (start, end) = (i.saturating_sub(context), (i + context + 1).min(ops.len()));
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut output = String::from("--- before\n+++ after\n");
+    for (start, end) in hunks {
+        let lines_in = |range: &[(SpanKind, &str)], kind: SpanKind| {
+            range.iter().filter(|(k, _)| *k == SpanKind::Unchanged || *k == kind).count()
+        };
+        let (old_before, new_before) = (
+            lines_in(&ops[..start], SpanKind::Removed),
+            lines_in(&ops[..start], SpanKind::Added),
+        );
+        let (old_count, new_count) = (
+            lines_in(&ops[start..end], SpanKind::Removed),
+            lines_in(&ops[start..end], SpanKind::Added),
+        );
+        // An empty range starts at the line before it
+        let first_line = |before: usize, count: usize| if count == 0 { before } else { before + 1 };
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            first_line(old_before, old_count),
+            old_count,
+            first_line(new_before, new_count),
+            new_count
+        ));
+
+        for (kind, line) in &ops[start..end] {
+            let prefix = match kind {
+                SpanKind::Unchanged => ' ',
+                SpanKind::Removed => '-',
+                SpanKind::Added => '+',
+            };
+            output.push(prefix);
+            output.push_str(line.strip_suffix('\n').unwrap_or(line));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = "The quick brown fox jumps over the lazy dog.";
+    const AFTER: &str = "The quick red fox leaps over the lazy dog.";
+
+    fn spans(diff: &TextDiff, kind: SpanKind) -> Vec<&str> {
+        diff.spans
+            .iter()
+            .filter(|span| span.kind == kind)
+            .map(|span| span.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_word_diff_identifies_changed_words() {
+        let node = DiffNode::new("before", "after").with_granularity(DiffGranularity::Word);
+        let diff = node.diff(BEFORE, AFTER);
+
+        assert_eq!(spans(&diff, SpanKind::Removed), ["brown", "jumps"]);
+        assert_eq!(spans(&diff, SpanKind::Added), ["red", "leaps"]);
+        assert_eq!((diff.added, diff.removed), (2, 2));
+
+        // The spans rebuild both versions
+        let rebuild = |skip: SpanKind| {
+            diff.spans
+                .iter()
+                .filter(|span| span.kind != skip)
+                .map(|span| span.text.as_str())
+                .collect::<String>()
+        };
+        assert_eq!(rebuild(SpanKind::Added), BEFORE);
+        assert_eq!(rebuild(SpanKind::Removed), AFTER);
+    }
+
+    #[test]
+    fn test_line_diff_and_unified_output() {
+        let before = "one\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+        let after = "one\ntwo\nthree\n4\nfive\nsix\nseven\neight\n";
+        let diff = DiffNode::new("before", "after").with_context_lines(1).diff(before, after);
+
+        assert_eq!(spans(&diff, SpanKind::Removed), ["four\n"]);
+        assert_eq!(spans(&diff, SpanKind::Added), ["4\n", "eight\n"]);
+        assert_eq!(
+            diff.unified,
+            "--- before\n+++ after\n\
+             @@ -3,3 +3,3 @@\n three\n-four\n+4\n five\n\
+             @@ -7,1 +7,2 @@\n seven\n+eight\n"
+        );
+
+        assert!(DiffNode::new("a", "b").diff(before, before).unified.is_empty());
+    }
+
+    #[test]
+    fn test_node_diffs_context_values() {
+        let mut context = TaskContext::new("diff_test".to_string(), json!({ "draft": BEFORE }));
+        context.update_node("edited", AFTER);
+
+        let node = DiffNode::new("draft", "edited").with_granularity(DiffGranularity::Word);
+        let context = node.process(context).unwrap();
+        let diff: Value = context.get_node_data("diff").unwrap().unwrap();
+        assert_eq!(diff["added"], 2);
+        assert_eq!(diff["spans"][1], json!({ "kind": "removed", "text": "brown" }));
+
+        let missing = TaskContext::new("diff_test".to_string(), json!({ "draft": BEFORE }));
+        assert!(node.process(missing).is_err());
+    }
+}
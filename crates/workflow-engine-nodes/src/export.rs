@@ -0,0 +1,316 @@
+//! Export nodes
+//!
+//! This module provides nodes that serialize workflow outputs to CSV or JSON.
+
+use std::path::PathBuf;
+use workflow_engine_core::prelude::*;
+
+/// Output format of an [`ExportNode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// Compact JSON
+    Json,
+    /// Indented JSON
+    PrettyJson,
+}
+
+/// Serializes selected node results to CSV or JSON.
+///
+/// The serialized string is stored as a node result (under `export` by
+/// default) and, if a file path is configured, also written to that file.
+///
+/// JSON output is an object mapping each selected key to its value. CSV output
+/// has one row per array element (or one row per non-array value) across all
+/// selected keys. Nested objects are flattened into dotted column names such
+/// as `user.address.city`, and arrays inside a row are written as JSON.
+#[derive(Debug, Clone)]
+pub struct ExportNode {
+    format: ExportFormat,
+    keys: Vec<String>,
+    output_key: String,
+    file_path: Option<PathBuf>,
+}
+
+impl ExportNode {
+    /// Create a node exporting every node result in `format`
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            keys: Vec::new(),
+            output_key: "export".to_string(),
+            file_path: None,
+        }
+    }
+
+    /// Export only the results of the given nodes
+    pub fn with_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Also write the serialized output to `path`
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file_path = Some(path.into());
+        self
+    }
+
+    /// Collect the selected `(key, value)` pairs from the context
+    fn selected<'a>(&'a self, context: &'a TaskContext) -> Result<Vec<(&'a str, &'a Value)>> {
+        let nodes = context.get_all_data();
+        if self.keys.is_empty() {
+            let mut selected: Vec<_> = nodes
+                .iter()
+                .filter(|(key, _)| **key != self.output_key)
+                .map(|(key, value)| (key.as_str(), value))
+                .collect();
+            selected.sort_by_key(|(key, _)| *key);
+            return Ok(selected);
+        }
+
+        self.keys
+            .iter()
+            .map(|key| {
+                nodes
+                    .get(key)
+                    .map(|value| (key.as_str(), value))
+                    .ok_or_else(|| {
+                        WorkflowError::validation_error(
+                            format!("No result for '{}' to export", key),
+                            key,
+                            "key must name an existing node result",
+                            "in ExportNode::process",
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Serialize the selected results of `context`
+    pub fn export(&self, context: &TaskContext) -> Result<String> {
+        let selected = self.selected(context)?;
+        match self.format {
+            ExportFormat::Csv => to_csv(&selected),
+            ExportFormat::Json | ExportFormat::PrettyJson => {
+                let object: serde_json::Map<String, Value> = selected
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value.clone()))
+                    .collect();
+                let object = Value::Object(object);
+                let serialized = if self.format == ExportFormat::PrettyJson {
+                    serde_json::to_string_pretty(&object)
+                } else {
+                    serde_json::to_string(&object)
+                };
+                serialized.map_err(|e| WorkflowError::SerializationError {
+                    message: format!("Failed to serialize export: {}", e),
+                    type_name: "Value".to_string(),
+                    context: "in ExportNode::export".to_string(),
+                    source: Some(e),
+                })
+            }
+        }
+    }
+}
+
+/// Flatten `value` into `row`, joining nested object keys with dots
+fn flatten(prefix: &str, value: &Value, row: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, nested) in object {
+                let column = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&column, nested, row);
+            }
+        }
+        Value::Null => row.push((prefix.to_string(), String::new())),
+        Value::String(s) => row.push((prefix.to_string(), s.clone())),
+        other => row.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+fn to_csv(selected: &[(&str, &Value)]) -> Result<String> {
+    let rows: Vec<Vec<(String, String)>> = selected
+        .iter()
+        .flat_map(|(_, value)| match value {
+            Value::Array(items) => items.iter().collect::<Vec<_>>(),
+            other => vec![*other],
+        })
+        .map(|value| {
+            let mut row = Vec::new();
+            let prefix = if value.is_object() { "" } else { "value" };
+            flatten(prefix, value, &mut row);
+            row
+        })
+        .collect();
+
+    // Columns appear in the order they are first seen
+    let mut headers: Vec<&str> = Vec::new();
+    for row in &rows {
+        for (column, _) in row {
+            if !headers.contains(&column.as_str()) {
+                headers.push(column);
+            }
+        }
+    }
+
+    let csv_error = |e: csv::Error| WorkflowError::SerializationError {
+        message: format!("Failed to write CSV: {}", e),
+        type_name: "Value".to_string(),
+        context: "in ExportNode::export".to_string(),
+        source: None,
+    };
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&headers).map_err(csv_error)?;
+    for row in &rows {
+        let record = headers.iter().map(|header| {
+            row.iter()
+                .find(|(column, _)| column == header)
+                .map(|(_, value)| value.as_str())
+                .unwrap_or("")
+        });
+        writer.write_record(record).map_err(csv_error)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| WorkflowError::SerializationError {
+        message: format!("Failed to write CSV: {}", e),
+        type_name: "Value".to_string(),
+        context: "in ExportNode::export".to_string(),
+        source: None,
+    })?;
+    // The writer only ever receives UTF-8 input
+    Ok(String::from_utf8(bytes).expect("CSV output is valid UTF-8"))
+}
+
+impl Node for ExportNode {
+    fn node_name(&self) -> String {
+        "ExportNode".to_string()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let exported = self.export(&task_context)?;
+
+        if let Some(path) = &self.file_path {
+            std::fs::write(path, &exported).map_err(|e| {
+                WorkflowError::processing_error_with_context(
+                    format!("Failed to write export to {}: {}", path.display(), e),
+                    "ExportNode",
+                    None,
+                    Some(Box::new(e)),
+                )
+            })?;
+        }
+
+        task_context.update_node(&self.output_key, exported);
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TaskContext {
+        let mut context = TaskContext::new("export_test".to_string(), json!({}));
+        context.update_node(
+            "orders",
+            json!([
+                { "id": 1, "customer": { "name": "Ada", "city": "London" }, "total": 12.5 },
+                { "id": 2, "customer": { "name": "Grace, H.", "city": null }, "total": 30 }
+            ]),
+        );
+        context.update_node("summary", json!({ "count": 2, "tags": ["a", "b"] }));
+        context
+    }
+
+    #[test]
+    fn test_csv_flattens_nested_objects() {
+        let node = ExportNode::new(ExportFormat::Csv).with_keys(["orders"]);
+
+        let context = node.process(context()).unwrap();
+        let csv: String = context.get_node_data("export").unwrap().unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "customer.city,customer.name,id,total",
+                "London,Ada,1,12.5",
+                ",\"Grace, H.\",2,30",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_merges_columns_across_keys() {
+        let csv = ExportNode::new(ExportFormat::Csv)
+            .with_keys(["summary", "orders"])
+            .export(&context())
+            .unwrap();
+
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let headers: Vec<_> = reader.headers().unwrap().iter().map(String::from).collect();
+        assert_eq!(headers, ["count", "tags", "customer.city", "customer.name", "id", "total"]);
+        let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(&rows[0][1], "[\"a\",\"b\"]");
+        assert_eq!(&rows[1][0], "");
+    }
+
+    #[test]
+    fn test_json_compact_and_pretty() {
+        let compact = ExportNode::new(ExportFormat::Json)
+            .with_keys(["summary"])
+            .export(&context())
+            .unwrap();
+        assert_eq!(compact, r#"{"summary":{"count":2,"tags":["a","b"]}}"#);
+
+        let pretty = ExportNode::new(ExportFormat::PrettyJson)
+            .with_keys(["orders", "summary"])
+            .export(&context())
+            .unwrap();
+        assert!(pretty.contains('\n'));
+        let parsed: Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(parsed["orders"][1]["customer"]["name"], "Grace, H.");
+        assert_eq!(parsed["summary"]["count"], 2);
+    }
+
+    #[test]
+    fn test_writes_file_and_exports_all_results_by_default() {
+        let path = std::env::temp_dir().join(format!("export_test_{}.json", std::process::id()));
+        let node = ExportNode::new(ExportFormat::Json)
+            .with_output_key("report")
+            .with_file(&path);
+
+        let context = node.process(context()).unwrap();
+        let exported: String = context.get_node_data("report").unwrap().unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(written, exported);
+        let parsed: Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(parsed.as_object().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_missing_key_is_rejected() {
+        let result = ExportNode::new(ExportFormat::Csv)
+            .with_keys(["missing"])
+            .export(&context());
+        assert!(matches!(
+            result,
+            Err(WorkflowError::ValidationError { ref field, .. }) if field == "missing"
+        ));
+    }
+}
@@ -6,6 +6,19 @@
 use workflow_engine_core::prelude::*;
 use workflow_engine_mcp::prelude::*;
 
+pub mod client;
+pub mod notion;
+pub mod slack;
+pub mod stdio;
+
+pub use client::{
+    AuthConfig, BaseExternalMcpClient, ExternalMcpClientNode, ExternalMcpConfig, HealthStatus,
+    RetryConfig,
+};
+pub use notion::NotionClientNode;
+pub use slack::SlackClientNode;
+pub use stdio::StdioMcpClientNode;
+
 /// External MCP client node
 #[derive(Debug)]
 pub struct ExternalMCPClientNode {
@@ -0,0 +1,2134 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use workflow_engine_core::error::{RetryPolicy, WorkflowError};
+use workflow_engine_core::nodes::Node;
+use workflow_engine_core::task::TaskContext;
+use workflow_engine_mcp::clients::{McpClient, StdioMcpClient, ToolCall, WebSocketMcpClient};
+use workflow_engine_mcp::connection_pool::McpConnectionPool;
+use workflow_engine_mcp::protocol::{
+    CallToolResult, McpError, McpResponse, ResponseResult, ToolDefinition,
+};
+use workflow_engine_mcp::transport::TransportType;
+
+/// Configuration for external MCP server connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalMcpConfig {
+    /// Name of the external service (e.g., "notion", "slack", "helpscout")
+    pub service_name: String,
+
+    /// Transport configuration for connecting to the MCP server
+    pub transport: TransportType,
+
+    /// Optional authentication configuration
+    pub auth: Option<AuthConfig>,
+
+    /// Retry configuration
+    pub retry_config: RetryConfig,
+
+    /// Optional connection pool shared between client nodes. When set, tool
+    /// calls borrow a pooled connection instead of holding a dedicated one.
+    #[serde(skip)]
+    pub connection_pool: Option<Arc<McpConnectionPool>>,
+}
+
+/// Authentication configuration for external MCP servers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// API key or token
+    pub token: Option<String>,
+
+    /// Additional headers for HTTP transport
+    pub headers: Option<HashMap<String, String>>,
+}
+
+/// Retry configuration for connections and tool calls.
+///
+/// Mirrors the core [`RetryPolicy`]: only transient errors (connection and
+/// transport failures, 5xx responses) are retried, up to `max_attempts`
+/// times after the first attempt. The delay before retry `n` is
+/// `initial_delay * multiplier^(n-1)`, capped at `max_delay`, then varied by
+/// up to `jitter` of itself either way. Delays serialize as milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Maximum number of retries after the first attempt
+    #[serde(alias = "max_retries")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry
+    #[serde(alias = "initial_delay_ms", with = "duration_millis")]
+    pub initial_delay: Duration,
+
+    /// Maximum delay between retries
+    #[serde(alias = "max_delay_ms", with = "duration_millis")]
+    pub max_delay: Duration,
+
+    /// Backoff multiplier for exponential backoff
+    #[serde(alias = "backoff_multiplier")]
+    pub multiplier: f64,
+
+    /// Random jitter applied to each delay, as a fraction of the delay (0.0 to 1.0)
+    #[serde(alias = "jitter_factor", default = "RetryConfig::default_jitter")]
+    pub jitter: f64,
+}
+
+impl RetryConfig {
+    fn default_jitter() -> f64 {
+        0.1
+    }
+
+    /// The equivalent core retry policy
+    pub fn to_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts,
+            initial_delay: self.initial_delay,
+            max_delay: self.max_delay,
+            multiplier: self.multiplier,
+            jitter_factor: self.jitter,
+            retry_on: None,
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1000),
+            max_delay: Duration::from_millis(30000),
+            multiplier: 2.0,
+            jitter: Self::default_jitter(),
+        }
+    }
+}
+
+mod duration_millis {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (duration.as_millis() as u64).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = u64::deserialize(deserializer)?;
+        Ok(Duration::from_millis(millis))
+    }
+}
+
+/// Base trait for nodes that connect to external MCP servers
+#[async_trait]
+pub trait ExternalMcpClientNode: Node + Send + Sync {
+    /// Get the configuration for this external MCP client
+    fn get_config(&self) -> &ExternalMcpConfig;
+
+    /// Connect to the external MCP server
+    async fn connect(&mut self) -> Result<(), WorkflowError>;
+
+    /// Execute a tool on the external MCP server
+    async fn execute_tool(
+        &mut self,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError>;
+
+    /// List available tools from the external MCP server
+    async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError>;
+
+    /// Disconnect from the external MCP server
+    async fn disconnect(&mut self) -> Result<(), WorkflowError>;
+
+    /// Check if the client is connected
+    fn is_connected(&self) -> bool;
+
+    /// Execute several tools, returning one result per call in order.
+    ///
+    /// The default runs the calls one after another; HTTP clients send them
+    /// as a single JSON-RPC batch when the server accepts one.
+    async fn execute_tools_batch(
+        &mut self,
+        calls: Vec<ToolCall>,
+    ) -> Vec<Result<CallToolResult, WorkflowError>> {
+        let mut results = Vec::with_capacity(calls.len());
+        for (tool_name, arguments) in calls {
+            results.push(self.execute_tool(&tool_name, arguments).await);
+        }
+        results
+    }
+
+    /// Check reachability and latency with a lightweight `tools/list` call,
+    /// connecting first if needed. Failures are reported in the returned
+    /// status rather than as errors.
+    async fn health_check(&mut self) -> Result<HealthStatus, WorkflowError> {
+        let service_name = self.get_config().service_name.clone();
+        let start = Instant::now();
+
+        let result = if self.is_connected() {
+            self.list_tools().await
+        } else {
+            match self.connect().await {
+                Ok(()) => self.list_tools().await,
+                Err(e) => Err(e),
+            }
+        };
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        Ok(match result {
+            Ok(_) => HealthStatus {
+                service_name,
+                healthy: true,
+                reachable: true,
+                latency_ms,
+                error: None,
+            },
+            Err(e) => HealthStatus {
+                service_name,
+                healthy: false,
+                reachable: !matches!(e, WorkflowError::MCPConnectionError { .. }),
+                latency_ms,
+                error: Some(e.to_string()),
+            },
+        })
+    }
+}
+
+/// Result of [`ExternalMcpClientNode::health_check`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// Name of the external service
+    pub service_name: String,
+
+    /// Whether the server answered the health probe successfully
+    pub healthy: bool,
+
+    /// Whether the server could be reached at all
+    pub reachable: bool,
+
+    /// Time taken by the probe, including connecting, in milliseconds
+    pub latency_ms: u64,
+
+    /// Error that made the server unhealthy
+    pub error: Option<String>,
+}
+
+/// Base implementation for external MCP client nodes
+pub struct BaseExternalMcpClient {
+    config: ExternalMcpConfig,
+    client: Option<Box<dyn McpClient>>,
+    pool_registered: bool,
+}
+
+impl Debug for BaseExternalMcpClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BaseExternalMcpClient")
+            .field("config", &self.config)
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
+
+impl BaseExternalMcpClient {
+    pub fn new(config: ExternalMcpConfig) -> Self {
+        Self {
+            config,
+            client: None,
+            pool_registered: false,
+        }
+    }
+
+    /// Call a tool on a connection borrowed from the configured pool.
+    ///
+    /// Takes `&self`, so concurrent workflow executions can share one client
+    /// node and reuse the pool's connections.
+    pub async fn execute_pooled_tool(
+        &self,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let pool = self.registered_pool()?;
+        let mut attempt = 0;
+
+        loop {
+            match self.call_pooled_tool(pool, tool_name, arguments.clone()).await {
+                Err(e) => match self.retry_delay("Tool call", &e, attempt) {
+                    Some(delay) => {
+                        attempt += 1;
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => return Err(e),
+                },
+                result => return result,
+            }
+        }
+    }
+
+    /// Backoff before retrying a failed `operation`, or `None` if the error
+    /// is not transient or `attempt` retries have used up the retry budget
+    fn retry_delay(&self, operation: &str, error: &WorkflowError, attempt: u32) -> Option<Duration> {
+        let policy = self.config.retry_config.to_policy();
+        if !policy.should_retry(error, attempt) {
+            return None;
+        }
+        let delay = policy.calculate_delay(attempt + 1);
+        log::warn!(
+            "[{}] {} failed, retry {} of {} in {:?}: {}",
+            self.config.service_name,
+            operation,
+            attempt + 1,
+            policy.max_attempts,
+            delay,
+            error
+        );
+        Some(delay)
+    }
+
+    async fn call_pooled_tool(
+        &self,
+        pool: &McpConnectionPool,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let connection = pool.get_connection(&self.config.service_name).await?;
+        let arguments = arguments
+            .map(|args| serde_json::Value::Object(args.into_iter().collect()))
+            .unwrap_or(serde_json::Value::Null);
+
+        match connection.call_tool(tool_name, arguments).await? {
+            McpResponse::Result {
+                result: ResponseResult::CallTool(result),
+                ..
+            } => Ok(result),
+            McpResponse::Error { error, .. } => Err(WorkflowError::mcp_error(
+                format!("Tool {} failed: {}", tool_name, error.message),
+                &self.config.service_name,
+                "call_tool",
+            )),
+            other => Err(WorkflowError::mcp_protocol_error(
+                "Unexpected response to tool call",
+                &self.config.service_name,
+                "CallToolResult",
+                format!("{:?}", other),
+                "response",
+            )),
+        }
+    }
+
+    fn registered_pool(&self) -> Result<&Arc<McpConnectionPool>, WorkflowError> {
+        match &self.config.connection_pool {
+            Some(pool) if self.pool_registered => Ok(pool),
+            _ => Err(self.not_connected_error()),
+        }
+    }
+
+    fn not_connected_error(&self) -> WorkflowError {
+        WorkflowError::mcp_connection_error(
+            format!("{} client not connected", self.config.service_name),
+            &self.config.service_name,
+            transport_name(&self.config.transport),
+            transport_endpoint(&self.config.transport),
+        )
+    }
+
+    /// Create an MCP client based on the transport type
+    fn create_client(&self) -> Result<Box<dyn McpClient>, WorkflowError> {
+        match &self.config.transport {
+            TransportType::Stdio { command, args, .. } => {
+                Ok(Box::new(StdioMcpClient::new(command.clone(), args.clone())))
+            }
+            TransportType::WebSocket { url, .. } => Ok(Box::new(WebSocketMcpClient::new(url.clone()))),
+            TransportType::Http { base_url, .. } => {
+                // For HTTP transport, we need a custom implementation
+                // that handles request/response pattern
+                Ok(Box::new(HttpMcpClient::new(
+                    base_url.clone(),
+                    self.config.auth.clone(),
+                )))
+            }
+        }
+    }
+
+    /// Connect, retrying transient failures under the retry configuration
+    async fn connect_with_retry(&mut self) -> Result<(), WorkflowError> {
+        let mut attempt = 0;
+
+        loop {
+            match self.connect_internal().await {
+                Err(e) => match self.retry_delay("Connection attempt", &e, attempt) {
+                    Some(delay) => {
+                        attempt += 1;
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => return Err(e),
+                },
+                result => return result,
+            }
+        }
+    }
+
+    /// Internal connection logic
+    async fn connect_internal(&mut self) -> Result<(), WorkflowError> {
+        let mut client = self.create_client()?;
+        client.connect().await?;
+        client
+            .initialize(&self.config.service_name, "1.0.0")
+            .await?;
+        self.client = Some(client);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExternalMcpClientNode for BaseExternalMcpClient {
+    fn get_config(&self) -> &ExternalMcpConfig {
+        &self.config
+    }
+
+    async fn connect(&mut self) -> Result<(), WorkflowError> {
+        if let Some(pool) = &self.config.connection_pool {
+            pool.register_server(
+                self.config.service_name.clone(),
+                self.config.transport.clone(),
+                self.config.service_name.clone(),
+                "1.0.0".to_string(),
+            )
+            .await;
+            self.pool_registered = true;
+            return Ok(());
+        }
+        self.connect_with_retry().await
+    }
+
+    async fn execute_tool(
+        &mut self,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        if self.config.connection_pool.is_some() {
+            return self.execute_pooled_tool(tool_name, arguments).await;
+        }
+        let mut attempt = 0;
+
+        loop {
+            let result = match self.client.as_mut() {
+                Some(client) => client.call_tool(tool_name, arguments.clone()).await,
+                None => return Err(self.not_connected_error()),
+            };
+            match result {
+                Err(e) => match self.retry_delay("Tool call", &e, attempt) {
+                    Some(delay) => {
+                        attempt += 1;
+                        tokio::time::sleep(delay).await;
+                        // A dropped stdio or websocket connection has to be re-established
+                        if !self.is_connected() {
+                            if let Err(e) = self.connect_internal().await {
+                                log::warn!(
+                                    "[{}] Reconnect before retry failed: {}",
+                                    self.config.service_name,
+                                    e
+                                );
+                            }
+                        }
+                    }
+                    None => return Err(e),
+                },
+                result => return result,
+            }
+        }
+    }
+
+    async fn execute_tools_batch(
+        &mut self,
+        calls: Vec<ToolCall>,
+    ) -> Vec<Result<CallToolResult, WorkflowError>> {
+        // Pooled connections only expose single calls
+        if self.config.connection_pool.is_some() {
+            let mut results = Vec::with_capacity(calls.len());
+            for (tool_name, arguments) in calls {
+                results.push(self.execute_pooled_tool(&tool_name, arguments).await);
+            }
+            return results;
+        }
+        match self.client.as_mut() {
+            Some(client) => client.call_tools_batch(calls).await,
+            None => calls.iter().map(|_| Err(self.not_connected_error())).collect(),
+        }
+    }
+
+    async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError> {
+        if self.config.connection_pool.is_some() {
+            let pool = self.registered_pool()?;
+            return pool
+                .get_connection(&self.config.service_name)
+                .await?
+                .list_tools()
+                .await;
+        }
+        if let Some(ref mut client) = self.client {
+            client.list_tools().await
+        } else {
+            Err(self.not_connected_error())
+        }
+    }
+
+    async fn disconnect(&mut self) -> Result<(), WorkflowError> {
+        if let Some(ref mut client) = self.client {
+            client.disconnect().await?;
+        }
+        self.client = None;
+        // Pooled connections belong to the pool and stay open for other nodes
+        self.pool_registered = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.pool_registered
+            || self
+                .client
+                .as_ref()
+                .map(|c| c.is_connected())
+                .unwrap_or(false)
+    }
+}
+
+impl Node for BaseExternalMcpClient {
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        task_context.set_data("external_mcp_client_processed", true)?;
+        Ok(task_context)
+    }
+}
+
+/// Short transport name used in error reporting
+pub(crate) fn transport_name(transport: &TransportType) -> &'static str {
+    match transport {
+        TransportType::Stdio { .. } => "stdio",
+        TransportType::WebSocket { .. } => "websocket",
+        TransportType::Http { .. } => "http",
+    }
+}
+
+/// Endpoint (command or URL) used in error reporting
+pub(crate) fn transport_endpoint(transport: &TransportType) -> String {
+    match transport {
+        TransportType::Stdio { command, .. } => command.clone(),
+        TransportType::WebSocket { url, .. } => url.clone(),
+        TransportType::Http { base_url, .. } => base_url.clone(),
+    }
+}
+
+/// Shared [`Node::process`] for service client nodes.
+///
+/// Calls the tool named by `tool_name` in the event data with its `arguments`
+/// object on a dedicated connection, storing the result under the service name.
+/// The call goes through [`TaskContext::external_call_async`], so recorded
+/// runs keep its result and replays answer it without connecting.
+pub(crate) fn process_tool_call(
+    config: &ExternalMcpConfig,
+    node_name: &str,
+    mut task_context: TaskContext,
+) -> Result<TaskContext, WorkflowError> {
+    let event: serde_json::Value = task_context.get_event_data()?;
+    let tool_name = event
+        .get("tool_name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| WorkflowError::validation_error(
+            "Missing 'tool_name' in event data",
+            "tool_name",
+            "string naming the MCP tool to call",
+            format!("in {}::process", node_name),
+        ))?
+        .to_string();
+    let arguments = event
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .map(|args| args.clone().into_iter().collect::<HashMap<_, _>>());
+
+    let request = serde_json::json!({ "tool_name": tool_name, "arguments": arguments });
+    // The whole call, including connecting, must fit within the workflow deadline
+    let call = task_context.external_call_async(&config.service_name, request, || async {
+        let mut client = BaseExternalMcpClient::new(config.clone());
+        client.connect().await?;
+        let result = client.execute_tool(&tool_name, arguments).await;
+        let _ = client.disconnect().await;
+        serde_json::to_value(result?).map_err(|e| WorkflowError::SerializationError {
+            message: format!("Failed to serialize tool result: {}", e),
+            type_name: "CallToolResult".to_string(),
+            context: format!("for tool '{}'", tool_name),
+            source: Some(e),
+        })
+    });
+    let result = block_on(task_context.within_deadline(node_name, call))?;
+
+    task_context.update_node(&config.service_name, serde_json::json!({
+        "tool_name": tool_name,
+        "result": result,
+    }));
+    Ok(task_context)
+}
+
+/// Runtime driving tool calls made from outside any runtime
+static TOOL_CALL_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+/// Waits for `future` from synchronous node code, wherever it is called.
+///
+/// On a multi-threaded runtime the worker blocks in place. Elsewhere the
+/// future runs on a runtime shared by all tool calls, from a helper thread
+/// if the caller is itself on a current-thread runtime, which can't block.
+fn block_on<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send,
+    F::Output: Send,
+{
+    let shared = || {
+        TOOL_CALL_RUNTIME.get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .thread_name("mcp-tool-call")
+                .enable_all()
+                .build()
+                .expect("failed to build the MCP tool call runtime")
+        })
+    };
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        Ok(_) => std::thread::scope(|scope| {
+            scope
+                .spawn(|| shared().block_on(future))
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        }),
+        Err(_) => shared().block_on(future),
+    }
+}
+
+/// HTTP MCP Client implementation
+#[derive(Debug)]
+struct HttpMcpClient {
+    base_url: String,
+    auth: Option<AuthConfig>,
+    client: reqwest::Client,
+    is_connected: bool,
+    /// Whether the server accepts JSON-RPC batches; unknown until first tried
+    supports_batch: Option<bool>,
+}
+
+/// One entry of a JSON-RPC batch response
+#[derive(Debug, Deserialize)]
+struct BatchResponse {
+    id: serde_json::Value,
+    #[serde(default)]
+    result: Option<CallToolResult>,
+    #[serde(default)]
+    error: Option<McpError>,
+}
+
+impl HttpMcpClient {
+    fn new(base_url: String, auth: Option<AuthConfig>) -> Self {
+        Self {
+            base_url,
+            auth,
+            client: reqwest::Client::new(),
+            is_connected: false,
+            supports_batch: None,
+        }
+    }
+
+    /// Send all calls as one JSON-RPC batch of `tools/call` requests to the
+    /// server's `/mcp` endpoint, matching responses to calls by id. Returns
+    /// `Ok(None)` if the server doesn't accept batches.
+    async fn post_batch(
+        &self,
+        calls: &[ToolCall],
+    ) -> Result<Option<Vec<Result<CallToolResult, WorkflowError>>>, WorkflowError> {
+        let url = format!("{}/mcp", self.base_url);
+        let body = calls
+            .iter()
+            .enumerate()
+            .map(|(id, (name, arguments))| serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": arguments },
+            }))
+            .collect::<Vec<_>>();
+
+        let response = self
+            .authorize(self.client.post(&url))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                WorkflowError::mcp_connection_error(
+                    format!("Failed to call tool batch: {}", e),
+                    &self.base_url,
+                    "http",
+                    &url,
+                )
+            })?;
+
+        let status = response.status();
+        if matches!(
+            status,
+            reqwest::StatusCode::NOT_FOUND
+                | reqwest::StatusCode::METHOD_NOT_ALLOWED
+                | reqwest::StatusCode::NOT_IMPLEMENTED
+        ) {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(self.status_error(status, "call_tools_batch"));
+        }
+
+        let body: serde_json::Value = response.json().await.map_err(|e| {
+            WorkflowError::mcp_protocol_error(
+                format!("Failed to parse tool batch response: {}", e),
+                &self.base_url,
+                "JSON-RPC batch response",
+                "unparseable body",
+                "response",
+            )
+        })?;
+        // Servers without batch support answer with a single error object
+        let serde_json::Value::Array(entries) = body else {
+            return Ok(None);
+        };
+        let mut responses = HashMap::new();
+        for entry in entries {
+            let response: BatchResponse = serde_json::from_value(entry).map_err(|e| {
+                WorkflowError::mcp_protocol_error(
+                    format!("Failed to parse tool batch response: {}", e),
+                    &self.base_url,
+                    "JSON-RPC response",
+                    "unparseable entry",
+                    "response",
+                )
+            })?;
+            if let Some(id) = response.id.as_u64() {
+                responses.insert(id as usize, response);
+            }
+        }
+
+        Ok(Some(
+            calls
+                .iter()
+                .enumerate()
+                .map(|(id, (name, _))| match responses.remove(&id) {
+                    Some(BatchResponse { error: Some(error), .. }) => Err(WorkflowError::mcp_error(
+                        error.message,
+                        &self.base_url,
+                        format!("call_tool:{}", name),
+                    )),
+                    Some(BatchResponse { result: Some(result), .. }) => Ok(result),
+                    _ => Err(WorkflowError::mcp_protocol_error(
+                        "Tool batch response has no result for the call",
+                        &self.base_url,
+                        format!("response with id {}", id),
+                        "no result",
+                        "response",
+                    )),
+                })
+                .collect(),
+        ))
+    }
+
+    /// Add authentication headers if configured. An explicit `Authorization`
+    /// header takes precedence over the bearer token.
+    fn authorize(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(ref auth) = self.auth {
+            let has_authorization = auth.headers.as_ref().is_some_and(|headers| {
+                headers.keys().any(|key| key.eq_ignore_ascii_case("authorization"))
+            });
+            if let (Some(token), false) = (&auth.token, has_authorization) {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+            if let Some(ref headers) = auth.headers {
+                for (key, value) in headers {
+                    request = request.header(key, value);
+                }
+            }
+        }
+        request
+    }
+
+    /// Server errors are transient transport failures; client errors are not
+    fn status_error(&self, status: reqwest::StatusCode, operation: &str) -> WorkflowError {
+        let message = format!("HTTP error: {}", status);
+        if status.is_server_error() {
+            WorkflowError::mcp_transport_error(message, &self.base_url, "http", operation)
+        } else {
+            WorkflowError::mcp_error(message, &self.base_url, operation)
+        }
+    }
+}
+
+#[async_trait]
+impl McpClient for HttpMcpClient {
+    async fn connect(&mut self) -> Result<(), WorkflowError> {
+        // For HTTP, "connection" is just validation that the server is reachable
+        self.is_connected = true;
+        Ok(())
+    }
+
+    async fn initialize(
+        &mut self,
+        _client_name: &str,
+        _client_version: &str,
+    ) -> Result<(), WorkflowError> {
+        // HTTP clients may not need initialization
+        Ok(())
+    }
+
+    async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError> {
+        let url = format!("{}/tools/list", self.base_url);
+        let request = self.authorize(self.client.get(&url));
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| {
+                WorkflowError::mcp_connection_error(
+                    format!("Failed to list tools: {}", e),
+                    &self.base_url,
+                    "http",
+                    &url,
+                )
+            })?;
+
+        if !response.status().is_success() {
+            return Err(self.status_error(response.status(), "list_tools"));
+        }
+
+        let tools: Vec<ToolDefinition> =
+            response
+                .json()
+                .await
+                .map_err(|e| {
+                    WorkflowError::mcp_protocol_error(
+                        format!("Failed to parse tools response: {}", e),
+                        &self.base_url,
+                        "ListToolsResult",
+                        "unparseable body",
+                        "response",
+                    )
+                })?;
+
+        Ok(tools)
+    }
+
+    async fn call_tool(
+        &mut self,
+        name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let url = format!("{}/tools/call", self.base_url);
+        let request = self.authorize(self.client.post(&url));
+
+        let body = serde_json::json!({
+            "name": name,
+            "arguments": arguments,
+        });
+
+        let response =
+            request
+                .json(&body)
+                .send()
+                .await
+                .map_err(|e| {
+                    WorkflowError::mcp_connection_error(
+                        format!("Failed to call tool: {}", e),
+                        &self.base_url,
+                        "http",
+                        &url,
+                    )
+                })?;
+
+        if !response.status().is_success() {
+            return Err(self.status_error(response.status(), &format!("call_tool:{}", name)));
+        }
+
+        let result: CallToolResult =
+            response
+                .json()
+                .await
+                .map_err(|e| {
+                    WorkflowError::mcp_protocol_error(
+                        format!("Failed to parse tool call response: {}", e),
+                        &self.base_url,
+                        "CallToolResult",
+                        "unparseable body",
+                        "response",
+                    )
+                })?;
+
+        Ok(result)
+    }
+
+    async fn call_tools_batch(
+        &mut self,
+        calls: Vec<ToolCall>,
+    ) -> Vec<Result<CallToolResult, WorkflowError>> {
+        if self.supports_batch != Some(false) {
+            match self.post_batch(&calls).await {
+                Ok(Some(results)) => {
+                    self.supports_batch = Some(true);
+                    return results;
+                }
+                Ok(None) => self.supports_batch = Some(false),
+                // Some calls may already have run, so don't resend them one by one
+                Err(e) => {
+                    let message = e.to_string();
+                    return calls
+                        .iter()
+                        .map(|(name, _)| {
+                            Err(WorkflowError::mcp_error(
+                                format!("Tool batch failed: {}", message),
+                                &self.base_url,
+                                format!("call_tool:{}", name),
+                            ))
+                        })
+                        .collect();
+                }
+            }
+        }
+
+        let mut results = Vec::with_capacity(calls.len());
+        for (name, arguments) in calls {
+            results.push(self.call_tool(&name, arguments).await);
+        }
+        results
+    }
+
+    async fn disconnect(&mut self) -> Result<(), WorkflowError> {
+        self.is_connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.is_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::*;
+    use mockall::predicate::*;
+
+    // Mock trait for McpClient
+    mock! {
+        TestMcpClient {}
+        
+        impl std::fmt::Debug for TestMcpClient {
+            fn fmt<'a>(&self, f: &mut std::fmt::Formatter<'a>) -> std::fmt::Result;
+        }
+        
+        #[async_trait]
+        impl McpClient for TestMcpClient {
+            async fn connect(&mut self) -> Result<(), WorkflowError>;
+            async fn initialize(&mut self, client_name: &str, client_version: &str) -> Result<(), WorkflowError>;
+            async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError>;
+            async fn call_tool(&mut self, name: &str, arguments: Option<HashMap<String, serde_json::Value>>) -> Result<CallToolResult, WorkflowError>;
+            async fn disconnect(&mut self) -> Result<(), WorkflowError>;
+            fn is_connected(&self) -> bool;
+        }
+    }
+
+    fn create_test_config(service_name: &str) -> ExternalMcpConfig {
+        ExternalMcpConfig {
+            service_name: service_name.to_string(),
+            transport: TransportType::Http {
+                base_url: "http://localhost:8080".to_string(),
+                pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+            },
+            auth: None,
+            retry_config: RetryConfig::default(),
+            connection_pool: None,
+        }
+    }
+
+    #[test]
+    fn test_base_external_mcp_client_creation() {
+        let config = create_test_config("test_service");
+        let client = BaseExternalMcpClient::new(config.clone());
+        
+        assert_eq!(client.config.service_name, "test_service");
+        assert!(!client.is_connected());
+    }
+
+    #[test]
+    fn test_retry_config_default() {
+        let config = RetryConfig::default();
+        
+        assert_eq!(config.max_attempts, 3);
+        assert_eq!(config.initial_delay, Duration::from_millis(1000));
+        assert_eq!(config.max_delay, Duration::from_millis(30000));
+        assert_eq!(config.multiplier, 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_connect_success() {
+        let config = create_test_config("test_service");
+        let _client = BaseExternalMcpClient::new(config);
+        
+        // We can't easily mock the internal client creation, so we'll test the HttpMcpClient directly
+        let mut http_client = HttpMcpClient::new("http://localhost:8080".to_string(), None);
+        let result = http_client.connect().await;
+        
+        assert!(result.is_ok());
+        assert!(http_client.is_connected());
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_not_connected() {
+        let config = create_test_config("test_service");
+        let mut client = BaseExternalMcpClient::new(config);
+        
+        let result = client.execute_tool("test_tool", None).await;
+        
+        assert!(result.is_err());
+        match result {
+            Err(WorkflowError::MCPConnectionError { message, .. }) => {
+                assert!(message.contains("not connected"));
+            }
+            _ => panic!("Expected MCPConnectionError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_not_connected() {
+        let config = create_test_config("test_service");
+        let mut client = BaseExternalMcpClient::new(config);
+        
+        let result = client.list_tools().await;
+        
+        assert!(result.is_err());
+        match result {
+            Err(WorkflowError::MCPConnectionError { message, .. }) => {
+                assert!(message.contains("not connected"));
+            }
+            _ => panic!("Expected MCPConnectionError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_when_not_connected() {
+        let config = create_test_config("test_service");
+        let mut client = BaseExternalMcpClient::new(config);
+        
+        let result = client.disconnect().await;
+        
+        assert!(result.is_ok());
+        assert!(!client.is_connected());
+    }
+
+    #[test]
+    fn test_node_process() {
+        let config = create_test_config("test_service");
+        let client = BaseExternalMcpClient::new(config);
+        
+        let task_context = TaskContext::new("test_workflow".to_string(), serde_json::json!({}));
+        let result = client.process(task_context);
+        
+        assert!(result.is_ok());
+        let updated_context = result.unwrap();
+        let data = updated_context.get_data::<bool>("external_mcp_client_processed");
+        assert!(data.is_ok());
+        assert_eq!(data.unwrap(), Some(true));
+    }
+
+    #[test]
+    fn test_auth_config_creation() {
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer token123".to_string());
+        
+        let auth = AuthConfig {
+            token: Some("token123".to_string()),
+            headers: Some(headers.clone()),
+        };
+        
+        assert_eq!(auth.token, Some("token123".to_string()));
+        assert_eq!(auth.headers.unwrap().get("Authorization"), Some(&"Bearer token123".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_http_mcp_client_initialization() {
+        let auth = AuthConfig {
+            token: Some("api_key".to_string()),
+            headers: None,
+        };
+        
+        let mut client = HttpMcpClient::new("http://api.example.com".to_string(), Some(auth));
+        
+        assert_eq!(client.base_url, "http://api.example.com");
+        assert!(!client.is_connected());
+        
+        let result = client.initialize("test_client", "1.0.0").await;
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_transport_type_creation() {
+        // Test HTTP transport
+        let http_transport = TransportType::Http {
+            base_url: "http://localhost:8080".to_string(),
+            pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+        };
+        
+        match http_transport {
+            TransportType::Http { base_url, .. } => {
+                assert_eq!(base_url, "http://localhost:8080");
+            }
+            _ => panic!("Expected HTTP transport"),
+        }
+        
+        // Test WebSocket transport
+        let ws_transport = TransportType::WebSocket {
+            url: "ws://localhost:8080".to_string(),
+            heartbeat_interval: Some(std::time::Duration::from_secs(30)),
+            reconnect_config: workflow_engine_mcp::transport::ReconnectConfig::default(),
+        };
+        
+        match ws_transport {
+            TransportType::WebSocket { url, .. } => {
+                assert_eq!(url, "ws://localhost:8080");
+            }
+            _ => panic!("Expected WebSocket transport"),
+        }
+        
+        // Test stdio transport
+        let stdio_transport = TransportType::Stdio {
+            command: "python".to_string(),
+            args: vec!["-m".to_string(), "mcp_server".to_string()],
+            auto_restart: true,
+            max_restarts: 3,
+        };
+        
+        match stdio_transport {
+            TransportType::Stdio { command, args, .. } => {
+                assert_eq!(command, "python");
+                assert_eq!(args, vec!["-m", "mcp_server"]);
+            }
+            _ => panic!("Expected stdio transport"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_config_backoff() {
+        let config = create_test_config("test_service");
+        let retry_config = config.retry_config.clone();
+        
+        let mut delay = retry_config.initial_delay.as_millis() as u64;
+        
+        // Test exponential backoff calculation
+        for _ in 0..3 {
+            delay = (delay as f64 * retry_config.multiplier) as u64;
+            delay = delay.min(retry_config.max_delay.as_millis() as u64);
+        }
+        
+        // After 3 iterations with 2.0 multiplier: 1000 -> 2000 -> 4000 -> 8000
+        assert_eq!(delay, 8000);
+    }
+
+    #[test]
+    fn test_external_mcp_config_with_auth() {
+        let mut headers = HashMap::new();
+        headers.insert("X-API-Key".to_string(), "secret_key".to_string());
+        
+        let auth = Some(AuthConfig {
+            token: Some("secret_key".to_string()),
+            headers: Some(headers),
+        });
+        
+        let config = ExternalMcpConfig {
+            service_name: "auth_service".to_string(),
+            transport: TransportType::Http {
+                base_url: "https://api.example.com".to_string(),
+                pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+            },
+            auth: auth.clone(),
+            retry_config: RetryConfig::default(),
+            connection_pool: None,
+        };
+        
+        assert_eq!(config.service_name, "auth_service");
+        assert!(config.auth.is_some());
+        
+        let auth_config = config.auth.unwrap();
+        assert_eq!(auth_config.token, Some("secret_key".to_string()));
+        assert!(auth_config.headers.is_some());
+    }
+
+    // Tests for mocking external MCP services
+    #[tokio::test]
+    async fn test_mock_external_service_success() {
+        let mut mock_client = MockTestMcpClient::new();
+        
+        // Set up expectations
+        mock_client
+            .expect_connect()
+            .times(1)
+            .returning(|| Ok(()));
+            
+        mock_client
+            .expect_initialize()
+            .with(eq("test_service"), eq("1.0.0"))
+            .times(1)
+            .returning(|_, _| Ok(()));
+            
+        mock_client
+            .expect_is_connected()
+            .returning(|| true);
+            
+        mock_client
+            .expect_list_tools()
+            .times(1)
+            .returning(|| Ok(vec![
+                ToolDefinition {
+                    name: "test_tool".to_string(),
+                    description: Some("A test tool".to_string()),
+                    version: None,
+                    input_schema: serde_json::json!({}),
+                },
+            ]));
+            
+        // Execute test
+        mock_client.connect().await.unwrap();
+        mock_client.initialize("test_service", "1.0.0").await.unwrap();
+        assert!(mock_client.is_connected());
+        
+        let tools = mock_client.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "test_tool");
+    }
+
+    #[tokio::test]
+    async fn test_mock_external_service_connection_failure() {
+        let mut mock_client = MockTestMcpClient::new();
+        
+        mock_client
+            .expect_connect()
+            .times(1)
+            .returning(|| Err(WorkflowError::mcp_connection_error_simple("Connection refused")));
+            
+        let result = mock_client.connect().await;
+        assert!(result.is_err());
+        
+        match result {
+            Err(WorkflowError::MCPConnectionError { message, .. }) => {
+                assert_eq!(message, "Connection refused");
+            }
+            _ => panic!("Expected MCPConnectionError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_tool_execution() {
+        let mut mock_client = MockTestMcpClient::new();
+        
+        let mut args = HashMap::new();
+        args.insert("param1".to_string(), serde_json::Value::String("value1".to_string()));
+        
+        mock_client
+            .expect_call_tool()
+            .with(eq("test_tool"), eq(Some(args.clone())))
+            .times(1)
+            .returning(|_, _| Ok(CallToolResult {
+                content: vec![workflow_engine_mcp::protocol::ToolContent::Text {
+                    text: "success".to_string(),
+                }],
+                is_error: Some(false),
+            }));
+            
+        let result = mock_client.call_tool("test_tool", Some(args)).await.unwrap();
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(result.content.len(), 1);
+    }
+
+    // Test error propagation
+    #[tokio::test]
+    async fn test_error_propagation_from_transport() {
+        let config = create_test_config("error_service");
+        let mut client = BaseExternalMcpClient::new(config);
+        
+        // Since we can't inject a mock easily, we test with disconnected state
+        let result = client.execute_tool("any_tool", None).await;
+        
+        assert!(result.is_err());
+        match result {
+            Err(WorkflowError::MCPConnectionError { message, .. }) => {
+                assert!(message.contains("not connected"));
+            }
+            _ => panic!("Expected MCPConnectionError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_propagation_invalid_tool() {
+        let mut mock_client = MockTestMcpClient::new();
+        
+        mock_client
+            .expect_call_tool()
+            .with(eq("invalid_tool"), eq(None))
+            .times(1)
+            .returning(|_, _| Err(WorkflowError::mcp_error_simple("Tool not found: invalid_tool")));
+            
+        let result = mock_client.call_tool("invalid_tool", None).await;
+        
+        assert!(result.is_err());
+        match result {
+            Err(WorkflowError::MCPError { message, .. }) => {
+                assert!(message.contains("Tool not found"));
+            }
+            _ => panic!("Expected MCPError"),
+        }
+    }
+
+    // Test timeout and retry behavior
+    #[tokio::test]
+    async fn test_retry_on_connection_failure() {
+        let retry_config = RetryConfig {
+            max_attempts: 2,
+            initial_delay: Duration::from_millis(10), // Short delay for testing
+            max_delay: Duration::from_millis(50),
+            ..RetryConfig::default()
+        };
+        
+        let mut config = create_test_config("retry_service");
+        config.retry_config = retry_config;
+        
+        let client = BaseExternalMcpClient::new(config);
+        
+        // We can verify the retry config is properly set
+        assert_eq!(client.config.retry_config.max_attempts, 2);
+        assert_eq!(client.config.retry_config.initial_delay, Duration::from_millis(10));
+    }
+
+    #[tokio::test]
+    async fn test_exponential_backoff_calculation() {
+        let retry_config = RetryConfig {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(5000),
+            multiplier: 2.5,
+            jitter: 0.0,
+        };
+        
+        let mut delay = retry_config.initial_delay.as_millis() as u64;
+        let mut delays = vec![delay];
+        
+        for _ in 0..4 {
+            delay = (delay as f64 * retry_config.multiplier) as u64;
+            delay = delay.min(retry_config.max_delay.as_millis() as u64);
+            delays.push(delay);
+        }
+        
+        // Expected: 100, 250, 625, 1562, 3905
+        assert_eq!(delays[0], 100);
+        assert_eq!(delays[1], 250);
+        assert_eq!(delays[2], 625);
+        assert_eq!(delays[3], 1562);
+        assert_eq!(delays[4], 3905);
+    }
+
+    // Integration test placeholder (would need real MCP servers)
+    #[tokio::test]
+    #[ignore] // Run with cargo test -- --ignored
+    async fn test_integration_with_real_mcp_server() {
+        // This test would connect to a real MCP server
+        // For now, it's a placeholder showing the structure
+        
+        let config = ExternalMcpConfig {
+            service_name: "test_integration".to_string(),
+            transport: TransportType::Http {
+                base_url: "http://localhost:8001".to_string(), // HelpScout test server
+                pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+            },
+            auth: None,
+            retry_config: RetryConfig::default(),
+            connection_pool: None,
+        };
+        
+        let mut client = BaseExternalMcpClient::new(config);
+        
+        // Would test real connection
+        let connect_result = client.connect().await;
+        if connect_result.is_ok() {
+            assert!(client.is_connected());
+            
+            let tools = client.list_tools().await;
+            assert!(tools.is_ok());
+            
+            let _ = client.disconnect().await;
+        }
+    }
+
+    // Test different transport types
+    #[test]
+    fn test_create_client_with_different_transports() {
+        let config_http = ExternalMcpConfig {
+            service_name: "http_service".to_string(),
+            transport: TransportType::Http {
+                base_url: "http://example.com".to_string(),
+                pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+            },
+            auth: None,
+            retry_config: RetryConfig::default(),
+            connection_pool: None,
+        };
+        
+        let config_ws = ExternalMcpConfig {
+            service_name: "ws_service".to_string(),
+            transport: TransportType::WebSocket {
+                url: "ws://example.com".to_string(),
+                heartbeat_interval: None,
+                reconnect_config: workflow_engine_mcp::transport::ReconnectConfig::default(),
+            },
+            auth: None,
+            retry_config: RetryConfig::default(),
+            connection_pool: None,
+        };
+        
+        let config_stdio = ExternalMcpConfig {
+            service_name: "stdio_service".to_string(),
+            transport: TransportType::Stdio {
+                command: "python".to_string(),
+                args: vec![],
+                auto_restart: false,
+                max_restarts: 0,
+            },
+            auth: None,
+            retry_config: RetryConfig::default(),
+            connection_pool: None,
+        };
+        
+        let client_http = BaseExternalMcpClient::new(config_http);
+        let client_ws = BaseExternalMcpClient::new(config_ws);
+        let client_stdio = BaseExternalMcpClient::new(config_stdio);
+        
+        assert_eq!(client_http.config.service_name, "http_service");
+        assert_eq!(client_ws.config.service_name, "ws_service");
+        assert_eq!(client_stdio.config.service_name, "stdio_service");
+    }
+
+    // Test HTTP client specific functionality
+    #[tokio::test]
+    async fn test_http_client_with_auth_headers() {
+        let mut headers = HashMap::new();
+        headers.insert("X-Custom-Header".to_string(), "custom_value".to_string());
+        headers.insert("X-API-Version".to_string(), "v2".to_string());
+        
+        let auth = Some(AuthConfig {
+            token: Some("bearer_token".to_string()),
+            headers: Some(headers),
+        });
+        
+        let client = HttpMcpClient::new("https://api.example.com".to_string(), auth.clone());
+        
+        assert_eq!(client.base_url, "https://api.example.com");
+        assert!(client.auth.is_some());
+        
+        let client_auth = client.auth.unwrap();
+        assert_eq!(client_auth.token, Some("bearer_token".to_string()));
+        assert!(client_auth.headers.is_some());
+        
+        let headers = client_auth.headers.unwrap();
+        assert_eq!(headers.get("X-Custom-Header"), Some(&"custom_value".to_string()));
+        assert_eq!(headers.get("X-API-Version"), Some(&"v2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_http_client_error_handling() {
+        let mut client = HttpMcpClient::new("http://invalid.local".to_string(), None);
+        client.is_connected = true; // Simulate connected state
+        
+        // This would fail with a real request to invalid.local
+        // For testing purposes, we're verifying the structure
+        assert_eq!(client.base_url, "http://invalid.local");
+        assert!(client.is_connected());
+    }
+
+    fn fast_retry_config(service_name: &str) -> ExternalMcpConfig {
+        let mut config = create_test_config(service_name);
+        config.retry_config = RetryConfig {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            multiplier: 2.0,
+            jitter: 0.5,
+        };
+        config
+    }
+
+    fn text_result(text: &str) -> CallToolResult {
+        CallToolResult {
+            content: vec![workflow_engine_mcp::protocol::ToolContent::Text {
+                text: text.to_string(),
+            }],
+            is_error: Some(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_retries_connection_errors() {
+        let mut mock_client = MockTestMcpClient::new();
+        let mut sequence = Sequence::new();
+        mock_client
+            .expect_call_tool()
+            .times(2)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Err(WorkflowError::mcp_connection_error_simple("connection reset")));
+        mock_client
+            .expect_call_tool()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(text_result("recovered")));
+        mock_client.expect_is_connected().return_const(true);
+
+        let mut client = BaseExternalMcpClient::new(fast_retry_config("flaky_service"));
+        client.client = Some(Box::new(mock_client));
+
+        let result = client.execute_tool("search", None).await.unwrap();
+        assert_eq!(result.is_error, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_does_not_retry_application_errors() {
+        let mut mock_client = MockTestMcpClient::new();
+        mock_client
+            .expect_call_tool()
+            .times(1)
+            .returning(|_, _| Err(WorkflowError::mcp_error("invalid arguments", "app_service", "call_tool")));
+        mock_client.expect_is_connected().return_const(true);
+
+        let mut client = BaseExternalMcpClient::new(fast_retry_config("app_service"));
+        client.client = Some(Box::new(mock_client));
+
+        let result = client.execute_tool("search", None).await;
+        assert!(matches!(result, Err(WorkflowError::MCPError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_gives_up_after_max_attempts() {
+        let mut mock_client = MockTestMcpClient::new();
+        mock_client
+            .expect_call_tool()
+            .times(4)
+            .returning(|_, _| Err(WorkflowError::mcp_transport_error_simple("broken pipe")));
+        mock_client.expect_is_connected().return_const(true);
+
+        let mut client = BaseExternalMcpClient::new(fast_retry_config("down_service"));
+        client.client = Some(Box::new(mock_client));
+
+        let result = client.execute_tool("search", None).await;
+        assert!(matches!(result, Err(WorkflowError::MCPTransportError { .. })));
+    }
+
+    #[test]
+    fn test_retry_config_backoff_caps_at_max_delay() {
+        let retry_config = RetryConfig {
+            max_attempts: 6,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            multiplier: 2.0,
+            jitter: 0.0,
+        };
+        let policy = retry_config.to_policy();
+        let delays: Vec<u128> = (1..=6).map(|retry| policy.calculate_delay(retry).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+
+        // Jitter varies each delay by at most its fraction
+        let policy = RetryConfig { jitter: 0.5, ..retry_config }.to_policy();
+        for _ in 0..20 {
+            let delay = policy.calculate_delay(6).as_millis();
+            assert!((500..=1500).contains(&delay), "{}", delay);
+        }
+    }
+
+    #[test]
+    fn test_retry_config_accepts_legacy_field_names() {
+        let retry_config: RetryConfig = serde_json::from_value(serde_json::json!({
+            "max_retries": 5,
+            "initial_delay_ms": 250,
+            "max_delay_ms": 4000,
+            "backoff_multiplier": 3.0,
+        }))
+        .unwrap();
+        assert_eq!(retry_config.max_attempts, 5);
+        assert_eq!(retry_config.initial_delay, Duration::from_millis(250));
+        assert_eq!(retry_config.max_delay, Duration::from_millis(4000));
+        assert_eq!(retry_config.multiplier, 3.0);
+        assert_eq!(retry_config.jitter, 0.1);
+
+        let serialized = serde_json::to_value(&retry_config).unwrap();
+        assert_eq!(serialized["initial_delay"], 250);
+        assert_eq!(serialized["max_delay"], 4000);
+    }
+
+    #[test]
+    fn test_retry_delay_only_for_transient_errors_within_max_attempts() {
+        let mut config = fast_retry_config("budget_service");
+        config.retry_config.max_attempts = 2;
+        let client = BaseExternalMcpClient::new(config);
+
+        let transient = WorkflowError::mcp_transport_error_simple("broken pipe");
+        assert!(client.retry_delay("Tool call", &transient, 0).is_some());
+        assert!(client.retry_delay("Tool call", &transient, 1).is_some());
+        assert!(client.retry_delay("Tool call", &transient, 2).is_none());
+
+        let permanent = WorkflowError::mcp_error("invalid arguments", "budget_service", "call_tool");
+        assert!(client.retry_delay("Tool call", &permanent, 0).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connect_gives_up_after_max_attempts() {
+        // Accepts connections and drops them, so every WebSocket handshake fails
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let accepted = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                drop(stream);
+            }
+        });
+
+        let mut config = fast_retry_config("unreachable_service");
+        config.transport = TransportType::WebSocket {
+            url: format!("ws://{}", address),
+            heartbeat_interval: None,
+            reconnect_config: workflow_engine_mcp::transport::ReconnectConfig::default(),
+        };
+        let mut client = BaseExternalMcpClient::new(config);
+
+        assert!(client.connect().await.is_err());
+        assert!(!client.is_connected());
+        // The first attempt and max_attempts retries
+        assert_eq!(accepted.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_http_execute_tool_retries_server_errors() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(text_result("ok")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = fast_retry_config("http_service");
+        config.transport = TransportType::Http {
+            base_url: server.uri(),
+            pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+        };
+        let mut client = BaseExternalMcpClient::new(config);
+        client.connect().await.unwrap();
+
+        let result = client.execute_tool("search", None).await.unwrap();
+        assert_eq!(result.is_error, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_http_execute_tool_does_not_retry_client_errors() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = fast_retry_config("http_service");
+        config.transport = TransportType::Http {
+            base_url: server.uri(),
+            pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+        };
+        let mut client = BaseExternalMcpClient::new(config);
+        client.connect().await.unwrap();
+
+        let result = client.execute_tool("search", None).await;
+        assert!(matches!(result, Err(WorkflowError::MCPError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reachable_server() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/tools/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "name": "search", "description": "Search", "input_schema": { "type": "object" } }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = fast_retry_config("healthy_service");
+        config.transport = TransportType::Http {
+            base_url: server.uri(),
+            pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+        };
+        let mut client = BaseExternalMcpClient::new(config);
+
+        let status = client.health_check().await.unwrap();
+        assert_eq!(status.service_name, "healthy_service");
+        assert!(status.healthy);
+        assert!(status.reachable);
+        assert!(status.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_unreachable_server() {
+        // Bind and drop a listener so nothing is accepting on the port
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut config = fast_retry_config("down_service");
+        config.transport = TransportType::Http {
+            base_url: format!("http://127.0.0.1:{}", port),
+            pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+        };
+        let mut client = BaseExternalMcpClient::new(config);
+
+        let status = client.health_check().await.unwrap();
+        assert!(!status.healthy);
+        assert!(!status.reachable);
+        assert!(status.error.unwrap().contains("Failed to list tools"));
+    }
+
+    #[test]
+    fn test_process_tool_call_honors_workflow_deadline() {
+        use std::time::{Duration, Instant};
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/tools/call"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(text_result("late"))
+                        .set_delay(Duration::from_millis(500)),
+                )
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let config = http_config("slow_service", server.uri());
+        let mut context = TaskContext::new(
+            "deadline_test".to_string(),
+            serde_json::json!({ "tool_name": "search" }),
+        );
+        context.set_deadline(Instant::now() + Duration::from_millis(100));
+
+        let start = Instant::now();
+        let result = process_tool_call(&config, "SlowClientNode", context);
+        assert!(start.elapsed() < Duration::from_millis(400));
+        match result {
+            Err(WorkflowError::NodeTimeout { node_name, deadline_exceeded, .. }) => {
+                assert_eq!(node_name, "SlowClientNode");
+                assert!(deadline_exceeded);
+            }
+            other => panic!("Expected deadline-exceeded NodeTimeout, got {:?}", other),
+        }
+    }
+
+    async fn ticket_server() -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(text_result("ticket 1138: refund pending")))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn ticket_context() -> TaskContext {
+        TaskContext::new(
+            "async_caller_test".to_string(),
+            serde_json::json!({ "tool_name": "get_ticket", "arguments": { "id": 1138 } }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_process_tool_call_from_a_current_thread_runtime() {
+        let server = ticket_server().await;
+        let node = TicketLookupNode {
+            config: http_config("helpscout", server.uri()),
+        };
+
+        let context = node.process(ticket_context()).unwrap();
+        assert_eq!(
+            context.get_all_data()["helpscout"]["result"]["content"][0]["text"],
+            "ticket 1138: refund pending"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_process_tool_call_from_a_multi_thread_runtime() {
+        let server = ticket_server().await;
+        let node = TicketLookupNode {
+            config: http_config("helpscout", server.uri()),
+        };
+
+        let context = node.process(ticket_context()).unwrap();
+        assert_eq!(
+            context.get_all_data()["helpscout"]["result"]["content"][0]["text"],
+            "ticket 1138: refund pending"
+        );
+    }
+
+    #[derive(Debug)]
+    struct TicketLookupNode {
+        config: ExternalMcpConfig,
+    }
+
+    impl Node for TicketLookupNode {
+        fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+            process_tool_call(&self.config, "TicketLookupNode", task_context)
+        }
+    }
+
+    #[test]
+    fn test_replay_answers_tool_calls_from_the_recording() {
+        use std::sync::Arc;
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use workflow_engine_core::workflow::builder::WorkflowBuilder;
+        use workflow_engine_core::workflow::replay::{InMemoryReplayStore, ReplayStore};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = runtime.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/tools/call"))
+                .and(body_partial_json(serde_json::json!({ "name": "get_ticket" })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(text_result("ticket 1138: refund pending")))
+                .expect(1)
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let store = Arc::new(InMemoryReplayStore::new());
+        let workflow = WorkflowBuilder::new::<TicketLookupNode>("ticket_replay".to_string())
+            .build()
+            .unwrap()
+            .with_replay_store(store.clone());
+        workflow.register_node(TicketLookupNode {
+            config: http_config("helpscout", server.uri()),
+        });
+
+        let recorded = workflow
+            .run(serde_json::json!({ "tool_name": "get_ticket", "arguments": { "id": 1138 } }))
+            .unwrap();
+        let record = store.load(recorded.event_id).unwrap().unwrap();
+        assert_eq!(record.external_calls.len(), 1);
+        assert_eq!(record.external_calls[0].service, "helpscout");
+
+        // The server is gone, so only the recording can answer the replay
+        runtime.block_on(server.verify());
+        drop(server);
+        let replayed = workflow.replay(recorded.event_id).unwrap();
+
+        assert_eq!(replayed.event_id, recorded.event_id);
+        assert_eq!(replayed.event_data, recorded.event_data);
+        assert_eq!(replayed.get_all_data(), recorded.get_all_data());
+        assert_eq!(replayed.get_all_metadata(), recorded.get_all_metadata());
+        assert_eq!(
+            replayed.get_all_data()["helpscout"]["result"]["content"][0]["text"],
+            "ticket 1138: refund pending"
+        );
+    }
+
+    fn http_config(service_name: &str, base_url: String) -> ExternalMcpConfig {
+        let mut config = fast_retry_config(service_name);
+        config.transport = TransportType::Http {
+            base_url,
+            pool_config: workflow_engine_mcp::transport::HttpPoolConfig::default(),
+        };
+        config
+    }
+
+    fn batch_calls() -> Vec<ToolCall> {
+        vec![
+            (
+                "search".to_string(),
+                Some(HashMap::from([("query".to_string(), serde_json::json!("billing"))])),
+            ),
+            ("missing".to_string(), None),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_execute_tools_batch_uses_one_round_trip() {
+        use wiremock::matchers::{body_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .and(body_json(serde_json::json!([
+                {
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "method": "tools/call",
+                    "params": { "name": "search", "arguments": { "query": "billing" } }
+                },
+                {
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": { "name": "missing", "arguments": null }
+                }
+            ])))
+            // Batch responses may come back in any order
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": "Unknown tool: missing" } },
+                {
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "result": { "content": [{ "type": "text", "text": "3 matches" }], "is_error": false }
+                }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let mut client = BaseExternalMcpClient::new(http_config("batch_service", server.uri()));
+        client.connect().await.unwrap();
+
+        let results = client.execute_tools_batch(batch_calls()).await;
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            &results[0].as_ref().unwrap().content[0],
+            workflow_engine_mcp::protocol::ToolContent::Text { text } if text == "3 matches"
+        ));
+        match &results[1] {
+            Err(WorkflowError::MCPError { message, .. }) => {
+                assert_eq!(message, "Unknown tool: missing")
+            }
+            other => panic!("Expected MCPError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_tools_batch_reports_unanswered_calls() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "jsonrpc": "2.0", "id": 0, "result": { "content": [], "is_error": false } }
+            ])))
+            .mount(&server)
+            .await;
+
+        let mut client = BaseExternalMcpClient::new(http_config("batch_service", server.uri()));
+        client.connect().await.unwrap();
+
+        let results = client.execute_tools_batch(batch_calls()).await;
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(WorkflowError::MCPProtocolError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tools_batch_falls_back_to_sequential_calls() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            // A server that only takes single requests rejects the array
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32600, "message": "Invalid Request" }
+            })))
+            // Probed once; later batches go straight to sequential calls
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .and(body_partial_json(serde_json::json!({ "name": "search" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(text_result("3 matches")))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .and(body_partial_json(serde_json::json!({ "name": "missing" })))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut client = BaseExternalMcpClient::new(http_config("plain_service", server.uri()));
+        client.connect().await.unwrap();
+
+        for _ in 0..2 {
+            let results = client.execute_tools_batch(batch_calls()).await;
+            assert_eq!(results.len(), 2);
+            assert!(matches!(
+                &results[0].as_ref().unwrap().content[0],
+                workflow_engine_mcp::protocol::ToolContent::Text { text } if text == "3 matches"
+            ));
+            assert!(matches!(results[1], Err(WorkflowError::MCPError { .. })));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_tools_batch_falls_back_without_an_mcp_endpoint() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(text_result("ok")))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut client = BaseExternalMcpClient::new(http_config("plain_service", server.uri()));
+        client.connect().await.unwrap();
+
+        let results = client.execute_tools_batch(batch_calls()).await;
+        assert!(results.iter().all(Result::is_ok));
+    }
+
+    /// Line-delimited MCP server that appends a line to the file named by `$1`
+    /// each time a process is spawned, so tests can count connections.
+    const COUNTING_SERVER: &str = r#"
+echo started >> "$1"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":"\([^"]*\)".*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"type":"result","id":"%s","result":{"protocol_version":"2024-11-05","capabilities":{},"server_info":{"name":"counting","version":"1.0.0"}}}\n' "$id" ;;
+    *'"method":"tools/call"'*)
+      sleep 0.05
+      printf '{"type":"result","id":"%s","result":{"content":[{"type":"text","text":"ok"}],"is_error":false}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_pooled_client_reuses_connections() {
+        let count_file =
+            std::env::temp_dir().join(format!("external_mcp_pool_{}.log", std::process::id()));
+        let pool = Arc::new(McpConnectionPool::new(
+            workflow_engine_mcp::connection_pool::ConnectionConfig {
+                max_connections_per_server: 2,
+                ..Default::default()
+            },
+        ));
+        let config = ExternalMcpConfig {
+            service_name: "counting".to_string(),
+            transport: TransportType::Stdio {
+                command: "sh".to_string(),
+                args: vec![
+                    "-c".to_string(),
+                    COUNTING_SERVER.to_string(),
+                    "sh".to_string(),
+                    count_file.to_string_lossy().into_owned(),
+                ],
+                auto_restart: false,
+                max_restarts: 0,
+            },
+            auth: None,
+            retry_config: RetryConfig::default(),
+            connection_pool: Some(Arc::clone(&pool)),
+        };
+
+        let mut client = BaseExternalMcpClient::new(config);
+        client.connect().await.unwrap();
+        assert!(client.is_connected());
+        let client = Arc::new(client);
+
+        let calls: Vec<_> = (0..10)
+            .map(|_| {
+                let client = Arc::clone(&client);
+                tokio::spawn(async move { client.execute_pooled_tool("work", None).await })
+            })
+            .collect();
+        for call in calls {
+            let result = call.await.unwrap().unwrap();
+            assert_eq!(result.is_error, Some(false));
+        }
+
+        let spawned = std::fs::read_to_string(&count_file).unwrap().lines().count();
+        assert!(
+            (1..=2).contains(&spawned),
+            "expected at most 2 server connections, got {}",
+            spawned
+        );
+        let stats = pool.get_pool_stats().await;
+        assert_eq!(stats["counting"].total_connections, spawned);
+        assert_eq!(stats["counting"].total_use_count, 10);
+
+        pool.disconnect_all().await.unwrap();
+        let _ = std::fs::remove_file(&count_file);
+    }
+
+    #[tokio::test]
+    async fn test_pooled_client_requires_connect() {
+        let mut config = create_test_config("pooled_service");
+        config.connection_pool = Some(Arc::new(McpConnectionPool::new(Default::default())));
+        let mut client = BaseExternalMcpClient::new(config);
+
+        assert!(!client.is_connected());
+        let result = client.execute_tool("any_tool", None).await;
+        assert!(matches!(result, Err(WorkflowError::MCPConnectionError { .. })));
+    }
+}
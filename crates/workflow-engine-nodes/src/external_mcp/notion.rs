@@ -0,0 +1,189 @@
+//! Client node for the Notion MCP server
+//!
+//! [`NotionClientNode`] wraps [`BaseExternalMcpClient`] with typed helpers for
+//! the Notion server's tools. By default it connects to `NOTION_MCP_URL` and
+//! authenticates with `NOTION_API_KEY`.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use workflow_engine_core::error::WorkflowError;
+use workflow_engine_core::nodes::Node;
+use workflow_engine_core::task::TaskContext;
+use workflow_engine_mcp::clients::ToolCall;
+use workflow_engine_mcp::protocol::{CallToolResult, ToolDefinition};
+
+use super::client::{process_tool_call, BaseExternalMcpClient, ExternalMcpClientNode, ExternalMcpConfig};
+use crate::external_config::NotionServerConfig;
+
+/// Node that talks to the Notion MCP server
+#[derive(Debug)]
+pub struct NotionClientNode {
+    base: BaseExternalMcpClient,
+}
+
+impl NotionClientNode {
+    /// Create a client configured from the environment
+    pub fn new() -> Self {
+        Self::from_server_config(&NotionServerConfig::default())
+    }
+
+    pub fn from_server_config(server_config: &NotionServerConfig) -> Self {
+        Self::with_config(server_config.to_external_config())
+    }
+
+    pub fn with_config(config: ExternalMcpConfig) -> Self {
+        Self {
+            base: BaseExternalMcpClient::new(config),
+        }
+    }
+
+    /// Search pages matching `query`
+    pub async fn search(
+        &mut self,
+        query: &str,
+        limit: Option<u32>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let mut arguments = HashMap::from([("query".to_string(), serde_json::json!(query))]);
+        if let Some(limit) = limit {
+            arguments.insert("limit".to_string(), serde_json::json!(limit));
+        }
+        self.execute_tool("search_pages", Some(arguments)).await
+    }
+
+    pub async fn get_page(&mut self, page_id: &str) -> Result<CallToolResult, WorkflowError> {
+        let arguments = HashMap::from([("page_id".to_string(), serde_json::json!(page_id))]);
+        self.execute_tool("get_page", Some(arguments)).await
+    }
+
+    /// Create a page under `parent_id` with the given title and content
+    pub async fn create_page(
+        &mut self,
+        parent_id: &str,
+        title: &str,
+        content: &str,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let arguments = HashMap::from([
+            ("parent_id".to_string(), serde_json::json!(parent_id)),
+            ("title".to_string(), serde_json::json!(title)),
+            ("content".to_string(), serde_json::json!(content)),
+        ]);
+        self.execute_tool("create_page", Some(arguments)).await
+    }
+}
+
+impl Default for NotionClientNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ExternalMcpClientNode for NotionClientNode {
+    fn get_config(&self) -> &ExternalMcpConfig {
+        self.base.get_config()
+    }
+
+    async fn connect(&mut self) -> Result<(), WorkflowError> {
+        self.base.connect().await
+    }
+
+    async fn execute_tool(
+        &mut self,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        self.base.execute_tool(tool_name, arguments).await
+    }
+
+    async fn execute_tools_batch(
+        &mut self,
+        calls: Vec<ToolCall>,
+    ) -> Vec<Result<CallToolResult, WorkflowError>> {
+        self.base.execute_tools_batch(calls).await
+    }
+
+    async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError> {
+        self.base.list_tools().await
+    }
+
+    async fn disconnect(&mut self) -> Result<(), WorkflowError> {
+        self.base.disconnect().await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.base.is_connected()
+    }
+}
+
+impl Node for NotionClientNode {
+    fn node_name(&self) -> String {
+        "NotionClientNode".to_string()
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        process_tool_call(self.get_config(), "NotionClientNode", task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use workflow_engine_mcp::transport::{HttpPoolConfig, TransportType};
+
+    fn server_config(url: &str) -> NotionServerConfig {
+        NotionServerConfig {
+            url: url.to_string(),
+            api_key: Some("test-notion-key".to_string()),
+            transport: TransportType::Http {
+                base_url: url.to_string(),
+                pool_config: HttpPoolConfig::default(),
+            },
+            enabled: true,
+            retry_config: None,
+            headers: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_calls_search_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/tools/call"))
+            .and(header("Notion-Version", "2022-06-28"))
+            .and(body_partial_json(serde_json::json!({
+                "name": "search_pages",
+                "arguments": { "query": "roadmap", "limit": 5 }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "type": "text", "text": "Q3 Roadmap" }],
+                "is_error": false
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut node = NotionClientNode::from_server_config(&server_config(&server.uri()));
+        node.connect().await.unwrap();
+
+        let result = node.search("roadmap", Some(5)).await.unwrap();
+        assert_eq!(result.is_error, Some(false));
+        assert!(matches!(
+            &result.content[0],
+            workflow_engine_mcp::protocol::ToolContent::Text { text } if text == "Q3 Roadmap"
+        ));
+    }
+
+    #[test]
+    fn test_config_uses_notion_service_name() {
+        let node = NotionClientNode::from_server_config(&server_config("http://localhost:8002"));
+        let config = node.get_config();
+        assert_eq!(config.service_name, "notion");
+        assert_eq!(
+            config.auth.as_ref().unwrap().token.as_deref(),
+            Some("test-notion-key")
+        );
+    }
+}
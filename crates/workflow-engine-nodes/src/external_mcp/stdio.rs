@@ -0,0 +1,371 @@
+//! Generic client node for MCP servers that speak over stdio
+//!
+//! [`StdioMcpClientNode`] spawns any local MCP server process, performs the
+//! MCP handshake and exposes the server's tools to workflows. If the process
+//! crashes mid-call it is restarted and the call is retried, up to the
+//! configured restart limit.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use workflow_engine_core::error::WorkflowError;
+use workflow_engine_core::nodes::Node;
+use workflow_engine_core::task::TaskContext;
+use workflow_engine_mcp::clients::{McpClient, StdioMcpClient};
+use workflow_engine_mcp::protocol::{CallToolResult, ToolDefinition};
+use workflow_engine_mcp::transport::TransportType;
+
+use super::client::{ExternalMcpClientNode, ExternalMcpConfig, RetryConfig};
+
+/// Node that talks to an arbitrary MCP server process over stdin/stdout
+#[derive(Debug)]
+pub struct StdioMcpClientNode {
+    config: ExternalMcpConfig,
+    env: HashMap<String, String>,
+    client: Option<StdioMcpClient>,
+    restart_count: u32,
+}
+
+impl StdioMcpClientNode {
+    pub fn new(service_name: impl Into<String>, command: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            config: ExternalMcpConfig {
+                service_name: service_name.into(),
+                transport: TransportType::Stdio {
+                    command: command.into(),
+                    args,
+                    auto_restart: true,
+                    max_restarts: 3,
+                },
+                auth: None,
+                retry_config: RetryConfig::default(),
+                connection_pool: None,
+            },
+            env: HashMap::new(),
+            client: None,
+            restart_count: 0,
+        }
+    }
+
+    /// Set environment variables for the server process
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Configure whether a crashed process is restarted, and how many times per call
+    pub fn with_restart_config(mut self, auto_restart: bool, max_restarts: u32) -> Self {
+        if let TransportType::Stdio {
+            auto_restart: ref mut restart,
+            max_restarts: ref mut max,
+            ..
+        } = self.config.transport
+        {
+            *restart = auto_restart;
+            *max = max_restarts;
+        }
+        self
+    }
+
+    /// Total number of times the server process has been restarted
+    pub fn restart_count(&self) -> u32 {
+        self.restart_count
+    }
+
+    fn command(&self) -> (&str, &[String], bool, u32) {
+        match &self.config.transport {
+            TransportType::Stdio {
+                command,
+                args,
+                auto_restart,
+                max_restarts,
+            } => (command, args, *auto_restart, *max_restarts),
+            // The constructor only ever builds a stdio transport
+            _ => unreachable!("StdioMcpClientNode requires a stdio transport"),
+        }
+    }
+
+    fn not_connected_error(&self) -> WorkflowError {
+        let (command, ..) = self.command();
+        WorkflowError::mcp_connection_error(
+            format!("{} client not connected", self.config.service_name),
+            &self.config.service_name,
+            "stdio",
+            command,
+        )
+    }
+
+    /// Only failures of the process or pipe warrant a restart; tool errors do not
+    fn is_crash(error: &WorkflowError) -> bool {
+        matches!(
+            error,
+            WorkflowError::MCPConnectionError { .. } | WorkflowError::MCPTransportError { .. }
+        )
+    }
+
+    async fn restart(&mut self) -> Result<(), WorkflowError> {
+        self.restart_count += 1;
+        log::warn!(
+            "[{}] MCP server process exited, restarting (restart #{})",
+            self.config.service_name,
+            self.restart_count
+        );
+        // The old process is already gone; ignore errors tearing it down
+        let _ = self.disconnect().await;
+        self.connect().await
+    }
+
+    async fn call_with_restart(
+        &mut self,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let (_, _, auto_restart, max_restarts) = self.command();
+        let mut restarts = 0;
+
+        loop {
+            let result = match self.client.as_mut() {
+                Some(client) => client.call_tool(tool_name, arguments.clone()).await,
+                None => return Err(self.not_connected_error()),
+            };
+            match result {
+                Err(e) if auto_restart && restarts < max_restarts && Self::is_crash(&e) => {
+                    restarts += 1;
+                    self.restart().await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Run a single tool call on a dedicated server process.
+    ///
+    /// Used by [`Node::process`], which runs outside of any async context.
+    async fn run_once(
+        &self,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        let mut session = Self {
+            config: self.config.clone(),
+            env: self.env.clone(),
+            client: None,
+            restart_count: 0,
+        };
+        session.connect().await?;
+        let result = session.execute_tool(tool_name, arguments).await;
+        let _ = session.disconnect().await;
+        result
+    }
+}
+
+#[async_trait]
+impl ExternalMcpClientNode for StdioMcpClientNode {
+    fn get_config(&self) -> &ExternalMcpConfig {
+        &self.config
+    }
+
+    async fn connect(&mut self) -> Result<(), WorkflowError> {
+        let (command, args, ..) = self.command();
+        let mut client =
+            StdioMcpClient::new(command.to_string(), args.to_vec()).with_env(self.env.clone());
+        client.connect().await?;
+        client
+            .initialize(&self.config.service_name, crate::VERSION)
+            .await?;
+        self.client = Some(client);
+        Ok(())
+    }
+
+    async fn execute_tool(
+        &mut self,
+        tool_name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<CallToolResult, WorkflowError> {
+        self.call_with_restart(tool_name, arguments).await
+    }
+
+    async fn list_tools(&mut self) -> Result<Vec<ToolDefinition>, WorkflowError> {
+        let (_, _, auto_restart, max_restarts) = self.command();
+        let mut restarts = 0;
+
+        loop {
+            let result = match self.client.as_mut() {
+                Some(client) => client.list_tools().await,
+                None => return Err(self.not_connected_error()),
+            };
+            match result {
+                Err(e) if auto_restart && restarts < max_restarts && Self::is_crash(&e) => {
+                    restarts += 1;
+                    self.restart().await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn disconnect(&mut self) -> Result<(), WorkflowError> {
+        if let Some(mut client) = self.client.take() {
+            client.disconnect().await?;
+        }
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.client
+            .as_ref()
+            .map(|c| c.is_connected())
+            .unwrap_or(false)
+    }
+}
+
+impl Node for StdioMcpClientNode {
+    fn node_name(&self) -> String {
+        format!("StdioMcpClientNode({})", self.config.service_name)
+    }
+
+    /// Calls the tool named by `tool_name` in the event data with its
+    /// `arguments` object, storing the result under the service name.
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext, WorkflowError> {
+        let event: serde_json::Value = task_context.get_event_data()?;
+        let tool_name = event
+            .get("tool_name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WorkflowError::validation_error(
+                "Missing 'tool_name' in event data",
+                "tool_name",
+                "string naming the MCP tool to call",
+                "in StdioMcpClientNode::process",
+            ))?
+            .to_string();
+        let arguments = event
+            .get("arguments")
+            .and_then(|v| v.as_object())
+            .map(|args| args.clone().into_iter().collect::<HashMap<_, _>>());
+
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| WorkflowError::RuntimeError {
+                message: format!("Failed to create runtime: {}", e),
+            })?;
+        let node_name = self.node_name();
+        let result = runtime.block_on(
+            task_context.within_deadline(&node_name, self.run_once(&tool_name, arguments)),
+        )?;
+
+        task_context.update_node(&self.config.service_name, serde_json::json!({
+            "tool_name": tool_name,
+            "result": result,
+        }));
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal line-delimited MCP server: echoes `message` (prefixed with
+    /// `$ECHO_PREFIX`) and exits when the `crash` tool is called.
+    const ECHO_SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":"\([^"]*\)".*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"type":"result","id":"%s","result":{"protocol_version":"2024-11-05","capabilities":{},"server_info":{"name":"echo","version":"1.0.0"}}}\n' "$id" ;;
+    *'"method":"tools/list"'*)
+      printf '{"type":"result","id":"%s","result":{"tools":[{"name":"echo","description":"Echoes a message","input_schema":{"type":"object"}}]}}\n' "$id" ;;
+    *'"name":"crash"'*)
+      exit 1 ;;
+    *'"method":"tools/call"'*)
+      message=$(printf '%s' "$line" | sed -n 's/.*"message":"\([^"]*\)".*/\1/p')
+      printf '{"type":"result","id":"%s","result":{"content":[{"type":"text","text":"%s%s"}],"is_error":false}}\n' "$id" "$ECHO_PREFIX" "$message" ;;
+  esac
+done
+"#;
+
+    fn echo_node() -> StdioMcpClientNode {
+        StdioMcpClientNode::new(
+            "echo",
+            "sh",
+            vec!["-c".to_string(), ECHO_SERVER.to_string()],
+        )
+        .with_env(HashMap::from([(
+            "ECHO_PREFIX".to_string(),
+            "echo: ".to_string(),
+        )]))
+    }
+
+    fn echo_args(message: &str) -> Option<HashMap<String, serde_json::Value>> {
+        Some(HashMap::from([(
+            "message".to_string(),
+            serde_json::json!(message),
+        )]))
+    }
+
+    fn text_of(result: &CallToolResult) -> &str {
+        match &result.content[0] {
+            workflow_engine_mcp::protocol::ToolContent::Text { text } => text,
+            other => panic!("Expected text content, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_round_trips() {
+        let mut node = echo_node();
+        node.connect().await.unwrap();
+        assert!(node.is_connected());
+
+        let tools = node.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "echo");
+
+        let result = node.execute_tool("echo", echo_args("hello")).await.unwrap();
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(text_of(&result), "echo: hello");
+
+        node.disconnect().await.unwrap();
+        assert!(!node.is_connected());
+    }
+
+    #[tokio::test]
+    async fn test_restarts_crashed_server() {
+        let mut node = echo_node().with_restart_config(true, 1);
+        node.connect().await.unwrap();
+
+        // The crash tool kills the process on every attempt, exhausting the restart budget
+        assert!(node.execute_tool("crash", None).await.is_err());
+        assert_eq!(node.restart_count(), 1);
+
+        // The next call finds the process dead, restarts it and succeeds
+        let result = node.execute_tool("echo", echo_args("again")).await.unwrap();
+        assert_eq!(text_of(&result), "echo: again");
+        assert_eq!(node.restart_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_not_connected() {
+        let mut node = echo_node();
+        let result = node.execute_tool("echo", None).await;
+        assert!(matches!(result, Err(WorkflowError::MCPConnectionError { .. })));
+    }
+
+    #[test]
+    fn test_node_process_calls_tool() {
+        let node = echo_node();
+        let context = TaskContext::new(
+            "stdio_test".to_string(),
+            serde_json::json!({
+                "tool_name": "echo",
+                "arguments": { "message": "from workflow" }
+            }),
+        );
+
+        let context = node.process(context).unwrap();
+        let output: serde_json::Value = context.get_node_data("echo").unwrap().unwrap();
+        assert_eq!(output["tool_name"], "echo");
+        assert_eq!(
+            output["result"]["content"][0]["text"],
+            "echo: from workflow"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! GraphQL nodes
+//!
+//! This module provides a node that queries another service's GraphQL
+//! endpoint, such as the content processing or knowledge graph services.
+
+use std::collections::HashMap;
+use workflow_engine_core::ai::templates::{EngineConfig, Template, TemplateEngine, TemplateVariables};
+use workflow_engine_core::prelude::*;
+
+/// Sends a GraphQL query to a service and stores the `data` of the response.
+///
+/// The variables are rendered from a Handlebars template, which sees the
+/// context as `event`, `nodes` and `metadata` and must produce a JSON
+/// object; use the `json` helper to embed values as JSON. The response's
+/// `data` is stored as the `graphql` node result.
+///
+/// A response with `errors`, a non-2xx status or a failed request fails the
+/// node with a [`WorkflowError::ApiError`] naming the service and carrying
+/// the GraphQL error messages.
+///
+/// ```rust
+/// use workflow_engine_nodes::graphql::GraphQLQueryNode;
+///
+/// let node = GraphQLQueryNode::new(
+///     "knowledge_graph",
+///     "http://localhost:3002/graphql",
+///     "query Related($concept: String!) { related(concept: $concept) { name weight } }",
+/// )
+/// .with_variables_template(r#"{"concept": {{json event.topic}}}"#)
+/// .with_output_key("related_concepts");
+/// ```
+#[derive(Debug)]
+pub struct GraphQLQueryNode {
+    service: String,
+    url: String,
+    query: String,
+    operation_name: Option<String>,
+    variables_template: Option<Template>,
+    headers: Vec<(String, String)>,
+    output_key: String,
+    client: reqwest::Client,
+    templates: TemplateEngine,
+}
+
+impl GraphQLQueryNode {
+    /// Create a node sending `query` to the GraphQL endpoint of `service` at
+    /// `url`
+    pub fn new(service: impl Into<String>, url: impl Into<String>, query: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            url: url.into(),
+            query: query.into(),
+            operation_name: None,
+            variables_template: None,
+            headers: Vec::new(),
+            output_key: "graphql".to_string(),
+            client: reqwest::Client::new(),
+            // Variables are JSON, which HTML escaping would corrupt
+            templates: TemplateEngine::with_config(EngineConfig {
+                escape_html: false,
+                ..EngineConfig::default()
+            }),
+        }
+    }
+
+    /// Render the query variables from a Handlebars template
+    pub fn with_variables_template(mut self, template: impl Into<String>) -> Self {
+        self.variables_template = Some(
+            Template::new("graphql_variables", template).expect("creating a template cannot fail"),
+        );
+        self
+    }
+
+    /// Select the operation to run from a query document holding several
+    pub fn with_operation_name(mut self, name: impl Into<String>) -> Self {
+        self.operation_name = Some(name.into());
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Build the query variables for `context`; an empty object when no
+    /// template is set
+    pub fn render_variables(&self, context: &TaskContext) -> Result<Value> {
+        let Some(template) = &self.variables_template else {
+            return Ok(json!({}));
+        };
+
+        let variables = HashMap::from([
+            ("event".to_string(), context.event_data.clone()),
+            ("nodes".to_string(), json!(context.get_all_data())),
+            ("metadata".to_string(), json!(context.get_all_metadata())),
+        ]);
+        let rendered = self
+            .templates
+            .render(template, &TemplateVariables::from_map(variables))?;
+        match serde_json::from_str(&rendered) {
+            Ok(Value::Object(variables)) => Ok(Value::Object(variables)),
+            Ok(_) => Err(WorkflowError::validation_error(
+                format!("variables must render to a JSON object, got {}", rendered),
+                "variables",
+                "json_object",
+                "in GraphQLQueryNode",
+            )),
+            Err(e) => Err(WorkflowError::validation_error(
+                format!("variables did not render to JSON ({}): {}", e, rendered),
+                "variables",
+                "json_object",
+                "in GraphQLQueryNode",
+            )),
+        }
+    }
+
+    /// Send the query, returning the response's `data`
+    pub async fn execute(&self, variables: Value) -> Result<Value> {
+        let mut body = json!({ "query": self.query, "variables": variables });
+        if let Some(operation_name) = &self.operation_name {
+            body["operationName"] = json!(operation_name);
+        }
+
+        let mut request = self.client.post(&self.url).json(&body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            WorkflowError::api_error(
+                format!("GraphQL request failed: {}", e),
+                &self.service,
+                &self.url,
+                None,
+            )
+        })?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        let payload: Option<Value> = serde_json::from_str(&text).ok();
+
+        // GraphQL servers may report errors with a 200 or with a 4xx status;
+        // either way the error messages are the useful part
+        if let Some(errors) = payload
+            .as_ref()
+            .and_then(|payload| payload.get("errors"))
+            .and_then(Value::as_array)
+            .filter(|errors| !errors.is_empty())
+        {
+            let messages: Vec<&str> = errors
+                .iter()
+                .map(|error| error.get("message").and_then(Value::as_str).unwrap_or("unknown error"))
+                .collect();
+            return Err(WorkflowError::api_error(
+                format!("GraphQL query returned errors: {}", messages.join("; ")),
+                &self.service,
+                &self.url,
+                Some(status.as_u16()),
+            ));
+        }
+        if !status.is_success() {
+            return Err(WorkflowError::api_error(
+                format!("GraphQL endpoint returned {}: {}", status, text),
+                &self.service,
+                &self.url,
+                Some(status.as_u16()),
+            ));
+        }
+
+        match payload.and_then(|mut payload| payload.get_mut("data").map(Value::take)) {
+            Some(data) => Ok(data),
+            None => Err(WorkflowError::api_error(
+                format!("GraphQL response has no data: {}", text),
+                &self.service,
+                &self.url,
+                Some(status.as_u16()),
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncNode for GraphQLQueryNode {
+    fn node_name(&self) -> String {
+        "GraphQLQueryNode".to_string()
+    }
+
+    async fn process_async(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let variables = self.render_variables(&task_context)?;
+        let data = self.execute(variables).await?;
+        task_context.update_node(&self.output_key, data);
+        Ok(task_context)
+    }
+}
+
+impl Node for GraphQLQueryNode {
+    fn node_name(&self) -> String {
+        "GraphQLQueryNode".to_string()
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| WorkflowError::RuntimeError {
+            message: format!("Failed to create runtime: {}", e),
+        })?;
+        runtime.block_on(self.process_with_cancellation(task_context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const QUERY: &str = "query Document($id: ID!) { document(id: $id) { title concepts } }";
+
+    fn context() -> TaskContext {
+        TaskContext::new("graphql_test".to_string(), json!({ "document_id": "doc-7" }))
+    }
+
+    fn node(server: &MockServer) -> GraphQLQueryNode {
+        GraphQLQueryNode::new("content_processing", format!("{}/graphql", server.uri()), QUERY)
+            .with_variables_template(r#"{"id": {{json event.document_id}}}"#)
+    }
+
+    #[tokio::test]
+    async fn test_query_data_is_stored_in_context() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(header("authorization", "Bearer token"))
+            .and(body_json(json!({ "query": QUERY, "variables": { "id": "doc-7" } })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "document": { "title": "Release notes", "concepts": ["rust", "async"] } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let node = node(&server)
+            .with_header("Authorization", "Bearer token")
+            .with_output_key("document");
+        let context = node.process_async(context()).await.unwrap();
+        let data: Value = context.get_node_data("document").unwrap().unwrap();
+        assert_eq!(data["document"]["title"], "Release notes");
+        assert_eq!(data["document"]["concepts"], json!(["rust", "async"]));
+    }
+
+    #[tokio::test]
+    async fn test_graphql_errors_fail_the_node() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": null,
+                "errors": [
+                    { "message": "Document doc-7 not found", "path": ["document"] },
+                    { "message": "Access denied" }
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        match node(&server).process_async(context()).await {
+            Err(WorkflowError::ApiError { message, service, status_code, .. }) => {
+                assert_eq!(service, "content_processing");
+                assert_eq!(status_code, Some(200));
+                assert!(message.contains("Document doc-7 not found; Access denied"));
+            }
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_variables_must_render_to_an_object() {
+        let node = GraphQLQueryNode::new("knowledge_graph", "http://localhost/graphql", QUERY)
+            .with_variables_template("{{json event.document_id}}");
+        assert!(node.render_variables(&context()).is_err());
+
+        let node = GraphQLQueryNode::new("knowledge_graph", "http://localhost/graphql", QUERY);
+        assert_eq!(node.render_variables(&context()).unwrap(), json!({}));
+    }
+}
@@ -7,6 +7,17 @@
 //! - External MCP client nodes 
 //! - Research and analysis nodes
 //! - Template processing nodes
+//! - Data transformation and JSON Patch nodes
+//! - Delay/pacing nodes
+//! - Text diff nodes
+//! - Deduplication nodes
+//! - Logging and metrics nodes
+//! - Conditional (guard clause) nodes
+//! - CSV/JSON export nodes
+//! - Outbound HTTP webhook nodes
+//! - GraphQL query nodes for other services
+//! - Locale-aware PII redaction nodes
+//! - Cache lookup nodes for reference data
 //! 
 //! ## Features
 //! 
@@ -14,6 +25,13 @@
 //! - `external-mcp` - External MCP server integration (enabled by default)
 //! - `research` - Research and analysis nodes
 //! - `template` - Template processing and generation nodes
+//! - `transform` - JSONPath-driven data transformation, JSON Patch and conditional nodes (enabled by default)
+//! - `export` - CSV/JSON export nodes (enabled by default)
+//! - `webhook` - Signed outbound HTTP webhook nodes (enabled by default)
+//! - `graphql` - Nodes querying other services' GraphQL endpoints (enabled by default)
+//! - `redaction` - PII redaction nodes with locale-specific detectors (enabled by default)
+//! - `cache` - Cache lookup nodes with an in-memory cache (enabled by default)
+//! - `redis` - Redis-backed cache for the cache lookup nodes
 //! - `all` - All node types
 //! 
 //! ## Node Categories
@@ -22,6 +40,15 @@
 //! - **External MCP**: Connect to external MCP servers for tool access
 //! - **Research**: Perform research and data analysis tasks
 //! - **Template**: Process templates and generate content
+//! - **Transform**: Reshape context data declaratively
+//! - **Export**: Serialize workflow outputs to CSV or JSON
+//! - **Webhook**: Notify external systems over HTTP
+//! - **GraphQL**: Query other microservices' GraphQL APIs
+//! - **Redaction**: Mask personal data before it leaves the workflow
+//! - **Cache**: Enrich the context with cached reference data
+//! - **Diff**: Show what changed between two versions of a text
+//! - **Dedupe**: Drop repeated items from lists
+//! - **Logging**: Log messages and emit metrics mid-workflow
 //! 
 //! ## Examples
 //! 
@@ -56,6 +83,10 @@ pub mod ai_agents;
 #[cfg_attr(docsrs, doc(cfg(feature = "external-mcp")))]
 pub mod external_mcp;
 
+#[cfg(feature = "external-mcp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "external-mcp")))]
+pub mod external_config;
+
 // Research nodes
 #[cfg(feature = "research")]
 #[cfg_attr(docsrs, doc(cfg(feature = "research")))]
@@ -66,6 +97,58 @@ pub mod research;
 #[cfg_attr(docsrs, doc(cfg(feature = "template")))]
 pub mod template;
 
+// Transformation nodes
+#[cfg(feature = "transform")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transform")))]
+pub mod transform;
+
+// JSON Patch nodes
+#[cfg(feature = "transform")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transform")))]
+pub mod patch;
+
+// Guard-clause nodes
+#[cfg(feature = "transform")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transform")))]
+pub mod conditional;
+
+// Export nodes
+#[cfg(feature = "export")]
+#[cfg_attr(docsrs, doc(cfg(feature = "export")))]
+pub mod export;
+
+// Webhook nodes
+#[cfg(feature = "webhook")]
+#[cfg_attr(docsrs, doc(cfg(feature = "webhook")))]
+pub mod webhook;
+
+// GraphQL nodes
+#[cfg(feature = "graphql")]
+#[cfg_attr(docsrs, doc(cfg(feature = "graphql")))]
+pub mod graphql;
+
+// Redaction nodes
+#[cfg(feature = "redaction")]
+#[cfg_attr(docsrs, doc(cfg(feature = "redaction")))]
+pub mod redaction;
+
+// Cache lookup nodes
+#[cfg(feature = "cache")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cache")))]
+pub mod cache;
+
+// Pacing nodes
+pub mod delay;
+
+// Text comparison nodes
+pub mod diff;
+
+// Deduplication nodes
+pub mod dedupe;
+
+// Logging and metrics nodes
+pub mod logging;
+
 // Common node utilities
 pub mod utils;
 
@@ -84,6 +167,34 @@ pub mod prelude {
     #[cfg(feature = "external-mcp")]
     pub use crate::external_mcp::*;
     
+    #[cfg(feature = "transform")]
+    pub use crate::transform::*;
+
+    #[cfg(feature = "transform")]
+    pub use crate::patch::*;
+
+    #[cfg(feature = "transform")]
+    pub use crate::conditional::*;
+
+    #[cfg(feature = "export")]
+    pub use crate::export::*;
+
+    #[cfg(feature = "webhook")]
+    pub use crate::webhook::*;
+
+    #[cfg(feature = "graphql")]
+    pub use crate::graphql::*;
+
+    #[cfg(feature = "redaction")]
+    pub use crate::redaction::*;
+
+    #[cfg(feature = "cache")]
+    pub use crate::cache::*;
+    
+    pub use crate::delay::{DelayMode, DelayNode};
+    pub use crate::diff::{DiffGranularity, DiffNode, TextDiff};
+    pub use crate::dedupe::{DedupeMode, DedupeNode};
+    pub use crate::logging::LogNode;
     pub use workflow_engine_core::prelude::*;
     pub use workflow_engine_mcp::prelude::*;
 }
\ No newline at end of file
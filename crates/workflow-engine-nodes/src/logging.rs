@@ -0,0 +1,244 @@
+//! Logging and metrics nodes
+//!
+//! This module provides a node that instruments a point in a workflow
+//! declaratively: it logs a message rendered from the context and emits
+//! metrics, leaving the context unchanged.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::Level;
+use workflow_engine_core::ai::templates::{EngineConfig, Template, TemplateEngine, TemplateVariables};
+use workflow_engine_core::metrics::{MetricsRecorder, NoopRecorder};
+use workflow_engine_core::prelude::*;
+
+/// A metric emitted each time the node runs
+#[derive(Debug)]
+enum Emission {
+    /// Increment a counter by one
+    Counter { name: String },
+    /// Set a gauge to the number rendered from a template
+    Gauge { name: String, value: Box<Template> },
+}
+
+/// Logs a message and emits metrics as the workflow passes through it.
+///
+/// The message and gauge values are rendered from Handlebars templates,
+/// which see the context as `event`, `nodes` and `metadata`. The message is
+/// logged through `tracing` at the configured level, with the run's
+/// `workflow` and `event_id` as fields. Metrics are labelled by `workflow`.
+/// A gauge whose value doesn't render to a number is logged and skipped.
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use tracing::Level;
+/// use workflow_engine_core::metrics::NoopRecorder;
+/// use workflow_engine_nodes::logging::LogNode;
+///
+/// let node = LogNode::new(Level::INFO, "Order {{event.order_id}} reached fulfilment")
+///     .with_metrics(Arc::new(NoopRecorder))
+///     .with_counter("orders_fulfilled_total")
+///     .with_gauge("order_total_usd", "{{event.total}}");
+/// ```
+#[derive(Debug)]
+pub struct LogNode {
+    level: Level,
+    message: Template,
+    emissions: Vec<Emission>,
+    metrics: Arc<dyn MetricsRecorder>,
+    templates: TemplateEngine,
+}
+
+impl LogNode {
+    pub fn new(level: Level, message_template: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: Template::new("log_message", message_template).expect("creating a template cannot fail"),
+            emissions: Vec::new(),
+            metrics: Arc::new(NoopRecorder),
+            // Messages are logged verbatim, so they must not be HTML escaped
+            templates: TemplateEngine::with_config(EngineConfig {
+                escape_html: false,
+                ..EngineConfig::default()
+            }),
+        }
+    }
+
+    /// Emit metrics to `metrics` rather than discarding them
+    pub fn with_metrics(mut self, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Increment the counter `name` each time the node runs
+    pub fn with_counter(mut self, name: impl Into<String>) -> Self {
+        self.emissions.push(Emission::Counter { name: name.into() });
+        self
+    }
+
+    /// Set the gauge `name` to the number rendered from `value_template`
+    /// each time the node runs
+    pub fn with_gauge(mut self, name: impl Into<String>, value_template: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = Template::new(format!("gauge_{}", name), value_template)
+            .expect("creating a template cannot fail");
+        self.emissions.push(Emission::Gauge { name, value: Box::new(value) });
+        self
+    }
+
+    fn variables(context: &TaskContext) -> TemplateVariables {
+        TemplateVariables::from_map(HashMap::from([
+            ("event".to_string(), context.event_data.clone()),
+            ("nodes".to_string(), json!(context.get_all_data())),
+            ("metadata".to_string(), json!(context.get_all_metadata())),
+        ]))
+    }
+
+    /// The message logged for `context`
+    pub fn render_message(&self, context: &TaskContext) -> Result<String> {
+        Ok(self.templates.render(&self.message, &Self::variables(context))?)
+    }
+
+    fn log(&self, context: &TaskContext, message: &str) {
+        let workflow = context.workflow_type.as_str();
+        let event_id = context.event_id;
+        match self.level {
+            Level::ERROR => tracing::error!(workflow, %event_id, "{}", message),
+            Level::WARN => tracing::warn!(workflow, %event_id, "{}", message),
+            Level::INFO => tracing::info!(workflow, %event_id, "{}", message),
+            Level::DEBUG => tracing::debug!(workflow, %event_id, "{}", message),
+            Level::TRACE => tracing::trace!(workflow, %event_id, "{}", message),
+        }
+    }
+
+    fn emit(&self, context: &TaskContext) -> Result<()> {
+        let labels = [("workflow", context.workflow_type.as_str())];
+        let variables = Self::variables(context);
+        for emission in &self.emissions {
+            match emission {
+                Emission::Counter { name } => self.metrics.increment_counter(name, &labels, 1),
+                Emission::Gauge { name, value } => {
+                    let rendered = self.templates.render(value, &variables)?;
+                    match rendered.trim().parse::<f64>() {
+                        Ok(value) => self.metrics.set_gauge(name, &labels, value),
+                        Err(_) => tracing::warn!("Gauge '{}' value '{}' is not a number, skipping it", name, rendered),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Node for LogNode {
+    fn node_name(&self) -> String {
+        "LogNode".to_string()
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext> {
+        let message = self.render_message(&task_context)?;
+        self.log(&task_context, &message);
+        self.emit(&task_context)?;
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::Mutex;
+
+    /// Records the metrics it is given
+    #[derive(Debug, Default)]
+    struct RecordingMetrics {
+        counters: Mutex<Vec<(String, u64)>>,
+        gauges: Mutex<Vec<(String, f64)>>,
+    }
+
+    impl MetricsRecorder for RecordingMetrics {
+        fn increment_counter(&self, name: &str, labels: &[(&str, &str)], value: u64) {
+            assert_eq!(labels, &[("workflow", "orders")]);
+            self.counters.lock().unwrap().push((name.to_string(), value));
+        }
+
+        fn set_gauge(&self, name: &str, _labels: &[(&str, &str)], value: f64) {
+            self.gauges.lock().unwrap().push((name.to_string(), value));
+        }
+
+        fn record_histogram(&self, _name: &str, _labels: &[(&str, &str)], _value: f64) {}
+    }
+
+    /// Collects formatted log output
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Process `context` with `node`, returning what was logged at
+    /// `max_level` or above
+    fn process_logged(node: &LogNode, context: TaskContext, max_level: Level) -> (TaskContext, String) {
+        let output = Output::default();
+        let writer = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(max_level)
+            .with_ansi(false)
+            .without_time()
+            .with_writer(move || writer.clone())
+            .finish();
+        let context = tracing::subscriber::with_default(subscriber, || node.process(context)).unwrap();
+        let logged = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        (context, logged)
+    }
+
+    fn context() -> TaskContext {
+        let mut context = TaskContext::new("orders".to_string(), json!({ "order_id": 42, "total": "19.5" }));
+        context.update_node("carrier", json!({ "name": "UPS & Co" }));
+        context
+    }
+
+    #[test]
+    fn test_logs_rendered_message_at_level() {
+        let node = LogNode::new(Level::WARN, "Order {{event.order_id}} shipped with {{nodes.carrier.name}}");
+        let (processed, logged) = process_logged(&node, context(), Level::WARN);
+
+        assert!(logged.contains("WARN"), "{}", logged);
+        assert!(logged.contains("Order 42 shipped with UPS & Co"), "{}", logged);
+        assert!(logged.contains("workflow=\"orders\""), "{}", logged);
+        assert_eq!(processed.get_all_data(), context().get_all_data());
+
+        // Below the subscriber's level, nothing is logged
+        let node = LogNode::new(Level::DEBUG, "Order {{event.order_id}} shipped");
+        let (_, logged) = process_logged(&node, context(), Level::INFO);
+        assert!(logged.is_empty(), "{}", logged);
+    }
+
+    #[test]
+    fn test_emits_metrics() {
+        let metrics = Arc::new(RecordingMetrics::default());
+        let node = LogNode::new(Level::INFO, "Order {{event.order_id}} fulfilled")
+            .with_metrics(metrics.clone())
+            .with_counter("orders_fulfilled_total")
+            .with_gauge("order_total_usd", "{{event.total}}")
+            .with_gauge("order_weight_kg", "{{event.weight}}");
+
+        let (_, logged) = process_logged(&node, context(), Level::INFO);
+        assert!(logged.contains("INFO"), "{}", logged);
+        assert!(logged.contains("Order 42 fulfilled"), "{}", logged);
+        assert_eq!(
+            *metrics.counters.lock().unwrap(),
+            vec![("orders_fulfilled_total".to_string(), 1)]
+        );
+        // The weight is missing, so its gauge is skipped
+        assert_eq!(*metrics.gauges.lock().unwrap(), vec![("order_total_usd".to_string(), 19.5)]);
+        assert!(logged.contains("Gauge 'order_weight_kg'"), "{}", logged);
+    }
+}
@@ -0,0 +1,237 @@
+//! JSON Patch nodes
+//!
+//! This module provides nodes that apply RFC 6902 JSON Patches to context data.
+
+use json_patch::{Patch, PatchErrorKind};
+use workflow_engine_core::prelude::*;
+
+use crate::utils::InputExtractor;
+
+/// Where a [`JsonPatchNode`] gets its patch from
+#[derive(Debug, Clone)]
+pub enum PatchSource {
+    /// A patch fixed when the node is built
+    Fixed(Patch),
+    /// A patch read from the context on each run
+    Context(InputExtractor),
+}
+
+/// Applies an RFC 6902 JSON Patch to a document in the context.
+///
+/// Supports the `add`, `remove`, `replace`, `move`, `copy` and `test`
+/// operations. The patch is applied atomically: if any operation fails,
+/// including a `test` whose value does not match, the node fails with a
+/// [`WorkflowError::ValidationError`] naming the operation and its path, and
+/// nothing is written to the context.
+///
+/// ```rust
+/// use serde_json::json;
+/// use workflow_engine_nodes::patch::JsonPatchNode;
+///
+/// let node = JsonPatchNode::new(json!([
+///     { "op": "test", "path": "/status", "value": "draft" },
+///     { "op": "replace", "path": "/status", "value": "published" }
+/// ]))
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonPatchNode {
+    patch: PatchSource,
+    target: InputExtractor,
+    output_key: String,
+}
+
+impl JsonPatchNode {
+    /// Create a node applying a fixed patch, given as an array of operations.
+    ///
+    /// By default the patch is applied to the event data and the result is
+    /// stored as the `json_patch` node result.
+    pub fn new(patch: Value) -> Result<Self> {
+        Ok(Self::with_patch_source(PatchSource::Fixed(parse_patch(patch)?)))
+    }
+
+    /// Create a node applying whatever patch `patch` finds in the context
+    pub fn from_context(patch: InputExtractor) -> Self {
+        Self::with_patch_source(PatchSource::Context(patch))
+    }
+
+    fn with_patch_source(patch: PatchSource) -> Self {
+        Self {
+            patch,
+            target: InputExtractor::new().event_data(),
+            output_key: "json_patch".to_string(),
+        }
+    }
+
+    /// Patch the value found by `target` instead of the event data
+    pub fn with_target(mut self, target: InputExtractor) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Store the patched document under `key` instead of `json_patch`
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Apply `patch` to a copy of `document`
+    pub fn apply(patch: &Patch, document: &Value) -> Result<Value> {
+        let mut patched = document.clone();
+        json_patch::patch(&mut patched, patch).map_err(|e| {
+            let op = patch.0.get(e.operation).map(op_name).unwrap_or("unknown");
+            let constraint = match e.kind {
+                PatchErrorKind::TestFailed => "value must match the test operation",
+                PatchErrorKind::InvalidFromPointer => "\"from\" must point to an existing value",
+                PatchErrorKind::CannotMoveInsideItself => "value cannot be moved inside itself",
+                _ => "path must point to a valid location",
+            };
+            WorkflowError::validation_error(
+                format!(
+                    "JSON Patch operation {} ({}) failed at '{}': {}",
+                    e.operation, op, e.path, e.kind
+                ),
+                e.path.to_string(),
+                constraint,
+                "in JsonPatchNode::process",
+            )
+        })?;
+        Ok(patched)
+    }
+}
+
+fn parse_patch(patch: Value) -> Result<Patch> {
+    serde_json::from_value(patch.clone()).map_err(|e| WorkflowError::DeserializationError {
+        message: format!("Invalid JSON Patch: {}", e),
+        expected_type: "RFC 6902 JSON Patch".to_string(),
+        context: "in JsonPatchNode".to_string(),
+        raw_data: Some(patch.to_string()),
+        source: Some(e),
+    })
+}
+
+fn op_name(operation: &json_patch::PatchOperation) -> &'static str {
+    use json_patch::PatchOperation::*;
+    match operation {
+        Add(_) => "add",
+        Remove(_) => "remove",
+        Replace(_) => "replace",
+        Move(_) => "move",
+        Copy(_) => "copy",
+        Test(_) => "test",
+    }
+}
+
+impl Node for JsonPatchNode {
+    fn node_name(&self) -> String {
+        "JsonPatchNode".to_string()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let document = self.target.extract(&task_context)?;
+        let patched = match &self.patch {
+            PatchSource::Fixed(patch) => Self::apply(patch, &document)?,
+            PatchSource::Context(source) => {
+                let patch = parse_patch(source.extract(&task_context)?)?;
+                Self::apply(&patch, &document)?
+            }
+        };
+        task_context.update_node(&self.output_key, patched);
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TaskContext {
+        TaskContext::new(
+            "patch_test".to_string(),
+            json!({
+                "title": "Draft",
+                "status": "draft",
+                "tags": ["internal"],
+                "author": { "name": "Ada", "email": "ada@example.com" }
+            }),
+        )
+    }
+
+    #[test]
+    fn test_applies_multi_operation_patch() {
+        let node = JsonPatchNode::new(json!([
+            { "op": "test", "path": "/status", "value": "draft" },
+            { "op": "replace", "path": "/status", "value": "published" },
+            { "op": "add", "path": "/tags/-", "value": "release" },
+            { "op": "remove", "path": "/author/email" },
+            { "op": "copy", "from": "/author/name", "path": "/reviewer" },
+            { "op": "move", "from": "/title", "path": "/heading" }
+        ]))
+        .unwrap();
+
+        let context = node.process(context()).unwrap();
+        let patched: Value = context.get_node_data("json_patch").unwrap().unwrap();
+        assert_eq!(
+            patched,
+            json!({
+                "heading": "Draft",
+                "status": "published",
+                "tags": ["internal", "release"],
+                "author": { "name": "Ada" },
+                "reviewer": "Ada"
+            })
+        );
+    }
+
+    #[test]
+    fn test_failing_test_operation_aborts_node() {
+        let node = JsonPatchNode::new(json!([
+            { "op": "replace", "path": "/title", "value": "Final" },
+            { "op": "test", "path": "/status", "value": "approved" }
+        ]))
+        .unwrap();
+
+        let result = node.process(context());
+        match result {
+            Err(WorkflowError::ValidationError { message, field, .. }) => {
+                assert_eq!(field, "/status");
+                assert!(message.contains("operation 1 (test)"));
+            }
+            other => panic!("Expected ValidationError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_path_is_reported() {
+        let node = JsonPatchNode::new(json!([
+            { "op": "remove", "path": "/author/phone" }
+        ]))
+        .unwrap();
+
+        assert!(matches!(
+            node.process(context()),
+            Err(WorkflowError::ValidationError { ref field, .. }) if field == "/author/phone"
+        ));
+    }
+
+    #[test]
+    fn test_patch_and_target_from_context() {
+        let mut context = context();
+        context.update_node("draft", json!({ "body": "Hello" }));
+        context.update_node("edits", json!([{ "op": "add", "path": "/footer", "value": "Bye" }]));
+
+        let node = JsonPatchNode::from_context(InputExtractor::new().node_result("edits"))
+            .with_target(InputExtractor::new().node_result("draft"))
+            .with_output_key("draft");
+
+        let context = node.process(context).unwrap();
+        let draft: Value = context.get_node_data("draft").unwrap().unwrap();
+        assert_eq!(draft, json!({ "body": "Hello", "footer": "Bye" }));
+    }
+
+    #[test]
+    fn test_malformed_patch_fails_at_construction() {
+        let result = JsonPatchNode::new(json!([{ "op": "rename", "path": "/title" }]));
+        assert!(matches!(result, Err(WorkflowError::DeserializationError { .. })));
+    }
+}
@@ -0,0 +1,402 @@
+//! PII redaction nodes
+//!
+//! This module provides a node that masks personal data in context values,
+//! using detectors chosen by locale so regional formats such as phone
+//! numbers and national IDs are recognised.
+
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use workflow_engine_core::prelude::*;
+
+use crate::utils::InputExtractor;
+
+/// A span of text recognised as personal data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiiMatch {
+    /// What was found, e.g. `email` or `nhs_number`
+    pub kind: String,
+    /// Byte range of the match
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds personal data in text
+pub trait PiiDetector: Send + Sync + Debug {
+    /// Locale this detector is for, e.g. `GB`
+    fn locale(&self) -> &str;
+
+    /// Matches in `text`, in any order
+    fn detect(&self, text: &str) -> Vec<PiiMatch>;
+}
+
+/// A pattern and an optional check a match must pass, such as a checksum
+#[derive(Clone)]
+struct Pattern {
+    kind: String,
+    regex: Regex,
+    check: Option<fn(&str) -> bool>,
+}
+
+impl Debug for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pattern")
+            .field("kind", &self.kind)
+            .field("regex", &self.regex.as_str())
+            .finish()
+    }
+}
+
+/// Detector built from regular expressions.
+///
+/// [`PatternDetector::generic`] knows formats that are the same everywhere;
+/// the locale-specific detectors add their region's formats to those.
+#[derive(Debug, Clone)]
+pub struct PatternDetector {
+    locale: String,
+    patterns: Vec<Pattern>,
+}
+
+impl PatternDetector {
+    /// An empty detector for `locale`
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Detect matches of `pattern` as `kind`
+    pub fn with_pattern(self, kind: impl Into<String>, pattern: &str) -> Result<Self> {
+        self.push(kind.into(), pattern, None)
+    }
+
+    fn push(mut self, kind: String, pattern: &str, check: Option<fn(&str) -> bool>) -> Result<Self> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            WorkflowError::validation_error(
+                format!("Invalid PII pattern '{}': {}", pattern, e),
+                kind.clone(),
+                "valid regular expression",
+                "in PatternDetector::with_pattern",
+            )
+        })?;
+        self.patterns.push(Pattern { kind, regex, check });
+        Ok(self)
+    }
+
+    fn builtin(self, kind: &str, pattern: &str, check: Option<fn(&str) -> bool>) -> Self {
+        self.push(kind.to_string(), pattern, check)
+            .expect("built-in PII patterns are valid")
+    }
+
+    /// Emails, international phone numbers and payment card numbers
+    pub fn generic() -> Self {
+        Self::new(GENERIC_LOCALE).with_generic_patterns()
+    }
+
+    /// Generic formats plus US phone numbers and Social Security numbers
+    pub fn us() -> Self {
+        Self::new("US")
+            .with_generic_patterns()
+            .builtin("phone", r"\(?\b[2-9]\d{2}\)?[-. ]\d{3}[-. ]\d{4}\b", None)
+            .builtin("ssn", r"\b\d{3}-\d{2}-\d{4}\b", None)
+    }
+
+    /// Generic formats plus UK phone numbers, NHS numbers and National
+    /// Insurance numbers
+    pub fn gb() -> Self {
+        Self::new("GB")
+            .with_generic_patterns()
+            .builtin("phone", r"\b0\d{4} ?\d{6}\b|\b0\d{2,3} ?\d{3,4} ?\d{4}\b", None)
+            .builtin("nhs_number", r"\b\d{3}[ -]?\d{3}[ -]?\d{4}\b", Some(valid_nhs_number))
+            .builtin("national_insurance_number", r"(?i)\b[A-CEGHJ-PR-TW-Z]{2} ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b", None)
+    }
+
+    fn with_generic_patterns(self) -> Self {
+        self.builtin("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", None)
+            .builtin("phone", r"\+\d{1,3}[ -]?\d(?:[ -]?\d){6,13}\b", None)
+            .builtin("card_number", r"\b\d(?:[ -]?\d){12,18}\b", Some(passes_luhn))
+    }
+}
+
+impl PiiDetector for PatternDetector {
+    fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    fn detect(&self, text: &str) -> Vec<PiiMatch> {
+        self.patterns
+            .iter()
+            .flat_map(|pattern| {
+                pattern
+                    .regex
+                    .find_iter(text)
+                    .filter(|m| pattern.check.is_none_or(|check| check(m.as_str())))
+                    .map(|m| PiiMatch {
+                        kind: pattern.kind.clone(),
+                        start: m.start(),
+                        end: m.end(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// NHS numbers end in a modulus 11 check digit
+fn valid_nhs_number(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() != 10 {
+        return false;
+    }
+    let sum: u32 = digits[..9].iter().zip((2..=10).rev()).map(|(d, w)| d * w).sum();
+    match 11 - sum % 11 {
+        11 => digits[9] == 0,
+        10 => false,
+        check => digits[9] == check,
+    }
+}
+
+/// Payment card numbers pass the Luhn check
+fn passes_luhn(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Locale of the detector used when no other matches
+pub const GENERIC_LOCALE: &str = "generic";
+
+/// Detectors by locale.
+///
+/// Locales are matched on their region, so `GB`, `en-GB` and `en_GB` all
+/// find the `GB` detector. Unknown locales get the generic detector.
+#[derive(Debug, Clone)]
+pub struct PiiDetectorRegistry {
+    detectors: HashMap<String, Arc<dyn PiiDetector>>,
+    fallback: Arc<dyn PiiDetector>,
+}
+
+impl Default for PiiDetectorRegistry {
+    /// The built-in `US` and `GB` detectors
+    fn default() -> Self {
+        Self::new().with_detector(PatternDetector::us()).with_detector(PatternDetector::gb())
+    }
+}
+
+impl PiiDetectorRegistry {
+    /// A registry with only the generic detector
+    pub fn new() -> Self {
+        Self {
+            detectors: HashMap::new(),
+            fallback: Arc::new(PatternDetector::generic()),
+        }
+    }
+
+    /// Register `detector` for its locale, replacing any already registered
+    pub fn with_detector(mut self, detector: impl PiiDetector + 'static) -> Self {
+        self.detectors.insert(region(detector.locale()), Arc::new(detector));
+        self
+    }
+
+    /// Detector for `locale`, or the generic one if none is registered
+    pub fn get(&self, locale: &str) -> Arc<dyn PiiDetector> {
+        match self.detectors.get(&region(locale)) {
+            Some(detector) => detector.clone(),
+            None => {
+                log::debug!("No PII detector for locale '{}', using generic detector", locale);
+                self.fallback.clone()
+            }
+        }
+    }
+}
+
+/// The region of a locale tag, upper-cased
+fn region(locale: &str) -> String {
+    locale.rsplit(['-', '_']).next().unwrap_or(locale).to_uppercase()
+}
+
+/// Configuration for a [`RedactionNode`], e.g. from a workflow definition
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedactionConfig {
+    /// Locale whose detector is used, e.g. `en-GB`
+    pub locale: String,
+    /// Mask to replace matches with; `{kind}` is replaced with what was found
+    #[serde(default = "default_mask")]
+    pub mask: String,
+}
+
+fn default_mask() -> String {
+    "[REDACTED:{kind}]".to_string()
+}
+
+/// Masks personal data in every string of its input.
+///
+/// ```rust
+/// use workflow_engine_nodes::redaction::{PiiDetectorRegistry, RedactionConfig, RedactionNode};
+///
+/// let config: RedactionConfig = serde_json::from_value(serde_json::json!({ "locale": "en-GB" })).unwrap();
+/// let node = RedactionNode::from_config(&config, &PiiDetectorRegistry::default());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RedactionNode {
+    detector: Arc<dyn PiiDetector>,
+    mask: String,
+    input: InputExtractor,
+    output_key: String,
+}
+
+impl RedactionNode {
+    /// A node using the built-in detector for `locale`
+    pub fn new(locale: &str) -> Self {
+        Self::with_detector(PiiDetectorRegistry::default().get(locale))
+    }
+
+    /// A node using the detector `registry` has for the configured locale
+    pub fn from_config(config: &RedactionConfig, registry: &PiiDetectorRegistry) -> Self {
+        let mut node = Self::with_detector(registry.get(&config.locale));
+        node.mask = config.mask.clone();
+        node
+    }
+
+    pub fn with_detector(detector: Arc<dyn PiiDetector>) -> Self {
+        Self {
+            detector,
+            mask: default_mask(),
+            input: InputExtractor::new().event_data(),
+            output_key: "redaction".to_string(),
+        }
+    }
+
+    /// Redact the value found by `input` instead of the event data
+    pub fn with_input(mut self, input: InputExtractor) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Store the redacted value under `key` instead of `redaction`
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    pub fn locale(&self) -> &str {
+        self.detector.locale()
+    }
+
+    /// Mask the personal data in `text`
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut matches = self.detector.detect(text);
+        // Where matches overlap, the earliest and then the longest wins
+        matches.sort_by_key(|m| (m.start, std::cmp::Reverse(m.end)));
+
+        let mut redacted = String::with_capacity(text.len());
+        let mut position = 0;
+        for m in matches {
+            if m.start < position {
+                continue;
+            }
+            redacted.push_str(&text[position..m.start]);
+            redacted.push_str(&self.mask.replace("{kind}", &m.kind));
+            position = m.end;
+        }
+        redacted.push_str(&text[position..]);
+        redacted
+    }
+
+    /// Mask the personal data in every string of `value`
+    pub fn redact(&self, value: &Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.redact_text(text)),
+            Value::Array(items) => Value::Array(items.iter().map(|item| self.redact(item)).collect()),
+            Value::Object(fields) => Value::Object(
+                fields.iter().map(|(key, value)| (key.clone(), self.redact(value))).collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+}
+
+impl Node for RedactionNode {
+    fn node_name(&self) -> String {
+        "RedactionNode".to_string()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let input = self.input.extract(&task_context)?;
+        task_context.update_node(&self.output_key, self.redact(&input));
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NHS_NUMBER: &str = "943 476 5919";
+
+    #[test]
+    fn test_gb_locale_detects_nhs_number_generic_misses() {
+        let text = format!("Patient NHS no. {} registered", NHS_NUMBER);
+
+        let generic = PatternDetector::generic();
+        assert!(generic.detect(&text).is_empty());
+
+        let gb = PiiDetectorRegistry::default().get("en-GB");
+        assert_eq!(gb.locale(), "GB");
+        let matches = gb.detect(&text);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].kind, "nhs_number");
+        assert_eq!(&text[matches[0].start..matches[0].end], NHS_NUMBER);
+
+        // Ten digits with a bad check digit aren't an NHS number
+        assert!(gb.detect("Order 943 476 5918").is_empty());
+    }
+
+    #[test]
+    fn test_unknown_locale_falls_back_to_generic() {
+        let registry = PiiDetectorRegistry::default();
+        assert_eq!(registry.get("fr-FR").locale(), GENERIC_LOCALE);
+        assert_eq!(registry.get("en_us").locale(), "US");
+    }
+
+    #[test]
+    fn test_node_redacts_strings_by_configured_locale() {
+        let config: RedactionConfig = serde_json::from_value(json!({ "locale": "en-GB" })).unwrap();
+        let node = RedactionNode::from_config(&config, &PiiDetectorRegistry::default());
+        let context = TaskContext::new(
+            "redaction_test".to_string(),
+            json!({
+                "note": format!("Call 07700 900123 about NHS {}", NHS_NUMBER),
+                "contacts": ["jo@example.co.uk", "Card 4111 1111 1111 1111"],
+                "age": 42
+            }),
+        );
+
+        let context = node.process(context).unwrap();
+        let redacted: Value = context.get_node_data("redaction").unwrap().unwrap();
+        assert_eq!(
+            redacted,
+            json!({
+                "note": "Call [REDACTED:phone] about NHS [REDACTED:nhs_number]",
+                "contacts": ["[REDACTED:email]", "Card [REDACTED:card_number]"],
+                "age": 42
+            })
+        );
+
+        // The GB detector doesn't know US formats
+        assert_eq!(node.redact_text("SSN 078-05-1120"), "SSN 078-05-1120");
+        let us = RedactionNode::new("US");
+        assert_eq!(us.redact_text("SSN 078-05-1120"), "SSN [REDACTED:ssn]");
+    }
+}
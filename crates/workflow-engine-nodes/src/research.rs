@@ -3,8 +3,11 @@
 //! This module provides nodes for research tasks, data analysis,
 //! and information gathering workflows.
 
+use serde::{Deserialize, Serialize};
 use workflow_engine_core::prelude::*;
 
+use crate::utils::InputExtractor;
+
 /// Research node for information gathering
 #[derive(Debug)]
 pub struct ResearchNode {
@@ -25,4 +28,466 @@ impl Node for ResearchNode {
         // For now, return a placeholder
         Ok(context)
     }
-}
\ No newline at end of file
+}
+/// A single hit returned by a [`ResearchSource`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub title: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub snippet: Option<String>,
+    /// Relevance reported by the source, if any (expected in `0.0..=1.0`)
+    #[serde(default)]
+    pub score: Option<f64>,
+}
+
+/// Aggregated result written by [`MultiSourceResearchNode`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResearchResult {
+    pub title: String,
+    pub url: Option<String>,
+    pub snippet: Option<String>,
+    pub score: f64,
+    /// Names of every source that returned this result
+    pub sources: Vec<String>,
+}
+
+/// A searchable backend queried by [`MultiSourceResearchNode`]
+#[async_trait]
+pub trait ResearchSource: Send + Sync + std::fmt::Debug {
+    fn name(&self) -> &str;
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>>;
+}
+
+/// Accepts either a bare array of hits or an object with a `results` array
+fn parse_hits(value: Value, source: &str) -> Result<Vec<SearchHit>> {
+    let hits = match value {
+        Value::Object(mut object) => object.remove("results").unwrap_or(Value::Null),
+        other => other,
+    };
+    serde_json::from_value(hits.clone()).map_err(|e| WorkflowError::DeserializationError {
+        message: format!("Invalid search results from '{}': {}", source, e),
+        expected_type: "Vec<SearchHit>".to_string(),
+        context: "in ResearchSource::search".to_string(),
+        raw_data: Some(hits.to_string()),
+        source: Some(e),
+    })
+}
+
+/// Source backed by an HTTP search endpoint.
+///
+/// Sends `GET {url}?q={query}` and expects either an array of hits or an
+/// object with a `results` array.
+#[derive(Debug, Clone)]
+pub struct HttpResearchSource {
+    name: String,
+    url: String,
+    query_param: String,
+    client: reqwest::Client,
+}
+
+impl HttpResearchSource {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            query_param: "q".to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Send the query under `param` instead of `q`
+    pub fn with_query_param(mut self, param: impl Into<String>) -> Self {
+        self.query_param = param.into();
+        self
+    }
+}
+
+#[async_trait]
+impl ResearchSource for HttpResearchSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let response = self
+            .client
+            .get(&self.url)
+            .query(&[(self.query_param.as_str(), query)])
+            .send()
+            .await
+            .map_err(|e| {
+                WorkflowError::api_error(
+                    format!("Search request failed: {}", e),
+                    &self.name,
+                    &self.url,
+                    None,
+                )
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(WorkflowError::api_error(
+                format!("Search returned HTTP {}", status),
+                &self.name,
+                &self.url,
+                Some(status.as_u16()),
+            ));
+        }
+
+        let body: Value = response.json().await.map_err(|e| {
+            WorkflowError::api_error(
+                format!("Failed to read search response: {}", e),
+                &self.name,
+                &self.url,
+                Some(status.as_u16()),
+            )
+        })?;
+        parse_hits(body, &self.name)
+    }
+}
+
+/// Source backed by a tool on an external MCP server.
+///
+/// Calls the tool with a `query` argument and parses its first text content
+/// as JSON hits.
+#[cfg(feature = "external-mcp")]
+#[derive(Debug)]
+pub struct McpResearchSource {
+    tool_name: String,
+    client: tokio::sync::Mutex<crate::external_mcp::BaseExternalMcpClient>,
+    name: String,
+}
+
+#[cfg(feature = "external-mcp")]
+impl McpResearchSource {
+    pub fn new(config: crate::external_mcp::ExternalMcpConfig, tool_name: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            name: config.service_name.clone(),
+            client: tokio::sync::Mutex::new(crate::external_mcp::BaseExternalMcpClient::new(config)),
+        }
+    }
+}
+
+#[cfg(feature = "external-mcp")]
+#[async_trait]
+impl ResearchSource for McpResearchSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        use crate::external_mcp::ExternalMcpClientNode;
+        use workflow_engine_mcp::protocol::ToolContent;
+
+        let mut client = self.client.lock().await;
+        if !client.is_connected() {
+            client.connect().await?;
+        }
+        let arguments = std::collections::HashMap::from([("query".to_string(), json!(query))]);
+        let result = client.execute_tool(&self.tool_name, Some(arguments)).await?;
+
+        let text = result
+            .content
+            .iter()
+            .find_map(|content| match content {
+                ToolContent::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .unwrap_or("[]");
+        let value = serde_json::from_str(text).map_err(|e| WorkflowError::DeserializationError {
+            message: format!("Tool '{}' did not return JSON: {}", self.tool_name, e),
+            expected_type: "Vec<SearchHit>".to_string(),
+            context: "in McpResearchSource::search".to_string(),
+            raw_data: Some(text.to_string()),
+            source: Some(e),
+        })?;
+        parse_hits(value, &self.name)
+    }
+}
+
+/// Queries several sources in parallel and merges their results.
+///
+/// Results are deduplicated by URL (or title when there is no URL), keeping
+/// the highest score and every source that returned them, then sorted by
+/// relevance. Hits without a source score are scored by the fraction of query
+/// terms they contain. A failing source is logged and reported under
+/// `failed_sources` rather than failing the node.
+#[derive(Debug)]
+pub struct MultiSourceResearchNode {
+    sources: Vec<Box<dyn ResearchSource>>,
+    input: InputExtractor,
+    output_key: String,
+    max_results: usize,
+}
+
+impl MultiSourceResearchNode {
+    /// Create a node reading the query from the `query` event field and
+    /// storing results under `research`
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            input: InputExtractor::new().event_field("query"),
+            output_key: "research".to_string(),
+            max_results: 20,
+        }
+    }
+
+    pub fn with_source(mut self, source: impl ResearchSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Read the query from `input` instead of the `query` event field
+    pub fn with_input(mut self, input: InputExtractor) -> Self {
+        self.input = input;
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    /// Query every source and return the merged, ranked results along with
+    /// the names of sources that failed
+    pub async fn research(&self, query: &str) -> (Vec<ResearchResult>, Vec<String>) {
+        let responses = futures_util::future::join_all(
+            self.sources.iter().map(|source| async move { (source.name(), source.search(query).await) }),
+        )
+        .await;
+
+        let mut merged: Vec<ResearchResult> = Vec::new();
+        let mut failed_sources = Vec::new();
+        for (source, response) in responses {
+            let hits = match response {
+                Ok(hits) => hits,
+                Err(e) => {
+                    log::warn!("Research source '{}' failed, skipping: {}", source, e);
+                    failed_sources.push(source.to_string());
+                    continue;
+                }
+            };
+
+            for hit in hits {
+                let score = hit.score.unwrap_or_else(|| term_overlap(query, &hit));
+                let key = dedup_key(&hit);
+                match merged.iter_mut().find(|existing| dedup_key_of(existing) == key) {
+                    Some(existing) => {
+                        if score > existing.score {
+                            existing.score = score;
+                            existing.snippet = hit.snippet.or(existing.snippet.take());
+                        }
+                        if !existing.sources.iter().any(|s| s == source) {
+                            existing.sources.push(source.to_string());
+                        }
+                    }
+                    None => merged.push(ResearchResult {
+                        title: hit.title,
+                        url: hit.url,
+                        snippet: hit.snippet,
+                        score,
+                        sources: vec![source.to_string()],
+                    }),
+                }
+            }
+        }
+
+        merged.sort_by(|a, b| b.score.total_cmp(&a.score));
+        merged.truncate(self.max_results);
+        (merged, failed_sources)
+    }
+}
+
+impl Default for MultiSourceResearchNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn normalize_key(url: Option<&str>, title: &str) -> String {
+    match url {
+        Some(url) => url.trim().trim_end_matches('/').to_lowercase(),
+        None => title.trim().to_lowercase(),
+    }
+}
+
+fn dedup_key(hit: &SearchHit) -> String {
+    normalize_key(hit.url.as_deref(), &hit.title)
+}
+
+fn dedup_key_of(result: &ResearchResult) -> String {
+    normalize_key(result.url.as_deref(), &result.title)
+}
+
+/// Fraction of query terms that appear in the hit's title or snippet
+fn term_overlap(query: &str, hit: &SearchHit) -> f64 {
+    let text = format!("{} {}", hit.title, hit.snippet.as_deref().unwrap_or_default()).to_lowercase();
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let found = terms.iter().filter(|term| text.contains(term.as_str())).count();
+    found as f64 / terms.len() as f64
+}
+
+#[async_trait]
+impl AsyncNode for MultiSourceResearchNode {
+    fn node_name(&self) -> String {
+        "MultiSourceResearchNode".to_string()
+    }
+
+    async fn process_async(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let query: String = self.input.extract_as(&task_context)?;
+        let (results, failed_sources) = self.research(&query).await;
+
+        task_context.update_node(
+            &self.output_key,
+            json!({
+                "query": query,
+                "results": results,
+                "failed_sources": failed_sources,
+            }),
+        );
+        Ok(task_context)
+    }
+}
+
+impl Node for MultiSourceResearchNode {
+    fn node_name(&self) -> String {
+        "MultiSourceResearchNode".to_string()
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| WorkflowError::RuntimeError {
+            message: format!("Failed to create runtime: {}", e),
+        })?;
+        runtime.block_on(self.process_async(task_context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Debug)]
+    struct StaticSource {
+        name: &'static str,
+        hits: Vec<SearchHit>,
+    }
+
+    #[async_trait]
+    impl ResearchSource for StaticSource {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn search(&self, _query: &str) -> Result<Vec<SearchHit>> {
+            Ok(self.hits.clone())
+        }
+    }
+
+    fn hit(title: &str, url: Option<&str>, score: Option<f64>) -> SearchHit {
+        SearchHit {
+            title: title.to_string(),
+            url: url.map(str::to_string),
+            snippet: None,
+            score,
+        }
+    }
+
+    async fn mock_source(server: &MockServer, route: &str, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .and(query_param("q", "rust async"))
+            .respond_with(response)
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_aggregates_attributes_and_deduplicates() {
+        let server = MockServer::start().await;
+        mock_source(
+            &server,
+            "/docs",
+            ResponseTemplate::new(200).set_body_json(json!({
+                "results": [
+                    { "title": "Async Rust book", "url": "https://rust-lang.github.io/async-book/", "score": 0.9 },
+                    { "title": "Tokio tutorial", "url": "https://tokio.rs/tokio/tutorial", "score": 0.7 }
+                ]
+            })),
+        )
+        .await;
+        mock_source(
+            &server,
+            "/forum",
+            ResponseTemplate::new(200).set_body_json(json!([
+                { "title": "Async book", "url": "https://rust-lang.github.io/async-book", "score": 0.6 },
+                { "title": "Pinning explained", "url": "https://example.com/pin", "score": 0.8 }
+            ])),
+        )
+        .await;
+        mock_source(&server, "/broken", ResponseTemplate::new(500)).await;
+
+        let node = MultiSourceResearchNode::new()
+            .with_source(HttpResearchSource::new("docs", format!("{}/docs", server.uri())))
+            .with_source(HttpResearchSource::new("forum", format!("{}/forum", server.uri())))
+            .with_source(HttpResearchSource::new("broken", format!("{}/broken", server.uri())));
+        let context = TaskContext::new("research_test".to_string(), json!({ "query": "rust async" }));
+
+        let context = node.process_async(context).await.unwrap();
+        let output: Value = context.get_node_data("research").unwrap().unwrap();
+        let results: Vec<ResearchResult> = serde_json::from_value(output["results"].clone()).unwrap();
+
+        assert_eq!(output["failed_sources"], json!(["broken"]));
+        let titles: Vec<_> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, ["Async Rust book", "Pinning explained", "Tokio tutorial"]);
+        assert_eq!(results[0].score, 0.9);
+        assert_eq!(results[0].sources, ["docs", "forum"]);
+        assert_eq!(results[1].sources, ["forum"]);
+        assert_eq!(results[2].sources, ["docs"]);
+    }
+
+    #[tokio::test]
+    async fn test_unscored_hits_ranked_by_term_overlap() {
+        let node = MultiSourceResearchNode::new()
+            .with_source(StaticSource {
+                name: "notes",
+                hits: vec![
+                    hit("Unrelated page", None, None),
+                    hit("Rust async patterns", None, None),
+                    hit("Rust ownership", None, None),
+                ],
+            })
+            .with_max_results(2);
+
+        let (results, failed) = node.research("rust async").await;
+        assert!(failed.is_empty());
+        let titles: Vec<_> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, ["Rust async patterns", "Rust ownership"]);
+        assert_eq!(results[1].score, 0.5);
+    }
+
+    #[test]
+    fn test_missing_query_is_rejected() {
+        let node = MultiSourceResearchNode::new().with_source(StaticSource { name: "notes", hits: vec![] });
+        let context = TaskContext::new("research_test".to_string(), json!({ "topic": "rust" }));
+
+        assert!(matches!(
+            node.process(context),
+            Err(WorkflowError::ValidationError { .. })
+        ));
+    }
+}
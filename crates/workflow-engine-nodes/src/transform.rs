@@ -0,0 +1,175 @@
+//! Data transformation nodes
+//!
+//! This module provides nodes that reshape context data declaratively.
+
+use serde_json_path::JsonPath;
+use workflow_engine_core::prelude::*;
+
+use crate::utils::InputExtractor;
+
+/// Builds a new object from JSONPath expressions evaluated against the node input.
+///
+/// Each mapping pairs an output key with an expression. A path matching a
+/// single value yields that value, one matching several yields an array, and
+/// one matching nothing yields `null`. Expressions are parsed when the node is
+/// created, so a malformed expression is reported before any workflow runs.
+///
+/// ```rust
+/// use workflow_engine_nodes::transform::TransformNode;
+///
+/// let node = TransformNode::new([
+///     ("customer", "$.user.name"),
+///     ("first_item", "$.items[0].id"),
+/// ])
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransformNode {
+    mappings: Vec<(String, JsonPath)>,
+    input: InputExtractor,
+    output_key: String,
+}
+
+impl TransformNode {
+    /// Create a node from `(output key, JSONPath expression)` pairs.
+    ///
+    /// By default expressions are evaluated against the event data and the
+    /// result is stored as the `transform` node result.
+    pub fn new<K, E>(mappings: impl IntoIterator<Item = (K, E)>) -> Result<Self>
+    where
+        K: Into<String>,
+        E: AsRef<str>,
+    {
+        let mappings = mappings
+            .into_iter()
+            .map(|(key, expression)| {
+                let key = key.into();
+                let expression = expression.as_ref();
+                JsonPath::parse(expression)
+                    .map(|path| (key.clone(), path))
+                    .map_err(|e| {
+                        WorkflowError::validation_error(
+                            format!("Invalid JSONPath expression '{}': {}", expression, e),
+                            key,
+                            "valid JSONPath expression",
+                            "in TransformNode::new",
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            mappings,
+            input: InputExtractor::new().event_data(),
+            output_key: "transform".to_string(),
+        })
+    }
+
+    /// Evaluate expressions against the value found by `input` instead of the event data
+    pub fn with_input(mut self, input: InputExtractor) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Store the transformed object under `key` instead of `transform`
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Apply the mappings to `input`
+    pub fn transform(&self, input: &Value) -> Value {
+        let output = self
+            .mappings
+            .iter()
+            .map(|(key, path)| {
+                let nodes = path.query(input).all();
+                let value = match nodes.as_slice() {
+                    [] => Value::Null,
+                    [single] => (*single).clone(),
+                    many => Value::Array(many.iter().map(|v| (*v).clone()).collect()),
+                };
+                (key.clone(), value)
+            })
+            .collect::<serde_json::Map<_, _>>();
+        Value::Object(output)
+    }
+}
+
+impl Node for TransformNode {
+    fn node_name(&self) -> String {
+        "TransformNode".to_string()
+    }
+
+    fn process(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let input = self.input.extract(&task_context)?;
+        let output = self.transform(&input);
+        task_context.update_node(&self.output_key, output);
+        Ok(task_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TaskContext {
+        TaskContext::new(
+            "transform_test".to_string(),
+            json!({
+                "user": { "name": "Ada", "email": "ada@example.com" },
+                "items": [{ "id": "item-1" }, { "id": "item-2" }]
+            }),
+        )
+    }
+
+    #[test]
+    fn test_maps_paths_into_new_keys() {
+        let node = TransformNode::new([
+            ("customer", "$.user.name"),
+            ("first_item", "$.items[0].id"),
+        ])
+        .unwrap();
+
+        let context = node.process(context()).unwrap();
+        let output: Value = context.get_node_data("transform").unwrap().unwrap();
+        assert_eq!(output, json!({ "customer": "Ada", "first_item": "item-1" }));
+    }
+
+    #[test]
+    fn test_multiple_and_missing_matches() {
+        let node = TransformNode::new([("ids", "$.items[*].id"), ("phone", "$.user.phone")]).unwrap();
+
+        let output = node.transform(&context().event_data);
+        assert_eq!(output["ids"], json!(["item-1", "item-2"]));
+        assert_eq!(output["phone"], Value::Null);
+    }
+
+    #[test]
+    fn test_custom_input_and_output_key() {
+        let mut context = context();
+        context.update_node("lookup", json!({ "account": { "plan": "pro" } }));
+
+        let node = TransformNode::new([("plan", "$.account.plan")])
+            .unwrap()
+            .with_input(InputExtractor::new().node_result("lookup"))
+            .with_output_key("account_summary");
+
+        let context = node.process(context).unwrap();
+        let output: Value = context.get_node_data("account_summary").unwrap().unwrap();
+        assert_eq!(output, json!({ "plan": "pro" }));
+    }
+
+    #[test]
+    fn test_malformed_expression_fails_at_construction() {
+        let result = TransformNode::new([("customer", "$.user.name"), ("broken", "$.items[0")]);
+
+        match result {
+            Err(WorkflowError::ValidationError { field, message, .. }) => {
+                assert_eq!(field, "broken");
+                assert!(message.contains("$.items[0"));
+            }
+            other => panic!("Expected ValidationError, got {:?}", other),
+        }
+    }
+}
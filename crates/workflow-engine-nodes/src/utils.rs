@@ -59,4 +59,225 @@ impl NodeTestFixture {
             }
         }
     }
-}
\ No newline at end of file
+}
+/// A place [`InputExtractor`] looks for a node's input
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// The whole event data
+    EventData,
+    /// A top-level field of the event data
+    EventField(String),
+    /// The result stored by a previous node
+    NodeResult(String),
+    /// A JSON pointer (RFC 6901) into the whole context, e.g. `/nodes/research/summary`
+    JsonPointer(String),
+}
+
+impl std::fmt::Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::EventData => write!(f, "event data"),
+            InputSource::EventField(key) => write!(f, "event data field '{}'", key),
+            InputSource::NodeResult(node) => write!(f, "result of node '{}'", node),
+            InputSource::JsonPointer(pointer) => write!(f, "JSON pointer '{}'", pointer),
+        }
+    }
+}
+
+/// Extracts a node's input from the first of an ordered list of sources.
+///
+/// ```rust
+/// use workflow_engine_nodes::utils::InputExtractor;
+///
+/// let extractor = InputExtractor::new()
+///     .node_result("summarizer")
+///     .event_field("text");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputExtractor {
+    sources: Vec<InputSource>,
+}
+
+impl InputExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source(mut self, source: InputSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    pub fn event_data(self) -> Self {
+        self.with_source(InputSource::EventData)
+    }
+
+    pub fn event_field(self, key: impl Into<String>) -> Self {
+        self.with_source(InputSource::EventField(key.into()))
+    }
+
+    pub fn node_result(self, node_name: impl Into<String>) -> Self {
+        self.with_source(InputSource::NodeResult(node_name.into()))
+    }
+
+    pub fn json_pointer(self, pointer: impl Into<String>) -> Self {
+        self.with_source(InputSource::JsonPointer(pointer.into()))
+    }
+
+    pub fn sources(&self) -> &[InputSource] {
+        &self.sources
+    }
+
+    /// Return the value at the first source that holds a non-null value
+    pub fn extract(&self, context: &TaskContext) -> Result<Value> {
+        // Only serialize the whole context if a pointer source is reached
+        let mut context_value = None;
+
+        for source in &self.sources {
+            let found = match source {
+                InputSource::EventData => Some(context.event_data.clone()),
+                InputSource::EventField(key) => context.event_data.get(key).cloned(),
+                InputSource::NodeResult(node_name) => context.get_all_data().get(node_name).cloned(),
+                InputSource::JsonPointer(pointer) => {
+                    if context_value.is_none() {
+                        context_value = Some(serde_json::to_value(context).map_err(|e| {
+                            WorkflowError::SerializationError {
+                                message: format!("Failed to serialize task context: {}", e),
+                                type_name: "TaskContext".to_string(),
+                                context: "while extracting node input".to_string(),
+                                source: Some(e),
+                            }
+                        })?);
+                    }
+                    context_value
+                        .as_ref()
+                        .and_then(|value| value.pointer(pointer))
+                        .cloned()
+                }
+            };
+
+            if let Some(value) = found.filter(|value| !value.is_null()) {
+                return Ok(value);
+            }
+        }
+
+        let tried = self
+            .sources
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(WorkflowError::validation_error(
+            format!("No input found; tried: [{}]", tried),
+            "input",
+            "present at one of the configured sources",
+            "in InputExtractor::extract",
+        ))
+    }
+
+    /// Extract the input and deserialize it into `T`
+    pub fn extract_as<T: serde::de::DeserializeOwned>(&self, context: &TaskContext) -> Result<T> {
+        let value = self.extract(context)?;
+        serde_json::from_value(value).map_err(|e| WorkflowError::DeserializationError {
+            message: format!("Failed to deserialize node input: {}", e),
+            expected_type: std::any::type_name::<T>().to_string(),
+            context: "in InputExtractor::extract_as".to_string(),
+            raw_data: None,
+            source: Some(e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TaskContext {
+        let mut context = TaskContext::new(
+            "extract_test".to_string(),
+            json!({ "query": "billing", "ticket": { "id": 42, "priority": null } }),
+        );
+        context.update_node("classifier", json!({ "category": "billing" }));
+        context
+    }
+
+    #[test]
+    fn test_extracts_event_data() {
+        let value = InputExtractor::new().event_data().extract(&context()).unwrap();
+        assert_eq!(value["query"], "billing");
+    }
+
+    #[test]
+    fn test_extracts_event_field() {
+        let value = InputExtractor::new().event_field("query").extract(&context()).unwrap();
+        assert_eq!(value, json!("billing"));
+    }
+
+    #[test]
+    fn test_extracts_node_result() {
+        let value = InputExtractor::new()
+            .node_result("classifier")
+            .extract(&context())
+            .unwrap();
+        assert_eq!(value, json!({ "category": "billing" }));
+    }
+
+    #[test]
+    fn test_extracts_json_pointer() {
+        let value = InputExtractor::new()
+            .json_pointer("/event_data/ticket/id")
+            .extract(&context())
+            .unwrap();
+        assert_eq!(value, json!(42));
+
+        let value = InputExtractor::new()
+            .json_pointer("/nodes/classifier/category")
+            .extract(&context())
+            .unwrap();
+        assert_eq!(value, json!("billing"));
+    }
+
+    #[test]
+    fn test_falls_through_missing_and_null_sources() {
+        let value = InputExtractor::new()
+            .node_result("summarizer")
+            .json_pointer("/event_data/ticket/priority")
+            .event_field("query")
+            .extract(&context())
+            .unwrap();
+        assert_eq!(value, json!("billing"));
+    }
+
+    #[test]
+    fn test_extract_as_deserializes() {
+        #[derive(serde::Deserialize)]
+        struct Classification {
+            category: String,
+        }
+
+        let classification: Classification = InputExtractor::new()
+            .node_result("classifier")
+            .extract_as(&context())
+            .unwrap();
+        assert_eq!(classification.category, "billing");
+    }
+
+    #[test]
+    fn test_all_sources_missing() {
+        let result = InputExtractor::new()
+            .node_result("summarizer")
+            .event_field("text")
+            .json_pointer("/nodes/summarizer/text")
+            .extract(&context());
+
+        match result {
+            Err(WorkflowError::ValidationError { message, field, .. }) => {
+                assert_eq!(field, "input");
+                assert!(message.contains("result of node 'summarizer'"));
+                assert!(message.contains("event data field 'text'"));
+                assert!(message.contains("JSON pointer '/nodes/summarizer/text'"));
+            }
+            other => panic!("Expected ValidationError, got {:?}", other),
+        }
+    }
+}
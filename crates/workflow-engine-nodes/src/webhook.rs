@@ -0,0 +1,279 @@
+//! Webhook nodes
+//!
+//! This module provides nodes that send workflow results to external systems
+//! over HTTP.
+
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use sha2::Sha256;
+use std::collections::HashMap;
+use workflow_engine_core::ai::templates::{EngineConfig, Template, TemplateEngine, TemplateVariables};
+use workflow_engine_core::error::{retry_with_policy, RetryPolicy};
+use workflow_engine_core::prelude::*;
+
+/// Header carrying the payload signature when a secret is configured
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Sends an HTTP request, by default a JSON `POST` of all node results.
+///
+/// The body can instead be rendered from a Handlebars template, which sees
+/// the context as `event`, `nodes` and `metadata`; use the `json` helper to
+/// embed values as JSON. With a shared secret, the body is signed with
+/// HMAC-SHA256 and the signature sent as `X-Signature: sha256=<hex>`.
+///
+/// Failed requests and non-2xx responses are retried according to the
+/// retry policy (three retries with exponential backoff by default), after
+/// which the node fails with a [`WorkflowError::ApiError`]. The response
+/// status and body are stored as the `webhook` node result.
+///
+/// ```rust
+/// use workflow_engine_nodes::webhook::WebhookNode;
+///
+/// let node = WebhookNode::post("https://example.com/hooks/orders")
+///     .with_header("Authorization", "Bearer token")
+///     .with_body_template(r#"{"order": {{json event.order_id}}, "status": "done"}"#)
+///     .with_secret("shared-secret");
+/// ```
+#[derive(Debug)]
+pub struct WebhookNode {
+    method: Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    body_template: Option<Template>,
+    secret: Option<String>,
+    retry_policy: RetryPolicy,
+    output_key: String,
+    client: reqwest::Client,
+    templates: TemplateEngine,
+}
+
+impl WebhookNode {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body_template: None,
+            secret: None,
+            retry_policy: RetryPolicy::default(),
+            output_key: "webhook".to_string(),
+            client: reqwest::Client::new(),
+            // Bodies are usually JSON, which HTML escaping would corrupt
+            templates: TemplateEngine::with_config(EngineConfig {
+                escape_html: false,
+                ..EngineConfig::default()
+            }),
+        }
+    }
+
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Render the request body from a Handlebars template instead of sending
+    /// all node results
+    pub fn with_body_template(mut self, template: impl Into<String>) -> Self {
+        self.body_template = Some(
+            Template::new("webhook_body", template).expect("creating a template cannot fail"),
+        );
+        self
+    }
+
+    /// Sign each request body with `secret`
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn with_output_key(mut self, key: impl Into<String>) -> Self {
+        self.output_key = key.into();
+        self
+    }
+
+    /// Build the request body for `context`
+    pub fn render_body(&self, context: &TaskContext) -> Result<String> {
+        let Some(template) = &self.body_template else {
+            return serde_json::to_string(context.get_all_data()).map_err(|e| {
+                WorkflowError::SerializationError {
+                    message: format!("Failed to serialize webhook body: {}", e),
+                    type_name: "HashMap<String, Value>".to_string(),
+                    context: "in WebhookNode::render_body".to_string(),
+                    source: Some(e),
+                }
+            });
+        };
+
+        let variables = HashMap::from([
+            ("event".to_string(), context.event_data.clone()),
+            ("nodes".to_string(), json!(context.get_all_data())),
+            ("metadata".to_string(), json!(context.get_all_metadata())),
+        ]);
+        Ok(self
+            .templates
+            .render(template, &TemplateVariables::from_map(variables))?)
+    }
+
+    /// `sha256=<hex>` HMAC-SHA256 signature of `body` under `secret`
+    pub fn sign(secret: &str, body: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+        mac.update(body.as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    async fn send(&self, body: &str) -> Result<Value> {
+        let mut request = self
+            .client
+            .request(self.method.clone(), &self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, Self::sign(secret, body));
+        }
+
+        let response = request.send().await.map_err(|e| {
+            WorkflowError::api_error(
+                format!("Webhook request failed: {}", e),
+                "webhook",
+                &self.url,
+                None,
+            )
+        })?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(WorkflowError::api_error(
+                format!("Webhook returned {}: {}", status, text),
+                "webhook",
+                &self.url,
+                Some(status.as_u16()),
+            ));
+        }
+
+        let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        Ok(json!({ "status": status.as_u16(), "body": body }))
+    }
+}
+
+#[async_trait]
+impl AsyncNode for WebhookNode {
+    fn node_name(&self) -> String {
+        "WebhookNode".to_string()
+    }
+
+    async fn process_async(&self, mut task_context: TaskContext) -> Result<TaskContext> {
+        let body = self.render_body(&task_context)?;
+        let response = retry_with_policy(&self.retry_policy, || self.send(&body)).await?;
+        task_context.update_node(&self.output_key, response);
+        Ok(task_context)
+    }
+}
+
+impl Node for WebhookNode {
+    fn node_name(&self) -> String {
+        "WebhookNode".to_string()
+    }
+
+    fn process(&self, task_context: TaskContext) -> Result<TaskContext> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| WorkflowError::RuntimeError {
+            message: format!("Failed to create runtime: {}", e),
+        })?;
+        runtime.block_on(self.process_async(task_context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use wiremock::matchers::{body_string, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn context() -> TaskContext {
+        let mut context = TaskContext::new("webhook_test".to_string(), json!({ "order_id": 42 }));
+        context.update_node("summary", json!({ "total": 9.5 }));
+        context
+    }
+
+    fn fast_retries(retries: u32) -> RetryPolicy {
+        RetryPolicy::fixed(retries, Duration::from_millis(10))
+    }
+
+    #[tokio::test]
+    async fn test_delivers_signed_templated_body() {
+        let server = MockServer::start().await;
+        let expected_body = r#"{"order":42,"total":9.5}"#;
+        Mock::given(method("POST"))
+            .and(path("/hooks"))
+            .and(header("x-api-key", "key-1"))
+            .and(header(SIGNATURE_HEADER, WebhookNode::sign("s3cret", expected_body).as_str()))
+            .and(body_string(expected_body))
+            .respond_with(ResponseTemplate::new(202).set_body_json(json!({ "accepted": true })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let node = WebhookNode::post(format!("{}/hooks", server.uri()))
+            .with_header("x-api-key", "key-1")
+            .with_body_template(r#"{"order":{{event.order_id}},"total":{{nodes.summary.total}}}"#)
+            .with_secret("s3cret");
+
+        let context = node.process_async(context()).await.unwrap();
+        let response: Value = context.get_node_data("webhook").unwrap().unwrap();
+        assert_eq!(response, json!({ "status": 202, "body": { "accepted": true } }));
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let node = WebhookNode::post(server.uri()).with_retry_policy(fast_retries(3));
+        let context = node.process_async(context()).await.unwrap();
+        let response: Value = context.get_node_data("webhook").unwrap().unwrap();
+        assert_eq!(response["status"], 200);
+    }
+
+    #[tokio::test]
+    async fn test_fails_once_retries_are_spent() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string(r#"{"summary":{"total":9.5}}"#))
+            .respond_with(ResponseTemplate::new(500).set_body_string("down"))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let node = WebhookNode::post(server.uri()).with_retry_policy(fast_retries(2));
+        match node.process_async(context()).await {
+            Err(WorkflowError::ApiError { message, status_code, .. }) => {
+                assert_eq!(status_code, Some(500));
+                assert!(message.contains("down"));
+            }
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+}
@@ -457,6 +457,7 @@ async fn main() -> Result<(), WorkflowError> {
         model_provider: ModelProvider::Anthropic,
         model_name: "claude-3-opus-20240229".to_string(),
         mcp_server_uri: None,
+        ai_fallback_template: None,
     };
     
     let sonnet_config = AgentConfig {
@@ -464,6 +465,7 @@ async fn main() -> Result<(), WorkflowError> {
         model_provider: ModelProvider::Anthropic,
         model_name: "claude-3-sonnet-20240229".to_string(),
         mcp_server_uri: None,
+        ai_fallback_template: None,
     };
     
     // Create workflow nodes
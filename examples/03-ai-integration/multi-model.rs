@@ -95,6 +95,7 @@ impl ParallelModelExecutorNode {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-4".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
         
         let anthropic_config = AgentConfig {
@@ -102,6 +103,7 @@ impl ParallelModelExecutorNode {
             model_provider: ModelProvider::Anthropic,
             model_name: "claude-3-opus-20240229".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
         
         Ok(Self {
@@ -363,6 +363,7 @@ async fn main() -> Result<(), WorkflowError> {
         model_provider: ModelProvider::OpenAI,
         model_name: "gpt-4".to_string(),
         mcp_server_uri: None,
+        ai_fallback_template: None,
     };
     
     let gpt35_config = AgentConfig {
@@ -370,6 +371,7 @@ async fn main() -> Result<(), WorkflowError> {
         model_provider: ModelProvider::OpenAI,
         model_name: "gpt-3.5-turbo".to_string(),
         mcp_server_uri: None,
+        ai_fallback_template: None,
     };
     
     // Create the workflow nodes
@@ -101,7 +101,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config: None,
     };
     
-    match service_data.trigger_workflow(trigger_request).await {
+    match service_data.trigger_workflow(trigger_request, None).await {
         Ok(response) => {
             println!("✅ Workflow triggered successfully!");
             println!("   Instance ID: {}", response.instance_id);
@@ -135,7 +135,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }),
     };
     
-    match service_data.trigger_workflow(advanced_trigger_request).await {
+    match service_data.trigger_workflow(advanced_trigger_request, None).await {
         Ok(response) => {
             println!("✅ Advanced workflow triggered successfully!");
             println!("   Instance ID: {}", response.instance_id);
@@ -160,7 +160,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config: None,
     };
     
-    match service_data.trigger_workflow(slack_trigger_request).await {
+    match service_data.trigger_workflow(slack_trigger_request, None).await {
         Ok(response) => {
             println!("✅ Slack workflow triggered successfully!");
             println!("   Instance ID: {}", response.instance_id);
@@ -175,7 +175,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // List all running instances
     println!("\n📊 All Workflow Instances Summary");
     println!("=================================");
-    let instances = service_data.list_instances().await;
+    let instances = service_data.list_instances(None).await;
     for (instance_id, status, workflow_name) in instances {
         println!("   {} | {:?} | {}", instance_id, status, workflow_name);
     }
@@ -205,7 +205,7 @@ async fn demo_workflow_monitoring(
     while attempts < max_attempts {
         sleep(Duration::from_millis(1000)).await;
         
-        match service.get_workflow_status(instance_id).await {
+        match service.get_workflow_status(instance_id, None).await {
             Ok(status) => {
                 println!("   📊 Progress: {}% ({}/{} steps completed)", 
                     status.progress.percentage,
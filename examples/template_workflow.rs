@@ -36,6 +36,7 @@ async fn main() -> Result<(), WorkflowError> {
         model_provider: ModelProvider::Anthropic,
         model_name: "claude-3-sonnet-20240229".to_string(),
         mcp_server_uri: Some("ws://localhost:8001/mcp".to_string()),
+        ai_fallback_template: None,
     };
     
     // Create template-enhanced agent configuration
@@ -0,0 +1,26 @@
+-- Saga execution persistence
+-- Stores each saga with its step definitions and step statuses so that
+-- interrupted sagas can be recovered and resumed after a restart
+
+CREATE TABLE IF NOT EXISTS saga_executions (
+    saga_id UUID PRIMARY KEY,
+    saga_type VARCHAR(255) NOT NULL,
+
+    -- running, completed, compensating, failed or aborted
+    state VARCHAR(50) NOT NULL,
+
+    -- Step definitions together with their execution status, in definition order
+    steps JSONB NOT NULL,
+
+    global_context JSONB NOT NULL DEFAULT '{}'::jsonb,
+    compensation_strategy JSONB NOT NULL,
+    error_message TEXT,
+    total_timeout_seconds BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+-- Recovery looks up unfinished sagas by state
+CREATE INDEX IF NOT EXISTS idx_saga_executions_state ON saga_executions(state);
+CREATE INDEX IF NOT EXISTS idx_saga_executions_completed_at ON saga_executions(completed_at) WHERE completed_at IS NOT NULL;
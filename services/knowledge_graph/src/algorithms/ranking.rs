@@ -337,6 +337,47 @@ impl ConceptRanking {
         })
     }
 
+    /// Recommend concepts related to `concept` by the neighbors they share
+    /// with it ("learners of X also learned Y"), best first.
+    ///
+    /// Edges count in both directions. Each shared neighbor adds
+    /// `1 / ln(degree)` to a candidate's score (Adamic-Adar), so a neighbor
+    /// connected to everything says less than one connected to a few
+    /// concepts. Ties are broken by id.
+    pub fn recommend_related(&self, concept: Uuid, limit: usize) -> Result<Vec<(Uuid, f32)>> {
+        if !self.concepts.contains_key(&concept) {
+            return Err(anyhow::anyhow!("Concept not found: {:?}", concept));
+        }
+
+        let mut neighbors: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+        for edge in self.edges.iter().filter(|edge| edge.from != edge.to) {
+            neighbors.entry(edge.from).or_default().insert(edge.to);
+            neighbors.entry(edge.to).or_default().insert(edge.from);
+        }
+
+        let mut scores: HashMap<Uuid, f32> = HashMap::new();
+        for shared in neighbors.get(&concept).into_iter().flatten() {
+            let shared_neighbors = &neighbors[shared];
+            let weight = 1.0 / (shared_neighbors.len() as f32).ln();
+            for &candidate in shared_neighbors {
+                if candidate != concept && self.concepts.contains_key(&candidate) {
+                    *scores.entry(candidate).or_default() += weight;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(Uuid, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|(a_id, a_score), (b_id, b_score)| {
+            b_score.partial_cmp(a_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a_id.cmp(b_id))
+        });
+        ranked.truncate(limit);
+
+        debug!("Found {} related concepts for {:?}", ranked.len(), concept);
+        Ok(ranked)
+    }
+
     /// Rank concepts by learning difficulty progression
     pub async fn difficulty_ranking(&self) -> Result<RankingResult> {
         info!("Ranking concepts by difficulty progression");
@@ -730,4 +771,36 @@ mod tests {
         assert!(result.communities.len() >= 2);
         assert!(result.modularity > 0.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_recommend_related_by_shared_neighbors() {
+        let mut ranking = ConceptRanking::new();
+        let concepts: Vec<Concept> = ["Rust", "C++", "Go", "Python", "Ownership", "Pointers", "Syntax"].iter()
+            .map(|name| create_test_concept(name, "intermediate", 0.8))
+            .collect();
+        let [rust, cpp, go, python, ownership, pointers, syntax] = [0, 1, 2, 3, 4, 5, 6].map(|i| concepts[i].id);
+        let edge = |from, to| RankingEdge { from, to, weight: 1.0 };
+
+        ranking.add_concepts(concepts);
+        ranking.add_edges(vec![
+            // C++ shares both of Rust's specific neighbors
+            edge(rust, ownership), edge(cpp, ownership),
+            edge(rust, pointers), edge(pointers, cpp),
+            // Go shares only one of them
+            edge(go, pointers),
+            // Every language links to Syntax, which says little
+            edge(rust, syntax), edge(cpp, syntax), edge(go, syntax), edge(python, syntax),
+        ]);
+
+        let recommendations = ranking.recommend_related(rust, 10).unwrap();
+        assert_eq!(recommendations[0].0, cpp);
+        assert_eq!(recommendations[1].0, go);
+        assert!(recommendations[0].1 > recommendations[1].1);
+        assert!(recommendations[1].1 > recommendations[2].1);
+        assert_eq!(recommendations[2].0, python);
+        assert!(recommendations.iter().all(|(id, _)| *id != rust));
+
+        assert_eq!(ranking.recommend_related(rust, 1).unwrap().len(), 1);
+        assert!(ranking.recommend_related(Uuid::new_v4(), 10).is_err());
+    }
+}
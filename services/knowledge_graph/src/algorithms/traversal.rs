@@ -48,6 +48,26 @@ pub struct ConceptEdge {
     pub weight: f32,
 }
 
+/// A concept and its neighborhood, for visualization
+#[derive(Debug, Clone)]
+pub struct SubGraph {
+    pub center: Uuid,
+    pub radius: usize,
+    /// Concepts ordered by distance from the center, then by id
+    pub nodes: Vec<SubGraphNode>,
+    /// Edges between the included concepts
+    pub edges: Vec<ConceptEdge>,
+    /// Whether concepts within the radius were left out by the node cap
+    pub truncated: bool,
+}
+
+/// A concept in a [`SubGraph`]
+#[derive(Debug, Clone)]
+pub struct SubGraphNode {
+    pub concept: Concept,
+    pub distance: usize,
+}
+
 impl Default for TraversalConfig {
     fn default() -> Self {
         Self {
@@ -271,6 +291,73 @@ impl GraphTraversal {
         Ok(distance_groups)
     }
 
+    /// Extract every concept within `radius` hops of `center`, following
+    /// edges in either direction, and the edges between them.
+    ///
+    /// At most `max_nodes` concepts are kept: nearer concepts first, ties
+    /// broken by id, so the same graph always yields the same subgraph.
+    pub fn extract_subgraph(&self, center: Uuid, radius: usize, max_nodes: usize) -> Result<SubGraph> {
+        if !self.concepts.contains_key(&center) {
+            return Err(anyhow::anyhow!("Concept not found: {:?}", center));
+        }
+
+        let mut adjacency: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for edge in &self.edges {
+            adjacency.entry(edge.from).or_default().push(edge.to);
+            adjacency.entry(edge.to).or_default().push(edge.from);
+        }
+
+        let mut distances = HashMap::from([(center, 0)]);
+        let mut frontier = vec![center];
+        for distance in 1..=radius {
+            let mut next: Vec<Uuid> = frontier.iter()
+                .flat_map(|id| adjacency.get(id).into_iter().flatten())
+                .filter(|id| self.concepts.contains_key(id) && !distances.contains_key(id))
+                .copied()
+                .collect();
+            next.sort();
+            next.dedup();
+            if next.is_empty() {
+                break;
+            }
+            for id in &next {
+                distances.insert(*id, distance);
+            }
+            frontier = next;
+        }
+
+        let mut ranked: Vec<(usize, Uuid)> = distances.into_iter()
+            .map(|(id, distance)| (distance, id))
+            .collect();
+        ranked.sort();
+        let max_nodes = max_nodes.max(1);
+        let truncated = ranked.len() > max_nodes;
+        ranked.truncate(max_nodes);
+
+        let included: HashSet<Uuid> = ranked.iter().map(|(_, id)| *id).collect();
+        let nodes = ranked.into_iter()
+            .map(|(distance, id)| SubGraphNode {
+                concept: self.concepts[&id].clone(),
+                distance,
+            })
+            .collect();
+        let edges = self.edges.iter()
+            .filter(|edge| included.contains(&edge.from) && included.contains(&edge.to))
+            .cloned()
+            .collect();
+
+        debug!("Extracted subgraph of {} concepts around {:?} (truncated: {})",
+               included.len(), center, truncated);
+
+        Ok(SubGraph {
+            center,
+            radius,
+            nodes,
+            edges,
+            truncated,
+        })
+    }
+
     /// Find strongly connected components (for concept clusters)
     pub async fn find_connected_components(&self) -> Result<Vec<Vec<Uuid>>> {
         info!("Finding connected components in the graph");
@@ -658,4 +745,56 @@ mod tests {
         assert!(result.visited_concepts.contains(&id3));
         assert!(!result.visited_concepts.contains(&id2));
     }
-}
\ No newline at end of file
+
+    /// A - B - C - D, with E also linked to A
+    fn neighborhood_graph() -> (GraphTraversal, [Uuid; 5]) {
+        let concepts: Vec<Concept> = ["A", "B", "C", "D", "E"].iter()
+            .map(|name| create_test_concept(name, "math", vec![]))
+            .collect();
+        let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|i| concepts[i].id);
+        let edge = |from, to| ConceptEdge { from, to, relationship: RelationshipType::Related, weight: 1.0 };
+
+        let mut traversal = GraphTraversal::new();
+        traversal.add_concepts(concepts);
+        // B -> A points toward the center; extraction follows it anyway
+        traversal.add_edges(vec![edge(b, a), edge(b, c), edge(c, d), edge(a, e)]);
+        (traversal, [a, b, c, d, e])
+    }
+
+    fn node_ids(subgraph: &SubGraph) -> Vec<Uuid> {
+        subgraph.nodes.iter().map(|node| node.concept.id).collect()
+    }
+
+    #[test]
+    fn test_extract_subgraph_by_radius() {
+        let (traversal, [a, b, c, _d, e]) = neighborhood_graph();
+
+        let radius_1 = traversal.extract_subgraph(a, 1, 100).unwrap();
+        let mut expected = vec![b, e];
+        expected.sort();
+        assert_eq!(node_ids(&radius_1), [vec![a], expected.clone()].concat());
+        assert_eq!(radius_1.edges.len(), 2);
+        assert!(!radius_1.truncated);
+
+        let radius_2 = traversal.extract_subgraph(a, 2, 100).unwrap();
+        assert_eq!(node_ids(&radius_2), [vec![a], expected, vec![c]].concat());
+        assert_eq!(radius_2.nodes.iter().map(|n| n.distance).collect::<Vec<_>>(), [0, 1, 1, 2]);
+        assert_eq!(radius_2.edges.len(), 3);
+
+        assert!(traversal.extract_subgraph(Uuid::new_v4(), 1, 100).is_err());
+    }
+
+    #[test]
+    fn test_extract_subgraph_cap_is_deterministic() {
+        let (traversal, [a, b, _c, _d, e]) = neighborhood_graph();
+
+        let capped = traversal.extract_subgraph(a, 3, 2).unwrap();
+        assert!(capped.truncated);
+        assert_eq!(node_ids(&capped), [a, b.min(e)]);
+        assert_eq!(capped.edges.len(), 1);
+
+        for _ in 0..5 {
+            assert_eq!(node_ids(&traversal.extract_subgraph(a, 3, 2).unwrap()), node_ids(&capped));
+        }
+    }
+}
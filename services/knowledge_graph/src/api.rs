@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::client::DgraphClient;
-use crate::query::{QueryType, QueryParameters, QueryBuilder, QueryConstraints};
+use crate::query::{QueryType, QueryParameters, QueryBuilder, QueryConstraints, ConceptCursor};
 use crate::service::{KnowledgeGraphService, RelationshipDiscoveryRequest, PathFindingRequest};
 
 #[derive(Deserialize)]
@@ -512,6 +512,44 @@ pub async fn search_concepts(
     }
 }
 
+#[derive(Deserialize)]
+pub struct ConceptListParams {
+    pub after: Option<String>,
+    pub first: Option<u32>,
+}
+
+/// List concepts a page at a time, following `end_cursor` from page to page
+pub async fn list_concepts(
+    params: web::Query<ConceptListParams>,
+    service: web::Data<Arc<KnowledgeGraphService>>,
+) -> ActixResult<HttpResponse> {
+    let start_time = std::time::Instant::now();
+    
+    if let Some(Err(e)) = params.after.as_deref().map(ConceptCursor::decode) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            execution_time_ms: start_time.elapsed().as_millis(),
+        }));
+    }
+    
+    match service.list_concepts(params.after.as_deref(), params.first).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(page),
+            error: None,
+            execution_time_ms: start_time.elapsed().as_millis(),
+        })),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            error: Some(format!("Failed to list concepts: {}", e)),
+            execution_time_ms: start_time.elapsed().as_millis(),
+        })),
+    }
+}
+
 /// Configure API routes
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -522,6 +560,7 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .route("/path", web::post().to(find_path))
             .route("/similarity/{concept_id}", web::get().to(calculate_similarity))
             .route("/recommendations/{user_id}", web::get().to(get_recommendations))
+            .route("/concepts", web::get().to(list_concepts))
             .route("/concepts", web::post().to(create_concept))
             .route("/concepts/{concept_id}", web::put().to(update_concept))
             .route("/concepts/{concept_id}", web::delete().to(delete_concept))
@@ -11,15 +11,26 @@ pub use connection::{ConnectionConfig, ConnectionStats, DgraphConnection};
 pub use dgraph::{DgraphResponseParser, MutationResult, MutationOperationType, ConflictInfo};
 pub use pool::{ConnectionPool, PoolConfig, PoolStats, PooledConnection};
 
-use crate::error::{Result, ErrorContext, RetryExecutor, RetryPolicy, CircuitBreaker, ResultExt};
+use crate::error::{Result, ErrorContext, RetryExecutor, RetryPolicy, CircuitBreaker, CircuitBreakerStats, ResultExt};
+use crate::graph::DgraphConfig;
+use crate::query::graphql_literal;
+use crate::schema::GraphSchema;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::info;
 
 /// High-level DGraph client with connection pooling
+///
+/// Connecting and queries retry transient failures, and all of them share
+/// one circuit breaker: once DGraph keeps failing, calls fail fast with
+/// [`KnowledgeGraphError::CircuitBreakerOpen`](crate::error::KnowledgeGraphError::CircuitBreakerOpen)
+/// until it has had time to recover.
 pub struct DgraphClient {
     pool: Arc<ConnectionPool>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: CircuitBreaker,
+    schema: Arc<GraphSchema>,
 }
 
 impl DgraphClient {
@@ -31,21 +42,83 @@ impl DgraphClient {
 
     /// Create a new DGraph client with custom pool configuration
     pub async fn with_config(endpoint: String, pool_config: PoolConfig) -> Result<Self> {
+        let config = DgraphConfig::default();
+        Self::connect(endpoint, pool_config, config.retry_policy(), config.circuit_breaker()).await
+    }
+
+    /// Create a new DGraph client with the retry and circuit breaker
+    /// thresholds from `config`
+    pub async fn from_dgraph_config(config: &DgraphConfig) -> Result<Self> {
+        let endpoint = format!("{}:{}", config.host, config.grpc_port);
+        Self::connect(endpoint, PoolConfig::default(), config.retry_policy(), config.circuit_breaker()).await
+    }
+
+    async fn connect(
+        endpoint: String,
+        pool_config: PoolConfig,
+        retry_policy: RetryPolicy,
+        circuit_breaker: CircuitBreaker,
+    ) -> Result<Self> {
         info!("Initializing DGraph client with endpoint: {}", endpoint);
         
-        let pool = ConnectionPool::new(endpoint.clone(), pool_config)
-            .await
-            .with_context(|| ErrorContext::new("create_connection_pool")
-                .with_endpoint(&endpoint))?;
+        let pool = RetryExecutor::new(retry_policy.clone())
+            .with_circuit_breaker(circuit_breaker.clone())
+            .execute(|| async {
+                ConnectionPool::new(endpoint.clone(), pool_config.clone())
+                    .await
+                    .with_context(|| ErrorContext::new("create_connection_pool")
+                        .with_endpoint(&endpoint))
+            })
+            .await?;
         
         Ok(Self {
             pool: Arc::new(pool),
+            retry_policy,
+            circuit_breaker,
+            schema: Arc::new(GraphSchema::knowledge_graph()),
+        })
+    }
+
+    /// Validate payloads against `schema` instead of the knowledge graph's
+    /// own schema
+    pub fn with_schema(mut self, schema: GraphSchema) -> Self {
+        self.schema = Arc::new(schema);
+        self
+    }
+
+    /// Check a node payload against the client's schema
+    pub fn validate(&self, type_name: &str, payload: &Value) -> Result<()> {
+        self.schema.validate(type_name, payload)
+    }
+
+    /// Add nodes of type `type_name`, checking every payload against the
+    /// schema before anything is sent
+    pub async fn add(&self, type_name: &str, payloads: &[Value]) -> Result<Value> {
+        for payload in payloads {
+            self.validate(type_name, payload)?;
+        }
+
+        let inputs: Vec<String> = payloads.iter().map(graphql_literal).collect();
+        let mutation = format!(
+            "mutation {{ add{}(input: [{}]) {{ numUids }} }}",
+            type_name,
+            inputs.join(", ")
+        );
+        self.mutate(&mutation).await
+    }
+
+    /// Retry executor sharing this client's circuit breaker
+    fn retry_executor(&self, max_attempts: usize) -> RetryExecutor {
+        RetryExecutor::new(RetryPolicy {
+            max_attempts,
+            ..self.retry_policy.clone()
         })
+        .with_circuit_breaker(self.circuit_breaker.clone())
     }
 
     /// Execute a read-only query with retry logic
     pub async fn query(&self, query: &str) -> Result<Value> {
-        let retry_executor = RetryExecutor::new(RetryPolicy::default());
+        let retry_executor = self.retry_executor(self.retry_policy.max_attempts);
         
         retry_executor.execute(|| async {
             let conn = self.pool.acquire().await
@@ -64,7 +137,7 @@ impl DgraphClient {
         query: &str,
         vars: HashMap<String, String>,
     ) -> Result<Value> {
-        let retry_executor = RetryExecutor::new(RetryPolicy::default());
+        let retry_executor = self.retry_executor(self.retry_policy.max_attempts);
         
         retry_executor.execute(|| async {
             let conn = self.pool.acquire().await
@@ -79,10 +152,8 @@ impl DgraphClient {
 
     /// Execute a mutation with retry logic
     pub async fn mutate(&self, mutation: &str) -> Result<Value> {
-        let retry_executor = RetryExecutor::new(RetryPolicy {
-            max_attempts: 2, // Fewer retries for mutations
-            ..Default::default()
-        });
+        // Fewer retries for mutations
+        let retry_executor = self.retry_executor(self.retry_policy.max_attempts.min(2));
         
         retry_executor.execute(|| async {
             let conn = self.pool.acquire().await
@@ -107,6 +178,11 @@ impl DgraphClient {
         conn.connection().transaction(operations).await
     }
 
+    /// Get the state of the client's circuit breaker
+    pub async fn circuit_breaker_stats(&self) -> CircuitBreakerStats {
+        self.circuit_breaker.stats().await
+    }
+
     /// Get pool statistics
     pub async fn pool_stats(&self) -> PoolStats {
         self.pool.stats().await
@@ -128,6 +204,9 @@ impl Clone for DgraphClient {
     fn clone(&self) -> Self {
         Self {
             pool: Arc::clone(&self.pool),
+            retry_policy: self.retry_policy.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
+            schema: Arc::clone(&self.schema),
         }
     }
 }
@@ -83,6 +83,13 @@ pub enum KnowledgeGraphError {
         constraints: Vec<String>,
     },
 
+    /// A payload that doesn't match the declared graph schema
+    #[error("Schema violation on {predicate}: {reason}")]
+    SchemaViolation {
+        predicate: String,
+        reason: String,
+    },
+
     /// Partial result error (for graceful degradation)
     #[error("Partial results available: {message}")]
     PartialResultError {
@@ -183,6 +190,9 @@ impl ErrorContext {
 }
 
 /// Circuit breaker for handling repeated failures
+///
+/// Clones share the same state, so one breaker can guard several executors.
+#[derive(Clone)]
 pub struct CircuitBreaker {
     name: String,
     failure_threshold: usize,
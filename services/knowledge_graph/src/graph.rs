@@ -6,9 +6,12 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use std::time::Duration;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::error::{CircuitBreaker, KnowledgeGraphError, RetryExecutor, RetryPolicy};
+
 /// Configuration for DGraph connection
 #[derive(Debug, Clone)]
 pub struct DgraphConfig {
@@ -18,6 +21,16 @@ pub struct DgraphConfig {
     pub max_connections: usize,
     pub query_timeout_ms: u64,
     pub mutation_timeout_ms: u64,
+    /// Attempts per connection or query before giving up on a transient error
+    pub retry_max_attempts: usize,
+    pub retry_initial_delay_ms: u64,
+    pub retry_max_delay_ms: u64,
+    /// Consecutive failures that open the circuit
+    pub circuit_failure_threshold: usize,
+    /// Successes while half-open that close the circuit again
+    pub circuit_success_threshold: usize,
+    /// How long the circuit stays open before letting a request through
+    pub circuit_open_timeout_ms: u64,
 }
 
 impl Default for DgraphConfig {
@@ -29,8 +42,36 @@ impl Default for DgraphConfig {
             max_connections: 20,
             query_timeout_ms: 30_000,
             mutation_timeout_ms: 60_000,
+            retry_max_attempts: 3,
+            retry_initial_delay_ms: 100,
+            retry_max_delay_ms: 10_000,
+            circuit_failure_threshold: 5,
+            circuit_success_threshold: 2,
+            circuit_open_timeout_ms: 30_000,
+        }
+    }
+}
+
+impl DgraphConfig {
+    /// Retry policy for connecting to and querying DGraph
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.retry_max_attempts.max(1),
+            initial_delay: Duration::from_millis(self.retry_initial_delay_ms),
+            max_delay: Duration::from_millis(self.retry_max_delay_ms),
+            ..Default::default()
         }
     }
+
+    /// A new circuit breaker with this configuration's thresholds
+    pub fn circuit_breaker(&self) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "dgraph",
+            self.circuit_failure_threshold,
+            self.circuit_success_threshold,
+            Duration::from_millis(self.circuit_open_timeout_ms),
+        )
+    }
 }
 
 /// DGraph client wrapper with connection pooling
@@ -55,11 +96,11 @@ impl GraphDatabase {
         
         info!("Connecting to DGraph at {}", endpoint);
         
-        let client = Client::new(&endpoint)
-            .context("Failed to create DGraph client")?;
-        
-        // Test the connection
-        Self::test_connection(&client).await?;
+        // A DGraph that is still starting up shouldn't fail startup outright
+        let client = RetryExecutor::new(config.retry_policy())
+            .execute(|| Self::connect(&endpoint))
+            .await
+            .context("Failed to connect to DGraph")?;
         
         info!("Successfully connected to DGraph");
         
@@ -70,6 +111,23 @@ impl GraphDatabase {
         })
     }
     
+    /// Create a client and check that DGraph answers
+    async fn connect(endpoint: &str) -> crate::error::Result<Client> {
+        let network_error = |message: &str, error: String| KnowledgeGraphError::NetworkError {
+            message: message.to_string(),
+            endpoint: endpoint.to_string(),
+            retry_count: 0,
+            source_error: Some(error),
+        };
+
+        let client = Client::new(endpoint)
+            .map_err(|e| network_error("Failed to create DGraph client", e.to_string()))?;
+        Self::test_connection(&client)
+            .await
+            .map_err(|e| network_error("DGraph health check failed", format!("{:#}", e)))?;
+        Ok(client)
+    }
+
     /// Test the DGraph connection
     async fn test_connection(client: &Client) -> Result<()> {
         let query = "{ health() }";
@@ -239,6 +297,22 @@ pub struct Concept {
     pub version: i32,
 }
 
+impl Concept {
+    /// Check the fields the schema requires before writing the concept
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if self.category.trim().is_empty() {
+            return Err("category must not be empty".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.quality_score) {
+            return Err(format!("quality_score {} is outside 0..=1", self.quality_score));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LearningResource {
     pub id: Uuid,
@@ -297,6 +371,85 @@ pub struct UserProgress {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    fn flaky_config() -> DgraphConfig {
+        DgraphConfig {
+            retry_max_attempts: 3,
+            retry_initial_delay_ms: 1,
+            retry_max_delay_ms: 1,
+            circuit_failure_threshold: 4,
+            circuit_open_timeout_ms: 60_000,
+            ..Default::default()
+        }
+    }
+    
+    fn connection_refused() -> KnowledgeGraphError {
+        KnowledgeGraphError::NetworkError {
+            message: "connection refused".to_string(),
+            endpoint: "localhost:9080".to_string(),
+            retry_count: 0,
+            source_error: None,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let config = flaky_config();
+        let breaker = config.circuit_breaker();
+        let executor = RetryExecutor::new(config.retry_policy()).with_circuit_breaker(breaker.clone());
+        let attempts = AtomicUsize::new(0);
+        
+        // DGraph comes up on the third attempt
+        let result = executor
+            .execute(|| async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(connection_refused()),
+                    _ => Ok("connected"),
+                }
+            })
+            .await;
+        
+        assert_eq!(result.unwrap(), "connected");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        let stats = breaker.stats().await;
+        assert_eq!(stats.state, "Closed");
+        assert_eq!(stats.failure_count, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_sustained_failures_open_the_circuit() {
+        let config = flaky_config();
+        let breaker = config.circuit_breaker();
+        let executor = RetryExecutor::new(config.retry_policy()).with_circuit_breaker(breaker.clone());
+        let attempts = AtomicUsize::new(0);
+        let unavailable = || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(connection_refused())
+        };
+        
+        // Each call retries up to the configured attempts
+        match executor.execute(unavailable).await {
+            Err(KnowledgeGraphError::NetworkError { retry_count, .. }) => assert_eq!(retry_count, 3),
+            other => panic!("Expected NetworkError, got {:?}", other),
+        }
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        
+        // The fourth failure opens the circuit mid-retry
+        match executor.execute(unavailable).await {
+            Err(KnowledgeGraphError::CircuitBreakerOpen { failure_count, .. }) => assert_eq!(failure_count, 4),
+            other => panic!("Expected CircuitBreakerOpen, got {:?}", other),
+        }
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+        
+        // While open, calls fail fast without reaching DGraph
+        assert!(matches!(
+            executor.execute(unavailable).await,
+            Err(KnowledgeGraphError::CircuitBreakerOpen { .. })
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+        assert_eq!(breaker.stats().await.state, "Open");
+    }
     
     #[tokio::test]
     #[ignore] // Requires DGraph instance
@@ -10,6 +10,7 @@ pub mod graceful_degradation;
 pub mod client;
 pub mod graph;
 pub mod query;
+pub mod schema;
 pub mod algorithms;
 pub mod api;
 pub mod service;
@@ -19,5 +20,6 @@ pub use graceful_degradation::{GracefulDegradationHandler, DegradationStrategy,
 pub use client::*;
 pub use graph::*;
 pub use query::*;
+pub use schema::GraphSchema;
 pub use algorithms::*;
 pub use service::*;
\ No newline at end of file
@@ -1,8 +1,13 @@
 //! Query building and execution
 
 use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::graph::Concept;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -490,6 +495,195 @@ impl QueryBuilder {
     }
 }
 
+impl QueryBuilder {
+    /// Build one mutation upserting a batch of concepts.
+    ///
+    /// Without `transactional`, each concept gets its own aliased field
+    /// ([`upsert_alias`] of its index), which Dgraph commits separately, so
+    /// one failure doesn't undo the rest. With it, the whole batch is a
+    /// single field that succeeds or fails as a unit under the
+    /// [`TRANSACTIONAL_UPSERT_ALIAS`] alias.
+    pub fn build_upsert_concepts_mutation(&self, concepts: &[(usize, &Concept)], transactional: bool) -> String {
+        let fields = if transactional {
+            let inputs: Vec<String> = concepts.iter()
+                .map(|(_, concept)| graphql_literal(&concept_input(concept)))
+                .collect();
+            vec![upsert_field(TRANSACTIONAL_UPSERT_ALIAS, &inputs.join(", "))]
+        } else {
+            concepts.iter()
+                .map(|(index, concept)| {
+                    upsert_field(&upsert_alias(*index), &graphql_literal(&concept_input(concept)))
+                })
+                .collect()
+        };
+
+        format!("mutation UpsertConcepts {{\n{}\n}}", fields.join("\n"))
+    }
+}
+
+/// Alias of the field upserting the whole batch in a transactional upsert
+pub const TRANSACTIONAL_UPSERT_ALIAS: &str = "upsertBatch";
+
+/// Alias of the field upserting the concept at `index` of a batch
+pub fn upsert_alias(index: usize) -> String {
+    format!("upsert{}", index)
+}
+
+fn upsert_field(alias: &str, inputs: &str) -> String {
+    format!("  {}: addConcept(input: [{}], upsert: true) {{ concept {{ id name }} }}", alias, inputs)
+}
+
+pub(crate) fn concept_input(concept: &Concept) -> Value {
+    json!({
+        "name": concept.name,
+        "description": concept.description,
+        "difficulty": concept.difficulty,
+        "category": concept.category,
+        "subcategory": concept.subcategory,
+        "tags": concept.tags,
+        "qualityScore": concept.quality_score,
+        "estimatedTime": concept.estimated_time,
+        "embedding": concept.embeddings,
+        "createdAt": concept.created_at,
+        "updatedAt": concept.updated_at,
+        "version": concept.version,
+    })
+}
+
+/// Write a JSON value as a GraphQL input literal. Strings keep JSON's
+/// escaping, which GraphQL shares, so values can't break out of the query.
+pub(crate) fn graphql_literal(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => {
+            let fields: Vec<String> = fields.iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(name, value)| format!("{}: {}", name, graphql_literal(value)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(graphql_literal).collect();
+            format!("[{}]", items.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Page size of a concept listing when the client doesn't ask for one
+pub const DEFAULT_CONCEPT_PAGE_SIZE: u32 = 20;
+
+/// Most concepts one page of a concept listing holds
+pub const MAX_CONCEPT_PAGE_SIZE: u32 = 100;
+
+/// Where a page of a concept listing ends.
+///
+/// Listings are ordered by name and then id, a pair no two concepts share,
+/// so the cursor holds the pair of the last concept listed and the next page
+/// starts right after it. Concepts added or removed between pages don't
+/// shift the listing and cause others to be skipped or repeated, and the
+/// cursor stays the same size however many pages have been read. Clients
+/// only see the [`encode`](Self::encode)d form and pass it back to get the
+/// next page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConceptCursor {
+    /// Name of the last concept listed
+    name: String,
+    /// Id of the last concept listed
+    id: Uuid,
+}
+
+impl ConceptCursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("a cursor always serializes");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self> {
+        URL_SAFE_NO_PAD.decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| anyhow!("Invalid concept cursor: {}", cursor))
+    }
+}
+
+/// Where a concept listing stands after a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageInfo {
+    /// Cursor to pass as `after` for the next page
+    pub end_cursor: Option<String>,
+    pub has_next_page: bool,
+}
+
+/// One page of a concept listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptPage {
+    pub concepts: Vec<Concept>,
+    pub page_info: PageInfo,
+}
+
+impl ConceptPage {
+    /// Build the page of `first` concepts from what the
+    /// [page query](QueryBuilder::build_concept_page_query) returned
+    pub fn from_fetched(first: u32, mut concepts: Vec<Concept>) -> Self {
+        let has_next_page = concepts.len() > first as usize;
+        concepts.truncate(first as usize);
+
+        let end_cursor = concepts.last().map(|last| {
+            ConceptCursor { name: last.name.clone(), id: last.id }.encode()
+        });
+
+        Self {
+            concepts,
+            page_info: PageInfo { end_cursor, has_next_page },
+        }
+    }
+}
+
+impl QueryBuilder {
+    /// Build the query for the `first` concepts after `after`, ordered by
+    /// name and then id, with one extra concept to tell whether another page
+    /// follows.
+    pub fn build_concept_page_query(&self, after: Option<&ConceptCursor>, first: u32) -> String {
+        let filter = match after {
+            Some(after) => format!(
+                "filter: {},",
+                graphql_literal(&json!({
+                    "or": [
+                        { "name": { "gt": after.name } },
+                        { "name": { "eq": after.name }, "id": { "gt": after.id } },
+                    ]
+                }))
+            ),
+            None => String::new(),
+        };
+        format!(r#"
+            query ConceptPage {{
+                concepts: queryConcept(
+                    {}
+                    order: {{ asc: name, then: {{ asc: id }} }},
+                    first: {}
+                ) {{
+                    id
+                    name
+                    description
+                    difficulty
+                    category
+                    subcategory
+                    tags
+                    qualityScore
+                    estimatedTime
+                    createdAt
+                    updatedAt
+                    version
+                }}
+            }}
+        "#,
+        filter,
+        first + 1
+        )
+    }
+}
+
 impl Default for QueryBuilder {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,305 @@
+//! Graph schema validation
+//!
+//! Declares the types and predicates Dgraph expects, read from the same
+//! GraphQL SDL the database is configured with, so payloads can be checked
+//! before they are sent. A mismatch is reported as
+//! [`KnowledgeGraphError::SchemaViolation`] naming the offending predicate,
+//! instead of a cryptic error back from Dgraph.
+
+use chrono::DateTime;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::{KnowledgeGraphError, Result};
+
+/// The schema `dgraph/schema.graphql` declares
+const KNOWLEDGE_GRAPH_SDL: &str = include_str!("../dgraph/schema.graphql");
+
+/// Built-in scalar types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarType {
+    Id,
+    String,
+    Int,
+    Float,
+    Boolean,
+    DateTime,
+}
+
+/// The type of a predicate's values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    Scalar(ScalarType),
+    /// An edge to a node of the named type
+    Object(String),
+}
+
+/// A predicate declared on a type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredicateSchema {
+    pub field_type: FieldType,
+    pub list: bool,
+    pub required: bool,
+}
+
+impl PredicateSchema {
+    /// Parse a GraphQL type expression such as `String!` or `[Concept]`
+    fn parse(expression: &str) -> Option<Self> {
+        let required = expression.ends_with('!');
+        let expression = expression.trim_end_matches('!');
+        let (list, inner) = match expression.strip_prefix('[').and_then(|e| e.strip_suffix(']')) {
+            Some(inner) => (true, inner.trim_end_matches('!')),
+            None => (false, expression),
+        };
+        if inner.is_empty() || !inner.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+
+        let field_type = match inner {
+            "ID" => FieldType::Scalar(ScalarType::Id),
+            "String" => FieldType::Scalar(ScalarType::String),
+            "Int" => FieldType::Scalar(ScalarType::Int),
+            "Float" => FieldType::Scalar(ScalarType::Float),
+            "Boolean" => FieldType::Scalar(ScalarType::Boolean),
+            "DateTime" => FieldType::Scalar(ScalarType::DateTime),
+            other => FieldType::Object(other.to_string()),
+        };
+        Some(Self { field_type, list, required })
+    }
+}
+
+/// Types and their predicates
+#[derive(Debug, Clone, Default)]
+pub struct GraphSchema {
+    types: HashMap<String, BTreeMap<String, PredicateSchema>>,
+}
+
+impl GraphSchema {
+    /// The schema the knowledge graph's Dgraph instance is set up with
+    pub fn knowledge_graph() -> Self {
+        Self::parse(KNOWLEDGE_GRAPH_SDL).expect("bundled Dgraph schema is valid")
+    }
+
+    /// Parse the `type` definitions of a GraphQL SDL document. Directives
+    /// are ignored.
+    pub fn parse(sdl: &str) -> Result<Self> {
+        let parse_error = |line: usize, message: &str| KnowledgeGraphError::ParseError {
+            message: format!("Invalid schema at line {}: {}", line + 1, message),
+            field: None,
+            raw_data: sdl.lines().nth(line).map(str::to_string),
+            source_error: None,
+        };
+
+        let mut schema = Self::default();
+        let mut current: Option<(String, BTreeMap<String, PredicateSchema>)> = None;
+        for (number, line) in sdl.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(definition) = line.strip_prefix("type ") {
+                let name = definition.split(|c: char| c.is_whitespace() || c == '{').next().unwrap_or_default();
+                if name.is_empty() || current.is_some() {
+                    return Err(parse_error(number, "unexpected type definition"));
+                }
+                current = Some((name.to_string(), BTreeMap::new()));
+            } else if line == "}" {
+                let (name, predicates) = current.take()
+                    .ok_or_else(|| parse_error(number, "unmatched '}'"))?;
+                schema.types.insert(name, predicates);
+            } else {
+                let (_, predicates) = current.as_mut()
+                    .ok_or_else(|| parse_error(number, "field outside a type"))?;
+                let (name, rest) = line.split_once(':')
+                    .ok_or_else(|| parse_error(number, "expected 'name: Type'"))?;
+                let predicate = rest.split_whitespace().next()
+                    .and_then(PredicateSchema::parse)
+                    .ok_or_else(|| parse_error(number, "invalid field type"))?;
+                predicates.insert(name.trim().to_string(), predicate);
+            }
+        }
+
+        match current {
+            Some((name, _)) => Err(parse_error(sdl.lines().count().saturating_sub(1), &format!("type {} is not closed", name))),
+            None => Ok(schema),
+        }
+    }
+
+    /// Predicates of a type, if the schema declares it
+    pub fn predicates(&self, type_name: &str) -> Option<&BTreeMap<String, PredicateSchema>> {
+        self.types.get(type_name)
+    }
+
+    /// Check a node payload of type `type_name`, such as the input of an
+    /// `add` mutation.
+    ///
+    /// Every predicate must be declared with a value of its type, and every
+    /// required predicate except the generated `ID` must be present. Nested
+    /// nodes are checked against their own type; a nested object holding
+    /// only an `id` is a reference to an existing node.
+    pub fn validate(&self, type_name: &str, payload: &Value) -> Result<()> {
+        let predicates = self.types.get(type_name)
+            .ok_or_else(|| violation(type_name, "is not a type in the schema"))?;
+        let fields = payload.as_object()
+            .ok_or_else(|| violation(type_name, "expected an object"))?;
+
+        for (name, value) in fields {
+            let predicate_name = format!("{}.{}", type_name, name);
+            let predicate = predicates.get(name)
+                .ok_or_else(|| violation(&predicate_name, &format!("is not a predicate of {}", type_name)))?;
+            self.validate_value(&predicate_name, predicate, value)?;
+        }
+
+        for (name, predicate) in predicates {
+            let generated = predicate.field_type == FieldType::Scalar(ScalarType::Id);
+            if predicate.required && !generated && !fields.contains_key(name) {
+                return Err(violation(&format!("{}.{}", type_name, name), "is required"));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_value(&self, predicate_name: &str, predicate: &PredicateSchema, value: &Value) -> Result<()> {
+        match value {
+            Value::Null if predicate.required => Err(violation(predicate_name, "is required")),
+            Value::Null => Ok(()),
+            Value::Array(items) if predicate.list => items.iter()
+                .try_for_each(|item| self.validate_item(predicate_name, &predicate.field_type, item)),
+            _ if predicate.list => Err(violation(predicate_name, &format!("expected a list, got {}", describe(value)))),
+            _ => self.validate_item(predicate_name, &predicate.field_type, value),
+        }
+    }
+
+    fn validate_item(&self, predicate_name: &str, field_type: &FieldType, value: &Value) -> Result<()> {
+        let scalar = match field_type {
+            FieldType::Object(type_name) => {
+                let reference = value.as_object()
+                    .is_some_and(|fields| fields.len() == 1 && fields.get("id").is_some_and(Value::is_string));
+                if reference {
+                    return Ok(());
+                }
+                if !value.is_object() {
+                    return Err(violation(predicate_name, &format!("expected a {} node, got {}", type_name, describe(value))));
+                }
+                return self.validate(type_name, value);
+            }
+            FieldType::Scalar(scalar) => *scalar,
+        };
+
+        let valid = match scalar {
+            ScalarType::Id | ScalarType::String => value.is_string(),
+            ScalarType::Int => value.is_i64() || value.is_u64(),
+            ScalarType::Float => value.is_number(),
+            ScalarType::Boolean => value.is_boolean(),
+            ScalarType::DateTime => value.as_str().is_some_and(|s| DateTime::parse_from_rfc3339(s).is_ok()),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(violation(predicate_name, &format!("expected {:?}, got {}", scalar, describe(value))))
+        }
+    }
+}
+
+fn violation(predicate: &str, reason: &str) -> KnowledgeGraphError {
+    KnowledgeGraphError::SchemaViolation {
+        predicate: predicate.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// A short description of a value for error messages
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(_) => "a boolean".to_string(),
+        Value::Number(n) => format!("the number {}", n),
+        Value::String(s) => format!("the string {:?}", s),
+        Value::Array(_) => "a list".to_string(),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Concept;
+    use crate::query::concept_input;
+    use serde_json::json;
+
+    fn concept_payload() -> Value {
+        concept_input(&Concept {
+            id: uuid::Uuid::new_v4(),
+            name: "Ownership".to_string(),
+            description: Some("How Rust manages memory".to_string()),
+            difficulty: "intermediate".to_string(),
+            category: "programming".to_string(),
+            subcategory: None,
+            tags: vec!["rust".to_string()],
+            quality_score: 0.9,
+            estimated_time: Some(45.0),
+            embeddings: vec![0.1, 0.2],
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            version: 1,
+        })
+    }
+
+    #[test]
+    fn test_wrong_typed_predicate_is_named() {
+        let schema = GraphSchema::knowledge_graph();
+        assert!(schema.validate("Concept", &concept_payload()).is_ok());
+
+        let mut payload = concept_payload();
+        payload["qualityScore"] = json!("high");
+        match schema.validate("Concept", &payload) {
+            Err(KnowledgeGraphError::SchemaViolation { predicate, reason }) => {
+                assert_eq!(predicate, "Concept.qualityScore");
+                assert_eq!(reason, "expected Float, got the string \"high\"");
+            }
+            other => panic!("Expected SchemaViolation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_unknown_and_nested_predicates() {
+        let schema = GraphSchema::knowledge_graph();
+        let violated = |payload: Value| match schema.validate("Concept", &payload) {
+            Err(KnowledgeGraphError::SchemaViolation { predicate, .. }) => predicate,
+            other => panic!("Expected SchemaViolation, got {:?}", other),
+        };
+
+        let mut payload = concept_payload();
+        payload.as_object_mut().unwrap().remove("category");
+        assert_eq!(violated(payload), "Concept.category");
+
+        let mut payload = concept_payload();
+        payload["popularity"] = json!(3);
+        assert_eq!(violated(payload), "Concept.popularity");
+
+        // Edges may reference existing nodes by id, or add nodes of their type
+        let mut payload = concept_payload();
+        payload["prerequisites"] = json!([{ "id": "0x1" }, concept_payload()]);
+        assert!(schema.validate("Concept", &payload).is_ok());
+        payload["resources"] = json!([{ "url": "https://doc.rust-lang.org/book/", "title": 42 }]);
+        assert_eq!(violated(payload), "LearningResource.title");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_sdl() {
+        assert!(GraphSchema::parse("type Concept {\n  name: String!\n").is_err());
+        assert!(GraphSchema::parse("name: String!").is_err());
+        assert!(GraphSchema::parse("type Concept {\n  name String\n}").is_err());
+
+        let schema = GraphSchema::parse("type Tag { # a label\n  label: [String!]! @search\n}").unwrap();
+        assert_eq!(
+            schema.predicates("Tag").unwrap()["label"],
+            PredicateSchema {
+                field_type: FieldType::Scalar(ScalarType::String),
+                list: true,
+                required: true,
+            }
+        );
+    }
+}
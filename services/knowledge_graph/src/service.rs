@@ -3,16 +3,17 @@
 //! Integrates Dgraph queries with graph algorithms to provide
 //! comprehensive knowledge graph operations and relationship discovery.
 
-use crate::algorithms::{GraphAlgorithms, shortest_path::{GraphEdge, EdgeRelationship}, traversal::{TraversalConfig, ConceptEdge, RelationshipType}};
+use crate::algorithms::{GraphAlgorithms, ranking::RankingEdge, shortest_path::{GraphEdge, EdgeRelationship}, traversal::{TraversalConfig, ConceptEdge, RelationshipType, SubGraph}};
 use crate::client::{DgraphClient, DgraphResponseParser};
 use crate::graph::{Concept, DgraphConfig, GraphDatabase};
-use crate::query::{QueryBuilder, QueryType, QueryParameters, QueryConstraints};
+use crate::query::{QueryBuilder, QueryType, QueryParameters, QueryConstraints, ConceptCursor, ConceptPage, upsert_alias, DEFAULT_CONCEPT_PAGE_SIZE, MAX_CONCEPT_PAGE_SIZE, TRANSACTIONAL_UPSERT_ALIAS};
 use crate::error::{KnowledgeGraphError, Result as KgResult, ErrorContext};
 use anyhow::{anyhow, Context, Result};
 use anyhow::Context as AnyhowContext;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
@@ -67,6 +68,108 @@ pub struct PathMetadata {
     pub estimated_time: f32,
 }
 
+/// Most concepts a subgraph extraction returns
+pub const SUBGRAPH_MAX_NODES: usize = 200;
+
+/// Outcome of upserting one concept of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptUpsertResult {
+    pub concept_id: Uuid,
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// A batch of concepts validated and ready to upsert in one mutation
+#[derive(Debug, Clone)]
+pub struct ConceptUpsertBatch {
+    concepts: Vec<Concept>,
+    /// Validation failures by index; these concepts are never sent
+    rejected: HashMap<usize, String>,
+    transactional: bool,
+}
+
+impl ConceptUpsertBatch {
+    /// Validate `concepts`. A transactional batch with an invalid concept is
+    /// rejected as a whole.
+    pub fn new(concepts: Vec<Concept>, transactional: bool) -> Self {
+        let mut rejected: HashMap<usize, String> = concepts.iter()
+            .enumerate()
+            .filter_map(|(index, concept)| concept.validate().err().map(|e| (index, e)))
+            .collect();
+
+        if transactional && !rejected.is_empty() {
+            let reason = format!("Batch rejected: {} invalid concept(s)", rejected.len());
+            for index in 0..concepts.len() {
+                rejected.entry(index).or_insert_with(|| reason.clone());
+            }
+        }
+
+        Self { concepts, rejected, transactional }
+    }
+
+    /// The mutation for the valid concepts, or `None` if there are none
+    pub fn mutation(&self, query_builder: &QueryBuilder) -> Option<String> {
+        let valid: Vec<(usize, &Concept)> = self.concepts.iter()
+            .enumerate()
+            .filter(|(index, _)| !self.rejected.contains_key(index))
+            .collect();
+        if valid.is_empty() {
+            return None;
+        }
+        Some(query_builder.build_upsert_concepts_mutation(&valid, self.transactional))
+    }
+
+    /// Per-concept results, in batch order, from the mutation's response
+    pub fn results(self, response: &serde_json::Value) -> Vec<ConceptUpsertResult> {
+        let errors: Vec<(Option<&str>, &str)> = response.get("errors")
+            .and_then(|e| e.as_array())
+            .map(|errors| errors.iter()
+                .map(|error| (
+                    error.pointer("/path/0").and_then(|p| p.as_str()),
+                    error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error"),
+                ))
+                .collect())
+            .unwrap_or_default();
+        let field_error = |alias: &str| -> Option<String> {
+            let written = response.pointer(&format!("/data/{}", alias))
+                .is_some_and(|field| !field.is_null());
+            let messages: Vec<&str> = errors.iter()
+                .filter(|(path, _)| path.map_or(true, |p| p == alias))
+                .map(|(_, message)| *message)
+                .collect();
+            match (written, messages.is_empty()) {
+                (true, true) => None,
+                (_, false) => Some(messages.join("; ")),
+                (false, true) => Some("Concept was not written".to_string()),
+            }
+        };
+        let batch_error = if self.transactional {
+            field_error(TRANSACTIONAL_UPSERT_ALIAS)
+        } else {
+            None
+        };
+
+        let mut rejected = self.rejected;
+        self.concepts.into_iter()
+            .enumerate()
+            .map(|(index, concept)| {
+                let error = match rejected.remove(&index) {
+                    Some(reason) => Some(reason),
+                    None if self.transactional => batch_error.clone(),
+                    None => field_error(&upsert_alias(index)),
+                };
+                ConceptUpsertResult {
+                    concept_id: concept.id,
+                    name: concept.name,
+                    success: error.is_none(),
+                    error,
+                }
+            })
+            .collect()
+    }
+}
+
 /// High-level knowledge graph service
 pub struct KnowledgeGraphService {
     client: Arc<DgraphClient>,
@@ -74,6 +177,7 @@ pub struct KnowledgeGraphService {
     algorithms: GraphAlgorithms,
     query_builder: QueryBuilder,
     response_parser: DgraphResponseParser,
+    mutation_timeout: Duration,
 }
 
 impl KnowledgeGraphService {
@@ -81,9 +185,10 @@ impl KnowledgeGraphService {
     pub async fn new(config: DgraphConfig) -> Result<Self> {
         info!("Initializing Knowledge Graph Service");
         
+        let mutation_timeout = Duration::from_millis(config.mutation_timeout_ms);
+        
         // Create clients
-        let endpoint = format!("{}:{}", config.host, config.grpc_port);
-        let client = Arc::new(DgraphClient::new(endpoint).await
+        let client = Arc::new(DgraphClient::from_dgraph_config(&config).await
             .map_err(|e| anyhow::Error::from(e))
             .context("Failed to create Dgraph client")?);
         
@@ -103,6 +208,7 @@ impl KnowledgeGraphService {
             algorithms,
             query_builder,
             response_parser,
+            mutation_timeout,
         })
     }
 
@@ -247,6 +353,72 @@ impl KnowledgeGraphService {
         Ok(results)
     }
 
+    /// Recommend concepts that share the most neighbors with a concept,
+    /// scored so that widely connected neighbors count for less
+    pub async fn recommend_related(&self, concept_id: Uuid, limit: u32) -> Result<Vec<(Concept, f32)>> {
+        info!("Recommending concepts related to {}", concept_id);
+        
+        // Shared neighbors are two hops out
+        let concepts = self.load_related_concepts(&concept_id, 2).await?;
+        let edges = self.build_concept_edges(&concepts).await?
+            .into_iter()
+            .map(|edge| RankingEdge { from: edge.from, to: edge.to, weight: edge.weight })
+            .collect();
+        
+        let mut algorithms = self.algorithms.clone();
+        algorithms.add_concepts(concepts.clone());
+        algorithms.ranking_engine_mut().add_edges(edges);
+        
+        let recommendations = algorithms.ranking_engine()
+            .recommend_related(concept_id, limit as usize)?
+            .into_iter()
+            .filter_map(|(id, score)| {
+                concepts.iter().find(|c| c.id == id).map(|c| (c.clone(), score))
+            })
+            .collect::<Vec<_>>();
+        
+        info!("Found {} related concept recommendations", recommendations.len());
+        Ok(recommendations)
+    }
+
+    /// Extract the concepts within `radius` hops of `center`, and the edges
+    /// between them, capped at [`SUBGRAPH_MAX_NODES`] concepts
+    pub async fn extract_subgraph(&self, center: Uuid, radius: u32) -> KgResult<SubGraph> {
+        info!("Extracting subgraph around {} with radius {}", center, radius);
+        
+        let concepts = self.load_related_concepts(&center, radius as usize).await?;
+        let edges = self.build_concept_edges(&concepts).await?;
+        
+        let mut algorithms = self.algorithms.clone();
+        algorithms.add_concepts(concepts);
+        algorithms.traversal_engine_mut().add_edges(edges);
+        
+        let subgraph = algorithms.traversal_engine()
+            .extract_subgraph(center, radius as usize, SUBGRAPH_MAX_NODES)?;
+        
+        if subgraph.truncated {
+            warn!("Subgraph around {} truncated to {} concepts", center, SUBGRAPH_MAX_NODES);
+        }
+        Ok(subgraph)
+    }
+
+    /// List concepts by name one page at a time.
+    ///
+    /// `after` is the `end_cursor` of the previous page, and `first` is
+    /// capped at [`MAX_CONCEPT_PAGE_SIZE`]. The listing is over once a page
+    /// reports no next page.
+    pub async fn list_concepts(&self, after: Option<&str>, first: Option<u32>) -> Result<ConceptPage> {
+        let after = after.map(ConceptCursor::decode).transpose()?;
+        let first = first.unwrap_or(DEFAULT_CONCEPT_PAGE_SIZE).clamp(1, MAX_CONCEPT_PAGE_SIZE);
+        debug!("Listing {} concepts after {:?}", first, after);
+        
+        let query = self.query_builder.build_concept_page_query(after.as_ref(), first);
+        let result = self.client.query(&query).await?;
+        let fetched = self.response_parser.parse_concepts_from_search_result(result, None)?;
+        
+        Ok(ConceptPage::from_fetched(first, fetched))
+    }
+
     /// Get concept recommendations based on user progress
     pub async fn get_recommendations(&self, user_id: &str, limit: Option<u32>) -> Result<Vec<Concept>> {
         info!("Getting recommendations for user: {}", user_id);
@@ -259,6 +431,34 @@ impl KnowledgeGraphService {
         Ok(popular_concepts)
     }
 
+    /// Upsert a batch of concepts in a single mutation, reporting the
+    /// outcome of each.
+    ///
+    /// Invalid concepts are reported without being sent. Otherwise each
+    /// concept succeeds or fails on its own, unless `transactional` is set,
+    /// in which case any failure fails the whole batch. The mutation must
+    /// finish within the configured `mutation_timeout_ms`.
+    pub async fn upsert_concepts(&self, batch: Vec<Concept>, transactional: bool) -> Result<Vec<ConceptUpsertResult>> {
+        info!("Upserting {} concepts (transactional: {})", batch.len(), transactional);
+        
+        let batch = ConceptUpsertBatch::new(batch, transactional);
+        let response = match batch.mutation(&self.query_builder) {
+            Some(mutation) => tokio::time::timeout(self.mutation_timeout, self.client.mutate(&mutation))
+                .await
+                .map_err(|_| anyhow!("Concept upsert timed out after {:?}", self.mutation_timeout))?
+                .map_err(anyhow::Error::from)
+                .context("Failed to upsert concepts")?,
+            None => serde_json::Value::Null,
+        };
+        
+        let results = batch.results(&response);
+        let failed = results.iter().filter(|r| !r.success).count();
+        if failed > 0 {
+            warn!("{} of {} concept upserts failed", failed, results.len());
+        }
+        Ok(results)
+    }
+
     /// Private helper methods
     
     async fn get_concept_by_id(&self, concept_id: &str) -> Result<Concept> {
@@ -11,8 +11,8 @@ use knowledge_graph::{
     },
     client::{DgraphClient, ConnectionConfig},
     graph::{Concept, DgraphConfig, GraphDatabase},
-    query::{QueryBuilder, QueryType, QueryParameters, QueryConstraints},
-    service::{KnowledgeGraphService, RelationshipDiscoveryRequest, PathFindingRequest},
+    query::{QueryBuilder, QueryType, QueryParameters, QueryConstraints, ConceptCursor, ConceptPage},
+    service::{KnowledgeGraphService, RelationshipDiscoveryRequest, PathFindingRequest, ConceptUpsertBatch},
 };
 use chrono::Utc;
 use std::collections::HashMap;
@@ -92,6 +92,205 @@ async fn test_concept_creation_query() {
     assert!(mutation_string.contains("intermediate"));
 }
 
+/// Ten concepts, the fourth of which has no name
+fn upsert_batch_with_one_invalid() -> Vec<Concept> {
+    let mut concepts: Vec<Concept> = (0..10)
+        .map(|i| create_test_concept(&format!("Concept {}", i), "programming", "beginner"))
+        .collect();
+    concepts[3].name = "  ".to_string();
+    concepts
+}
+
+#[tokio::test]
+async fn test_bulk_upsert_reports_each_concept() {
+    let query_builder = QueryBuilder::new();
+    let concepts = upsert_batch_with_one_invalid();
+    let ids: Vec<Uuid> = concepts.iter().map(|c| c.id).collect();
+    let batch = ConceptUpsertBatch::new(concepts, false);
+    
+    // One mutation, with a field for every valid concept
+    let mutation = batch.mutation(&query_builder).unwrap();
+    assert_eq!(mutation.matches("addConcept(").count(), 9);
+    assert!(!mutation.contains("upsert3:"));
+    assert!(mutation.contains(r#"name: "Concept 9""#));
+    
+    // Dgraph writes every concept except the seventh
+    let mut data = serde_json::Map::new();
+    for i in (0..10).filter(|i| *i != 3) {
+        let field = if i == 6 {
+            serde_json::Value::Null
+        } else {
+            serde_json::json!({ "concept": [{ "id": format!("0x{}", i), "name": format!("Concept {}", i) }] })
+        };
+        data.insert(format!("upsert{}", i), field);
+    }
+    let response = serde_json::json!({
+        "data": data,
+        "errors": [{ "message": "category is too long", "path": ["upsert6"] }]
+    });
+    
+    let results = batch.results(&response);
+    assert_eq!(results.len(), 10);
+    assert_eq!(results.iter().map(|r| r.concept_id).collect::<Vec<_>>(), ids);
+    for (i, result) in results.iter().enumerate() {
+        match i {
+            3 => assert_eq!(result.error.as_deref(), Some("name must not be empty")),
+            6 => assert_eq!(result.error.as_deref(), Some("category is too long")),
+            _ => assert!(result.success, "concept {} should succeed: {:?}", i, result.error),
+        }
+    }
+    assert_eq!(results.iter().filter(|r| r.success).count(), 8);
+}
+
+#[tokio::test]
+async fn test_transactional_bulk_upsert_fails_as_a_unit() {
+    let query_builder = QueryBuilder::new();
+    
+    // An invalid concept rejects the batch without sending anything
+    let batch = ConceptUpsertBatch::new(upsert_batch_with_one_invalid(), true);
+    assert!(batch.mutation(&query_builder).is_none());
+    let results = batch.results(&serde_json::Value::Null);
+    assert!(results.iter().all(|r| !r.success));
+    assert_eq!(results[3].error.as_deref(), Some("name must not be empty"));
+    assert_eq!(results[0].error.as_deref(), Some("Batch rejected: 1 invalid concept(s)"));
+    
+    // A valid batch is a single field that succeeds or fails together
+    let concepts: Vec<Concept> = (0..10)
+        .map(|i| create_test_concept(&format!("Concept {}", i), "programming", "beginner"))
+        .collect();
+    let batch = ConceptUpsertBatch::new(concepts, true);
+    let mutation = batch.mutation(&query_builder).unwrap();
+    assert_eq!(mutation.matches("addConcept(").count(), 1);
+    
+    let response = serde_json::json!({
+        "data": { "upsertBatch": null },
+        "errors": [{ "message": "transaction aborted", "path": ["upsertBatch"] }]
+    });
+    let results = batch.results(&response);
+    assert!(results.iter().all(|r| r.error.as_deref() == Some("transaction aborted")));
+}
+
+/// Answer a concept page query the way Dgraph would, from `stored`
+/// concepts ordered by name and then id
+fn fetch_page(stored: &[Concept], query: &str) -> Vec<Concept> {
+    let first_at = query.find("first: ").unwrap() + "first: ".len();
+    let first: usize = query[first_at..].chars().take_while(|c| c.is_ascii_digit()).collect::<String>().parse().unwrap();
+    let string_after = |key: &str| {
+        query.find(key).map(|at| {
+            serde_json::Deserializer::from_str(&query[at + key.len()..])
+                .into_iter::<String>()
+                .next()
+                .unwrap()
+                .unwrap()
+        })
+    };
+    let after = string_after("eq: ").map(|name| (name, Uuid::parse_str(&string_after("id: {gt: ").unwrap()).unwrap()));
+    let mut ordered: Vec<&Concept> = stored.iter().collect();
+    ordered.sort_by(|a, b| (&a.name, a.id).cmp(&(&b.name, b.id)));
+    ordered.into_iter()
+        .filter(|c| after.as_ref().is_none_or(|(name, id)| (&c.name, c.id) > (name, *id)))
+        .take(first)
+        .cloned()
+        .collect()
+}
+
+/// Ids of `concepts` in listing order
+fn listing_order(concepts: &[Concept]) -> Vec<Uuid> {
+    let mut ordered: Vec<&Concept> = concepts.iter().collect();
+    ordered.sort_by(|a, b| (&a.name, a.id).cmp(&(&b.name, b.id)));
+    ordered.into_iter().map(|c| c.id).collect()
+}
+
+/// List every concept `page_size` at a time, calling `between_pages` with
+/// the stored concepts after each page
+fn list_all(
+    stored: &mut Vec<Concept>,
+    page_size: u32,
+    mut between_pages: impl FnMut(&mut Vec<Concept>, usize),
+) -> (Vec<Uuid>, Vec<usize>) {
+    let query_builder = QueryBuilder::new();
+    let mut listed = Vec::new();
+    let mut page_sizes = Vec::new();
+    let mut after: Option<ConceptCursor> = None;
+    loop {
+        let query = query_builder.build_concept_page_query(after.as_ref(), page_size);
+        let page = ConceptPage::from_fetched(page_size, fetch_page(stored, &query));
+        page_sizes.push(page.concepts.len());
+        listed.extend(page.concepts.into_iter().map(|c| c.id));
+        
+        if !page.page_info.has_next_page {
+            break;
+        }
+        let cursor = page.page_info.end_cursor.expect("a page with more to come has a cursor");
+        after = Some(ConceptCursor::decode(&cursor).unwrap());
+        assert!(page_sizes.len() <= 20, "listing did not terminate");
+        between_pages(stored, page_sizes.len());
+    }
+    (listed, page_sizes)
+}
+
+#[tokio::test]
+async fn test_concept_listing_pages_through_large_sets() {
+    // Names repeat three times, so some pages end partway through a name
+    let mut stored: Vec<Concept> = (0..1005)
+        .map(|i| create_test_concept(&format!("Concept {:04}", i / 3), "programming", "beginner"))
+        .collect();
+    
+    let (listed, page_sizes) = list_all(&mut stored, 100, |_, _| {});
+    assert_eq!(page_sizes, [vec![100usize; 10], vec![5]].concat());
+    assert_eq!(listed, listing_order(&stored));
+    
+    // A set that fills its last page exactly ends without an empty page
+    let (_, page_sizes) = list_all(&mut stored[..1000].to_vec(), 100, |_, _| {});
+    assert_eq!(page_sizes, vec![100usize; 10]);
+    
+    assert!(ConceptCursor::decode("not-a-cursor").is_err());
+    assert!(ConceptCursor::decode("e30").is_err());
+    assert!(ConceptPage::from_fetched(20, vec![]).page_info.end_cursor.is_none());
+    
+    // The cursor is the same size on every page
+    let cursor_sizes: std::collections::HashSet<usize> = (0..10)
+        .map(|page| {
+            let concepts = stored[page * 100..page * 100 + 101].to_vec();
+            ConceptPage::from_fetched(100, concepts).page_info.end_cursor.unwrap().len()
+        })
+        .collect();
+    assert_eq!(cursor_sizes.len(), 1);
+}
+
+#[tokio::test]
+async fn test_concept_listing_is_stable_under_concurrent_inserts() {
+    let mut stored: Vec<Concept> = (0..300)
+        .map(|i| create_test_concept(&format!("Concept {:04}", i / 2), "programming", "beginner"))
+        .collect();
+    let original: Vec<Uuid> = listing_order(&stored);
+    let mut inserted_later = None;
+    
+    let (listed, _) = list_all(&mut stored, 50, |stored, pages| {
+        if pages == 1 {
+            // One concept sorting before the listed ones, one tied with the
+            // last listed name, and one still to come
+            for name in ["Concept 0000", "Concept 0024", "Concept 0100"] {
+                let concept = create_test_concept(name, "programming", "beginner");
+                if name == "Concept 0100" {
+                    inserted_later = Some(concept.id);
+                }
+                let at = stored.iter().rposition(|c| c.name.as_str() <= name).map_or(0, |i| i + 1);
+                stored.insert(at, concept);
+            }
+        }
+    });
+    
+    // Every concept stored from the start is listed once and in order
+    let listed_original: Vec<Uuid> = listed.iter().copied().filter(|id| original.contains(id)).collect();
+    assert_eq!(listed_original, original);
+    assert!(listed.contains(&inserted_later.unwrap()));
+    let mut unique = listed.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), listed.len());
+}
+
 #[tokio::test]
 async fn test_shortest_path_algorithm() {
     let mut shortest_path = ShortestPath::new();
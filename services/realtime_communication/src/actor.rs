@@ -11,7 +11,7 @@ use tokio_stream::StreamExt;
 use tracing::{info, warn, error, debug};
 use serde::{Deserialize, Serialize};
 
-use crate::connection::{ConnectionManager, ConnectionInfo};
+use crate::connection::ConnectionManager;
 use crate::server::{ServerConfig, ServerMetrics};
 
 /// WebSocket message types
@@ -62,17 +62,11 @@ impl WebSocketActor {
         }
     }
 
-    /// Run the WebSocket actor
+    /// Run the WebSocket actor. The connection must already be registered
+    /// with the connection manager, which happens during the handshake.
     pub async fn run(mut self, mut stream: MessageStream) -> Result<(), ActorError> {
         info!("WebSocket actor started for connection {}", self.connection_id);
 
-        // Create connection info and add to manager
-        let connection_info = ConnectionInfo::new(self.connection_id);
-        if let Err(e) = self.connection_manager.add_connection(connection_info).await {
-            error!("Failed to add connection {}: {}", self.connection_id, e);
-            return Err(ActorError::ConnectionSetupFailed(e.to_string()));
-        }
-
         // Send welcome message
         if let Err(e) = self.send_welcome().await {
             warn!("Failed to send welcome message: {}", e);
@@ -337,6 +337,7 @@ impl SessionManagerActor {
             messages_routed: 0, // Would be maintained by router
             messages_delivered: 0, // Would be maintained by router
             messages_failed: 0, // Would be maintained by router
+            messages_dropped: 0, // Would be maintained by router
             topics_active: 0, // Would be maintained by router
             uptime_seconds: self.startup_time.elapsed().as_secs(),
         }
@@ -4,7 +4,7 @@
 
 use actix::{Message, Recipient};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// WebSocket message types for client communication
@@ -23,11 +23,17 @@ pub enum ClientMessage {
     DirectMessage { to: String, content: serde_json::Value, message_id: Option<String> },
     TopicMessage { topic: String, content: serde_json::Value, message_id: Option<String> },
     BroadcastMessage { content: serde_json::Value, message_id: Option<String> },
+    /// Edit a topic message; only its sender or an admin may
+    EditMessage { message_id: String, new_content: serde_json::Value },
+    /// Delete a topic message, leaving a tombstone in the history
+    DeleteMessage { message_id: String },
     
     // Presence
     UpdatePresence { status: PresenceStatus, message: Option<String> },
+    /// Typing in a room (a topic); resend to keep the indicator alive
     TypingStart { conversation_id: String },
-    TypingEnd { conversation_id: String },
+    #[serde(alias = "TypingEnd")]
+    TypingStop { conversation_id: String },
     
     // Control
     Ping { timestamp: u64 },
@@ -47,12 +53,15 @@ pub enum ServerMessage {
     MessageReceived { from: String, content: serde_json::Value, message_id: String, timestamp: i64 },
     TopicMessageReceived { topic: String, from: String, content: serde_json::Value, message_id: String, timestamp: i64 },
     BroadcastReceived { from: String, content: serde_json::Value, message_id: String, timestamp: i64 },
+    MessageEdited { topic: String, message_id: String, edited_by: String, content: serde_json::Value, edited_at: i64 },
+    MessageDeleted { topic: String, message_id: String, deleted_by: String, deleted_at: i64 },
     
     // Notifications
     Notification { level: NotificationLevel, title: String, message: String, timestamp: i64 },
     
     // Presence updates
     PresenceUpdate { user_id: String, status: PresenceStatus, last_seen: Option<i64> },
+    PresenceBatch { updates: Vec<PresenceChange> },
     TypingIndicator { user_id: String, conversation_id: String, is_typing: bool },
     
     // System messages
@@ -74,6 +83,14 @@ pub enum PresenceStatus {
     Offline,
 }
 
+/// One user's presence within a [`ServerMessage::PresenceBatch`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PresenceChange {
+    pub user_id: String,
+    pub status: PresenceStatus,
+    pub last_seen: Option<i64>,
+}
+
 /// Notification levels
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NotificationLevel {
@@ -156,6 +173,37 @@ pub struct UnsubscribeFromTopic {
     pub topics: Vec<String>,
 }
 
+/// Record the roles of an authenticated connection. Sent by the server
+/// after validating the connection's token, never taken from the client.
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct SetConnectionRoles {
+    pub connection_id: Uuid,
+    pub roles: HashSet<String>,
+}
+
+/// Who receives an [`AdminBroadcast`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum BroadcastScope {
+    All,
+    /// Connections whose user has this role
+    Role(String),
+    /// Connections subscribed to this topic
+    Room(String),
+}
+
+/// Push an announcement to every connection in `scope`, returning how many
+/// connections it was delivered to
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "usize")]
+pub struct AdminBroadcast {
+    pub scope: BroadcastScope,
+    pub from: String,
+    pub content: serde_json::Value,
+    pub message_id: Option<String>,
+}
+
 #[derive(Message, Debug, Clone)]
 #[rtype(result = "Vec<ConnectionSummary>")]
 pub struct GetConnections {
@@ -195,6 +243,8 @@ pub struct SystemStats {
     pub messages_routed: u64,
     pub messages_delivered: u64,
     pub messages_failed: u64,
+    /// Messages discarded because a slow client's send queue was full
+    pub messages_dropped: u64,
     pub topics_active: usize,
     pub uptime_seconds: u64,
 }
@@ -7,8 +7,10 @@ pub mod router;
 pub mod session;
 pub mod manager;
 pub mod messages;
+pub mod outbox;
 
 pub use router::*;
 pub use session::*;
 pub use manager::*;
-pub use messages::*;
\ No newline at end of file
+pub use messages::*;
+pub use outbox::*;
\ No newline at end of file
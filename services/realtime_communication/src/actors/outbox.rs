@@ -0,0 +1,293 @@
+//! Session Outbox
+//!
+//! Bounded per-connection send queues between the router and session actors,
+//! so a slow client can't make the server buffer without limit.
+
+use actix::prelude::SendError;
+use actix::Recipient;
+use std::collections::{HashMap, HashSet, VecDeque};
+use tracing::warn;
+use uuid::Uuid;
+
+use super::messages::SessionMessage;
+
+/// What to do when a connection's send queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the oldest queued messages to make room
+    DropOldest,
+    /// Disconnect the client; it is too far behind to catch up
+    Disconnect,
+}
+
+/// Send queue configuration
+#[derive(Debug, Clone, Copy)]
+pub struct OutboxConfig {
+    /// Messages queued per connection once its session's mailbox is full
+    pub capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 256,
+            overflow_policy: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+/// Result of handing a message to the outbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    /// Accepted by the session's mailbox
+    Delivered,
+    /// Waiting for the session to catch up
+    Queued,
+    /// Queued after dropping this many older messages
+    DroppedOldest(usize),
+    /// The queue overflowed and the connection must be disconnected
+    Overflowed,
+}
+
+/// Delivers messages to sessions through bounded queues.
+///
+/// Messages go straight to a session's mailbox while it has room. Once it
+/// is full they wait in the connection's queue, which [`Outbox::flush`]
+/// drains as the session catches up. A full queue is handled according to
+/// the [`OverflowPolicy`].
+#[derive(Debug, Default)]
+pub struct Outbox {
+    config: OutboxConfig,
+    queues: HashMap<Uuid, VecDeque<SessionMessage>>,
+    /// Connections that overflowed under [`OverflowPolicy::Disconnect`]
+    overflowed: HashSet<Uuid>,
+    dropped: u64,
+}
+
+impl Outbox {
+    pub fn new(config: OutboxConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Deliver or queue a message for a connection
+    pub fn send(
+        &mut self,
+        connection_id: Uuid,
+        recipient: &Recipient<SessionMessage>,
+        message: SessionMessage,
+    ) -> DeliveryOutcome {
+        if self.overflowed.contains(&connection_id) {
+            self.dropped += 1;
+            return DeliveryOutcome::Overflowed;
+        }
+
+        let queue = self.queues.entry(connection_id).or_default();
+        queue.push_back(message);
+        drain(queue, recipient);
+        let queued = queue.len();
+        if queued == 0 {
+            self.queues.remove(&connection_id);
+            return DeliveryOutcome::Delivered;
+        }
+        if queued <= self.config.capacity {
+            return DeliveryOutcome::Queued;
+        }
+
+        match self.config.overflow_policy {
+            OverflowPolicy::DropOldest => {
+                let excess = queued - self.config.capacity;
+                if let Some(queue) = self.queues.get_mut(&connection_id) {
+                    queue.drain(..excess);
+                }
+                self.dropped += excess as u64;
+                warn!(
+                    "Send queue full for connection {}, dropped {} oldest messages",
+                    connection_id, excess
+                );
+                DeliveryOutcome::DroppedOldest(excess)
+            }
+            OverflowPolicy::Disconnect => {
+                self.queues.remove(&connection_id);
+                self.overflowed.insert(connection_id);
+                self.dropped += queued as u64;
+                warn!(
+                    "Send queue full for connection {}, disconnecting ({} messages discarded)",
+                    connection_id, queued
+                );
+                DeliveryOutcome::Overflowed
+            }
+        }
+    }
+
+    /// Retry queued messages for every connection
+    pub fn flush(&mut self, sessions: &HashMap<Uuid, Recipient<SessionMessage>>) {
+        self.queues.retain(|connection_id, queue| {
+            if let Some(recipient) = sessions.get(connection_id) {
+                drain(queue, recipient);
+            }
+            !queue.is_empty()
+        });
+    }
+
+    /// Connections that overflowed since the last call and must be disconnected
+    pub fn take_overflowed(&mut self) -> Vec<Uuid> {
+        self.overflowed.drain().collect()
+    }
+
+    /// Forget a connection's queue
+    pub fn remove(&mut self, connection_id: &Uuid) {
+        self.queues.remove(connection_id);
+    }
+
+    /// Messages waiting for a connection
+    pub fn queued(&self, connection_id: &Uuid) -> usize {
+        self.queues.get(connection_id).map_or(0, VecDeque::len)
+    }
+
+    /// Messages dropped because a queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+/// Move queued messages into the session's mailbox until it is full
+fn drain(queue: &mut VecDeque<SessionMessage>, recipient: &Recipient<SessionMessage>) {
+    while let Some(message) = queue.pop_front() {
+        match recipient.try_send(message) {
+            Ok(()) => {}
+            Err(SendError::Full(message)) => {
+                queue.push_front(message);
+                return;
+            }
+            Err(SendError::Closed(_)) => {
+                // The session is gone; the router will remove it
+                queue.clear();
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::messages::{MessagePriority, ServerMessage};
+    use actix::{Actor, Context, Handler};
+    use std::sync::{Arc, Mutex};
+
+    /// Session that records the sequence numbers it receives
+    struct Consumer(Arc<Mutex<Vec<u64>>>);
+
+    impl Actor for Consumer {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<SessionMessage> for Consumer {
+        type Result = ();
+
+        fn handle(&mut self, msg: SessionMessage, _ctx: &mut Self::Context) -> Self::Result {
+            if let ServerMessage::Ping { timestamp } = msg.message {
+                self.0.lock().unwrap().push(timestamp);
+            }
+        }
+    }
+
+    fn message(sequence: u64) -> SessionMessage {
+        SessionMessage {
+            message: ServerMessage::Ping { timestamp: sequence },
+            priority: MessagePriority::Normal,
+        }
+    }
+
+    /// A consumer whose mailbox is not processed until it is started, so
+    /// it stays full for as long as the producer runs
+    fn slow_consumer(mailbox: usize) -> (Context<Consumer>, Recipient<SessionMessage>) {
+        let mut ctx = Context::new();
+        ctx.set_mailbox_capacity(mailbox);
+        let recipient = ctx.address().recipient();
+        (ctx, recipient)
+    }
+
+    #[actix::test]
+    async fn test_drop_oldest_keeps_queue_bounded() {
+        let connection_id = Uuid::new_v4();
+        let (ctx, recipient) = slow_consumer(4);
+        let sessions = HashMap::from([(connection_id, recipient)]);
+        let recipient = &sessions[&connection_id];
+        let mut outbox = Outbox::new(OutboxConfig {
+            capacity: 10,
+            overflow_policy: OverflowPolicy::DropOldest,
+        });
+
+        let mut delivered = 0;
+        for sequence in 0..100 {
+            match outbox.send(connection_id, recipient, message(sequence)) {
+                DeliveryOutcome::Delivered => delivered += 1,
+                DeliveryOutcome::Queued | DeliveryOutcome::DroppedOldest(_) => {}
+                DeliveryOutcome::Overflowed => panic!("DropOldest never disconnects"),
+            }
+            assert!(outbox.queued(&connection_id) <= 10);
+        }
+        assert_eq!(outbox.queued(&connection_id), 10);
+        assert_eq!(outbox.dropped(), 100 - 10 - delivered);
+        assert!(outbox.take_overflowed().is_empty());
+
+        // Once the consumer catches up it gets the first messages and the
+        // newest ones, with the oldest queued messages dropped in between
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let _consumer = ctx.run(Consumer(received.clone()));
+        for _ in 0..10 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            outbox.flush(&sessions);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        let received = received.lock().unwrap().clone();
+        let expected: Vec<u64> = (0..delivered).chain(90..100).collect();
+        assert_eq!(received, expected);
+        assert_eq!(outbox.queued(&connection_id), 0);
+    }
+
+    #[actix::test]
+    async fn test_disconnect_policy_flags_overflowing_connection() {
+        let (slow, fast) = (Uuid::new_v4(), Uuid::new_v4());
+        let (_slow_ctx, slow_recipient) = slow_consumer(2);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let fast_recipient = Consumer::create(|ctx| {
+            ctx.set_mailbox_capacity(64);
+            Consumer(received.clone())
+        })
+        .recipient();
+        let mut outbox = Outbox::new(OutboxConfig {
+            capacity: 3,
+            overflow_policy: OverflowPolicy::Disconnect,
+        });
+
+        let outcomes: Vec<DeliveryOutcome> = (0..20)
+            .map(|sequence| {
+                outbox.send(fast, &fast_recipient, message(sequence));
+                outbox.send(slow, &slow_recipient, message(sequence))
+            })
+            .collect();
+
+        let first_overflow = outcomes
+            .iter()
+            .position(|outcome| *outcome == DeliveryOutcome::Overflowed)
+            .expect("slow connection should overflow");
+        assert!(outcomes[first_overflow..].iter().all(|o| *o == DeliveryOutcome::Overflowed));
+        assert_eq!(outbox.queued(&slow), 0);
+        assert_eq!(outbox.take_overflowed(), vec![slow]);
+        assert!(outbox.dropped() > 0);
+
+        // The fast consumer is unaffected
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        outbox.flush(&HashMap::from([(fast, fast_recipient)]));
+        assert_eq!(outbox.queued(&fast), 0);
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(*received.lock().unwrap(), (0..20).collect::<Vec<u64>>());
+    }
+}
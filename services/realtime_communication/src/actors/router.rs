@@ -12,10 +12,16 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 use super::messages::*;
+use super::outbox::{Outbox, OutboxConfig};
 use super::session::SessionActor;
 use crate::routing::messages::RoutingMessage;
 use crate::routing::router::{MessageRouter, TopicMessageRouter, RouterConfig};
 use crate::connection::ConnectionManager;
+use crate::messaging::{MessageStore, MessageStoreError, TypingStopped, TypingTracker};
+use crate::presence::{PresenceCoalescer, PresenceConfig};
+
+/// How often queued deliveries to slow sessions are retried
+const OUTBOX_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
 
 /// Router Actor - Central message distribution hub
 pub struct RouterActor {
@@ -39,6 +45,25 @@ pub struct RouterActor {
     
     /// Configuration
     config: RouterConfig,
+
+    /// Presence changes waiting for the next batched broadcast
+    presence_batch: PresenceCoalescer,
+
+    /// How often batched presence changes are broadcast; `None` broadcasts
+    /// each change immediately
+    presence_batch_window: Option<std::time::Duration>,
+
+    /// Active typing indicators, which are relayed but never persisted
+    typing: TypingTracker,
+
+    /// Recent topic messages, so they can be edited and deleted
+    message_store: MessageStore,
+
+    /// Users allowed to edit and delete anyone's messages
+    admins: HashSet<String>,
+
+    /// Bounded send queues for sessions that fall behind
+    outbox: Outbox,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +73,7 @@ struct ConnectionInfo {
     connected_at: DateTime<Utc>,
     last_activity: DateTime<Utc>,
     presence_status: PresenceStatus,
+    roles: HashSet<String>,
 }
 
 #[derive(Debug, Default)]
@@ -80,9 +106,42 @@ impl RouterActor {
             message_router,
             metrics: RouterMetrics::default(),
             config: router_config,
+            presence_batch: PresenceCoalescer::new(),
+            presence_batch_window: PresenceConfig::default().broadcast_batch_window(),
+            typing: TypingTracker::new(PresenceConfig::default().typing_timeout),
+            message_store: MessageStore::default(),
+            admins: HashSet::new(),
+            outbox: Outbox::default(),
         }
     }
 
+    /// Collect presence changes for `window` and broadcast them as one
+    /// [`ServerMessage::PresenceBatch`], or broadcast each change as it
+    /// happens when `None`. Takes effect when the actor starts.
+    pub fn with_presence_batch_window(mut self, window: Option<std::time::Duration>) -> Self {
+        self.presence_batch_window = window;
+        self
+    }
+
+    /// Expire typing indicators that are not refreshed within `timeout`
+    pub fn with_typing_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.typing = TypingTracker::new(timeout);
+        self
+    }
+
+    /// Let these users edit and delete messages sent by others
+    pub fn with_admins(mut self, admins: impl IntoIterator<Item = String>) -> Self {
+        self.admins = admins.into_iter().collect();
+        self
+    }
+
+    /// Bound each session's send queue and choose what happens when a
+    /// slow client fills it
+    pub fn with_outbox(mut self, config: OutboxConfig) -> Self {
+        self.outbox = Outbox::new(config);
+        self
+    }
+
     /// Add a new session to the router
     fn add_session(
         &mut self,
@@ -109,6 +168,7 @@ impl RouterActor {
             connected_at: Utc::now(),
             last_activity: Utc::now(),
             presence_status: PresenceStatus::Online,
+            roles: HashSet::new(),
         };
         self.connection_metadata.insert(connection_id, connection_info);
         
@@ -126,7 +186,12 @@ impl RouterActor {
 
     /// Remove a session from the router
     fn remove_session(&mut self, connection_id: &Uuid, reason: Option<&str>) {
+        for stopped in self.typing.stop_connection(*connection_id) {
+            self.broadcast_typing_stopped(stopped);
+        }
+
         // Remove session
+        self.outbox.remove(connection_id);
         if self.sessions.remove(connection_id).is_some() {
             info!(
                 "Session removed: connection_id={}, reason={:?}",
@@ -192,7 +257,7 @@ impl RouterActor {
             let mut delivered_count = 0;
             for &target_connection in target_connections {
                 if let Some(session_addr) = self.sessions.get(&target_connection) {
-                    session_addr.do_send(session_message.clone());
+                    self.outbox.send(target_connection, session_addr, session_message.clone());
                     delivered_count += 1;
                 }
             }
@@ -214,7 +279,7 @@ impl RouterActor {
                         },
                         priority: MessagePriority::Normal,
                     };
-                    sender_session.do_send(confirmation);
+                    self.outbox.send(from_connection, sender_session, confirmation);
                 }
             } else {
                 warn!("No active sessions found for user: {}", to_user);
@@ -229,7 +294,7 @@ impl RouterActor {
                         },
                         priority: MessagePriority::Normal,
                     };
-                    sender_session.do_send(error_message);
+                    self.outbox.send(from_connection, sender_session, error_message);
                 }
             }
         } else {
@@ -245,7 +310,7 @@ impl RouterActor {
                     },
                     priority: MessagePriority::Normal,
                 };
-                sender_session.do_send(error_message);
+                self.outbox.send(from_connection, sender_session, error_message);
             }
         }
     }
@@ -264,6 +329,9 @@ impl RouterActor {
         
         self.metrics.topic_messages += 1;
         self.metrics.messages_routed += 1;
+
+        let sender = from_user.clone().unwrap_or_else(|| from_connection.to_string());
+        self.message_store.record(topic, &message_id, &sender, content.clone(), timestamp);
         
         // Find topic subscribers
         if let Some(subscribers) = self.topic_subscriptions.get(topic) {
@@ -285,7 +353,7 @@ impl RouterActor {
                 // Don't send to the sender
                 if subscriber_connection != from_connection {
                     if let Some(session_addr) = self.sessions.get(&subscriber_connection) {
-                        session_addr.do_send(session_message.clone());
+                        self.outbox.send(subscriber_connection, session_addr, session_message.clone());
                         delivered_count += 1;
                     }
                 }
@@ -307,7 +375,7 @@ impl RouterActor {
                     },
                     priority: MessagePriority::Normal,
                 };
-                sender_session.do_send(ack_message);
+                self.outbox.send(from_connection, sender_session, ack_message);
             }
         } else {
             warn!("No subscribers found for topic: {}", topic);
@@ -322,7 +390,7 @@ impl RouterActor {
                     },
                     priority: MessagePriority::Normal,
                 };
-                sender_session.do_send(ack_message);
+                self.outbox.send(from_connection, sender_session, ack_message);
             }
         }
     }
@@ -357,7 +425,7 @@ impl RouterActor {
         for (&connection_id, session_addr) in &self.sessions {
             // Don't send to the sender
             if connection_id != from_connection {
-                session_addr.do_send(session_message.clone());
+                self.outbox.send(connection_id, session_addr, session_message.clone());
                 delivered_count += 1;
             }
         }
@@ -378,7 +446,7 @@ impl RouterActor {
                 },
                 priority: MessagePriority::Normal,
             };
-            sender_session.do_send(ack_message);
+            self.outbox.send(from_connection, sender_session, ack_message);
         }
     }
 
@@ -415,10 +483,20 @@ impl RouterActor {
             connection_info.last_activity = Utc::now();
         }
         
+        if self.presence_batch_window.is_some() {
+            self.presence_batch.push(connection_id, PresenceChange {
+                user_id: user_id.clone(),
+                status: status.clone(),
+                last_seen: Some(Utc::now().timestamp()),
+            });
+            debug!("Presence change queued: user_id={} status={:?}", user_id, status);
+            return;
+        }
+
         // Broadcast presence update to all users who have this user in their connections
         let presence_message = ServerMessage::PresenceUpdate {
             user_id: user_id.clone(),
-            status,
+            status: status.clone(),
             last_seen: Some(Utc::now().timestamp()),
         };
         
@@ -430,19 +508,235 @@ impl RouterActor {
         // For now, broadcast to all sessions (in production, should be more targeted)
         for (&conn_id, session_addr) in &self.sessions {
             if conn_id != connection_id {
-                session_addr.do_send(session_message.clone());
+                self.outbox.send(conn_id, session_addr, session_message.clone());
             }
         }
         
-        info!("Presence updated: user_id={} status={:?}", user_id, connection_info.presence_status);
+        info!("Presence updated: user_id={} status={:?}", user_id, status);
+    }
+
+    /// Deliver an announcement to every connection in `scope`
+    fn admin_broadcast(&mut self, msg: AdminBroadcast) -> usize {
+        let recipients: Vec<Uuid> = match &msg.scope {
+            BroadcastScope::All => self.sessions.keys().copied().collect(),
+            BroadcastScope::Role(role) => self
+                .connection_metadata
+                .values()
+                .filter(|info| info.roles.contains(role))
+                .map(|info| info.connection_id)
+                .collect(),
+            BroadcastScope::Room(room) => self
+                .topic_subscriptions
+                .get(room)
+                .map(|members| members.iter().copied().collect())
+                .unwrap_or_default(),
+        };
+
+        let session_message = SessionMessage {
+            message: ServerMessage::BroadcastReceived {
+                from: msg.from.clone(),
+                content: msg.content,
+                message_id: msg.message_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+                timestamp: Utc::now().timestamp(),
+            },
+            priority: MessagePriority::High,
+        };
+
+        let mut delivered_count = 0;
+        for connection_id in recipients {
+            if let Some(session_addr) = self.sessions.get(&connection_id) {
+                self.outbox.send(connection_id, session_addr, session_message.clone());
+                delivered_count += 1;
+            }
+        }
+
+        self.metrics.broadcast_messages += 1;
+        self.metrics.messages_routed += 1;
+        self.metrics.messages_delivered += delivered_count as u64;
+
+        info!(
+            "Admin broadcast delivered: from={} scope={:?} recipients={}",
+            msg.from, msg.scope, delivered_count
+        );
+        delivered_count
+    }
+
+    /// Edit a stored topic message and tell the room, or tell the editor why not
+    fn edit_message(
+        &mut self,
+        from_connection: Uuid,
+        editor: String,
+        message_id: &str,
+        new_content: serde_json::Value,
+    ) {
+        let is_admin = self.admins.contains(&editor);
+        let result = self
+            .message_store
+            .edit(message_id, &editor, is_admin, new_content, Utc::now())
+            .map(|message| {
+                let event = ServerMessage::MessageEdited {
+                    topic: message.room.clone(),
+                    message_id: message.message_id.clone(),
+                    edited_by: editor.clone(),
+                    content: message.content.clone().unwrap_or_default(),
+                    edited_at: message.edited_at.unwrap_or_else(Utc::now).timestamp(),
+                };
+                (message.room.clone(), event)
+            });
+        self.deliver_message_change(from_connection, result);
+    }
+
+    /// Replace a stored topic message with a tombstone and tell the room
+    fn delete_message(&mut self, from_connection: Uuid, editor: String, message_id: &str) {
+        let is_admin = self.admins.contains(&editor);
+        let result = self
+            .message_store
+            .delete(message_id, &editor, is_admin, Utc::now())
+            .map(|message| {
+                let event = ServerMessage::MessageDeleted {
+                    topic: message.room.clone(),
+                    message_id: message.message_id.clone(),
+                    deleted_by: editor.clone(),
+                    deleted_at: message.edited_at.unwrap_or_else(Utc::now).timestamp(),
+                };
+                (message.room.clone(), event)
+            });
+        self.deliver_message_change(from_connection, result);
+    }
+
+    /// Send an edit or delete to every room member, including the editor so
+    /// their other views update, or the rejection to the editor alone
+    fn deliver_message_change(
+        &mut self,
+        from_connection: Uuid,
+        result: Result<(String, ServerMessage), MessageStoreError>,
+    ) {
+        match result {
+            Ok((room, event)) => {
+                let session_message = SessionMessage {
+                    message: event,
+                    priority: MessagePriority::Normal,
+                };
+                let members = self.topic_subscriptions.get(&room).into_iter().flatten();
+                let mut delivered_count = 0;
+                for connection_id in members {
+                    if let Some(session_addr) = self.sessions.get(connection_id) {
+                        self.outbox.send(*connection_id, session_addr, session_message.clone());
+                        delivered_count += 1;
+                    }
+                }
+                self.metrics.messages_delivered += delivered_count;
+            }
+            Err(e) => {
+                warn!("Rejected message change from {}: {}", from_connection, e);
+                if let Some(sender_session) = self.sessions.get(&from_connection) {
+                    self.outbox.send(from_connection, sender_session, SessionMessage {
+                        message: ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                        },
+                        priority: MessagePriority::High,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Relay a typing signal to the other members of the room
+    fn update_typing(&mut self, user_id: String, connection_id: Uuid, room: String, is_typing: bool) {
+        if is_typing {
+            if !self.typing.start(&room, connection_id, &user_id, std::time::Instant::now()) {
+                // Only refreshes the timeout
+                return;
+            }
+            self.broadcast_typing(&room, connection_id, user_id, true);
+        } else if let Some(stopped) = self.typing.stop(&room, connection_id) {
+            self.broadcast_typing_stopped(stopped);
+        }
+    }
+
+    /// Stop the typing indicators that timed out
+    fn expire_typing(&mut self) {
+        for stopped in self.typing.expire(std::time::Instant::now()) {
+            debug!("Typing indicator expired: user_id={} room={}", stopped.user_id, stopped.room);
+            self.broadcast_typing_stopped(stopped);
+        }
+    }
+
+    fn broadcast_typing_stopped(&mut self, stopped: TypingStopped) {
+        self.broadcast_typing(&stopped.room, stopped.connection_id, stopped.user_id, false);
+    }
+
+    /// Send a typing indicator to every room member except the typist's connection
+    fn broadcast_typing(&mut self, room: &str, from_connection: Uuid, user_id: String, is_typing: bool) {
+        let Some(members) = self.topic_subscriptions.get(room) else {
+            return;
+        };
+        let session_message = SessionMessage {
+            message: ServerMessage::TypingIndicator {
+                user_id,
+                conversation_id: room.to_string(),
+                is_typing,
+            },
+            priority: MessagePriority::Low,
+        };
+        for connection_id in members.iter().filter(|&&member| member != from_connection) {
+            if let Some(session_addr) = self.sessions.get(connection_id) {
+                self.outbox.send(*connection_id, session_addr, session_message.clone());
+            }
+        }
+    }
+
+    /// Retry queued deliveries and disconnect sessions whose queue overflowed
+    fn pump_outbox(&mut self) {
+        self.outbox.flush(&self.sessions);
+        for connection_id in self.outbox.take_overflowed() {
+            if let Some(session_addr) = self.sessions.get(&connection_id) {
+                // Bypasses the full queue; the session closes when it sees this
+                session_addr.do_send(SessionMessage {
+                    message: ServerMessage::Disconnected {
+                        reason: "Too many undelivered messages".to_string(),
+                    },
+                    priority: MessagePriority::Critical,
+                });
+            }
+            self.remove_session(&connection_id, Some("Send queue overflow"));
+        }
+    }
+
+    /// Broadcast queued presence changes, one batch per session
+    fn flush_presence_batch(&mut self) {
+        if self.presence_batch.is_empty() {
+            return;
+        }
+        let changes = self.presence_batch.len();
+        let batches = self.presence_batch.flush(self.sessions.keys().copied());
+        for (connection_id, message) in batches {
+            if let Some(session_addr) = self.sessions.get(&connection_id) {
+                self.outbox.send(connection_id, session_addr, SessionMessage {
+                    message,
+                    priority: MessagePriority::Normal,
+                });
+            }
+        }
+        info!("Presence batch broadcast: changes={} sessions={}", changes, self.sessions.len());
     }
 }
 
 impl Actor for RouterActor {
     type Context = Context<Self>;
 
-    fn started(&mut self, _ctx: &mut Self::Context) {
+    fn started(&mut self, ctx: &mut Self::Context) {
         info!("Router actor started");
+
+        if let Some(window) = self.presence_batch_window {
+            ctx.run_interval(window, |act, _ctx| act.flush_presence_batch());
+        }
+
+        ctx.run_interval(OUTBOX_FLUSH_INTERVAL, |act, _ctx| act.pump_outbox());
+
+        // Sweep often enough that indicators end soon after timing out
+        ctx.run_interval(self.typing.timeout() / 4, |act, _ctx| act.expire_typing());
     }
 
     fn stopped(&mut self, _ctx: &mut Self::Context) {
@@ -496,10 +790,52 @@ impl Handler<RouteMessage> for RouterActor {
                     self.update_presence(user_id, from_connection, status);
                 }
             }
+            ClientMessage::EditMessage { message_id, new_content } => {
+                let editor = from_user.unwrap_or_else(|| from_connection.to_string());
+                self.edit_message(from_connection, editor, &message_id, new_content);
+            }
+            ClientMessage::DeleteMessage { message_id } => {
+                let editor = from_user.unwrap_or_else(|| from_connection.to_string());
+                self.delete_message(from_connection, editor, &message_id);
+            }
+            ClientMessage::TypingStart { conversation_id } => {
+                let user_id = from_user.unwrap_or_else(|| from_connection.to_string());
+                self.update_typing(user_id, from_connection, conversation_id, true);
+            }
+            ClientMessage::TypingStop { conversation_id } => {
+                let user_id = from_user.unwrap_or_else(|| from_connection.to_string());
+                self.update_typing(user_id, from_connection, conversation_id, false);
+            }
             _ => {
                 debug!("Unhandled message type in router: {:?}", msg.message);
             }
         }
+
+        self.pump_outbox();
+    }
+}
+
+/// Connection roles handler
+impl Handler<SetConnectionRoles> for RouterActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetConnectionRoles, _ctx: &mut Self::Context) -> Self::Result {
+        if let Some(connection_info) = self.connection_metadata.get_mut(&msg.connection_id) {
+            connection_info.roles = msg.roles;
+        } else {
+            warn!("Roles set for unknown connection: {}", msg.connection_id);
+        }
+    }
+}
+
+/// Admin broadcast handler
+impl Handler<AdminBroadcast> for RouterActor {
+    type Result = usize;
+
+    fn handle(&mut self, msg: AdminBroadcast, _ctx: &mut Self::Context) -> Self::Result {
+        let delivered = self.admin_broadcast(msg);
+        self.pump_outbox();
+        delivered
     }
 }
 
@@ -577,6 +913,7 @@ impl Handler<GetSystemStats> for RouterActor {
             messages_routed: self.metrics.messages_routed,
             messages_delivered: self.metrics.messages_delivered,
             messages_failed: self.metrics.messages_failed,
+            messages_dropped: self.outbox.dropped(),
             topics_active: self.topic_subscriptions.len(),
             uptime_seconds: 0, // Would need to track start time
         }
@@ -514,6 +514,13 @@ impl Handler<SessionMessage> for SessionActor {
     type Result = ();
 
     fn handle(&mut self, msg: SessionMessage, ctx: &mut Self::Context) -> Self::Result {
+        // The router has already dropped this session (e.g. its send queue
+        // overflowed); tell the client and close
+        if let ServerMessage::Disconnected { reason } = &msg.message {
+            self.disconnect(ctx, Some(reason.clone()));
+            return;
+        }
+
         // If connected, send immediately; otherwise buffer
         match self.state {
             SessionState::Connected | SessionState::Authenticated(_) => {
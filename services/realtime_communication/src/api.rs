@@ -1,7 +1,12 @@
 //! WebSocket API endpoints and server management
 
-use actix_web::{web, HttpResponse, App};
+use actix::Addr;
+use actix_web::{web, HttpRequest, HttpResponse, App};
+use serde::Deserialize;
 
+use crate::actors::messages::{AdminBroadcast, BroadcastScope};
+use crate::actors::router::RouterActor;
+use crate::auth::{AuthError, AuthResult, SystemRole, TokenExtractor};
 use crate::server::{WebSocketServer, ServerConfig, websocket_handler, health_handler, metrics_handler};
 
 /// Start the WebSocket server with the given configuration
@@ -13,6 +18,7 @@ pub async fn start_server(config: ServerConfig) -> std::io::Result<()> {
 /// Create the Actix web application with WebSocket routes
 pub fn create_app(
     server_state: web::Data<crate::server::ServerState>,
+    router: web::Data<Addr<RouterActor>>,
 ) -> App<
     impl actix_web::dev::ServiceFactory<
         actix_web::dev::ServiceRequest,
@@ -24,10 +30,12 @@ pub fn create_app(
 > {
     App::new()
         .app_data(server_state)
+        .app_data(router)
         .route("/ws", web::get().to(websocket_handler))
         .route("/health", web::get().to(health_handler))
         .route("/metrics", web::get().to(metrics_handler))
         .route("/info", web::get().to(server_info_handler))
+        .route("/admin/broadcast", web::post().to(admin_broadcast_handler))
 }
 
 /// Server information endpoint
@@ -44,6 +52,7 @@ pub async fn server_info_handler(
                 "host": state.config.host,
                 "port": state.config.port,
                 "max_connections": state.config.max_connections,
+                "max_connections_per_user": state.config.max_connections_per_user,
                 "heartbeat_interval_secs": state.config.heartbeat_interval.as_secs(),
                 "client_timeout_secs": state.config.client_timeout.as_secs(),
                 "max_frame_size": state.config.max_frame_size
@@ -62,11 +71,61 @@ pub async fn server_info_handler(
     }))
 }
 
+/// Body of an admin broadcast request
+#[derive(Debug, Deserialize)]
+pub struct BroadcastRequest {
+    pub scope: BroadcastScope,
+    pub content: serde_json::Value,
+    pub message_id: Option<String>,
+}
+
+/// `POST /admin/broadcast`: push an announcement to everyone, a role or a room.
+///
+/// Requires a bearer token with the `admin` role. Roles are matched against
+/// the tokens connections authenticated with.
+pub async fn admin_broadcast_handler(
+    req: HttpRequest,
+    body: web::Json<BroadcastRequest>,
+    state: web::Data<crate::server::ServerState>,
+    router: web::Data<Addr<RouterActor>>,
+) -> HttpResponse {
+    let authenticated = TokenExtractor::extract_token(req.headers(), None)
+        .and_then(|token| match state.jwt_service.validate_token(&token) {
+            AuthResult::Authenticated(user) => Ok(user),
+            AuthResult::RequiresRefresh(_) => Err(AuthError::TokenRefreshRequired),
+            AuthResult::Unauthenticated(e) => Err(e),
+        });
+    let admin = match authenticated {
+        Ok(user) if user.has_role(SystemRole::Admin.as_str()) => user,
+        Ok(user) => {
+            return HttpResponse::Forbidden().json(serde_json::json!({
+                "error": format!("User {} is not an admin", user.user_id)
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({"error": e.to_string()}));
+        }
+    };
+
+    let body = body.into_inner();
+    let broadcast = AdminBroadcast {
+        scope: body.scope,
+        from: admin.user_id,
+        content: body.content,
+        message_id: body.message_id,
+    };
+    match router.send(broadcast).await {
+        Ok(delivered) => HttpResponse::Ok().json(serde_json::json!({"delivered": delivered})),
+        Err(e) => HttpResponse::ServiceUnavailable().json(serde_json::json!({"error": e.to_string()})),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use actix_web::{test, App};
     use crate::server::{ServerState, ServerMetrics};
+    use crate::auth::JwtService;
     use crate::connection::ConnectionManager;
     use std::sync::Arc;
 
@@ -80,6 +139,7 @@ mod tests {
             connection_manager,
             config: config.clone(),
             metrics,
+            jwt_service: Arc::new(JwtService::new(b"test_secret").unwrap()),
         };
 
         let app = test::init_service(
@@ -95,4 +155,103 @@ mod tests {
         let resp = test::call_service(&app, req).await;
         assert!(resp.status().is_success());
     }
-}
\ No newline at end of file
+
+    #[actix_web::test]
+    async fn test_admin_broadcast_requires_admin_role() {
+        use actix::Actor;
+
+        let config = ServerConfig::default();
+        let jwt_service = Arc::new(JwtService::new(b"test_secret").unwrap());
+        let state = ServerState {
+            connection_manager: Arc::new(ConnectionManager::new(config.max_connections)),
+            config,
+            metrics: Arc::new(ServerMetrics::default()),
+            jwt_service: jwt_service.clone(),
+        };
+        let router = RouterActor::new(state.connection_manager.clone()).start();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .app_data(web::Data::new(router))
+                .route("/admin/broadcast", web::post().to(admin_broadcast_handler))
+        ).await;
+        let broadcast = |token: Option<&str>| {
+            let req = test::TestRequest::post()
+                .uri("/admin/broadcast")
+                .set_json(serde_json::json!({
+                    "scope": {"type": "Role", "value": "agent"},
+                    "content": {"text": "Maintenance at 02:00 UTC"}
+                }));
+            match token {
+                Some(token) => req.insert_header(("Authorization", format!("Bearer {}", token))),
+                None => req,
+            }
+            .to_request()
+        };
+
+        let resp = test::call_service(&app, broadcast(None)).await;
+        assert_eq!(resp.status(), 401);
+
+        let user_token = jwt_service
+            .generate_token("bob".to_string(), vec![SystemRole::User], None, None)
+            .unwrap();
+        let resp = test::call_service(&app, broadcast(Some(&user_token))).await;
+        assert_eq!(resp.status(), 403);
+
+        let admin_token = jwt_service
+            .generate_token("root".to_string(), vec![SystemRole::Admin], None, None)
+            .unwrap();
+        let resp = test::call_service(&app, broadcast(Some(&admin_token))).await;
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["delivered"], 0);
+    }
+
+    #[actix_web::test]
+    async fn test_admin_broadcast_reaches_connections_with_the_role() {
+        use actix::Actor;
+
+        let config = ServerConfig::default();
+        let jwt_service = Arc::new(JwtService::new(b"test_secret").unwrap());
+        let state = ServerState {
+            connection_manager: Arc::new(ConnectionManager::new(config.max_connections)),
+            config,
+            metrics: Arc::new(ServerMetrics::default()),
+            jwt_service: jwt_service.clone(),
+        };
+        let router = RouterActor::new(state.connection_manager.clone()).start();
+        let app = test::init_service(create_app(web::Data::new(state), web::Data::new(router))).await;
+
+        for (user, role) in [("agent_1", SystemRole::Agent), ("bob", SystemRole::User)] {
+            let token = jwt_service
+                .generate_token(user.to_string(), vec![role], None, None)
+                .unwrap();
+            let req = test::TestRequest::get()
+                .uri(&format!("/ws?token={}", token))
+                .insert_header(("upgrade", "websocket"))
+                .insert_header(("connection", "upgrade"))
+                .insert_header(("sec-websocket-version", "13"))
+                .insert_header(("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 101, "{}", user);
+        }
+
+        let admin_token = jwt_service
+            .generate_token("root".to_string(), vec![SystemRole::Admin], None, None)
+            .unwrap();
+        let req = test::TestRequest::post()
+            .uri("/admin/broadcast")
+            .insert_header(("Authorization", format!("Bearer {}", admin_token)))
+            .set_json(serde_json::json!({
+                "scope": {"type": "Role", "value": "agent"},
+                "content": {"text": "New tasks available"}
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["delivered"], 1);
+    }
+}
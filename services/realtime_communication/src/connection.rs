@@ -101,6 +101,7 @@ pub struct ConnectionManager {
     connections: Arc<DashMap<Uuid, ConnectionInfo>>,
     user_connections: Arc<DashMap<String, Vec<Uuid>>>,
     max_connections: usize,
+    max_connections_per_user: Option<usize>,
     connection_count: Arc<RwLock<usize>>,
 }
 
@@ -110,10 +111,18 @@ impl ConnectionManager {
             connections: Arc::new(DashMap::new()),
             user_connections: Arc::new(DashMap::new()),
             max_connections,
+            max_connections_per_user: None,
             connection_count: Arc::new(RwLock::new(0)),
         }
     }
 
+    /// Limit how many connections one user may hold at once. Anonymous
+    /// connections only count towards the global limit.
+    pub fn with_max_connections_per_user(mut self, limit: Option<usize>) -> Self {
+        self.max_connections_per_user = limit;
+        self
+    }
+
     /// Add a new connection
     pub async fn add_connection(&self, mut connection_info: ConnectionInfo) -> Result<(), ConnectionError> {
         let current_count = *self.connection_count.read().await;
@@ -123,19 +132,29 @@ impl ConnectionManager {
 
         connection_info.mark_connected();
         let connection_id = connection_info.id;
-        let user_id = connection_info.user_id.clone();
+
+        // Add to user connections map if user_id exists, holding the entry
+        // so concurrent connections for the same user can't overshoot the limit
+        if let Some(user_id) = &connection_info.user_id {
+            let mut user_connections = self.user_connections
+                .entry(user_id.clone())
+                .or_insert_with(Vec::new);
+            if let Some(limit) = self.max_connections_per_user {
+                if user_connections.len() >= limit {
+                    drop(user_connections);
+                    self.user_connections.remove_if(user_id, |_, ids| ids.is_empty());
+                    return Err(ConnectionError::UserLimitReached {
+                        user_id: user_id.clone(),
+                        limit,
+                    });
+                }
+            }
+            user_connections.push(connection_id);
+        }
 
         // Add to connections map
         self.connections.insert(connection_id, connection_info);
 
-        // Add to user connections map if user_id exists
-        if let Some(user_id) = user_id {
-            self.user_connections
-                .entry(user_id)
-                .or_insert_with(Vec::new)
-                .push(connection_id);
-        }
-
         // Update connection count
         let mut count = self.connection_count.write().await;
         *count += 1;
@@ -289,6 +308,8 @@ pub struct ConnectionStats {
 pub enum ConnectionError {
     #[error("Connection capacity reached")]
     CapacityReached,
+    #[error("User {user_id} already has the maximum of {limit} connections")]
+    UserLimitReached { user_id: String, limit: usize },
     #[error("Connection not found")]
     NotFound,
     #[error("Invalid connection state")]
@@ -297,6 +318,20 @@ pub enum ConnectionError {
     Generic(String),
 }
 
+impl ConnectionError {
+    /// WebSocket close code to send when a connection is refused for this error
+    pub fn close_code(&self) -> u16 {
+        match self {
+            // Try Again Later
+            ConnectionError::CapacityReached => 1013,
+            // Policy Violation
+            ConnectionError::UserLimitReached { .. } => 1008,
+            // Internal Error
+            _ => 1011,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -369,4 +404,36 @@ mod tests {
         let subscribers = manager.get_topic_subscribers("test_topic").await;
         assert_eq!(subscribers.len(), 0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_per_user_connection_limit() {
+        let manager = ConnectionManager::new(100).with_max_connections_per_user(Some(3));
+        let connect = |user_id: &str| {
+            let mut info = ConnectionInfo::new(Uuid::new_v4());
+            info.user_id = Some(user_id.to_string());
+            info
+        };
+
+        for _ in 0..3 {
+            manager.add_connection(connect("greedy")).await.unwrap();
+        }
+        for _ in 0..2 {
+            match manager.add_connection(connect("greedy")).await {
+                Err(e @ ConnectionError::UserLimitReached { .. }) => assert_eq!(e.close_code(), 1008),
+                other => panic!("Expected UserLimitReached, got {:?}", other),
+            }
+        }
+        assert_eq!(manager.get_user_connections("greedy").await.len(), 3);
+        assert_eq!(*manager.get_connection_count().await.read().await, 3);
+
+        // Other users and anonymous connections are unaffected
+        manager.add_connection(connect("polite")).await.unwrap();
+        manager.add_connection(ConnectionInfo::new(Uuid::new_v4())).await.unwrap();
+        assert_eq!(manager.get_user_connections("polite").await.len(), 1);
+
+        // Closing a connection frees a slot
+        let first = manager.get_user_connections("greedy").await[0];
+        manager.remove_connection(&first).await;
+        manager.add_connection(connect("greedy")).await.unwrap();
+    }
+}
@@ -1,6 +1,10 @@
 //! Message routing and broadcasting
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,4 +37,330 @@ pub struct UserMessage {
     #[serde(flatten)]
     pub message_type: MessageType,
     pub timestamp: chrono::DateTime<chrono::Utc>,
-}
\ No newline at end of file
+}
+
+/// Who is typing in which room.
+///
+/// Typing signals are ephemeral: they are only held here until the typist
+/// stops, disconnects or stays silent for longer than the timeout, and are
+/// never persisted with the message history. Each connection types
+/// independently, so a user typing on two devices counts twice.
+#[derive(Debug)]
+pub struct TypingTracker {
+    timeout: Duration,
+    /// (room, connection) -> (user, when the signal expires)
+    active: HashMap<(String, Uuid), (String, Instant)>,
+}
+
+/// A typist whose signal has ended
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypingStopped {
+    pub room: String,
+    pub connection_id: Uuid,
+    pub user_id: String,
+}
+
+impl TypingTracker {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            active: HashMap::new(),
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Record that `user_id` is typing in `room` from `connection_id`,
+    /// extending the signal if it is already active. Returns whether the
+    /// user just started typing.
+    pub fn start(&mut self, room: &str, connection_id: Uuid, user_id: &str, now: Instant) -> bool {
+        self.active
+            .insert((room.to_string(), connection_id), (user_id.to_string(), now + self.timeout))
+            .is_none()
+    }
+
+    /// Record that the connection stopped typing in `room`, returning the
+    /// typist if it was typing
+    pub fn stop(&mut self, room: &str, connection_id: Uuid) -> Option<TypingStopped> {
+        self.active
+            .remove(&(room.to_string(), connection_id))
+            .map(|(user_id, _)| TypingStopped {
+                room: room.to_string(),
+                connection_id,
+                user_id,
+            })
+    }
+
+    /// Stop every signal from a connection, e.g. when it disconnects
+    pub fn stop_connection(&mut self, connection_id: Uuid) -> Vec<TypingStopped> {
+        self.remove_where(|(_, connection), _| *connection == connection_id)
+    }
+
+    /// Stop the signals that have not been refreshed within the timeout
+    pub fn expire(&mut self, now: Instant) -> Vec<TypingStopped> {
+        self.remove_where(|_, (_, expires_at)| *expires_at <= now)
+    }
+
+    /// Users typing in `room`
+    pub fn typing_in(&self, room: &str) -> Vec<&str> {
+        self.active
+            .iter()
+            .filter(|((typing_room, _), _)| typing_room == room)
+            .map(|(_, (user_id, _))| user_id.as_str())
+            .collect()
+    }
+
+    fn remove_where(
+        &mut self,
+        predicate: impl Fn(&(String, Uuid), &(String, Instant)) -> bool,
+    ) -> Vec<TypingStopped> {
+        let keys: Vec<(String, Uuid)> = self
+            .active
+            .iter()
+            .filter(|(key, value)| predicate(key, value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.into_iter()
+            .filter_map(|(room, connection_id)| self.stop(&room, connection_id))
+            .collect()
+    }
+}
+
+/// A room message as kept in a [`MessageStore`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredMessage {
+    pub message_id: String,
+    pub room: String,
+    pub sender: String,
+    /// `None` once the message is deleted, leaving a tombstone in its place
+    pub content: Option<serde_json::Value>,
+    pub timestamp: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl StoredMessage {
+    pub fn is_deleted(&self) -> bool {
+        self.content.is_none()
+    }
+}
+
+/// Why a message could not be edited or deleted
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageStoreError {
+    NotFound(String),
+    NotSender(String),
+    Deleted(String),
+}
+
+impl MessageStoreError {
+    /// Error code sent to the client in a `ServerMessage::Error`
+    pub fn code(&self) -> u32 {
+        match self {
+            MessageStoreError::NotFound(_) => 404,
+            MessageStoreError::NotSender(_) => 403,
+            MessageStoreError::Deleted(_) => 410,
+        }
+    }
+}
+
+impl fmt::Display for MessageStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageStoreError::NotFound(id) => write!(f, "Message '{}' not found", id),
+            MessageStoreError::NotSender(id) => {
+                write!(f, "Only the sender or an admin may change message '{}'", id)
+            }
+            MessageStoreError::Deleted(id) => write!(f, "Message '{}' has been deleted", id),
+        }
+    }
+}
+
+impl std::error::Error for MessageStoreError {}
+
+/// Recent messages per room, in the order they were sent.
+///
+/// Edits change a message in place and deletes leave a tombstone, so the
+/// history keeps its order and length. Each room holds at most
+/// `max_per_room` messages; older ones are dropped.
+#[derive(Debug)]
+pub struct MessageStore {
+    max_per_room: usize,
+    rooms: HashMap<String, VecDeque<StoredMessage>>,
+    /// message_id -> room
+    index: HashMap<String, String>,
+}
+
+impl Default for MessageStore {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl MessageStore {
+    pub fn new(max_per_room: usize) -> Self {
+        Self {
+            max_per_room,
+            rooms: HashMap::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Append a message to the room's history
+    pub fn record(
+        &mut self,
+        room: &str,
+        message_id: &str,
+        sender: &str,
+        content: serde_json::Value,
+        timestamp: DateTime<Utc>,
+    ) {
+        let history = self.rooms.entry(room.to_string()).or_default();
+        history.push_back(StoredMessage {
+            message_id: message_id.to_string(),
+            room: room.to_string(),
+            sender: sender.to_string(),
+            content: Some(content),
+            timestamp,
+            edited_at: None,
+        });
+        self.index.insert(message_id.to_string(), room.to_string());
+
+        while history.len() > self.max_per_room {
+            if let Some(dropped) = history.pop_front() {
+                self.index.remove(&dropped.message_id);
+            }
+        }
+    }
+
+    pub fn get(&self, message_id: &str) -> Option<&StoredMessage> {
+        let room = self.index.get(message_id)?;
+        self.rooms.get(room)?.iter().find(|m| m.message_id == message_id)
+    }
+
+    /// Messages in `room`, oldest first, including tombstones
+    pub fn history(&self, room: &str) -> impl Iterator<Item = &StoredMessage> {
+        self.rooms.get(room).into_iter().flatten()
+    }
+
+    /// Replace a message's content on behalf of `editor`
+    pub fn edit(
+        &mut self,
+        message_id: &str,
+        editor: &str,
+        is_admin: bool,
+        new_content: serde_json::Value,
+        now: DateTime<Utc>,
+    ) -> Result<&StoredMessage, MessageStoreError> {
+        let message = self.find_changeable(message_id, editor, is_admin)?;
+        message.content = Some(new_content);
+        message.edited_at = Some(now);
+        Ok(message)
+    }
+
+    /// Replace a message with a tombstone on behalf of `editor`
+    pub fn delete(
+        &mut self,
+        message_id: &str,
+        editor: &str,
+        is_admin: bool,
+        now: DateTime<Utc>,
+    ) -> Result<&StoredMessage, MessageStoreError> {
+        let message = self.find_changeable(message_id, editor, is_admin)?;
+        message.content = None;
+        message.edited_at = Some(now);
+        Ok(message)
+    }
+
+    fn find_changeable(
+        &mut self,
+        message_id: &str,
+        editor: &str,
+        is_admin: bool,
+    ) -> Result<&mut StoredMessage, MessageStoreError> {
+        let not_found = || MessageStoreError::NotFound(message_id.to_string());
+        let room = self.index.get(message_id).ok_or_else(not_found)?;
+        let message = self
+            .rooms
+            .get_mut(room)
+            .and_then(|history| history.iter_mut().find(|m| m.message_id == message_id))
+            .ok_or_else(not_found)?;
+
+        if message.sender != editor && !is_admin {
+            return Err(MessageStoreError::NotSender(message_id.to_string()));
+        }
+        if message.is_deleted() {
+            return Err(MessageStoreError::Deleted(message_id.to_string()));
+        }
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typing_signals_expire_unless_refreshed() {
+        let mut tracker = TypingTracker::new(Duration::from_secs(10));
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = Instant::now();
+
+        assert!(tracker.start("room-1", alice, "alice", start));
+        assert!(tracker.start("room-1", bob, "bob", start));
+        // Refreshing an active signal is not a new start
+        assert!(!tracker.start("room-1", alice, "alice", start + Duration::from_secs(8)));
+
+        let expired = tracker.expire(start + Duration::from_secs(10));
+        assert_eq!(
+            expired,
+            [TypingStopped { room: "room-1".to_string(), connection_id: bob, user_id: "bob".to_string() }]
+        );
+        assert_eq!(tracker.typing_in("room-1"), ["alice"]);
+
+        assert_eq!(tracker.stop("room-1", alice).map(|s| s.user_id), Some("alice".to_string()));
+        assert!(tracker.stop("room-1", alice).is_none());
+        assert!(tracker.typing_in("room-1").is_empty());
+    }
+
+    #[test]
+    fn test_disconnect_stops_all_rooms() {
+        let mut tracker = TypingTracker::new(Duration::from_secs(10));
+        let alice = Uuid::new_v4();
+        tracker.start("room-1", alice, "alice", Instant::now());
+        tracker.start("room-2", alice, "alice", Instant::now());
+
+        let mut rooms: Vec<String> = tracker.stop_connection(alice).into_iter().map(|s| s.room).collect();
+        rooms.sort();
+        assert_eq!(rooms, ["room-1", "room-2"]);
+    }
+
+    #[test]
+    fn test_deleted_messages_leave_tombstones_in_order() {
+        let mut store = MessageStore::new(2);
+        let now = Utc::now();
+        store.record("room-1", "m1", "alice", serde_json::json!("first"), now);
+        store.record("room-1", "m2", "bob", serde_json::json!("second"), now);
+        store.record("room-1", "m3", "alice", serde_json::json!("third"), now);
+
+        // The oldest message was dropped to stay within capacity
+        assert!(store.get("m1").is_none());
+
+        assert_eq!(
+            store.delete("m2", "alice", false, now),
+            Err(MessageStoreError::NotSender("m2".to_string()))
+        );
+        assert!(store.delete("m2", "alice", true, now).unwrap().is_deleted());
+        assert_eq!(
+            store.edit("m2", "bob", false, serde_json::json!("again"), now),
+            Err(MessageStoreError::Deleted("m2".to_string()))
+        );
+
+        let history: Vec<(&str, bool)> = store
+            .history("room-1")
+            .map(|m| (m.message_id.as_str(), m.is_deleted()))
+            .collect();
+        assert_eq!(history, [("m2", true), ("m3", false)]);
+    }
+}
@@ -113,6 +113,9 @@ pub struct PresenceConfig {
     pub enable_auto_away: bool,
     pub enable_redis_sync: bool,
     pub batch_updates: bool,
+    /// How long presence changes are collected before being broadcast as
+    /// one batch, when `batch_updates` is enabled
+    pub batch_window: Duration,
 }
 
 impl Default for PresenceConfig {
@@ -127,10 +130,72 @@ impl Default for PresenceConfig {
             enable_auto_away: true,
             enable_redis_sync: false,
             batch_updates: true,
+            batch_window: Duration::from_millis(250),
         }
     }
 }
 
+impl PresenceConfig {
+    /// The window presence broadcasts are batched over, if batching is enabled
+    pub fn broadcast_batch_window(&self) -> Option<Duration> {
+        self.batch_updates.then_some(self.batch_window)
+    }
+}
+
+/// Coalesces presence changes into one [`ServerMessage::PresenceBatch`] per
+/// recipient, so a burst of changes costs each client a single message.
+///
+/// A later change for a user replaces any queued change for the same user.
+#[derive(Debug, Default)]
+pub struct PresenceCoalescer {
+    /// Queued changes with the connection each was made from
+    pending: Vec<(Uuid, PresenceChange)>,
+}
+
+impl PresenceCoalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a change made from the `source` connection
+    pub fn push(&mut self, source: Uuid, change: PresenceChange) {
+        match self.pending.iter_mut().find(|(_, queued)| queued.user_id == change.user_id) {
+            Some(queued) => *queued = (source, change),
+            None => self.pending.push((source, change)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Take the queued changes as one batch for each recipient, leaving out
+    /// changes made from the recipient's own connection. Recipients with
+    /// nothing to receive get no message.
+    pub fn flush(&mut self, recipients: impl IntoIterator<Item = Uuid>) -> Vec<(Uuid, ServerMessage)> {
+        if self.pending.is_empty() {
+            return Vec::new();
+        }
+        let pending = std::mem::take(&mut self.pending);
+
+        recipients
+            .into_iter()
+            .filter_map(|recipient| {
+                let updates: Vec<PresenceChange> = pending
+                    .iter()
+                    .filter(|(source, _)| *source != recipient)
+                    .map(|(_, change)| change.clone())
+                    .collect();
+                (!updates.is_empty()).then_some((recipient, ServerMessage::PresenceBatch { updates }))
+            })
+            .collect()
+    }
+}
+
 impl PresenceTrackingActor {
     pub fn new(
         config: PresenceConfig,
@@ -668,6 +733,46 @@ mod tests {
         assert!(config.enable_auto_away);
         assert!(!config.enable_redis_sync);
         assert!(config.batch_updates);
+        assert_eq!(config.broadcast_batch_window(), Some(Duration::from_millis(250)));
+    }
+
+    fn change(user_id: &str, status: PresenceStatus) -> PresenceChange {
+        PresenceChange {
+            user_id: user_id.to_string(),
+            status,
+            last_seen: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_coalescer_keeps_latest_change_per_user() {
+        let (alice, bob, observer) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut coalescer = PresenceCoalescer::new();
+        coalescer.push(alice, change("alice", PresenceStatus::Online));
+        coalescer.push(bob, change("bob", PresenceStatus::Online));
+        coalescer.push(alice, change("alice", PresenceStatus::Busy));
+        assert_eq!(coalescer.len(), 2);
+
+        let batches = coalescer.flush([alice, observer]);
+        assert!(coalescer.is_empty());
+        assert_eq!(batches.len(), 2);
+        match &batches[0] {
+            // Alice's own change is not echoed back to her connection
+            (connection, ServerMessage::PresenceBatch { updates }) => {
+                assert_eq!(*connection, alice);
+                assert_eq!(updates, &[change("bob", PresenceStatus::Online)]);
+            }
+            other => panic!("Expected PresenceBatch, got {:?}", other),
+        }
+        match &batches[1] {
+            (_, ServerMessage::PresenceBatch { updates }) => assert_eq!(
+                updates,
+                &[change("alice", PresenceStatus::Busy), change("bob", PresenceStatus::Online)]
+            ),
+            other => panic!("Expected PresenceBatch, got {:?}", other),
+        }
+
+        assert!(coalescer.flush([observer]).is_empty());
     }
 
     #[test]
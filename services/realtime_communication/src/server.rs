@@ -3,26 +3,35 @@
 //! High-performance WebSocket server built on Actix-ws with support for
 //! 10,000+ concurrent connections, heartbeat management, and graceful shutdown.
 
-use actix_web::{web, App, HttpServer, HttpRequest, HttpResponse};
+use actix::{Actor, Addr};
+use actix_web::{web, HttpServer, HttpRequest, HttpResponse};
 use actix_ws;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 use tracing::{info, warn, error};
 use std::time::{Duration, Instant};
 
-use crate::connection::ConnectionManager;
+use crate::auth::{AuthError, AuthResult, JwtService, TokenExtractor, UserContext};
+use crate::connection::{ConnectionInfo, ConnectionManager};
 use crate::actor::WebSocketActor;
+use crate::actors::messages::{Connect, Disconnect, SessionMessage, SetConnectionRoles};
+use crate::actors::router::RouterActor;
 
 /// WebSocket server configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub max_connections: usize,
+    /// Most connections one user may hold at once; `None` for no limit
+    pub max_connections_per_user: Option<usize>,
     pub heartbeat_interval: Duration,
     pub client_timeout: Duration,
     pub max_frame_size: usize,
+    /// Secret used to validate the JWTs clients connect with
+    pub jwt_secret: String,
 }
 
 impl Default for ServerConfig {
@@ -31,19 +40,37 @@ impl Default for ServerConfig {
             host: "0.0.0.0".to_string(),
             port: 8081,
             max_connections: 10_000,
+            max_connections_per_user: Some(10),
             heartbeat_interval: Duration::from_secs(30),
             client_timeout: Duration::from_secs(60),
             max_frame_size: 64 * 1024, // 64KB
+            jwt_secret: std::env::var("JWT_SECRET").unwrap_or_default(),
         }
     }
 }
 
+impl std::fmt::Debug for ServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("max_connections", &self.max_connections)
+            .field("max_connections_per_user", &self.max_connections_per_user)
+            .field("heartbeat_interval", &self.heartbeat_interval)
+            .field("client_timeout", &self.client_timeout)
+            .field("max_frame_size", &self.max_frame_size)
+            .field("jwt_secret", &"<redacted>")
+            .finish()
+    }
+}
+
 /// WebSocket server state
 #[derive(Clone)]
 pub struct ServerState {
     pub connection_manager: Arc<ConnectionManager>,
     pub config: ServerConfig,
     pub metrics: Arc<ServerMetrics>,
+    pub jwt_service: Arc<JwtService>,
 }
 
 /// Server metrics for monitoring
@@ -128,13 +155,20 @@ pub struct WebSocketServer {
 impl WebSocketServer {
     /// Create a new WebSocket server
     pub fn new(config: ServerConfig) -> Self {
-        let connection_manager = Arc::new(ConnectionManager::new(config.max_connections));
+        let connection_manager = Arc::new(
+            ConnectionManager::new(config.max_connections)
+                .with_max_connections_per_user(config.max_connections_per_user),
+        );
         let metrics = Arc::new(ServerMetrics::default());
+        let jwt_service = Arc::new(
+            JwtService::new(config.jwt_secret.as_bytes()).expect("HS256 keys accept any secret"),
+        );
 
         let state = ServerState {
             connection_manager,
             config,
             metrics,
+            jwt_service,
         };
 
         Self { state }
@@ -142,6 +176,13 @@ impl WebSocketServer {
 
     /// Start the WebSocket server
     pub async fn start(self) -> std::io::Result<()> {
+        if self.state.config.jwt_secret.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "A JWT secret is required; set JWT_SECRET",
+            ));
+        }
+
         let bind_address = format!("{}:{}", self.state.config.host, self.state.config.port);
         
         info!(
@@ -161,12 +202,13 @@ impl WebSocketServer {
             Self::metrics_task(metrics_state).await;
         });
 
+        let router = RouterActor::new(self.state.connection_manager.clone()).start();
+
         HttpServer::new(move || {
-            App::new()
-                .app_data(web::Data::new(self.state.clone()))
-                .route("/ws", web::get().to(websocket_handler))
-                .route("/health", web::get().to(health_handler))
-                .route("/metrics", web::get().to(metrics_handler))
+            crate::api::create_app(
+                web::Data::new(self.state.clone()),
+                web::Data::new(router.clone()),
+            )
         })
         .workers(num_cpus::get())
         .bind(&bind_address)?
@@ -222,10 +264,18 @@ impl WebSocketServer {
 }
 
 /// WebSocket connection handler
+///
+/// The upgrade must carry a valid JWT, either as a `token` (or
+/// `access_token`) query parameter, a bearer `Authorization` header, or an
+/// `access_token.<jwt>` entry in `Sec-WebSocket-Protocol`. Unauthenticated
+/// upgrades are rejected with 401 before the connection is established.
+/// Accepted connections are registered with the router along with the
+/// token's roles, so role-scoped broadcasts can reach them.
 pub async fn websocket_handler(
     req: HttpRequest,
     stream: web::Payload,
     state: web::Data<ServerState>,
+    router: web::Data<Addr<RouterActor>>,
 ) -> actix_web::Result<HttpResponse> {
     // Check connection limit
     let current_connections = *state.connection_manager.get_connection_count().await.read().await;
@@ -236,11 +286,53 @@ pub async fn websocket_handler(
         ));
     }
 
-    // Upgrade to WebSocket
-    let (response, session, stream) = actix_ws::handle(&req, stream)?;
-    
     let connection_id = Uuid::new_v4();
-    info!("New WebSocket connection: {}", connection_id);
+    let (user, subprotocol) = match authenticate_upgrade(&req, &state.jwt_service, connection_id) {
+        Ok(authenticated) => authenticated,
+        Err(e) => {
+            warn!("Rejecting unauthenticated WebSocket upgrade: {}", e);
+            return Ok(HttpResponse::Unauthorized().json(
+                serde_json::json!({"error": e.to_string()})
+            ));
+        }
+    };
+
+    // Upgrade to WebSocket
+    let (mut response, session, stream) = actix_ws::handle(&req, stream)?;
+    if let Some(protocol) = subprotocol {
+        // Browsers drop the connection unless the server picks one of the offered protocols
+        if let Ok(value) = actix_web::http::header::HeaderValue::from_str(&protocol) {
+            response.headers_mut().insert(actix_web::http::header::SEC_WEBSOCKET_PROTOCOL, value);
+        }
+    }
+
+    let mut connection_info = ConnectionInfo::new(connection_id);
+    connection_info.user_id = Some(user.user_id.clone());
+    if let Err(e) = state.connection_manager.add_connection(connection_info).await {
+        // Finish the handshake so the client sees why it was refused
+        warn!("Refusing connection {}: {}", connection_id, e);
+        let reason = actix_ws::CloseReason {
+            code: e.close_code().into(),
+            description: Some(e.to_string()),
+        };
+        actix_web::rt::spawn(async move {
+            let _ = session.close(Some(reason)).await;
+        });
+        return Ok(response);
+    }
+    info!("New WebSocket connection: {} (user {})", connection_id, user.user_id);
+
+    let delivery = RouterDelivery { session: session.clone() }.start();
+    router.do_send(Connect {
+        connection_id,
+        user_id: Some(user.user_id.clone()),
+        session_addr: delivery.recipient(),
+        metadata: HashMap::new(),
+    });
+    router.do_send(SetConnectionRoles {
+        connection_id,
+        roles: user.roles.clone(),
+    });
 
     // Create WebSocket actor
     let actor = WebSocketActor::new(
@@ -252,10 +344,15 @@ pub async fn websocket_handler(
     );
 
     // Spawn actor task
+    let router = router.get_ref().clone();
     actix_web::rt::spawn(async move {
         if let Err(e) = actor.run(stream).await {
             error!("WebSocket actor error for connection {}: {}", connection_id, e);
         }
+        router.do_send(Disconnect {
+            connection_id,
+            reason: None,
+        });
     });
 
     // Update metrics
@@ -264,6 +361,69 @@ pub async fn websocket_handler(
     Ok(response)
 }
 
+/// Writes what the router delivers to a connection onto its WebSocket
+struct RouterDelivery {
+    session: actix_ws::Session,
+}
+
+impl Actor for RouterDelivery {
+    type Context = actix::Context<Self>;
+}
+
+impl actix::Handler<SessionMessage> for RouterDelivery {
+    type Result = ();
+
+    fn handle(&mut self, msg: SessionMessage, _ctx: &mut Self::Context) -> Self::Result {
+        let text = match serde_json::to_string(&msg.message) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Failed to serialize routed message: {}", e);
+                return;
+            }
+        };
+        let mut session = self.session.clone();
+        actix_web::rt::spawn(async move {
+            let _ = session.text(text).await;
+        });
+    }
+}
+
+/// Validate the JWT presented with a WebSocket upgrade.
+///
+/// Returns the authenticated user and, when the token came from
+/// `Sec-WebSocket-Protocol`, the protocol entry to accept.
+pub fn authenticate_upgrade(
+    req: &HttpRequest,
+    jwt_service: &JwtService,
+    connection_id: Uuid,
+) -> Result<(UserContext, Option<String>), AuthError> {
+    let query = Some(req.query_string()).filter(|query| !query.is_empty());
+    let (token, subprotocol) = match TokenExtractor::extract_token(req.headers(), query) {
+        Ok(token) => (token, None),
+        Err(e) => {
+            let protocols: Vec<String> = req
+                .headers()
+                .get_all(actix_web::http::header::SEC_WEBSOCKET_PROTOCOL)
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .map(|protocol| protocol.trim().to_string())
+                .collect();
+            let token = TokenExtractor::extract_from_subprotocol(&protocols).ok_or(e)?;
+            let protocol = format!("access_token.{}", token);
+            (token, Some(protocol))
+        }
+    };
+
+    match jwt_service.validate_token(&token) {
+        AuthResult::Authenticated(mut user) => {
+            user.connection_id = connection_id;
+            Ok((user, subprotocol))
+        }
+        AuthResult::RequiresRefresh(_) => Err(AuthError::TokenRefreshRequired),
+        AuthResult::Unauthenticated(e) => Err(e),
+    }
+}
+
 /// Health check endpoint
 pub async fn health_handler(state: web::Data<ServerState>) -> HttpResponse {
     let stats = state.metrics.get_stats().await;
@@ -298,7 +458,21 @@ pub async fn metrics_handler(state: web::Data<ServerState>) -> HttpResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use actix_web::test;
+    use crate::auth::SystemRole;
+    use actix_web::{test, App};
+
+    fn test_jwt_service() -> Arc<JwtService> {
+        Arc::new(JwtService::new(b"test_secret_key_for_jwt_validation").unwrap())
+    }
+
+    fn upgrade_request(uri: &str) -> test::TestRequest {
+        test::TestRequest::get()
+            .uri(uri)
+            .insert_header(("upgrade", "websocket"))
+            .insert_header(("connection", "upgrade"))
+            .insert_header(("sec-websocket-version", "13"))
+            .insert_header(("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="))
+    }
     
     #[tokio::test]
     async fn test_server_config_default() {
@@ -390,6 +564,7 @@ mod tests {
             connection_manager,
             config,
             metrics,
+            jwt_service: test_jwt_service(),
         };
         
         let app = test::init_service(
@@ -426,6 +601,7 @@ mod tests {
             connection_manager,
             config,
             metrics,
+            jwt_service: test_jwt_service(),
         };
         
         let app = test::init_service(
@@ -462,6 +638,7 @@ mod tests {
             connection_manager,
             config,
             metrics,
+            jwt_service: test_jwt_service(),
         };
         
         let app = test::init_service(
@@ -531,4 +708,70 @@ mod tests {
         assert_eq!(deserialized.messages_received, stats.messages_received);
         assert_eq!(deserialized.errors, stats.errors);
     }
-}
\ No newline at end of file
+
+    #[actix_web::test]
+    async fn test_unauthenticated_upgrade_is_rejected() {
+        let config = ServerConfig::default();
+        let state = ServerState {
+            connection_manager: Arc::new(ConnectionManager::new(config.max_connections)),
+            config,
+            metrics: Arc::new(ServerMetrics::default()),
+            jwt_service: test_jwt_service(),
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state.clone()))
+                .app_data(web::Data::new(RouterActor::new(state.connection_manager.clone()).start()))
+                .route("/ws", web::get().to(websocket_handler))
+        ).await;
+
+        for uri in ["/ws", "/ws?token=not-a-jwt"] {
+            let resp = test::call_service(&app, upgrade_request(uri).to_request()).await;
+            assert_eq!(resp.status(), 401, "{}", uri);
+        }
+        assert_eq!(*state.connection_manager.get_connection_count().await.read().await, 0);
+        assert_eq!(state.metrics.get_stats().await.total_connections, 0);
+    }
+
+    #[actix_web::test]
+    async fn test_authenticated_upgrade_attaches_user_id() {
+        let config = ServerConfig::default();
+        let jwt_service = test_jwt_service();
+        let state = ServerState {
+            connection_manager: Arc::new(ConnectionManager::new(config.max_connections)),
+            config,
+            metrics: Arc::new(ServerMetrics::default()),
+            jwt_service: jwt_service.clone(),
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state.clone()))
+                .app_data(web::Data::new(RouterActor::new(state.connection_manager.clone()).start()))
+                .route("/ws", web::get().to(websocket_handler))
+        ).await;
+
+        let token = jwt_service
+            .generate_token("alice".to_string(), vec![SystemRole::User], None, None)
+            .unwrap();
+        let resp = test::call_service(
+            &app,
+            upgrade_request(&format!("/ws?token={}", token)).to_request(),
+        ).await;
+        assert_eq!(resp.status(), 101);
+
+        let connections = state.connection_manager.get_user_connections("alice").await;
+        assert_eq!(connections.len(), 1);
+        let connection = state.connection_manager.get_connection(&connections[0]).await.unwrap();
+        assert_eq!(connection.user_id.as_deref(), Some("alice"));
+
+        // The token may also arrive as a WebSocket subprotocol
+        let protocol = format!("access_token.{}", token);
+        let req = upgrade_request("/ws")
+            .insert_header(("sec-websocket-protocol", protocol.as_str()))
+            .to_http_request();
+        let (user, accepted) = authenticate_upgrade(&req, &jwt_service, connections[0]).unwrap();
+        assert_eq!(user.user_id, "alice");
+        assert_eq!(user.connection_id, connections[0]);
+        assert_eq!(accepted, Some(protocol));
+    }
+}
@@ -5,7 +5,7 @@
 
 use actix::{System, Actor, Addr, Recipient};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::time::{sleep, Duration};
 use uuid::Uuid;
 
@@ -394,6 +394,7 @@ async fn test_system_stats() {
         messages_routed: 1000,
         messages_delivered: 980,
         messages_failed: 20,
+        messages_dropped: 5,
         topics_active: 15,
         uptime_seconds: 3600,
     };
@@ -407,4 +408,142 @@ async fn test_system_stats() {
     let deserialized: SystemStats = serde_json::from_str(&json).unwrap();
     assert_eq!(deserialized.total_connections, stats.total_connections);
     assert_eq!(deserialized.messages_routed, stats.messages_routed);
-}
\ No newline at end of file
+}
+/// Session that records every message the router delivers to it
+struct RecordingSession(Arc<Mutex<Vec<ServerMessage>>>);
+
+impl Actor for RecordingSession {
+    type Context = actix::Context<Self>;
+}
+
+impl actix::Handler<SessionMessage> for RecordingSession {
+    type Result = ();
+    fn handle(&mut self, msg: SessionMessage, _ctx: &mut Self::Context) -> Self::Result {
+        self.0.lock().unwrap().push(msg.message);
+    }
+}
+
+#[actix::test]
+async fn test_message_edits_reach_room_and_require_sender() {
+    let router_addr = RouterActor::new(Arc::new(ConnectionManager::new(100))).start();
+    let mut members = Vec::new();
+    for user in ["alice", "bob"] {
+        let connection_id = Uuid::new_v4();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        router_addr.do_send(Connect {
+            connection_id,
+            user_id: Some(user.to_string()),
+            session_addr: RecordingSession(received.clone()).start().recipient(),
+            metadata: HashMap::new(),
+        });
+        router_addr.do_send(SubscribeToTopic { connection_id, topics: vec!["general".to_string()] });
+        members.push((connection_id, user.to_string(), received));
+    }
+    let route = |member: &(Uuid, String, Arc<Mutex<Vec<ServerMessage>>>), message: ClientMessage| RouteMessage {
+        from_connection: member.0,
+        from_user: Some(member.1.clone()),
+        message,
+        timestamp: chrono::Utc::now(),
+    };
+
+    router_addr.do_send(route(&members[0], ClientMessage::TopicMessage {
+        topic: "general".to_string(),
+        content: serde_json::json!({"text": "Helo"}),
+        message_id: Some("msg_1".to_string()),
+    }));
+    // Bob may not edit Alice's message
+    router_addr.do_send(route(&members[1], ClientMessage::EditMessage {
+        message_id: "msg_1".to_string(),
+        new_content: serde_json::json!({"text": "Hijacked"}),
+    }));
+    router_addr.do_send(route(&members[0], ClientMessage::EditMessage {
+        message_id: "msg_1".to_string(),
+        new_content: serde_json::json!({"text": "Hello"}),
+    }));
+    sleep(Duration::from_millis(50)).await;
+
+    let edits = |received: &Arc<Mutex<Vec<ServerMessage>>>| -> Vec<(String, serde_json::Value)> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|message| match message {
+                ServerMessage::MessageEdited { topic, message_id, edited_by, content, .. } => {
+                    assert_eq!((topic.as_str(), message_id.as_str()), ("general", "msg_1"));
+                    Some((edited_by.clone(), content.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+    let expected = vec![("alice".to_string(), serde_json::json!({"text": "Hello"}))];
+    assert_eq!(edits(&members[0].2), expected);
+    assert_eq!(edits(&members[1].2), expected);
+
+    let rejected = members[1].2.lock().unwrap().iter().any(|message| {
+        matches!(message, ServerMessage::Error { code: 403, .. })
+    });
+    assert!(rejected, "bob's edit should have been rejected");
+}
+
+#[actix::test]
+async fn test_admin_broadcast_to_role_reaches_only_that_role() {
+    let router_addr = RouterActor::new(Arc::new(ConnectionManager::new(100))).start();
+
+    let mut sessions = Vec::new();
+    for (user, roles) in [("agent_1", vec!["agent"]), ("agent_2", vec!["agent", "user"]), ("guest", vec!["user"])] {
+        let connection_id = Uuid::new_v4();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        router_addr.do_send(Connect {
+            connection_id,
+            user_id: Some(user.to_string()),
+            session_addr: RecordingSession(received.clone()).start().recipient(),
+            metadata: HashMap::new(),
+        });
+        router_addr.do_send(SetConnectionRoles {
+            connection_id,
+            roles: roles.into_iter().map(String::from).collect(),
+        });
+        sessions.push((user, received));
+    }
+
+    let delivered = router_addr
+        .send(AdminBroadcast {
+            scope: BroadcastScope::Role("agent".to_string()),
+            from: "root".to_string(),
+            content: serde_json::json!({"text": "New tasks available"}),
+            message_id: Some("announcement_1".to_string()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(delivered, 2);
+    sleep(Duration::from_millis(20)).await;
+
+    for (user, received) in &sessions {
+        let announcements: Vec<String> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|message| match message {
+                ServerMessage::BroadcastReceived { from, message_id, .. } => {
+                    assert_eq!(from, "root");
+                    Some(message_id.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        let expected: &[&str] = if user.starts_with("agent") { &["announcement_1"] } else { &[] };
+        assert_eq!(announcements, expected, "{}", user);
+    }
+
+    let everyone = router_addr
+        .send(AdminBroadcast {
+            scope: BroadcastScope::All,
+            from: "root".to_string(),
+            content: serde_json::json!({"text": "Hello"}),
+            message_id: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(everyone, 3);
+}
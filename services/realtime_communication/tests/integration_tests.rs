@@ -3,6 +3,7 @@
 //! End-to-end tests for the complete realtime communication system
 //! including WebSocket connections, message flow, and service integration.
 
+use actix::Actor;
 use actix_web::{test, web, App};
 use actix_ws;
 use futures_util::{SinkExt, StreamExt};
@@ -23,11 +24,14 @@ async fn test_websocket_connection_establishment() {
         connection_manager,
         config,
         metrics,
+        jwt_service: Arc::new(JwtService::new(b"test_secret").unwrap()),
     };
     
+    let router = RouterActor::new(state.connection_manager.clone()).start();
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(state))
+            .app_data(web::Data::new(router))
             .route("/ws", web::get().to(websocket_handler))
     ).await;
     
@@ -51,6 +55,7 @@ async fn test_health_and_metrics_endpoints() {
         connection_manager,
         config,
         metrics: metrics.clone(),
+        jwt_service: Arc::new(JwtService::new(b"test_secret").unwrap()),
     };
     
     let app = test::init_service(
@@ -100,11 +105,14 @@ async fn test_connection_limit_enforcement() {
         connection_manager,
         config,
         metrics,
+        jwt_service: Arc::new(JwtService::new(b"test_secret").unwrap()),
     };
     
+    let router = RouterActor::new(state.connection_manager.clone()).start();
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(state))
+            .app_data(web::Data::new(router))
             .route("/ws", web::get().to(websocket_handler))
     ).await;
     
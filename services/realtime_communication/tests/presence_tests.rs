@@ -23,6 +23,7 @@ async fn test_presence_config_default() {
     assert!(config.enable_auto_away);
     assert!(!config.enable_redis_sync);
     assert!(config.batch_updates);
+    assert_eq!(config.batch_window.as_millis(), 250);
 }
 
 #[tokio::test]
@@ -422,4 +423,124 @@ async fn test_device_history_management() {
         let expected_index = i + 2; // Should be devices 2, 3, 4
         assert_eq!(device.device_type, format!("device_{}", expected_index));
     }
-}
\ No newline at end of file
+}
+
+/// Session that records every message the router delivers to it
+struct RecordingSession(std::sync::Arc<std::sync::Mutex<Vec<ServerMessage>>>);
+
+impl actix::Actor for RecordingSession {
+    type Context = actix::Context<Self>;
+}
+
+impl actix::Handler<SessionMessage> for RecordingSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: SessionMessage, _ctx: &mut Self::Context) -> Self::Result {
+        self.0.lock().unwrap().push(msg.message);
+    }
+}
+
+#[actix::test]
+async fn test_presence_changes_within_window_are_batched() {
+    use actix::Actor;
+    use realtime_communication::connection::ConnectionManager;
+    use realtime_communication::actors::router::RouterActor;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let router = RouterActor::new(Arc::new(ConnectionManager::new(100)))
+        .with_presence_batch_window(Some(Duration::from_millis(200)))
+        .start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let observer = RecordingSession(received.clone()).start();
+    router.do_send(Connect {
+        connection_id: Uuid::new_v4(),
+        user_id: Some("observer".to_string()),
+        session_addr: observer.recipient(),
+        metadata: HashMap::new(),
+    });
+
+    for i in 0..50 {
+        router.do_send(RouteMessage {
+            from_connection: Uuid::new_v4(),
+            from_user: Some(format!("user_{}", i)),
+            message: ClientMessage::UpdatePresence { status: PresenceStatus::Online, message: None },
+            timestamp: Utc::now(),
+        });
+    }
+    tokio::time::sleep(Duration::from_millis(350)).await;
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1, "expected one batched update, got {:?}", received);
+    match &received[0] {
+        ServerMessage::PresenceBatch { updates } => {
+            assert_eq!(updates.len(), 50);
+            let users: HashSet<&str> = updates.iter().map(|u| u.user_id.as_str()).collect();
+            assert!((0..50).all(|i| users.contains(format!("user_{}", i).as_str())));
+            assert!(updates.iter().all(|u| u.status == PresenceStatus::Online));
+        }
+        other => panic!("Expected PresenceBatch, got {:?}", other),
+    }
+}
+
+#[actix::test]
+async fn test_typing_indicator_reaches_other_members_and_expires() {
+    use actix::Actor;
+    use realtime_communication::connection::ConnectionManager;
+    use realtime_communication::actors::router::RouterActor;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let router = RouterActor::new(Arc::new(ConnectionManager::new(100)))
+        .with_typing_timeout(Duration::from_millis(200))
+        .start();
+
+    let mut members = Vec::new();
+    for user in ["alice", "bob"] {
+        let connection_id = Uuid::new_v4();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        router.do_send(Connect {
+            connection_id,
+            user_id: Some(user.to_string()),
+            session_addr: RecordingSession(received.clone()).start().recipient(),
+            metadata: HashMap::new(),
+        });
+        router.do_send(SubscribeToTopic { connection_id, topics: vec!["room-1".to_string()] });
+        members.push((connection_id, received));
+    }
+    let typing_signals = |received: &Arc<Mutex<Vec<ServerMessage>>>| -> Vec<(String, bool)> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|message| match message {
+                ServerMessage::TypingIndicator { user_id, conversation_id, is_typing } => {
+                    assert_eq!(conversation_id, "room-1");
+                    Some((user_id.clone(), *is_typing))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+
+    router.do_send(RouteMessage {
+        from_connection: members[0].0,
+        from_user: Some("alice".to_string()),
+        message: ClientMessage::TypingStart { conversation_id: "room-1".to_string() },
+        timestamp: Utc::now(),
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(typing_signals(&members[1].1), vec![("alice".to_string(), true)]);
+    assert!(typing_signals(&members[0].1).is_empty());
+
+    // No TypingStop is sent, so the indicator times out
+    tokio::time::sleep(Duration::from_millis(400)).await;
+
+    assert_eq!(
+        typing_signals(&members[1].1),
+        vec![("alice".to_string(), true), ("alice".to_string(), false)]
+    );
+    assert!(typing_signals(&members[0].1).is_empty());
+}
@@ -21,6 +21,7 @@ mod base_agent_tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-3.5-turbo".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config.clone());
@@ -38,6 +39,7 @@ mod base_agent_tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-3.5-turbo".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -75,6 +77,7 @@ mod base_agent_tests {
             model_provider: ModelProvider::Anthropic,
             model_name: "claude-3-sonnet-20240229".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -109,6 +112,7 @@ mod base_agent_tests {
             model_provider: ModelProvider::Bedrock,
             model_name: "anthropic.claude-v2".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config.clone());
@@ -133,6 +137,7 @@ mod integration_tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-3.5-turbo".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -168,6 +173,7 @@ mod integration_tests {
             model_provider: ModelProvider::Anthropic,
             model_name: "claude-3-sonnet-20240229".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -205,6 +211,7 @@ mod integration_tests {
             model_provider: ModelProvider::Bedrock,
             model_name: "anthropic.claude-v2".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -248,6 +255,7 @@ mod error_handling_tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-3.5-turbo".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -272,6 +280,7 @@ mod error_handling_tests {
             model_provider: ModelProvider::Gemini,
             model_name: "gemini-pro".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -291,6 +300,7 @@ mod error_handling_tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-3.5-turbo".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -335,6 +345,7 @@ mod node_trait_tests {
             model_provider: ModelProvider::OpenAI,
             model_name: "gpt-3.5-turbo".to_string(),
             mcp_server_uri: None,
+            ai_fallback_template: None,
         };
 
         let agent = BaseAgentNode::new(config);
@@ -349,6 +360,7 @@ mod node_trait_tests {
             model_provider: ModelProvider::Anthropic,
             model_name: "claude-3-opus-20240229".to_string(),
             mcp_server_uri: Some("ws://localhost:8080".to_string()),
+            ai_fallback_template: None,
         };
 
         let cloned = config.clone();
@@ -118,6 +118,7 @@ async fn test_anthropic_agent_with_mcp_client() {
         model_provider: ModelProvider::Anthropic,
         model_name: "claude-3-opus".to_string(),
         mcp_server_uri: Some("mock://customer-support".to_string()),
+        ai_fallback_template: None,
     };
 
     let mut agent = AnthropicAgentNode::new(config);
@@ -161,6 +162,7 @@ async fn test_openai_agent_with_mcp_client() {
         model_provider: ModelProvider::OpenAI,
         model_name: "gpt-4".to_string(),
         mcp_server_uri: Some("mock://customer-support".to_string()),
+        ai_fallback_template: None,
     };
 
     let mut agent = OpenAIAgentNode::new(config).unwrap();
@@ -325,6 +327,7 @@ async fn test_agent_config_with_mcp_uri() {
         model_provider: ModelProvider::Anthropic,
         model_name: "claude-3".to_string(),
         mcp_server_uri: Some("stdio://customer-support-server".to_string()),
+        ai_fallback_template: None,
     };
 
     assert!(config.mcp_server_uri.is_some());
@@ -626,4 +626,250 @@ mod database_specific_tests {
             assert!(!event.checksum.as_ref().unwrap().is_empty(), "Checksum should not be empty");
         }
     }
-}
\ No newline at end of file
+
+    #[derive(QueryableByName)]
+    struct TransactionCount {
+        #[diesel(sql_type = diesel::sql_types::BigInt)]
+        transactions: i64,
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires PostgreSQL database
+    async fn test_postgresql_append_batch_single_transaction() {
+        let (event_store, pool) = match (create_test_event_store(), create_test_connection_pool()) {
+            (Some(store), Some(pool)) => (store, pool),
+            _ => {
+                println!("Skipping test - could not connect to PostgreSQL database");
+                return;
+            }
+        };
+
+        let aggregate_ids: Vec<Uuid> = (0..10).map(|_| Uuid::new_v4()).collect();
+        let events: Vec<EventEnvelope> = (0..100)
+            .map(|i| {
+                create_test_event(
+                    aggregate_ids[i % 10],
+                    "batch_event",
+                    json!({"index": i}),
+                    (i / 10 + 1) as i64,
+                )
+            })
+            .collect();
+
+        let result = event_store.append_batch(events.clone()).await.unwrap();
+        assert_eq!(result.appended.len(), 100);
+        assert!(result.conflicts.is_empty());
+
+        for aggregate_id in &aggregate_ids {
+            let stored = event_store.get_events(*aggregate_id).await.unwrap();
+            assert_eq!(stored.len(), 10);
+            assert!(stored.iter().all(|event| event.checksum.is_some()));
+        }
+
+        // Rows written by one transaction share its transaction id
+        let mut conn = pool.get().unwrap();
+        let count = diesel::sql_query(
+            "SELECT COUNT(DISTINCT xmin::text) AS transactions FROM event_store WHERE aggregate_id = ANY($1)",
+        )
+        .bind::<diesel::sql_types::Array<diesel::sql_types::Uuid>, _>(&aggregate_ids)
+        .get_result::<TransactionCount>(&mut conn)
+        .unwrap();
+        assert_eq!(count.transactions, 1);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires PostgreSQL database
+    async fn test_postgresql_append_batch_reports_conflicts() {
+        let event_store = match create_test_event_store() {
+            Some(store) => store,
+            None => {
+                println!("Skipping test - could not connect to PostgreSQL database");
+                return;
+            }
+        };
+
+        let existing = Uuid::new_v4();
+        let fresh = Uuid::new_v4();
+        event_store
+            .append_event(&create_test_event(existing, "seed", json!({}), 1))
+            .await
+            .unwrap();
+
+        let conflicting = create_test_event(existing, "duplicate", json!({}), 1);
+        let follow_up = create_test_event(existing, "follow_up", json!({}), 2);
+        let accepted = create_test_event(fresh, "created", json!({}), 1);
+        let result = event_store
+            .append_batch(vec![conflicting.clone(), follow_up.clone(), accepted.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(result.appended, vec![accepted.event_id]);
+        let conflicts: Vec<Uuid> = result.conflicts.iter().map(|c| c.event_id).collect();
+        assert_eq!(conflicts, vec![conflicting.event_id, follow_up.event_id]);
+        assert!(result.conflicts.iter().all(|c| c.current_version == 1));
+
+        assert_eq!(event_store.get_events(existing).await.unwrap().len(), 1);
+        assert_eq!(event_store.get_events(fresh).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires PostgreSQL database
+    async fn test_postgresql_event_query_filters() {
+        let event_store = match create_test_event_store() {
+            Some(store) => store,
+            None => {
+                println!("Skipping test - could not connect to PostgreSQL database");
+                return;
+            }
+        };
+
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = Utc::now() - chrono::Duration::hours(10);
+        let mut events = Vec::new();
+        for (hour, (aggregate_id, event_type, version)) in [
+            (first, "WorkflowStarted", 1),
+            (second, "WorkflowStarted", 1),
+            (first, "StepCompleted", 2),
+            (second, "StepCompleted", 2),
+            (first, "WorkflowCompleted", 3),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut event = create_test_event(aggregate_id, event_type, json!({"hour": hour}), version);
+            event.occurred_at = start + chrono::Duration::hours(hour as i64);
+            events.push(event);
+        }
+        event_store.append_events(&events).await.unwrap();
+
+        let by_aggregate = event_store.query(&EventQuery::new().aggregate(second)).await.unwrap();
+        assert_eq!(by_aggregate.iter().map(|e| e.aggregate_version).collect::<Vec<_>>(), vec![1, 2]);
+
+        let by_type = event_store
+            .query(&EventQuery::new().event_type("WorkflowStarted").since(start))
+            .await
+            .unwrap();
+        let started: Vec<Uuid> = by_type
+            .iter()
+            .filter(|e| e.aggregate_id == first || e.aggregate_id == second)
+            .map(|e| e.aggregate_id)
+            .collect();
+        assert_eq!(started, vec![first, second]);
+
+        let window = event_store
+            .query(
+                &EventQuery::new()
+                    .aggregate(first)
+                    .since(start + chrono::Duration::hours(1))
+                    .until(start + chrono::Duration::hours(4)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(window.len(), 1);
+        assert_eq!(window[0].event_type, "StepCompleted");
+
+        let limited = event_store.query(&EventQuery::new().aggregate(first).limit(2)).await.unwrap();
+        assert_eq!(limited.iter().map(|e| e.aggregate_version).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires PostgreSQL database
+    async fn test_postgresql_detects_tampered_event() {
+        let (event_store, pool) = match (create_test_event_store(), create_test_connection_pool()) {
+            (Some(store), Some(pool)) => (store, pool),
+            _ => {
+                println!("Skipping test - could not connect to PostgreSQL database");
+                return;
+            }
+        };
+
+        let aggregate_id = Uuid::new_v4();
+        let event = create_test_event(aggregate_id, "payment_recorded", json!({"amount": 100}), 1);
+        event_store.append_event(&event).await.unwrap();
+        assert_eq!(event_store.get_events(aggregate_id).await.unwrap().len(), 1);
+
+        // Change the payload behind the store's back
+        let mut conn = pool.get().unwrap();
+        diesel::sql_query("UPDATE event_store SET event_data = '{\"amount\": 1000}' WHERE id = $1")
+            .bind::<diesel::sql_types::Uuid, _>(event.event_id)
+            .execute(&mut conn)
+            .unwrap();
+
+        match event_store.get_events(aggregate_id).await {
+            Err(EventError::CorruptionError { event_id, .. }) => assert_eq!(event_id, event.event_id),
+            other => panic!("Expected CorruptionError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires PostgreSQL database
+    async fn test_postgresql_saga_store_round_trip() {
+        use diesel::connection::SimpleConnection;
+
+        let pool = match create_test_connection_pool() {
+            Some(pool) => pool,
+            None => {
+                println!("Skipping test - could not connect to PostgreSQL database");
+                return;
+            }
+        };
+        pool.get()
+            .unwrap()
+            .batch_execute(include_str!("../migrations/20241213_000003_create_saga_executions.sql"))
+            .unwrap();
+        let saga_store = PostgreSQLSagaStore::new(Arc::new(pool));
+
+        let step = |step_id: &str, status| SagaStepExecution {
+            step: SagaStep {
+                step_id: step_id.to_string(),
+                service_name: "orders".to_string(),
+                operation: step_id.to_string(),
+                input_data: json!({}),
+                compensation_operation: Some(format!("undo_{}", step_id)),
+                compensation_data: None,
+                timeout_seconds: Some(30),
+                retry_policy: None,
+                depends_on: vec![],
+                parallel_group: None,
+            },
+            status,
+            attempt_count: 1,
+            started_at: Some(Utc::now()),
+            completed_at: None,
+            output_data: None,
+            error_message: None,
+            compensation_started_at: None,
+            compensation_completed_at: None,
+        };
+        let mut execution = SagaExecution {
+            saga_id: Uuid::new_v4(),
+            saga_type: "order".to_string(),
+            state: SagaState::Running,
+            steps: vec![step("reserve", SagaStepStatus::Completed), step("charge", SagaStepStatus::Running)],
+            global_context: json!({"order_id": 7}),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            completed_at: None,
+            error_message: None,
+            total_timeout_seconds: None,
+            compensation_strategy: CompensationStrategy::ReverseOrder,
+        };
+        saga_store.save_saga(&execution).await.unwrap();
+
+        let running = saga_store.list_sagas_by_state(SagaState::Running).await.unwrap();
+        let loaded = running.iter().find(|s| s.saga_id == execution.saga_id).unwrap();
+        assert_eq!(loaded.steps[0].status, SagaStepStatus::Completed);
+        assert_eq!(loaded.steps[1].status, SagaStepStatus::Running);
+        assert_eq!(loaded.global_context, json!({"order_id": 7}));
+
+        // Saving again updates the row in place
+        execution.state = SagaState::Completed;
+        execution.completed_at = Some(Utc::now());
+        saga_store.save_saga(&execution).await.unwrap();
+        let loaded = saga_store.get_saga(execution.saga_id).await.unwrap().unwrap();
+        assert_eq!(loaded.state, SagaState::Completed);
+
+        assert!(saga_store.cleanup_old_sagas(Utc::now()).await.unwrap() >= 1);
+        assert!(saga_store.get_saga(execution.saga_id).await.unwrap().is_none());
+    }
+}